@@ -0,0 +1,363 @@
+//! Read, write and convert throughput of all formats on the `countries` dataset.
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use flatgeobuf::FgbReader;
+use geozero::csv::{Csv, CsvWriter};
+use geozero::geo_types::GeoWriter;
+use geozero::geojson::{GeoJson, GeoJsonWriter};
+use geozero::mvt::{tile, Tile, TileRef};
+use geozero::processor::TilePixels;
+use geozero::svg::SvgWriter;
+use geozero::wkb::{process_wkb_geom, Wkb, WkbDialect, WkbWriter};
+use geozero::wkt::{WktStr, WktWriter};
+use geozero::{
+    CoordDimensions, FeatureProcessor, GeozeroDatasource, GeozeroGeometry, ProcessToCsv,
+    ProcessToJson, ProcessorSink, ToGeo, ToMvt, ToWkb, ToWkt,
+};
+use prost::Message;
+use std::fs::File;
+use std::io::Cursor;
+
+const COUNTRIES_FGB: &str = "../geozero/tests/data/countries.fgb";
+
+/// Countries dataset in all benchmarked formats, converted from FlatGeobuf
+struct Dataset {
+    fgb: Vec<u8>,
+    geojson: String,
+    csv: String,
+    geo: Vec<geo_types::Geometry<f64>>,
+    wkt: Vec<String>,
+    wkb: Vec<Vec<u8>>,
+    /// Geometries in tile coordinates
+    mvt: Vec<tile::Feature>,
+}
+
+impl Dataset {
+    fn load() -> geozero::error::Result<Self> {
+        let fgb = std::fs::read(COUNTRIES_FGB)?;
+        let mut cursor = Cursor::new(&fgb);
+        let mut features = FgbReader::open(&mut cursor)?.select_all()?;
+        let mut geo = Vec::new();
+        while let Some(feature) = features.next()? {
+            geo.push(feature.to_geo()?);
+        }
+        let geojson = fgb_features(&fgb).to_json()?;
+        let csv = GeoJson(&geojson).to_csv()?;
+        let wkt = geo.iter().map(|g| g.to_wkt()).collect::<Result<_, _>>()?;
+        let wkb = geo
+            .iter()
+            .map(|g| g.to_wkb(CoordDimensions::xy()))
+            .collect::<Result<_, _>>()?;
+        let mvt = geo
+            .iter()
+            .map(|g| {
+                let mut pixels = TilePixels::new(GeoWriter::new(), 0).tile_size(4096);
+                g.process_geom(&mut pixels)?;
+                pixels.into_inner().take_geometry().unwrap().to_mvt()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Dataset {
+            fgb,
+            geojson,
+            csv,
+            geo,
+            wkt,
+            wkb,
+            mvt,
+        })
+    }
+}
+
+/// In-memory FlatGeobuf datasource with all features selected
+fn fgb_features(fgb: &[u8]) -> impl GeozeroDatasource + '_ {
+    FgbDatasource(Cursor::new(fgb))
+}
+
+struct FgbDatasource<'a>(Cursor<&'a [u8]>);
+
+impl GeozeroDatasource for FgbDatasource<'_> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> geozero::error::Result<()> {
+        FgbReader::open(&mut self.0)?
+            .select_all()?
+            .process_features(processor)?;
+        Ok(())
+    }
+}
+
+/// In-memory geo-types features without properties
+struct GeoFeatures<'a>(&'a [geo_types::Geometry<f64>]);
+
+impl GeozeroDatasource for GeoFeatures<'_> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> geozero::error::Result<()> {
+        processor.dataset_begin(None)?;
+        for (idx, geom) in self.0.iter().enumerate() {
+            processor.feature_begin(idx as u64)?;
+            processor.geometry_begin()?;
+            geom.process_geom(processor)?;
+            processor.geometry_end()?;
+            processor.feature_end(idx as u64)?;
+        }
+        processor.dataset_end()
+    }
+}
+
+/// Write all geometries with `writer`
+fn write_geoms<P: FeatureProcessor>(geoms: &[geo_types::Geometry<f64>], writer: &mut P) {
+    GeoFeatures(geoms).process(writer).unwrap();
+}
+
+fn read_benchmark(c: &mut Criterion, data: &Dataset) {
+    let mut group = c.benchmark_group("read");
+    group.throughput(Throughput::Elements(data.geo.len() as u64));
+    group.bench_function("fgb", |b| {
+        b.iter(|| {
+            fgb_features(&data.fgb)
+                .process(&mut ProcessorSink::new())
+                .unwrap()
+        })
+    });
+    group.bench_function("geojson", |b| {
+        b.iter(|| {
+            GeoJson(&data.geojson)
+                .process(&mut ProcessorSink::new())
+                .unwrap()
+        })
+    });
+    group.bench_function("csv", |b| {
+        b.iter(|| {
+            Csv::new("geometry", &data.csv)
+                .process(&mut ProcessorSink::new())
+                .unwrap()
+        })
+    });
+    group.bench_function("wkt", |b| {
+        b.iter(|| {
+            for wkt in &data.wkt {
+                WktStr(wkt).process_geom(&mut ProcessorSink::new()).unwrap();
+            }
+        })
+    });
+    group.bench_function("wkb", |b| {
+        b.iter(|| {
+            for wkb in &data.wkb {
+                process_wkb_geom(&mut wkb.as_slice(), &mut ProcessorSink::new()).unwrap();
+            }
+        })
+    });
+    group.bench_function("mvt", |b| {
+        b.iter(|| {
+            for feature in &data.mvt {
+                feature.process_geom(&mut ProcessorSink::new()).unwrap();
+            }
+        })
+    });
+    group.bench_function("geo", |b| {
+        b.iter(|| {
+            for geom in &data.geo {
+                geom.process_geom(&mut ProcessorSink::new()).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+fn write_benchmark(c: &mut Criterion, data: &Dataset) {
+    let mut group = c.benchmark_group("write");
+    group.throughput(Throughput::Elements(data.geo.len() as u64));
+    group.bench_function("geojson", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            write_geoms(&data.geo, &mut GeoJsonWriter::new(&mut out));
+            out
+        })
+    });
+    group.bench_function("csv", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            write_geoms(&data.geo, &mut CsvWriter::new(&mut out));
+            out
+        })
+    });
+    group.bench_function("wkt", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            write_geoms(&data.geo, &mut WktWriter::new(&mut out));
+            out
+        })
+    });
+    group.bench_function("wkb", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            write_geoms(&data.geo, &mut WkbWriter::new(&mut out, WkbDialect::Wkb));
+            out
+        })
+    });
+    group.bench_function("svg", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            write_geoms(&data.geo, &mut SvgWriter::new(&mut out, true));
+            out
+        })
+    });
+    group.bench_function("mvt", |b| {
+        b.iter(|| {
+            data.geo
+                .iter()
+                .map(|geom| geom.to_mvt().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("geo", |b| {
+        b.iter(|| {
+            let mut writer = GeoWriter::new();
+            write_geoms(&data.geo, &mut writer);
+            writer.take_geometry()
+        })
+    });
+    group.finish();
+}
+
+fn convert_benchmark(c: &mut Criterion, data: &Dataset) {
+    let mut group = c.benchmark_group("convert");
+    group.throughput(Throughput::Elements(data.geo.len() as u64));
+    group.bench_function("fgb-geojson", |b| {
+        b.iter(|| fgb_features(&data.fgb).to_json().unwrap())
+    });
+    group.bench_function("fgb-csv", |b| {
+        b.iter(|| fgb_features(&data.fgb).to_csv().unwrap())
+    });
+    group.bench_function("geojson-csv", |b| {
+        b.iter(|| GeoJson(&data.geojson).to_csv().unwrap())
+    });
+    group.bench_function("csv-geojson", |b| {
+        b.iter(|| Csv::new("geometry", &data.csv).to_json().unwrap())
+    });
+    group.bench_function("geojson-svg", |b| {
+        b.iter(|| {
+            let mut out: Vec<u8> = Vec::new();
+            GeoJson(&data.geojson)
+                .process(&mut SvgWriter::new(&mut out, true))
+                .unwrap();
+            out
+        })
+    });
+    group.finish();
+}
+
+/// Unbuffered text writers compared with writers using an output buffer, writing to a file
+fn text_output_benchmark(c: &mut Criterion, data: &Dataset) {
+    let mut group = c.benchmark_group("text-output");
+    group.throughput(Throughput::Elements(data.geo.len() as u64));
+    let path = std::env::temp_dir().join("geozero-bench-output");
+    let buffer_size = 64 * 1024;
+    group.bench_function("geojson", |b| {
+        b.iter(|| {
+            let mut file = File::create(&path).unwrap();
+            write_geoms(&data.geo, &mut GeoJsonWriter::new(&mut file));
+        })
+    });
+    group.bench_function("geojson-buffered", |b| {
+        b.iter(|| {
+            let mut file = File::create(&path).unwrap();
+            write_geoms(
+                &data.geo,
+                &mut GeoJsonWriter::new(&mut file).buffer_size(buffer_size),
+            );
+        })
+    });
+    group.bench_function("wkt", |b| {
+        b.iter(|| {
+            let mut file = File::create(&path).unwrap();
+            write_geoms(&data.geo, &mut WktWriter::new(&mut file));
+        })
+    });
+    group.bench_function("wkt-buffered", |b| {
+        b.iter(|| {
+            let mut file = File::create(&path).unwrap();
+            write_geoms(
+                &data.geo,
+                &mut WktWriter::new(&mut file).buffer_size(buffer_size),
+            );
+        })
+    });
+    group.finish();
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Encoded tile with one layer of `features` and 20 distinct string properties per feature
+fn attribute_heavy_tile(features: &[tile::Feature]) -> Vec<u8> {
+    let mut layer = tile::Layer {
+        version: 2,
+        name: "countries".to_string(),
+        extent: Some(4096),
+        ..Default::default()
+    };
+    layer.keys = (0..20).map(|i| format!("attribute_{}", i)).collect();
+    for (idx, feature) in features.iter().enumerate() {
+        let mut feature = feature.clone();
+        for key in 0..20 {
+            feature.tags.push(key);
+            feature.tags.push(layer.values.len() as u32);
+            layer.values.push(tile::Value {
+                string_value: Some(format!("value of attribute {} of feature {}", key, idx)),
+                ..Default::default()
+            });
+        }
+        layer.features.push(feature);
+    }
+    Tile {
+        layers: vec![layer],
+    }
+    .encode_to_vec()
+}
+
+/// Owned decoding compared with borrowing from the input slice
+fn zero_copy_benchmark(c: &mut Criterion, data: &Dataset) {
+    let mut group = c.benchmark_group("zero-copy");
+    group.throughput(Throughput::Elements(data.geo.len() as u64));
+    let tile = attribute_heavy_tile(&data.mvt);
+    group.bench_function("mvt-decoded", |b| {
+        b.iter(|| {
+            let mut tile = Tile::decode(tile.as_slice()).unwrap();
+            tile.layers[0].process(&mut ProcessorSink::new()).unwrap()
+        })
+    });
+    group.bench_function("mvt-borrowed", |b| {
+        b.iter(|| {
+            TileRef(&tile)
+                .layer("countries")
+                .unwrap()
+                .unwrap()
+                .process(&mut ProcessorSink::new())
+                .unwrap()
+        })
+    });
+    group.bench_function("wkb-owned", |b| {
+        b.iter(|| {
+            for wkb in &data.wkb {
+                Wkb(wkb.clone())
+                    .process_geom(&mut ProcessorSink::new())
+                    .unwrap();
+            }
+        })
+    });
+    group.bench_function("wkb-borrowed", |b| {
+        b.iter(|| {
+            for wkb in &data.wkb {
+                process_wkb_geom(&mut wkb.as_slice(), &mut ProcessorSink::new()).unwrap();
+            }
+        })
+    });
+    group.finish();
+}
+
+fn formats_benchmark(c: &mut Criterion) {
+    let data = Dataset::load().expect("loading countries dataset");
+    read_benchmark(c, &data);
+    write_benchmark(c, &data);
+    convert_benchmark(c, &data);
+    zero_copy_benchmark(c, &data);
+    text_output_benchmark(c, &data);
+}
+
+criterion_group!(benches, formats_benchmark);
+criterion_main!(benches);
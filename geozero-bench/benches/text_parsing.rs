@@ -0,0 +1,73 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use geozero::csv::CsvString;
+use geozero::geojson::GeoJsonString;
+use geozero::wkt::WktString;
+use geozero::{GeozeroDatasource, GeozeroGeometry, ProcessorSink};
+
+/// Ring with `n` vertices and full precision coordinates, similar to `canada.json`
+fn ring(n: usize) -> Vec<(f64, f64)> {
+    (0..=n)
+        .map(|i| {
+            let a = (i % n) as f64 / n as f64 * std::f64::consts::TAU;
+            (-65.613617 + a.cos() * 3.123456789, 43.420273 + a.sin())
+        })
+        .collect()
+}
+
+fn geojson(coords: &[(f64, f64)]) -> String {
+    let coords: Vec<String> = coords
+        .iter()
+        .map(|(x, y)| format!("[{:?},{:?}]", x, y))
+        .collect();
+    format!(
+        r#"{{"type": "FeatureCollection", "features": [{{"type": "Feature", "properties": {{"name": "Canada"}}, "geometry": {{"type": "Polygon", "coordinates": [[{}]]}}}}]}}"#,
+        coords.join(",")
+    )
+}
+
+fn wkt(coords: &[(f64, f64)]) -> String {
+    let coords: Vec<String> = coords
+        .iter()
+        .map(|(x, y)| format!("{:?} {:?}", x, y))
+        .collect();
+    format!("POLYGON(({}))", coords.join(","))
+}
+
+fn text_parsing_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("text_parsing");
+    for n in [1_000, 100_000] {
+        let coords = ring(n);
+        let json = geojson(&coords);
+        let wkt = wkt(&coords);
+        let csv = format!("name,geometry\nCanada,\"{}\"\n", wkt);
+
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::new("geojson", n), &json, |b, json| {
+            b.iter(|| {
+                GeoJsonString(json.clone())
+                    .process(&mut ProcessorSink::new())
+                    .unwrap()
+            })
+        });
+        group.throughput(Throughput::Bytes(wkt.len() as u64));
+        group.bench_with_input(BenchmarkId::new("wkt", n), &wkt, |b, wkt| {
+            b.iter(|| {
+                WktString(wkt.clone())
+                    .process_geom(&mut ProcessorSink::new())
+                    .unwrap()
+            })
+        });
+        group.throughput(Throughput::Bytes(csv.len() as u64));
+        group.bench_with_input(BenchmarkId::new("csv", n), &csv, |b, csv| {
+            b.iter(|| {
+                CsvString::new("geometry", csv.clone())
+                    .process(&mut ProcessorSink::new())
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, text_parsing_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+#!/bin/bash
+# Compare `formats` benchmark conversions with ogr2ogr timings on the same dataset.
+#
+# Usage: cargo bench --bench formats && ./compare_ogr2ogr.sh [runs]
+
+set -e
+
+runs=${1:-10}
+src=../geozero/tests/data/countries.fgb
+tmp=$(mktemp -d)
+trap 'rm -rf $tmp' EXIT
+
+# Median of ogr2ogr wall time in ms
+ogr2ogr_median() {
+  local driver=$1 dst=$2 input=$3
+  for i in $(seq $runs); do
+    rm -f $tmp/$dst
+    local start=$(date +%s%N)
+    ogr2ogr -f "$driver" $tmp/$dst $input >/dev/null 2>&1
+    echo $((($(date +%s%N) - start) / 1000))
+  done | sort -n | awk '{a[NR]=$1} END {printf "%.2f", a[int((NR+1)/2)] / 1000}'
+}
+
+# Median of criterion benchmark in ms
+geozero_median() {
+  jq '.median.point_estimate / 1000000' target/criterion/convert/$1/new/estimates.json | xargs printf "%.2f"
+}
+
+ogr2ogr -f GeoJSON $tmp/countries.geojson $src
+ogr2ogr -f CSV $tmp/countries.csv $src -lco GEOMETRY=AS_WKT -lco GEOMETRY_NAME=geometry
+
+echo "Conversion,geozero (ms),ogr2ogr (ms)"
+echo "fgb-geojson,$(geozero_median fgb-geojson),$(ogr2ogr_median GeoJSON out.geojson $src)"
+echo "fgb-csv,$(geozero_median fgb-csv),$(ogr2ogr_median CSV out.csv $src)"
+echo "geojson-csv,$(geozero_median geojson-csv),$(ogr2ogr_median CSV out.csv $tmp/countries.geojson)"
+echo "csv-geojson,$(geozero_median csv-geojson),$(ogr2ogr_median GeoJSON out.geojson $tmp/countries.csv)"
+echo "geojson-svg,$(geozero_median geojson-svg),-"
@@ -0,0 +1,251 @@
+//! `geozero convert` command
+use crate::input::{parse_bbox, InputArgs, Selection};
+use clap::Args;
+use flatgeobuf::*;
+use geozero::csv::CsvWriter;
+use geozero::error::{GeozeroError, Result};
+use geozero::geojson::GeoJsonWriter;
+use geozero::processor::{Expr, Extent, ExtentCalc, Reproject, XYSwap};
+use geozero::svg::SvgWriter;
+use geozero::wkt::WktWriter;
+use geozero::{ConvertStats, Crs, FeatureProcessor};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    #[clap(flatten)]
+    input: InputArgs,
+    /// The path to the file to write, or `-` for stdout.
+    /// The output format is taken from the extension.
+    #[clap(parse(from_os_str))]
+    dest: PathBuf,
+    /// Output format (fgb, geojson, csv, svg, wkt)
+    #[clap(long, value_name = "FORMAT")]
+    to: Option<String>,
+    /// Only features intersecting the bounding box `minx,miny,maxx,maxy`.
+    /// The spatial index of the input is used where available.
+    #[clap(
+        short = 'b',
+        long,
+        alias = "extent",
+        short_alias = 'e',
+        value_name = "MINX,MINY,MAXX,MAXY",
+        allow_hyphen_values = true,
+        parse(try_from_str = parse_bbox)
+    )]
+    bbox: Option<Extent>,
+    /// Only the listed property columns, in given order (e.g. `name,population`)
+    #[clap(long, value_name = "COLUMNS", use_value_delimiter = true)]
+    select: Option<Vec<String>>,
+    /// Only features matching a CQL2-text filter (e.g. `"population > 100000"`)
+    #[clap(long = "where", value_name = "EXPR", parse(try_from_str = parse_filter))]
+    filter: Option<Expr>,
+    /// Source CRS (e.g. EPSG:2056), overriding the CRS of the input
+    #[clap(
+        long = "s_srs",
+        value_name = "SRS",
+        requires = "t-srs",
+        parse(try_from_str = parse_crs)
+    )]
+    s_srs: Option<Crs>,
+    /// Reproject to CRS (e.g. EPSG:4326)
+    #[clap(long = "t_srs", value_name = "SRS", parse(try_from_str = parse_crs))]
+    t_srs: Option<Crs>,
+}
+
+fn parse_filter(src: &str) -> std::result::Result<Expr, String> {
+    Expr::parse(src).map_err(|e| e.to_string())
+}
+
+fn parse_crs(src: &str) -> std::result::Result<Crs, String> {
+    Crs::from_definition(src).ok_or_else(|| format!("invalid CRS `{}`", src))
+}
+
+/// Output format
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Output {
+    Csv,
+    FlatGeobuf,
+    GeoJson,
+    Svg,
+    Wkt,
+}
+
+impl Output {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "csv" => Some(Output::Csv),
+            "fgb" => Some(Output::FlatGeobuf),
+            "json" | "geojson" => Some(Output::GeoJson),
+            "svg" => Some(Output::Svg),
+            "wkt" => Some(Output::Wkt),
+            _ => None,
+        }
+    }
+}
+
+impl ConvertArgs {
+    /// Output to stdout
+    fn is_stdout(&self) -> bool {
+        self.dest == Path::new("-")
+    }
+    fn output_format(&self) -> Result<Output> {
+        if self.to.is_none() && self.is_stdout() {
+            return Err(GeozeroError::Dataset(
+                "writing to stdout requires --to".to_string(),
+            ));
+        }
+        let name = match &self.to {
+            Some(name) => Some(name.as_str()),
+            None => self.dest.extension().and_then(OsStr::to_str),
+        };
+        name.and_then(Output::from_name).ok_or_else(|| {
+            GeozeroError::Dataset(format!(
+                "unknown output format of `{}`",
+                self.dest.display()
+            ))
+        })
+    }
+    fn output(&self) -> Result<Box<dyn Write>> {
+        if self.is_stdout() {
+            Ok(Box::new(BufWriter::new(io::stdout())))
+        } else {
+            Ok(Box::new(BufWriter::new(File::create(&self.dest)?)))
+        }
+    }
+    /// Process input features with `processor`, reprojecting them if requested
+    fn process_input<P: FeatureProcessor>(&self, processor: P) -> Result<(ConvertStats, P)> {
+        let selection = Selection {
+            bbox: self.bbox,
+            filter: self.filter.clone(),
+            columns: self.select.clone(),
+        };
+        match &self.t_srs {
+            Some(t_srs) => {
+                let mut reproject = Reproject::new(processor, t_srs.clone());
+                if let Some(s_srs) = &self.s_srs {
+                    reproject = reproject.source_crs(s_srs.clone());
+                }
+                let (stats, reproject) = self.input.process(&selection, reproject)?;
+                Ok((stats, reproject.into_inner()))
+            }
+            None => {
+                // Sources declaring north-first axis order are written easting first
+                let (stats, swap) = self
+                    .input
+                    .process(&selection, XYSwap::from_crs(processor))?;
+                Ok((stats, swap.into_inner()))
+            }
+        }
+    }
+}
+
+fn process(args: &ConvertArgs) -> Result<ConvertStats> {
+    let format = args.output_format()?;
+    if format == Output::Svg && args.bbox.is_none() && args.input.is_stdin() {
+        // The extent of stdin input can't be calculated before writing
+        return Err(GeozeroError::Dataset(
+            "SVG output of stdin input requires --bbox".to_string(),
+        ));
+    }
+    let mut fout = args.output()?;
+    let stats = match format {
+        Output::Csv => args.process_input(CsvWriter::new(&mut fout))?.0,
+        Output::FlatGeobuf => {
+            let fgb = FgbWriter::create("fgb", GeometryType::Unknown)?;
+            let (stats, fgb) = args.process_input(fgb)?;
+            fgb.write(&mut fout)?;
+            stats
+        }
+        Output::GeoJson => args.process_input(GeoJsonWriter::new(&mut fout))?.0,
+        Output::Svg => {
+            // The bbox is given in the source CRS
+            let extent = match (args.bbox, &args.t_srs) {
+                (Some(bbox), None) => Some(bbox),
+                _ => args.process_input(ExtentCalc::new())?.1.extent(),
+            };
+            let mut processor = SvgWriter::new(&mut fout, true);
+            // TODO: get image size as opts
+            if let Some(extent) = extent {
+                processor.set_dimensions(
+                    extent.minx,
+                    extent.miny,
+                    extent.maxx,
+                    extent.maxy,
+                    800,
+                    600,
+                );
+            }
+            args.process_input(processor)?.0
+        }
+        Output::Wkt => args.process_input(WktWriter::new(&mut fout))?.0,
+    };
+    fout.flush()?;
+    Ok(stats)
+}
+
+#[tokio::main]
+async fn process_url(args: &ConvertArgs) -> Result<()> {
+    let format = args.output_format()?;
+    if args.t_srs.is_some() || args.select.is_some() || args.filter.is_some() {
+        return Err(GeozeroError::Dataset(
+            "reprojection and attribute selection not supported for URL input".to_string(),
+        ));
+    }
+    let ds = HttpFgbReader::open(&args.input.input).await?;
+    let mut ds = if let Some(bbox) = &args.bbox {
+        ds.select_bbox(bbox.minx, bbox.miny, bbox.maxx, bbox.maxy)
+            .await?
+    } else {
+        ds.select_all().await?
+    };
+
+    let mut fout = args.output()?;
+    match format {
+        Output::GeoJson => {
+            let mut processor = GeoJsonWriter::new(&mut fout);
+            ds.process_features(&mut processor).await?;
+        }
+        Output::Svg => {
+            let mut processor = SvgWriter::new(&mut fout, true);
+            if let Some(extent) = args.bbox {
+                processor.set_dimensions(
+                    extent.minx,
+                    extent.miny,
+                    extent.maxx,
+                    extent.maxy,
+                    800,
+                    600,
+                );
+            } else {
+                processor.set_dimensions(-180.0, -90.0, 180.0, 90.0, 800, 600);
+            }
+            ds.process_features(&mut processor).await?;
+        }
+        format => {
+            return Err(GeozeroError::Dataset(format!(
+                "output format {:?} not supported for URL input",
+                format
+            )))
+        }
+    }
+    fout.flush()?;
+    Ok(())
+}
+
+pub fn run(args: ConvertArgs) -> Result<()> {
+    if args.input.is_url() {
+        process_url(&args)
+    } else {
+        let stats = process(&args)?;
+        if args.is_stdout() {
+            eprintln!("Converted {} features", stats.feature_count);
+        } else {
+            println!("Converted {} features", stats.feature_count);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+//! `geozero index` command
+use clap::Args;
+use flatgeobuf::*;
+use geozero::error::{GeozeroError, Result};
+use geozero::processor::{Extent, ExtentCalc};
+use geozero::wkb::GpkgWkb;
+use geozero::{GeozeroGeometry, InputFormat};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::convert::TryInto;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct IndexArgs {
+    /// FlatGeobuf, GeoPackage or Shapefile to index
+    #[clap(parse(from_os_str))]
+    input: PathBuf,
+    /// Rebuild existing indexes
+    #[clap(long)]
+    force: bool,
+}
+
+pub fn run(args: IndexArgs) -> Result<()> {
+    let format = InputFormat::detect(&args.input)?.ok_or_else(|| {
+        GeozeroError::Dataset(format!("unknown format of `{}`", args.input.display()))
+    })?;
+    match format {
+        InputFormat::FlatGeobuf => index_fgb(&args.input, args.force),
+        InputFormat::GeoPackage => index_gpkg(&args.input, args.force),
+        InputFormat::Shapefile => index_shp(&args.input, args.force),
+        format => Err(GeozeroError::Dataset(format!(
+            "spatial index not supported for {:?} input",
+            format
+        ))),
+    }
+}
+
+/// Rewrite FlatGeobuf file with packed Hilbert R-tree
+fn index_fgb(path: &Path, force: bool) -> Result<()> {
+    let mut filein = BufReader::new(File::open(path)?);
+    let ds = FgbReader::open(&mut filein)?;
+    if ds.header().index_node_size() > 0 && !force {
+        println!("{}: already indexed", path.display());
+        return Ok(());
+    }
+    let name = ds.header().name().unwrap_or("fgb").to_string();
+    let feature_count = ds.header().features_count();
+    let mut fgb = FgbWriter::create(&name, ds.header().geometry_type())?;
+    let mut ds = ds.select_all()?;
+    ds.process_features(&mut fgb)?;
+
+    // Write next to the input and replace it, when complete
+    let tmp_path = path.with_extension("fgb.tmp");
+    let mut fout = BufWriter::new(File::create(&tmp_path)?);
+    fgb.write(&mut fout)?;
+    fout.flush()?;
+    drop(fout);
+    fs::rename(&tmp_path, path)?;
+    println!(
+        "{}: packed R-tree of {} features written",
+        path.display(),
+        feature_count
+    );
+    Ok(())
+}
+
+fn sqlite_error(e: rusqlite::Error) -> GeozeroError {
+    GeozeroError::Dataset(e.to_string())
+}
+
+/// Quote SQL identifier
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Create `gpkg_rtree_index` extension for all geometry columns of a GeoPackage
+fn index_gpkg(path: &Path, force: bool) -> Result<()> {
+    let conn = Connection::open(path).map_err(sqlite_error)?;
+    let columns = conn
+        .prepare("SELECT table_name, column_name FROM gpkg_geometry_columns")
+        .and_then(|mut stmt| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<Vec<(String, String)>>>()
+        })
+        .map_err(sqlite_error)?;
+    for (table, column) in columns {
+        index_gpkg_column(&conn, &table, &column, force).map_err(sqlite_error)?;
+    }
+    Ok(())
+}
+
+fn index_gpkg_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    force: bool,
+) -> rusqlite::Result<()> {
+    let rtree_name = format!("rtree_{}_{}", table, column);
+    let rtree = quoted(&rtree_name);
+    let exists = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE name = ?",
+            params![rtree_name],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()?
+        .is_some();
+    if exists && !force {
+        println!("{}.{}: already indexed", table, column);
+        return Ok(());
+    }
+    let pk: String = conn.query_row(
+        "SELECT name FROM pragma_table_info(?) WHERE pk = 1",
+        params![table],
+        |row| row.get(0),
+    )?;
+    let (t, c, i) = (quoted(table), quoted(column), quoted(&pk));
+
+    let tx = conn.unchecked_transaction()?;
+    if exists {
+        tx.execute_batch(&format!("DROP TABLE {};", rtree))?;
+        for suffix in &[
+            "insert", "update1", "update2", "update3", "update4", "delete",
+        ] {
+            tx.execute_batch(&format!(
+                "DROP TRIGGER IF EXISTS {};",
+                quoted(&format!("{}_{}", rtree_name, suffix))
+            ))?;
+        }
+    }
+    tx.execute_batch(&format!(
+        "CREATE VIRTUAL TABLE {} USING rtree(id, minx, maxx, miny, maxy);",
+        rtree
+    ))?;
+    let mut count = 0;
+    {
+        let mut select = tx.prepare(&format!("SELECT {}, {} FROM {}", i, c, t))?;
+        let mut insert = tx.prepare(&format!("INSERT INTO {} VALUES (?, ?, ?, ?, ?)", rtree))?;
+        let mut rows = select.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let geom: Option<Vec<u8>> = row.get(1)?;
+            if let Some(extent) = geom.as_deref().and_then(gpkg_extent) {
+                insert.execute(params![
+                    id,
+                    extent.minx,
+                    extent.maxx,
+                    extent.miny,
+                    extent.maxy
+                ])?;
+                count += 1;
+            }
+        }
+    }
+    // Triggers of the GeoPackage specification, Annex F.3
+    let envelope =
+        "NEW.{i}, ST_MinX(NEW.{c}), ST_MaxX(NEW.{c}), ST_MinY(NEW.{c}), ST_MaxY(NEW.{c})";
+    let triggers = [
+        ("insert", "AFTER INSERT ON {t} WHEN (NEW.{c} NOT NULL AND NOT ST_IsEmpty(NEW.{c}))",
+            "INSERT OR REPLACE INTO {r} VALUES ({envelope});"),
+        ("update1", "AFTER UPDATE OF {c} ON {t} WHEN OLD.{i} = NEW.{i} AND (NEW.{c} NOTNULL AND NOT ST_IsEmpty(NEW.{c}))",
+            "INSERT OR REPLACE INTO {r} VALUES ({envelope});"),
+        ("update2", "AFTER UPDATE OF {c} ON {t} WHEN OLD.{i} = NEW.{i} AND (NEW.{c} ISNULL OR ST_IsEmpty(NEW.{c}))",
+            "DELETE FROM {r} WHERE id = OLD.{i};"),
+        ("update3", "AFTER UPDATE ON {t} WHEN OLD.{i} != NEW.{i} AND (NEW.{c} NOTNULL AND NOT ST_IsEmpty(NEW.{c}))",
+            "DELETE FROM {r} WHERE id = OLD.{i}; INSERT OR REPLACE INTO {r} VALUES ({envelope});"),
+        ("update4", "AFTER UPDATE ON {t} WHEN OLD.{i} != NEW.{i} AND (NEW.{c} ISNULL OR ST_IsEmpty(NEW.{c}))",
+            "DELETE FROM {r} WHERE id IN (OLD.{i}, NEW.{i});"),
+        ("delete", "AFTER DELETE ON {t} WHEN OLD.{c} NOT NULL",
+            "DELETE FROM {r} WHERE id = OLD.{i};"),
+    ];
+    for (suffix, when, body) in &triggers {
+        let sql = format!(
+            "CREATE TRIGGER {} {} BEGIN {} END;",
+            quoted(&format!("{}_{}", rtree_name, suffix)),
+            when,
+            body
+        )
+        .replace("{envelope}", envelope)
+        .replace("{t}", &t)
+        .replace("{c}", &c)
+        .replace("{i}", &i)
+        .replace("{r}", &rtree);
+        tx.execute_batch(&sql)?;
+    }
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS gpkg_extensions (
+            table_name TEXT, column_name TEXT, extension_name TEXT NOT NULL,
+            definition TEXT NOT NULL, scope TEXT NOT NULL,
+            CONSTRAINT ge_tce UNIQUE (table_name, column_name, extension_name));",
+    )?;
+    tx.execute(
+        "INSERT OR REPLACE INTO gpkg_extensions (table_name, column_name, extension_name, definition, scope)
+         VALUES (?, ?, 'gpkg_rtree_index', 'http://www.geopackage.org/spec120/#extension_rtree', 'write-only')",
+        params![table, column],
+    )?;
+    tx.commit()?;
+    println!("{}.{}: R-tree of {} features written", table, column, count);
+    Ok(())
+}
+
+/// Extent of GeoPackage geometry, `None` for empty geometries
+fn gpkg_extent(blob: &[u8]) -> Option<Extent> {
+    let mut calc = ExtentCalc::new();
+    GpkgWkb(blob).process_geom(&mut calc).ok()?;
+    calc.extent()
+}
+
+/// Maximal depth of quadtree, as used by shapelib
+const MAX_TREE_DEPTH: usize = 12;
+/// Extent of node halves in percent of the split dimension
+const SPLIT_RATIO: f64 = 0.55;
+
+/// Quadtree node of a `.qix` spatial index
+struct QuadNode {
+    extent: Extent,
+    shape_ids: Vec<i32>,
+    children: Vec<QuadNode>,
+}
+
+impl QuadNode {
+    fn new(extent: Extent) -> Self {
+        QuadNode {
+            extent,
+            shape_ids: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+    /// Insert shape into the smallest node containing it
+    fn insert(&mut self, id: i32, extent: &Extent, depth: usize) {
+        if depth > 1 {
+            if self.children.is_empty() {
+                let (half1, half2) = split(&self.extent);
+                let (quad1, quad2) = split(&half1);
+                let (quad3, quad4) = split(&half2);
+                let quads = [quad1, quad2, quad3, quad4];
+                if quads.iter().any(|quad| contains(quad, extent)) {
+                    self.children = quads.iter().map(|quad| QuadNode::new(*quad)).collect();
+                }
+            }
+            if let Some(child) = self
+                .children
+                .iter_mut()
+                .find(|child| contains(&child.extent, extent))
+            {
+                child.insert(id, extent, depth - 1);
+                return;
+            }
+        }
+        self.shape_ids.push(id);
+    }
+    /// Remove subtrees without shapes, returns `true` if this node is empty
+    fn trim(&mut self) -> bool {
+        let empty: Vec<bool> = self.children.iter_mut().map(QuadNode::trim).collect();
+        let mut empty = empty.into_iter();
+        self.children.retain(|_| !empty.next().unwrap_or(false));
+        self.shape_ids.is_empty() && self.children.is_empty()
+    }
+    /// Size of all descendant node records in bytes
+    fn subtree_size(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.subtree_size() + 44 + 4 * child.shape_ids.len())
+            .sum()
+    }
+    fn write<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        out.write_all(&(self.subtree_size() as i32).to_le_bytes())?;
+        for v in &[
+            self.extent.minx,
+            self.extent.miny,
+            self.extent.maxx,
+            self.extent.maxy,
+        ] {
+            out.write_all(&v.to_le_bytes())?;
+        }
+        out.write_all(&(self.shape_ids.len() as i32).to_le_bytes())?;
+        for id in &self.shape_ids {
+            out.write_all(&id.to_le_bytes())?;
+        }
+        out.write_all(&(self.children.len() as i32).to_le_bytes())?;
+        for child in &self.children {
+            child.write(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split extent along its longer side into two overlapping halves
+fn split(extent: &Extent) -> (Extent, Extent) {
+    let (mut half1, mut half2) = (*extent, *extent);
+    if extent.width() > extent.height() {
+        let range = extent.width() * SPLIT_RATIO;
+        half1.maxx = extent.minx + range;
+        half2.minx = extent.maxx - range;
+    } else {
+        let range = extent.height() * SPLIT_RATIO;
+        half1.maxy = extent.miny + range;
+        half2.miny = extent.maxy - range;
+    }
+    (half1, half2)
+}
+
+fn contains(outer: &Extent, inner: &Extent) -> bool {
+    outer.minx <= inner.minx
+        && outer.miny <= inner.miny
+        && outer.maxx >= inner.maxx
+        && outer.maxy >= inner.maxy
+}
+
+fn le_f64(buf: &[u8], pos: usize) -> f64 {
+    f64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap())
+}
+
+/// Shapefile extent and shape extents (`None` for NULL shapes)
+fn read_shape_extents(path: &Path) -> Result<(Extent, Vec<Option<Extent>>)> {
+    let mut data = Vec::new();
+    BufReader::new(File::open(path)?).read_to_end(&mut data)?;
+    if data.len() < 100 {
+        return Err(GeozeroError::Dataset(
+            "invalid shapefile header".to_string(),
+        ));
+    }
+    let bounds = Extent::new(
+        le_f64(&data, 36),
+        le_f64(&data, 44),
+        le_f64(&data, 52),
+        le_f64(&data, 60),
+    );
+    let mut extents = Vec::new();
+    let mut pos = 100;
+    while pos + 12 <= data.len() {
+        // Record header: record number and content length in 16-bit words (big endian)
+        let len = i32::from_be_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize * 2;
+        let content = data
+            .get(pos + 8..pos + 8 + len)
+            .ok_or_else(|| GeozeroError::Dataset("truncated shapefile record".to_string()))?;
+        let shape_type = i32::from_le_bytes(content[0..4].try_into().unwrap());
+        let extent = match shape_type {
+            0 => None,
+            1 | 11 | 21 => Some(Extent::from_point(le_f64(content, 4), le_f64(content, 12))),
+            _ => Some(Extent::new(
+                le_f64(content, 4),
+                le_f64(content, 12),
+                le_f64(content, 20),
+                le_f64(content, 28),
+            )),
+        };
+        extents.push(extent);
+        pos += 8 + len;
+    }
+    Ok((bounds, extents))
+}
+
+/// Write `.qix` quadtree index (MapServer/shapelib format) next to a shapefile
+fn index_shp(path: &Path, force: bool) -> Result<()> {
+    let qix_path = path.with_extension("qix");
+    if qix_path.exists() && !force {
+        println!("{}: already indexed", path.display());
+        return Ok(());
+    }
+    let (bounds, extents) = read_shape_extents(path)?;
+    let mut depth = 0;
+    let mut max_node_count = 1;
+    while max_node_count * 4 < extents.len() {
+        depth += 1;
+        max_node_count *= 2;
+    }
+    let depth = depth.clamp(1, MAX_TREE_DEPTH);
+    let mut root = QuadNode::new(bounds);
+    for (id, extent) in extents.iter().enumerate() {
+        if let Some(extent) = extent {
+            root.insert(id as i32, extent, depth);
+        }
+    }
+    root.trim();
+
+    let mut fout = BufWriter::new(File::create(&qix_path)?);
+    // Signature, little endian byte order, version 1
+    fout.write_all(b"SQT\x03\x01\0\0\0")?;
+    fout.write_all(&(extents.len() as i32).to_le_bytes())?;
+    fout.write_all(&(depth as i32).to_le_bytes())?;
+    root.write(&mut fout)?;
+    fout.flush()?;
+    println!(
+        "{}: quadtree of {} shapes written",
+        qix_path.display(),
+        extents.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! `geozero info` command
+use crate::input::{InputArgs, Selection};
+use clap::Args;
+use geozero::error::Result;
+use geozero::processor::StatsCollector;
+use geozero::{
+    ColumnDef, ColumnStatsCollector, ColumnValue, CoordDimensions, Crs, FeatureProcessor,
+    GeomProcessor, PropertyProcessor,
+};
+
+#[derive(Args)]
+pub struct InfoArgs {
+    #[clap(flatten)]
+    input: InputArgs,
+    /// Number of features sampled for column statistics (0: all features)
+    #[clap(long, default_value = "1000")]
+    sample: u64,
+}
+
+/// Collects dataset metadata, geometry statistics and sampled column statistics
+struct InfoCollector {
+    layers: Vec<String>,
+    crs: Option<Crs>,
+    schema: Vec<ColumnDef>,
+    geometries: StatsCollector,
+    columns: ColumnStatsCollector,
+    sample: u64,
+    /// Current feature is sampled
+    sampled: bool,
+}
+
+impl InfoCollector {
+    fn new(sample: u64) -> Self {
+        InfoCollector {
+            layers: Vec::new(),
+            crs: None,
+            schema: Vec::new(),
+            geometries: StatsCollector::new(),
+            columns: ColumnStatsCollector::new(),
+            sample,
+            sampled: false,
+        }
+    }
+}
+
+impl FeatureProcessor for InfoCollector {
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        if let Some(name) = name {
+            self.layers.push(name.to_string());
+        }
+        Ok(())
+    }
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.schema = columns.to_vec();
+        Ok(())
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        self.crs = Some(crs.clone());
+        Ok(())
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.sampled = self.sample == 0 || self.columns.feature_count() < self.sample;
+        if self.sampled {
+            self.columns.feature_begin(idx)?;
+        }
+        self.geometries.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        if self.sampled {
+            self.columns.feature_end(idx)?;
+        }
+        self.geometries.feature_end(idx)
+    }
+}
+
+impl PropertyProcessor for InfoCollector {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if self.sampled {
+            self.columns.property(idx, name, value)?;
+        }
+        Ok(false)
+    }
+}
+
+/// Forward geometry calls to the geometry statistics
+macro_rules! forward_to_stats {
+    ($( $method:ident($($arg:ident: $ty:ty),*); )*) => {
+        $(
+            fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
+                self.geometries.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl GeomProcessor for InfoCollector {
+    fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions::xyzm()
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.geometries.coordinate(x, y, z, m, t, tm, idx)
+    }
+    forward_to_stats! {
+        xy(x: f64, y: f64, idx: usize);
+        empty_point(idx: usize);
+        point_begin(idx: usize);
+        point_end(idx: usize);
+        multipoint_begin(size: usize, idx: usize);
+        multipoint_end(idx: usize);
+        linestring_begin(tagged: bool, size: usize, idx: usize);
+        linestring_end(tagged: bool, idx: usize);
+        multilinestring_begin(size: usize, idx: usize);
+        multilinestring_end(idx: usize);
+        polygon_begin(tagged: bool, size: usize, idx: usize);
+        polygon_end(tagged: bool, idx: usize);
+        multipolygon_begin(size: usize, idx: usize);
+        multipolygon_end(idx: usize);
+        geometrycollection_begin(size: usize, idx: usize);
+        geometrycollection_end(idx: usize);
+        circularstring_begin(size: usize, idx: usize);
+        circularstring_end(idx: usize);
+        compoundcurve_begin(size: usize, idx: usize);
+        compoundcurve_end(idx: usize);
+        curvepolygon_begin(size: usize, idx: usize);
+        curvepolygon_end(idx: usize);
+        multicurve_begin(size: usize, idx: usize);
+        multicurve_end(idx: usize);
+        multisurface_begin(size: usize, idx: usize);
+        multisurface_end(idx: usize);
+        triangle_begin(tagged: bool, size: usize, idx: usize);
+        triangle_end(tagged: bool, idx: usize);
+        polyhedralsurface_begin(size: usize, idx: usize);
+        polyhedralsurface_end(idx: usize);
+        tin_begin(size: usize, idx: usize);
+        tin_end(idx: usize);
+    }
+}
+
+pub fn run(args: InfoArgs) -> Result<()> {
+    let format = args.input.format()?;
+    let (stats, info) = args
+        .input
+        .process(&Selection::default(), InfoCollector::new(args.sample))?;
+
+    println!("Input: {} ({})", args.input.input, format);
+    if !info.layers.is_empty() {
+        println!("Layers: {}", info.layers.join(", "));
+    }
+    match &info.crs {
+        Some(crs) => println!("CRS: {}", crs),
+        None => println!("CRS: unknown"),
+    }
+    println!("Features: {}", stats.feature_count);
+    let geometry_types = info
+        .geometries
+        .geometry_types
+        .iter()
+        .map(|(geometry_type, count)| format!("{} ({})", geometry_type, count))
+        .collect::<Vec<_>>();
+    println!("Geometry types: {}", geometry_types.join(", "));
+    let dims = info.geometries.dimensions();
+    println!(
+        "Dimensions: XY{}{}",
+        if dims.z { "Z" } else { "" },
+        if dims.m { "M" } else { "" }
+    );
+    if let Some(e) = stats.extent {
+        println!(
+            "Extent: ({}, {}) - ({}, {})",
+            e.minx, e.miny, e.maxx, e.maxy
+        );
+    }
+
+    let columns = info.columns.stats();
+    println!(
+        "Columns (statistics of {} features):",
+        info.columns.feature_count()
+    );
+    for def in &info.schema {
+        if !columns.iter().any(|col| col.name == def.name) {
+            println!("  {}: {:?}", def.name, def.col_type);
+        }
+    }
+    for col in columns {
+        let col_type = info
+            .schema
+            .iter()
+            .find(|def| def.name == col.name)
+            .map_or(col.col_type, |def| def.col_type);
+        let mut line = format!(
+            "  {}: {:?}, {} values, {} nulls, ~{} distinct",
+            col.name,
+            col_type,
+            col.count,
+            col.null_count,
+            col.distinct_count()
+        );
+        if let (Some(min), Some(max)) = (&col.min, &col.max) {
+            line.push_str(&format!(
+                ", min {}, max {}",
+                min.as_column_value(),
+                max.as_column_value()
+            ));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
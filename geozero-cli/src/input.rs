@@ -0,0 +1,330 @@
+//! Input datasources
+use clap::Args;
+use flatgeobuf::reader_state::FeaturesSelectedSeek;
+use flatgeobuf::*;
+use geozero::error::{GeozeroError, Result};
+use geozero::geojson::GeoJsonLineReader;
+use geozero::processor::{
+    AttributeFilter, BboxFilter, Expr, Extent, ExtentCalc, Progress, PropertyMapper,
+};
+use geozero::{
+    ConvertStats, DetectedReader, DetectedSource, FeatureProcessor, GeozeroDatasource, InputFormat,
+    ReadOptions,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Cursor, Read, Seek};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Args)]
+pub struct InputArgs {
+    /// The path to the file to read, the URL of a FlatGeobuf file, or `-` for stdin.
+    /// The input format is detected from the file content or extension.
+    pub input: String,
+    /// Input format (fgb, geojson, geojsonl, gpx, csv, wkt)
+    #[clap(long, value_name = "FORMAT")]
+    from: Option<String>,
+    /// When processing CSV, the name of the column holding a WKT geometry (default: WKT).
+    #[clap(long)]
+    csv_geometry_column: Option<String>,
+    /// Don't show a progress bar
+    #[clap(long)]
+    no_progress: bool,
+}
+
+/// Parse `minx,miny,maxx,maxy`
+pub fn parse_bbox(src: &str) -> std::result::Result<Extent, String> {
+    let arr = src
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| format!("invalid bbox `{}`: {}", src, e))?;
+    match arr[..] {
+        [minx, miny, maxx, maxy] if minx <= maxx && miny <= maxy => {
+            Ok(Extent::new(minx, miny, maxx, maxy))
+        }
+        [_, _, _, _] => Err(format!("invalid bbox `{}`: min greater than max", src)),
+        _ => Err(format!("expected `minx,miny,maxx,maxy`, got `{}`", src)),
+    }
+}
+
+/// Features and columns selected from the input
+#[derive(Default)]
+pub struct Selection {
+    /// Features intersecting bbox
+    pub bbox: Option<Extent>,
+    /// Features matching filter expression
+    pub filter: Option<Expr>,
+    /// Property columns in output order
+    pub columns: Option<Vec<String>>,
+}
+
+impl Selection {
+    /// Columns read from the input, including columns referenced by the filter
+    fn read_options(&self) -> ReadOptions {
+        let columns = self.columns.as_ref().map(|columns| {
+            let mut columns = columns.clone();
+            if let Some(filter) = &self.filter {
+                for name in filter.properties() {
+                    if !columns.iter().any(|col| col == name) {
+                        columns.push(name.to_string());
+                    }
+                }
+            }
+            columns
+        });
+        ReadOptions {
+            columns,
+            ..Default::default()
+        }
+    }
+}
+
+/// Input format
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Input {
+    Detected(InputFormat),
+    /// Newline-delimited GeoJSON
+    GeoJsonLines,
+}
+
+impl Input {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "geojsonl" | "geojsons" | "ndjson" => Some(Input::GeoJsonLines),
+            name => InputFormat::from_extension(name).map(Input::Detected),
+        }
+    }
+}
+
+impl std::fmt::Display for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Input::Detected(format) => write!(f, "{}", format),
+            Input::GeoJsonLines => f.write_str("GeoJSON lines"),
+        }
+    }
+}
+
+/// FlatGeobuf reader with selected features
+struct FgbFeatures<'a, R: Read + Seek>(FgbReader<'a, R, FeaturesSelectedSeek>);
+
+impl<R: Read + Seek> GeozeroDatasource for FgbFeatures<'_, R> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.0.process_features(processor)?;
+        Ok(())
+    }
+}
+
+impl InputArgs {
+    /// Input URL
+    pub fn is_url(&self) -> bool {
+        self.input.starts_with("http")
+    }
+    /// Input from stdin
+    pub fn is_stdin(&self) -> bool {
+        self.input == "-"
+    }
+    /// Format given with `--from` or detected from the input file
+    pub fn format(&self) -> Result<Input> {
+        let path = Path::new(&self.input);
+        match &self.from {
+            Some(name) => Input::from_name(name)
+                .ok_or_else(|| GeozeroError::Dataset(format!("unknown input format `{}`", name))),
+            None if self.is_stdin() => Err(GeozeroError::Dataset(
+                "reading from stdin requires --from".to_string(),
+            )),
+            None => match path
+                .extension()
+                .and_then(OsStr::to_str)
+                .map(Input::from_name)
+            {
+                Some(Some(Input::GeoJsonLines)) => Ok(Input::GeoJsonLines),
+                _ => InputFormat::detect(path)?
+                    .map(Input::Detected)
+                    .ok_or_else(|| {
+                        GeozeroError::Dataset(format!("unknown format of `{}`", path.display()))
+                    }),
+            },
+        }
+    }
+    /// Progress bar on stderr, showing read bytes and processed features
+    fn progress_bar(&self, format: Input) -> ProgressBar {
+        if self.no_progress {
+            return ProgressBar::hidden();
+        }
+        let size = fs::metadata(&self.input)
+            .ok()
+            .map(|metadata| metadata.len());
+        let (pb, template) = match (format, size) {
+            // CSV files are read by path
+            (Input::Detected(InputFormat::Csv), _) if !self.is_stdin() => (
+                ProgressBar::new_spinner(),
+                "{spinner} [{elapsed_precise}] {msg}",
+            ),
+            (_, Some(size)) if !self.is_stdin() => (
+                ProgressBar::new(size),
+                "{spinner} [{elapsed_precise}] {wide_bar} {bytes}/{total_bytes} {msg}",
+            ),
+            _ => (
+                ProgressBar::new_spinner(),
+                "{spinner} [{elapsed_precise}] {bytes} {msg}",
+            ),
+        };
+        pb.set_style(ProgressStyle::with_template(template).expect("valid template"));
+        pb.enable_steady_tick(Duration::from_millis(200));
+        pb
+    }
+    /// Process selected input features and return the processor.
+    ///
+    /// The spatial index of the input is used where available, otherwise
+    /// features are filtered with a [BboxFilter]. Only selected columns are
+    /// read, if supported by the format. Progress is shown on stderr.
+    pub fn process<P: FeatureProcessor>(
+        &self,
+        selection: &Selection,
+        processor: P,
+    ) -> Result<(ConvertStats, P)> {
+        let format = self.format()?;
+        let pb = self.progress_bar(format);
+        let processor = Progress::new(processor, |count| {
+            pb.set_message(format!("{} features", count))
+        });
+        let (stats, processor) = self.process_format(format, selection, processor, &pb)?;
+        pb.finish_and_clear();
+        Ok((stats, processor.into_inner()))
+    }
+    /// Process selected features, counting read bytes with `pb`
+    fn process_format<P: FeatureProcessor>(
+        &self,
+        format: Input,
+        selection: &Selection,
+        processor: P,
+        pb: &ProgressBar,
+    ) -> Result<(ConvertStats, P)> {
+        let pathin = Path::new(&self.input);
+        let bbox = selection.bbox.as_ref();
+        let stdin = io::stdin();
+        match format {
+            Input::Detected(InputFormat::FlatGeobuf) if self.is_stdin() => {
+                // FlatGeobuf is read with random access
+                let mut data = Vec::new();
+                pb.wrap_read(stdin.lock()).read_to_end(&mut data)?;
+                process_fgb(&mut Cursor::new(data), selection, processor)
+            }
+            Input::Detected(InputFormat::FlatGeobuf) => {
+                let mut filein = BufReader::new(pb.wrap_read(File::open(pathin)?));
+                process_fgb(&mut filein, selection, processor)
+            }
+            Input::Detected(InputFormat::Csv) if !self.is_stdin() => {
+                // Reads CRS from `.prj` sidecar file
+                let mut ds = DetectedSource::with_format(pathin, InputFormat::Csv);
+                if let Some(geometry_column_name) = &self.csv_geometry_column {
+                    ds = ds.csv_geometry_column(geometry_column_name);
+                }
+                process_selected(&mut ds, selection, bbox, processor)
+            }
+            Input::Detected(format) => {
+                let reader: Box<dyn Read + '_> = if self.is_stdin() {
+                    Box::new(stdin.lock())
+                } else {
+                    Box::new(File::open(pathin)?)
+                };
+                let mut ds = DetectedReader::new(BufReader::new(pb.wrap_read(reader)), format);
+                if let Some(geometry_column_name) = &self.csv_geometry_column {
+                    ds = ds.csv_geometry_column(geometry_column_name);
+                }
+                process_selected(&mut ds, selection, bbox, processor)
+            }
+            Input::GeoJsonLines => {
+                let reader: Box<dyn Read + '_> = if self.is_stdin() {
+                    Box::new(stdin.lock())
+                } else {
+                    Box::new(File::open(pathin)?)
+                };
+                let mut ds = GeoJsonLineReader(BufReader::new(pb.wrap_read(reader)));
+                process_selected(&mut ds, selection, bbox, processor)
+            }
+        }
+    }
+}
+
+/// Process FlatGeobuf features, selected with the spatial index
+fn process_fgb<R: Read + Seek, P: FeatureProcessor>(
+    reader: &mut R,
+    selection: &Selection,
+    processor: P,
+) -> Result<(ConvertStats, P)> {
+    let ds = FgbReader::open(reader)?;
+    let ds = if let Some(bbox) = &selection.bbox {
+        ds.select_bbox(bbox.minx, bbox.miny, bbox.maxx, bbox.maxy)?
+    } else {
+        ds.select_all()?
+    };
+    process_selected(&mut FgbFeatures(ds), selection, None, processor)
+}
+
+/// Process selected features of `source`, counting passed features only.
+///
+/// Features are filtered with `bbox`, unless already selected by the source.
+fn process_selected<D: GeozeroDatasource, P: FeatureProcessor>(
+    source: &mut D,
+    selection: &Selection,
+    bbox: Option<&Extent>,
+    processor: P,
+) -> Result<(ConvertStats, P)> {
+    let options = selection.read_options();
+    let calc = ExtentCalc::tee(processor);
+    let calc = match &selection.columns {
+        Some(columns) => {
+            let names: Vec<&str> = columns.iter().map(String::as_str).collect();
+            let mapper = PropertyMapper::new(calc).select(&names).order(&names);
+            filter_attributes(source, &options, selection, bbox, mapper)?.into_inner()
+        }
+        None => filter_attributes(source, &options, selection, bbox, calc)?,
+    };
+    let stats = ConvertStats {
+        feature_count: calc.feature_count(),
+        vertex_count: calc.vertex_count(),
+        extent: calc.extent(),
+    };
+    Ok((stats, calc.into_inner()))
+}
+
+fn filter_attributes<D: GeozeroDatasource, P: FeatureProcessor>(
+    source: &mut D,
+    options: &ReadOptions,
+    selection: &Selection,
+    bbox: Option<&Extent>,
+    processor: P,
+) -> Result<P> {
+    match &selection.filter {
+        Some(expr) => {
+            let filter = AttributeFilter::new(processor, expr.clone());
+            Ok(filter_bbox(source, options, bbox, filter)?.into_inner())
+        }
+        None => filter_bbox(source, options, bbox, processor),
+    }
+}
+
+fn filter_bbox<D: GeozeroDatasource, P: FeatureProcessor>(
+    source: &mut D,
+    options: &ReadOptions,
+    bbox: Option<&Extent>,
+    processor: P,
+) -> Result<P> {
+    match bbox {
+        Some(bbox) => {
+            let mut filter = BboxFilter::new(processor, *bbox);
+            source.process_with_options(&mut filter, options)?;
+            Ok(filter.into_inner())
+        }
+        None => {
+            let mut processor = processor;
+            source.process_with_options(&mut processor, options)?;
+            Ok(processor)
+        }
+    }
+}
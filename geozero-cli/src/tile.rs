@@ -0,0 +1,199 @@
+//! `geozero tile` command
+use crate::input::{InputArgs, Selection};
+use clap::Args;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use geozero::error::{GeozeroError, Result};
+use geozero::mvt::MvtTiler;
+use geozero::processor::{Extent, Reproject, MAX_MERCATOR_LAT};
+use geozero::Crs;
+use rayon::prelude::*;
+use rusqlite::{params, Connection};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct TileArgs {
+    #[clap(flatten)]
+    input: InputArgs,
+    /// Minimal zoom level
+    #[clap(long, default_value = "0")]
+    minzoom: u8,
+    /// Maximal zoom level
+    #[clap(long, default_value = "14")]
+    maxzoom: u8,
+    /// MBTiles file (`.mbtiles`) or directory for `{z}/{x}/{y}.pbf` tiles
+    #[clap(short, long, parse(from_os_str))]
+    output: PathBuf,
+    /// Layer name (default: name of input file)
+    #[clap(long)]
+    layer: Option<String>,
+    /// Simplification tolerance in pixels of 4096 pixel tiles (0: no simplification)
+    #[clap(long, default_value = "1")]
+    simplify: f64,
+}
+
+impl TileArgs {
+    fn layer_name(&self) -> String {
+        match &self.layer {
+            Some(name) => name.clone(),
+            None if self.input.is_stdin() || self.input.is_url() => "features".to_string(),
+            None => Path::new(&self.input.input)
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .unwrap_or("features")
+                .to_string(),
+        }
+    }
+}
+
+/// Destination of encoded tiles
+enum TileOutput {
+    /// MBTiles SQLite database with gzipped tiles
+    MbTiles(Connection),
+    /// Directory with `{z}/{x}/{y}.pbf` tiles
+    Directory(PathBuf),
+}
+
+fn sqlite_error(e: rusqlite::Error) -> GeozeroError {
+    GeozeroError::Dataset(e.to_string())
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    gz.write_all(data)?;
+    Ok(gz.finish()?)
+}
+
+impl TileOutput {
+    fn create(path: &Path) -> Result<Self> {
+        if path.extension().and_then(OsStr::to_str) == Some("mbtiles") {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            let conn = Connection::open(path).map_err(sqlite_error)?;
+            conn.execute_batch(
+                "CREATE TABLE metadata (name TEXT, value TEXT);
+                 CREATE TABLE tiles (zoom_level INTEGER, tile_column INTEGER, tile_row INTEGER, tile_data BLOB);
+                 CREATE UNIQUE INDEX tile_index ON tiles (zoom_level, tile_column, tile_row);
+                 BEGIN;",
+            )
+            .map_err(sqlite_error)?;
+            Ok(TileOutput::MbTiles(conn))
+        } else {
+            fs::create_dir_all(path)?;
+            Ok(TileOutput::Directory(path.to_path_buf()))
+        }
+    }
+    /// Tiles are stored gzip compressed
+    fn compressed(&self) -> bool {
+        matches!(self, TileOutput::MbTiles(_))
+    }
+    fn write_tile(&self, z: u8, x: u32, y: u32, data: &[u8]) -> Result<()> {
+        match self {
+            TileOutput::MbTiles(conn) => {
+                // MBTiles rows are numbered from the south (TMS scheme)
+                let row = (1u32 << z) - 1 - y;
+                conn.execute(
+                    "INSERT INTO tiles (zoom_level, tile_column, tile_row, tile_data) VALUES (?, ?, ?, ?)",
+                    params![z, x, row, data],
+                )
+                .map_err(sqlite_error)?;
+            }
+            TileOutput::Directory(dir) => {
+                let dir = dir.join(z.to_string()).join(x.to_string());
+                fs::create_dir_all(&dir)?;
+                fs::write(dir.join(format!("{}.pbf", y)), data)?;
+            }
+        }
+        Ok(())
+    }
+    fn finish(self, tiler: &MvtTiler) -> Result<()> {
+        let bounds = tiler
+            .bounds()
+            .unwrap_or_else(|| Extent::new(-180.0, -MAX_MERCATOR_LAT, 180.0, MAX_MERCATOR_LAT));
+        match self {
+            TileOutput::MbTiles(conn) => {
+                let vector_layers = serde_json::json!({
+                    "vector_layers": [{
+                        "id": tiler.layer_name(),
+                        "fields": {},
+                        "minzoom": tiler.minzoom(),
+                        "maxzoom": tiler.maxzoom(),
+                    }]
+                });
+                let metadata = [
+                    ("name", tiler.layer_name().to_string()),
+                    ("format", "pbf".to_string()),
+                    ("type", "overlay".to_string()),
+                    ("minzoom", tiler.minzoom().to_string()),
+                    ("maxzoom", tiler.maxzoom().to_string()),
+                    (
+                        "bounds",
+                        format!(
+                            "{},{},{},{}",
+                            bounds.minx, bounds.miny, bounds.maxx, bounds.maxy
+                        ),
+                    ),
+                    ("json", vector_layers.to_string()),
+                ];
+                for (name, value) in &metadata {
+                    conn.execute(
+                        "INSERT INTO metadata (name, value) VALUES (?, ?)",
+                        params![name, value],
+                    )
+                    .map_err(sqlite_error)?;
+                }
+                conn.execute_batch("COMMIT;").map_err(sqlite_error)?;
+            }
+            TileOutput::Directory(_) => {}
+        }
+        Ok(())
+    }
+}
+
+pub fn run(args: TileArgs) -> Result<()> {
+    if args.minzoom > args.maxzoom || args.maxzoom > 24 {
+        return Err(GeozeroError::Dataset(
+            "invalid zoom range, minzoom <= maxzoom <= 24 required".to_string(),
+        ));
+    }
+    let tiler =
+        MvtTiler::new(&args.layer_name(), args.minzoom, args.maxzoom).simplify(args.simplify);
+    let (_, reproject) = args
+        .input
+        .process(&Selection::default(), Reproject::new(tiler, Crs::wgs84()))?;
+    let tiler = reproject.into_inner();
+
+    let output = TileOutput::create(&args.output)?;
+    let compressed = output.compressed();
+    let mut tile_count = 0;
+    for zoom in tiler.minzoom()..=tiler.maxzoom() {
+        // Tiles of a zoom level are encoded in parallel and written sequentially
+        let tiles = tiler
+            .tiles(zoom)
+            .par_iter()
+            .map(|tile| {
+                let data = match tile.encode()? {
+                    Some(data) if compressed => gzip(&data)?,
+                    Some(data) => data,
+                    None => return Ok(None),
+                };
+                Ok(Some((tile.x, tile.y, data)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        for (x, y, data) in tiles.into_iter().flatten() {
+            output.write_tile(zoom, x, y, &data)?;
+            tile_count += 1;
+        }
+    }
+    output.finish(&tiler)?;
+    println!(
+        "Generated {} tiles of {} features",
+        tile_count,
+        tiler.feature_count()
+    );
+    Ok(())
+}
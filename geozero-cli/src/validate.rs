@@ -0,0 +1,71 @@
+//! `geozero validate` command
+use crate::input::{Input, InputArgs, Selection};
+use clap::Args;
+use geozero::error::Result;
+use geozero::processor::{ValidityCheck, ValidityIssueKind};
+use geozero::{InputFormat, ProcessorSink, SchemaCheck};
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    #[clap(flatten)]
+    input: InputArgs,
+    /// Fail also on warnings (e.g. ring orientation of GeoJSON polygons)
+    #[clap(long)]
+    strict: bool,
+    /// Skip RFC 7946 checks of GeoJSON input
+    #[clap(long)]
+    no_rfc7946: bool,
+}
+
+/// Number of reported problems
+#[derive(Default)]
+struct Counts {
+    errors: usize,
+    warnings: usize,
+}
+
+impl Counts {
+    fn error(&mut self, msg: &dyn std::fmt::Display) {
+        self.errors += 1;
+        println!("error: {}", msg);
+    }
+    fn warning(&mut self, msg: &dyn std::fmt::Display) {
+        self.warnings += 1;
+        println!("warning: {}", msg);
+    }
+}
+
+/// Validate INPUT and exit with status 1 if errors (or warnings with `--strict`) were found
+pub fn run(args: ValidateArgs) -> Result<()> {
+    let geojson = matches!(
+        args.input.format()?,
+        Input::Detected(InputFormat::GeoJson) | Input::GeoJsonLines
+    );
+    let mut geometry_counts = Counts::default();
+    let mut schema_counts = Counts::default();
+    let geometry_check = ValidityCheck::new(ProcessorSink::new(), |issue| match issue.kind {
+        // RFC 7946 recommendations
+        ValidityIssueKind::RingOrientation { .. } | ValidityIssueKind::AntimeridianCrossing => {
+            geometry_counts.warning(issue)
+        }
+        _ => geometry_counts.error(issue),
+    });
+    let geometry_check = if geojson && !args.no_rfc7946 {
+        geometry_check.rfc7946()
+    } else {
+        geometry_check
+    };
+    let checker = SchemaCheck::new(geometry_check, |issue| schema_counts.error(issue));
+    let (stats, _) = args.input.process(&Selection::default(), checker)?;
+
+    let errors = geometry_counts.errors + schema_counts.errors;
+    let warnings = geometry_counts.warnings;
+    println!(
+        "{}: {} features, {} errors, {} warnings",
+        args.input.input, stats.feature_count, errors, warnings
+    );
+    if errors > 0 || args.strict && warnings > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
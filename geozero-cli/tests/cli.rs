@@ -0,0 +1,72 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const PLACES: &str = "../geozero/tests/data/places.json";
+
+fn geozero(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_geozero"))
+        .args(args)
+        .output()
+        .expect("geozero not executable");
+    assert!(
+        output.status.success(),
+        "geozero {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn tmp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("geozero-cli-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn convert_and_info() {
+    let fgb = tmp_path("places.fgb");
+    let fgb = fgb.to_str().unwrap();
+    geozero(&["convert", "--no-progress", PLACES, fgb]);
+
+    let output = geozero(&["info", "--no-progress", fgb]);
+    let info = String::from_utf8(output.stdout).unwrap();
+    assert!(info.contains("(FlatGeobuf)"), "{}", info);
+    assert!(info.contains("Features: 1249"), "{}", info);
+    assert!(info.contains("Geometry types: Point (1249)"), "{}", info);
+
+    let output = geozero(&[
+        "convert",
+        "--no-progress",
+        "--bbox",
+        "8,47,9,48",
+        "--select",
+        "NAME",
+        fgb,
+        "-",
+        "--to",
+        "geojson",
+    ]);
+    let geojson = String::from_utf8(output.stdout).unwrap();
+    assert!(geojson.contains(r#""NAME": "Zürich""#), "{}", geojson);
+    assert!(!geojson.contains("ADM0_A3"), "{}", geojson);
+    fs::remove_file(fgb).unwrap();
+}
+
+#[test]
+fn convert_reprojected() {
+    let output = geozero(&[
+        "convert",
+        "--no-progress",
+        "--bbox",
+        "8,47,9,48",
+        "--t_srs",
+        "EPSG:3857",
+        PLACES,
+        "-",
+        "--to",
+        "wkt",
+    ]);
+    let wkt = String::from_utf8(output.stdout).unwrap();
+    assert!(wkt.starts_with("POINT(951566."), "{}", wkt);
+}
@@ -0,0 +1,33 @@
+/// Regenerate `include/geozero.h` with feature `generate-header`.
+///
+/// The header is committed, regular builds don't modify the source tree.
+#[cfg(feature = "generate-header")]
+fn main() {
+    use std::env;
+    use std::path::PathBuf;
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let header = crate_dir.join("include").join("geozero.h");
+    let generated = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .map_err(|e| e.to_string())
+        .and_then(|config| {
+            cbindgen::Builder::new()
+                .with_crate(&crate_dir)
+                .with_config(config)
+                .generate()
+                .map_err(|e| e.to_string())
+        });
+    match generated {
+        Ok(bindings) => {
+            bindings.write_to_file(header);
+        }
+        Err(e) => println!("cargo:warning=Unable to generate C header: {}", e),
+    }
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
+
+#[cfg(not(feature = "generate-header"))]
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+}
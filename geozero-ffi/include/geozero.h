@@ -0,0 +1,106 @@
+/* Generated with cbindgen from geozero-ffi. Do not edit. */
+
+#ifndef GEOZERO_H
+#define GEOZERO_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result status of API calls
+typedef enum GzStatus {
+  GZ_STATUS_OK = 0,
+  GZ_STATUS_ERROR = 1,
+} GzStatus;
+
+// Opened datasource with all features read into memory
+typedef struct GzDatasource GzDatasource;
+
+// Feature with WKB geometry and string properties
+typedef struct GzFeature GzFeature;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Open the datasource at `path` and read all features.
+//
+// The format is detected from the file content or extension.
+// Returns NULL on failure, see `gz_last_error`.
+//
+// # Safety
+//
+// `path` must be NULL or a valid NUL-terminated string.
+GzDatasource *gz_open(const char *path);
+
+// Free a datasource returned by `gz_open`.
+//
+// # Safety
+//
+// `ds` must be returned by `gz_open` and not be used afterwards.
+void gz_datasource_free(GzDatasource *ds);
+
+// Number of features of the datasource.
+//
+// # Safety
+//
+// `ds` must be a valid datasource.
+uintptr_t gz_feature_count(const GzDatasource *ds);
+
+// Feature at index `idx`, or NULL if out of range.
+//
+// # Safety
+//
+// `ds` must be a valid datasource.
+const GzFeature *gz_feature(const GzDatasource *ds, uintptr_t idx);
+
+// Geometry of the feature as OGC WKB with its length in bytes stored in `len`.
+//
+// Features without geometry have a length of 0.
+//
+// # Safety
+//
+// `feature` must be a valid feature and `len` a valid pointer.
+const uint8_t *gz_feature_wkb(const GzFeature *feature, uintptr_t *len);
+
+// Number of properties of the feature.
+//
+// # Safety
+//
+// `feature` must be a valid feature.
+uintptr_t gz_feature_property_count(const GzFeature *feature);
+
+// Name of property `idx`, or NULL if out of range.
+//
+// # Safety
+//
+// `feature` must be a valid feature.
+const char *gz_feature_property_name(const GzFeature *feature, uintptr_t idx);
+
+// Value of property `idx` as string, or NULL for null values or if out of range.
+//
+// # Safety
+//
+// `feature` must be a valid feature.
+const char *gz_feature_property_value(const GzFeature *feature, uintptr_t idx);
+
+// Write all features of the datasource to `path`.
+//
+// The format is taken from the extension (`.geojson`, `.json`, `.csv`, `.svg` or `.wkt`).
+//
+// # Safety
+//
+// `ds` must be a valid datasource and `path` NULL or a valid NUL-terminated string.
+GzStatus gz_write(GzDatasource *ds, const char *path);
+
+// Message of the last error in the current thread, or NULL.
+//
+// The message is valid until the next failing call in the same thread.
+const char *gz_last_error(void);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* GEOZERO_H */
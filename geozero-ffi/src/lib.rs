@@ -0,0 +1,349 @@
+//! C API for GeoZero.
+//!
+//! Datasources are opened with `gz_open` and read completely into memory. Features are
+//! accessed by index, with geometries as OGC WKB and properties as strings. All returned
+//! pointers are owned by the datasource and valid until `gz_datasource_free`.
+//! The C header `include/geozero.h` is generated with cbindgen when building with the
+//! `generate-header` feature.
+//!
+//! ```c
+//! #include "geozero.h"
+//!
+//! GzDatasource *ds = gz_open("places.gpkg");
+//! if (ds == NULL) {
+//!     fprintf(stderr, "%s\n", gz_last_error());
+//!     return 1;
+//! }
+//! for (size_t i = 0; i < gz_feature_count(ds); i++) {
+//!     const GzFeature *feature = gz_feature(ds, i);
+//!     size_t len;
+//!     const uint8_t *wkb = gz_feature_wkb(feature, &len);
+//!     for (size_t p = 0; p < gz_feature_property_count(feature); p++) {
+//!         printf("%s=%s\n", gz_feature_property_name(feature, p), gz_feature_property_value(feature, p));
+//!     }
+//! }
+//! gz_write(ds, "places.geojson");
+//! gz_datasource_free(ds);
+//! ```
+use geozero::csv::CsvWriter;
+use geozero::error::{GeozeroError, Result};
+use geozero::geojson::GeoJsonWriter;
+use geozero::svg::SvgWriter;
+use geozero::wkt::WktWriter;
+use geozero::{
+    ColumnValue, CoordDimensions, DetectedSource, FeatureProperties, GeozeroDatasource,
+    ProcessorSink, PropertyProcessor, ToWkb,
+};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, OsStr};
+use std::fs::File;
+use std::io::BufWriter;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+
+/// Result status of API calls
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GzStatus {
+    Ok = 0,
+    Error = 1,
+}
+
+/// Opened datasource with all features read into memory
+pub struct GzDatasource {
+    source: DetectedSource,
+    features: Vec<GzFeature>,
+}
+
+/// Feature with WKB geometry and string properties
+pub struct GzFeature {
+    wkb: Vec<u8>,
+    names: Vec<CString>,
+    /// Property values (`None` for null values)
+    values: Vec<Option<CString>>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(msg: &str) {
+    let msg = CString::new(msg.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Collects feature properties as C strings
+#[derive(Default)]
+struct PropertyCollector {
+    names: Vec<CString>,
+    values: Vec<Option<CString>>,
+}
+
+impl PropertyProcessor for PropertyCollector {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.names.push(c_string(name));
+        self.values.push(match value {
+            ColumnValue::Null => None,
+            value => Some(c_string(&value.to_string())),
+        });
+        Ok(false)
+    }
+}
+
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap_or_default()
+}
+
+fn read_features(source: &mut DetectedSource) -> Result<Vec<GzFeature>> {
+    let mut features = Vec::new();
+    for feature in source.features()? {
+        let feature = feature?;
+        let mut properties = PropertyCollector::default();
+        feature.process_properties(&mut properties)?;
+        features.push(GzFeature {
+            wkb: feature.to_wkb(CoordDimensions::xy())?,
+            names: properties.names,
+            values: properties.values,
+        });
+    }
+    Ok(features)
+}
+
+/// # Safety
+///
+/// `path` must be NULL or a valid NUL-terminated string.
+unsafe fn path_arg<'a>(path: *const c_char) -> Result<&'a str> {
+    if path.is_null() {
+        return Err(GeozeroError::Dataset("path is NULL".to_string()));
+    }
+    CStr::from_ptr(path)
+        .to_str()
+        .map_err(|e| GeozeroError::Dataset(e.to_string()))
+}
+
+/// Open the datasource at `path` and read all features.
+///
+/// The format is detected from the file content or extension.
+/// Returns NULL on failure, see `gz_last_error`.
+///
+/// # Safety
+///
+/// `path` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gz_open(path: *const c_char) -> *mut GzDatasource {
+    let open = || -> Result<GzDatasource> {
+        let mut source = DetectedSource::open(path_arg(path)?)?;
+        let features = read_features(&mut source)?;
+        Ok(GzDatasource { source, features })
+    };
+    match open() {
+        Ok(ds) => Box::into_raw(Box::new(ds)),
+        Err(e) => {
+            set_last_error(&e.to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Free a datasource returned by `gz_open`.
+///
+/// # Safety
+///
+/// `ds` must be returned by `gz_open` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn gz_datasource_free(ds: *mut GzDatasource) {
+    if !ds.is_null() {
+        drop(Box::from_raw(ds));
+    }
+}
+
+/// Number of features of the datasource.
+///
+/// # Safety
+///
+/// `ds` must be a valid datasource.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature_count(ds: *const GzDatasource) -> usize {
+    ds.as_ref().map(|ds| ds.features.len()).unwrap_or(0)
+}
+
+/// Feature at index `idx`, or NULL if out of range.
+///
+/// # Safety
+///
+/// `ds` must be a valid datasource.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature(ds: *const GzDatasource, idx: usize) -> *const GzFeature {
+    ds.as_ref()
+        .and_then(|ds| ds.features.get(idx))
+        .map_or(ptr::null(), |feature| feature as *const GzFeature)
+}
+
+/// Geometry of the feature as OGC WKB with its length in bytes stored in `len`.
+///
+/// Features without geometry have a length of 0.
+///
+/// # Safety
+///
+/// `feature` must be a valid feature and `len` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature_wkb(feature: *const GzFeature, len: *mut usize) -> *const u8 {
+    let wkb = feature.as_ref().map(|f| f.wkb.as_slice()).unwrap_or(&[]);
+    if !len.is_null() {
+        *len = wkb.len();
+    }
+    wkb.as_ptr()
+}
+
+/// Number of properties of the feature.
+///
+/// # Safety
+///
+/// `feature` must be a valid feature.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature_property_count(feature: *const GzFeature) -> usize {
+    feature.as_ref().map(|f| f.names.len()).unwrap_or(0)
+}
+
+/// Name of property `idx`, or NULL if out of range.
+///
+/// # Safety
+///
+/// `feature` must be a valid feature.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature_property_name(
+    feature: *const GzFeature,
+    idx: usize,
+) -> *const c_char {
+    feature
+        .as_ref()
+        .and_then(|f| f.names.get(idx))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Value of property `idx` as string, or NULL for null values or if out of range.
+///
+/// # Safety
+///
+/// `feature` must be a valid feature.
+#[no_mangle]
+pub unsafe extern "C" fn gz_feature_property_value(
+    feature: *const GzFeature,
+    idx: usize,
+) -> *const c_char {
+    feature
+        .as_ref()
+        .and_then(|f| f.values.get(idx))
+        .and_then(|value| value.as_ref())
+        .map_or(ptr::null(), |value| value.as_ptr())
+}
+
+/// Write all features of the datasource to `path`.
+///
+/// The format is taken from the extension (`.geojson`, `.json`, `.csv`, `.svg` or `.wkt`).
+///
+/// # Safety
+///
+/// `ds` must be a valid datasource and `path` NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gz_write(ds: *mut GzDatasource, path: *const c_char) -> GzStatus {
+    let ds = match ds.as_mut() {
+        Some(ds) => ds,
+        None => {
+            set_last_error("datasource is NULL");
+            return GzStatus::Error;
+        }
+    };
+    match path_arg(path).and_then(|path| write(&mut ds.source, Path::new(path))) {
+        Ok(()) => GzStatus::Ok,
+        Err(e) => {
+            set_last_error(&e.to_string());
+            GzStatus::Error
+        }
+    }
+}
+
+fn write(source: &mut DetectedSource, path: &Path) -> Result<()> {
+    let ext = path
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase);
+    let mut out = BufWriter::new(File::create(path)?);
+    match ext.as_deref() {
+        Some("json") | Some("geojson") => {
+            source.process(&mut GeoJsonWriter::new(&mut out))?;
+        }
+        Some("csv") => source.process(&mut CsvWriter::new(&mut out))?,
+        Some("svg") => {
+            let stats = geozero::convert(source, &mut ProcessorSink::new())?;
+            let mut svg = SvgWriter::new(&mut out, true);
+            if let Some(b) = stats.extent {
+                svg.set_dimensions(b.minx, b.miny, b.maxx, b.maxy, 800, 600);
+            }
+            source.process(&mut svg)?;
+        }
+        Some("wkt") => source.process(&mut WktWriter::new(&mut out))?,
+        _ => {
+            return Err(GeozeroError::Dataset(format!(
+                "unknown output format of `{}`",
+                path.display()
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Message of the last error in the current thread, or NULL.
+///
+/// The message is valid until the next failing call in the same thread.
+#[no_mangle]
+pub extern "C" fn gz_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |msg| msg.as_ptr()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn open_and_read() {
+        let path = std::env::temp_dir().join("geozero-ffi-places.geojson");
+        File::create(&path)
+            .unwrap()
+            .write_all(br#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"name": "Bern", "pop": null}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}
+            ]}"#)
+            .unwrap();
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+        unsafe {
+            let ds = gz_open(path.as_ptr());
+            assert!(!ds.is_null());
+            assert_eq!(gz_feature_count(ds), 1);
+            let feature = gz_feature(ds, 0);
+            let mut len = 0;
+            let wkb = gz_feature_wkb(feature, &mut len);
+            assert_eq!(len, 21);
+            assert_eq!(*wkb.add(1), 1); // wkbPoint
+            assert_eq!(gz_feature_property_count(feature), 2);
+            let name = CStr::from_ptr(gz_feature_property_name(feature, 0));
+            let value = CStr::from_ptr(gz_feature_property_value(feature, 0));
+            assert_eq!(
+                (name.to_str().unwrap(), value.to_str().unwrap()),
+                ("name", "Bern")
+            );
+            assert!(gz_feature_property_value(feature, 1).is_null());
+            assert!(gz_feature(ds, 1).is_null());
+            gz_datasource_free(ds);
+        }
+    }
+
+    #[test]
+    fn open_error() {
+        let path = CString::new("missing.geojson").unwrap();
+        assert!(unsafe { gz_open(path.as_ptr()) }.is_null());
+        assert!(unsafe { gz_open(std::ptr::null()) }.is_null());
+        assert!(!gz_last_error().is_null());
+    }
+}
@@ -0,0 +1,351 @@
+//! Node.js bindings for GeoZero conversions and vector tile generation.
+//!
+//! All operations run on the libuv thread pool and return promises.
+//!
+//! ```js
+//! const { convert, convertStream, tile } = require("geozero");
+//!
+//! const stats = await convert("places.gpkg", "places.geojson", { bbox: [5.9, 45.8, 10.5, 47.8] });
+//! await convertStream("places.gpkg", "geojson", (err, chunk) => process.stdout.write(chunk));
+//! const mvt = await tile("places.gpkg", 8, 133, 90, { layerName: "places" });
+//! ```
+mod tile;
+
+use crate::tile::{encode_tile, TileParams};
+use geozero::csv::CsvWriter;
+use geozero::error::Result;
+use geozero::geojson::GeoJsonWriter;
+use geozero::processor::{BboxFilter, Extent};
+use geozero::svg::SvgWriter;
+use geozero::wkt::WktWriter;
+use geozero::{ConvertStats, DetectedSource, FeatureProcessor, ProcessorSink};
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{Env, JsFunction, Task};
+use napi_derive::napi;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Size of chunks passed to the stream callback
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Number of chunks queued for the stream callback before conversion blocks
+const CHUNK_QUEUE_SIZE: usize = 16;
+
+fn js_err(e: geozero::error::GeozeroError) -> napi::Error {
+    napi::Error::from_reason(e.to_string())
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct ConvertOptions {
+    /// Only features intersecting [minx, miny, maxx, maxy]
+    pub bbox: Option<Vec<f64>>,
+    /// Name of CSV column holding a WKT geometry (default: WKT)
+    pub csv_geometry_column: Option<String>,
+}
+
+#[napi(object)]
+pub struct Stats {
+    pub feature_count: i64,
+    pub vertex_count: i64,
+    /// Extent of converted features as [minx, miny, maxx, maxy]
+    pub bbox: Option<Vec<f64>>,
+}
+
+impl From<ConvertStats> for Stats {
+    fn from(stats: ConvertStats) -> Self {
+        Stats {
+            feature_count: stats.feature_count as i64,
+            vertex_count: stats.vertex_count as i64,
+            bbox: stats.extent.map(|e| vec![e.minx, e.miny, e.maxx, e.maxy]),
+        }
+    }
+}
+
+impl ConvertOptions {
+    fn extent(&self) -> napi::Result<Option<Extent>> {
+        match self.bbox.as_deref() {
+            None => Ok(None),
+            Some(&[minx, miny, maxx, maxy]) => Ok(Some(Extent::new(minx, miny, maxx, maxy))),
+            Some(_) => Err(napi::Error::from_reason(
+                "bbox must have 4 values".to_string(),
+            )),
+        }
+    }
+    fn source(&self, path: &str) -> Result<DetectedSource> {
+        let mut source = DetectedSource::open(path)?;
+        if let Some(name) = &self.csv_geometry_column {
+            source = source.csv_geometry_column(name);
+        }
+        Ok(source)
+    }
+}
+
+/// Convert features of `path` with `processor`, optionally filtered by `extent`
+fn process<P: FeatureProcessor>(
+    path: &str,
+    options: &ConvertOptions,
+    extent: Option<Extent>,
+    mut processor: P,
+) -> Result<ConvertStats> {
+    let mut source = options.source(path)?;
+    match extent {
+        Some(extent) => geozero::convert(&mut source, &mut BboxFilter::new(processor, extent)),
+        None => geozero::convert(&mut source, &mut processor),
+    }
+}
+
+/// Write features of `path` in `format` to `out`
+fn write<W: Write>(
+    path: &str,
+    format: &str,
+    options: &ConvertOptions,
+    out: &mut W,
+) -> napi::Result<ConvertStats> {
+    let extent = options.extent()?;
+    let stats = match format {
+        "json" | "geojson" => process(path, options, extent, GeoJsonWriter::new(out)),
+        "csv" => process(path, options, extent, CsvWriter::new(out)),
+        "svg" => {
+            let bounds = match extent {
+                Some(extent) => Some(extent),
+                None => {
+                    process(path, options, None, ProcessorSink::new())
+                        .map_err(js_err)?
+                        .extent
+                }
+            };
+            let mut svg = SvgWriter::new(out, true);
+            if let Some(b) = bounds {
+                svg.set_dimensions(b.minx, b.miny, b.maxx, b.maxy, 800, 600);
+            }
+            process(path, options, extent, svg)
+        }
+        "wkt" => process(path, options, extent, WktWriter::new(out)),
+        _ => {
+            return Err(napi::Error::from_reason(format!(
+                "unknown output format `{}`",
+                format
+            )))
+        }
+    };
+    stats.map_err(js_err)
+}
+
+pub struct ConvertTask {
+    src: String,
+    dst: String,
+    options: ConvertOptions,
+}
+
+impl Task for ConvertTask {
+    type Output = ConvertStats;
+    type JsValue = Stats;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let dst = Path::new(&self.dst);
+        let format = dst
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        let mut out = BufWriter::new(File::create(dst)?);
+        let stats = write(&self.src, &format, &self.options, &mut out)?;
+        out.flush()?;
+        Ok(stats)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// Convert the file `src` into `dst`.
+///
+/// The input format is detected from the file content or extension, the output format
+/// is taken from the extension of `dst` (geojson, json, csv, svg or wkt).
+#[napi(ts_return_type = "Promise<Stats>")]
+pub fn convert(
+    src: String,
+    dst: String,
+    options: Option<ConvertOptions>,
+) -> AsyncTask<ConvertTask> {
+    AsyncTask::new(ConvertTask {
+        src,
+        dst,
+        options: options.unwrap_or_default(),
+    })
+}
+
+/// Output passed in chunks to a JavaScript callback.
+///
+/// Calls block while the callback queue is full, which throttles the
+/// conversion to the speed of the consumer.
+struct ChunkWriter {
+    callback: ThreadsafeFunction<Vec<u8>, ErrorStrategy::CalleeHandled>,
+    buf: Vec<u8>,
+}
+
+impl ChunkWriter {
+    fn send(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = std::mem::replace(&mut self.buf, Vec::with_capacity(CHUNK_SIZE));
+        match self
+            .callback
+            .call(Ok(chunk), ThreadsafeFunctionCallMode::Blocking)
+        {
+            napi::Status::Ok => Ok(()),
+            status => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                format!("stream callback failed: {}", status),
+            )),
+        }
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}
+
+pub struct StreamTask {
+    src: String,
+    format: String,
+    options: ConvertOptions,
+    callback: ThreadsafeFunction<Vec<u8>, ErrorStrategy::CalleeHandled>,
+}
+
+impl Task for StreamTask {
+    type Output = ConvertStats;
+    type JsValue = Stats;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let mut out = ChunkWriter {
+            callback: self.callback.clone(),
+            buf: Vec::with_capacity(CHUNK_SIZE),
+        };
+        let stats = write(&self.src, &self.format, &self.options, &mut out)?;
+        out.flush()?;
+        Ok(stats)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// Convert the file `src` to `format`, passing the output in chunks to `onChunk`.
+///
+/// At most 16 chunks of 64 KiB are queued, further output waits for the callback.
+/// The promise resolves after the last chunk was queued.
+#[napi(
+    ts_args_type = "src: string, format: string, onChunk: (err: null | Error, chunk: Buffer) => void, options?: ConvertOptions",
+    ts_return_type = "Promise<Stats>"
+)]
+pub fn convert_stream(
+    src: String,
+    format: String,
+    on_chunk: JsFunction,
+    options: Option<ConvertOptions>,
+) -> napi::Result<AsyncTask<StreamTask>> {
+    let callback = on_chunk
+        .create_threadsafe_function(CHUNK_QUEUE_SIZE, |ctx: ThreadSafeCallContext<Vec<u8>>| {
+            Ok(vec![Buffer::from(ctx.value)])
+        })?;
+    Ok(AsyncTask::new(StreamTask {
+        src,
+        format: format.to_lowercase(),
+        options: options.unwrap_or_default(),
+        callback,
+    }))
+}
+
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct TileOptions {
+    /// Name of the MVT layer (default: file name without extension)
+    pub layer_name: Option<String>,
+    /// Tile extent in MVT coordinates (default: 4096)
+    pub extent: Option<u32>,
+    /// Clip buffer around the tile in MVT coordinates (default: 64)
+    pub buffer: Option<u32>,
+    /// Name of CSV column holding a WKT geometry (default: WKT)
+    pub csv_geometry_column: Option<String>,
+}
+
+pub struct TileTask {
+    src: String,
+    z: u8,
+    x: u32,
+    y: u32,
+    options: TileOptions,
+}
+
+impl Task for TileTask {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let layer_name = match &self.options.layer_name {
+            Some(name) => name.clone(),
+            None => Path::new(&self.src)
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .unwrap_or("layer")
+                .to_string(),
+        };
+        let params = TileParams {
+            layer_name,
+            extent: self.options.extent.unwrap_or(4096),
+            buffer: self.options.buffer.unwrap_or(64),
+        };
+        let convert_options = ConvertOptions {
+            bbox: None,
+            csv_geometry_column: self.options.csv_geometry_column.clone(),
+        };
+        let mut source = convert_options.source(&self.src).map_err(js_err)?;
+        encode_tile(&mut source, self.z, self.x, self.y, &params).map_err(js_err)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// Generate the Mapbox Vector Tile `z`/`x`/`y` from the WGS84 features of `src`.
+#[napi(ts_return_type = "Promise<Buffer>")]
+pub fn tile(
+    src: String,
+    z: u32,
+    x: u32,
+    y: u32,
+    options: Option<TileOptions>,
+) -> napi::Result<AsyncTask<TileTask>> {
+    if z > 24 {
+        return Err(napi::Error::from_reason(format!(
+            "zoom level {} out of range",
+            z
+        )));
+    }
+    Ok(AsyncTask::new(TileTask {
+        src,
+        z: z as u8,
+        x,
+        y,
+        options: options.unwrap_or_default(),
+    }))
+}
@@ -0,0 +1,218 @@
+//! Vector tile generation.
+use geozero::error::{GeozeroError, Result};
+use geozero::mvt::tile::{self, GeomType};
+use geozero::mvt::{MvtWriter, Tile};
+use geozero::processor::{ClipToExtent, Extent, TilePixels};
+use geozero::{ColumnValue, FeatureProcessor, GeomProcessor, GeozeroDatasource, PropertyProcessor};
+use prost::Message;
+use std::collections::HashMap;
+
+/// Tile generation parameters
+pub(crate) struct TileParams {
+    pub layer_name: String,
+    /// Tile size in MVT coordinates
+    pub extent: u32,
+    /// Clip buffer around tile in MVT coordinates
+    pub buffer: u32,
+}
+
+/// Encode the features of `source` intersecting tile `z`/`x`/`y` as MVT.
+///
+/// Input coordinates are expected in WGS84.
+pub(crate) fn encode_tile<D: GeozeroDatasource>(
+    source: &mut D,
+    z: u8,
+    x: u32,
+    y: u32,
+    params: &TileParams,
+) -> Result<Vec<u8>> {
+    let size = f64::from(params.extent);
+    let buffer = f64::from(params.buffer);
+    let clip = ClipToExtent::new(
+        LayerBuilder::new(&params.layer_name, params.extent),
+        Extent::new(-buffer, -buffer, size + buffer, size + buffer),
+    );
+    let mut pixels = TilePixels::new(clip, z).tile_size(params.extent).tile(x, y);
+    source.process(&mut pixels)?;
+    let layer = pixels.into_inner().into_inner().layer;
+    let tile = Tile {
+        layers: vec![layer],
+    };
+    Ok(tile.encode_to_vec())
+}
+
+/// Collects features into an MVT layer
+struct LayerBuilder {
+    layer: tile::Layer,
+    keys: HashMap<String, u32>,
+    feature: Option<tile::Feature>,
+    /// Writer of current feature geometry
+    writer: Option<MvtWriter>,
+}
+
+impl LayerBuilder {
+    fn new(name: &str, extent: u32) -> Self {
+        LayerBuilder {
+            layer: tile::Layer {
+                version: 2,
+                name: name.to_string(),
+                extent: Some(extent),
+                ..Default::default()
+            },
+            keys: HashMap::new(),
+            feature: None,
+            writer: None,
+        }
+    }
+    fn writer(&mut self) -> Result<&mut MvtWriter> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| GeozeroError::Geometry("Geometry outside of feature".to_string()))
+    }
+    fn key_idx(&mut self, key: &str) -> u32 {
+        if let Some(idx) = self.keys.get(key) {
+            return *idx;
+        }
+        let idx = self.layer.keys.len() as u32;
+        self.layer.keys.push(key.to_string());
+        self.keys.insert(key.to_string(), idx);
+        idx
+    }
+    fn value_idx(&mut self, value: tile::Value) -> u32 {
+        match self.layer.values.iter().position(|v| *v == value) {
+            Some(idx) => idx as u32,
+            None => {
+                self.layer.values.push(value);
+                (self.layer.values.len() - 1) as u32
+            }
+        }
+    }
+}
+
+/// MVT value of property value
+fn mvt_value(value: &ColumnValue) -> Option<tile::Value> {
+    let mut mvt = tile::Value::default();
+    match *value {
+        ColumnValue::Bool(v) => mvt.bool_value = Some(v),
+        ColumnValue::Byte(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::Short(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::Int(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::Long(v) => mvt.sint_value = Some(v),
+        ColumnValue::UByte(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::UShort(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::UInt(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::ULong(v) => mvt.uint_value = Some(v),
+        ColumnValue::Float(v) => mvt.float_value = Some(v),
+        ColumnValue::Double(v) => mvt.double_value = Some(v),
+        // MVT has no integer type beyond 64 bits
+        ColumnValue::BigInt(v)
+        | ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v) => mvt.string_value = Some(v.to_string()),
+        ColumnValue::Binary(_) | ColumnValue::Null => return None,
+    }
+    Some(mvt)
+}
+
+impl PropertyProcessor for LayerBuilder {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if let Some(value) = mvt_value(value) {
+            let key_idx = self.key_idx(name);
+            let value_idx = self.value_idx(value);
+            if let Some(feature) = &mut self.feature {
+                feature.tags.extend([key_idx, value_idx]);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for LayerBuilder {
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature = Some(tile::Feature {
+            id: Some(idx),
+            ..Default::default()
+        });
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        if let Some(feature) = self.feature.take() {
+            if !feature.geometry.is_empty() && feature.r#type() != GeomType::Unknown {
+                self.layer.features.push(feature);
+            }
+        }
+        Ok(())
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.writer = Some(MvtWriter::new());
+        Ok(())
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        if let (Some(writer), Some(feature)) = (self.writer.take(), &mut self.feature) {
+            let geometry = writer.geometry();
+            feature.r#type = geometry.r#type;
+            feature.geometry = geometry.geometry.clone();
+        }
+        Ok(())
+    }
+}
+
+/// Forward geometry calls to the writer of the current feature
+macro_rules! forward_to_writer {
+    ($( $method:ident($($arg:ident: $ty:ty),*); )*) => {
+        $(
+            fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
+                self.writer()?.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl GeomProcessor for LayerBuilder {
+    forward_to_writer! {
+        xy(x: f64, y: f64, idx: usize);
+        point_begin(idx: usize);
+        point_end(idx: usize);
+        multipoint_begin(size: usize, idx: usize);
+        multipoint_end(idx: usize);
+        linestring_begin(tagged: bool, size: usize, idx: usize);
+        linestring_end(tagged: bool, idx: usize);
+        multilinestring_begin(size: usize, idx: usize);
+        multilinestring_end(idx: usize);
+        polygon_begin(tagged: bool, size: usize, idx: usize);
+        polygon_end(tagged: bool, idx: usize);
+        multipolygon_begin(size: usize, idx: usize);
+        multipolygon_end(idx: usize);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use geozero::geojson::GeoJson;
+
+    #[test]
+    fn point_tile() -> Result<()> {
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+            {"type": "Feature", "properties": {"name": "Sydney"}, "geometry": {"type": "Point", "coordinates": [151.2, -33.87]}}
+        ]}"#,
+        );
+        let params = TileParams {
+            layer_name: "places".to_string(),
+            extent: 4096,
+            buffer: 64,
+        };
+        let mvt = encode_tile(&mut geojson, 1, 1, 0, &params)?;
+        let tile = Tile::decode(mvt.as_slice()).unwrap();
+        let layer = &tile.layers[0];
+        assert_eq!(layer.name, "places");
+        assert_eq!(layer.features.len(), 1);
+        assert_eq!(layer.keys, vec!["name"]);
+        assert_eq!(layer.values[0].string_value.as_deref(), Some("Bern"));
+        assert_eq!(layer.features[0].r#type(), GeomType::Point);
+        Ok(())
+    }
+}
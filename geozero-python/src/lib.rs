@@ -0,0 +1,198 @@
+//! Python bindings for GeoZero conversions.
+//!
+//! ```python
+//! import geozero
+//!
+//! stats = geozero.convert("places.gpkg", "places.geojson", bbox=(5.9, 45.8, 10.5, 47.8))
+//! print(stats["feature_count"], stats["bbox"])
+//! geojson = geozero.to_geojson("places.csv", crs="EPSG:3857")
+//! ```
+use geozero::csv::CsvWriter;
+use geozero::error::Result;
+use geozero::geojson::GeoJsonWriter;
+use geozero::processor::{BboxFilter, Extent, Reproject};
+use geozero::svg::SvgWriter;
+use geozero::wkt::WktWriter;
+use geozero::{ConvertStats, Crs, DetectedSource, FeatureProcessor, ProcessorSink};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+create_exception!(geozero, GeozeroError, PyException);
+
+fn py_err(e: geozero::error::GeozeroError) -> PyErr {
+    GeozeroError::new_err(e.to_string())
+}
+
+/// Processing options shared by all functions
+struct Options {
+    bbox: Option<Extent>,
+    crs: Option<Crs>,
+    csv_geometry_column: Option<String>,
+}
+
+impl Options {
+    fn new(
+        bbox: Option<(f64, f64, f64, f64)>,
+        crs: Option<&str>,
+        csv_geometry_column: Option<String>,
+    ) -> PyResult<Self> {
+        let crs =
+            match crs {
+                Some(definition) => Some(Crs::from_definition(definition).ok_or_else(|| {
+                    PyValueError::new_err(format!("invalid CRS `{}`", definition))
+                })?),
+                None => None,
+            };
+        Ok(Options {
+            bbox: bbox.map(|(minx, miny, maxx, maxy)| Extent::new(minx, miny, maxx, maxy)),
+            crs,
+            csv_geometry_column,
+        })
+    }
+    fn source(&self, path: &str) -> Result<DetectedSource> {
+        let mut source = DetectedSource::open(path)?;
+        if let Some(name) = &self.csv_geometry_column {
+            source = source.csv_geometry_column(name);
+        }
+        Ok(source)
+    }
+}
+
+/// Convert features of `path` with `processor`, applying filter and reprojection.
+///
+/// The bounding box refers to the output CRS.
+fn process<P: FeatureProcessor>(
+    path: &str,
+    options: &Options,
+    processor: P,
+) -> Result<ConvertStats> {
+    let mut source = options.source(path)?;
+    match (options.bbox, &options.crs) {
+        (Some(bbox), Some(crs)) => geozero::convert(
+            &mut source,
+            &mut Reproject::new(BboxFilter::new(processor, bbox), crs.clone()),
+        ),
+        (Some(bbox), None) => geozero::convert(&mut source, &mut BboxFilter::new(processor, bbox)),
+        (None, Some(crs)) => {
+            geozero::convert(&mut source, &mut Reproject::new(processor, crs.clone()))
+        }
+        (None, None) => {
+            let mut processor = processor;
+            geozero::convert(&mut source, &mut processor)
+        }
+    }
+}
+
+fn stats_dict(py: Python, stats: ConvertStats) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("feature_count", stats.feature_count)?;
+    dict.set_item("vertex_count", stats.vertex_count)?;
+    let bbox = stats.extent.map(|e| (e.minx, e.miny, e.maxx, e.maxy));
+    dict.set_item("bbox", bbox)?;
+    Ok(dict.into())
+}
+
+/// Convert the file `src` into `dst`.
+///
+/// The input format is detected from the file content or extension, the output format
+/// from the extension of `dst` (`.geojson`, `.json`, `.csv`, `.svg` or `.wkt`).
+/// Only features intersecting `bbox` (minx, miny, maxx, maxy) are converted and
+/// coordinates are reprojected to `crs` (e.g. "EPSG:4326").
+/// Returns feature count, vertex count and bounding box of the converted features.
+#[pyfunction]
+#[pyo3(text_signature = "(src, dst, bbox=None, crs=None, csv_geometry_column=None)")]
+fn convert(
+    py: Python,
+    src: &str,
+    dst: &str,
+    bbox: Option<(f64, f64, f64, f64)>,
+    crs: Option<&str>,
+    csv_geometry_column: Option<String>,
+) -> PyResult<PyObject> {
+    let options = Options::new(bbox, crs, csv_geometry_column)?;
+    let dst = Path::new(dst);
+    let ext = dst
+        .extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase);
+    let stats = py.allow_threads(|| -> PyResult<ConvertStats> {
+        let mut out = BufWriter::new(File::create(dst)?);
+        let stats = match ext.as_deref() {
+            Some("json") | Some("geojson") => process(src, &options, GeoJsonWriter::new(&mut out)),
+            Some("csv") => process(src, &options, CsvWriter::new(&mut out)),
+            Some("svg") => {
+                let bounds = match options.bbox {
+                    Some(bbox) => Some(bbox),
+                    None => {
+                        process(src, &options, ProcessorSink::new())
+                            .map_err(py_err)?
+                            .extent
+                    }
+                };
+                let mut svg = SvgWriter::new(&mut out, true);
+                if let Some(b) = bounds {
+                    svg.set_dimensions(b.minx, b.miny, b.maxx, b.maxy, 800, 600);
+                }
+                process(src, &options, svg)
+            }
+            Some("wkt") => process(src, &options, WktWriter::new(&mut out)),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown output format of `{}`",
+                    dst.display()
+                )))
+            }
+        };
+        stats.map_err(py_err)
+    })?;
+    stats_dict(py, stats)
+}
+
+/// Convert the file `src` into a GeoJSON string.
+///
+/// See `convert` for the options.
+#[pyfunction]
+#[pyo3(text_signature = "(src, bbox=None, crs=None, csv_geometry_column=None)")]
+fn to_geojson(
+    py: Python,
+    src: &str,
+    bbox: Option<(f64, f64, f64, f64)>,
+    crs: Option<&str>,
+    csv_geometry_column: Option<String>,
+) -> PyResult<String> {
+    let options = Options::new(bbox, crs, csv_geometry_column)?;
+    py.allow_threads(|| {
+        let mut out: Vec<u8> = Vec::new();
+        process(src, &options, GeoJsonWriter::new(&mut out)).map_err(py_err)?;
+        String::from_utf8(out).map_err(|e| GeozeroError::new_err(e.to_string()))
+    })
+}
+
+/// Feature count, vertex count and bounding box of the file `src`.
+#[pyfunction]
+#[pyo3(text_signature = "(src, csv_geometry_column=None)")]
+fn info(py: Python, src: &str, csv_geometry_column: Option<String>) -> PyResult<PyObject> {
+    let options = Options::new(None, None, csv_geometry_column)?;
+    let stats = py
+        .allow_threads(|| process(src, &options, ProcessorSink::new()))
+        .map_err(py_err)?;
+    stats_dict(py, stats)
+}
+
+/// Fast conversion of geospatial vector data.
+// Not named `geozero`, which would shadow the geozero crate
+#[pymodule]
+#[pyo3(name = "geozero")]
+fn geozero_py(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("GeozeroError", py.get_type::<GeozeroError>())?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    m.add_function(wrap_pyfunction!(to_geojson, m)?)?;
+    m.add_function(wrap_pyfunction!(info, m)?)?;
+    Ok(())
+}
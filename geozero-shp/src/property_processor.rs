@@ -2,11 +2,14 @@ use crate::reader::ShapeRecord;
 use dbase::FieldValue;
 use geozero::error::Result;
 use geozero::{ColumnValue, FeatureProperties, PropertyProcessor};
+use std::fmt::Write;
 
 impl FeatureProperties for ShapeRecord {
     /// Process feature properties.
     fn process_properties<P: PropertyProcessor>(&self, processor: &mut P) -> Result<bool> {
         let mut i = 0;
+        // Buffer for formatted values
+        let mut buf = String::new();
         for (name, value) in self.record.as_ref().iter() {
             let finish = match value {
                 FieldValue::Character(Some(val)) => {
@@ -18,9 +21,11 @@ impl FeatureProperties for ShapeRecord {
                 FieldValue::Logical(Some(val)) => {
                     processor.property(i, name, &ColumnValue::Bool(*val))?
                 }
-                FieldValue::Date(Some(_)) => {
-                    let s = format!("{}", value);
-                    processor.property(i, name, &ColumnValue::DateTime(&s))?
+                FieldValue::Date(Some(date)) => {
+                    buf.clear();
+                    let (y, m, d) = (date.year(), date.month(), date.day());
+                    let _ = write!(buf, "{:04}-{:02}-{:02}", y, m, d);
+                    processor.property(i, name, &ColumnValue::Date(&buf))?
                 }
                 FieldValue::Float(Some(val)) => {
                     processor.property(i, name, &ColumnValue::Float(*val))?
@@ -32,18 +37,25 @@ impl FeatureProperties for ShapeRecord {
                 FieldValue::Currency(val) => {
                     processor.property(i, name, &ColumnValue::Double(*val))?
                 }
-                FieldValue::DateTime(_) => {
-                    let s = format!("{}", value);
-                    processor.property(i, name, &ColumnValue::DateTime(&s))?
+                FieldValue::DateTime(datetime) => {
+                    // DBF date-times have no time zone, they are passed as UTC
+                    buf.clear();
+                    let (date, time) = (datetime.date(), datetime.time());
+                    let (y, m, d) = (date.year(), date.month(), date.day());
+                    let (h, min, sec) = (time.hours(), time.minutes(), time.seconds());
+                    let _ = write!(
+                        buf,
+                        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                        y, m, d, h, min, sec
+                    );
+                    processor.property(i, name, &ColumnValue::DateTime(&buf))?
                 }
                 FieldValue::Memo(val) => processor.property(i, name, &ColumnValue::String(val))?,
                 FieldValue::Character(None)
                 | FieldValue::Numeric(None)
                 | FieldValue::Logical(None)
                 | FieldValue::Date(None)
-                | FieldValue::Float(None) => {
-                    continue; // Ignore NULL values
-                }
+                | FieldValue::Float(None) => processor.property(i, name, &ColumnValue::Null)?,
             };
             if finish {
                 return Ok(true);
@@ -2,7 +2,7 @@ use crate::shp_reader::{read_shape, RecordHeader};
 use crate::shx_reader::{read_index_file, ShapeIndex};
 use crate::{header, Error};
 pub use dbase::{FieldInfo,FieldType};
-use geozero::{FeatureProcessor, FeatureProperties, GeomProcessor};
+use geozero::{ColumnDef, ColumnType, FeatureProcessor, FeatureProperties, GeomProcessor};
 use std::fs::File;
 use std::io::{BufReader, Read, Seek};
 use std::iter::FusedIterator;
@@ -42,6 +42,13 @@ pub struct ShapeRecordIterator<P: FeatureProcessor, T: Read + Seek> {
     featno: u64,
 }
 
+impl<P: FeatureProcessor, T: Read + Seek> ShapeRecordIterator<P, T> {
+    /// Returns a reference to the feature processor
+    pub fn processor(&self) -> &P {
+        &self.shape_iter.processor
+    }
+}
+
 pub struct ShapeRecord {
     pub record: dbase::Record,
 }
@@ -52,6 +59,10 @@ impl<P: FeatureProcessor, T: Read + Seek> Iterator for ShapeRecordIterator<P, T>
     fn next(&mut self) -> Option<Self::Item> {
         if self.featno == 0 {
             self.shape_iter.processor.dataset_begin(None).ok();
+            let columns = dbf_schema(self.dbf_reader.fields());
+            if let Err(e) = self.shape_iter.processor.schema(&columns) {
+                return Some(Err(Error::GeozeroError(e)));
+            }
         }
         let record = match self.dbf_reader.iter_records().next() {
             None => {
@@ -90,6 +101,31 @@ impl<P: FeatureProcessor, T: Read + Seek> Iterator for ShapeRecordIterator<P, T>
 
 impl<P: FeatureProcessor, T: Read + Seek> FusedIterator for ShapeRecordIterator<P, T> {}
 
+/// Column definitions of dbf fields
+fn dbf_schema(fields: &[FieldInfo]) -> Vec<ColumnDef> {
+    fields
+        .iter()
+        .filter(|f| f.name() != "DeletionFlag")
+        .map(|f| {
+            let col_type = match f.field_type() {
+                FieldType::Character | FieldType::Memo => ColumnType::String,
+                FieldType::Numeric | FieldType::Double | FieldType::Currency => ColumnType::Double,
+                FieldType::Float => ColumnType::Float,
+                FieldType::Integer => ColumnType::Int,
+                FieldType::Logical => ColumnType::Bool,
+                FieldType::Date => ColumnType::Date,
+                FieldType::DateTime => ColumnType::DateTime,
+            };
+            ColumnDef {
+                name: f.name().to_string(),
+                col_type,
+                nullable: true,
+                width: Some(f.length() as usize),
+            }
+        })
+        .collect()
+}
+
 /// struct that reads the content of a shapefile
 pub struct Reader<T: Read + Seek> {
     source: T,
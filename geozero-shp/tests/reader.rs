@@ -123,6 +123,61 @@ fn property_access() -> Result<(), geozero_shp::Error> {
     Ok(())
 }
 
+#[test]
+fn typed_property_access() -> Result<(), geozero_shp::Error> {
+    use geozero::OwnedColumnValue;
+
+    #[derive(serde::Deserialize)]
+    struct Poly {
+        #[serde(rename = "AREA")]
+        area: f64,
+        #[serde(rename = "PRFEDEA")]
+        prfedea: String,
+    }
+
+    let reader = geozero_shp::Reader::from_path("./tests/data/poly.shp")?;
+    let feat = reader
+        .iter_features(ProcessorSink::new())?
+        .next()
+        .unwrap()?;
+    let props = feat.property_values()?;
+    assert_eq!(props["EAS_ID"], OwnedColumnValue::Double(168.0));
+    let poly: Poly = feat.properties_into()?;
+    assert_eq!(poly.area, 215229.266);
+    assert_eq!(poly.prfedea, "35043411");
+
+    Ok(())
+}
+
+#[test]
+fn schema() -> Result<(), geozero_shp::Error> {
+    use geozero::error::Result as GeozeroResult;
+    use geozero::{ColumnDef, ColumnType, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+    #[derive(Default)]
+    struct SchemaReader(Vec<ColumnDef>);
+    impl FeatureProcessor for SchemaReader {
+        fn schema(&mut self, columns: &[ColumnDef]) -> GeozeroResult<()> {
+            self.0 = columns.to_vec();
+            Ok(())
+        }
+    }
+    impl GeomProcessor for SchemaReader {}
+    impl PropertyProcessor for SchemaReader {}
+
+    let reader = geozero_shp::Reader::from_path("./tests/data/poly.shp")?;
+    let mut features = reader.iter_features(SchemaReader::default())?;
+    features.next();
+    let columns = &features.processor().0;
+    assert_eq!(columns.len(), 3);
+    assert_eq!(columns[0].name, "AREA");
+    assert_eq!(columns[0].col_type, ColumnType::Double);
+    assert_eq!(columns[0].width, Some(12));
+    assert_eq!(columns[2].name, "PRFEDEA");
+    assert_eq!(columns[2].col_type, ColumnType::String);
+    Ok(())
+}
+
 #[test]
 fn property_file() -> Result<(), geozero_shp::Error> {
     let reader = geozero_shp::Reader::from_path("./tests/data/poly.shp")?;
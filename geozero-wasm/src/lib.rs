@@ -0,0 +1,248 @@
+//! WebAssembly bindings for GeoZero conversions.
+//!
+//! Exposes conversion, info and bounding box filtering of FlatGeobuf, GeoJSON, MVT, CSV and WKT
+//! data to JavaScript. Input is passed as `Uint8Array`, text formats are UTF-8 encoded.
+//! Output formats are FlatGeobuf, GeoJSON, CSV, SVG and WKT.
+//!
+//! ```js
+//! import init, { convert, info, bboxFilter } from "geozero-wasm";
+//!
+//! await init();
+//! const fgb = new Uint8Array(await (await fetch("countries.fgb")).arrayBuffer());
+//! const geojson = new TextDecoder().decode(convert(fgb, "fgb", "geojson"));
+//! const swiss = bboxFilter(fgb, "fgb", "geojson", 5.9, 45.8, 10.5, 47.8);
+//! const { featureCount, bbox } = JSON.parse(info(fgb, "fgb"));
+//! ```
+use flatgeobuf::{FgbReader, FgbWriter, GeometryType};
+use geozero::csv::{Csv, CsvWriter};
+use geozero::error::{GeozeroError, Result};
+use geozero::geojson::{GeoJson, GeoJsonWriter};
+use geozero::mvt::Tile;
+use geozero::processor::{BboxFilter, Extent};
+use geozero::svg::SvgWriter;
+use geozero::wkt::{WktStr, WktWriter};
+use geozero::{ConvertStats, FeatureProcessor, GeozeroDatasource, ProcessorSink};
+use prost::Message;
+use std::io::Cursor;
+use wasm_bindgen::prelude::*;
+
+/// Convert `input` in format `from` to format `to`.
+#[wasm_bindgen]
+pub fn convert(input: &[u8], from: &str, to: &str) -> std::result::Result<Vec<u8>, JsError> {
+    Ok(write(input, from, to, None)?)
+}
+
+/// Convert features of `input` intersecting the given bounding box.
+#[wasm_bindgen(js_name = bboxFilter)]
+pub fn bbox_filter(
+    input: &[u8],
+    from: &str,
+    to: &str,
+    minx: f64,
+    miny: f64,
+    maxx: f64,
+    maxy: f64,
+) -> std::result::Result<Vec<u8>, JsError> {
+    let extent = Extent::new(minx, miny, maxx, maxy);
+    Ok(write(input, from, to, Some(extent))?)
+}
+
+/// Feature count, vertex count and bounding box of `input` as JSON string.
+///
+/// Example: `{"featureCount": 2, "vertexCount": 2, "bbox": [2.35, 46.95, 7.44, 48.86]}`
+#[wasm_bindgen]
+pub fn info(input: &[u8], from: &str) -> std::result::Result<String, JsError> {
+    let (stats, _) = read(input, from, None, ProcessorSink::new())?;
+    let bbox = match stats.extent {
+        Some(e) => format!("[{}, {}, {}, {}]", e.minx, e.miny, e.maxx, e.maxy),
+        None => "null".to_string(),
+    };
+    Ok(format!(
+        r#"{{"featureCount": {}, "vertexCount": {}, "bbox": {}}}"#,
+        stats.feature_count, stats.vertex_count, bbox
+    ))
+}
+
+/// Supported input formats
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum InputFormat {
+    FlatGeobuf,
+    GeoJson,
+    Mvt,
+    Csv,
+    Wkt,
+}
+
+impl InputFormat {
+    fn from_name(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "fgb" | "flatgeobuf" => Ok(InputFormat::FlatGeobuf),
+            "json" | "geojson" => Ok(InputFormat::GeoJson),
+            "mvt" | "pbf" => Ok(InputFormat::Mvt),
+            "csv" => Ok(InputFormat::Csv),
+            "wkt" => Ok(InputFormat::Wkt),
+            _ => Err(GeozeroError::Dataset(format!(
+                "unsupported input format `{}`",
+                name
+            ))),
+        }
+    }
+}
+
+/// Write `input` in format `from` as format `to`
+fn write(input: &[u8], from: &str, to: &str, extent: Option<Extent>) -> Result<Vec<u8>> {
+    let mut out: Vec<u8> = Vec::new();
+    match to.to_lowercase().as_str() {
+        "fgb" | "flatgeobuf" => {
+            let fgb = FgbWriter::create("fgb", GeometryType::Unknown)?;
+            let (_, fgb) = read(input, from, extent, fgb)?;
+            fgb.write(&mut out)?;
+        }
+        "json" | "geojson" => {
+            read(input, from, extent, GeoJsonWriter::new(&mut out))?;
+        }
+        "csv" => {
+            read(input, from, extent, CsvWriter::new(&mut out))?;
+        }
+        "svg" => {
+            let bounds = match extent {
+                Some(extent) => Some(extent),
+                None => read(input, from, None, ProcessorSink::new())?.0.extent,
+            };
+            let mut svg = SvgWriter::new(&mut out, true);
+            if let Some(b) = bounds {
+                svg.set_dimensions(b.minx, b.miny, b.maxx, b.maxy, 800, 600);
+            }
+            read(input, from, extent, svg)?;
+        }
+        "wkt" => {
+            read(input, from, extent, WktWriter::new(&mut out))?;
+        }
+        _ => {
+            return Err(GeozeroError::Dataset(format!(
+                "unsupported output format `{}`",
+                to
+            )))
+        }
+    }
+    Ok(out)
+}
+
+/// Process `input` in format `from` with `processor`, optionally filtered by `extent`
+fn read<P: FeatureProcessor>(
+    input: &[u8],
+    from: &str,
+    extent: Option<Extent>,
+    mut processor: P,
+) -> Result<(ConvertStats, P)> {
+    match extent {
+        Some(extent) => {
+            let mut filter = BboxFilter::new(processor, extent);
+            let stats = read_format(input, from, Some(extent), &mut filter)?;
+            Ok((stats, filter.into_inner()))
+        }
+        None => {
+            let stats = read_format(input, from, None, &mut processor)?;
+            Ok((stats, processor))
+        }
+    }
+}
+
+fn read_format<P: FeatureProcessor>(
+    input: &[u8],
+    from: &str,
+    extent: Option<Extent>,
+    processor: &mut P,
+) -> Result<ConvertStats> {
+    match InputFormat::from_name(from)? {
+        InputFormat::FlatGeobuf => {
+            let mut cursor = Cursor::new(input);
+            let fgb = FgbReader::open(&mut cursor)?;
+            // Use spatial index of FlatGeobuf if available
+            let fgb = match extent {
+                Some(e) => fgb.select_bbox(e.minx, e.miny, e.maxx, e.maxy)?,
+                None => fgb.select_all()?,
+            };
+            geozero::convert(&mut FgbFeatures(fgb), processor)
+        }
+        InputFormat::GeoJson => geozero::convert(&mut GeoJson(utf8(input)?), processor),
+        InputFormat::Mvt => {
+            let tile = Tile::decode(input)
+                .map_err(|e| GeozeroError::Dataset(format!("invalid MVT: {}", e)))?;
+            let mut stats = ConvertStats::default();
+            for mut layer in tile.layers {
+                let layer_stats = geozero::convert(&mut layer, processor)?;
+                stats.feature_count += layer_stats.feature_count;
+                stats.vertex_count += layer_stats.vertex_count;
+                stats.extent = match (stats.extent, layer_stats.extent) {
+                    (Some(mut extent), Some(other)) => {
+                        extent.merge(&other);
+                        Some(extent)
+                    }
+                    (extent, other) => extent.or(other),
+                };
+            }
+            Ok(stats)
+        }
+        InputFormat::Csv => geozero::convert(&mut Csv::new("WKT", utf8(input)?), processor),
+        InputFormat::Wkt => geozero::convert(&mut WktStr(utf8(input)?), processor),
+    }
+}
+
+/// FlatGeobuf reader with selected features
+struct FgbFeatures<'a, R: std::io::Read + std::io::Seek>(
+    FgbReader<'a, R, flatgeobuf::reader_state::FeaturesSelectedSeek>,
+);
+
+impl<R: std::io::Read + std::io::Seek> GeozeroDatasource for FgbFeatures<'_, R> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.0.process_features(processor)?;
+        Ok(())
+    }
+}
+
+fn utf8(input: &[u8]) -> Result<&str> {
+    std::str::from_utf8(input).map_err(|e| GeozeroError::Dataset(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GEOJSON: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+        {"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}}
+    ]}"#;
+
+    #[test]
+    fn geojson_to_wkt() -> Result<()> {
+        let wkt = write(GEOJSON.as_bytes(), "geojson", "wkt", None)?;
+        assert_eq!(
+            std::str::from_utf8(&wkt).unwrap(),
+            "POINT(7.44 46.95),POINT(2.35 48.86)"
+        );
+        let wkt = write(
+            GEOJSON.as_bytes(),
+            "geojson",
+            "wkt",
+            Some(Extent::new(5.9, 45.8, 10.5, 47.8)),
+        )?;
+        assert_eq!(std::str::from_utf8(&wkt).unwrap(), "POINT(7.44 46.95)");
+        Ok(())
+    }
+
+    #[test]
+    fn fgb_roundtrip() -> Result<()> {
+        let fgb = write(GEOJSON.as_bytes(), "geojson", "fgb", None)?;
+        let (stats, _) = read(&fgb, "fgb", None, ProcessorSink::new())?;
+        assert_eq!(stats.feature_count, 2);
+        assert_eq!(stats.extent, Some(Extent::new(2.35, 46.95, 7.44, 48.86)));
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert!(write(GEOJSON.as_bytes(), "kml", "wkt", None).is_err());
+        assert!(write(GEOJSON.as_bytes(), "geojson", "kml", None).is_err());
+    }
+}
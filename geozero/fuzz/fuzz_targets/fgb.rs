@@ -0,0 +1,14 @@
+#![no_main]
+use flatgeobuf::FgbReader;
+use geozero::ProcessorSink;
+use libfuzzer_sys::fuzz_target;
+use std::io::Cursor;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = Cursor::new(data);
+    if let Ok(reader) = FgbReader::open(&mut input) {
+        if let Ok(mut features) = reader.select_all() {
+            let _ = features.process_features(&mut ProcessorSink::new());
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+use geozero::geojson::{GeoJson, GeoJsonLineReader, GeoJsonReader};
+use geozero::{GeozeroDatasource, ProcessorSink, ToWkt};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut input = data;
+    let _ = GeoJsonReader(&mut input).process(&mut ProcessorSink::new());
+    let _ = GeoJsonLineReader::new(data).process(&mut ProcessorSink::new());
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = GeoJson(text).to_wkt();
+    }
+});
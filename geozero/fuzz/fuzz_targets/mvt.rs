@@ -0,0 +1,18 @@
+#![no_main]
+use geozero::mvt::{process_layer, Tile, TileRef};
+use geozero::{GeozeroDatasource, ProcessorSink};
+use libfuzzer_sys::fuzz_target;
+use prost::Message;
+
+fuzz_target!(|data: &[u8]| {
+    // Zero-copy decoder
+    for layer in TileRef(data).layers().flatten() {
+        let _ = process_layer(&layer, &mut ProcessorSink::new());
+    }
+    // Decoded protobuf
+    if let Ok(mut tile) = Tile::decode(data) {
+        for layer in &mut tile.layers {
+            let _ = layer.process(&mut ProcessorSink::new());
+        }
+    }
+});
@@ -0,0 +1,36 @@
+#![no_main]
+//! Write generated features and read them back
+use geozero::geojson::{GeoJsonReader, GeoJsonWriter};
+use geozero::wkb::Wkb;
+use geozero::wkt::WktStr;
+use geozero::{
+    CoordDimensions, FeatureAccess, FeatureProcessor, GeozeroDatasource, ProcessorSink, ToJson,
+    ToWkb, ToWkt,
+};
+use geozero_fuzz::FuzzFeature;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|feature: FuzzFeature| {
+    let mut json: Vec<u8> = Vec::new();
+    let mut writer = GeoJsonWriter::new(&mut json);
+    let written = writer
+        .dataset_begin(None)
+        .and_then(|_| feature.process(&mut writer, 0))
+        .and_then(|_| writer.dataset_end());
+    if written.is_ok() {
+        let mut input = &json[..];
+        let _ = GeoJsonReader(&mut input).process(&mut ProcessorSink::new());
+    }
+
+    if let Some(geometry) = &feature.geometry {
+        let _ = geometry.to_json();
+        if let Ok(wkt) = geometry.to_wkt() {
+            let _ = WktStr(&wkt).to_wkt();
+        }
+        for dims in [CoordDimensions::xy(), CoordDimensions::xyz()] {
+            if let Ok(wkb) = geometry.to_wkb(dims) {
+                let _ = Wkb(&wkb).to_wkt_ndim(dims);
+            }
+        }
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+use geozero::wkb::{Ewkb, GpkgWkb, Wkb};
+use geozero::ToWkt;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Wkb(data).to_wkt();
+    let _ = Ewkb(data).to_wkt();
+    let _ = GpkgWkb(data).to_wkt();
+});
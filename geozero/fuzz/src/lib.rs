@@ -0,0 +1,184 @@
+//! Random geometries and features for fuzzing.
+//!
+//! Geometries are generated with [arbitrary] and include near-valid input like
+//! unclosed rings, empty parts and non-finite coordinates. They implement the
+//! geozero processing API, so they can be written with any geozero writer.
+use arbitrary::Arbitrary;
+use geozero::error::Result;
+use geozero::{
+    ColumnValue, FeatureAccess, FeatureProperties, GeomProcessor, GeozeroGeometry,
+    PropertyProcessor,
+};
+
+/// Coordinate with optional Z value
+#[derive(Arbitrary, Clone, Copy, Debug)]
+pub struct Coord {
+    pub x: f64,
+    pub y: f64,
+    pub z: Option<f64>,
+}
+
+impl Coord {
+    fn process<P: GeomProcessor>(&self, processor: &mut P, idx: usize) -> Result<()> {
+        if processor.multi_dim() {
+            processor.coordinate(self.x, self.y, self.z, None, None, None, idx)
+        } else {
+            processor.xy(self.x, self.y, idx)
+        }
+    }
+}
+
+/// Geometry without nested collections
+#[derive(Arbitrary, Debug)]
+pub enum Part {
+    /// Point, empty if `None`
+    Point(Option<Coord>),
+    LineString(Vec<Coord>),
+    /// Polygon with rings, which are not necessarily closed
+    Polygon(Vec<Vec<Coord>>),
+    MultiPoint(Vec<Coord>),
+    MultiLineString(Vec<Vec<Coord>>),
+    MultiPolygon(Vec<Vec<Vec<Coord>>>),
+}
+
+fn process_line<P: GeomProcessor>(
+    line: &[Coord],
+    tagged: bool,
+    processor: &mut P,
+    idx: usize,
+) -> Result<()> {
+    processor.linestring_begin(tagged, line.len(), idx)?;
+    for (i, coord) in line.iter().enumerate() {
+        coord.process(processor, i)?;
+    }
+    processor.linestring_end(tagged, idx)
+}
+
+fn process_polygon<P: GeomProcessor>(
+    rings: &[Vec<Coord>],
+    tagged: bool,
+    processor: &mut P,
+    idx: usize,
+) -> Result<()> {
+    processor.polygon_begin(tagged, rings.len(), idx)?;
+    for (i, ring) in rings.iter().enumerate() {
+        process_line(ring, false, processor, i)?;
+    }
+    processor.polygon_end(tagged, idx)
+}
+
+impl Part {
+    fn process<P: GeomProcessor>(&self, processor: &mut P, idx: usize) -> Result<()> {
+        match self {
+            Part::Point(Some(coord)) => {
+                processor.point_begin(idx)?;
+                coord.process(processor, 0)?;
+                processor.point_end(idx)
+            }
+            Part::Point(None) => processor.empty_point(idx),
+            Part::LineString(line) => process_line(line, true, processor, idx),
+            Part::Polygon(rings) => process_polygon(rings, true, processor, idx),
+            Part::MultiPoint(points) => {
+                processor.multipoint_begin(points.len(), idx)?;
+                for (i, coord) in points.iter().enumerate() {
+                    coord.process(processor, i)?;
+                }
+                processor.multipoint_end(idx)
+            }
+            Part::MultiLineString(lines) => {
+                processor.multilinestring_begin(lines.len(), idx)?;
+                for (i, line) in lines.iter().enumerate() {
+                    process_line(line, false, processor, i)?;
+                }
+                processor.multilinestring_end(idx)
+            }
+            Part::MultiPolygon(polygons) => {
+                processor.multipolygon_begin(polygons.len(), idx)?;
+                for (i, rings) in polygons.iter().enumerate() {
+                    process_polygon(rings, false, processor, i)?;
+                }
+                processor.multipolygon_end(idx)
+            }
+        }
+    }
+}
+
+/// Generated geometry
+#[derive(Arbitrary, Debug)]
+pub enum FuzzGeometry {
+    Part(Part),
+    GeometryCollection(Vec<Part>),
+}
+
+impl GeozeroGeometry for FuzzGeometry {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        match self {
+            FuzzGeometry::Part(part) => part.process(processor, 0),
+            FuzzGeometry::GeometryCollection(parts) => {
+                processor.geometrycollection_begin(parts.len(), 0)?;
+                for (i, part) in parts.iter().enumerate() {
+                    part.process(processor, i)?;
+                }
+                processor.geometrycollection_end(0)
+            }
+        }
+    }
+}
+
+/// Generated property value
+#[derive(Arbitrary, Debug)]
+pub enum FuzzValue {
+    Bool(bool),
+    Int(i32),
+    Long(i64),
+    Double(f64),
+    String(String),
+    /// Arbitrary text passed as JSON value
+    Json(String),
+    Binary(Vec<u8>),
+    Null,
+}
+
+impl FuzzValue {
+    fn column_value(&self) -> ColumnValue {
+        match self {
+            FuzzValue::Bool(v) => ColumnValue::Bool(*v),
+            FuzzValue::Int(v) => ColumnValue::Int(*v),
+            FuzzValue::Long(v) => ColumnValue::Long(*v),
+            FuzzValue::Double(v) => ColumnValue::Double(*v),
+            FuzzValue::String(v) => ColumnValue::String(v),
+            FuzzValue::Json(v) => ColumnValue::Json(v),
+            FuzzValue::Binary(v) => ColumnValue::Binary(v),
+            FuzzValue::Null => ColumnValue::Null,
+        }
+    }
+}
+
+/// Generated feature with possibly duplicate property names
+#[derive(Arbitrary, Debug)]
+pub struct FuzzFeature {
+    pub properties: Vec<(String, FuzzValue)>,
+    pub geometry: Option<FuzzGeometry>,
+}
+
+impl FeatureProperties for FuzzFeature {
+    fn process_properties<P: PropertyProcessor>(&self, processor: &mut P) -> Result<bool> {
+        for (i, (name, value)) in self.properties.iter().enumerate() {
+            if processor.property(i, name, &value.column_value())? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl GeozeroGeometry for FuzzFeature {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        match &self.geometry {
+            Some(geometry) => geometry.process_geom(processor),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FeatureAccess for FuzzFeature {}
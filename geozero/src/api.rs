@@ -6,10 +6,13 @@
 
 use crate::error::{GeozeroError, Result};
 use crate::feature_processor::FeatureProcessor;
+use crate::features::{record_features, FeatureIter};
+use crate::processor::{Borrowed, ResourceLimiter};
 use crate::property_processor::{
-    PropertyProcessor, PropertyReadType, PropertyReader, PropertyReaderIdx,
+    OwnedColumnValue, PropertyProcessor, PropertyReadType, PropertyReader, PropertyReaderIdx,
 };
-use crate::{CoordDimensions, GeomProcessor};
+use crate::{CoordDimensions, Crs, GeomProcessor};
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 
 /// Geometry processing trait.
@@ -28,15 +31,280 @@ pub trait GeozeroGeometry {
     }
 }
 
+/// Datasource reading options.
+#[derive(Clone, Default, Debug)]
+pub struct ReadOptions {
+    /// Emit string properties formatted as RFC 3339 date-time or as full date
+    /// as `ColumnValue::DateTime` and `ColumnValue::Date` (GeoJSON, CSV).
+    pub parse_datetimes: bool,
+    /// Time zone of date-times without offset, e.g. `2022-07-21T12:55:00` (GeoJSON, CSV).
+    ///
+    /// Only used with `parse_datetimes`.
+    pub timezone_fallback: TimezoneFallback,
+    /// Property columns to read. All columns are read if `None`.
+    ///
+    /// Properties of other columns are not passed to the processor. The GeoJSON reader
+    /// still parses all properties of a feature, so the selection doesn't speed up reading.
+    pub columns: Option<Vec<String>>,
+    /// Handling of nested properties like arrays and objects (GeoJSON).
+    pub nested: NestedProperties,
+    /// Handling of duplicate property names within a feature (GeoJSON).
+    pub duplicate_keys: DuplicateKeyPolicy,
+    /// Limits for untrusted input, checked while reading.
+    pub limits: ResourceLimits,
+}
+
+/// Limits for reading untrusted input.
+///
+/// Readers check the limits while passing the input to the processor and fail with
+/// `GeozeroError::ResourceLimit` when a limit is exceeded. No limits are set by default.
+///
+/// GeoJSON documents and WKT are read into memory and parsed completely before
+/// processing. For these, only `max_input_size` is checked while reading, all other
+/// limits are checked after parsing.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub struct ResourceLimits {
+    /// Maximal nesting depth of geometry parts, e.g. 2 for a polygon with its rings
+    pub max_depth: Option<usize>,
+    /// Maximal number of vertices of a geometry
+    pub max_vertices: Option<usize>,
+    /// Maximal estimated size of a feature in bytes, counting property names,
+    /// property values and coordinates
+    pub max_feature_size: Option<usize>,
+    /// Maximal number of properties of a feature
+    pub max_properties: Option<usize>,
+    /// Maximal size of a property value in bytes
+    pub max_property_size: Option<usize>,
+    /// Maximal size in bytes of input read into memory before parsing
+    /// (GeoJSON documents, WKT)
+    pub max_input_size: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// Limits for user uploads, allowing all but unusually large features
+    pub fn untrusted() -> Self {
+        ResourceLimits {
+            max_depth: Some(32),
+            max_vertices: Some(1_000_000),
+            max_feature_size: Some(64 * 1024 * 1024),
+            max_properties: Some(1_000),
+            max_property_size: Some(1024 * 1024),
+            max_input_size: Some(1024 * 1024 * 1024),
+        }
+    }
+}
+
+/// Handling of nested property values.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum NestedProperties {
+    /// Pass arrays and objects as serialized `ColumnValue::Json`
+    #[default]
+    Json,
+    /// Flatten arrays and objects into scalar properties with dotted keys
+    /// (e.g. `address.city`, `tags.0`)
+    Flatten,
+}
+
+/// Time zone assumed for date-times without offset.
+///
+/// Date-times with offset keep their offset in any case.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum TimezoneFallback {
+    /// Pass date-times without offset as `ColumnValue::String`
+    #[default]
+    Keep,
+    /// Assume UTC and append `Z`
+    Utc,
+    /// Assume a fixed offset in minutes east of UTC, e.g. the local time zone of the data source
+    Offset(i16),
+}
+
+/// Handling of duplicate property names within a feature.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum DuplicateKeyPolicy {
+    /// Keep the value of the first occurrence
+    FirstWins,
+    /// Keep the value of the last occurrence, at the position of the first occurrence
+    #[default]
+    LastWins,
+    /// Fail with a `GeozeroError::Property` error
+    Error,
+}
+
+impl DuplicateKeyPolicy {
+    /// Remove entries with duplicate names according to policy
+    pub(crate) fn apply<V>(self, entries: &mut Vec<(String, V)>) -> Result<()> {
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut unique: Vec<(String, V)> = Vec::with_capacity(entries.len());
+        for (name, value) in entries.drain(..) {
+            match (positions.get(&name), self) {
+                (None, _) => {
+                    positions.insert(name.clone(), unique.len());
+                    unique.push((name, value));
+                }
+                (Some(_), DuplicateKeyPolicy::FirstWins) => {}
+                (Some(pos), DuplicateKeyPolicy::LastWins) => unique[*pos].1 = value,
+                (Some(_), DuplicateKeyPolicy::Error) => {
+                    return Err(GeozeroError::Property(format!(
+                        "duplicate property `{}`",
+                        name
+                    )))
+                }
+            }
+        }
+        *entries = unique;
+        Ok(())
+    }
+}
+
+impl ReadOptions {
+    /// Check whether property column `name` is selected for reading.
+    pub fn selects_column(&self, name: &str) -> bool {
+        match &self.columns {
+            Some(columns) => columns.iter().any(|col| col == name),
+            None => true,
+        }
+    }
+}
+
+/// Options shared by writers.
+///
+/// Options not supported by a writer are ignored.
+#[derive(Clone, Default, Debug)]
+pub struct WriterOptions {
+    /// CRS written to the output metadata (GeoJSON, EWKB, GeoPackage WKB).
+    ///
+    /// Coordinates are not transformed, use a reprojection processor for that.
+    pub crs: Option<Crs>,
+    /// Writing of feature ids (GeoJSON).
+    pub fid: FidStrategy,
+    /// Coordinate dimensions to write.
+    pub dims: CoordDimensions,
+    /// Size of output buffer in bytes, 0 for unbuffered output (GeoJSON, WKT).
+    pub buffer_size: usize,
+    /// Perform all checks of the writer without writing any output (GeoJSON, WKT, CSV).
+    ///
+    /// Use a [ContinueOnError](crate::processor::ContinueOnError) processor to report
+    /// the errors of all features.
+    pub dry_run: bool,
+}
+
+/// Feature id written by a writer.
+#[derive(Clone, PartialEq, Default, Debug)]
+pub enum FidStrategy {
+    /// Don't write feature ids
+    #[default]
+    None,
+    /// Write the feature index as id, numbering features sequentially
+    FeatureIndex,
+    /// Write the value of a property as id
+    Property(String),
+    /// Preserve the feature id of the source passed with [FeatureProcessor::fid].
+    ///
+    /// Features without source id are written without id.
+    Source,
+    /// Write a hash of the feature properties and coordinates as id.
+    ///
+    /// Equal features get the same id, independent of their position in the dataset.
+    Hash,
+}
+
+/// Stability of feature ids passed by a datasource with [FeatureProcessor::fid].
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum FidStability {
+    /// No feature ids, only the feature index is known
+    #[default]
+    None,
+    /// Feature ids of the source are passed where present, uniqueness is not guaranteed
+    Optional,
+    /// All features have a unique id of the source, which doesn't change between reads
+    Stable,
+}
+
+impl WriterOptions {
+    /// Set output CRS
+    pub fn with_crs(mut self, crs: Crs) -> Self {
+        self.crs = Some(crs);
+        self
+    }
+    /// Set feature id strategy
+    pub fn with_fid(mut self, fid: FidStrategy) -> Self {
+        self.fid = fid;
+        self
+    }
+    /// Set coordinate dimensions to write
+    pub fn with_dims(mut self, dims: CoordDimensions) -> Self {
+        self.dims = dims;
+        self
+    }
+    /// Set size of output buffer in bytes
+    pub fn with_buffer_size(mut self, bytes: usize) -> Self {
+        self.buffer_size = bytes;
+        self
+    }
+    /// Validate without writing output
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
 /// Datasource feature consumer trait.
 pub trait GeozeroDatasource {
     /// Consume and process all selected features.
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()>;
+    /// Consume and process all selected features with reading options.
+    ///
+    /// Options not supported by a datasource are ignored, resource limits are
+    /// checked for all datasources.
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+        self.process(&mut processor)
+    }
+    /// Stability of feature ids passed to the processor.
+    ///
+    /// Use [FidStrategy::Source] for writing unique ids only with datasources
+    /// returning [FidStability::Stable].
+    fn fid_stability(&self) -> FidStability {
+        FidStability::None
+    }
     /// Consume and process geometries of all selected features.
     fn process_geom<P: GeomProcessor>(&mut self, processor: &mut P) -> Result<()> {
         let mut geom_processor = DatasourceGeomProcessor(processor);
         self.process(&mut geom_processor)
     }
+    /// Iterator over all selected features.
+    ///
+    /// Geometries and properties of the returned features are processed on demand.
+    ///
+    /// The default implementation is eager: it processes the whole datasource on the
+    /// first call and keeps all features in memory. Streaming datasources override it
+    /// to read one feature at a time, like `GeoJsonReader`, `GeoJsonLineReader` and
+    /// `CsvReader`.
+    fn features(&mut self) -> Result<FeatureIter<'_>>
+    where
+        Self: Sized,
+    {
+        let features = record_features(|buffer| self.process(buffer))?;
+        Ok(FeatureIter::new(features.into_iter().map(Ok)))
+    }
+}
+
+/// Geometry member of features deserialized with [FeatureAccess::from_feature]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GeometryField {
+    /// Properties only
+    None,
+    /// Geometry as GeoJSON object in member `geometry`
+    #[cfg(feature = "with-geojson")]
+    GeoJson,
+    /// Geometry as WKT string in member `geometry`
+    #[cfg(feature = "with-wkt")]
+    Wkt,
 }
 
 /// Feature processing API
@@ -55,6 +323,68 @@ pub trait FeatureAccess: FeatureProperties + GeozeroGeometry {
         processor.geometry_end()?;
         processor.feature_end(idx)
     }
+    /// Deserialize properties and geometry into a user defined type
+    ///
+    /// Properties are converted to a JSON object, which is deserialized with serde.
+    /// The geometry is added as member `geometry` (replacing a property with the same name),
+    /// features without geometry have a `null` value.
+    ///
+    /// # Usage example:
+    ///
+    /// ```rust
+    /// use geozero::geojson::GeoJson;
+    /// use geozero::{FeatureAccess, GeometryField, GeozeroDatasource};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Place {
+    ///     name: String,
+    ///     #[serde(rename = "geometry")]
+    ///     wkt: String,
+    /// }
+    ///
+    /// let geojson = r#"{"type": "FeatureCollection", "features": [
+    ///     {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}
+    /// ]}"#;
+    /// for feature in GeoJson(geojson).features().unwrap() {
+    ///     let place: Place = feature.unwrap().from_feature(GeometryField::Wkt).unwrap();
+    ///     assert_eq!((place.name.as_str(), place.wkt.as_str()), ("Bern", "POINT(7.44 46.95)"));
+    /// }
+    /// ```
+    #[allow(clippy::wrong_self_convention)]
+    fn from_feature<T: DeserializeOwned>(&self, geometry: GeometryField) -> Result<T>
+    where
+        Self: Sized,
+    {
+        let mut properties = serde_json::Map::new();
+        let _ = self.process_properties(&mut properties)?;
+        let value = match geometry {
+            GeometryField::None => None,
+            #[cfg(feature = "with-geojson")]
+            GeometryField::GeoJson => {
+                let json = crate::ToJson::to_json(self)?;
+                if json.is_empty() {
+                    Some(serde_json::Value::Null)
+                } else {
+                    Some(serde_json::from_str(&json)?)
+                }
+            }
+            #[cfg(feature = "with-wkt")]
+            GeometryField::Wkt => {
+                let wkt = crate::ToWkt::to_wkt(self)?;
+                if wkt.is_empty() {
+                    Some(serde_json::Value::Null)
+                } else {
+                    Some(serde_json::Value::String(wkt))
+                }
+            }
+        };
+        if let Some(value) = value {
+            properties.insert("geometry".to_string(), value);
+        }
+        serde_json::from_value(serde_json::Value::Object(properties))
+            .map_err(|e| GeozeroError::Properties(e.to_string()))
+    }
 }
 
 /// Feature properties processing API
@@ -91,6 +421,21 @@ pub trait FeatureProperties {
         let _ = self.process_properties(&mut properties)?;
         Ok(properties)
     }
+    /// Return all properties with typed values in a HashMap
+    fn property_values(&self) -> Result<HashMap<String, OwnedColumnValue>> {
+        let mut properties = HashMap::new();
+        let _ = self.process_properties(&mut properties)?;
+        Ok(properties)
+    }
+    /// Deserialize properties into a user defined type
+    ///
+    /// Properties are converted to a JSON object, which is deserialized with serde.
+    fn properties_into<T: DeserializeOwned>(&self) -> Result<T> {
+        let mut properties = serde_json::Map::new();
+        let _ = self.process_properties(&mut properties)?;
+        serde_json::from_value(serde_json::Value::Object(properties))
+            .map_err(|e| GeozeroError::Properties(e.to_string()))
+    }
 }
 
 // Newtype for GeomProcessor impl for adding no-op PropertyProcessor/FeatureProcessor impl
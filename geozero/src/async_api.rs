@@ -0,0 +1,52 @@
+//! Async API traits for reading datasets in async services.
+//!
+//! Processors are called synchronously, only reading input is asynchronous.
+//! Writers can be used with an in-memory buffer, which is written to an
+//! async output with [write_async].
+
+use crate::error::Result;
+use crate::feature_processor::FeatureProcessor;
+use crate::processor::{Borrowed, ResourceLimiter};
+use crate::ReadOptions;
+use async_trait::async_trait;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Datasource feature consumer reading its input asynchronously.
+///
+/// Async counterpart of [GeozeroDatasource](crate::GeozeroDatasource).
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::{AsyncGeoJsonReader, GeoJsonWriter};
+/// use geozero::{write_async, AsyncGeozeroDatasource};
+///
+/// let mut reader = AsyncGeoJsonReader(tokio::fs::File::open("countries.geojson").await?);
+/// let mut buf: Vec<u8> = Vec::new();
+/// reader.process(&mut GeoJsonWriter::new(&mut buf)).await?;
+/// write_async(&mut socket, &buf).await?;
+/// ```
+#[async_trait]
+pub trait AsyncGeozeroDatasource {
+    /// Consume and process all selected features.
+    async fn process<P: FeatureProcessor + Send>(&mut self, processor: &mut P) -> Result<()>;
+    /// Consume and process all selected features with reading options.
+    ///
+    /// Like [GeozeroDatasource::process_with_options](crate::GeozeroDatasource::process_with_options),
+    /// resource limits are checked for all datasources.
+    async fn process_with_options<P: FeatureProcessor + Send>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+        self.process(&mut processor).await
+    }
+}
+
+/// Write and flush writer output buffered in `buf` to an async output.
+pub async fn write_async<W: AsyncWrite + Unpin + ?Sized>(out: &mut W, buf: &[u8]) -> Result<()> {
+    out.write_all(buf).await?;
+    out.flush().await?;
+    Ok(())
+}
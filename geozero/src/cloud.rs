@@ -0,0 +1,162 @@
+//! Cloud object storage input and output with [object_store](https://docs.rs/object_store).
+//!
+//! Objects are addressed by URL, e.g. `s3://bucket/path`, `gs://bucket/path`,
+//! `az://container/path`, `https://host/path` or `file:///path`. Credentials and
+//! store options are read from the environment (e.g. `AWS_ACCESS_KEY_ID`,
+//! `GOOGLE_SERVICE_ACCOUNT`, `AZURE_STORAGE_ACCOUNT_NAME`).
+//!
+//! Readers fetch whole objects or byte ranges, e.g. the header and index of formats
+//! with a spatial index. Writers buffer their output, which is uploaded with a
+//! multipart upload.
+//!
+//! # Usage example:
+//!
+//! ```rust,ignore
+//! use geozero::cloud::{ObjectReader, ObjectWriter};
+//! use geozero::geojson::{GeoJson, GeoJsonWriter};
+//! use geozero::GeozeroDatasource;
+//!
+//! let input = ObjectReader::open("s3://bucket/countries.geojson")?.read_all().await?;
+//! let mut buf: Vec<u8> = Vec::new();
+//! GeoJson(std::str::from_utf8(&input)?).process(&mut GeoJsonWriter::new(&mut buf))?;
+//! let mut output = ObjectWriter::create("gs://bucket/countries.geojson").await?;
+//! output.write(&buf).await?;
+//! output.finish().await?;
+//! ```
+use crate::error::{GeozeroError, Result};
+use crate::write_async;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::{MultipartId, ObjectStore};
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use url::Url;
+
+fn store_error(e: object_store::Error) -> GeozeroError {
+    GeozeroError::HttpError(e.to_string())
+}
+
+/// Object store and object path of `url`, configured from environment variables
+pub fn parse_url(url: &str) -> Result<(Arc<dyn ObjectStore>, Path)> {
+    let url = Url::parse(url).map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+    let options = std::env::vars().map(|(key, value)| (key.to_ascii_lowercase(), value));
+    let (store, path) = object_store::parse_url_opts(&url, options).map_err(store_error)?;
+    Ok((Arc::from(store), path))
+}
+
+/// Object in a cloud object store.
+pub struct ObjectReader {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+}
+
+impl ObjectReader {
+    pub fn new(store: Arc<dyn ObjectStore>, path: Path) -> Self {
+        ObjectReader { store, path }
+    }
+    /// Object at `url`
+    pub fn open(url: &str) -> Result<Self> {
+        let (store, path) = parse_url(url)?;
+        Ok(Self::new(store, path))
+    }
+    /// Object size in bytes
+    pub async fn size(&self) -> Result<usize> {
+        let meta = self.store.head(&self.path).await.map_err(store_error)?;
+        Ok(meta.size)
+    }
+    /// Read byte range
+    pub async fn read_range(&self, range: Range<usize>) -> Result<Bytes> {
+        self.store
+            .get_range(&self.path, range)
+            .await
+            .map_err(store_error)
+    }
+    /// Read multiple byte ranges, coalescing close ranges into a single request
+    pub async fn read_ranges(&self, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.store
+            .get_ranges(&self.path, ranges)
+            .await
+            .map_err(store_error)
+    }
+    /// Read the whole object
+    pub async fn read_all(&self) -> Result<Bytes> {
+        let result = self.store.get(&self.path).await.map_err(store_error)?;
+        result.bytes().await.map_err(store_error)
+    }
+}
+
+/// Multipart upload of an object to a cloud object store.
+///
+/// The object is created by [finish](ObjectWriter::finish), an unfinished upload
+/// should be cancelled with [abort](ObjectWriter::abort).
+pub struct ObjectWriter {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    multipart_id: MultipartId,
+    upload: Box<dyn AsyncWrite + Unpin + Send>,
+}
+
+impl ObjectWriter {
+    /// Start upload of object `path`
+    pub async fn new(store: Arc<dyn ObjectStore>, path: Path) -> Result<Self> {
+        let (multipart_id, upload) = store.put_multipart(&path).await.map_err(store_error)?;
+        Ok(ObjectWriter {
+            store,
+            path,
+            multipart_id,
+            upload,
+        })
+    }
+    /// Start upload of object at `url`
+    pub async fn create(url: &str) -> Result<Self> {
+        let (store, path) = parse_url(url)?;
+        Self::new(store, path).await
+    }
+    /// Upload writer output buffered in `buf`
+    pub async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        write_async(&mut self.upload, buf).await
+    }
+    /// Complete the upload
+    pub async fn finish(mut self) -> Result<()> {
+        self.upload.shutdown().await?;
+        Ok(())
+    }
+    /// Cancel the upload
+    pub async fn abort(self) -> Result<()> {
+        self.store
+            .abort_multipart(&self.path, &self.multipart_id)
+            .await
+            .map_err(store_error)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::{GeoJson, GeoJsonWriter};
+    use crate::GeozeroDatasource;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn upload_and_read() -> Result<()> {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let path = Path::from("points.geojson");
+
+        let mut buf: Vec<u8> = Vec::new();
+        GeoJson(r#"{"type": "Point", "coordinates": [1, 1]}"#)
+            .process(&mut GeoJsonWriter::new(&mut buf))?;
+        let mut writer = ObjectWriter::new(store.clone(), path.clone()).await?;
+        writer.write(&buf).await?;
+        writer.finish().await?;
+
+        let reader = ObjectReader::new(store, path);
+        assert_eq!(reader.size().await?, buf.len());
+        assert_eq!(reader.read_all().await?, buf);
+        assert_eq!(reader.read_range(0..8).await?, &buf[0..8]);
+        let ranges = reader.read_ranges(&[0..4, 4..8]).await?;
+        assert_eq!(ranges[1], &buf[4..8]);
+        Ok(())
+    }
+}
@@ -0,0 +1,375 @@
+//! Per-column property statistics.
+use crate::datetime::compare_datetimes;
+use crate::error::Result;
+use crate::feature_processor::FeatureProcessor;
+use crate::geometry_processor::GeomProcessor;
+use crate::property_processor::{ColumnType, ColumnValue, OwnedColumnValue, PropertyProcessor};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Statistics of a property column.
+#[derive(Clone, Debug)]
+pub struct ColumnStats {
+    /// Column name
+    pub name: String,
+    /// Type of the first non-null value
+    pub col_type: ColumnType,
+    /// Number of non-null values
+    pub count: u64,
+    /// Number of NULL values, including features without this property
+    pub null_count: u64,
+    /// Minimal value (numeric, boolean and string values)
+    pub min: Option<OwnedColumnValue>,
+    /// Maximal value (numeric, boolean and string values)
+    pub max: Option<OwnedColumnValue>,
+    histogram: Option<HashMap<String, u64>>,
+    hll: HyperLogLog,
+}
+
+impl ColumnStats {
+    fn new(name: &str) -> Self {
+        ColumnStats {
+            name: name.to_string(),
+            col_type: ColumnType::Null,
+            count: 0,
+            null_count: 0,
+            min: None,
+            max: None,
+            histogram: Some(HashMap::new()),
+            hll: HyperLogLog::new(),
+        }
+    }
+    /// Estimated number of distinct non-null values
+    pub fn distinct_count(&self) -> u64 {
+        self.hll.estimate()
+    }
+    /// Number of occurrences per formatted value.
+    ///
+    /// `None` if the column contains more distinct values than the histogram limit.
+    pub fn histogram(&self) -> Option<&HashMap<String, u64>> {
+        self.histogram.as_ref()
+    }
+    fn add(&mut self, value: &ColumnValue, histogram_limit: usize) {
+        if value == &ColumnValue::Null {
+            self.null_count += 1;
+            return;
+        }
+        self.count += 1;
+        if self.col_type == ColumnType::Null {
+            self.col_type = value.column_type();
+        }
+        // Skip values without ordering (NaN, binary, ...)
+        if compare_values(value, value).is_some() {
+            let cmp = |other: &Option<OwnedColumnValue>| match other {
+                Some(other) => compare_values(value, &other.as_column_value()),
+                None => None,
+            };
+            if self.min.is_none() || cmp(&self.min) == Some(Ordering::Less) {
+                self.min = Some(value.into());
+            }
+            if self.max.is_none() || cmp(&self.max) == Some(Ordering::Greater) {
+                self.max = Some(value.into());
+            }
+        }
+        let mut hasher = DefaultHasher::new();
+        hash_value(value, &mut hasher);
+        self.hll.insert(hasher.finish());
+        if let Some(histogram) = &mut self.histogram {
+            let key = value.to_string();
+            if let Some(cnt) = histogram.get_mut(&key) {
+                *cnt += 1;
+            } else if histogram.len() < histogram_limit {
+                histogram.insert(key, 1);
+            } else {
+                self.histogram = None;
+            }
+        }
+    }
+}
+
+/// Compare numeric, boolean, string and date-time values
+fn compare_values(v1: &ColumnValue, v2: &ColumnValue) -> Option<Ordering> {
+    match (v1, v2) {
+        (ColumnValue::Bool(a), ColumnValue::Bool(b)) => a.partial_cmp(b),
+        (ColumnValue::String(a), ColumnValue::String(b))
+        | (ColumnValue::Date(a), ColumnValue::Date(b)) => a.partial_cmp(b),
+        (ColumnValue::DateTime(a), ColumnValue::DateTime(b)) => compare_datetimes(a, b),
+        _ => match (integer_value(v1), integer_value(v2)) {
+            // Compare integers exactly, 64-bit values are not representable as f64
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => numeric_value(v1)?.partial_cmp(&numeric_value(v2)?),
+        },
+    }
+}
+
+fn integer_value(v: &ColumnValue) -> Option<i128> {
+    match *v {
+        ColumnValue::Byte(v) => Some(v as i128),
+        ColumnValue::UByte(v) => Some(v as i128),
+        ColumnValue::Short(v) => Some(v as i128),
+        ColumnValue::UShort(v) => Some(v as i128),
+        ColumnValue::Int(v) => Some(v as i128),
+        ColumnValue::UInt(v) => Some(v as i128),
+        ColumnValue::Long(v) => Some(v as i128),
+        ColumnValue::ULong(v) => Some(v as i128),
+        ColumnValue::BigInt(v) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn numeric_value(v: &ColumnValue) -> Option<f64> {
+    match *v {
+        ColumnValue::Byte(v) => Some(v as f64),
+        ColumnValue::UByte(v) => Some(v as f64),
+        ColumnValue::Short(v) => Some(v as f64),
+        ColumnValue::UShort(v) => Some(v as f64),
+        ColumnValue::Int(v) => Some(v as f64),
+        ColumnValue::UInt(v) => Some(v as f64),
+        ColumnValue::Long(v) => Some(v as f64),
+        ColumnValue::ULong(v) => Some(v as f64),
+        ColumnValue::Float(v) => Some(v as f64),
+        ColumnValue::Double(v) => Some(v),
+        ColumnValue::BigInt(v) => v.parse().ok(),
+        _ => None,
+    }
+}
+
+fn hash_value<H: Hasher>(v: &ColumnValue, state: &mut H) {
+    match v {
+        ColumnValue::Binary(v) => v.hash(state),
+        ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v) => v.hash(state),
+        _ => match (integer_value(v), numeric_value(v)) {
+            // Hash numbers by value, independent of their type
+            (Some(int), _) => int.hash(state),
+            (None, Some(num)) if num.fract() == 0.0 && num.abs() < 1e38 => {
+                (num as i128).hash(state)
+            }
+            (None, Some(num)) => num.to_bits().hash(state),
+            (None, None) => v.to_string().hash(state),
+        },
+    }
+}
+
+/// HyperLogLog distinct count estimator
+#[derive(Clone, Debug)]
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Number of index bits (4096 registers, ~1.6% standard error)
+    const P: u32 = 12;
+
+    fn new() -> Self {
+        HyperLogLog {
+            registers: vec![0; 1 << Self::P],
+        }
+    }
+    fn insert(&mut self, hash: u64) {
+        let idx = (hash >> (64 - Self::P)) as usize;
+        let rank = ((hash << Self::P) | (1 << (Self::P - 1))).leading_zeros() + 1;
+        let register = &mut self.registers[idx];
+        *register = (*register).max(rank as u8);
+    }
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            // Linear counting for small cardinalities
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+/// Collects per-column statistics while streaming features.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::{ColumnStatsCollector, ColumnValue, FeatureProcessor, PropertyProcessor};
+///
+/// let mut collector = ColumnStatsCollector::new();
+/// for (idx, pop) in [133115, 421878, 140202].iter().enumerate() {
+///     collector.feature_begin(idx as u64).unwrap();
+///     collector.property(0, "pop", &ColumnValue::Int(*pop)).unwrap();
+///     collector.feature_end(idx as u64).unwrap();
+/// }
+/// let stats = &collector.stats()[0];
+/// assert_eq!(stats.min, Some(geozero::OwnedColumnValue::Int(133115)));
+/// assert_eq!(stats.distinct_count(), 3);
+/// ```
+#[derive(Debug)]
+pub struct ColumnStatsCollector {
+    columns: Vec<ColumnStats>,
+    column_idx: HashMap<String, usize>,
+    /// Columns seen in current feature
+    seen: Vec<bool>,
+    histogram_limit: usize,
+    feature_count: u64,
+}
+
+impl Default for ColumnStatsCollector {
+    fn default() -> Self {
+        Self::with_histogram_limit(100)
+    }
+}
+
+impl ColumnStatsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Collect value histograms for columns with at most `limit` distinct values
+    pub fn with_histogram_limit(limit: usize) -> Self {
+        ColumnStatsCollector {
+            columns: Vec::new(),
+            column_idx: HashMap::new(),
+            seen: Vec::new(),
+            histogram_limit: limit,
+            feature_count: 0,
+        }
+    }
+    /// Statistics of all columns in order of their first appearance
+    pub fn stats(&self) -> &[ColumnStats] {
+        &self.columns
+    }
+    /// Statistics of column `name`
+    pub fn column_stats(&self, name: &str) -> Option<&ColumnStats> {
+        self.column_idx.get(name).map(|idx| &self.columns[*idx])
+    }
+    /// Number of processed features
+    pub fn feature_count(&self) -> u64 {
+        self.feature_count
+    }
+    /// Consume collector and return column statistics
+    pub fn into_stats(self) -> Vec<ColumnStats> {
+        self.columns
+    }
+}
+
+impl PropertyProcessor for ColumnStatsCollector {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        let idx = if let Some(&idx) = self.column_idx.get(name) {
+            idx
+        } else {
+            let mut column = ColumnStats::new(name);
+            // Column was missing in previous features
+            column.null_count = self.feature_count;
+            self.column_idx.insert(name.to_string(), self.columns.len());
+            self.columns.push(column);
+            self.seen.push(false);
+            self.columns.len() - 1
+        };
+        self.columns[idx].add(value, self.histogram_limit);
+        self.seen[idx] = true;
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for ColumnStatsCollector {
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.seen.iter_mut().for_each(|seen| *seen = false);
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        for (column, seen) in self.columns.iter_mut().zip(&self.seen) {
+            if !seen {
+                column.null_count += 1;
+            }
+        }
+        self.feature_count += 1;
+        Ok(())
+    }
+}
+
+impl GeomProcessor for ColumnStatsCollector {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn column_stats() -> Result<()> {
+        let mut collector = ColumnStatsCollector::with_histogram_limit(2);
+        let rows = [
+            (ColumnValue::String("b"), Some(ColumnValue::Double(2.5))),
+            (ColumnValue::String("a"), None),
+            (ColumnValue::String("c"), Some(ColumnValue::Null)),
+            (ColumnValue::String("a"), Some(ColumnValue::Double(-1.0))),
+        ];
+        for (idx, (name, value)) in rows.iter().enumerate() {
+            collector.feature_begin(idx as u64)?;
+            collector.property(0, "name", name)?;
+            if let Some(value) = value {
+                collector.property(1, "value", value)?;
+            }
+            collector.feature_end(idx as u64)?;
+        }
+        assert_eq!(collector.feature_count(), 4);
+
+        let name = collector.column_stats("name").unwrap();
+        assert_eq!(name.count, 4);
+        assert_eq!(name.null_count, 0);
+        assert_eq!(name.min, Some(OwnedColumnValue::String("a".to_string())));
+        assert_eq!(name.max, Some(OwnedColumnValue::String("c".to_string())));
+        assert_eq!(name.distinct_count(), 3);
+        assert!(name.histogram().is_none());
+
+        let value = collector.column_stats("value").unwrap();
+        assert_eq!(value.col_type, ColumnType::Double);
+        assert_eq!(value.count, 2);
+        assert_eq!(value.null_count, 2);
+        assert_eq!(value.min, Some(OwnedColumnValue::Double(-1.0)));
+        assert_eq!(value.max, Some(OwnedColumnValue::Double(2.5)));
+        assert_eq!(value.histogram().unwrap()["2.5"], 1);
+        Ok(())
+    }
+
+    #[test]
+    fn integer_stats() -> Result<()> {
+        let mut collector = ColumnStatsCollector::new();
+        let values = [
+            ColumnValue::ULong(9_007_199_254_740_993),
+            ColumnValue::ULong(9_007_199_254_740_992),
+            ColumnValue::BigInt("-36893488147419103232"),
+            ColumnValue::Double(2.0),
+            ColumnValue::Int(2),
+        ];
+        for (idx, value) in values.iter().enumerate() {
+            collector.feature_begin(idx as u64)?;
+            collector.property(0, "id", value)?;
+            collector.feature_end(idx as u64)?;
+        }
+        let id = collector.column_stats("id").unwrap();
+        assert_eq!(
+            id.min,
+            Some(OwnedColumnValue::BigInt(
+                "-36893488147419103232".to_string()
+            ))
+        );
+        assert_eq!(id.max, Some(OwnedColumnValue::ULong(9_007_199_254_740_993)));
+        assert_eq!(id.distinct_count(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_count_estimate() {
+        let mut hll = HyperLogLog::new();
+        for i in 0..100_000u64 {
+            let mut hasher = DefaultHasher::new();
+            i.hash(&mut hasher);
+            hll.insert(hasher.finish());
+        }
+        let estimate = hll.estimate() as f64;
+        assert!((estimate - 100_000.0).abs() / 100_000.0 < 0.05);
+    }
+}
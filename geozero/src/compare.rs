@@ -0,0 +1,407 @@
+//! Comparison of geometries within a coordinate tolerance.
+use crate::api::GeozeroGeometry;
+use crate::error::Result;
+use crate::geometry_processor::CoordDimensions;
+use crate::processor::{Event, ProcessorRecorder};
+
+/// Coordinate with optional Z and M values
+type Coord = [Option<f64>; 4];
+
+/// Geometry tree built from recorded processor calls
+#[derive(Debug)]
+struct Node {
+    kind: &'static str,
+    coords: Vec<Coord>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn new(kind: &'static str) -> Self {
+        Node {
+            kind,
+            coords: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+    /// Parts may be reordered
+    fn is_collection(&self) -> bool {
+        matches!(
+            self.kind,
+            "MultiPoint"
+                | "MultiLineString"
+                | "MultiPolygon"
+                | "GeometryCollection"
+                | "MultiCurve"
+                | "MultiSurface"
+                | "PolyhedralSurface"
+                | "Tin"
+        )
+    }
+    /// Interior rings may be reordered
+    fn is_surface(&self) -> bool {
+        matches!(self.kind, "Polygon" | "CurvePolygon" | "Triangle")
+    }
+}
+
+/// Geometry type name of `*_begin` calls
+fn begin_kind(event: &Event) -> Option<&'static str> {
+    Some(match event {
+        Event::PointBegin(..) => "Point",
+        Event::MultiPointBegin(..) => "MultiPoint",
+        Event::LineStringBegin(..) => "LineString",
+        Event::MultiLineStringBegin(..) => "MultiLineString",
+        Event::PolygonBegin(..) => "Polygon",
+        Event::MultiPolygonBegin(..) => "MultiPolygon",
+        Event::GeometryCollectionBegin(..) => "GeometryCollection",
+        Event::CircularStringBegin(..) => "CircularString",
+        Event::CompoundCurveBegin(..) => "CompoundCurve",
+        Event::CurvePolygonBegin(..) => "CurvePolygon",
+        Event::MultiCurveBegin(..) => "MultiCurve",
+        Event::MultiSurfaceBegin(..) => "MultiSurface",
+        Event::TriangleBegin(..) => "Triangle",
+        Event::PolyhedralSurfaceBegin(..) => "PolyhedralSurface",
+        Event::TinBegin(..) => "Tin",
+        _ => return None,
+    })
+}
+
+/// Build geometry trees of recorded calls
+fn build_tree(events: &[Event]) -> Vec<Node> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Node> = Vec::new();
+    let mut add = |stack: &mut Vec<Node>, node: Node| match stack.last_mut() {
+        Some(parent) => parent.children.push(node),
+        None => roots.push(node),
+    };
+    for event in events {
+        let coord = match event {
+            Event::Xy(x, y, _) => Some([Some(*x), Some(*y), None, None]),
+            Event::Coordinate(x, y, z, m, ..) => Some([Some(*x), Some(*y), *z, *m]),
+            _ => None,
+        };
+        if let Some(coord) = coord {
+            match stack.last_mut() {
+                Some(node) => node.coords.push(coord),
+                None => {
+                    // Coordinate without geometry
+                    let mut node = Node::new("Point");
+                    node.coords.push(coord);
+                    add(&mut stack, node);
+                }
+            }
+        } else if let Event::EmptyPoint(_) = event {
+            add(&mut stack, Node::new("Point"));
+        } else if let Some(kind) = begin_kind(event) {
+            stack.push(Node::new(kind));
+        } else if event.nesting() < 0 {
+            if let Some(mut node) = stack.pop() {
+                // Points with NaN coordinates are empty (e.g. in WKB)
+                if node.kind == "Point" && node.coords.iter().all(all_nan) {
+                    node.coords.clear();
+                }
+                add(&mut stack, node);
+            }
+        }
+    }
+    // Unterminated geometries
+    while let Some(node) = stack.pop() {
+        add(&mut stack, node);
+    }
+    roots
+}
+
+fn all_nan(coord: &Coord) -> bool {
+    coord.iter().flatten().all(|v| v.is_nan())
+}
+
+/// Compares geometries within a coordinate tolerance.
+///
+/// Geometries are equivalent, if they have the same structure and coordinates differ
+/// by at most `tolerance` in each compared dimension. Size hints, part indices and
+/// SRIDs are not compared. Points with NaN coordinates are treated as empty points.
+/// Optionally closed rings may start at a different vertex and parts of collections
+/// and interior rings of polygons may appear in a different order.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::wkt::WktStr;
+/// use geozero::GeometryComparison;
+///
+/// let cmp = GeometryComparison::new(1e-6).ring_rotation(true);
+/// let a = WktStr("POLYGON((0 0,1 0,1 1,0 0))");
+/// let b = WktStr("POLYGON((1 0,1 1,0 0,1 0))");
+/// assert!(cmp.equivalent(&a, &b).unwrap());
+/// assert_eq!(
+///     cmp.difference(&a, &WktStr("POLYGON((0 0,1 0,1 2,0 0))")).unwrap().as_deref(),
+///     Some("Polygon/0 LineString: coordinates differ")
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct GeometryComparison {
+    tolerance: f64,
+    dims: CoordDimensions,
+    ring_rotation: bool,
+    unordered_parts: bool,
+}
+
+impl GeometryComparison {
+    pub fn new(tolerance: f64) -> Self {
+        GeometryComparison {
+            tolerance,
+            dims: CoordDimensions::xy(),
+            ring_rotation: false,
+            unordered_parts: false,
+        }
+    }
+    /// Compared dimensions (default: XY)
+    pub fn with_dims(mut self, dims: CoordDimensions) -> Self {
+        self.dims = dims;
+        self
+    }
+    /// Accept closed rings starting at a different vertex (default: false)
+    pub fn ring_rotation(mut self, enabled: bool) -> Self {
+        self.ring_rotation = enabled;
+        self
+    }
+    /// Accept collection parts and interior rings in any order (default: false)
+    pub fn unordered_parts(mut self, enabled: bool) -> Self {
+        self.unordered_parts = enabled;
+        self
+    }
+    /// Geometries are equivalent
+    pub fn equivalent<A: GeozeroGeometry, B: GeozeroGeometry>(&self, a: &A, b: &B) -> Result<bool> {
+        Ok(self.difference(a, b)?.is_none())
+    }
+    /// Description of the first difference found, `None` if geometries are equivalent
+    pub fn difference<A: GeozeroGeometry, B: GeozeroGeometry>(
+        &self,
+        a: &A,
+        b: &B,
+    ) -> Result<Option<String>> {
+        let a = self.tree(a)?;
+        let b = self.tree(b)?;
+        Ok(self.diff_nodes(&a, &b, false, "", 0))
+    }
+    fn tree<G: GeozeroGeometry>(&self, geom: &G) -> Result<Vec<Node>> {
+        let mut recorder = ProcessorRecorder::new().with_dims(self.dims);
+        geom.process_geom(&mut recorder)?;
+        Ok(build_tree(recorder.events()))
+    }
+    fn coord_eq(&self, a: &Coord, b: &Coord) -> bool {
+        let compared = [true, true, self.dims.z, self.dims.m];
+        a.iter()
+            .zip(b.iter())
+            .zip(compared.iter())
+            .all(|((a, b), compared)| match (a, b) {
+                _ if !compared => true,
+                (Some(a), Some(b)) => (a.is_nan() && b.is_nan()) || (a - b).abs() <= self.tolerance,
+                (None, None) => true,
+                _ => false,
+            })
+    }
+    fn coords_eq(&self, a: &[Coord], b: &[Coord]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        if a.iter().zip(b).all(|(a, b)| self.coord_eq(a, b)) {
+            return true;
+        }
+        let closed = |c: &[Coord]| c.len() >= 4 && self.coord_eq(&c[0], &c[c.len() - 1]);
+        if !self.ring_rotation || !closed(a) || !closed(b) {
+            return false;
+        }
+        // Compare rings without closing vertex at every start vertex
+        let n = a.len() - 1;
+        (1..n).any(|start| (0..n).all(|i| self.coord_eq(&a[i], &b[(start + i) % n])))
+    }
+    fn unordered_coords_eq(&self, a: &[Coord], b: &[Coord]) -> bool {
+        let mut used = vec![false; b.len()];
+        a.len() == b.len()
+            && a.iter().all(|ca| {
+                match (0..b.len()).find(|j| !used[*j] && self.coord_eq(ca, &b[*j])) {
+                    Some(j) => {
+                        used[j] = true;
+                        true
+                    }
+                    None => false,
+                }
+            })
+    }
+    fn diff_node(&self, a: &Node, b: &Node, prefix: &str) -> Option<String> {
+        let path = if prefix.is_empty() {
+            a.kind.to_string()
+        } else {
+            format!("{} {}", prefix, a.kind)
+        };
+        if a.kind != b.kind {
+            return Some(format!("{}: expected {}", path, b.kind));
+        }
+        // Points of a MultiPoint may be processed without `point_begin`
+        let coords_eq = if a.kind == "MultiPoint" && self.unordered_parts {
+            self.unordered_coords_eq(&a.coords, &b.coords)
+        } else {
+            self.coords_eq(&a.coords, &b.coords)
+        };
+        if !coords_eq {
+            return Some(format!("{}: coordinates differ", path));
+        }
+        if a.is_surface() && !a.children.is_empty() && !b.children.is_empty() {
+            // Exterior ring is compared in place
+            let prefix = format!("{}/0", path);
+            if let Some(diff) = self.diff_node(&a.children[0], &b.children[0], &prefix) {
+                return Some(diff);
+            }
+            return self.diff_nodes(&a.children[1..], &b.children[1..], true, &path, 1);
+        }
+        self.diff_nodes(&a.children, &b.children, a.is_collection(), &path, 0)
+    }
+    /// Compare child nodes, numbered from `first`
+    fn diff_nodes(
+        &self,
+        a: &[Node],
+        b: &[Node],
+        reorder: bool,
+        path: &str,
+        first: usize,
+    ) -> Option<String> {
+        if a.len() != b.len() {
+            return Some(format!(
+                "{}: {} parts instead of {}",
+                if path.is_empty() { "geometry" } else { path },
+                a.len(),
+                b.len()
+            ));
+        }
+        let mut used = vec![false; b.len()];
+        for (i, node) in a.iter().enumerate() {
+            let prefix = if path.is_empty() {
+                String::new()
+            } else {
+                format!("{}/{}", path, first + i)
+            };
+            let prefix = prefix.as_str();
+            let diff = match self.diff_node(node, &b[i], prefix) {
+                Some(diff) if reorder && self.unordered_parts => diff,
+                Some(diff) => return Some(diff),
+                None if !used[i] => {
+                    used[i] = true;
+                    continue;
+                }
+                None => String::new(),
+            };
+            // Search equivalent unused part
+            match (0..b.len()).find(|j| !used[*j] && self.diff_node(node, &b[*j], prefix).is_none())
+            {
+                Some(j) => used[j] = true,
+                None if diff.is_empty() => {
+                    return Some(format!("{} {}: no matching part", prefix, node.kind))
+                }
+                None => return Some(diff),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-wkt")]
+mod test {
+    use super::*;
+    use crate::wkt::WktStr;
+
+    fn diff(cmp: &GeometryComparison, a: &str, b: &str) -> Option<String> {
+        cmp.difference(&WktStr(a), &WktStr(b)).unwrap()
+    }
+
+    #[test]
+    fn tolerance() {
+        let cmp = GeometryComparison::new(0.01);
+        assert_eq!(diff(&cmp, "POINT(1 2)", "POINT(1.005 2)"), None);
+        assert_eq!(
+            diff(&cmp, "POINT(1 2)", "POINT(1.1 2)").as_deref(),
+            Some("Point: coordinates differ")
+        );
+        assert_eq!(
+            diff(&cmp, "POINT(1 2)", "LINESTRING(1 2,3 4)").as_deref(),
+            Some("Point: expected LineString")
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn dims() {
+        use crate::geojson::GeoJson;
+        let a = GeoJson(r#"{"type": "Point", "coordinates": [1, 2, 3]}"#);
+        let b = GeoJson(r#"{"type": "Point", "coordinates": [1, 2, 4]}"#);
+        let c = GeoJson(r#"{"type": "Point", "coordinates": [1, 2]}"#);
+        // Z is compared only if requested
+        let cmp = GeometryComparison::new(0.0);
+        assert!(cmp.equivalent(&a, &b).unwrap());
+        let cmp = cmp.with_dims(CoordDimensions::xyz());
+        assert!(!cmp.equivalent(&a, &b).unwrap());
+        assert!(!cmp.equivalent(&a, &c).unwrap());
+        assert!(cmp.equivalent(&a, &a).unwrap());
+    }
+
+    #[test]
+    fn empty_points() {
+        let cmp = GeometryComparison::new(0.0);
+        assert_eq!(diff(&cmp, "POINT EMPTY", "POINT EMPTY"), None);
+        assert!(diff(&cmp, "POINT EMPTY", "POINT(0 0)").is_some());
+    }
+
+    #[test]
+    fn rings_and_parts() {
+        let cmp = GeometryComparison::new(0.0);
+        let poly = "POLYGON((0 0,4 0,4 4,0 0),(1 1,2 1,2 2,1 1),(3 1,3 2,2 1,3 1))";
+        let rotated = "POLYGON((4 0,4 4,0 0,4 0),(1 1,2 1,2 2,1 1),(3 1,3 2,2 1,3 1))";
+        let reordered = "POLYGON((0 0,4 0,4 4,0 0),(3 1,3 2,2 1,3 1),(1 1,2 1,2 2,1 1))";
+        assert!(diff(&cmp, poly, rotated).is_some());
+        assert!(diff(&cmp, poly, reordered).is_some());
+        let cmp = cmp.ring_rotation(true).unordered_parts(true);
+        assert_eq!(diff(&cmp, poly, rotated), None);
+        assert_eq!(diff(&cmp, poly, reordered), None);
+        // Exterior ring stays first
+        let swapped = "POLYGON((1 1,2 1,2 2,1 1),(0 0,4 0,4 4,0 0),(3 1,3 2,2 1,3 1))";
+        assert!(diff(&cmp, poly, swapped).is_some());
+
+        let multi = "MULTIPOINT(0 0,1 1,2 2)";
+        assert_eq!(diff(&cmp, multi, "MULTIPOINT(2 2,0 0,1 1)"), None);
+        assert_eq!(
+            diff(&cmp, multi, "MULTIPOINT(2 2,0 0,0 0)").as_deref(),
+            Some("MultiPoint: coordinates differ")
+        );
+        let coll = "GEOMETRYCOLLECTION(POINT(0 0),LINESTRING(1 1,2 2))";
+        assert_eq!(
+            diff(
+                &cmp,
+                coll,
+                "GEOMETRYCOLLECTION(LINESTRING(1 1,2 2),POINT(0 0))"
+            ),
+            None
+        );
+        assert_eq!(
+            diff(
+                &cmp,
+                coll,
+                "GEOMETRYCOLLECTION(LINESTRING(1 1,2 2),POINT(0 1))"
+            )
+            .as_deref(),
+            Some("GeometryCollection/0 Point: expected LineString")
+        );
+        assert_eq!(
+            diff(
+                &cmp,
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 0)))",
+                "MULTIPOLYGON EMPTY"
+            )
+            .as_deref(),
+            Some("MultiPolygon: 1 parts instead of 0")
+        );
+        assert_eq!(
+            diff(&cmp, multi, "MULTIPOINT(0 0,1 1)").as_deref(),
+            Some("MultiPoint: coordinates differ")
+        );
+    }
+}
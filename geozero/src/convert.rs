@@ -0,0 +1,54 @@
+use crate::error::Result;
+use crate::processor::{Borrowed, Extent, ExtentCalc};
+use crate::{FeatureProcessor, GeozeroDatasource};
+
+/// Statistics of a conversion with [convert].
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct ConvertStats {
+    /// Number of converted features
+    pub feature_count: u64,
+    /// Number of converted vertices
+    pub vertex_count: u64,
+    /// Extent of converted geometries
+    pub extent: Option<Extent>,
+}
+
+/// Convert all features of `source` with processor `sink`.
+///
+/// Any datasource can be converted with any writer or processor pipeline.
+/// With feature `with-tracing`, the conversion is traced with a [TraceProcessor](crate::processor::TraceProcessor).
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::Extent;
+/// use geozero::wkt::WktWriter;
+///
+/// let mut source = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///   {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[1, 2], [3, 4]]}}
+/// ]}"#);
+/// let mut out: Vec<u8> = Vec::new();
+/// let stats = geozero::convert(&mut source, &mut WktWriter::new(&mut out)).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(1 2,3 4)");
+/// assert_eq!(stats.feature_count, 1);
+/// assert_eq!(stats.vertex_count, 2);
+/// assert_eq!(stats.extent, Some(Extent::new(1.0, 2.0, 3.0, 4.0)));
+/// ```
+pub fn convert<D, P>(source: &mut D, sink: &mut P) -> Result<ConvertStats>
+where
+    D: GeozeroDatasource,
+    P: FeatureProcessor,
+{
+    #[cfg(feature = "with-tracing")]
+    let sink = crate::processor::TraceProcessor::new(Borrowed::new(sink));
+    #[cfg(not(feature = "with-tracing"))]
+    let sink = Borrowed::new(sink);
+    let mut calc = ExtentCalc::tee(sink);
+    source.process(&mut calc)?;
+    Ok(ConvertStats {
+        feature_count: calc.feature_count(),
+        vertex_count: calc.vertex_count(),
+        extent: calc.extent(),
+    })
+}
@@ -0,0 +1,518 @@
+//! Coordinate reference systems.
+use crate::error::Result;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Coordinate reference system definition.
+///
+/// Passed from datasources to processors with [FeatureProcessor::crs](crate::FeatureProcessor::crs).
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::Crs;
+///
+/// let crs = Crs::from_definition("EPSG:2056").unwrap();
+/// assert_eq!(crs, Crs::Epsg(2056));
+/// assert_eq!(crs.to_string(), "EPSG:2056");
+///
+/// let crs = Crs::from_definition("urn:ogc:def:crs:EPSG::2056").unwrap();
+/// assert_eq!(crs, Crs::EpsgUrn(2056));
+/// assert_eq!(crs.epsg(), Some(2056));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub enum Crs {
+    /// EPSG code
+    Epsg(i32),
+    /// EPSG code as OGC URN or URI (`urn:ogc:def:crs:EPSG::4326`), declaring coordinates
+    /// in the axis order of the EPSG registry
+    EpsgUrn(i32),
+    /// OGC WKT (WKT1, WKT2 or ESRI WKT)
+    Wkt(String),
+    /// PROJJSON
+    ProjJson(String),
+}
+
+impl Crs {
+    /// Geographic WGS 84 (`EPSG:4326`)
+    pub fn wgs84() -> Self {
+        Crs::Epsg(4326)
+    }
+    /// Parse CRS definition.
+    ///
+    /// Supported are EPSG codes like `EPSG:4326`, `urn:ogc:def:crs:EPSG::4326` or
+    /// `http://www.opengis.net/def/crs/EPSG/0/4326`, WKT and PROJJSON.
+    pub fn from_definition(definition: &str) -> Option<Self> {
+        let definition = definition.trim();
+        if let Some(crs) = epsg_crs(definition) {
+            Some(crs)
+        } else if definition.starts_with('{') {
+            Some(Crs::ProjJson(definition.to_string()))
+        } else if is_wkt(definition) {
+            Some(Crs::Wkt(definition.to_string()))
+        } else {
+            None
+        }
+    }
+    /// EPSG code, if known
+    pub fn epsg(&self) -> Option<i32> {
+        match self {
+            Crs::Epsg(code) | Crs::EpsgUrn(code) => Some(*code),
+            Crs::Wkt(wkt) => WktCrs::parse(wkt)?.epsg(),
+            Crs::ProjJson(json) => {
+                let json: serde_json::Value = serde_json::from_str(json).ok()?;
+                let id = &json["id"];
+                if id["authority"].as_str()? == "EPSG" {
+                    id["code"].as_i64().map(|code| code as i32)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+    /// CRS name, if contained in definition
+    pub fn name(&self) -> Option<String> {
+        match self {
+            Crs::Epsg(_) | Crs::EpsgUrn(_) => None,
+            Crs::Wkt(wkt) => WktCrs::parse(wkt)?.name,
+            Crs::ProjJson(json) => {
+                let json: serde_json::Value = serde_json::from_str(json).ok()?;
+                json["name"].as_str().map(str::to_string)
+            }
+        }
+    }
+    /// Axis order of horizontal coordinates as defined by the CRS authority.
+    ///
+    /// EPSG codes are looked up in a built-in table of common geographic and
+    /// north-first projected CRS, other codes are assumed to be east-first.
+    pub fn axis_order(&self) -> Option<AxisOrder> {
+        match self {
+            Crs::Epsg(code) | Crs::EpsgUrn(code) if NORTH_FIRST_EPSG.contains(code) => {
+                Some(AxisOrder::NorthEast)
+            }
+            Crs::Epsg(_) | Crs::EpsgUrn(_) => Some(AxisOrder::EastNorth),
+            Crs::Wkt(wkt) => WktCrs::parse(wkt)?.axis_order,
+            Crs::ProjJson(json) => {
+                let json: serde_json::Value = serde_json::from_str(json).ok()?;
+                let crs = if json["type"] == "BoundCRS" {
+                    &json["source_crs"]
+                } else {
+                    &json
+                };
+                match crs["coordinate_system"]["axis"][0]["direction"].as_str()? {
+                    "north" | "south" => Some(AxisOrder::NorthEast),
+                    "east" | "west" => Some(AxisOrder::EastNorth),
+                    _ => None,
+                }
+            }
+        }
+    }
+    /// Axis order of coordinates of a datasource declaring this CRS.
+    ///
+    /// EPSG URNs and URIs (GML, WFS 2.0) and WKT or PROJJSON definitions with axes
+    /// declare the authority axis order. Plain EPSG codes and SRIDs are used with
+    /// easting or longitude first, like in GeoJSON, Shapefiles or GeoPackages.
+    pub fn coordinate_axis_order(&self) -> AxisOrder {
+        match self {
+            Crs::Epsg(_) => AxisOrder::EastNorth,
+            _ => self.axis_order().unwrap_or(AxisOrder::EastNorth),
+        }
+    }
+    /// SRID for formats identifying CRS by EPSG code (EWKB, GeoPackage)
+    pub fn srid(&self) -> Option<i32> {
+        self.epsg()
+    }
+    /// Definition accepted by PROJ
+    pub fn definition(&self) -> String {
+        match self {
+            Crs::Epsg(code) => format!("EPSG:{}", code),
+            Crs::EpsgUrn(code) => format!("urn:ogc:def:crs:EPSG::{}", code),
+            Crs::Wkt(def) | Crs::ProjJson(def) => def.clone(),
+        }
+    }
+}
+
+impl From<i32> for Crs {
+    fn from(code: i32) -> Self {
+        Crs::Epsg(code)
+    }
+}
+
+impl fmt::Display for Crs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.definition())
+    }
+}
+
+/// EPSG codes of geographic and projected CRS with latitude or northing as first axis
+const NORTH_FIRST_EPSG: &[i32] = &[
+    4149, 4150, 4167, 4230, 4258, 4267, 4269, 4283, 4314, 4326, 4490, 4612, 4617, 4619, 4674, 4937,
+    4979, 6668, 7844, 2180, 2193, 3006, 3034, 3035, 3844, 31466, 31467, 31468, 31469,
+];
+
+/// Axis order of horizontal coordinates
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AxisOrder {
+    /// Easting or longitude first
+    EastNorth,
+    /// Northing or latitude first
+    NorthEast,
+}
+
+/// CRS properties read from a WKT definition.
+///
+/// Supports OGC WKT2, WKT1 and ESRI WKT as found in `.prj` files, without requiring PROJ.
+/// For bound and compound CRS, the properties of the (horizontal) source CRS are returned.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::{AxisOrder, WktCrs};
+///
+/// let wkt = r#"GEOGCRS["WGS 84",
+///     DATUM["World Geodetic System 1984", ELLIPSOID["WGS 84",6378137,298.257223563]],
+///     CS[ellipsoidal,2],
+///         AXIS["geodetic latitude (Lat)",north],
+///         AXIS["geodetic longitude (Lon)",east],
+///     ID["EPSG",4326]]"#;
+/// let crs = WktCrs::parse(wkt).unwrap();
+/// assert_eq!(crs.name.as_deref(), Some("WGS 84"));
+/// assert_eq!(crs.epsg(), Some(4326));
+/// assert_eq!(crs.axis_order, Some(AxisOrder::NorthEast));
+/// ```
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct WktCrs {
+    /// CRS name
+    pub name: Option<String>,
+    /// Authority name and code (e.g. `("EPSG", "2056")`)
+    pub authority: Option<(String, String)>,
+    /// Axis order, if declared with `AXIS` elements
+    pub axis_order: Option<AxisOrder>,
+}
+
+impl WktCrs {
+    /// Parse WKT CRS definition. Returns `None` for invalid WKT.
+    pub fn parse(wkt: &str) -> Option<Self> {
+        let mut parser = WktParser {
+            chars: wkt.chars().peekable(),
+        };
+        let root = parser.node()?;
+        let crs = horizontal_crs(&root);
+        let name = crs.args.iter().find_map(|arg| match arg {
+            WktValue::Text(name) => Some(name.clone()),
+            _ => None,
+        });
+        let authority = crs
+            .children()
+            .find(|node| node.is("ID") || node.is("AUTHORITY"))
+            .and_then(|node| match node.args.as_slice() {
+                [authority, code, ..] => Some((authority.as_str()?, code.as_str()?)),
+                _ => None,
+            })
+            .map(|(authority, code)| (authority.to_string(), code.to_string()));
+        let axis_order = crs
+            .children()
+            .find(|node| node.is("AXIS"))
+            .and_then(|axis| axis.args.get(1)?.as_str())
+            .and_then(|direction| match direction.to_ascii_lowercase().as_str() {
+                "north" | "south" => Some(AxisOrder::NorthEast),
+                "east" | "west" => Some(AxisOrder::EastNorth),
+                _ => None,
+            });
+        Some(WktCrs {
+            name,
+            authority,
+            axis_order,
+        })
+    }
+    /// EPSG code from authority or well-known ESRI CRS name
+    pub fn epsg(&self) -> Option<i32> {
+        match &self.authority {
+            Some((authority, code)) if authority.eq_ignore_ascii_case("EPSG") => code.parse().ok(),
+            Some(_) => None,
+            None => esri_epsg(self.name.as_deref()?),
+        }
+    }
+}
+
+/// EPSG code of common ESRI CRS names without authority
+fn esri_epsg(name: &str) -> Option<i32> {
+    match name {
+        "GCS_WGS_1984" => Some(4326),
+        "WGS_1984_Web_Mercator_Auxiliary_Sphere" => Some(3857),
+        "ETRS_1989_LAEA" => Some(3035),
+        _ => {
+            let zone = name.strip_prefix("WGS_1984_UTM_Zone_")?;
+            let (number, hemisphere) = zone.split_at(zone.len().checked_sub(1)?);
+            let number: i32 = number.parse().ok().filter(|n| (1..=60).contains(n))?;
+            match hemisphere {
+                "N" => Some(32600 + number),
+                "S" => Some(32700 + number),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// WKT element value
+#[derive(Debug)]
+enum WktValue {
+    /// Quoted text
+    Text(String),
+    /// Number or enumeration value
+    Word(String),
+    Node(WktNode),
+}
+
+impl WktValue {
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            WktValue::Text(s) | WktValue::Word(s) => Some(s),
+            WktValue::Node(_) => None,
+        }
+    }
+}
+
+/// WKT element `KEYWORD[value, ...]`
+#[derive(Debug)]
+struct WktNode {
+    keyword: String,
+    args: Vec<WktValue>,
+}
+
+impl WktNode {
+    fn is(&self, keyword: &str) -> bool {
+        self.keyword.eq_ignore_ascii_case(keyword)
+    }
+    fn children(&self) -> impl Iterator<Item = &WktNode> {
+        self.args.iter().filter_map(|arg| match arg {
+            WktValue::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+}
+
+/// Source CRS of bound CRS or horizontal component of compound CRS
+fn horizontal_crs(node: &WktNode) -> &WktNode {
+    if node.is("BOUNDCRS") {
+        match node
+            .children()
+            .find(|child| child.is("SOURCECRS"))
+            .and_then(|source| source.children().next())
+        {
+            Some(source) => horizontal_crs(source),
+            None => node,
+        }
+    } else if node.is("COMPOUNDCRS") || node.is("COMPD_CS") {
+        node.children().next().map(horizontal_crs).unwrap_or(node)
+    } else {
+        node
+    }
+}
+
+struct WktParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl WktParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(c) if c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_alphanumeric() || "_.-+".contains(c) {
+                word.push(c);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        word
+    }
+    /// Quoted text with `""` as escaped quote
+    fn text(&mut self) -> Option<String> {
+        self.chars.next();
+        let mut text = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' if self.chars.peek() == Some(&'"') => {
+                    self.chars.next();
+                    text.push('"');
+                }
+                '"' => return Some(text),
+                c => text.push(c),
+            }
+        }
+    }
+    fn node(&mut self) -> Option<WktNode> {
+        self.skip_whitespace();
+        let keyword = self.word();
+        self.skip_whitespace();
+        if keyword.is_empty() || !matches!(self.chars.next(), Some('[') | Some('(')) {
+            return None;
+        }
+        self.args(keyword)
+    }
+    /// Values of node after opening bracket
+    fn args(&mut self, keyword: String) -> Option<WktNode> {
+        let mut args = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let value = if self.chars.peek() == Some(&'"') {
+                WktValue::Text(self.text()?)
+            } else {
+                let word = self.word();
+                self.skip_whitespace();
+                if matches!(self.chars.peek(), Some('[') | Some('(')) {
+                    self.chars.next();
+                    WktValue::Node(self.args(word)?)
+                } else if word.is_empty() {
+                    return None;
+                } else {
+                    WktValue::Word(word)
+                }
+            };
+            args.push(value);
+            self.skip_whitespace();
+            match self.chars.next()? {
+                ',' => {}
+                ']' | ')' => return Some(WktNode { keyword, args }),
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Read CRS from the `.prj` sidecar file of dataset `path` (e.g. `roads.shp` or `points.csv`).
+///
+/// Returns `None` if there is no sidecar file or its content is not a CRS definition.
+pub fn read_prj_sidecar<P: AsRef<Path>>(path: P) -> Result<Option<Crs>> {
+    let prj = path.as_ref().with_extension("prj");
+    if !prj.is_file() {
+        return Ok(None);
+    }
+    let definition = fs::read_to_string(prj)?;
+    Ok(Crs::from_definition(&definition))
+}
+
+/// EPSG code of authority code definition
+fn epsg_crs(definition: &str) -> Option<Crs> {
+    let upper = definition.to_ascii_uppercase();
+    if let Some(code) = upper.strip_prefix("EPSG:") {
+        return code.parse().ok().map(Crs::Epsg);
+    }
+    let code = upper
+        .strip_prefix("URN:OGC:DEF:CRS:EPSG:")
+        .or_else(|| upper.strip_prefix("HTTP://WWW.OPENGIS.NET/DEF/CRS/EPSG/"))?;
+    // Skip version (`urn:ogc:def:crs:EPSG:9.8:4326`, `.../EPSG/0/4326`)
+    let code = code.rsplit([':', '/']).next()?;
+    code.parse().ok().map(Crs::EpsgUrn)
+}
+
+/// Definition starts with a WKT keyword
+fn is_wkt(definition: &str) -> bool {
+    match definition.find('[') {
+        Some(pos) => {
+            pos > 0
+                && definition[..pos]
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_definitions() {
+        assert_eq!(Crs::from_definition("epsg:4326"), Some(Crs::Epsg(4326)));
+        assert_eq!(
+            Crs::from_definition("http://www.opengis.net/def/crs/EPSG/0/3857"),
+            Some(Crs::EpsgUrn(3857))
+        );
+        let wkt = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563]]]"#;
+        assert_eq!(Crs::from_definition(wkt), Some(Crs::Wkt(wkt.to_string())));
+        let json = r#"{"type": "GeographicCRS", "id": {"authority": "EPSG", "code": 4326}}"#;
+        assert_eq!(Crs::from_definition(json).unwrap().epsg(), Some(4326));
+        assert_eq!(Crs::from_definition("+proj=longlat"), None);
+    }
+
+    #[test]
+    fn parse_wkt() {
+        let wkt1 = r#"PROJCS["CH1903+ / LV95",GEOGCS["CH1903+",AUTHORITY["EPSG","4150"]],
+            AXIS["Easting",EAST],AXIS["Northing",NORTH],AUTHORITY["EPSG","2056"]]"#;
+        let crs = WktCrs::parse(wkt1).unwrap();
+        assert_eq!(crs.name.as_deref(), Some("CH1903+ / LV95"));
+        assert_eq!(
+            crs.authority,
+            Some(("EPSG".to_string(), "2056".to_string()))
+        );
+        assert_eq!(crs.axis_order, Some(AxisOrder::EastNorth));
+        assert_eq!(Crs::Wkt(wkt1.to_string()).epsg(), Some(2056));
+
+        let esri = r#"PROJCS["WGS_1984_UTM_Zone_32N",GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",
+            SPHEROID["WGS_1984",6378137.0,298.257223563]],PRIMEM["Greenwich",0.0],
+            UNIT["Degree",0.0174532925199433]],PROJECTION["Transverse_Mercator"],
+            PARAMETER["Central_Meridian",9.0],UNIT["Meter",1.0]]"#;
+        let crs = WktCrs::parse(esri).unwrap();
+        assert_eq!(crs.authority, None);
+        assert_eq!(crs.axis_order, None);
+        assert_eq!(crs.epsg(), Some(32632));
+
+        let bound = r#"BOUNDCRS[SOURCECRS[PROJCRS["ETRS89 / LAEA Europe",ID["EPSG",3035]]],
+            TARGETCRS[GEOGCRS["WGS 84"]],ABRIDGEDTRANSFORMATION["Transformation"]]"#;
+        assert_eq!(WktCrs::parse(bound).unwrap().epsg(), Some(3035));
+
+        assert_eq!(WktCrs::parse(r#"GEOGCS["unterminated""#), None);
+    }
+
+    #[test]
+    fn axis_order() {
+        assert_eq!(Crs::wgs84().axis_order(), Some(AxisOrder::NorthEast));
+        assert_eq!(Crs::Epsg(2056).axis_order(), Some(AxisOrder::EastNorth));
+        let projjson = r#"{"type": "GeographicCRS", "name": "WGS 84",
+            "coordinate_system": {"subtype": "ellipsoidal", "axis": [
+                {"name": "Geodetic longitude", "direction": "east"},
+                {"name": "Geodetic latitude", "direction": "north"}]}}"#;
+        assert_eq!(
+            Crs::ProjJson(projjson.to_string()).axis_order(),
+            Some(AxisOrder::EastNorth)
+        );
+    }
+
+    #[test]
+    fn coordinate_axis_order() {
+        let crs = |def| Crs::from_definition(def).unwrap().coordinate_axis_order();
+        assert_eq!(crs("EPSG:4326"), AxisOrder::EastNorth);
+        assert_eq!(crs("urn:ogc:def:crs:EPSG::4326"), AxisOrder::NorthEast);
+        assert_eq!(
+            crs("http://www.opengis.net/def/crs/EPSG/0/4326"),
+            AxisOrder::NorthEast
+        );
+        assert_eq!(crs("urn:ogc:def:crs:EPSG::2056"), AxisOrder::EastNorth);
+        let wkt2 =
+            r#"GEOGCRS["WGS 84",CS[ellipsoidal,2],AXIS["latitude",north],AXIS["longitude",east]]"#;
+        assert_eq!(crs(wkt2), AxisOrder::NorthEast);
+        let wkt1 = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563]]]"#;
+        assert_eq!(crs(wkt1), AxisOrder::EastNorth);
+    }
+
+    #[test]
+    fn prj_sidecar() -> Result<()> {
+        let dir = std::env::temp_dir();
+        let wkt = r#"PROJCS["CH1903+_LV95",GEOGCS["GCS_CH1903+",DATUM["D_CH1903+"]]]"#;
+        fs::write(dir.join("geozero-prj-test.prj"), wkt)?;
+        let crs = read_prj_sidecar(dir.join("geozero-prj-test.csv"))?;
+        fs::remove_file(dir.join("geozero-prj-test.prj"))?;
+        assert_eq!(crs, Some(Crs::Wkt(wkt.to_string())));
+        assert_eq!(read_prj_sidecar(dir.join("geozero-no-prj.shp"))?, None);
+        Ok(())
+    }
+}
@@ -1,7 +1,15 @@
+use crate::datetime::string_value;
 use crate::error::{GeozeroError, Result};
-use crate::{ColumnValue, FeatureProcessor, GeomProcessor, GeozeroDatasource, GeozeroGeometry};
+use crate::features::record_features;
+use crate::processor::{Borrowed, ResourceLimiter};
+use crate::{
+    read_prj_sidecar, ColumnDef, ColumnType, Crs, FeatureIter, FeatureProcessor, GeomProcessor,
+    GeozeroDatasource, GeozeroGeometry, ReadOptions,
+};
 
-use std::io::Read;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
 
 pub struct Csv<'a> {
     csv_text: &'a str,
@@ -25,6 +33,18 @@ impl GeozeroDatasource for Csv<'_> {
             &self.geometry_column_name,
         )
     }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        process_csv_features_with_options(
+            self.csv_text.as_bytes(),
+            processor,
+            &self.geometry_column_name,
+            options,
+        )
+    }
 }
 
 impl GeozeroGeometry for Csv<'_> {
@@ -59,6 +79,18 @@ impl GeozeroDatasource for CsvString {
             &self.geometry_column_name,
         )
     }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        process_csv_features_with_options(
+            self.csv_text.as_bytes(),
+            processor,
+            &self.geometry_column_name,
+            options,
+        )
+    }
 }
 
 impl GeozeroGeometry for CsvString {
@@ -74,6 +106,7 @@ impl GeozeroGeometry for CsvString {
 pub struct CsvReader<R: Read> {
     inner: R,
     geometry_column_name: String,
+    crs: Option<Crs>,
 }
 
 impl<R: Read> CsvReader<R> {
@@ -81,13 +114,73 @@ impl<R: Read> CsvReader<R> {
         Self {
             inner,
             geometry_column_name: geometry_column_name.to_string(),
+            crs: None,
         }
     }
+    /// Set CRS of geometries (default: `EPSG:4326`)
+    pub fn with_crs(mut self, crs: Crs) -> Self {
+        self.crs = Some(crs);
+        self
+    }
+}
+
+impl CsvReader<BufReader<File>> {
+    /// Open CSV file with CRS from `.prj` sidecar file, if present.
+    pub fn from_path<P: AsRef<Path>>(geometry_column_name: &str, path: P) -> Result<Self> {
+        let file = BufReader::new(File::open(path.as_ref())?);
+        let mut reader = Self::new(geometry_column_name, file);
+        reader.crs = read_prj_sidecar(path)?;
+        Ok(reader)
+    }
 }
 
 impl<R: Read> GeozeroDatasource for CsvReader<R> {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
-        process_csv_features(&mut self.inner, processor, &self.geometry_column_name)
+        self.process_with_options(processor, &ReadOptions::default())
+    }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        process_csv_dataset(
+            &mut self.inner,
+            processor,
+            &self.geometry_column_name,
+            options,
+            self.crs.as_ref(),
+        )
+    }
+    /// Iterator over the features, reading one record at a time.
+    fn features(&mut self) -> Result<FeatureIter<'_>> {
+        let mut reader = csv::Reader::from_reader(&mut self.inner);
+        let headers = reader.headers()?.clone();
+        let geometry_idx = geometry_index(&headers, &self.geometry_column_name)?;
+        let options = ReadOptions::default();
+        let mut buf = String::new();
+        let features = reader
+            .into_records()
+            .enumerate()
+            .filter_map(move |(idx, record)| {
+                let record = match record {
+                    Ok(record) => record,
+                    Err(e) => return Some(Err(GeozeroError::from(e))),
+                };
+                record_features(|buffer| {
+                    process_csv_record(
+                        &record,
+                        &headers,
+                        geometry_idx,
+                        idx,
+                        buffer,
+                        &options,
+                        &mut buf,
+                    )
+                })
+                .map(|features| features.into_iter().next())
+                .transpose()
+            });
+        Ok(FeatureIter::new(features))
     }
 }
 
@@ -149,63 +242,130 @@ pub fn process_csv_features(
     processor: &mut impl FeatureProcessor,
     geometry_column: &str,
 ) -> Result<()> {
+    process_csv_features_with_options(input, processor, geometry_column, &ReadOptions::default())
+}
+
+pub fn process_csv_features_with_options(
+    input: impl Read,
+    processor: &mut impl FeatureProcessor,
+    geometry_column: &str,
+    options: &ReadOptions,
+) -> Result<()> {
+    process_csv_dataset(input, processor, geometry_column, options, None)
+}
+
+/// Process CSV features with geometries in `crs` (`EPSG:4326` if unknown)
+fn process_csv_dataset(
+    input: impl Read,
+    processor: &mut impl FeatureProcessor,
+    geometry_column: &str,
+    options: &ReadOptions,
+    crs: Option<&Crs>,
+) -> Result<()> {
+    let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+    let processor = &mut processor;
     let mut reader = csv::Reader::from_reader(input);
     let headers = reader.headers()?.clone();
     processor.dataset_begin(None)?;
+    let default_crs = Crs::wgs84();
+    processor.crs(crs.unwrap_or(&default_crs))?;
 
-    let geometry_idx = headers
+    let geometry_idx = geometry_index(&headers, geometry_column)?;
+
+    let columns: Vec<ColumnDef> = headers
         .iter()
-        .position(|f| f == geometry_column)
-        .ok_or(GeozeroError::ColumnNotFound)?;
+        .enumerate()
+        .filter(|(input_idx, header)| *input_idx != geometry_idx && options.selects_column(header))
+        .map(|(_input_idx, header)| ColumnDef::new(header, ColumnType::String))
+        .collect();
+    processor.schema(&columns)?;
+
+    // Reuse record buffer, property values are borrowed from it
+    let mut record = csv::StringRecord::new();
+    let mut feature_idx: usize = 0;
+    // Buffer for date-times completed with a fallback offset
+    let mut buf = String::new();
+    while reader.read_record(&mut record)? {
+        process_csv_record(
+            &record,
+            &headers,
+            geometry_idx,
+            feature_idx,
+            processor,
+            options,
+            &mut buf,
+        )?;
+        feature_idx += 1;
+    }
 
-    for (feature_idx, record) in reader.into_records().enumerate() {
-        let record = record?;
-        processor.feature_begin(feature_idx as u64)?;
+    processor.dataset_end()?;
+    Ok(())
+}
 
-        processor.properties_begin()?;
+/// Position of `geometry_column` in `headers`
+fn geometry_index(headers: &csv::StringRecord, geometry_column: &str) -> Result<usize> {
+    headers
+        .iter()
+        .position(|f| f == geometry_column)
+        .ok_or(GeozeroError::ColumnNotFound)
+}
 
-        let properties_iter = headers
-            .iter()
-            .zip(record.iter())
-            .enumerate()
-            // skip the geometry field -  we process it after all the "properties"
-            .filter(|(input_idx, _)| *input_idx != geometry_idx)
-            .map(|(_input_idx, (header, value))| (header, value));
+/// Process `record` as feature `feature_idx`
+///
+/// `buf` is reused for date-times completed with a fallback offset.
+fn process_csv_record(
+    record: &csv::StringRecord,
+    headers: &csv::StringRecord,
+    geometry_idx: usize,
+    feature_idx: usize,
+    processor: &mut impl FeatureProcessor,
+    options: &ReadOptions,
+    buf: &mut String,
+) -> Result<()> {
+    processor.feature_begin(feature_idx as u64)?;
 
-        for (output_idx, (header, field)) in properties_iter.enumerate() {
-            let value = &ColumnValue::String(field);
-            processor.property(output_idx, header, value)?;
-        }
+    processor.properties_begin()?;
 
-        processor.properties_end()?;
+    let properties_iter = headers
+        .iter()
+        .zip(record.iter())
+        .enumerate()
+        // skip the geometry field -  we process it after all the "properties"
+        .filter(|(input_idx, (header, _))| {
+            *input_idx != geometry_idx && options.selects_column(header)
+        })
+        .map(|(_input_idx, (header, value))| (header, value));
+
+    for (output_idx, (header, field)) in properties_iter.enumerate() {
+        let value = &string_value(field, options, buf);
+        processor.property(output_idx, header, value)?;
+    }
 
-        let geometry_field = record
-            .get(geometry_idx)
-            .ok_or(GeozeroError::ColumnNotFound)?;
+    processor.properties_end()?;
 
-        // Do all formats allow empty geometries?
-        if !geometry_field.is_empty() {
-            processor.geometry_begin()?;
-            crate::wkt::wkt_reader::read_wkt(&mut geometry_field.as_bytes(), processor).map_err(
-                |e| {
-                    // +2 to start at line 1 and to account for the header row
-                    let line = feature_idx + 2;
-                    log::warn!(
-                        "line {}: invalid WKT: '{}', record: {:?}",
-                        line,
-                        geometry_field,
-                        &record
-                    );
-                    e
-                },
-            )?;
-            processor.geometry_end()?;
-        }
+    let geometry_field = record
+        .get(geometry_idx)
+        .ok_or(GeozeroError::ColumnNotFound)?;
 
-        processor.feature_end(feature_idx as u64)?;
+    // Do all formats allow empty geometries?
+    if !geometry_field.is_empty() {
+        processor.geometry_begin()?;
+        crate::wkt::wkt_reader::read_wkt(&mut geometry_field.as_bytes(), processor).inspect_err(
+            |_| {
+                // +2 to start at line 1 and to account for the header row
+                let line = feature_idx + 2;
+                log::warn!(
+                    "line {}: invalid WKT: '{}', record: {:?}",
+                    line,
+                    geometry_field,
+                    &record
+                );
+            },
+        )?;
+        processor.geometry_end()?;
     }
 
-    processor.dataset_end()?;
+    processor.feature_end(feature_idx as u64)?;
     Ok(())
 }
 
@@ -372,6 +532,94 @@ mod tests {
         assert_eq!(expected_geojson, actual_geojson,)
     }
 
+    #[test]
+    fn schema() {
+        use crate::{GeomProcessor, PropertyProcessor};
+
+        struct SchemaReader(Vec<ColumnDef>);
+        impl FeatureProcessor for SchemaReader {
+            fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+                self.0 = columns.to_vec();
+                Ok(())
+            }
+        }
+        impl GeomProcessor for SchemaReader {}
+        impl PropertyProcessor for SchemaReader {}
+
+        let mut csv = Csv::new(
+            "report location",
+            r#"address,type,report location
+904 7th Av,Car Fire,POINT (-122.329051 47.6069)"#,
+        );
+        let mut reader = SchemaReader(Vec::new());
+        csv.process(&mut reader).unwrap();
+        assert_eq!(
+            reader.0,
+            vec![
+                ColumnDef::new("address", ColumnType::String),
+                ColumnDef::new("type", ColumnType::String)
+            ]
+        );
+    }
+
+    #[test]
+    fn select_columns() {
+        use crate::geojson::GeoJsonWriter;
+
+        let mut csv = Csv::new(
+            "report location",
+            r#"address,type,report location,incident number
+904 7th Av,Car Fire,POINT (-122.329051 47.6069),F190051945"#,
+        );
+        let options = ReadOptions {
+            columns: Some(vec!["incident number".to_string(), "type".to_string()]),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        csv.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            json["features"][0]["properties"],
+            serde_json::json!({"incident number": "F190051945", "type": "Car Fire"})
+        );
+    }
+
+    #[test]
+    fn timezone_fallback() {
+        use crate::geojson::GeoJsonWriter;
+        use crate::TimezoneFallback;
+
+        let mut csv = Csv::new(
+            "location",
+            r#"reported,closed,location
+2019-05-22T12:55:00,2019-05-22T14:10:00+02:00,POINT (-122.329051 47.6069)"#,
+        );
+        let mut options = ReadOptions {
+            parse_datetimes: true,
+            timezone_fallback: TimezoneFallback::Offset(-420),
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        csv.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            json["features"][0]["properties"],
+            serde_json::json!({"reported": "2019-05-22T12:55:00-07:00", "closed": "2019-05-22T14:10:00+02:00"})
+        );
+
+        options.timezone_fallback = TimezoneFallback::Keep;
+        let mut out: Vec<u8> = Vec::new();
+        csv.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(
+            json["features"][0]["properties"]["reported"],
+            "2019-05-22T12:55:00"
+        );
+    }
+
     #[test]
     fn geom_processor() {
         use crate::geojson::conversion::ToJson;
@@ -420,4 +668,20 @@ mod tests {
         // missing geometry. Some formats, like FGB, will tolerate this null geometry.
         serde_json::from_str::<serde_json::Value>(&json).unwrap();
     }
+
+    #[test]
+    fn features() -> Result<()> {
+        use crate::{FeatureProperties, ToWkt};
+
+        let csv = r#"name,geometry
+Bern,POINT(7.44 46.95)
+Zurich,POINT(8.54 47.37)"#;
+        let mut reader = CsvReader::new("geometry", csv.as_bytes());
+        let features = reader.features()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1].idx(), 1);
+        assert_eq!(features[1].property::<String>("name").unwrap(), "Zurich");
+        assert_eq!(features[1].to_wkt()?, "POINT(8.54 47.37)");
+        Ok(())
+    }
 }
@@ -1,15 +1,60 @@
-use crate::error::Result;
-use crate::{ColumnValue, CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use crate::error::{GeozeroError, Result};
+use crate::{
+    ColumnDef, ColumnValue, CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor,
+    WriterOptions,
+};
 use buffering_wkt_writer::BufferingWktWriter;
 
 use std::io::Write;
 
+/// CSV writer.
+///
+/// All property values are written as text, `Binary` values are hex encoded.
 pub struct CsvWriter<'w, W: Write> {
     csv: csv::Writer<&'w mut W>,
     headers: Vec<String>,
+    has_schema: bool,
     has_written_first_record: bool,
     current_row_props: Vec<String>,
     wkt_writer: BufferingWktWriter,
+    /// Check features without writing
+    dry_run: bool,
+}
+
+/// Builder for [CsvWriter].
+#[derive(Clone, Default, Debug)]
+pub struct CsvWriterBuilder {
+    options: WriterOptions,
+    precision: Option<usize>,
+}
+
+impl CsvWriterBuilder {
+    /// Set shared writer options
+    pub fn options(mut self, options: WriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Maximal number of decimals of geometry coordinates (default: shortest exact representation)
+    pub fn precision(mut self, decimals: usize) -> Self {
+        self.precision = Some(decimals);
+        self
+    }
+    /// Create writer
+    pub fn build<W: Write>(self, out: &mut W) -> CsvWriter<'_, W> {
+        let mut writer = CsvWriter::new(out);
+        writer.wkt_writer.dims = self.options.dims;
+        writer.wkt_writer.precision = self.precision;
+        writer.dry_run = self.options.dry_run;
+        writer
+    }
+}
+
+// Not generic over the output type, which is set by `build`
+impl CsvWriter<'_, Vec<u8>> {
+    /// Writer builder
+    pub fn builder() -> CsvWriterBuilder {
+        CsvWriterBuilder::default()
+    }
 }
 
 impl<'w, W: Write> CsvWriter<'w, W> {
@@ -18,9 +63,11 @@ impl<'w, W: Write> CsvWriter<'w, W> {
         Self {
             csv: csv::Writer::from_writer(out),
             headers: vec!["geometry".to_string()],
+            has_schema: false,
             has_written_first_record: false,
             current_row_props: vec![],
             wkt_writer,
+            dry_run: false,
         }
     }
 
@@ -47,8 +94,18 @@ impl<W: Write> FeatureProcessor for CsvWriter<'_, W> {
         debug_assert_eq!(self.headers, &["geometry"]);
         Ok(())
     }
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        if !self.has_written_first_record {
+            self.headers.truncate(1);
+            self.headers
+                .extend(columns.iter().map(|col| col.name.clone()));
+            self.has_schema = true;
+        }
+        Ok(())
+    }
     fn dataset_end(&mut self) -> Result<()> {
         self.headers = vec!["geometry".to_string()];
+        self.has_schema = false;
         Ok(())
     }
     fn feature_begin(&mut self, _idx: u64) -> Result<()> {
@@ -57,19 +114,28 @@ impl<W: Write> FeatureProcessor for CsvWriter<'_, W> {
     }
 
     fn feature_end(&mut self, _idx: u64) -> Result<()> {
-        if !self.has_written_first_record {
-            self.has_written_first_record = true;
-            self.csv.write_record(self.headers.clone())?;
-        }
-
-        let geom = self.wkt_writer.bytes();
-        self.csv.write_field(geom)?;
+        let columns = self.headers.len() - 1;
+        let found = self.current_row_props.len();
+        if found != columns {
+            self.wkt_writer.clear();
+            self.current_row_props.clear();
+            return Err(GeozeroError::Property(format!(
+                "CSV features must all have the same number of columns, expected {} found {}",
+                columns, found
+            )));
+        }
+        if !self.dry_run {
+            if !self.has_written_first_record {
+                self.csv.write_record(self.headers.clone())?;
+            }
+            self.csv.write_field(self.wkt_writer.bytes())?;
+            for field in &self.current_row_props {
+                self.csv.write_field(field)?;
+            }
+            self.csv.write_record(None::<&[u8]>)?;
+        }
+        self.has_written_first_record = true;
         self.wkt_writer.clear();
-
-        for field in &self.current_row_props {
-            self.csv.write_field(field)?;
-        }
-        self.csv.write_record(None::<&[u8]>)?;
         self.current_row_props.clear();
 
         Ok(())
@@ -93,18 +159,26 @@ impl<W: Write> FeatureProcessor for CsvWriter<'_, W> {
 impl<W: Write> PropertyProcessor for CsvWriter<'_, W> {
     fn property(&mut self, i: usize, colname: &str, colval: &ColumnValue) -> Result<bool> {
         // TODO: support mis-ordered properties?
-        if self.has_written_first_record {
-            assert_eq!(
-                colname,
-                &self.headers[i + 1],
-                "CSV features must all have the same column names"
-            );
+        if self.has_written_first_record || self.has_schema {
+            match self.headers.get(i + 1) {
+                Some(header) if header == colname => {}
+                header => {
+                    return Err(GeozeroError::Property(format!(
+                    "CSV features must all have the same column names, expected `{}` found `{}`",
+                    header.map(String::as_str).unwrap_or_default(),
+                    colname
+                )))
+                }
+            }
         } else {
             self.headers.push(colname.to_string());
         }
 
-        // TODO: support non-string colval
-        self.current_row_props.push(colval.to_string());
+        let field = match colval {
+            ColumnValue::Binary(v) => v.iter().map(|b| format!("{:02x}", b)).collect(),
+            _ => colval.to_string(),
+        };
+        self.current_row_props.push(field);
         Ok(false)
     }
 }
@@ -247,12 +321,15 @@ impl<W: Write> GeomProcessor for CsvWriter<'_, W> {
 // incorporated in the output of the CSV writer. Is there a better way?
 mod buffering_wkt_writer {
     use crate::error::Result;
+    use crate::wkt::wkt_writer::WktState;
     use crate::{wkt::WktWriter, CoordDimensions, GeomProcessor};
 
     #[derive(Default)]
     pub(crate) struct BufferingWktWriter {
         buffer: Vec<u8>,
         pub(crate) dims: CoordDimensions,
+        pub(crate) precision: Option<usize>,
+        state: WktState,
     }
 
     impl BufferingWktWriter {
@@ -261,15 +338,26 @@ mod buffering_wkt_writer {
         }
 
         pub(crate) fn clear(&mut self) {
-            self.buffer.clear()
+            self.buffer.clear();
+            self.state = WktState::default();
         }
 
         pub(crate) fn bytes(&self) -> &[u8] {
             &self.buffer
         }
 
-        fn wkt_writer(&mut self) -> WktWriter<'_, Vec<u8>> {
-            WktWriter::new(&mut self.buffer)
+        /// Write to buffer, keeping the writer state between calls
+        fn write<F>(&mut self, f: F) -> Result<()>
+        where
+            F: FnOnce(&mut WktWriter<'_, Vec<u8>>) -> Result<()>,
+        {
+            let mut writer = WktWriter::new(&mut self.buffer);
+            writer.dims = self.dims;
+            writer.precision = self.precision;
+            writer.state = self.state;
+            let result = f(&mut writer);
+            self.state = writer.state;
+            result
         }
     }
 
@@ -278,7 +366,7 @@ mod buffering_wkt_writer {
             self.dims
         }
         fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
-            self.wkt_writer().xy(x, y, idx)
+            self.write(|w| w.xy(x, y, idx))
         }
         fn coordinate(
             &mut self,
@@ -290,100 +378,100 @@ mod buffering_wkt_writer {
             tm: Option<u64>,
             idx: usize,
         ) -> Result<()> {
-            self.wkt_writer().coordinate(x, y, z, m, t, tm, idx)
+            self.write(|w| w.coordinate(x, y, z, m, t, tm, idx))
         }
         fn point_begin(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().point_begin(idx)
+            self.write(|w| w.point_begin(idx))
         }
         fn point_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().point_end(idx)
+            self.write(|w| w.point_end(idx))
         }
         fn empty_point(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().empty_point(idx)
+            self.write(|w| w.empty_point(idx))
         }
         fn multipoint_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().multipoint_begin(size, idx)
+            self.write(|w| w.multipoint_begin(size, idx))
         }
         fn multipoint_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().multipoint_end(idx)
+            self.write(|w| w.multipoint_end(idx))
         }
         fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().linestring_begin(tagged, size, idx)
+            self.write(|w| w.linestring_begin(tagged, size, idx))
         }
         fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
-            self.wkt_writer().linestring_end(tagged, idx)
+            self.write(|w| w.linestring_end(tagged, idx))
         }
         fn multilinestring_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().multilinestring_begin(size, idx)
+            self.write(|w| w.multilinestring_begin(size, idx))
         }
         fn multilinestring_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().multilinestring_end(idx)
+            self.write(|w| w.multilinestring_end(idx))
         }
         fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().polygon_begin(tagged, size, idx)
+            self.write(|w| w.polygon_begin(tagged, size, idx))
         }
         fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
-            self.wkt_writer().polygon_end(tagged, idx)
+            self.write(|w| w.polygon_end(tagged, idx))
         }
         fn multipolygon_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().multipolygon_begin(size, idx)
+            self.write(|w| w.multipolygon_begin(size, idx))
         }
         fn multipolygon_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().multipolygon_end(idx)
+            self.write(|w| w.multipolygon_end(idx))
         }
         fn geometrycollection_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().geometrycollection_begin(size, idx)
+            self.write(|w| w.geometrycollection_begin(size, idx))
         }
         fn geometrycollection_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().geometrycollection_end(idx)
+            self.write(|w| w.geometrycollection_end(idx))
         }
         fn circularstring_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().circularstring_begin(size, idx)
+            self.write(|w| w.circularstring_begin(size, idx))
         }
         fn circularstring_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().circularstring_end(idx)
+            self.write(|w| w.circularstring_end(idx))
         }
         fn compoundcurve_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().compoundcurve_begin(size, idx)
+            self.write(|w| w.compoundcurve_begin(size, idx))
         }
         fn compoundcurve_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().compoundcurve_end(idx)
+            self.write(|w| w.compoundcurve_end(idx))
         }
         fn curvepolygon_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().curvepolygon_begin(size, idx)
+            self.write(|w| w.curvepolygon_begin(size, idx))
         }
         fn curvepolygon_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().curvepolygon_end(idx)
+            self.write(|w| w.curvepolygon_end(idx))
         }
         fn multicurve_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().multicurve_begin(size, idx)
+            self.write(|w| w.multicurve_begin(size, idx))
         }
         fn multicurve_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().multicurve_end(idx)
+            self.write(|w| w.multicurve_end(idx))
         }
         fn multisurface_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().multisurface_begin(size, idx)
+            self.write(|w| w.multisurface_begin(size, idx))
         }
         fn multisurface_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().multisurface_end(idx)
+            self.write(|w| w.multisurface_end(idx))
         }
         fn triangle_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().triangle_begin(tagged, size, idx)
+            self.write(|w| w.triangle_begin(tagged, size, idx))
         }
         fn triangle_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
-            self.wkt_writer().triangle_end(tagged, idx)
+            self.write(|w| w.triangle_end(tagged, idx))
         }
         fn polyhedralsurface_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().polyhedralsurface_begin(size, idx)
+            self.write(|w| w.polyhedralsurface_begin(size, idx))
         }
         fn polyhedralsurface_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().polyhedralsurface_end(idx)
+            self.write(|w| w.polyhedralsurface_end(idx))
         }
         fn tin_begin(&mut self, size: usize, idx: usize) -> Result<()> {
-            self.wkt_writer().tin_begin(size, idx)
+            self.write(|w| w.tin_begin(size, idx))
         }
         fn tin_end(&mut self, idx: usize) -> Result<()> {
-            self.wkt_writer().tin_end(idx)
+            self.write(|w| w.tin_end(idx))
         }
     }
 }
@@ -440,6 +528,56 @@ POINT(-122.266529 47.515984),9610 53rd Av S,05/22/2019 12:55:00 PM,F190051946,Ai
         assert_eq!(expected_output, actual_output);
     }
 
+    #[test]
+    fn dry_run() {
+        use super::CsvWriter;
+        use crate::geojson::GeoJson;
+        use crate::processor::ContinueOnError;
+        use crate::{GeozeroDatasource, WriterOptions};
+
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+            {"type": "Feature", "properties": {"city": "Zürich"}, "geometry": null},
+            {"type": "Feature", "properties": {"name": "Basel"}, "geometry": null}]}"#,
+        );
+        let mut out: Vec<u8> = Vec::new();
+        let writer = CsvWriter::builder()
+            .options(WriterOptions::default().with_dry_run(true))
+            .build(&mut out);
+        let mut errors = Vec::new();
+        geojson
+            .process(&mut ContinueOnError::new(writer, |e| {
+                errors.push(e.to_string())
+            }))
+            .unwrap();
+        assert!(out.is_empty());
+        assert_eq!(
+            errors,
+            vec!["feature 1: processing feature property: `CSV features must all have the same column names, expected `name` found `city``"]
+        );
+    }
+
+    #[test]
+    fn missing_columns() {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern", "pop": 133115}, "geometry": null},
+            {"type": "Feature", "properties": {"name": "Basel"}, "geometry": null}]}"#,
+        );
+        let mut out: Vec<u8> = Vec::new();
+        let err = geojson
+            .process(&mut super::CsvWriter::new(&mut out))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "processing feature property: `CSV features must all have the same number of columns, expected 2 found 1`"
+        );
+    }
+
     #[test]
     fn geojson_geom_collection_to_csv() {
         let input_geojson = json!({
@@ -0,0 +1,221 @@
+//! Date and time helpers.
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+use crate::{ColumnValue, ReadOptions, TimezoneFallback};
+use std::cmp::Ordering;
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+use std::fmt::Write;
+
+fn is_digits(s: &[u8]) -> bool {
+    s.iter().all(u8::is_ascii_digit)
+}
+
+fn is_full_date(b: &[u8]) -> bool {
+    b.len() == 10
+        && is_digits(&b[0..4])
+        && b[4] == b'-'
+        && is_digits(&b[5..7])
+        && b[7] == b'-'
+        && is_digits(&b[8..10])
+}
+
+fn digits(b: &[u8]) -> i64 {
+    b.iter().fold(0, |n, c| n * 10 + (c - b'0') as i64)
+}
+
+/// Check for a full date `YYYY-MM-DD`.
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+pub(crate) fn is_iso_date(s: &str) -> bool {
+    is_full_date(s.as_bytes())
+}
+
+/// Length of a date-time without offset like `2022-07-21T12:55:00.5`.
+fn local_datetime_len(b: &[u8]) -> Option<usize> {
+    if b.len() < 19 || !is_full_date(&b[0..10]) || !matches!(b[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    // partial-time
+    let partial_time = is_digits(&b[11..13])
+        && b[13] == b':'
+        && is_digits(&b[14..16])
+        && b[16] == b':'
+        && is_digits(&b[17..19]);
+    if !partial_time {
+        return None;
+    }
+    if b.get(19) == Some(&b'.') {
+        let n = b[20..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if n == 0 {
+            return None;
+        }
+        return Some(20 + n);
+    }
+    Some(19)
+}
+
+/// Offset in minutes of a RFC 3339 time-offset like `Z` or `+02:00`.
+fn time_offset(b: &[u8]) -> Option<i64> {
+    match b {
+        [b'Z'] | [b'z'] => Some(0),
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] if is_digits(&[*h1, *h2, *m1, *m2]) => {
+            let minutes = digits(&[*h1, *h2]) * 60 + digits(&[*m1, *m2]);
+            Some(if *sign == b'-' { -minutes } else { minutes })
+        }
+        _ => None,
+    }
+}
+
+/// Check for a RFC 3339 date-time like `2022-07-21T12:55:00.5+02:00`.
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+pub(crate) fn is_rfc3339_datetime(s: &str) -> bool {
+    let b = s.as_bytes();
+    match local_datetime_len(b) {
+        Some(len) => time_offset(&b[len..]).is_some(),
+        None => false,
+    }
+}
+
+/// Seconds since the epoch in UTC and fraction digits of a RFC 3339 date-time.
+fn utc_instant(s: &str) -> Option<(i64, &str)> {
+    let b = s.as_bytes();
+    let len = local_datetime_len(b)?;
+    let offset = time_offset(&b[len..])?;
+    // Days since 1970-01-01 of the proleptic Gregorian calendar
+    let (y, m, d) = (digits(&b[0..4]), digits(&b[5..7]), digits(&b[8..10]));
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    let secs = digits(&b[11..13]) * 3600 + digits(&b[14..16]) * 60 + digits(&b[17..19]);
+    let fraction = s.get(20..len).unwrap_or("").trim_end_matches('0');
+    Some((days * 86_400 + secs - offset * 60, fraction))
+}
+
+/// Compare RFC 3339 date-times by their instant, independent of their offsets.
+///
+/// Falls back to comparing the strings for invalid date-times.
+pub(crate) fn compare_datetimes(a: &str, b: &str) -> Option<Ordering> {
+    match (utc_instant(a), utc_instant(b)) {
+        (Some(a), Some(b)) => a.partial_cmp(&b),
+        _ => a.partial_cmp(b),
+    }
+}
+
+/// String property value, recognizing RFC 3339 date-times and dates if requested.
+///
+/// Date-times without offset are completed with the offset of `options.timezone_fallback`
+/// into `buf`.
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+pub(crate) fn string_value<'a>(
+    s: &'a str,
+    options: &ReadOptions,
+    buf: &'a mut String,
+) -> ColumnValue<'a> {
+    if options.parse_datetimes {
+        if is_rfc3339_datetime(s) {
+            return ColumnValue::DateTime(s);
+        } else if is_iso_date(s) {
+            return ColumnValue::Date(s);
+        } else if local_datetime_len(s.as_bytes()) == Some(s.len()) {
+            buf.clear();
+            buf.push_str(s);
+            match options.timezone_fallback {
+                TimezoneFallback::Keep => return ColumnValue::String(s),
+                TimezoneFallback::Utc => buf.push('Z'),
+                TimezoneFallback::Offset(minutes) => {
+                    let sign = if minutes < 0 { '-' } else { '+' };
+                    let minutes = minutes.unsigned_abs();
+                    let _ = write!(buf, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60);
+                }
+            }
+            return ColumnValue::DateTime(buf);
+        }
+    }
+    ColumnValue::String(s)
+}
+
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+#[test]
+fn datetime_detection() {
+    let options = ReadOptions {
+        parse_datetimes: true,
+        ..Default::default()
+    };
+    let mut buf = String::new();
+    assert!(is_iso_date("2022-07-21"));
+    assert!(!is_iso_date("2022-7-21"));
+    assert!(is_rfc3339_datetime("2022-07-21T12:55:00Z"));
+    assert!(is_rfc3339_datetime("2022-07-21 12:55:00.123+02:00"));
+    assert!(!is_rfc3339_datetime("2022-07-21T12:55:00"));
+    assert!(!is_rfc3339_datetime("2022-07-21T12:55:00.+02:00"));
+    assert!(!is_rfc3339_datetime("05/22/2019 12:55:00 PM"));
+    assert_eq!(
+        string_value("2022-07-21", &options, &mut buf),
+        ColumnValue::Date("2022-07-21")
+    );
+    assert_eq!(
+        string_value("2022-07-21", &ReadOptions::default(), &mut buf),
+        ColumnValue::String("2022-07-21")
+    );
+}
+
+#[cfg(any(feature = "with-csv", feature = "with-geojson"))]
+#[test]
+fn timezone_fallback() {
+    let mut options = ReadOptions {
+        parse_datetimes: true,
+        ..Default::default()
+    };
+    let mut buf = String::new();
+    assert_eq!(
+        string_value("2022-07-21T12:55:00-05:30", &options, &mut buf),
+        ColumnValue::DateTime("2022-07-21T12:55:00-05:30")
+    );
+    assert_eq!(
+        string_value("2022-07-21T12:55:00", &options, &mut buf),
+        ColumnValue::String("2022-07-21T12:55:00")
+    );
+    options.timezone_fallback = TimezoneFallback::Utc;
+    assert_eq!(
+        string_value("2022-07-21T12:55:00.5", &options, &mut buf),
+        ColumnValue::DateTime("2022-07-21T12:55:00.5Z")
+    );
+    options.timezone_fallback = TimezoneFallback::Offset(-330);
+    assert_eq!(
+        string_value("2022-07-21 12:55:00", &options, &mut buf),
+        ColumnValue::DateTime("2022-07-21 12:55:00-05:30")
+    );
+    assert_eq!(
+        string_value("2022-07-21T12:55:00-01:00", &options, &mut buf),
+        ColumnValue::DateTime("2022-07-21T12:55:00-01:00")
+    );
+}
+
+#[test]
+fn datetime_ordering() {
+    assert_eq!(
+        compare_datetimes("2022-07-21T12:55:00+02:00", "2022-07-21T10:55:00Z"),
+        Some(Ordering::Equal)
+    );
+    assert_eq!(
+        compare_datetimes("2022-07-21T12:55:00+02:00", "2022-07-21T11:00:00Z"),
+        Some(Ordering::Less)
+    );
+    assert_eq!(
+        compare_datetimes("2022-07-21T23:30:00-01:00", "2022-07-22T00:10:00Z"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        compare_datetimes("2022-07-21T12:55:00.5Z", "2022-07-21T12:55:00.45Z"),
+        Some(Ordering::Greater)
+    );
+    assert_eq!(
+        compare_datetimes("2022-07-21T12:55:00.50Z", "2022-07-21T12:55:00.5Z"),
+        Some(Ordering::Equal)
+    );
+    assert_eq!(
+        compare_datetimes("1969-12-31T23:59:59Z", "1970-01-01T00:00:00Z"),
+        Some(Ordering::Less)
+    );
+}
@@ -0,0 +1,321 @@
+use crate::error::{GeozeroError, Result};
+use crate::{FeatureProcessor, GeozeroDatasource, ReadOptions};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Input file format.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputFormat {
+    FlatGeobuf,
+    /// GeoPackage or other SQLite database
+    GeoPackage,
+    /// Zip archive, e.g. a zipped shapefile
+    Zip,
+    OsmPbf,
+    Shapefile,
+    GeoJson,
+    Gpx,
+    Csv,
+    Wkt,
+}
+
+impl InputFormat {
+    /// Number of bytes inspected by [from_magic](InputFormat::from_magic)
+    pub const MAGIC_LEN: usize = 16;
+
+    /// Detect format of file at `path` from its magic bytes, falling back to its extension.
+    pub fn detect<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let mut header = Vec::with_capacity(Self::MAGIC_LEN);
+        File::open(path)?
+            .take(Self::MAGIC_LEN as u64)
+            .read_to_end(&mut header)?;
+        Ok(Self::from_magic(&header).or_else(|| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(Self::from_extension)
+        }))
+    }
+
+    /// Detect format from the first bytes of a file
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(b"fgb\x03") {
+            Some(InputFormat::FlatGeobuf)
+        } else if header.starts_with(b"SQLite format 3\0") {
+            Some(InputFormat::GeoPackage)
+        } else if header.starts_with(b"PK\x03\x04") {
+            Some(InputFormat::Zip)
+        } else if header.starts_with(&9994u32.to_be_bytes()) {
+            Some(InputFormat::Shapefile)
+        } else if header.len() >= 4 && header[4..].starts_with(b"\x0a\x09OSMHeader") {
+            // Size of first BlobHeader followed by its type field
+            Some(InputFormat::OsmPbf)
+        } else {
+            let text = header.strip_prefix(b"\xef\xbb\xbf").unwrap_or(header);
+            match text.iter().find(|b| !b.is_ascii_whitespace()) {
+                Some(b'{') => Some(InputFormat::GeoJson),
+                _ => None,
+            }
+        }
+    }
+
+    /// Detect format from a file extension
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "fgb" => Some(InputFormat::FlatGeobuf),
+            "gpkg" | "sqlite" => Some(InputFormat::GeoPackage),
+            "zip" => Some(InputFormat::Zip),
+            "pbf" => Some(InputFormat::OsmPbf),
+            "shp" => Some(InputFormat::Shapefile),
+            "json" | "geojson" => Some(InputFormat::GeoJson),
+            "gpx" => Some(InputFormat::Gpx),
+            "csv" => Some(InputFormat::Csv),
+            "wkt" => Some(InputFormat::Wkt),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InputFormat::FlatGeobuf => "FlatGeobuf",
+            InputFormat::GeoPackage => "GeoPackage",
+            InputFormat::Zip => "Zip",
+            InputFormat::OsmPbf => "OSM PBF",
+            InputFormat::Shapefile => "Shapefile",
+            InputFormat::GeoJson => "GeoJSON",
+            InputFormat::Gpx => "GPX",
+            InputFormat::Csv => "CSV",
+            InputFormat::Wkt => "WKT",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Datasource reading a file in a detected format.
+///
+/// Formats without a reader in this crate, or with a disabled feature, are detected,
+/// but fail with an error when processed. Readers of other crates can be selected by
+/// matching on [format](DetectedSource::format).
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::{DetectedSource, GeozeroDatasource};
+/// use geozero::wkt::WktWriter;
+///
+/// let mut source = DetectedSource::open("places.json")?;
+/// let mut out: Vec<u8> = Vec::new();
+/// source.process(&mut WktWriter::new(&mut out))?;
+/// ```
+pub struct DetectedSource {
+    path: PathBuf,
+    format: InputFormat,
+    csv_geometry_column: String,
+}
+
+impl DetectedSource {
+    /// Open file at `path`, failing if the format is not detected
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = InputFormat::detect(path)?.ok_or_else(|| {
+            GeozeroError::Dataset(format!("unknown format of `{}`", path.display()))
+        })?;
+        Ok(Self::with_format(path, format))
+    }
+    /// File at `path` in `format`
+    pub fn with_format<P: AsRef<Path>>(path: P, format: InputFormat) -> Self {
+        DetectedSource {
+            path: path.as_ref().to_path_buf(),
+            format,
+            csv_geometry_column: "WKT".to_string(),
+        }
+    }
+    /// Set name of CSV column holding a WKT geometry (default: `WKT`)
+    pub fn csv_geometry_column(mut self, name: &str) -> Self {
+        self.csv_geometry_column = name.to_string();
+        self
+    }
+    /// Detected format
+    pub fn format(&self) -> InputFormat {
+        self.format
+    }
+    /// File path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl GeozeroDatasource for DetectedSource {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.process_with_options(processor, &ReadOptions::default())
+    }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        match self.format {
+            // Reads CRS from `.prj` sidecar file
+            #[cfg(feature = "with-csv")]
+            InputFormat::Csv => {
+                crate::csv::CsvReader::from_path(&self.csv_geometry_column, &self.path)?
+                    .process_with_options(processor, options)
+            }
+            _ => DetectedReader::new(BufReader::new(File::open(&self.path)?), self.format)
+                .csv_geometry_column(&self.csv_geometry_column)
+                .process_with_options(processor, options),
+        }
+    }
+}
+
+/// Datasource reading a stream in a given format.
+///
+/// Works on non-seekable streams like stdin. Formats requiring random access,
+/// like FlatGeobuf or GeoPackage, fail with an error when processed.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::{DetectedReader, GeozeroDatasource, InputFormat};
+/// use geozero::wkt::WktWriter;
+///
+/// let mut source = DetectedReader::new(std::io::stdin().lock(), InputFormat::GeoJson);
+/// let mut out: Vec<u8> = Vec::new();
+/// source.process(&mut WktWriter::new(&mut out))?;
+/// ```
+pub struct DetectedReader<R: Read> {
+    reader: R,
+    format: InputFormat,
+    csv_geometry_column: String,
+}
+
+impl<R: Read> DetectedReader<R> {
+    /// Stream `reader` in `format`
+    pub fn new(reader: R, format: InputFormat) -> Self {
+        DetectedReader {
+            reader,
+            format,
+            csv_geometry_column: "WKT".to_string(),
+        }
+    }
+    /// Set name of CSV column holding a WKT geometry (default: `WKT`)
+    pub fn csv_geometry_column(mut self, name: &str) -> Self {
+        self.csv_geometry_column = name.to_string();
+        self
+    }
+    /// Stream format
+    pub fn format(&self) -> InputFormat {
+        self.format
+    }
+}
+
+impl<R: Read> GeozeroDatasource for DetectedReader<R> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.process_with_options(processor, &ReadOptions::default())
+    }
+    // Unused without reader features
+    #[allow(unused_variables)]
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        match self.format {
+            #[cfg(feature = "with-csv")]
+            InputFormat::Csv => {
+                crate::csv::CsvReader::new(&self.csv_geometry_column, &mut self.reader)
+                    .process_with_options(processor, options)
+            }
+            #[cfg(feature = "with-geojson")]
+            InputFormat::GeoJson => crate::geojson::GeoJsonReader(&mut self.reader)
+                .process_with_options(processor, options),
+            #[cfg(feature = "with-gpx")]
+            InputFormat::Gpx => {
+                crate::gpx::GpxReader(&mut self.reader).process_with_options(processor, options)
+            }
+            #[cfg(feature = "with-wkt")]
+            InputFormat::Wkt => {
+                crate::wkt::WktReader(&mut self.reader).process_with_options(processor, options)
+            }
+            format => Err(GeozeroError::Dataset(format!(
+                "{} input not supported",
+                format
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn magic_bytes() {
+        assert_eq!(
+            InputFormat::from_magic(b"fgb\x03fgb\x00"),
+            Some(InputFormat::FlatGeobuf)
+        );
+        assert_eq!(
+            InputFormat::from_magic(b"SQLite format 3\0"),
+            Some(InputFormat::GeoPackage)
+        );
+        assert_eq!(
+            InputFormat::from_magic(b"PK\x03\x04"),
+            Some(InputFormat::Zip)
+        );
+        assert_eq!(
+            InputFormat::from_magic(b"\x00\x00\x27\x0a\x00\x00"),
+            Some(InputFormat::Shapefile)
+        );
+        assert_eq!(
+            InputFormat::from_magic(b"\x00\x00\x00\x0d\x0a\x09OSMHeader"),
+            Some(InputFormat::OsmPbf)
+        );
+        assert_eq!(
+            InputFormat::from_magic(b"\xef\xbb\xbf\n  {\"type\""),
+            Some(InputFormat::GeoJson)
+        );
+        assert_eq!(InputFormat::from_magic(b"POINT(1 1)"), None);
+        assert_eq!(InputFormat::from_extension("WKT"), Some(InputFormat::Wkt));
+    }
+
+    #[test]
+    #[cfg(all(feature = "with-geojson", feature = "with-wkt"))]
+    fn detect_file() -> Result<()> {
+        use crate::wkt::WktWriter;
+
+        let mut source = DetectedSource::open("tests/data/places.json")?;
+        assert_eq!(source.format(), InputFormat::GeoJson);
+        let mut out: Vec<u8> = Vec::new();
+        source.process(&mut WktWriter::new(&mut out))?;
+        assert!(out.starts_with(b"POINT("));
+
+        let mut source = DetectedSource::open("tests/data/countries.fgb")?;
+        assert_eq!(source.format(), InputFormat::FlatGeobuf);
+        assert!(source.process(&mut WktWriter::new(&mut out)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "with-geojson", feature = "with-wkt"))]
+    fn detect_stream() -> Result<()> {
+        use crate::wkt::WktWriter;
+
+        // Slices are not seekable
+        let geojson: &[u8] = br#"{"type": "Point", "coordinates": [1, 2]}"#;
+        let mut out: Vec<u8> = Vec::new();
+        DetectedReader::new(geojson, InputFormat::GeoJson)
+            .process(&mut WktWriter::new(&mut out))?;
+        assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(1 2)");
+
+        let fgb: &[u8] = b"fgb\x03fgb\x00";
+        assert!(DetectedReader::new(fgb, InputFormat::FlatGeobuf)
+            .process(&mut WktWriter::new(&mut out))
+            .is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 //! Error and Result types.
 use thiserror::Error;
 
+/// Error type of all geozero functions.
+///
+/// Errors of underlying libraries are either converted into a message or kept as
+/// error [source](std::error::Error::source).
 #[derive(Error, Debug)]
 pub enum GeozeroError {
     // Geometry access errors
@@ -34,9 +38,51 @@ pub enum GeozeroError {
     Coord,
     #[error("processing geometry `{0}`")]
     Geometry(String),
+    // Processors
+    #[error("invalid filter expression: `{0}`")]
+    Filter(String),
+    #[error("processing stopped")]
+    Stopped,
+    #[error("feature limit of {0} features exceeded")]
+    FeatureLimit(u64),
+    #[error("resource limit exceeded: {0}")]
+    ResourceLimit(String),
+    // CRS
+    #[error("invalid or unsupported CRS: {0}")]
+    Crs(String),
+    #[error("transforming coordinates: `{0}`")]
+    Transform(String),
     // General
     #[error("I/O error")]
     IoError(#[from] std::io::Error),
+    #[error("JSON error")]
+    JsonError(#[from] serde_json::Error),
+    #[error("external library error")]
+    External(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl GeozeroError {
+    /// Wrap an error of another library, keeping it as error source
+    pub fn external<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> Self {
+        GeozeroError::External(error.into())
+    }
 }
 
 pub type Result<T> = std::result::Result<T, GeozeroError>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn error_source() {
+        let io = std::io::Error::other("disk full");
+        let err = GeozeroError::external(io);
+        assert_eq!(err.to_string(), "external library error");
+        assert_eq!(err.source().unwrap().to_string(), "disk full");
+
+        let err: GeozeroError = serde_json::from_str::<u32>("x").unwrap_err().into();
+        assert!(err.source().is_some());
+    }
+}
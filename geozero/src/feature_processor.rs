@@ -1,6 +1,84 @@
+use crate::crs::Crs;
 use crate::error::Result;
 use crate::geometry_processor::GeomProcessor;
-use crate::property_processor::PropertyProcessor;
+use crate::processor::Extent;
+use crate::property_processor::{ColumnDef, ColumnValue, PropertyProcessor};
+use std::fmt;
+
+/// Kind of a [Warning]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WarningKind {
+    /// Value converted to another type
+    CoercedType,
+    /// Value truncated to the size supported by the output, e.g. DBF strings
+    Truncated,
+    /// Coordinate values or vertices dropped
+    DroppedValue,
+    /// Feature skipped because of invalid content
+    SkippedFeature,
+    /// Other recoverable issue, e.g. an unsupported CRS replaced by a default
+    Other,
+}
+
+/// Recoverable issue reported with [FeatureProcessor::warning]
+#[derive(Clone, PartialEq, Debug)]
+pub struct Warning {
+    pub kind: WarningKind,
+    /// Index of affected feature, `None` if unknown or outside of features
+    pub feature_idx: Option<u64>,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new<S: Into<String>>(kind: WarningKind, message: S) -> Self {
+        Warning {
+            kind,
+            feature_idx: None,
+            message: message.into(),
+        }
+    }
+    /// Set index of affected feature
+    pub fn feature(mut self, idx: u64) -> Self {
+        self.feature_idx = Some(idx);
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(idx) = self.feature_idx {
+            write!(f, "feature {}: ", idx)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Descriptive metadata of a dataset reported with [FeatureProcessor::metadata]
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct DatasetMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Attribution of the data source, e.g. `© OpenStreetMap contributors`
+    pub attribution: Option<String>,
+    /// License, e.g. a SPDX identifier or URL
+    pub license: Option<String>,
+    /// Declared extent of the source data in the dataset CRS
+    pub extent: Option<Extent>,
+}
+
+impl DatasetMetadata {
+    /// Check whether no metadata is set
+    pub fn is_empty(&self) -> bool {
+        self == &DatasetMetadata::default()
+    }
+    /// Metadata without extent, e.g. for reprojected or partial data
+    pub fn without_extent(&self) -> Self {
+        DatasetMetadata {
+            extent: None,
+            ..self.clone()
+        }
+    }
+}
 
 /// Feature processing trait
 #[allow(unused_variables)]
@@ -9,6 +87,25 @@ pub trait FeatureProcessor: GeomProcessor + PropertyProcessor {
     fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
         Ok(())
     }
+    /// Dataset schema
+    ///
+    /// Emitted by datasources with a known schema after `dataset_begin` and before the first feature
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        Ok(())
+    }
+    /// Coordinate reference system of dataset
+    ///
+    /// Emitted by datasources with a known CRS after `dataset_begin` and before the first feature
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        Ok(())
+    }
+    /// Descriptive metadata of dataset
+    ///
+    /// Emitted by datasources with metadata after `dataset_begin` and before the first feature.
+    /// Processors changing the CRS drop the extent.
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        Ok(())
+    }
     /// End of dataset processing
     fn dataset_end(&mut self) -> Result<()> {
         Ok(())
@@ -17,6 +114,21 @@ pub trait FeatureProcessor: GeomProcessor + PropertyProcessor {
     fn feature_begin(&mut self, idx: u64) -> Result<()> {
         Ok(())
     }
+    /// Feature id of the source
+    ///
+    /// Emitted by datasources with feature ids after `feature_begin`, see
+    /// [GeozeroDatasource::fid_stability](crate::GeozeroDatasource::fid_stability).
+    fn fid(&mut self, fid: &ColumnValue) -> Result<()> {
+        Ok(())
+    }
+    /// Recoverable issue of the processed data
+    ///
+    /// Emitted by readers and processors when data was changed or dropped to continue
+    /// processing. Wrapping processors pass warnings on, use a
+    /// [WarningReporter](crate::processor::WarningReporter) to handle them.
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        Ok(())
+    }
     /// End of feature processing
     fn feature_end(&mut self, idx: u64) -> Result<()> {
         Ok(())
@@ -0,0 +1,193 @@
+//! Pull-based feature access.
+use crate::api::{FeatureAccess, FeatureProperties, GeozeroGeometry};
+use crate::error::Result;
+use crate::processor::{Event, FeatureBuffer};
+use crate::{GeomProcessor, ProcessorSink, PropertyProcessor};
+
+/// Feature handle returned by [GeozeroDatasource::features](crate::GeozeroDatasource::features).
+///
+/// Properties and geometry are processed on demand with the [FeatureAccess],
+/// [FeatureProperties] and [GeozeroGeometry] API.
+#[derive(Clone, Debug)]
+pub struct OwnedFeature {
+    idx: u64,
+    events: Vec<Event>,
+}
+
+impl OwnedFeature {
+    /// Feature index in datasource
+    pub fn idx(&self) -> u64 {
+        self.idx
+    }
+}
+
+impl FeatureProperties for OwnedFeature {
+    fn process_properties<P: PropertyProcessor>(&self, processor: &mut P) -> Result<bool> {
+        for event in &self.events {
+            if let Event::Property(idx, name, value) = event {
+                if processor.property(*idx, name, &value.as_column_value())? {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl GeozeroGeometry for OwnedFeature {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        for event in &self.events {
+            event.replay_geom(processor)?;
+        }
+        Ok(())
+    }
+}
+
+impl FeatureAccess for OwnedFeature {}
+
+/// Iterator over the features of a datasource.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::{FeatureProperties, GeozeroDatasource, ToWkt};
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"name": "Zurich"}, "geometry": {"type": "Point", "coordinates": [8.54, 47.37]}}
+/// ]}"#;
+/// let names = GeoJson(geojson)
+///     .features()
+///     .unwrap()
+///     .filter_map(|feature| feature.ok())
+///     .filter(|feature| feature.to_wkt().unwrap().contains("POINT(8"))
+///     .map(|feature| feature.property::<String>("name").unwrap())
+///     .collect::<Vec<_>>();
+/// assert_eq!(names, vec!["Zurich"]);
+/// ```
+pub struct FeatureIter<'a>(Box<dyn Iterator<Item = Result<OwnedFeature>> + 'a>);
+
+impl<'a> FeatureIter<'a> {
+    pub fn new(iter: impl Iterator<Item = Result<OwnedFeature>> + 'a) -> Self {
+        FeatureIter(Box::new(iter))
+    }
+}
+
+impl Iterator for FeatureIter<'_> {
+    type Item = Result<OwnedFeature>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// Record features passed to the processor by `process`
+pub(crate) fn record_features<F>(process: F) -> Result<Vec<OwnedFeature>>
+where
+    F: FnOnce(&mut FeatureBuffer<ProcessorSink>) -> Result<()>,
+{
+    let mut buffer = FeatureBuffer::new(ProcessorSink::new());
+    buffer.record();
+    process(&mut buffer)?;
+    let mut features = Vec::new();
+    let mut current: Option<OwnedFeature> = None;
+    for event in buffer.take() {
+        match event {
+            Event::FeatureBegin(idx) => {
+                current = Some(OwnedFeature {
+                    idx,
+                    events: Vec::new(),
+                })
+            }
+            Event::FeatureEnd(_) => features.extend(current.take()),
+            event => {
+                if let Some(feature) = &mut current {
+                    feature.events.push(event);
+                }
+            }
+        }
+    }
+    Ok(features)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ColumnValue, FeatureProcessor};
+
+    struct Source;
+
+    impl crate::GeozeroDatasource for Source {
+        fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+            processor.dataset_begin(None)?;
+            for idx in 0..3 {
+                processor.feature_begin(idx)?;
+                processor.properties_begin()?;
+                processor.property(0, "id", &ColumnValue::ULong(idx * 10))?;
+                processor.properties_end()?;
+                processor.geometry_begin()?;
+                processor.point_begin(0)?;
+                processor.xy(idx as f64, 0.0, 0)?;
+                processor.point_end(0)?;
+                processor.geometry_end()?;
+                processor.feature_end(idx)?;
+            }
+            processor.dataset_end()
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "with-geojson", feature = "with-wkt"))]
+    fn from_feature() -> Result<()> {
+        use crate::{GeometryField, GeozeroDatasource};
+        use serde::Deserialize;
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Record {
+            id: u64,
+            geometry: Option<String>,
+        }
+
+        let features = Source.features()?.collect::<Result<Vec<_>>>()?;
+        let record: Record = features[1].from_feature(GeometryField::Wkt)?;
+        assert_eq!(
+            record,
+            Record {
+                id: 10,
+                geometry: Some("POINT(1 0)".to_string())
+            }
+        );
+        let record: Record = features[2].from_feature(GeometryField::None)?;
+        assert_eq!(record.geometry, None);
+        let json: serde_json::Value = features[0].from_feature(GeometryField::GeoJson)?;
+        assert_eq!(
+            json,
+            serde_json::json!({"id": 0, "geometry": {"type": "Point", "coordinates": [0, 0]}})
+        );
+        Ok(())
+    }
+
+    /// Collects X values
+    struct Xs(Vec<f64>);
+
+    impl GeomProcessor for Xs {
+        fn xy(&mut self, x: f64, _y: f64, _idx: usize) -> Result<()> {
+            self.0.push(x);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn features() -> Result<()> {
+        use crate::GeozeroDatasource;
+
+        let features = Source.features()?.collect::<Result<Vec<_>>>()?;
+        assert_eq!(features.len(), 3);
+        assert_eq!(features[2].idx(), 2);
+        assert_eq!(features[2].property::<u64>("id")?, 20);
+        let mut xs = Xs(Vec::new());
+        features[1].process_geom(&mut xs)?;
+        assert_eq!(xs.0, vec![1.0]);
+        Ok(())
+    }
+}
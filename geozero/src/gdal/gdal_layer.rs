@@ -0,0 +1,297 @@
+use crate::error::{GeozeroError, Result};
+use crate::gdal::GdalWriter;
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, GeozeroDatasource,
+    PropertyProcessor,
+};
+use gdal::vector::{FieldValue, Geometry, LayerAccess};
+use gdal_sys::{OGRFieldType, OGRwkbGeometryType};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+/// OGR layer as datasource.
+///
+/// Fields with integer, real, string, date or binary type are passed as properties.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use gdal::Dataset;
+/// use geozero::gdal::GdalLayer;
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::GeozeroDatasource;
+///
+/// let dataset = Dataset::open("countries.shp")?;
+/// let mut layer = GdalLayer(dataset.layer(0)?);
+/// let mut out: Vec<u8> = Vec::new();
+/// layer.process(&mut GeoJsonWriter::new(&mut out))?;
+/// ```
+pub struct GdalLayer<L: LayerAccess>(pub L);
+
+impl<L: LayerAccess> GeozeroDatasource for GdalLayer<L> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        let layer = &mut self.0;
+        processor.dataset_begin(Some(&layer.name()))?;
+        let columns: Vec<ColumnDef> = layer
+            .defn()
+            .fields()
+            .map(|field| {
+                let mut column = ColumnDef::new(&field.name(), column_type(field.field_type()));
+                column.width = usize::try_from(field.width()).ok().filter(|w| *w > 0);
+                column
+            })
+            .collect();
+        processor.schema(&columns)?;
+        layer.reset_feature_reading();
+        for (idx, feature) in layer.features().enumerate() {
+            let idx = idx as u64;
+            processor.feature_begin(idx)?;
+            processor.properties_begin()?;
+            let mut i = 0;
+            for (name, value) in feature.fields() {
+                let value = match &value {
+                    Some(FieldValue::IntegerValue(v)) => ColumnValue::Int(*v),
+                    Some(FieldValue::Integer64Value(v)) => ColumnValue::Long(*v),
+                    Some(FieldValue::RealValue(v)) => ColumnValue::Double(*v),
+                    Some(FieldValue::StringValue(v)) => ColumnValue::String(v),
+                    Some(_) => continue,
+                    None => ColumnValue::Null,
+                };
+                if processor.property(i, &name, &value)? {
+                    break;
+                }
+                i += 1;
+            }
+            processor.properties_end()?;
+            processor.geometry_begin()?;
+            crate::gdal::process_geom(feature.geometry(), processor)?;
+            processor.geometry_end()?;
+            processor.feature_end(idx)?;
+        }
+        processor.dataset_end()
+    }
+}
+
+/// Column type of OGR field type
+fn column_type(field_type: OGRFieldType::Type) -> ColumnType {
+    match field_type {
+        OGRFieldType::OFTInteger => ColumnType::Int,
+        OGRFieldType::OFTInteger64 => ColumnType::Long,
+        OGRFieldType::OFTReal => ColumnType::Double,
+        OGRFieldType::OFTDate => ColumnType::Date,
+        OGRFieldType::OFTDateTime => ColumnType::DateTime,
+        OGRFieldType::OFTBinary => ColumnType::Binary,
+        _ => ColumnType::String,
+    }
+}
+
+/// OGR field type of column type
+fn field_type(col_type: ColumnType) -> OGRFieldType::Type {
+    match col_type {
+        ColumnType::Bool
+        | ColumnType::Byte
+        | ColumnType::UByte
+        | ColumnType::Short
+        | ColumnType::UShort
+        | ColumnType::Int => OGRFieldType::OFTInteger,
+        ColumnType::UInt | ColumnType::Long | ColumnType::ULong => OGRFieldType::OFTInteger64,
+        ColumnType::Float | ColumnType::Double => OGRFieldType::OFTReal,
+        ColumnType::Binary => OGRFieldType::OFTBinary,
+        // OGR has no integer type beyond 64 bits
+        ColumnType::BigInt => OGRFieldType::OFTString,
+        _ => OGRFieldType::OFTString,
+    }
+}
+
+/// OGR field value of property value
+fn field_value(value: &ColumnValue) -> Option<FieldValue> {
+    let value = match *value {
+        ColumnValue::Bool(v) => FieldValue::IntegerValue(v as i32),
+        ColumnValue::Byte(v) => FieldValue::IntegerValue(v as i32),
+        ColumnValue::UByte(v) => FieldValue::IntegerValue(v as i32),
+        ColumnValue::Short(v) => FieldValue::IntegerValue(v as i32),
+        ColumnValue::UShort(v) => FieldValue::IntegerValue(v as i32),
+        ColumnValue::Int(v) => FieldValue::IntegerValue(v),
+        ColumnValue::UInt(v) => FieldValue::Integer64Value(v as i64),
+        ColumnValue::Long(v) => FieldValue::Integer64Value(v),
+        ColumnValue::ULong(v) => match i64::try_from(v) {
+            Ok(v) => FieldValue::Integer64Value(v),
+            // OGR has no unsigned integer type, keep values beyond i64 as string
+            Err(_) => FieldValue::StringValue(v.to_string()),
+        },
+        ColumnValue::Float(v) => FieldValue::RealValue(v as f64),
+        ColumnValue::Double(v) => FieldValue::RealValue(v),
+        ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v)
+        | ColumnValue::BigInt(v) => FieldValue::StringValue(v.to_string()),
+        ColumnValue::Binary(_) | ColumnValue::Null => return None,
+    };
+    Some(value)
+}
+
+/// Writes features into an OGR layer.
+///
+/// Fields are created from the schema reported by the datasource or on first
+/// appearance of a property, unless disabled with [create_fields](GdalLayerWriter::create_fields)
+/// for existing layers. Properties without a matching field are ignored by OGR.
+///
+/// With [dry_run](GdalLayerWriter::dry_run), geometries and field values are
+/// created without changing the layer.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use gdal::{Dataset, DriverManager};
+/// use geozero::gdal::GdalLayerWriter;
+/// use geozero::geojson::GeoJsonReader;
+/// use geozero::GeozeroDatasource;
+///
+/// let driver = DriverManager::get_driver_by_name("GPKG")?;
+/// let mut dataset = driver.create_vector_only("countries.gpkg")?;
+/// let mut layer = dataset.create_layer(Default::default())?;
+/// GeoJsonReader(File::open("countries.geojson")?).process(&mut GdalLayerWriter::new(&mut layer))?;
+/// ```
+pub struct GdalLayerWriter<'l, L: LayerAccess> {
+    layer: &'l mut L,
+    create_fields: bool,
+    dry_run: bool,
+    /// Created fields
+    fields: HashSet<String>,
+    names: Vec<String>,
+    values: Vec<FieldValue>,
+    geometry: Option<Geometry>,
+    /// Writer of current feature geometry
+    writer: Option<GdalWriter>,
+}
+
+impl<'l, L: LayerAccess> GdalLayerWriter<'l, L> {
+    pub fn new(layer: &'l mut L) -> Self {
+        GdalLayerWriter {
+            layer,
+            create_fields: true,
+            dry_run: false,
+            fields: HashSet::new(),
+            names: Vec::new(),
+            values: Vec::new(),
+            geometry: None,
+            writer: None,
+        }
+    }
+    /// Create layer fields from the dataset schema (default: true)
+    pub fn create_fields(mut self, create_fields: bool) -> Self {
+        self.create_fields = create_fields;
+        self
+    }
+    /// Create neither fields nor features (default: false)
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+    fn create_field(&mut self, name: &str, col_type: ColumnType) -> Result<()> {
+        if self.create_fields && !self.fields.contains(name) {
+            if !self.dry_run {
+                self.layer
+                    .create_defn_fields(&[(name, field_type(col_type))])?;
+            }
+            self.fields.insert(name.to_string());
+        }
+        Ok(())
+    }
+    fn writer(&mut self) -> Result<&mut GdalWriter> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| GeozeroError::Geometry("Geometry outside of feature".to_string()))
+    }
+}
+
+impl<L: LayerAccess> PropertyProcessor for GdalLayerWriter<'_, L> {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if let Some(field_value) = field_value(value) {
+            self.create_field(name, value.column_type())?;
+            self.names.push(name.to_string());
+            self.values.push(field_value);
+        }
+        Ok(false)
+    }
+}
+
+impl<L: LayerAccess> FeatureProcessor for GdalLayerWriter<'_, L> {
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        for column in columns {
+            self.create_field(&column.name, column.col_type)?;
+        }
+        Ok(())
+    }
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.names.clear();
+        self.values.clear();
+        self.geometry = None;
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        let geometry = match self.geometry.take() {
+            Some(geometry) => geometry,
+            None => Geometry::empty(OGRwkbGeometryType::wkbUnknown)?,
+        };
+        if self.dry_run {
+            return Ok(());
+        }
+        let names: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        self.layer
+            .create_feature_fields(geometry, &names, &self.values)?;
+        Ok(())
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.writer = Some(GdalWriter::new());
+        Ok(())
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        self.geometry = self.writer.take().map(|writer| writer.geom);
+        Ok(())
+    }
+}
+
+/// Forward geometry calls to the writer of the current feature
+macro_rules! forward_to_writer {
+    ($( $method:ident($($arg:ident: $ty:ty),*); )*) => {
+        $(
+            fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
+                self.writer()?.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl<L: LayerAccess> GeomProcessor for GdalLayerWriter<'_, L> {
+    forward_to_writer! {
+        xy(x: f64, y: f64, idx: usize);
+        point_begin(idx: usize);
+        point_end(idx: usize);
+        multipoint_begin(size: usize, idx: usize);
+        multipoint_end(idx: usize);
+        linestring_begin(tagged: bool, size: usize, idx: usize);
+        linestring_end(tagged: bool, idx: usize);
+        multilinestring_begin(size: usize, idx: usize);
+        multilinestring_end(idx: usize);
+        polygon_begin(tagged: bool, size: usize, idx: usize);
+        polygon_end(tagged: bool, idx: usize);
+        multipolygon_begin(size: usize, idx: usize);
+        multipolygon_end(idx: usize);
+        geometrycollection_begin(size: usize, idx: usize);
+        geometrycollection_end(idx: usize);
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.writer()?.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
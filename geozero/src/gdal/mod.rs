@@ -1,7 +1,9 @@
 //! GDAL conversions.
+pub(crate) mod gdal_layer;
 pub(crate) mod gdal_reader;
 pub(crate) mod gdal_writer;
 
+pub use gdal_layer::*;
 pub use gdal_reader::*;
 pub use gdal_writer::*;
 
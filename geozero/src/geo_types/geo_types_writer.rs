@@ -206,7 +206,7 @@ mod test {
                     Point::new(1875038.447610231, -3269648.6879248763)
                 );
             }
-            _ => assert!(false),
+            other => panic!("expected LineString, found {:?}", other),
         }
         Ok(())
     }
@@ -226,7 +226,7 @@ mod test {
                     Point::new(173.020375, -40.919052)
                 );
             }
-            _ => assert!(false),
+            other => panic!("expected MultiPolygon, found {:?}", other),
         }
         Ok(())
     }
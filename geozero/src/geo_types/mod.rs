@@ -11,6 +11,17 @@ pub(crate) mod conversion {
     use crate::GeozeroGeometry;
 
     /// Convert to geo-types Geometry.
+    ///
+    /// # Usage example:
+    ///
+    /// Convert a WKT geometry to geo-types:
+    ///
+    /// ```
+    /// use geozero::{ToGeo, wkt::WktStr};
+    ///
+    /// let geom = WktStr("POINT(10 -20)").to_geo().unwrap();
+    /// assert_eq!(geom, geo_types::Point::new(10.0, -20.0).into());
+    /// ```
     pub trait ToGeo {
         /// Convert to geo-types Geometry.
         fn to_geo(&self) -> Result<geo_types::Geometry<f64>>;
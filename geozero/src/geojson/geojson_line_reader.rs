@@ -0,0 +1,337 @@
+use crate::error::{GeozeroError, Result};
+use crate::features::record_features;
+use crate::geojson::geojson_reader::{process_feature, resolve_duplicate_keys};
+use crate::processor::{Borrowed, ResourceLimiter};
+use crate::{Crs, FeatureIter, FeatureProcessor, FidStability, GeozeroDatasource, ReadOptions};
+use geojson::GeoJson as GeoGeoJson;
+use std::io::{BufRead, Read};
+
+/// Newline-delimited GeoJSON reader.
+///
+/// Each non-empty line contains a GeoJSON Feature.
+pub struct GeoJsonLineReader<R: BufRead>(pub R);
+
+impl<R: BufRead> GeozeroDatasource for GeoJsonLineReader<R> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        read_geojson_lines(&mut self.0, processor, &ReadOptions::default())
+    }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        read_geojson_lines(&mut self.0, processor, options)
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
+    /// Iterator over the features, reading one line at a time.
+    fn features(&mut self) -> Result<FeatureIter<'_>> {
+        let options = ReadOptions::default();
+        let features = (&mut self.0)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+            .enumerate()
+            .filter_map(move |(idx, (line_idx, line))| {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(GeozeroError::from(e))),
+                };
+                record_features(|buffer| process_line(&line, idx, line_idx + 1, buffer, &options))
+                    .map(|features| features.into_iter().next())
+                    .transpose()
+            });
+        Ok(FeatureIter::new(features))
+    }
+}
+
+/// Process GeoJSON Feature in line number `line_no` as feature `idx`
+fn process_line<P: FeatureProcessor>(
+    line: &str,
+    idx: usize,
+    line_no: usize,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
+    let mut geojson = line.parse::<GeoGeoJson>()?;
+    resolve_duplicate_keys(line, &mut geojson, options.duplicate_keys, idx)?;
+    match geojson {
+        GeoGeoJson::Feature(feature) => process_feature(&feature, idx, processor, options),
+        _ => Err(GeozeroError::Feature(format!(
+            "line {}: GeoJSON Feature expected",
+            line_no
+        ))),
+    }
+}
+
+/// Read and process newline-delimited GeoJSON with reading options.
+pub fn read_geojson_lines<R: BufRead, P: FeatureProcessor>(
+    reader: R,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
+    read_lines(reader, processor, options, 0)
+}
+
+/// Read lines following the first `line_offset` lines of the input
+fn read_lines<R: BufRead, P: FeatureProcessor>(
+    mut reader: R,
+    processor: &mut P,
+    options: &ReadOptions,
+    line_offset: usize,
+) -> Result<()> {
+    let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+    let processor = &mut processor;
+    processor.dataset_begin(None)?;
+    processor.crs(&Crs::wgs84())?;
+    // Reuse line buffer
+    let mut line = String::new();
+    let mut idx = 0;
+    let mut line_no = line_offset;
+    while reader.read_line(&mut line)? > 0 {
+        line_no += 1;
+        if !line.trim().is_empty() {
+            process_line(&line, idx, line_no, processor, options)?;
+            idx += 1;
+        }
+        line.clear();
+    }
+    processor.dataset_end()
+}
+
+/// Chunk of complete lines of newline-delimited GeoJSON.
+///
+/// Created by [GeoJsonLineChunks].
+pub struct GeoJsonLineChunk {
+    data: std::io::Result<Vec<u8>>,
+    /// Number of input lines before the chunk
+    line_offset: usize,
+}
+
+impl GeozeroDatasource for GeoJsonLineChunk {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        let data = std::mem::replace(&mut self.data, Ok(Vec::new()))?;
+        read_lines(
+            data.as_slice(),
+            processor,
+            &ReadOptions::default(),
+            self.line_offset,
+        )
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
+}
+
+/// Splits newline-delimited GeoJSON into byte chunks on line boundaries.
+///
+/// Chunks can be parsed and processed in parallel with
+/// [process_parallel](crate::processor::process_parallel), which passes the features
+/// in input order to the output processor.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::{GeoJsonLineChunks, GeoJsonWriter};
+/// use geozero::processor::{process_parallel, OutputCrs};
+/// use geozero::Crs;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// let input = BufReader::new(File::open("buildings.geojsonl")?);
+/// let mut writer = GeoJsonWriter::new(&mut out);
+/// process_parallel(GeoJsonLineChunks::new(input), &mut writer, |recorder| {
+///     Ok(recorder.output_crs(Crs::Epsg(3857)))
+/// })?;
+/// ```
+pub struct GeoJsonLineChunks<R: BufRead> {
+    reader: R,
+    chunk_size: usize,
+    /// Number of lines in previous chunks
+    line_offset: usize,
+    done: bool,
+}
+
+impl<R: BufRead> GeoJsonLineChunks<R> {
+    pub fn new(reader: R) -> Self {
+        GeoJsonLineChunks {
+            reader,
+            chunk_size: 4 * 1024 * 1024,
+            line_offset: 0,
+            done: false,
+        }
+    }
+    /// Minimal chunk size in bytes (default: 4 MiB)
+    pub fn chunk_size(mut self, size: usize) -> Self {
+        self.chunk_size = size;
+        self
+    }
+    /// Read `chunk_size` bytes and the rest of the last line
+    fn read_chunk(&mut self, data: &mut Vec<u8>) -> std::io::Result<()> {
+        (&mut self.reader)
+            .take(self.chunk_size as u64)
+            .read_to_end(data)?;
+        if !data.is_empty() && !data.ends_with(b"\n") {
+            self.reader.read_until(b'\n', data)?;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for GeoJsonLineChunks<R> {
+    type Item = GeoJsonLineChunk;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut data = Vec::with_capacity(self.chunk_size);
+        let line_offset = self.line_offset;
+        let data = match self.read_chunk(&mut data) {
+            Ok(()) if data.is_empty() => {
+                self.done = true;
+                return None;
+            }
+            Ok(()) => {
+                self.line_offset += data.iter().filter(|b| **b == b'\n').count();
+                Ok(data)
+            }
+            Err(e) => {
+                self.done = true;
+                Err(e)
+            }
+        };
+        Some(GeoJsonLineChunk { data, line_offset })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJsonWriter;
+    use crate::wkt::WktWriter;
+    use crate::{DuplicateKeyPolicy, ProcessToJson, ProcessorSink};
+
+    const GEOJSONL: &str = r#"{"type": "Feature", "properties": {"id": 1}, "geometry": {"type": "Point", "coordinates": [1, 1]}}
+{"type": "Feature", "properties": {"id": 2}, "geometry": {"type": "Point", "coordinates": [2, 2]}}
+
+{"type": "Feature", "properties": {"id": 3}, "geometry": {"type": "Point", "coordinates": [3, 3]}}
+"#;
+
+    #[test]
+    fn line_reader() -> Result<()> {
+        let mut wkt_data: Vec<u8> = Vec::new();
+        GeoJsonLineReader(GEOJSONL.as_bytes()).process(&mut WktWriter::new(&mut wkt_data))?;
+        assert_eq!(
+            std::str::from_utf8(&wkt_data).unwrap(),
+            "POINT(1 1),POINT(2 2),POINT(3 3)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn features() -> Result<()> {
+        use crate::{FeatureProperties, ToWkt};
+
+        let mut reader = GeoJsonLineReader(GEOJSONL.as_bytes());
+        let mut features = reader.features()?;
+        let feature = features.next().unwrap()?;
+        assert_eq!(feature.property::<i64>("id")?, 1);
+        assert_eq!(feature.to_wkt()?, "POINT(1 1)");
+        let ids = features
+            .map(|feature| feature.and_then(|feature| Ok((feature.idx(), feature.property("id")?))))
+            .collect::<Result<Vec<(u64, i64)>>>()?;
+        assert_eq!(ids, vec![(1, 2), (2, 3)]);
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<()> {
+        let geojsonl = r#"{"type": "Feature", "properties": {"id": 1}, "geometry": null}
+{"type": "Feature", "properties": {"id": 2, "id": 3}, "geometry": null}
+"#;
+        let options = ReadOptions {
+            duplicate_keys: DuplicateKeyPolicy::FirstWins,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        read_geojson_lines(
+            geojsonl.as_bytes(),
+            &mut GeoJsonWriter::new(&mut out),
+            &options,
+        )?;
+        assert!(std::str::from_utf8(&out).unwrap().contains(r#"{"id": 2}"#));
+
+        let options = ReadOptions {
+            duplicate_keys: DuplicateKeyPolicy::Error,
+            ..Default::default()
+        };
+        let result = read_geojson_lines(geojsonl.as_bytes(), &mut ProcessorSink::new(), &options);
+        assert!(result.unwrap_err().to_string().contains("in feature 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn line_numbers() {
+        // Lines 3 and 5 are empty
+        let geojsonl = format!(
+            "{}\n{{\"type\": \"Point\", \"coordinates\": [1, 2]}}\n",
+            GEOJSONL
+        );
+        let error = "processing feature: `line 6: GeoJSON Feature expected`";
+        let err = GeoJsonLineReader(geojsonl.as_bytes())
+            .process(&mut ProcessorSink::new())
+            .unwrap_err();
+        assert_eq!(err.to_string(), error);
+
+        let err = GeoJsonLineReader(geojsonl.as_bytes())
+            .features()
+            .unwrap()
+            .nth(3)
+            .unwrap()
+            .err()
+            .unwrap();
+        assert_eq!(err.to_string(), error);
+
+        let err = GeoJsonLineChunks::new(geojsonl.as_bytes())
+            .chunk_size(10)
+            .last()
+            .unwrap()
+            .process(&mut ProcessorSink::new())
+            .unwrap_err();
+        assert_eq!(err.to_string(), error);
+    }
+
+    #[test]
+    fn line_chunks() -> Result<()> {
+        let chunks = GeoJsonLineChunks::new(GEOJSONL.as_bytes())
+            .chunk_size(10)
+            .map(|mut chunk| chunk.to_json())
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].contains("[2,2]"));
+        assert!(chunks[2].contains("[3,3]"));
+        Ok(())
+    }
+
+    #[cfg(feature = "with-rayon")]
+    #[test]
+    fn parallel_chunks() -> Result<()> {
+        use crate::processor::{process_parallel, TransformXY};
+
+        let mut out: Vec<u8> = Vec::new();
+        let chunks = GeoJsonLineChunks::new(GEOJSONL.as_bytes()).chunk_size(10);
+        process_parallel(chunks, &mut GeoJsonWriter::new(&mut out), |recorder| {
+            Ok(TransformXY::new(recorder, |x, y| (x * 10.0, y)))
+        })?;
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let ids: Vec<_> = json["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|feature| feature["geometry"]["coordinates"][0].as_f64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![10.0, 20.0, 30.0]);
+        Ok(())
+    }
+}
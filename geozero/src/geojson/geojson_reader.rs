@@ -1,10 +1,16 @@
-use crate::error::Result;
+use crate::datetime::string_value;
+use crate::error::{GeozeroError, Result};
+use crate::features::record_features;
+use crate::processor::{read_input, Borrowed, Extent, ResourceLimiter};
 use crate::{
-    ColumnValue, FeatureProcessor, GeomProcessor, GeozeroDatasource, GeozeroGeometry,
-    PropertyProcessor,
+    ColumnValue, Crs, DatasetMetadata, DuplicateKeyPolicy, FeatureIter, FeatureProcessor,
+    FidStability, GeomProcessor, GeozeroDatasource, GeozeroGeometry, NestedProperties,
+    PropertyProcessor, ReadOptions, Warning, WarningKind,
 };
+use geojson::feature::Id;
 use geojson::{Feature, FeatureReader};
 use geojson::{GeoJson as GeoGeoJson, Geometry, Value};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde_json::map::Map;
 use serde_json::value::Value as JsonValue;
 use std::io::Read;
@@ -23,6 +29,16 @@ impl GeozeroDatasource for GeoJsonString {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
         read_geojson(&mut self.0.as_bytes(), processor)
     }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        read_geojson_with_options(&mut self.0.as_bytes(), processor, options)
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
 }
 
 /// GeoJSON String slice.
@@ -38,6 +54,16 @@ impl GeozeroDatasource for GeoJson<'_> {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
         read_geojson(&mut self.0.as_bytes(), processor)
     }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        read_geojson_with_options(&mut self.0.as_bytes(), processor, options)
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
 }
 
 /// GeoJSON Reader.
@@ -47,20 +73,234 @@ impl<'a, R: Read> GeozeroDatasource for GeoJsonReader<'a, R> {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
         read_geojson(&mut self.0, processor)
     }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        read_geojson_with_options(&mut self.0, processor, options)
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
+    /// Iterator over the features of a FeatureCollection, reading one feature at a time.
+    ///
+    /// Duplicate property names are resolved with [DuplicateKeyPolicy::LastWins].
+    fn features(&mut self) -> Result<FeatureIter<'_>> {
+        let options = ReadOptions::default();
+        let features = FeatureReader::from_reader(&mut *self.0)
+            .features()
+            .enumerate()
+            .filter_map(move |(idx, feature)| {
+                let feature = match feature {
+                    Ok(feature) => feature,
+                    Err(e) => return Some(Err(GeozeroError::from(e))),
+                };
+                record_features(|buffer| process_geojson_feature(&feature, idx, buffer, &options))
+                    .map(|features| features.into_iter().next())
+                    .transpose()
+            });
+        Ok(FeatureIter::new(features))
+    }
+}
+
+/// GeoJSON reader for async input.
+#[cfg(feature = "with-tokio")]
+pub struct AsyncGeoJsonReader<R: tokio::io::AsyncRead + Unpin + Send>(pub R);
+
+#[cfg(feature = "with-tokio")]
+#[async_trait::async_trait]
+impl<R: tokio::io::AsyncRead + Unpin + Send> crate::AsyncGeozeroDatasource
+    for AsyncGeoJsonReader<R>
+{
+    async fn process<P: FeatureProcessor + Send>(&mut self, processor: &mut P) -> Result<()> {
+        self.process_with_options(processor, &ReadOptions::default())
+            .await
+    }
+    async fn process_with_options<P: FeatureProcessor + Send>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        use tokio::io::AsyncReadExt;
+        // Read one byte more than `max_input_size` for detecting oversized input
+        let max = options
+            .limits
+            .max_input_size
+            .map_or(u64::MAX, |max| max as u64 + 1);
+        let mut geojson = Vec::new();
+        (&mut self.0).take(max).read_to_end(&mut geojson).await?;
+        read_geojson_with_options(geojson.as_slice(), processor, options)
+    }
 }
 
 /// Read and process GeoJSON.
-pub fn read_geojson<R: Read, P: FeatureProcessor>(mut reader: R, processor: &mut P) -> Result<()> {
-    let mut geojson_str = String::new();
-    reader.read_to_string(&mut geojson_str)?;
-    let geojson = geojson_str.parse::<GeoGeoJson>()?;
-    process_geojson(&geojson, processor)
+pub fn read_geojson<R: Read, P: FeatureProcessor>(reader: R, processor: &mut P) -> Result<()> {
+    read_geojson_with_options(reader, processor, &ReadOptions::default())
+}
+
+/// Read and process GeoJSON with reading options.
+///
+/// The document is read into memory up to the `max_input_size` limit and parsed,
+/// the other resource limits are checked while processing the parsed document.
+pub fn read_geojson_with_options<R: Read, P: FeatureProcessor>(
+    reader: R,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
+    let geojson_str = read_input(reader, &options.limits)?;
+    let mut geojson = geojson_str.parse::<GeoGeoJson>()?;
+    resolve_duplicate_keys(&geojson_str, &mut geojson, options.duplicate_keys, 0)?;
+    let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+    process_geojson(&geojson, &mut processor, options)
+}
+
+/// JSON value keeping duplicate object keys
+enum RawJson {
+    Object(Vec<(String, RawJson)>),
+    Array(Vec<RawJson>),
+    Value(JsonValue),
+}
+
+impl<'de> Deserialize<'de> for RawJson {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(RawJsonVisitor)
+    }
+}
+
+struct RawJsonVisitor;
+
+impl<'de> Visitor<'de> for RawJsonVisitor {
+    type Value = RawJson;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_f64<E>(self, v: f64) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_str<E>(self, v: &str) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_string<E>(self, v: String) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(v.into()))
+    }
+    fn visit_unit<E>(self) -> std::result::Result<RawJson, E> {
+        Ok(RawJson::Value(JsonValue::Null))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<RawJson, A::Error> {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(RawJson::Array(values))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<RawJson, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(RawJson::Object(entries))
+    }
+}
+
+impl RawJson {
+    /// Remove value of entry `name`, the last one like the GeoJSON parser
+    fn take(&mut self, name: &str) -> Option<RawJson> {
+        match self {
+            RawJson::Object(entries) => entries
+                .iter()
+                .rposition(|(k, _)| k == name)
+                .map(|pos| entries.swap_remove(pos).1),
+            _ => None,
+        }
+    }
+    /// Convert into object map, resolving duplicate keys according to `policy`
+    fn into_map(self, policy: DuplicateKeyPolicy) -> Result<Option<Map<String, JsonValue>>> {
+        match self {
+            RawJson::Object(mut entries) => {
+                policy.apply(&mut entries)?;
+                let mut map = Map::new();
+                for (key, value) in entries {
+                    map.insert(key, value.into_json(policy)?);
+                }
+                Ok(Some(map))
+            }
+            _ => Ok(None),
+        }
+    }
+    fn into_json(self, policy: DuplicateKeyPolicy) -> Result<JsonValue> {
+        match self {
+            RawJson::Object(_) => Ok(JsonValue::Object(
+                self.into_map(policy)?.unwrap_or_default(),
+            )),
+            RawJson::Array(values) => values
+                .into_iter()
+                .map(|value| value.into_json(policy))
+                .collect::<Result<_>>()
+                .map(JsonValue::Array),
+            RawJson::Value(value) => Ok(value),
+        }
+    }
+}
+
+/// Replace feature properties of `geojson` parsed from `json`, resolving duplicate
+/// property names with `policy` instead of the parser default (last wins).
+///
+/// `first_idx` is the feature index used in error messages for the first feature.
+pub(crate) fn resolve_duplicate_keys(
+    json: &str,
+    geojson: &mut GeoGeoJson,
+    policy: DuplicateKeyPolicy,
+    first_idx: usize,
+) -> Result<()> {
+    if policy == DuplicateKeyPolicy::LastWins {
+        return Ok(());
+    }
+    let mut raw: RawJson = serde_json::from_str(json)?;
+    let properties = |raw: &mut RawJson, idx: usize| -> Result<_> {
+        match raw.take("properties") {
+            Some(properties) => properties.into_map(policy).map_err(|e| match e {
+                GeozeroError::Property(msg) => {
+                    GeozeroError::Property(format!("{} in feature {}", msg, idx))
+                }
+                e => e,
+            }),
+            None => Ok(None),
+        }
+    };
+    match geojson {
+        GeoGeoJson::FeatureCollection(collection) => {
+            if let Some(RawJson::Array(raw_features)) = raw.take("features") {
+                for (idx, (feature, mut raw_feature)) in
+                    collection.features.iter_mut().zip(raw_features).enumerate()
+                {
+                    feature.properties = properties(&mut raw_feature, first_idx + idx)?;
+                }
+            }
+        }
+        GeoGeoJson::Feature(feature) => {
+            feature.properties = properties(&mut raw, first_idx)?;
+        }
+        GeoGeoJson::Geometry(_) => {}
+    }
+    Ok(())
 }
 
 pub fn read_geojson_fc<R: Read, P: FeatureProcessor>(reader: R, processor: &mut P) -> Result<()> {
+    let options = ReadOptions::default();
     let mut idx = 0;
     for feature in FeatureReader::from_reader(reader).features() {
-        process_geojson_feature(&feature?, idx, processor)?;
+        process_geojson_feature(&feature?, idx, processor, &options)?;
         idx += 1;
     }
     Ok(())
@@ -78,27 +318,24 @@ pub fn read_geojson_geom<R: Read, P: GeomProcessor>(
 }
 
 /// Process top-level GeoJSON items
-fn process_geojson<P: FeatureProcessor>(gj: &GeoGeoJson, processor: &mut P) -> Result<()> {
+fn process_geojson<P: FeatureProcessor>(
+    gj: &GeoGeoJson,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
     match *gj {
         GeoGeoJson::FeatureCollection(ref collection) => {
             processor.dataset_begin(None)?;
+            process_crs(&collection.foreign_members, processor)?;
+            process_metadata(&collection.foreign_members, &collection.bbox, processor)?;
             for (idx, feature) in collection.features.iter().enumerate() {
-                processor.feature_begin(idx as u64)?;
-                if let Some(ref properties) = feature.properties {
-                    processor.properties_begin()?;
-                    process_properties(properties, processor)?;
-                    processor.properties_end()?;
-                }
-                if let Some(ref geometry) = feature.geometry {
-                    processor.geometry_begin()?;
-                    process_geojson_geom_n(geometry, idx, processor)?;
-                    processor.geometry_end()?;
-                }
-                processor.feature_end(idx as u64)?;
+                process_feature(feature, idx, processor, options)?;
             }
             processor.dataset_end()?;
         }
-        GeoGeoJson::Feature(ref feature) => process_geojson_feature(feature, 0, processor)?,
+        GeoGeoJson::Feature(ref feature) => {
+            process_geojson_feature(feature, 0, processor, options)?
+        }
         GeoGeoJson::Geometry(ref geometry) => {
             process_geojson_geom_n(geometry, 0, processor)?;
         }
@@ -111,26 +348,134 @@ fn process_geojson_feature<P: FeatureProcessor>(
     feature: &Feature,
     idx: usize,
     processor: &mut P,
+    options: &ReadOptions,
 ) -> Result<()> {
     processor.dataset_begin(None)?;
+    process_crs(&feature.foreign_members, processor)?;
     if feature.geometry.is_some() || feature.properties.is_some() {
-        processor.feature_begin(idx as u64)?;
-        if let Some(ref properties) = feature.properties {
-            processor.properties_begin()?;
-            process_properties(properties, processor)?;
-            processor.properties_end()?;
-        }
-        if let Some(ref geometry) = feature.geometry {
-            processor.geometry_begin()?;
-            process_geojson_geom_n(geometry, idx, processor)?;
-            processor.geometry_end()?;
-        }
-        processor.feature_end(idx as u64)?;
+        process_feature(feature, idx, processor, options)?;
+    } else {
+        processor.warning(
+            &Warning::new(
+                WarningKind::SkippedFeature,
+                "skipped feature without geometry and properties",
+            )
+            .feature(idx as u64),
+        )?;
     }
     processor.dataset_end()?;
     Ok(())
 }
 
+/// Process properties and geometry of a GeoJSON feature
+pub(crate) fn process_feature<P: FeatureProcessor>(
+    feature: &Feature,
+    idx: usize,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
+    processor.feature_begin(idx as u64)?;
+    if let Some(ref id) = feature.id {
+        process_fid(id, processor)?;
+    }
+    if let Some(ref properties) = feature.properties {
+        for (name, value) in properties {
+            if options.selects_column(name) {
+                check_integer_range(name, value, idx, processor)?;
+            }
+        }
+        processor.properties_begin()?;
+        process_properties(properties, processor, options)?;
+        processor.properties_end()?;
+    }
+    if let Some(ref geometry) = feature.geometry {
+        processor.geometry_begin()?;
+        process_geojson_geom_n(geometry, idx, processor)?;
+        processor.geometry_end()?;
+    }
+    processor.feature_end(idx as u64)
+}
+
+/// Process GeoJSON feature id
+fn process_fid<P: FeatureProcessor>(id: &Id, processor: &mut P) -> Result<()> {
+    match id {
+        Id::String(v) => processor.fid(&ColumnValue::String(v)),
+        Id::Number(v) if v.is_f64() => {
+            processor.fid(&ColumnValue::Double(v.as_f64().unwrap_or_default()))
+        }
+        Id::Number(v) if v.is_i64() => {
+            processor.fid(&ColumnValue::Long(v.as_i64().unwrap_or_default()))
+        }
+        Id::Number(v) => processor.fid(&ColumnValue::ULong(v.as_u64().unwrap_or_default())),
+    }
+}
+
+/// Process CRS of a `crs` member (GeoJSON 2008) or `EPSG:4326`
+///
+/// Unsupported `crs` members are reported as warning.
+fn process_crs<P: FeatureProcessor>(
+    foreign_members: &Option<Map<String, JsonValue>>,
+    processor: &mut P,
+) -> Result<()> {
+    let member = foreign_members
+        .as_ref()
+        .and_then(|members| members.get("crs"));
+    let crs = member
+        .and_then(|crs| crs["properties"]["name"].as_str())
+        .and_then(Crs::from_definition)
+        // GeoJSON coordinates are easting or longitude first, also with EPSG URNs
+        .map(|crs| match crs {
+            Crs::EpsgUrn(code) => Crs::Epsg(code),
+            crs => crs,
+        });
+    match (member, crs) {
+        (_, Some(crs)) => processor.crs(&crs),
+        (Some(member), None) => {
+            processor.warning(&Warning::new(
+                WarningKind::Other,
+                format!("unsupported CRS {}, assuming EPSG:4326", member),
+            ))?;
+            processor.crs(&Crs::wgs84())
+        }
+        (None, None) => processor.crs(&Crs::wgs84()),
+    }
+}
+
+/// Process dataset metadata of a feature collection
+///
+/// Metadata is read from the `title`, `description`, `attribution` and `license`
+/// foreign members and the `bbox` member.
+fn process_metadata<P: FeatureProcessor>(
+    foreign_members: &Option<Map<String, JsonValue>>,
+    bbox: &Option<Vec<f64>>,
+    processor: &mut P,
+) -> Result<()> {
+    let member = |name| {
+        foreign_members
+            .as_ref()
+            .and_then(|members| members.get(name))
+            .and_then(JsonValue::as_str)
+            .map(str::to_string)
+    };
+    let extent = match bbox.as_deref() {
+        Some(&[minx, miny, maxx, maxy]) | Some(&[minx, miny, _, maxx, maxy, _]) => {
+            Some(Extent::new(minx, miny, maxx, maxy))
+        }
+        _ => None,
+    };
+    let metadata = DatasetMetadata {
+        title: member("title"),
+        description: member("description"),
+        attribution: member("attribution"),
+        license: member("license"),
+        extent,
+    };
+    if metadata.is_empty() {
+        return Ok(());
+    }
+    processor.metadata(&metadata)
+}
+
 /// Process top-level GeoJSON items (geometry only)
 fn process_geojson_geom<P: GeomProcessor>(gj: &GeoGeoJson, processor: &mut P) -> Result<()> {
     match *gj {
@@ -197,24 +542,127 @@ fn process_geojson_geom_n<P: GeomProcessor>(
 fn process_properties<P: PropertyProcessor>(
     properties: &Map<String, JsonValue>,
     processor: &mut P,
+    options: &ReadOptions,
 ) -> Result<()> {
-    for (i, (key, value)) in properties.iter().enumerate() {
+    let selected = properties
+        .iter()
+        .filter(|(name, _)| options.selects_column(name));
+    if options.nested == NestedProperties::Flatten {
+        // Flattened names may collide with other properties, e.g. `a.b` and `{"a": {"b": ..}}`
+        let mut leaves = Vec::new();
+        for (name, value) in selected {
+            flatten_property(name.clone(), value, &mut leaves);
+        }
+        options.duplicate_keys.apply(&mut leaves)?;
+        for (i, (name, value)) in leaves.iter().enumerate() {
+            process_property(i, name, value, processor, options)?;
+        }
+    } else {
         // Could we provide a stable property index?
-        match value {
-            JsonValue::String(v) => processor.property(i, &key, &ColumnValue::String(v))?,
-            JsonValue::Number(v) if v.is_f64() => {
-                processor.property(i, &key, &ColumnValue::Double(v.as_f64().unwrap()))?
+        for (i, (name, value)) in selected.enumerate() {
+            process_property(i, name, value, processor, options)?;
+        }
+    }
+    Ok(())
+}
+
+/// Collect scalar values of a nested property with dotted names
+fn flatten_property<'a>(
+    key: String,
+    value: &'a JsonValue,
+    leaves: &mut Vec<(String, &'a JsonValue)>,
+) {
+    match value {
+        JsonValue::Object(obj) => {
+            for (k, v) in obj {
+                flatten_property(format!("{}.{}", key, k), v, leaves);
             }
-            JsonValue::Number(v) if v.is_i64() => {
-                processor.property(i, &key, &ColumnValue::Long(v.as_i64().unwrap()))?
+        }
+        JsonValue::Array(arr) => {
+            for (n, v) in arr.iter().enumerate() {
+                flatten_property(format!("{}.{}", key, n), v, leaves);
             }
-            JsonValue::Number(v) if v.is_u64() => {
-                processor.property(i, &key, &ColumnValue::ULong(v.as_u64().unwrap()))?
+        }
+        _ => leaves.push((key, value)),
+    }
+}
+
+/// Process GeoJSON property
+fn process_property<P: PropertyProcessor>(
+    i: usize,
+    key: &str,
+    value: &JsonValue,
+    processor: &mut P,
+    options: &ReadOptions,
+) -> Result<()> {
+    match value {
+        JsonValue::String(v) => {
+            let mut buf = String::new();
+            processor.property(i, key, &string_value(v, options, &mut buf))?
+        }
+        JsonValue::Number(v) if v.is_i64() => {
+            processor.property(i, key, &ColumnValue::Long(v.as_i64().unwrap_or_default()))?
+        }
+        JsonValue::Number(v) if v.is_u64() => {
+            processor.property(i, key, &ColumnValue::ULong(v.as_u64().unwrap_or_default()))?
+        }
+        JsonValue::Number(v) => {
+            // Integers beyond 64 bits keep their digits with serde_json `arbitrary_precision`
+            let text = v.to_string();
+            if is_integer_literal(&text) {
+                processor.property(i, key, &ColumnValue::BigInt(&text))?
+            } else if let Some(v) = v.as_f64() {
+                processor.property(i, key, &ColumnValue::Double(v))?
+            } else {
+                processor.property(i, key, &ColumnValue::String(&text))?
             }
-            JsonValue::Bool(v) => processor.property(i, &key, &ColumnValue::Bool(*v))?,
-            // Null, Array(Vec<Value>), Object(Map<String, Value>)
-            _ => processor.property(i, &key, &ColumnValue::String(&value.to_string()))?,
-        };
+        }
+        JsonValue::Bool(v) => processor.property(i, key, &ColumnValue::Bool(*v))?,
+        JsonValue::Array(_) | JsonValue::Object(_) => {
+            processor.property(i, key, &ColumnValue::Json(&value.to_string()))?
+        }
+        JsonValue::Null => processor.property(i, key, &ColumnValue::Null)?,
+    };
+    Ok(())
+}
+
+/// Check for an integer literal like `-12345678901234567890123`
+fn is_integer_literal(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.bytes().all(|c| c.is_ascii_digit())
+}
+
+/// Report integers exceeding the 64-bit range, which are read as `f64` by serde_json
+fn check_integer_range<P: FeatureProcessor>(
+    name: &str,
+    value: &JsonValue,
+    idx: usize,
+    processor: &mut P,
+) -> Result<()> {
+    match value {
+        JsonValue::Number(v) if !v.is_i64() && !v.is_u64() => {
+            let f = v.as_f64().unwrap_or_default();
+            let overflow = f >= u64::MAX as f64 || f < i64::MIN as f64;
+            if overflow && f.fract() == 0.0 && !is_integer_literal(&v.to_string()) {
+                let msg = format!(
+                    "integer {} of property `{}` exceeds 64 bits, read as double",
+                    v, name
+                );
+                let warning = Warning::new(WarningKind::CoercedType, msg).feature(idx as u64);
+                processor.warning(&warning)?;
+            }
+        }
+        JsonValue::Array(arr) => {
+            for v in arr {
+                check_integer_range(name, v, idx, processor)?;
+            }
+        }
+        JsonValue::Object(obj) => {
+            for v in obj.values() {
+                check_integer_range(name, v, idx, processor)?;
+            }
+        }
+        _ => {}
     }
     Ok(())
 }
@@ -324,7 +772,7 @@ mod test {
     use super::*;
     use crate::geojson::GeoJsonWriter;
     use crate::wkt::WktWriter;
-    use crate::{ProcessToSvg, ToJson, ToWkt};
+    use crate::ToJson;
     use std::fs::File;
 
     #[test]
@@ -392,6 +840,218 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn property_types() -> Result<()> {
+        let mut geojson = GeoJson(
+            r#"{"type": "Feature", "properties": {"date": "2022-07-21", "tags": ["a","b"], "ts": "2022-07-21T12:55:00Z"}, "geometry": {"type": "Point", "coordinates": [10,20]}}"#,
+        );
+        let options = ReadOptions {
+            parse_datetimes: true,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        geojson.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"date": "2022-07-21", "tags": ["a","b"], "ts": "2022-07-21T12:55:00Z"}, "geometry": {"type": "Point", "coordinates": [10,20]}}]}"#
+        );
+
+        struct DateFinder(bool);
+        impl PropertyProcessor for DateFinder {
+            fn property(&mut self, _i: usize, n: &str, v: &ColumnValue) -> Result<bool> {
+                if n == "date" {
+                    self.0 = v == &ColumnValue::Date("2022-07-21");
+                }
+                Ok(false)
+            }
+        }
+        impl GeomProcessor for DateFinder {}
+        impl FeatureProcessor for DateFinder {}
+        let mut finder = DateFinder(false);
+        geojson.process_with_options(&mut finder, &options)?;
+        assert!(finder.0);
+        geojson.process(&mut finder)?;
+        assert!(!finder.0);
+        Ok(())
+    }
+
+    #[test]
+    fn timezone_roundtrip() -> Result<()> {
+        use crate::{ColumnStatsCollector, ColumnType, OwnedColumnValue, TimezoneFallback};
+
+        let mut geojson = GeoJson(
+            r#"{"type": "Feature", "properties": {"local": "2022-07-21T12:55:00", "utc": "2022-07-21T12:55:00Z", "zoned": "2022-07-21T12:55:00.25-03:30"}, "geometry": {"type": "Point", "coordinates": [10,20]}}"#,
+        );
+        let options = ReadOptions {
+            parse_datetimes: true,
+            timezone_fallback: TimezoneFallback::Utc,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        geojson.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)?;
+        let output = std::str::from_utf8(&out).unwrap();
+        assert_eq!(
+            output,
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"local": "2022-07-21T12:55:00Z", "utc": "2022-07-21T12:55:00Z", "zoned": "2022-07-21T12:55:00.25-03:30"}, "geometry": {"type": "Point", "coordinates": [10,20]}}]}"#
+        );
+
+        // Offsets are kept when reading the output again
+        let mut collector = ColumnStatsCollector::new();
+        GeoJson(output).process_with_options(&mut collector, &options)?;
+        let zoned = collector.column_stats("zoned").unwrap();
+        assert_eq!(zoned.col_type, ColumnType::DateTime);
+        assert_eq!(
+            zoned.min,
+            Some(OwnedColumnValue::DateTime(
+                "2022-07-21T12:55:00.25-03:30".to_string()
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn big_integers() -> Result<()> {
+        use crate::processor::WarningReporter;
+
+        let mut geojson = GeoJson(
+            r#"{"type": "Feature", "properties": {"osm_id": 18446744073709551615, "min": -9223372036854775808, "big": 123456789012345678901234567890, "num": 3.0}, "geometry": {"type": "Point", "coordinates": [10,20]}}"#,
+        );
+        let mut out: Vec<u8> = Vec::new();
+        let mut warnings = Vec::new();
+        geojson.process(&mut WarningReporter::new(
+            GeoJsonWriter::new(&mut out),
+            |w| warnings.push(w.to_string()),
+        ))?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"big": 123456789012345680000000000000, "min": -9223372036854775808, "num": 3, "osm_id": 18446744073709551615}, "geometry": {"type": "Point", "coordinates": [10,20]}}]}"#
+        );
+        assert_eq!(
+            warnings,
+            vec!["feature 0: integer 1.2345678901234568e+29 of property `big` exceeds 64 bits, read as double"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn metadata_roundtrip() -> Result<()> {
+        use crate::processor::XYSwap;
+
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "title": "Lakes", "attribution": "© Swisstopo", "license": "CC-BY-4.0", "bbox": [6.5, 46.0, 9.5, 47.5], "features": [{"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7,46.5]}}]}"#,
+        );
+        let mut out: Vec<u8> = Vec::new();
+        geojson.process(&mut GeoJsonWriter::new(&mut out))?;
+        let output = std::str::from_utf8(&out).unwrap();
+        assert_eq!(
+            output,
+            r#"{
+"type": "FeatureCollection",
+"title": "Lakes",
+"attribution": "© Swisstopo",
+"license": "CC-BY-4.0",
+"bbox": [6.5,46,9.5,47.5],
+"features": [{"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7,46.5]}}]}"#
+        );
+
+        // The extent follows coordinate changes
+        let mut out: Vec<u8> = Vec::new();
+        GeoJson(output).process(&mut XYSwap::new(GeoJsonWriter::new(&mut out)))?;
+        assert!(std::str::from_utf8(&out)
+            .unwrap()
+            .contains(r#""bbox": [46,6.5,47.5,9.5]"#));
+        Ok(())
+    }
+
+    #[test]
+    fn nested_properties() -> Result<()> {
+        let mut geojson = GeoJson(
+            r#"{"type": "Feature", "properties": {"address": {"city": "Bern", "zip": 3000}, "tags": ["a","b"]}, "geometry": null}"#,
+        );
+        let options = ReadOptions {
+            nested: NestedProperties::Flatten,
+            ..Default::default()
+        };
+        let mut out: Vec<u8> = Vec::new();
+        geojson.process_with_options(&mut GeoJsonWriter::new(&mut out), &options)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"address.city": "Bern", "address.zip": 3000, "tags.0": "a", "tags.1": "b"}}]}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys() -> Result<()> {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern", "id": 1, "name": "Berne"}, "geometry": null},
+            {"type": "Feature", "properties": {"a.b": 1, "a": {"b": 2, "c": 3}}, "geometry": null}]}"#;
+        let process = |policy, nested| -> Result<String> {
+            let options = ReadOptions {
+                duplicate_keys: policy,
+                nested,
+                ..Default::default()
+            };
+            let mut out: Vec<u8> = Vec::new();
+            read_geojson_with_options(
+                geojson.as_bytes(),
+                &mut GeoJsonWriter::new(&mut out),
+                &options,
+            )?;
+            Ok(String::from_utf8(out).unwrap())
+        };
+
+        let json = process(DuplicateKeyPolicy::LastWins, NestedProperties::Json)?;
+        assert!(json.contains(r#""properties": {"id": 1, "name": "Berne"}"#));
+        let json = process(DuplicateKeyPolicy::FirstWins, NestedProperties::Json)?;
+        assert!(json.contains(r#""properties": {"id": 1, "name": "Bern"}"#));
+        assert_eq!(
+            process(DuplicateKeyPolicy::Error, NestedProperties::Json)
+                .unwrap_err()
+                .to_string(),
+            "processing feature property: `duplicate property `name` in feature 0`"
+        );
+
+        // Flattened names colliding with other properties (properties are sorted by name)
+        let json = process(DuplicateKeyPolicy::LastWins, NestedProperties::Flatten)?;
+        assert!(json.contains(r#""properties": {"a.b": 1, "a.c": 3}"#));
+        let json = process(DuplicateKeyPolicy::FirstWins, NestedProperties::Flatten)?;
+        assert!(json.contains(r#""properties": {"a.b": 2, "a.c": 3}"#));
+        Ok(())
+    }
+
+    #[test]
+    fn null_properties() -> Result<()> {
+        use crate::OwnedColumnValue;
+        use std::collections::HashMap;
+
+        let geojson = r#"{"type": "Feature", "properties": {"a": null, "b": 1}, "geometry": null}"#;
+        let mut out: Vec<u8> = Vec::new();
+        read_geojson(geojson.as_bytes(), &mut GeoJsonWriter::new(&mut out))?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"a": null, "b": 1}}]}"#
+        );
+
+        let mut props: HashMap<String, OwnedColumnValue> = HashMap::new();
+        let mut map = Map::new();
+        map.insert("a".to_string(), JsonValue::Null);
+        process_properties(&map, &mut props, &ReadOptions::default())?;
+        assert_eq!(props["a"], OwnedColumnValue::Null);
+        assert!(!props.contains_key("b"));
+        Ok(())
+    }
+
     #[test]
     fn from_file() -> Result<()> {
         let f = File::open("tests/data/places.json")?;
@@ -427,7 +1087,10 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "with-svg")]
     fn conversions() -> Result<()> {
+        use crate::{ProcessToSvg, ToWkt};
+
         let geojson = GeoJson(r#"{"type": "Point", "coordinates": [10,20]}"#);
         assert_eq!(geojson.to_wkt().unwrap(), "POINT(10 20)");
 
@@ -444,4 +1107,46 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn crs_member() -> Result<()> {
+        #[derive(Default)]
+        struct CrsCollector(Option<Crs>, Vec<String>);
+        impl GeomProcessor for CrsCollector {}
+        impl PropertyProcessor for CrsCollector {}
+        impl FeatureProcessor for CrsCollector {
+            fn crs(&mut self, crs: &Crs) -> Result<()> {
+                self.0 = Some(crs.clone());
+                Ok(())
+            }
+            fn warning(&mut self, warning: &Warning) -> Result<()> {
+                self.1.push(warning.to_string());
+                Ok(())
+            }
+        }
+
+        let mut collector = CrsCollector::default();
+        let geojson = r#"{"type": "FeatureCollection", "features": [],
+            "crs": {"type": "name", "properties": {"name": "urn:ogc:def:crs:EPSG::2056"}}}"#;
+        read_geojson(geojson.as_bytes(), &mut collector)?;
+        assert_eq!(collector.0, Some(Crs::Epsg(2056)));
+
+        let geojson = r#"{"type": "FeatureCollection", "features": []}"#;
+        read_geojson(geojson.as_bytes(), &mut collector)?;
+        assert_eq!(collector.0, Some(Crs::wgs84()));
+        assert!(collector.1.is_empty());
+
+        let geojson = r#"{"type": "Feature", "properties": null, "geometry": null,
+            "crs": {"type": "name", "properties": {"name": "unknown"}}}"#;
+        read_geojson(geojson.as_bytes(), &mut collector)?;
+        assert_eq!(collector.0, Some(Crs::wgs84()));
+        assert_eq!(
+            collector.1,
+            vec![
+                r#"unsupported CRS {"properties":{"name":"unknown"},"type":"name"}, assuming EPSG:4326"#,
+                "feature 0: skipped feature without geometry and properties"
+            ]
+        );
+        Ok(())
+    }
 }
@@ -1,27 +1,178 @@
 use crate::error::Result;
-use crate::{ColumnValue, CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use crate::write_buffer::{check_finite, format_coord, FidHash, WriteBuffer};
+use crate::{
+    ColumnValue, CoordDimensions, Crs, DatasetMetadata, FeatureProcessor, FidStrategy,
+    GeomProcessor, PropertyProcessor, WriterOptions,
+};
 use std::fmt::Display;
 use std::io::Write;
 
 /// GeoJSON writer.
+///
+/// Property values are mapped as follows:
+/// * Numeric and boolean values are written as JSON numbers and booleans, including `ULong`
+///   and `BigInt` values exceeding the precision of most JSON parsers
+/// * `String`, `DateTime` and `Date` values are written as JSON strings
+/// * `Json` values are written unchanged as JSON value
+/// * `Binary` values are written as hex encoded JSON string
+///
+/// Dataset metadata is written as `bbox` and as `title`, `description`, `attribution`
+/// and `license` members of the feature collection.
+///
+/// Output is written unbuffered, unless an output buffer is set with `buffer_size`.
+/// Buffered output is written at the end of the dataset or with `flush`.
+///
+/// Further options are set with a [GeoJsonWriterBuilder]:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::{FidStrategy, GeozeroDatasource, WriterOptions};
+///
+/// let mut source = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///   {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [1.234567, 2]}}
+/// ]}"#);
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut writer = GeoJsonWriter::builder()
+///     .precision(2)
+///     .write_bbox(true)
+///     .options(WriterOptions::default().with_fid(FidStrategy::FeatureIndex))
+///     .build(&mut out);
+/// source.process(&mut writer).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains(r#"{"type": "Feature", "id": 0, "properties": {}, "geometry": {"type": "Point", "coordinates": [1.23,2]}, "bbox": [1.23,2,1.23,2]}"#));
+/// ```
 pub struct GeoJsonWriter<'a, W: Write> {
     pub dims: CoordDimensions,
-    out: &'a mut W,
+    out: WriteBuffer<'a, W>,
+    precision: Option<usize>,
+    write_bbox: bool,
+    crs: Option<Crs>,
+    fid: FidStrategy,
+    /// Formatted feature id of current feature
+    fid_value: Option<String>,
+    fid_hash: FidHash,
+    /// Bounding box of current feature geometry
+    bbox: Option<[f64; 4]>,
+    /// Next geometry is the top-level geometry of a feature
+    feature_geometry: bool,
+    /// Members of the feature collection may follow
+    collection_header: bool,
+}
+
+/// Builder for [GeoJsonWriter].
+#[derive(Clone, Default, Debug)]
+pub struct GeoJsonWriterBuilder {
+    options: WriterOptions,
+    precision: Option<usize>,
+    write_bbox: bool,
+}
+
+impl GeoJsonWriterBuilder {
+    /// Set shared writer options
+    pub fn options(mut self, options: WriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Maximal number of decimals of coordinates (default: shortest exact representation)
+    pub fn precision(mut self, decimals: usize) -> Self {
+        self.precision = Some(decimals);
+        self
+    }
+    /// Write a `bbox` member for each feature (default: false)
+    pub fn write_bbox(mut self, write_bbox: bool) -> Self {
+        self.write_bbox = write_bbox;
+        self
+    }
+    /// Create writer
+    pub fn build<W: Write>(self, out: &mut W) -> GeoJsonWriter<'_, W> {
+        let mut writer = GeoJsonWriter::new(out).buffer_size(self.options.buffer_size);
+        writer.dims = self.options.dims;
+        writer.precision = self.precision;
+        writer.write_bbox = self.write_bbox;
+        writer.crs = self.options.crs;
+        writer.fid = self.options.fid;
+        writer.out.set_discard(self.options.dry_run);
+        writer
+    }
+}
+
+// Not generic over the output type, which is set by `build`
+impl GeoJsonWriter<'_, Vec<u8>> {
+    /// Writer builder
+    pub fn builder() -> GeoJsonWriterBuilder {
+        GeoJsonWriterBuilder::default()
+    }
 }
 
 impl<'a, W: Write> GeoJsonWriter<'a, W> {
     pub fn new(out: &'a mut W) -> GeoJsonWriter<'a, W> {
         GeoJsonWriter {
             dims: CoordDimensions::default(),
-            out,
+            out: WriteBuffer::new(out),
+            precision: None,
+            write_bbox: false,
+            crs: None,
+            fid: FidStrategy::None,
+            fid_value: None,
+            fid_hash: FidHash::default(),
+            bbox: None,
+            feature_geometry: false,
+            collection_header: false,
+        }
+    }
+    /// Size of output buffer in bytes (default: 0, unbuffered)
+    pub fn buffer_size(mut self, bytes: usize) -> Self {
+        // Nothing is buffered yet
+        let _ = self.out.set_capacity(bytes);
+        self
+    }
+    /// Write buffered output
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+    /// Close feature collection members before the first feature
+    fn features_begin(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.collection_header) {
+            self.out.write_all(
+                br#",
+"features": ["#,
+            )?;
         }
+        Ok(())
     }
     fn comma(&mut self, idx: usize) -> Result<()> {
-        if idx > 0 {
+        // Readers may pass the feature index as index of the feature geometry
+        let feature_geometry = std::mem::take(&mut self.feature_geometry);
+        if idx > 0 && !feature_geometry {
             self.out.write_all(b",")?;
         }
         Ok(())
     }
+    fn write_coord(&mut self, x: f64, y: f64, z: Option<f64>) -> Result<()> {
+        check_finite(&[x, y, z.unwrap_or(0.0)])?;
+        if self.fid == FidStrategy::Hash {
+            self.fid_hash.coord(&[x, y, z.unwrap_or(0.0)]);
+        }
+        if self.write_bbox {
+            let bbox = self.bbox.get_or_insert([x, y, x, y]);
+            bbox[0] = bbox[0].min(x);
+            bbox[1] = bbox[1].min(y);
+            bbox[2] = bbox[2].max(x);
+            bbox[3] = bbox[3].max(y);
+        }
+        write!(
+            self.out,
+            "[{},{}",
+            format_coord(x, self.precision),
+            format_coord(y, self.precision)
+        )?;
+        if let Some(z) = z {
+            write!(self.out, ",{}", format_coord(z, self.precision))?;
+        }
+        self.out.write_all(b"]")?;
+        Ok(())
+    }
 }
 
 impl<W: Write> FeatureProcessor for GeoJsonWriter<'_, W> {
@@ -31,26 +182,76 @@ impl<W: Write> FeatureProcessor for GeoJsonWriter<'_, W> {
 "type": "FeatureCollection""#,
         )?;
         if let Some(name) = name {
-            write!(self.out, ",\n\"name\": \"{}\"", name)?;
+            write!(self.out, ",\n\"name\": {}", quote(name))?;
+        }
+        if let Some(code) = self.crs.as_ref().and_then(Crs::epsg) {
+            write!(
+                self.out,
+                ",\n\"crs\": {{\"type\": \"name\", \"properties\": {{\"name\": \"urn:ogc:def:crs:EPSG::{}\"}}}}",
+                code
+            )?;
+        }
+        self.collection_header = true;
+        Ok(())
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        if !self.collection_header {
+            return Ok(());
+        }
+        let members = [
+            ("title", &metadata.title),
+            ("description", &metadata.description),
+            ("attribution", &metadata.attribution),
+            ("license", &metadata.license),
+        ];
+        for (name, value) in &members {
+            if let Some(value) = value {
+                write!(self.out, ",\n\"{}\": {}", name, quote(value))?;
+            }
+        }
+        if let Some(extent) = &metadata.extent {
+            let coords = [extent.minx, extent.miny, extent.maxx, extent.maxy];
+            let coords: Vec<_> = coords
+                .iter()
+                .map(|&v| format_coord(v, self.precision))
+                .collect();
+            write!(self.out, ",\n\"bbox\": [{}]", coords.join(","))?;
         }
-        self.out.write_all(
-            br#",
-"features": ["#,
-        )?;
         Ok(())
     }
     fn dataset_end(&mut self) -> Result<()> {
+        self.features_begin()?;
         self.out.write_all(b"]}")?;
+        self.out.flush()?;
         Ok(())
     }
     fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.features_begin()?;
         if idx > 0 {
             self.out.write_all(b",\n")?;
         }
         self.out.write_all(br#"{"type": "Feature""#)?;
+        if self.fid == FidStrategy::FeatureIndex {
+            write!(self.out, r#", "id": {}"#, idx)?;
+        }
+        self.fid_value = None;
+        self.fid_hash = FidHash::default();
+        Ok(())
+    }
+    fn fid(&mut self, fid: &ColumnValue) -> Result<()> {
+        if self.fid == FidStrategy::Source {
+            self.fid_value = fid_value(fid);
+        }
         Ok(())
     }
     fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        if self.fid == FidStrategy::Hash {
+            // Quoted, since JSON numbers are often read as 53-bit floats
+            self.fid_value = Some(format!(r#""{:016x}""#, self.fid_hash.finish()));
+        }
+        if let Some(fid) = self.fid_value.take() {
+            write!(self.out, r#", "id": {}"#, fid)?;
+        }
         self.out.write_all(b"}")?;
         Ok(())
     }
@@ -64,9 +265,20 @@ impl<W: Write> FeatureProcessor for GeoJsonWriter<'_, W> {
     }
     fn geometry_begin(&mut self) -> Result<()> {
         self.out.write_all(br#", "geometry": "#)?;
+        self.feature_geometry = true;
         Ok(())
     }
     fn geometry_end(&mut self) -> Result<()> {
+        if let Some(bbox) = self.bbox.take() {
+            write!(
+                self.out,
+                r#", "bbox": [{},{},{},{}]"#,
+                format_coord(bbox[0], self.precision),
+                format_coord(bbox[1], self.precision),
+                format_coord(bbox[2], self.precision),
+                format_coord(bbox[3], self.precision)
+            )?;
+        }
         Ok(())
     }
 }
@@ -77,8 +289,7 @@ impl<W: Write> GeomProcessor for GeoJsonWriter<'_, W> {
     }
     fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
         self.comma(idx)?;
-        self.out.write_all(&format!("[{},{}]", x, y).as_bytes())?;
-        Ok(())
+        self.write_coord(x, y, None)
     }
     fn coordinate(
         &mut self,
@@ -91,12 +302,7 @@ impl<W: Write> GeomProcessor for GeoJsonWriter<'_, W> {
         idx: usize,
     ) -> Result<()> {
         self.comma(idx)?;
-        self.out.write_all(&format!("[{},{}", x, y).as_bytes())?;
-        if let Some(z) = z {
-            self.out.write_all(&format!(",{}", z).as_bytes())?;
-        }
-        self.out.write_all(b"]")?;
-        Ok(())
+        self.write_coord(x, y, z)
     }
     fn point_begin(&mut self, idx: usize) -> Result<()> {
         self.comma(idx)?;
@@ -186,37 +392,73 @@ impl<W: Write> GeomProcessor for GeoJsonWriter<'_, W> {
     }
 }
 
+/// JSON string literal with escaped special characters
+fn quote(v: &str) -> String {
+    serde_json::Value::from(v).to_string()
+}
+
 fn write_num_prop<'a, W: Write>(out: &'a mut W, colname: &str, v: &dyn Display) -> Result<()> {
-    out.write_all(&format!(r#""{}": {}"#, colname, v).as_bytes())?;
+    out.write_all(format!("{}: {}", quote(colname), v).as_bytes())?;
     Ok(())
 }
 
 fn write_str_prop<'a, W: Write>(out: &'a mut W, colname: &str, v: &dyn Display) -> Result<()> {
-    out.write_all(&format!(r#""{}": "{}""#, colname, v).as_bytes())?;
+    out.write_all(format!("{}: {}", quote(colname), quote(&v.to_string())).as_bytes())?;
     Ok(())
 }
 
+fn to_hex(v: &[u8]) -> String {
+    v.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Feature id value as JSON, `None` for values not usable as id
+fn fid_value(value: &ColumnValue) -> Option<String> {
+    match value {
+        ColumnValue::String(v) | ColumnValue::DateTime(v) | ColumnValue::Date(v) => Some(quote(v)),
+        ColumnValue::Binary(v) => Some(quote(&to_hex(v))),
+        ColumnValue::Null => None,
+        ColumnValue::Float(v) if !v.is_finite() => None,
+        ColumnValue::Double(v) if !v.is_finite() => None,
+        _ => Some(value.to_string()),
+    }
+}
+
 impl<W: Write> PropertyProcessor for GeoJsonWriter<'_, W> {
     fn property(&mut self, i: usize, colname: &str, colval: &ColumnValue) -> Result<bool> {
         if i > 0 {
             self.out.write_all(b", ")?;
         }
+        match &self.fid {
+            FidStrategy::Property(name) if name == colname => self.fid_value = fid_value(colval),
+            FidStrategy::Hash => self.fid_hash.property(colname, colval),
+            _ => {}
+        }
         match colval {
-            ColumnValue::Byte(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::UByte(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Bool(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Short(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::UShort(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Int(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::UInt(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Long(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::ULong(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Float(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::Double(v) => write_num_prop(self.out, colname, &v)?,
-            ColumnValue::String(v) => write_str_prop(self.out, colname, &v)?,
-            ColumnValue::Json(_v) => (),
-            ColumnValue::DateTime(v) => write_str_prop(self.out, colname, &v)?,
-            ColumnValue::Binary(_v) => (),
+            ColumnValue::Byte(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::UByte(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Bool(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Short(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::UShort(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Int(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::UInt(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Long(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::ULong(v) => write_num_prop(&mut self.out, colname, &v)?,
+            // JSON has no NaN and infinity
+            ColumnValue::Float(v) if !v.is_finite() => {
+                write_num_prop(&mut self.out, colname, &"null")?
+            }
+            ColumnValue::Double(v) if !v.is_finite() => {
+                write_num_prop(&mut self.out, colname, &"null")?
+            }
+            ColumnValue::Float(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Double(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::BigInt(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::String(v) => write_str_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Json(v) => write_num_prop(&mut self.out, colname, &v)?,
+            ColumnValue::DateTime(v) => write_str_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Date(v) => write_str_prop(&mut self.out, colname, &v)?,
+            ColumnValue::Binary(v) => write_str_prop(&mut self.out, colname, &to_hex(v))?,
+            ColumnValue::Null => write_num_prop(&mut self.out, colname, &"null")?,
         };
         Ok(false)
     }
@@ -226,7 +468,127 @@ impl<W: Write> PropertyProcessor for GeoJsonWriter<'_, W> {
 mod test {
     use super::*;
     use crate::geojson::read_geojson;
-    use crate::ToJson;
+
+    #[test]
+    fn buffered() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "LineString", "coordinates": [[1,1],[2,2],[3,3]]}}]}"#;
+        let mut unbuffered: Vec<u8> = Vec::new();
+        GeoJson(geojson).process(&mut GeoJsonWriter::new(&mut unbuffered))?;
+        let mut out: Vec<u8> = Vec::new();
+        GeoJson(geojson).process(&mut GeoJsonWriter::new(&mut out).buffer_size(16))?;
+        assert_eq!(out, unbuffered);
+        Ok(())
+    }
+
+    #[test]
+    fn builder_options() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {"code": "a", "n": 1}, "geometry": {"type": "LineString", "coordinates": [[1.0001,1],[3,-2]]}}]}"#;
+        let mut out: Vec<u8> = Vec::new();
+        let options = WriterOptions::default()
+            .with_crs(Crs::Epsg(2056))
+            .with_fid(FidStrategy::Property("code".to_string()));
+        GeoJson(geojson).process(
+            &mut GeoJsonWriter::builder()
+                .precision(3)
+                .write_bbox(true)
+                .options(options)
+                .build(&mut out),
+        )?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"crs": {"type": "name", "properties": {"name": "urn:ogc:def:crs:EPSG::2056"}},
+"features": [{"type": "Feature", "properties": {"code": "a", "n": 1}, "geometry": {"type": "LineString", "coordinates": [[1,1],[3,-2]]}, "bbox": [1,-2,3,1], "id": "a"}]}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fid_strategies() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::{FidStability, GeozeroDatasource};
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "id": "ch-1", "properties": {"n": 1}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+            {"type": "Feature", "properties": {"n": 1}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+            {"type": "Feature", "id": 7, "properties": {"n": 2}, "geometry": {"type": "Point", "coordinates": [1, 1]}}]}"#;
+        assert_eq!(GeoJson(geojson).fid_stability(), FidStability::Optional);
+        let ids = |fid: FidStrategy| -> Result<Vec<Option<String>>> {
+            let mut out: Vec<u8> = Vec::new();
+            let options = WriterOptions::default().with_fid(fid);
+            GeoJson(geojson)
+                .process(&mut GeoJsonWriter::builder().options(options).build(&mut out))?;
+            let json: serde_json::Value = serde_json::from_slice(&out)?;
+            Ok(json["features"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|f| f.get("id").map(|id| id.to_string()))
+                .collect())
+        };
+
+        assert_eq!(
+            ids(FidStrategy::Source)?,
+            vec![Some(r#""ch-1""#.to_string()), None, Some("7".to_string())]
+        );
+        assert_eq!(
+            ids(FidStrategy::FeatureIndex)?,
+            vec![
+                Some("0".to_string()),
+                Some("1".to_string()),
+                Some("2".to_string())
+            ]
+        );
+        let hashes = ids(FidStrategy::Hash)?;
+        assert_eq!(hashes[0], hashes[1]);
+        assert_ne!(hashes[0], hashes[2]);
+        assert_eq!(hashes[0].as_ref().map(String::len), Some(18));
+        Ok(())
+    }
+
+    #[test]
+    fn escaped_properties() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {"na\"me": "a \"b\"\nc\\"}, "geometry": {"type": "Point", "coordinates": [1,1]}}]}"#;
+        let mut out: Vec<u8> = Vec::new();
+        GeoJson(geojson).process(&mut GeoJsonWriter::new(&mut out))?;
+        let out = std::str::from_utf8(&out).unwrap();
+        assert!(out.contains(r#"{"na\"me": "a \"b\"\nc\\"}"#));
+        let json: serde_json::Value = serde_json::from_str(out).unwrap();
+        assert_eq!(json["features"][0]["properties"]["na\"me"], "a \"b\"\nc\\");
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite() -> Result<()> {
+        use crate::ColumnValue;
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut writer = GeoJsonWriter::new(&mut out);
+        writer.point_begin(0)?;
+        assert!(writer.xy(f64::INFINITY, 1.0, 0).is_err());
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut writer = GeoJsonWriter::new(&mut out);
+        writer.feature_begin(0)?;
+        writer.properties_begin()?;
+        writer.property(0, "a", &ColumnValue::Double(f64::NAN))?;
+        writer.property(1, "b", &ColumnValue::Float(f32::NEG_INFINITY))?;
+        writer.properties_end()?;
+        writer.feature_end(0)?;
+        let json = std::str::from_utf8(&out).unwrap();
+        assert!(json.contains(r#"{"a": null, "b": null}"#), "{}", json);
+        Ok(())
+    }
 
     #[test]
     fn geometries() -> Result<()> {
@@ -316,7 +678,29 @@ mod test {
     }
 
     #[test]
+    fn multiple_features() -> Result<()> {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"id": 1}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+            {"type": "Feature", "properties": {"id": 2}, "geometry": {"type": "LineString", "coordinates": [[1, 1], [2, 2]]}}
+        ]}"#;
+        let mut out: Vec<u8> = Vec::new();
+        read_geojson(geojson.as_bytes(), &mut GeoJsonWriter::new(&mut out))?;
+        let jsonout = std::str::from_utf8(&out).unwrap();
+        assert_eq!(
+            jsonout,
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"id": 1}, "geometry": {"type": "Point", "coordinates": [1,1]}},
+{"type": "Feature", "properties": {"id": 2}, "geometry": {"type": "LineString", "coordinates": [[1,1],[2,2]]}}]}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-geo")]
     fn conversions() {
+        use crate::ToJson;
+
         let geom: geo_types::Geometry<f64> = geo_types::Point::new(10.0, 20.0).into();
         assert_eq!(
             &geom.to_json().unwrap(),
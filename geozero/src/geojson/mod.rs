@@ -1,7 +1,9 @@
 //! GeoJSON conversions.
+pub(crate) mod geojson_line_reader;
 pub(crate) mod geojson_reader;
 pub(crate) mod geojson_writer;
 
+pub use geojson_line_reader::*;
 pub use geojson_reader::*;
 pub use geojson_writer::*;
 
@@ -11,6 +13,17 @@ pub(crate) mod conversion {
     use crate::{GeozeroDatasource, GeozeroGeometry};
 
     /// Convert to GeoJSON.
+    ///
+    /// # Usage example:
+    ///
+    /// Convert a WKT geometry to GeoJSON:
+    ///
+    /// ```
+    /// use geozero::{ToJson, wkt::WktStr};
+    ///
+    /// let wkt = WktStr("POINT(10 -20)");
+    /// assert_eq!(wkt.to_json().unwrap(), r#"{"type": "Point", "coordinates": [10,-20]}"#);
+    /// ```
     pub trait ToJson {
         /// Convert to GeoJSON String.
         fn to_json(&self) -> Result<String>;
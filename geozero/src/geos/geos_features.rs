@@ -0,0 +1,190 @@
+use crate::error::{GeozeroError, Result};
+use crate::geos::GeosWriter;
+use crate::{
+    ColumnValue, FeatureAccess, FeatureProcessor, FeatureProperties, GeomProcessor,
+    GeozeroDatasource, GeozeroGeometry, OwnedColumnValue, PropertyProcessor,
+};
+use geos::Geometry as GGeometry;
+
+/// Feature with a GEOS geometry.
+pub struct GeosFeature<'a> {
+    pub geometry: Option<GGeometry<'a>>,
+    pub properties: Vec<(String, OwnedColumnValue)>,
+}
+
+impl FeatureProperties for GeosFeature<'_> {
+    fn process_properties<P: PropertyProcessor>(&self, processor: &mut P) -> Result<bool> {
+        for (idx, (name, value)) in self.properties.iter().enumerate() {
+            if processor.property(idx, name, &value.as_column_value())? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl GeozeroGeometry for GeosFeature<'_> {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        match &self.geometry {
+            Some(geometry) => geometry.process_geom(processor),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FeatureAccess for GeosFeature<'_> {}
+
+/// Features with GEOS geometries.
+///
+/// Collects the features of a datasource as processor and passes them on as datasource,
+/// e.g. after applying GEOS predicates or overlay operations.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::geos::GeosFeatureCollection;
+/// use geozero::GeozeroDatasource;
+/// use geos::Geom;
+///
+/// let mut collection = GeosFeatureCollection::new();
+/// GeoJson(geojson).process(&mut collection)?;
+/// for feature in &mut collection.features {
+///     feature.geometry = feature.geometry.as_ref().map(|g| g.buffer(1.0, 8)).transpose()?;
+/// }
+/// collection.process(&mut GeoJsonWriter::new(&mut out))?;
+/// ```
+pub struct GeosFeatureCollection<'a> {
+    pub features: Vec<GeosFeature<'a>>,
+    /// Writer of current feature geometry
+    writer: Option<GeosWriter<'a>>,
+}
+
+impl<'a> GeosFeatureCollection<'a> {
+    pub fn new() -> Self {
+        GeosFeatureCollection {
+            features: Vec::new(),
+            writer: None,
+        }
+    }
+    fn current(&mut self) -> Result<&mut GeosFeature<'a>> {
+        self.features
+            .last_mut()
+            .ok_or_else(|| GeozeroError::Feature("Feature missing".to_string()))
+    }
+    fn writer(&mut self) -> Result<&mut GeosWriter<'a>> {
+        self.writer
+            .as_mut()
+            .ok_or_else(|| GeozeroError::Geometry("Geometry outside of feature".to_string()))
+    }
+}
+
+impl Default for GeosFeatureCollection<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeozeroDatasource for GeosFeatureCollection<'_> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        processor.dataset_begin(None)?;
+        for (idx, feature) in self.features.iter().enumerate() {
+            feature.process(processor, idx as u64)?;
+        }
+        processor.dataset_end()
+    }
+}
+
+impl PropertyProcessor for GeosFeatureCollection<'_> {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.current()?
+            .properties
+            .push((name.to_string(), value.into()));
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for GeosFeatureCollection<'_> {
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.features.push(GeosFeature {
+            geometry: None,
+            properties: Vec::new(),
+        });
+        Ok(())
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.writer = Some(GeosWriter::new());
+        Ok(())
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        let writer = self.writer.take();
+        self.current()?.geometry = writer.map(GeosWriter::into_geometry);
+        Ok(())
+    }
+}
+
+/// Forward geometry calls to the writer of the current feature
+macro_rules! forward_to_writer {
+    ($( $method:ident($($arg:ident: $ty:ty),*); )*) => {
+        $(
+            fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
+                self.writer()?.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+impl GeomProcessor for GeosFeatureCollection<'_> {
+    forward_to_writer! {
+        xy(x: f64, y: f64, idx: usize);
+        point_begin(idx: usize);
+        point_end(idx: usize);
+        multipoint_begin(size: usize, idx: usize);
+        multipoint_end(idx: usize);
+        linestring_begin(tagged: bool, size: usize, idx: usize);
+        linestring_end(tagged: bool, idx: usize);
+        multilinestring_begin(size: usize, idx: usize);
+        multilinestring_end(idx: usize);
+        polygon_begin(tagged: bool, size: usize, idx: usize);
+        polygon_end(tagged: bool, idx: usize);
+        multipolygon_begin(size: usize, idx: usize);
+        multipolygon_end(idx: usize);
+        geometrycollection_begin(size: usize, idx: usize);
+        geometrycollection_end(idx: usize);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::{GeoJson, GeoJsonWriter};
+    use geos::Geom;
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+            {"type": "Feature", "properties": {"name": "b"}, "geometry": {"type": "Point", "coordinates": [5, 5]}}
+        ]}"#,
+        );
+        let mut collection = GeosFeatureCollection::new();
+        geojson.process(&mut collection)?;
+        assert_eq!(collection.features.len(), 2);
+
+        let area = GGeometry::new_from_wkt("POLYGON ((0 0, 0 2, 2 2, 2 0, 0 0))")?;
+        collection.features.retain(|feature| {
+            feature
+                .geometry
+                .as_ref()
+                .map(|geometry| area.contains(geometry).unwrap_or(false))
+                .unwrap_or(false)
+        });
+        let mut out: Vec<u8> = Vec::new();
+        collection.process(&mut GeoJsonWriter::new(&mut out))?;
+        let json = std::str::from_utf8(&out).unwrap();
+        assert!(json.contains(r#""name": "a""#) && !json.contains(r#""name": "b""#));
+        Ok(())
+    }
+}
@@ -36,7 +36,7 @@ pub fn process_geom<P: GeomProcessor>(ggeom: &GGeometry, processor: &mut P) -> R
     process_geom_n(ggeom, 0, processor)
 }
 
-fn process_geom_n<'a, P: GeomProcessor, G: Geom<'a>>(
+pub(crate) fn process_geom_n<'a, P: GeomProcessor, G: Geom<'a>>(
     ggeom: &G,
     idx: usize,
     processor: &mut P,
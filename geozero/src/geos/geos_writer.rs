@@ -9,6 +9,8 @@ pub struct GeosWriter<'a> {
     cs: Vec<CoordSeq<'a>>,
     // Polygons or MultiPolygons
     polys: Vec<GGeometry<'a>>,
+    // Stack of in-progress (potentially nested) GeometryCollections
+    collections: Vec<Vec<GGeometry<'a>>>,
 }
 
 impl<'a> GeosWriter<'a> {
@@ -17,6 +19,7 @@ impl<'a> GeosWriter<'a> {
             geom: GGeometry::create_empty_point().unwrap(),
             cs: Vec::new(),
             polys: Vec::new(),
+            collections: Vec::new(),
         }
     }
     fn add_coord_seq(&mut self, len: usize) -> Result<()> {
@@ -27,6 +30,18 @@ impl<'a> GeosWriter<'a> {
     pub fn geometry(&self) -> &GGeometry<'a> {
         &self.geom
     }
+    /// Consume writer and return generated geometry
+    pub fn into_geometry(self) -> GGeometry<'a> {
+        self.geom
+    }
+    fn finish_geometry(&mut self, geom: GGeometry<'a>) {
+        // Add the geometry to an in-progress collection
+        if let Some(collection) = self.collections.last_mut() {
+            collection.push(geom);
+        } else {
+            self.geom = geom;
+        }
+    }
 }
 
 impl GeomProcessor for GeosWriter<'_> {
@@ -50,7 +65,7 @@ impl GeomProcessor for GeosWriter<'_> {
             .cs
             .pop()
             .ok_or_else(|| GeozeroError::Geometry("CoordSeq missing".to_string()))?;
-        self.geom = GGeometry::create_point(cs)?;
+        self.finish_geometry(GGeometry::create_point(cs)?);
         Ok(())
     }
     fn multipoint_begin(&mut self, size: usize, _idx: usize) -> Result<()> {
@@ -67,13 +82,10 @@ impl GeomProcessor for GeosWriter<'_> {
         let size = cs.size()?;
         let ggpts = (0..size)
             .map(|i| {
-                GGeometry::create_point(
-                    CoordSeq::new_from_vec(&[&[cs.get_x(i).unwrap(), cs.get_y(i).unwrap()]])
-                        .unwrap(),
-                )
+                GGeometry::create_point(CoordSeq::new_from_vec(&[&[cs.get_x(i)?, cs.get_y(i)?]])?)
             })
             .collect::<GResult<Vec<GGeometry>>>()?;
-        self.geom = GGeometry::create_multipoint(ggpts)?;
+        self.finish_geometry(GGeometry::create_multipoint(ggpts)?);
         Ok(())
     }
     fn linestring_begin(&mut self, tagged: bool, size: usize, _idx: usize) -> Result<()> {
@@ -89,7 +101,7 @@ impl GeomProcessor for GeosWriter<'_> {
                 .cs
                 .pop()
                 .ok_or_else(|| GeozeroError::Geometry("CoordSeq missing".to_string()))?;
-            self.geom = GGeometry::create_line_string(cs)?;
+            self.finish_geometry(GGeometry::create_line_string(cs)?);
         }
         Ok(())
     }
@@ -103,7 +115,7 @@ impl GeomProcessor for GeosWriter<'_> {
             .drain(..)
             .map(|cs| GGeometry::create_line_string(cs))
             .collect::<GResult<Vec<GGeometry>>>()?;
-        self.geom = GGeometry::create_multiline_string(gglines)?;
+        self.finish_geometry(GGeometry::create_multiline_string(gglines)?);
         Ok(())
     }
     fn polygon_begin(&mut self, _tagged: bool, size: usize, _idx: usize) -> Result<()> {
@@ -124,7 +136,7 @@ impl GeomProcessor for GeosWriter<'_> {
             .collect::<GResult<Vec<GGeometry>>>()?;
         let gpoly = GGeometry::create_polygon(exterior_ring, interiors)?;
         if tagged {
-            self.geom = gpoly;
+            self.finish_geometry(gpoly);
         } else {
             self.polys.push(gpoly)
         }
@@ -135,7 +147,19 @@ impl GeomProcessor for GeosWriter<'_> {
         Ok(())
     }
     fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
-        self.geom = GGeometry::create_multipolygon(std::mem::take(&mut self.polys))?;
+        let gmpoly = GGeometry::create_multipolygon(std::mem::take(&mut self.polys))?;
+        self.finish_geometry(gmpoly);
+        Ok(())
+    }
+    fn geometrycollection_begin(&mut self, size: usize, _idx: usize) -> Result<()> {
+        self.collections.push(Vec::with_capacity(size));
+        Ok(())
+    }
+    fn geometrycollection_end(&mut self, _idx: usize) -> Result<()> {
+        let geoms = self.collections.pop().ok_or_else(|| {
+            GeozeroError::Geometry("Unexpected geometry collection end".to_string())
+        })?;
+        self.finish_geometry(GGeometry::create_geometry_collection(geoms)?);
         Ok(())
     }
 }
@@ -214,13 +238,15 @@ mod test {
         assert_eq!(geos.to_wkt().unwrap(), wkt);
     }
 
-    // #[test]
-    // fn geometry_collection_geom() {
-    //     let geojson = GeoJson(r#"{"type": "Point", "coordinates": [1, 1]}"#);
-    //     let wkt = "GEOMETRYCOLLECTION(POINT(1 1), LINESTRING(1 1, 2 2))";
-    //     let geos = geojson.to_geos().unwrap();
-    //     assert_eq!(geos.to_wkt().unwrap(), wkt);
-    // }
+    #[test]
+    fn geometry_collection_geom() {
+        let geojson = GeoJson(
+            r#"{"type": "GeometryCollection", "geometries": [{"type": "Point", "coordinates": [1, 1]}, {"type": "LineString", "coordinates": [[1, 1], [2, 2]]}]}"#,
+        );
+        let wkt = "GEOMETRYCOLLECTION (POINT (1.0000000000000000 1.0000000000000000), LINESTRING (1.0000000000000000 1.0000000000000000, 2.0000000000000000 2.0000000000000000))";
+        let geos = geojson.to_geos().unwrap();
+        assert_eq!(geos.to_wkt().unwrap(), wkt);
+    }
 
     #[test]
     #[cfg(feature = "with-geo")]
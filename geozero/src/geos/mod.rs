@@ -1,7 +1,9 @@
 //! GEOS conversions.
+pub(crate) mod geos_features;
 pub(crate) mod geos_reader;
 pub(crate) mod geos_writer;
 
+pub use geos_features::*;
 pub use geos_reader::*;
 pub use geos_writer::*;
 
@@ -26,7 +26,11 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! The CRS of a geometry table can be read with [table_crs].
 
 mod geopackage;
+mod srs;
 
 pub use geopackage::*;
+pub use srs::*;
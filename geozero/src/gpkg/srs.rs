@@ -0,0 +1,32 @@
+use crate::error::{GeozeroError, Result};
+use crate::Crs;
+use sqlx::sqlite::Sqlite;
+
+/// Read CRS of geometry table `table` from `gpkg_spatial_ref_sys`.
+///
+/// Returns `None` for unknown tables and undefined spatial reference systems.
+pub async fn table_crs<'c, E>(executor: E, table: &str) -> Result<Option<Crs>>
+where
+    E: sqlx::Executor<'c, Database = Sqlite>,
+{
+    let srs: Option<(i32, String, i32, String)> = sqlx::query_as(
+        "SELECT srs.srs_id, srs.organization, srs.organization_coordsys_id, srs.definition
+         FROM gpkg_geometry_columns gc
+         JOIN gpkg_spatial_ref_sys srs ON gc.srs_id = srs.srs_id
+         WHERE gc.table_name = ?",
+    )
+    .bind(table)
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+    let crs = match srs {
+        // Undefined cartesian and geographic SRS
+        Some((srs_id, ..)) if srs_id <= 0 => None,
+        Some((_, organization, code, _)) if organization.eq_ignore_ascii_case("EPSG") => {
+            Some(Crs::Epsg(code))
+        }
+        Some((_, _, _, definition)) => Crs::from_definition(&definition),
+        None => None,
+    };
+    Ok(crs)
+}
@@ -0,0 +1,10 @@
+//! Spatial indexes.
+//!
+//! The [PackedRTree] is a static R-tree of feature bounding boxes sorted along a
+//! Hilbert curve, serialized in the layout of the [FlatGeobuf](https://flatgeobuf.org)
+//! spatial index. It can be built in memory, written to disk and queried directly
+//! on an encoded byte buffer.
+mod packed_rtree;
+
+pub(crate) use packed_rtree::hilbert;
+pub use packed_rtree::*;
@@ -0,0 +1,420 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::Extent;
+use std::convert::TryInto;
+use std::io::Write;
+use std::ops::Range;
+
+/// Bounding box of a node with the offset of its data
+///
+/// Leaf nodes contain the offset of a feature, e.g. a byte offset in a data file,
+/// inner nodes the index of their first child node.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NodeItem {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub offset: u64,
+}
+
+impl NodeItem {
+    /// Size of a serialized node in bytes
+    pub const SIZE: usize = 40;
+
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64, offset: u64) -> Self {
+        NodeItem {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            offset,
+        }
+    }
+    /// Node of `extent`
+    pub fn from_extent(extent: &Extent, offset: u64) -> Self {
+        NodeItem::new(extent.minx, extent.miny, extent.maxx, extent.maxy, offset)
+    }
+    /// Empty bounding box, neutral element of [expand](NodeItem::expand)
+    pub fn empty(offset: u64) -> Self {
+        NodeItem::new(
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+            offset,
+        )
+    }
+    /// Expand bounding box to include `other`
+    pub fn expand(&mut self, other: &NodeItem) {
+        self.min_x = self.min_x.min(other.min_x);
+        self.min_y = self.min_y.min(other.min_y);
+        self.max_x = self.max_x.max(other.max_x);
+        self.max_y = self.max_y.max(other.max_y);
+    }
+    /// Bounding boxes intersect or touch
+    pub fn intersects(&self, other: &NodeItem) -> bool {
+        self.min_x <= other.max_x
+            && self.min_y <= other.max_y
+            && self.max_x >= other.min_x
+            && self.max_y >= other.min_y
+    }
+    fn write<W: Write>(&self, out: &mut W) -> Result<()> {
+        out.write_all(&self.min_x.to_le_bytes())?;
+        out.write_all(&self.min_y.to_le_bytes())?;
+        out.write_all(&self.max_x.to_le_bytes())?;
+        out.write_all(&self.max_y.to_le_bytes())?;
+        out.write_all(&self.offset.to_le_bytes())?;
+        Ok(())
+    }
+    /// Read node `idx` of serialized index
+    fn read(data: &[u8], idx: usize) -> Result<Self> {
+        let node = data
+            .get(idx * Self::SIZE..(idx + 1) * Self::SIZE)
+            .ok_or(GeozeroError::GeometryIndex)?;
+        let f64_at = |pos: usize| f64::from_le_bytes(node[pos..pos + 8].try_into().unwrap());
+        Ok(NodeItem {
+            min_x: f64_at(0),
+            min_y: f64_at(8),
+            max_x: f64_at(16),
+            max_y: f64_at(24),
+            offset: u64::from_le_bytes(node[32..40].try_into().unwrap()),
+        })
+    }
+}
+
+/// Distance of a cell along a Hilbert curve covering 2^16 x 2^16 cells
+pub(crate) fn hilbert(mut x: u32, mut y: u32) -> u64 {
+    const N: u32 = 1 << 16;
+    let mut d = 0;
+    let mut s = N / 2;
+    while s > 0 {
+        let rx = (x & s > 0) as u32;
+        let ry = (y & s > 0) as u32;
+        d += (s as u64) * (s as u64) * ((3 * rx) ^ ry) as u64;
+        // Rotate quadrant
+        if ry == 0 {
+            if rx == 1 {
+                x = N - 1 - x;
+                y = N - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s /= 2;
+    }
+    d
+}
+
+/// Sort `items` along a Hilbert curve through the bounding box centers within `extent`
+pub fn hilbert_sort(items: &mut [NodeItem], extent: &NodeItem) {
+    let cell = |value: f64, min: f64, size: f64| {
+        if size > 0.0 {
+            ((value - min) / size * (u16::MAX as f64)) as u32
+        } else {
+            0
+        }
+    };
+    let width = extent.max_x - extent.min_x;
+    let height = extent.max_y - extent.min_y;
+    items.sort_by_cached_key(|item| {
+        hilbert(
+            cell((item.min_x + item.max_x) / 2.0, extent.min_x, width),
+            cell((item.min_y + item.max_y) / 2.0, extent.min_y, height),
+        )
+    });
+}
+
+/// Ranges of node indices per level, leaves first
+fn level_bounds(num_items: usize, node_size: u16) -> Vec<Range<usize>> {
+    let node_size = node_size as usize;
+    let mut n = num_items;
+    let mut num_nodes = n;
+    let mut level_num_nodes = vec![n];
+    // At least one level above the leaves
+    loop {
+        n = (n as f64 / node_size as f64).ceil() as usize;
+        num_nodes += n;
+        level_num_nodes.push(n);
+        if n == 1 {
+            break;
+        }
+    }
+    // Root level comes first in the node array
+    let mut end = num_nodes;
+    level_num_nodes
+        .into_iter()
+        .map(|size| {
+            let start = end - size;
+            end = start;
+            start..start + size
+        })
+        .collect()
+}
+
+fn check_params(num_items: usize, node_size: u16) -> Result<()> {
+    if num_items == 0 || node_size < 2 {
+        return Err(GeozeroError::GeometryIndex);
+    }
+    Ok(())
+}
+
+/// Leaf found by a bounding box query
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SearchResultItem {
+    /// Offset stored in the leaf node
+    pub offset: u64,
+    /// Position of the leaf in Hilbert order
+    pub index: usize,
+}
+
+/// Bounding box query on nodes returned by `node`
+fn search_nodes<F>(
+    num_items: usize,
+    node_size: u16,
+    bbox: &NodeItem,
+    mut node: F,
+) -> Result<Vec<SearchResultItem>>
+where
+    F: FnMut(usize) -> Result<NodeItem>,
+{
+    let level_bounds = level_bounds(num_items, node_size);
+    let leaf_start = level_bounds[0].start;
+    let mut results = Vec::new();
+    // Node index and level of nodes to visit
+    let mut queue = vec![(0, level_bounds.len() - 1)];
+    while let Some((node_idx, level)) = queue.pop() {
+        let end = (node_idx + node_size as usize).min(level_bounds[level].end);
+        for pos in node_idx..end {
+            let item = node(pos)?;
+            if !bbox.intersects(&item) {
+                continue;
+            }
+            if pos >= leaf_start {
+                results.push(SearchResultItem {
+                    offset: item.offset,
+                    index: pos - leaf_start,
+                });
+            } else {
+                let child = item.offset as usize;
+                if child >= level_bounds[level - 1].end {
+                    return Err(GeozeroError::GeometryIndex);
+                }
+                queue.push((child, level - 1));
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Static R-tree of bounding boxes packed in Hilbert order.
+///
+/// Nodes are stored level by level starting with the root, each inner node
+/// referencing its first child. The serialized tree is the spatial index of
+/// [FlatGeobuf](https://flatgeobuf.org) files, it can be queried without
+/// deserialization with [PackedRTree::search_buf].
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::index::{NodeItem, PackedRTree};
+///
+/// let items = vec![
+///     NodeItem::new(7.44, 46.95, 7.44, 46.95, 0),
+///     NodeItem::new(2.35, 48.86, 2.35, 48.86, 1),
+///     NodeItem::new(-0.13, 51.51, -0.13, 51.51, 2),
+/// ];
+/// let tree = PackedRTree::build(items, PackedRTree::DEFAULT_NODE_SIZE).unwrap();
+/// let mut data: Vec<u8> = Vec::new();
+/// tree.write(&mut data).unwrap();
+///
+/// let bbox = NodeItem::new(0.0, 45.0, 10.0, 50.0, 0);
+/// let found = PackedRTree::search_buf(&data, 3, PackedRTree::DEFAULT_NODE_SIZE, &bbox).unwrap();
+/// let mut offsets: Vec<u64> = found.iter().map(|item| item.offset).collect();
+/// offsets.sort();
+/// assert_eq!(offsets, vec![0, 1]);
+/// ```
+pub struct PackedRTree {
+    num_items: usize,
+    node_size: u16,
+    nodes: Vec<NodeItem>,
+}
+
+impl PackedRTree {
+    /// Default number of children per node
+    pub const DEFAULT_NODE_SIZE: u16 = 16;
+
+    /// Build tree of `items`, which are sorted in Hilbert order
+    pub fn build(mut items: Vec<NodeItem>, node_size: u16) -> Result<Self> {
+        let mut extent = NodeItem::empty(0);
+        for item in &items {
+            extent.expand(item);
+        }
+        hilbert_sort(&mut items, &extent);
+        Self::build_sorted(&items, node_size)
+    }
+    /// Build tree of `items` in given order
+    ///
+    /// Used when items are already sorted, e.g. with [hilbert_sort], and features
+    /// are stored in the same order.
+    pub fn build_sorted(items: &[NodeItem], node_size: u16) -> Result<Self> {
+        check_params(items.len(), node_size)?;
+        let level_bounds = level_bounds(items.len(), node_size);
+        let num_nodes = level_bounds[0].end;
+        let mut nodes = vec![NodeItem::empty(0); num_nodes];
+        nodes[level_bounds[0].clone()].copy_from_slice(items);
+        for level in 0..level_bounds.len() - 1 {
+            let children = level_bounds[level].clone();
+            let parents = level_bounds[level + 1].clone();
+            for (parent, first) in parents.zip(children.clone().step_by(node_size as usize)) {
+                let mut node = NodeItem::empty(first as u64);
+                let last = (first + node_size as usize).min(children.end);
+                for child in &nodes[first..last] {
+                    node.expand(child);
+                }
+                nodes[parent] = node;
+            }
+        }
+        Ok(PackedRTree {
+            num_items: items.len(),
+            node_size,
+            nodes,
+        })
+    }
+    /// Read tree from serialized index
+    pub fn from_buf(data: &[u8], num_items: usize, node_size: u16) -> Result<Self> {
+        check_params(num_items, node_size)?;
+        let num_nodes = level_bounds(num_items, node_size)[0].end;
+        let nodes = (0..num_nodes)
+            .map(|idx| NodeItem::read(data, idx))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PackedRTree {
+            num_items,
+            node_size,
+            nodes,
+        })
+    }
+    /// Size in bytes of a serialized tree
+    pub fn index_size(num_items: usize, node_size: u16) -> usize {
+        if check_params(num_items, node_size).is_err() {
+            return 0;
+        }
+        level_bounds(num_items, node_size)[0].end * NodeItem::SIZE
+    }
+    pub fn num_items(&self) -> usize {
+        self.num_items
+    }
+    pub fn node_size(&self) -> u16 {
+        self.node_size
+    }
+    /// Bounding box of all items
+    pub fn extent(&self) -> NodeItem {
+        self.nodes[0]
+    }
+    /// Leaf nodes in tree order
+    pub fn items(&self) -> &[NodeItem] {
+        &self.nodes[self.nodes.len() - self.num_items..]
+    }
+    /// Serialize tree
+    pub fn write<W: Write>(&self, out: &mut W) -> Result<()> {
+        for node in &self.nodes {
+            node.write(out)?;
+        }
+        Ok(())
+    }
+    /// Leaves intersecting `bbox`
+    pub fn search(&self, bbox: &NodeItem) -> Result<Vec<SearchResultItem>> {
+        search_nodes(self.num_items, self.node_size, bbox, |idx| {
+            self.nodes
+                .get(idx)
+                .copied()
+                .ok_or(GeozeroError::GeometryIndex)
+        })
+    }
+    /// Leaves intersecting `bbox`, reading nodes directly from serialized index
+    pub fn search_buf(
+        data: &[u8],
+        num_items: usize,
+        node_size: u16,
+        bbox: &NodeItem,
+    ) -> Result<Vec<SearchResultItem>> {
+        check_params(num_items, node_size)?;
+        search_nodes(num_items, node_size, bbox, |idx| NodeItem::read(data, idx))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(n: usize) -> Vec<NodeItem> {
+        (0..n * n)
+            .map(|i| {
+                let (x, y) = ((i % n) as f64, (i / n) as f64);
+                NodeItem::new(x, y, x + 0.5, y + 0.5, i as u64)
+            })
+            .collect()
+    }
+
+    fn offsets(results: Vec<SearchResultItem>) -> Vec<u64> {
+        let mut offsets: Vec<u64> = results.iter().map(|item| item.offset).collect();
+        offsets.sort_unstable();
+        offsets
+    }
+
+    #[test]
+    fn level_layout() {
+        assert_eq!(level_bounds(1, 16), vec![1..2, 0..1]);
+        assert_eq!(level_bounds(20, 4), vec![8..28, 3..8, 1..3, 0..1]);
+        assert_eq!(PackedRTree::index_size(20, 4), 28 * NodeItem::SIZE);
+        assert_eq!(PackedRTree::index_size(0, 16), 0);
+    }
+
+    #[test]
+    fn build_and_search() -> Result<()> {
+        let tree = PackedRTree::build(grid(10), 4)?;
+        assert_eq!(tree.num_items(), 100);
+        let extent = tree.extent();
+        assert_eq!(
+            (extent.min_x, extent.min_y, extent.max_x, extent.max_y),
+            (0.0, 0.0, 9.5, 9.5)
+        );
+
+        let bbox = NodeItem::new(2.2, 3.2, 3.7, 4.2, 0);
+        assert_eq!(offsets(tree.search(&bbox)?), vec![32, 33, 42, 43]);
+        // Search result index refers to position in tree order
+        for item in tree.search(&bbox)? {
+            assert_eq!(tree.items()[item.index].offset, item.offset);
+        }
+        let outside = NodeItem::new(20.0, 20.0, 21.0, 21.0, 0);
+        assert!(tree.search(&outside)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn serialization() -> Result<()> {
+        let tree = PackedRTree::build(grid(7), PackedRTree::DEFAULT_NODE_SIZE)?;
+        let mut data = Vec::new();
+        tree.write(&mut data)?;
+        assert_eq!(
+            data.len(),
+            PackedRTree::index_size(49, PackedRTree::DEFAULT_NODE_SIZE)
+        );
+
+        let bbox = NodeItem::new(0.0, 0.0, 1.0, 1.0, 0);
+        let expected = offsets(tree.search(&bbox)?);
+        assert_eq!(expected, vec![0, 1, 7, 8]);
+        let found = PackedRTree::search_buf(&data, 49, PackedRTree::DEFAULT_NODE_SIZE, &bbox)?;
+        assert_eq!(offsets(found), expected);
+        let read = PackedRTree::from_buf(&data, 49, PackedRTree::DEFAULT_NODE_SIZE)?;
+        assert_eq!(read.items(), tree.items());
+
+        // Truncated index
+        assert!(PackedRTree::from_buf(&data[..100], 49, 16).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_params() {
+        assert!(PackedRTree::build(Vec::new(), 16).is_err());
+        assert!(PackedRTree::build(grid(2), 1).is_err());
+    }
+}
@@ -11,6 +11,15 @@
 //!
 //! Supported dimensions: X, Y, Z, M, T
 //!
+//! Readers of binary formats (WKB, EWKB, GeoPackage WKB, MVT) don't allocate while
+//! processing: reading a feature into a processor which doesn't allocate itself,
+//! like [ProcessorSink], makes no heap allocation. The CSV reader reuses its
+//! record buffer across features.
+//!
+//! Slice-based decoders borrow from their input: [mvt::TileRef] passes layer names,
+//! property keys and string values to the processor without copying them out of the
+//! encoded tile, and WKB coordinates are read directly from the input slice.
+//!
 //! Available implementations:
 //! * [geozero-shp](https://docs.rs/geozero-shp)
 //! * [flatgeobuf](https://docs.rs/flatgeobuf)
@@ -32,21 +41,48 @@
 //! | WKT       | [wkt::WktStr], [wkt::WktString]                                      | XYZM       | [wkt::WktReader], [wkt::WktStr], [wkt::WktString]                    | [ToWkt]             | [WktWriter](wkt::WktWriter)             |
 
 mod api;
+#[cfg(feature = "with-tokio")]
+mod async_api;
+mod column_stats;
+mod compare;
+mod convert;
+mod crs;
+mod detect;
+mod datetime;
 pub mod error;
 mod feature_processor;
+mod features;
 mod geometry_processor;
+pub mod index;
+mod merge;
 mod multiplex;
+pub mod processor;
 mod property_processor;
+mod schema;
+mod write_buffer;
 
 pub use api::*;
+#[cfg(feature = "with-tokio")]
+pub use async_api::*;
+pub use column_stats::*;
+pub use compare::*;
+pub use convert::*;
+pub use crs::*;
+pub use detect::*;
 pub use feature_processor::*;
+pub use features::*;
 pub use geometry_processor::*;
+pub use merge::*;
 pub use multiplex::*;
 pub use property_processor::*;
+pub use schema::*;
 
 #[cfg(feature = "with-arrow")]
 pub mod arrow;
 
+#[cfg(feature = "with-object-store")]
+pub mod cloud;
+
 #[cfg(feature = "with-csv")]
 pub mod csv;
 #[cfg(feature = "with-csv")]
@@ -78,6 +114,9 @@ pub mod gpkg;
 #[cfg(feature = "with-gpx")]
 pub mod gpx;
 
+#[cfg(feature = "with-mmap")]
+pub mod mmap;
+
 #[cfg(any(feature = "with-postgis-postgres", feature = "with-postgis-sqlx"))]
 pub mod postgis;
 
@@ -86,7 +125,7 @@ pub mod svg;
 #[cfg(feature = "with-svg")]
 pub use crate::svg::conversion::*;
 
-#[cfg(feature = "with-tesselator")]
+#[cfg(feature = "with-tessellator")]
 pub mod tessellator;
 
 #[cfg(feature = "with-wkb")]
@@ -101,6 +140,9 @@ pub use crate::wkt::conversion::*;
 
 #[cfg(feature = "with-mvt")]
 pub mod mvt;
+
+#[cfg(feature = "with-ogcapi")]
+pub mod ogcapi;
 #[cfg(feature = "with-mvt")]
 pub use crate::mvt::conversion::*;
 
@@ -0,0 +1,363 @@
+//! Merging of multiple datasources into one dataset.
+use crate::api::{GeozeroDatasource, ReadOptions};
+use crate::crs::Crs;
+use crate::error::{GeozeroError, Result};
+use crate::feature_processor::{DatasetMetadata, FeatureProcessor, Warning};
+use crate::geometry_processor::GeomProcessor;
+use crate::processor::{forward_processor_methods, Borrowed};
+use crate::property_processor::{ColumnDef, ColumnValue, PropertyProcessor};
+use crate::schema::{widen_type, SchemaInferrer};
+use std::collections::HashMap;
+
+/// Concatenates several datasources into a single dataset.
+///
+/// Before processing the features, the schemas of all sources are reconciled:
+/// columns are reported in order of their first appearance, differing types are
+/// widened like in [SchemaInferrer], and columns missing in a source are nullable.
+/// Sources without a declared schema are inspected completely. Each source is
+/// therefore processed twice, which requires sources that can be read repeatedly,
+/// like [DetectedSource](crate::DetectedSource). Use [with_schema](MergeSource::with_schema)
+/// to skip reconciliation.
+///
+/// Features are renumbered consecutively and property indices refer to the
+/// reconciled schema. Only the first reported CRS and metadata are passed on, the latter
+/// without extent.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::{GeozeroDatasource, MergeSource};
+///
+/// let mut source = MergeSource::new(vec![
+///     GeoJson(r#"{"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}"#),
+///     GeoJson(r#"{"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}}"#),
+/// ]);
+/// let mut out: Vec<u8> = Vec::new();
+/// source.process(&mut GeoJsonWriter::new(&mut out)).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains("Bern") && json.contains("Paris"));
+/// ```
+pub struct MergeSource<D> {
+    sources: Vec<D>,
+    name: Option<String>,
+    schema: Option<Vec<ColumnDef>>,
+}
+
+impl<D: GeozeroDatasource> MergeSource<D> {
+    pub fn new(sources: Vec<D>) -> Self {
+        MergeSource {
+            sources,
+            name: None,
+            schema: None,
+        }
+    }
+    /// Set name of merged dataset
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+    /// Use `columns` as merged schema instead of reconciling the source schemas
+    pub fn with_schema(mut self, columns: Vec<ColumnDef>) -> Self {
+        self.schema = Some(columns);
+        self
+    }
+    /// Merged sources
+    pub fn sources(&self) -> &[D] {
+        &self.sources
+    }
+    /// Reconciled schema of all sources
+    pub fn reconcile_schema(&mut self) -> Result<Vec<ColumnDef>> {
+        let mut merged = SchemaMerge::default();
+        for source in &mut self.sources {
+            let mut collector = SourceSchema::default();
+            match source.process(&mut collector) {
+                Ok(()) | Err(GeozeroError::Stopped) => {}
+                Err(e) => return Err(e),
+            }
+            let columns = match collector.declared {
+                Some(columns) => columns,
+                None => collector.inferrer.into_columns(),
+            };
+            merged.add(&columns);
+        }
+        Ok(merged.columns)
+    }
+}
+
+impl<D: GeozeroDatasource> GeozeroDatasource for MergeSource<D> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.process_with_options(processor, &ReadOptions::default())
+    }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        let columns = match self.schema.clone() {
+            Some(columns) => columns,
+            None => self.reconcile_schema()?,
+        };
+        processor.dataset_begin(self.name.as_deref())?;
+        if !columns.is_empty() {
+            processor.schema(&columns)?;
+        }
+        let mut merge = MergeProcessor {
+            inner: Borrowed::new(processor),
+            column_idx: columns
+                .iter()
+                .enumerate()
+                .map(|(idx, column)| (column.name.clone(), idx))
+                .collect(),
+            next_idx: columns.len(),
+            crs_seen: false,
+            metadata_seen: false,
+            feature_offset: 0,
+            feature_count: 0,
+        };
+        for source in &mut self.sources {
+            source.process_with_options(&mut merge, options)?;
+            merge.feature_offset = merge.feature_count;
+        }
+        processor.dataset_end()
+    }
+}
+
+/// Union of column definitions
+#[derive(Default)]
+struct SchemaMerge {
+    columns: Vec<ColumnDef>,
+    sources: usize,
+}
+
+impl SchemaMerge {
+    fn add(&mut self, columns: &[ColumnDef]) {
+        let mut seen = vec![false; self.columns.len()];
+        for column in columns {
+            if let Some(idx) = self.columns.iter().position(|c| c.name == column.name) {
+                let merged = &mut self.columns[idx];
+                merged.col_type = widen_type(merged.col_type, column.col_type);
+                merged.nullable |= column.nullable;
+                merged.width = match (merged.width, column.width) {
+                    (Some(w1), Some(w2)) => Some(w1.max(w2)),
+                    _ => None,
+                };
+                seen[idx] = true;
+            } else {
+                let mut column = column.clone();
+                // Column was missing in previous sources
+                column.nullable |= self.sources > 0;
+                self.columns.push(column);
+            }
+        }
+        for (column, seen) in self.columns.iter_mut().zip(seen) {
+            if !seen {
+                column.nullable = true;
+            }
+        }
+        self.sources += 1;
+    }
+}
+
+/// Declared or inferred schema of a source
+#[derive(Default)]
+struct SourceSchema {
+    declared: Option<Vec<ColumnDef>>,
+    inferrer: SchemaInferrer,
+}
+
+impl GeomProcessor for SourceSchema {}
+
+impl PropertyProcessor for SourceSchema {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.inferrer.property(idx, name, value)
+    }
+}
+
+impl FeatureProcessor for SourceSchema {
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.declared = Some(columns.to_vec());
+        Ok(())
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        if self.declared.is_some() {
+            // Features are not needed for a declared schema
+            return Err(GeozeroError::Stopped);
+        }
+        self.inferrer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.inferrer.feature_end(idx)
+    }
+}
+
+/// Passes features of a single source into the merged dataset
+struct MergeProcessor<'a, P> {
+    inner: Borrowed<'a, P>,
+    column_idx: HashMap<String, usize>,
+    /// Index of columns missing in the merged schema
+    next_idx: usize,
+    crs_seen: bool,
+    metadata_seen: bool,
+    feature_offset: u64,
+    feature_count: u64,
+}
+
+impl<P: GeomProcessor> GeomProcessor for MergeProcessor<'_, P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for MergeProcessor<'_, P> {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        let idx = match self.column_idx.get(name) {
+            Some(&idx) => idx,
+            None => {
+                let idx = self.next_idx;
+                self.column_idx.insert(name.to_string(), idx);
+                self.next_idx += 1;
+                idx
+            }
+        };
+        self.inner.property(idx, name, value)
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for MergeProcessor<'_, P> {
+    forward_processor_methods!(inner; fid, properties, geometry);
+    fn dataset_begin(&mut self, _name: Option<&str>) -> Result<()> {
+        Ok(())
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        Ok(())
+    }
+    fn schema(&mut self, _columns: &[ColumnDef]) -> Result<()> {
+        Ok(())
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        if self.crs_seen {
+            return Ok(());
+        }
+        self.crs_seen = true;
+        self.inner.crs(crs)
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        if self.metadata_seen {
+            return Ok(());
+        }
+        self.metadata_seen = true;
+        // The extent of the first source doesn't cover the merged dataset
+        self.inner.metadata(&metadata.without_extent())
+    }
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        let mut warning = warning.clone();
+        warning.feature_idx = warning.feature_idx.map(|idx| self.feature_offset + idx);
+        self.inner.warning(&warning)
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.inner.feature_begin(self.feature_offset + idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let idx = self.feature_offset + idx;
+        self.feature_count = self.feature_count.max(idx + 1);
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::property_processor::ColumnType;
+
+    #[test]
+    fn merge_schemas() {
+        let mut merged = SchemaMerge::default();
+        let mut name = ColumnDef::new("name", ColumnType::String);
+        name.nullable = false;
+        name.width = Some(4);
+        let mut pop = ColumnDef::new("pop", ColumnType::Int);
+        pop.nullable = false;
+        merged.add(&[name.clone(), pop]);
+        name.width = Some(6);
+        merged.add(&[
+            name,
+            ColumnDef::new("pop", ColumnType::Double),
+            ColumnDef::new("area", ColumnType::Float),
+        ]);
+        let columns = merged.columns;
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].width, Some(6));
+        assert!(!columns[0].nullable);
+        assert_eq!(columns[1].col_type, ColumnType::Double);
+        assert!(columns[1].nullable);
+        assert_eq!(columns[2].name, "area");
+        assert!(columns[2].nullable);
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn merge_sources() -> Result<()> {
+        use crate::geojson::GeoJson;
+
+        /// Records feature indices and properties
+        #[derive(Default)]
+        struct Recorder(Vec<String>);
+
+        impl GeomProcessor for Recorder {}
+
+        impl PropertyProcessor for Recorder {
+            fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+                self.0.push(format!("{}:{}={}", idx, name, value));
+                Ok(false)
+            }
+        }
+
+        impl FeatureProcessor for Recorder {
+            fn dataset_begin(&mut self, _name: Option<&str>) -> Result<()> {
+                self.0.push("begin".to_string());
+                Ok(())
+            }
+            fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+                let names: Vec<_> = columns.iter().map(|c| c.name.as_str()).collect();
+                self.0.push(names.join(","));
+                Ok(())
+            }
+            fn dataset_end(&mut self) -> Result<()> {
+                self.0.push("end".to_string());
+                Ok(())
+            }
+            fn feature_begin(&mut self, idx: u64) -> Result<()> {
+                self.0.push(format!("feature {}", idx));
+                Ok(())
+            }
+        }
+
+        let mut source = MergeSource::new(vec![
+            GeoJson(
+                r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"name": "Bern"}, "geometry": null},
+                {"type": "Feature", "properties": {"name": "Thun"}, "geometry": null}]}"#,
+            ),
+            GeoJson(
+                r#"{"type": "Feature", "properties": {"pop": 2161000, "name": "Paris"}, "geometry": null}"#,
+            ),
+        ]);
+        let mut recorder = Recorder::default();
+        source.process(&mut recorder)?;
+        assert_eq!(
+            recorder.0,
+            vec![
+                "begin",
+                "name,pop",
+                "feature 0",
+                "0:name=Bern",
+                "feature 1",
+                "0:name=Thun",
+                "feature 2",
+                "0:name=Paris",
+                "1:pop=2161000",
+                "end"
+            ]
+        );
+        Ok(())
+    }
+}
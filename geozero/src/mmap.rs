@@ -0,0 +1,104 @@
+//! Memory-mapped file input.
+//!
+//! Byte-slice based readers (WKB, MVT) process a [MappedFile] directly, without
+//! read calls and without copying the file into an intermediate buffer.
+//!
+//! # Usage example:
+//!
+//! ```rust,ignore
+//! use geozero::mmap::MappedFile;
+//! use geozero::wkb::WkbDialect;
+//! use geozero::wkt::WktWriter;
+//!
+//! let file = MappedFile::open("geometry.wkb")?;
+//! let mut out: Vec<u8> = Vec::new();
+//! file.process_wkb_geom(WkbDialect::Wkb, &mut WktWriter::new(&mut out))?;
+//! ```
+use crate::error::Result;
+use memmap2::Mmap;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Read-only memory mapping of a local file.
+pub struct MappedFile {
+    mmap: Mmap,
+}
+
+impl MappedFile {
+    /// Map file at `path` into memory.
+    ///
+    /// The file must not be modified or truncated while it is mapped.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the mapping is read-only, concurrent modifications of the file
+        // are excluded by the documented contract of `open`.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(MappedFile { mmap })
+    }
+    /// Mapped file content
+    pub fn bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+    /// Process WKB geometry of the given dialect.
+    #[cfg(feature = "with-wkb")]
+    pub fn process_wkb_geom<P: crate::GeomProcessor>(
+        &self,
+        dialect: crate::wkb::WkbDialect,
+        processor: &mut P,
+    ) -> Result<()> {
+        crate::wkb::process_wkb_type_geom(&mut self.bytes(), processor, dialect)
+    }
+    /// Decode Mapbox Vector Tile.
+    #[cfg(feature = "with-mvt")]
+    pub fn mvt_tile(&self) -> Result<crate::mvt::Tile> {
+        use prost::Message;
+        crate::mvt::Tile::decode(self.bytes())
+            .map_err(|e| crate::error::GeozeroError::Dataset(e.to_string()))
+    }
+    /// Mapbox Vector Tile with layers decoded on demand, borrowing strings from the mapping.
+    #[cfg(feature = "with-mvt")]
+    pub fn mvt_tile_ref(&self) -> crate::mvt::TileRef<'_> {
+        crate::mvt::TileRef(self.bytes())
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        self.bytes()
+    }
+}
+
+impl AsRef<[u8]> for MappedFile {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "with-wkb", feature = "with-wkt"))]
+    fn mapped_wkb() -> Result<()> {
+        use crate::wkb::WkbDialect;
+        use crate::wkt::WktWriter;
+
+        let path = std::env::temp_dir().join(format!("geozero-mmap-{}.wkb", std::process::id()));
+        std::fs::write(
+            &path,
+            [
+                1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 36, 64, 0, 0, 0, 0, 0, 0, 52, 192,
+            ],
+        )?;
+        let file = MappedFile::open(&path)?;
+        let mut out: Vec<u8> = Vec::new();
+        file.process_wkb_geom(WkbDialect::Wkb, &mut WktWriter::new(&mut out))?;
+        drop(file);
+        std::fs::remove_file(&path)?;
+        assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(10 -20)");
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
+use crate::crs::Crs;
 use crate::error::Result;
-use crate::feature_processor::FeatureProcessor;
+use crate::feature_processor::{DatasetMetadata, FeatureProcessor, Warning};
 use crate::geometry_processor::GeomProcessor;
-use crate::property_processor::{ColumnValue, PropertyProcessor};
+use crate::property_processor::{ColumnDef, ColumnValue, PropertyProcessor};
 
 #[doc(hidden)]
 pub struct Multiplexer<P1: FeatureProcessor, P2: FeatureProcessor> {
@@ -20,6 +21,22 @@ impl<P1: FeatureProcessor, P2: FeatureProcessor> FeatureProcessor for Multiplexe
         self.p1.dataset_begin(name)?;
         self.p2.dataset_begin(name)
     }
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.p1.schema(columns)?;
+        self.p2.schema(columns)
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        self.p1.crs(crs)?;
+        self.p2.crs(crs)
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        self.p1.metadata(metadata)?;
+        self.p2.metadata(metadata)
+    }
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        self.p1.warning(warning)?;
+        self.p2.warning(warning)
+    }
     fn dataset_end(&mut self) -> Result<()> {
         self.p1.dataset_end()?;
         self.p2.dataset_end()
@@ -28,6 +45,10 @@ impl<P1: FeatureProcessor, P2: FeatureProcessor> FeatureProcessor for Multiplexe
         self.p1.feature_begin(idx)?;
         self.p2.feature_begin(idx)
     }
+    fn fid(&mut self, fid: &ColumnValue) -> Result<()> {
+        self.p1.fid(fid)?;
+        self.p2.fid(fid)
+    }
     fn feature_end(&mut self, idx: u64) -> Result<()> {
         self.p1.feature_end(idx)?;
         self.p2.feature_end(idx)
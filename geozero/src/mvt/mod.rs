@@ -1,11 +1,17 @@
 //! MVT conversions.
 mod mvt_commands;
+pub(crate) mod mvt_layer_writer;
 pub(crate) mod mvt_reader;
+pub(crate) mod mvt_tile_ref;
+pub(crate) mod mvt_tiler;
 pub(crate) mod mvt_writer;
 #[rustfmt::skip]
 mod vector_tile;
 
+pub use mvt_layer_writer::*;
 pub use mvt_reader::*;
+pub use mvt_tile_ref::*;
+pub use mvt_tiler::*;
 pub use mvt_writer::*;
 pub use vector_tile::*;
 
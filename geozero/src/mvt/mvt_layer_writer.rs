@@ -0,0 +1,192 @@
+//! Encode features with properties into an MVT layer
+use crate::error::Result;
+use crate::mvt::mvt_writer::MvtWriter;
+use crate::mvt::vector_tile::tile;
+use crate::processor::forward_processor_methods;
+use crate::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::collections::HashMap;
+
+/// Encodes features with properties into a layer of a Mapbox Vector Tile.
+///
+/// Coordinates are expected in tile pixels, e.g. converted with
+/// [TilePixels](crate::processor::TilePixels). Features without geometry and
+/// NULL or binary property values are skipped.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::mvt::{MvtLayerWriter, Tile};
+/// use geozero::processor::TilePixels;
+/// use prost::Message;
+///
+/// let mut writer = TilePixels::new(MvtLayerWriter::new("places", 4096), 10)
+///     .tile_size(4096)
+///     .tile(536, 358);
+/// source.process(&mut writer)?;
+/// let tile = Tile {
+///     layers: vec![writer.into_inner().into_layer()],
+/// };
+/// let data = tile.encode_to_vec();
+/// ```
+pub struct MvtLayerWriter {
+    layer: tile::Layer,
+    /// Index of key in layer keys
+    keys: HashMap<String, u32>,
+    /// Index of encoded value in layer values
+    values: HashMap<String, u32>,
+    tags: Vec<u32>,
+    geometry: MvtWriter,
+}
+
+impl MvtLayerWriter {
+    /// Layer `name` with tile `extent` in pixels (usually 4096)
+    pub fn new(name: &str, extent: u32) -> Self {
+        MvtLayerWriter {
+            layer: tile::Layer {
+                version: 2,
+                name: name.to_string(),
+                extent: Some(extent),
+                ..Default::default()
+            },
+            keys: HashMap::new(),
+            values: HashMap::new(),
+            tags: Vec::new(),
+            geometry: MvtWriter::new(),
+        }
+    }
+    /// Encoded layer
+    pub fn layer(&self) -> &tile::Layer {
+        &self.layer
+    }
+    /// Consume writer and return encoded layer
+    pub fn into_layer(self) -> tile::Layer {
+        self.layer
+    }
+    fn key_idx(&mut self, name: &str) -> u32 {
+        if let Some(idx) = self.keys.get(name) {
+            return *idx;
+        }
+        let idx = self.layer.keys.len() as u32;
+        self.layer.keys.push(name.to_string());
+        self.keys.insert(name.to_string(), idx);
+        idx
+    }
+    fn value_idx(&mut self, value: tile::Value) -> u32 {
+        // Values with the same content and type share an index
+        let key = format!("{:?}", value);
+        if let Some(idx) = self.values.get(&key) {
+            return *idx;
+        }
+        let idx = self.layer.values.len() as u32;
+        self.layer.values.push(value);
+        self.values.insert(key, idx);
+        idx
+    }
+}
+
+/// MVT value of property, `None` for values without MVT representation
+fn mvt_value(value: &ColumnValue) -> Option<tile::Value> {
+    let mut mvt = tile::Value::default();
+    match *value {
+        ColumnValue::Byte(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::UByte(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::Bool(v) => mvt.bool_value = Some(v),
+        ColumnValue::Short(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::UShort(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::Int(v) => mvt.sint_value = Some(i64::from(v)),
+        ColumnValue::UInt(v) => mvt.uint_value = Some(u64::from(v)),
+        ColumnValue::Long(v) => mvt.sint_value = Some(v),
+        ColumnValue::ULong(v) => mvt.uint_value = Some(v),
+        ColumnValue::Float(v) => mvt.float_value = Some(v),
+        ColumnValue::Double(v) => mvt.double_value = Some(v),
+        // MVT has no integer type beyond 64 bits
+        ColumnValue::BigInt(v)
+        | ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v) => mvt.string_value = Some(v.to_string()),
+        ColumnValue::Binary(_) | ColumnValue::Null => return None,
+    }
+    Some(mvt)
+}
+
+impl GeomProcessor for MvtLayerWriter {
+    forward_processor_methods!(geometry; xy, geometries);
+}
+
+impl PropertyProcessor for MvtLayerWriter {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if let Some(value) = mvt_value(value) {
+            let key = self.key_idx(name);
+            let value = self.value_idx(value);
+            self.tags.push(key);
+            self.tags.push(value);
+        }
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for MvtLayerWriter {
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.geometry = MvtWriter::new();
+        self.tags.clear();
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        let mut feature = std::mem::take(&mut self.geometry.feature);
+        if !feature.geometry.is_empty() {
+            feature.tags = std::mem::take(&mut self.tags);
+            self.layer.features.push(feature);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mvt::{Tile, TileRef};
+    use crate::ProcessToJson;
+    use prost::Message;
+
+    #[test]
+    fn layer() -> Result<()> {
+        let mut writer = MvtLayerWriter::new("places", 4096);
+        for (idx, name) in ["Bern", "Zurich"].iter().enumerate() {
+            writer.feature_begin(idx as u64)?;
+            writer.properties_begin()?;
+            writer.property(0, "name", &ColumnValue::String(name))?;
+            writer.property(1, "capital", &ColumnValue::Bool(idx == 0))?;
+            writer.property(2, "area", &ColumnValue::Null)?;
+            writer.properties_end()?;
+            writer.geometry_begin()?;
+            writer.point_begin(0)?;
+            writer.xy(25.0, 17.0, 0)?;
+            writer.point_end(0)?;
+            writer.geometry_end()?;
+            writer.feature_end(idx as u64)?;
+        }
+        // Feature without geometry
+        writer.feature_begin(2)?;
+        writer.feature_end(2)?;
+
+        let layer = writer.into_layer();
+        assert_eq!(layer.features.len(), 2);
+        assert_eq!(layer.keys, vec!["name", "capital"]);
+        assert_eq!(layer.values.len(), 4);
+        assert_eq!(layer.features[1].tags, vec![0, 2, 1, 3]);
+        assert_eq!(layer.features[0].geometry, vec![9, 50, 34]);
+
+        let data = Tile {
+            layers: vec![layer],
+        }
+        .encode_to_vec();
+        let json = TileRef(&data).layer("places")?.unwrap().to_json()?;
+        assert!(
+            json.contains(r#""name": "Zurich", "capital": false"#),
+            "{}",
+            json
+        );
+        Ok(())
+    }
+}
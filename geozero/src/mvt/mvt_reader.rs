@@ -1,6 +1,8 @@
 use crate::error::{GeozeroError, Result};
 use crate::mvt::vector_tile::{tile, tile::GeomType};
-use crate::{ColumnValue, FeatureProcessor, GeomProcessor, GeozeroDatasource, GeozeroGeometry};
+use crate::{
+    ColumnValue, FeatureProcessor, FidStability, GeomProcessor, GeozeroDatasource, GeozeroGeometry,
+};
 
 use super::mvt_commands::{Command, CommandInteger, ParameterInteger};
 
@@ -8,13 +10,21 @@ impl GeozeroDatasource for tile::Layer {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
         process(&self, processor)
     }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
 }
 
 /// Process MVT layer.
+///
+/// Features are processed without heap allocations.
 pub fn process(layer: &tile::Layer, processor: &mut impl FeatureProcessor) -> Result<()> {
     processor.dataset_begin(Some(&layer.name))?;
     for (idx, feature) in layer.features.iter().enumerate() {
         processor.feature_begin(idx as u64)?;
+        if let Some(id) = feature.id {
+            processor.fid(&ColumnValue::ULong(id))?;
+        }
 
         process_properties(layer, feature, processor)?;
 
@@ -86,11 +96,21 @@ fn process_geom_n<P: GeomProcessor>(
     geom: &tile::Feature,
     idx: usize,
     processor: &mut P,
+) -> Result<()> {
+    process_geom_commands(geom.r#type, &geom.geometry, idx, processor)
+}
+
+/// Process geometry command stream of given geometry type.
+pub(crate) fn process_geom_commands<P: GeomProcessor>(
+    geom_type: Option<i32>,
+    geom: &[u32],
+    idx: usize,
+    processor: &mut P,
 ) -> Result<()> {
     let mut cursor: [i32; 2] = [0, 0];
-    match geom.r#type {
+    match geom_type {
         Some(r#type) if r#type == GeomType::Point as i32 => {
-            process_point(&mut cursor, &geom.geometry, idx, processor)?;
+            process_point(&mut cursor, geom, idx, processor)?;
         }
         Some(r#type) if r#type == GeomType::Linestring as i32 => {
             process_linestrings(&mut cursor, geom, idx, processor)?;
@@ -174,48 +194,54 @@ fn process_linestring<P: GeomProcessor>(
 
 fn process_linestrings<P: GeomProcessor>(
     cursor: &mut [i32; 2],
-    geom: &tile::Feature,
+    geom: &[u32],
     idx: usize,
     processor: &mut P,
 ) -> Result<()> {
-    let mut line_string_slices: Vec<&[u32]> = vec![];
-    let mut geom: &[u32] = &geom.geometry;
-
-    while geom.len() > 0 {
-        let lineto = CommandInteger(geom[3]);
-        let slice_size = 4 + lineto.count() as usize * 2;
-        let (slice, rest) = geom.split_at(slice_size);
-        line_string_slices.push(slice);
-        geom = rest;
-    }
-
-    if line_string_slices.len() > 1 {
-        processor.multilinestring_begin(line_string_slices.len(), idx)?;
-        for i in 0..line_string_slices.len() {
-            process_linestring(cursor, line_string_slices[i], false, i, processor)?;
+    let mut line_strings = linestring_slices(geom);
+    let count = line_strings.clone().count();
+    if count > 1 {
+        processor.multilinestring_begin(count, idx)?;
+        for (i, slice) in line_strings.enumerate() {
+            process_linestring(cursor, slice, false, i, processor)?;
         }
         processor.multilinestring_end(idx)?;
-    } else {
-        process_linestring(cursor, line_string_slices[0], true, idx, processor)?;
+    } else if let Some(slice) = line_strings.next() {
+        process_linestring(cursor, slice, true, idx, processor)?;
     }
 
     Ok(())
 }
 
-fn process_polygon<P: GeomProcessor>(
+/// Split geometry commands into linestring command slices without allocating
+fn linestring_slices(geom: &[u32]) -> impl Iterator<Item = &[u32]> + Clone {
+    let mut rest = geom;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let lineto = CommandInteger(rest[3]);
+        let (slice, tail) = rest.split_at(4 + lineto.count() as usize * 2);
+        rest = tail;
+        Some(slice)
+    })
+}
+
+fn process_polygon<'a, P: GeomProcessor>(
     cursor: &mut [i32; 2],
-    rings: &[&[u32]],
+    rings: impl Iterator<Item = &'a [u32]>,
+    ring_count: usize,
     tagged: bool,
     idx: usize,
     processor: &mut P,
 ) -> Result<()> {
-    processor.polygon_begin(tagged, rings.len(), idx)?;
+    processor.polygon_begin(tagged, ring_count, idx)?;
 
-    for (i, ring) in rings.iter().enumerate() {
+    for (i, ring) in rings.enumerate() {
         if ring[0] != CommandInteger::from(Command::MoveTo, 1) {
             return Err(GeozeroError::GeometryFormat);
         }
-        if *ring.last().unwrap() != CommandInteger::from(Command::ClosePath, 1) {
+        if ring.last() != Some(&CommandInteger::from(Command::ClosePath, 1)) {
             return Err(GeozeroError::GeometryFormat);
         }
         let lineto = CommandInteger(ring[3]);
@@ -244,47 +270,63 @@ fn process_polygon<P: GeomProcessor>(
 
 fn process_polygons<P: GeomProcessor>(
     cursor: &mut [i32; 2],
-    geom: &tile::Feature,
+    geom: &[u32],
     idx: usize,
     processor: &mut P,
 ) -> Result<()> {
-    let mut polygon_slices: Vec<Vec<&[u32]>> = vec![];
-    let mut geom: &[u32] = &geom.geometry;
+    let mut rings = ring_slices(geom, *cursor);
+    // first ring has to be an exterior ring
+    if let Some((_, false)) = rings.clone().next() {
+        return Err(GeozeroError::GeometryFormat);
+    }
+    let polygon_count = rings.clone().filter(|(_, exterior)| *exterior).count();
+    let multi = polygon_count > 1;
 
-    while geom.len() > 0 {
-        let lineto = CommandInteger(geom[3]);
-        let slice_size = 4 + lineto.count() as usize * 2 + 1;
-        let (slice, rest) = geom.split_at(slice_size);
-        let positive_area = is_area_positive(
-            cursor.clone(),
-            &slice[1..3],
-            &slice[4..4 + lineto.count() as usize * 2],
-        );
-        if positive_area {
-            // new polygon with exterior ring
-            polygon_slices.push(vec![slice]);
-        } else if let Some(last_slice) = polygon_slices.last_mut() {
-            // add interior ring to previous polygon
-            last_slice.push(slice);
+    if multi {
+        processor.multipolygon_begin(polygon_count, idx)?;
+    }
+    for i in 0..polygon_count {
+        // exterior ring followed by its interior rings
+        let ring_count = 1 + rings
+            .clone()
+            .skip(1)
+            .take_while(|(_, exterior)| !*exterior)
+            .count();
+        let polygon = rings.by_ref().take(ring_count).map(|(ring, _)| ring);
+        if multi {
+            process_polygon(cursor, polygon, ring_count, false, i, processor)?;
         } else {
-            return Err(GeozeroError::GeometryFormat);
+            process_polygon(cursor, polygon, ring_count, true, idx, processor)?;
         }
-        geom = rest;
     }
-
-    if polygon_slices.len() > 1 {
-        processor.multipolygon_begin(polygon_slices.len(), idx)?;
-        for i in 0..polygon_slices.len() {
-            process_polygon(cursor, &polygon_slices[i], false, i, processor)?;
-        }
+    if multi {
         processor.multipolygon_end(idx)?;
-    } else {
-        process_polygon(cursor, &polygon_slices[0], true, idx, processor)?;
     }
 
     Ok(())
 }
 
+/// Split geometry commands into ring command slices without allocating.
+///
+/// Rings with positive area are flagged as exterior rings.
+fn ring_slices(geom: &[u32], cursor: [i32; 2]) -> impl Iterator<Item = (&[u32], bool)> + Clone {
+    let mut rest = geom;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let lineto = CommandInteger(rest[3]);
+        let (slice, tail) = rest.split_at(4 + lineto.count() as usize * 2 + 1);
+        rest = tail;
+        let positive_area = is_area_positive(
+            cursor,
+            &slice[1..3],
+            &slice[4..4 + lineto.count() as usize * 2],
+        );
+        Some((slice, positive_area))
+    })
+}
+
 // using surveyor's formula
 fn is_area_positive(mut cursor: [i32; 2], first: &[u32], rest: &[u32]) -> bool {
     let nb = 1 + rest.len() / 2;
@@ -294,12 +336,13 @@ fn is_area_positive(mut cursor: [i32; 2], first: &[u32], rest: &[u32]) -> bool {
         .chain(rest)
         .chain(first.iter())
         .map(|&x| ParameterInteger(x).value());
-    cursor[0] += coords.next().unwrap();
-    cursor[1] += coords.next().unwrap();
+    let mut next = || coords.next().unwrap_or(0);
+    cursor[0] += next();
+    cursor[1] += next();
     for _i in 0..nb {
         let [x0, y0] = cursor;
-        cursor[0] += coords.next().unwrap();
-        cursor[1] += coords.next().unwrap();
+        cursor[0] += next();
+        cursor[1] += next();
         area += x0 * cursor[1] - cursor[0] * y0;
     }
     area > 0
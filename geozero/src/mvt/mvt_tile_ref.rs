@@ -0,0 +1,333 @@
+use crate::error::{GeozeroError, Result};
+use crate::mvt::mvt_reader::process_geom_commands;
+use crate::{ColumnValue, FeatureProcessor, FidStability, GeozeroDatasource};
+use std::convert::TryInto;
+
+/// Encoded Mapbox Vector Tile, decoded on demand.
+///
+/// In contrast to the decoded [Tile](crate::mvt::Tile), layer names, keys and
+/// string values are borrowed from the encoded tile. Feature tags and geometry
+/// commands are decoded into buffers which are reused across features.
+/// Tiles in network buffers like `bytes::Bytes` are read without copying with `TileRef(&bytes)`.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::mvt::TileRef;
+/// use geozero::ProcessToJson;
+///
+/// let data = std::fs::read("tile.mvt")?;
+/// for layer in TileRef(&data).layers() {
+///     let mut layer = layer?;
+///     println!("{}: {}", layer.name(), layer.to_json()?);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct TileRef<'a>(pub &'a [u8]);
+
+impl<'a> TileRef<'a> {
+    /// Iterate over layers
+    pub fn layers(&self) -> impl Iterator<Item = Result<LayerRef<'a>>> {
+        let mut fields = Fields(self.0);
+        std::iter::from_fn(move || loop {
+            match fields.next_field() {
+                Ok(Some((3, Field::Bytes(data)))) => return Some(LayerRef::decode(data)),
+                Ok(Some(_)) => {}
+                Ok(None) => return None,
+                Err(e) => {
+                    fields.0 = &[];
+                    return Some(Err(e));
+                }
+            }
+        })
+    }
+    /// Layer with given name
+    pub fn layer(&self, name: &str) -> Result<Option<LayerRef<'a>>> {
+        for layer in self.layers() {
+            let layer = layer?;
+            if layer.name == name {
+                return Ok(Some(layer));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Layer of an encoded Mapbox Vector Tile.
+///
+/// Created by [TileRef].
+#[derive(Debug)]
+pub struct LayerRef<'a> {
+    name: &'a str,
+    extent: u32,
+    keys: Vec<&'a str>,
+    values: Vec<ColumnValue<'a>>,
+    /// Encoded layer message
+    data: &'a [u8],
+}
+
+impl<'a> LayerRef<'a> {
+    fn decode(data: &'a [u8]) -> Result<Self> {
+        let mut layer = LayerRef {
+            name: "",
+            extent: 4096,
+            keys: Vec::new(),
+            values: Vec::new(),
+            data,
+        };
+        let mut fields = Fields(data);
+        while let Some((tag, field)) = fields.next_field()? {
+            match (tag, field) {
+                (1, Field::Bytes(name)) => layer.name = utf8(name)?,
+                (3, Field::Bytes(key)) => layer.keys.push(utf8(key)?),
+                (4, Field::Bytes(value)) => layer.values.push(decode_value(value)?),
+                (5, Field::Varint(extent)) => layer.extent = extent as u32,
+                _ => {}
+            }
+        }
+        Ok(layer)
+    }
+    /// Layer name
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+    /// Tile extent (default: 4096)
+    pub fn extent(&self) -> u32 {
+        self.extent
+    }
+}
+
+impl GeozeroDatasource for LayerRef<'_> {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        process_layer(self, processor)
+    }
+    fn fid_stability(&self) -> FidStability {
+        FidStability::Optional
+    }
+}
+
+/// MVT reader for async input.
+///
+/// Reads the encoded tile until EOF and processes all layers.
+#[cfg(feature = "with-tokio")]
+pub struct AsyncMvtReader<R: tokio::io::AsyncRead + Unpin + Send>(pub R);
+
+#[cfg(feature = "with-tokio")]
+#[async_trait::async_trait]
+impl<R: tokio::io::AsyncRead + Unpin + Send> crate::AsyncGeozeroDatasource for AsyncMvtReader<R> {
+    async fn process<P: FeatureProcessor + Send>(&mut self, processor: &mut P) -> Result<()> {
+        use tokio::io::AsyncReadExt;
+        let mut data = Vec::new();
+        self.0.read_to_end(&mut data).await?;
+        for layer in TileRef(&data).layers() {
+            process_layer(&layer?, processor)?;
+        }
+        Ok(())
+    }
+}
+
+/// Process layer of an encoded MVT.
+///
+/// Property keys and string values are passed to the processor without copying.
+pub fn process_layer(layer: &LayerRef, processor: &mut impl FeatureProcessor) -> Result<()> {
+    processor.dataset_begin(Some(layer.name))?;
+    // Reused across features
+    let mut tags = Vec::new();
+    let mut geometry = Vec::new();
+    let mut idx = 0;
+    let mut fields = Fields(layer.data);
+    while let Some((tag, field)) = fields.next_field()? {
+        let data = match (tag, field) {
+            (2, Field::Bytes(data)) => data,
+            _ => continue,
+        };
+        tags.clear();
+        geometry.clear();
+        let mut id = None;
+        let mut geom_type = None;
+        let mut feature = Fields(data);
+        while let Some((tag, field)) = feature.next_field()? {
+            match (tag, field) {
+                (1, Field::Varint(v)) => id = Some(v),
+                (2, Field::Bytes(packed)) => decode_packed(packed, &mut tags)?,
+                (2, Field::Varint(v)) => tags.push(v as u32),
+                (3, Field::Varint(v)) => geom_type = Some(v as i32),
+                (4, Field::Bytes(packed)) => decode_packed(packed, &mut geometry)?,
+                (4, Field::Varint(v)) => geometry.push(v as u32),
+                _ => {}
+            }
+        }
+
+        processor.feature_begin(idx)?;
+        if let Some(id) = id {
+            processor.fid(&ColumnValue::ULong(id))?;
+        }
+        processor.properties_begin()?;
+        for (i, tag) in tags.chunks_exact(2).enumerate() {
+            let key = layer
+                .keys
+                .get(tag[0] as usize)
+                .ok_or_else(|| GeozeroError::Feature(format!("invalid key index {}", tag[0])))?;
+            let value = layer
+                .values
+                .get(tag[1] as usize)
+                .ok_or_else(|| GeozeroError::Feature(format!("invalid value index {}", tag[1])))?;
+            processor.property(i, key, value)?;
+        }
+        processor.properties_end()?;
+        processor.geometry_begin()?;
+        process_geom_commands(geom_type, &geometry, 0, processor)?;
+        processor.geometry_end()?;
+        processor.feature_end(idx)?;
+        idx += 1;
+    }
+    processor.dataset_end()
+}
+
+/// Protobuf field value
+enum Field<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Protobuf message fields
+struct Fields<'a>(&'a [u8]);
+
+impl<'a> Fields<'a> {
+    fn next_field(&mut self) -> Result<Option<(u64, Field<'a>)>> {
+        if self.0.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let field = match key & 0x7 {
+            0 => Field::Varint(self.varint()?),
+            1 => Field::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            2 => {
+                let len = self.varint()? as usize;
+                Field::Bytes(self.take(len)?)
+            }
+            5 => Field::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            wire_type => {
+                return Err(GeozeroError::Dataset(format!(
+                    "unsupported protobuf wire type {}",
+                    wire_type
+                )))
+            }
+        };
+        Ok(Some((key >> 3, field)))
+    }
+    fn varint(&mut self) -> Result<u64> {
+        let data = self.0;
+        let mut value = 0;
+        for (i, byte) in data.iter().take(10).enumerate() {
+            value |= u64::from(byte & 0x7f) << (i * 7);
+            if byte & 0x80 == 0 {
+                self.0 = &data[i + 1..];
+                return Ok(value);
+            }
+        }
+        Err(GeozeroError::Dataset("invalid protobuf varint".to_string()))
+    }
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.0.len() {
+            return Err(GeozeroError::Dataset(
+                "truncated protobuf message".to_string(),
+            ));
+        }
+        let (data, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(data)
+    }
+}
+
+fn utf8(data: &[u8]) -> Result<&str> {
+    std::str::from_utf8(data).map_err(|e| GeozeroError::Dataset(e.to_string()))
+}
+
+/// Append packed varints to `values`
+fn decode_packed(packed: &[u8], values: &mut Vec<u32>) -> Result<()> {
+    let mut fields = Fields(packed);
+    while !fields.0.is_empty() {
+        values.push(fields.varint()? as u32);
+    }
+    Ok(())
+}
+
+fn decode_value(data: &[u8]) -> Result<ColumnValue<'_>> {
+    let mut value = None;
+    let mut fields = Fields(data);
+    while let Some((tag, field)) = fields.next_field()? {
+        value = match (tag, field) {
+            (1, Field::Bytes(v)) => Some(ColumnValue::String(utf8(v)?)),
+            (2, Field::Fixed32(v)) => Some(ColumnValue::Float(f32::from_bits(v))),
+            (3, Field::Fixed64(v)) => Some(ColumnValue::Double(f64::from_bits(v))),
+            (4, Field::Varint(v)) => Some(ColumnValue::Long(v as i64)),
+            (5, Field::Varint(v)) => Some(ColumnValue::ULong(v)),
+            (6, Field::Varint(v)) => Some(ColumnValue::Long((v >> 1) as i64 ^ -((v & 1) as i64))),
+            (7, Field::Varint(v)) => Some(ColumnValue::Bool(v != 0)),
+            _ => value,
+        };
+    }
+    value.ok_or_else(|| GeozeroError::Property("unsupported value type".to_string()))
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::mvt::{tile, Tile};
+    use crate::ProcessToJson;
+    use prost::Message;
+
+    #[test]
+    fn borrowed_layer() -> Result<()> {
+        let mut layer = tile::Layer {
+            version: 2,
+            name: "points".to_string(),
+            keys: vec!["name".to_string(), "count".to_string()],
+            values: vec![
+                tile::Value {
+                    string_value: Some("world".to_string()),
+                    ..Default::default()
+                },
+                tile::Value {
+                    sint_value: Some(-2),
+                    ..Default::default()
+                },
+                tile::Value {
+                    double_value: Some(1.5),
+                    ..Default::default()
+                },
+            ],
+            extent: Some(4096),
+            ..Default::default()
+        };
+        let mut feature = tile::Feature {
+            tags: vec![0, 0, 1, 1],
+            geometry: vec![9, 2410, 3080],
+            ..Default::default()
+        };
+        feature.set_type(tile::GeomType::Point);
+        layer.features.push(feature);
+        let mut feature = tile::Feature {
+            tags: vec![1, 2],
+            geometry: vec![9, 4, 4, 18, 0, 16, 16, 0],
+            ..Default::default()
+        };
+        feature.set_type(tile::GeomType::Linestring);
+        layer.features.push(feature);
+        let tile = Tile {
+            layers: vec![layer.clone()],
+        };
+        let data = tile.encode_to_vec();
+
+        let mut layer_ref = TileRef(&data).layer("points")?.unwrap();
+        assert_eq!(layer_ref.name(), "points");
+        assert_eq!(layer_ref.extent(), 4096);
+        assert_eq!(layer_ref.to_json()?, layer.to_json()?);
+        assert!(TileRef(&data).layer("lines")?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+//! Generate vector tiles of a Web Mercator tile pyramid
+use crate::error::Result;
+use crate::mvt::mvt_layer_writer::MvtLayerWriter;
+use crate::mvt::vector_tile::Tile;
+use crate::processor::{
+    forward_processor_methods, tile_ranges, ClipToExtent, Event, Extent, FeatureBuffer, Orient,
+    Orientation, Simplify, SimplifyAlgorithm, TilePixels,
+};
+use crate::{FeatureProcessor, GeomProcessor, ProcessorSink, PropertyProcessor};
+use prost::Message;
+use std::collections::BTreeMap;
+
+/// Feature recorded in WGS84 lon/lat
+struct TilerFeature {
+    extent: Extent,
+    events: Vec<Event>,
+}
+
+/// Generates Mapbox Vector Tiles of a Web Mercator tile pyramid.
+///
+/// Processed features in WGS84 lon/lat are kept in memory. For each tile, the
+/// features intersecting the tile are converted to tile pixels, clipped to the
+/// tile extended by a buffer, simplified and encoded into a single layer.
+/// Simplification tolerance is given in tile pixels, which simplifies geometries
+/// according to the zoom level. Tiles of a zoom level are independent and can
+/// be encoded in parallel.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::mvt::MvtTiler;
+///
+/// let mut tiler = MvtTiler::new("countries", 0, 5);
+/// source.process(&mut tiler)?;
+/// for zoom in tiler.minzoom()..=tiler.maxzoom() {
+///     for tile in tiler.tiles(zoom) {
+///         if let Some(data) = tile.encode()? {
+///             std::fs::write(format!("tiles/{}/{}/{}.pbf", tile.z, tile.x, tile.y), data)?;
+///         }
+///     }
+/// }
+/// ```
+pub struct MvtTiler {
+    layer_name: String,
+    minzoom: u8,
+    maxzoom: u8,
+    extent: u32,
+    buffer: u32,
+    tolerance: f64,
+    recorder: FeatureBuffer<ProcessorSink>,
+    feature_extent: Option<Extent>,
+    in_feature: bool,
+    features: Vec<TilerFeature>,
+}
+
+impl MvtTiler {
+    /// Tiles of layer `layer_name` from `minzoom` to `maxzoom`
+    pub fn new(layer_name: &str, minzoom: u8, maxzoom: u8) -> Self {
+        MvtTiler {
+            layer_name: layer_name.to_string(),
+            minzoom,
+            maxzoom,
+            extent: 4096,
+            buffer: 64,
+            tolerance: 1.0,
+            recorder: FeatureBuffer::new(ProcessorSink::new()),
+            feature_extent: None,
+            in_feature: false,
+            features: Vec::new(),
+        }
+    }
+    /// Tile extent in pixels (default: 4096)
+    pub fn tile_extent(mut self, extent: u32) -> Self {
+        self.extent = extent;
+        self
+    }
+    /// Buffer around tiles in pixels (default: 64)
+    pub fn tile_buffer(mut self, buffer: u32) -> Self {
+        self.buffer = buffer;
+        self
+    }
+    /// Douglas-Peucker simplification tolerance in pixels (default: 1, 0 for no simplification)
+    pub fn simplify(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+    pub fn minzoom(&self) -> u8 {
+        self.minzoom
+    }
+    pub fn maxzoom(&self) -> u8 {
+        self.maxzoom
+    }
+    pub fn layer_name(&self) -> &str {
+        &self.layer_name
+    }
+    /// Number of collected features
+    pub fn feature_count(&self) -> usize {
+        self.features.len()
+    }
+    /// Extent of collected features in lon/lat
+    pub fn bounds(&self) -> Option<Extent> {
+        let mut features = self.features.iter();
+        let mut bounds = features.next()?.extent;
+        for feature in features {
+            bounds.merge(&feature.extent);
+        }
+        Some(bounds)
+    }
+    /// Tiles of `zoom` containing features
+    pub fn tiles(&self, zoom: u8) -> Vec<TileFeatures<'_>> {
+        let mut tiles: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+        // Buffer in tile units
+        let buffer = f64::from(self.buffer) / f64::from(self.extent);
+        for (idx, feature) in self.features.iter().enumerate() {
+            let (cols, rows) = tile_ranges(&feature.extent, zoom, buffer);
+            for x in cols {
+                for y in rows.clone() {
+                    tiles.entry((x, y)).or_default().push(idx);
+                }
+            }
+        }
+        tiles
+            .into_iter()
+            .map(|((x, y), features)| TileFeatures {
+                tiler: self,
+                z: zoom,
+                x,
+                y,
+                features,
+            })
+            .collect()
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        if !self.in_feature {
+            return;
+        }
+        match &mut self.feature_extent {
+            Some(extent) => extent.extend(x, y),
+            None => self.feature_extent = Some(Extent::from_point(x, y)),
+        }
+    }
+}
+
+/// Features of a tile returned by [MvtTiler::tiles]
+pub struct TileFeatures<'a> {
+    tiler: &'a MvtTiler,
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+    /// Indices of features intersecting the buffered tile
+    features: Vec<usize>,
+}
+
+impl TileFeatures<'_> {
+    /// Encode tile, `None` if no feature remains after clipping
+    pub fn encode(&self) -> Result<Option<Vec<u8>>> {
+        let tiler = self.tiler;
+        let extent = f64::from(tiler.extent);
+        let buffer = f64::from(tiler.buffer);
+        let writer = MvtLayerWriter::new(&tiler.layer_name, tiler.extent);
+        // Exterior rings with positive area in pixel coordinates (Y down)
+        let orient = Orient::new(writer, Orientation::CounterClockwise);
+        let simplify = Simplify::new(orient, SimplifyAlgorithm::DouglasPeucker, tiler.tolerance);
+        let clip = ClipToExtent::new(
+            simplify,
+            Extent::new(-buffer, -buffer, extent + buffer, extent + buffer),
+        );
+        let mut pipeline = TilePixels::new(clip, self.z)
+            .tile_size(tiler.extent)
+            .tile(self.x, self.y);
+        for idx in &self.features {
+            for event in &tiler.features[*idx].events {
+                event.replay(&mut pipeline)?;
+            }
+        }
+        let layer = pipeline
+            .into_inner()
+            .into_inner()
+            .into_inner()
+            .into_inner()
+            .into_layer();
+        if layer.features.is_empty() {
+            return Ok(None);
+        }
+        let tile = Tile {
+            layers: vec![layer],
+        };
+        Ok(Some(tile.encode_to_vec()))
+    }
+}
+
+impl GeomProcessor for MvtTiler {
+    forward_processor_methods!(recorder; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.add_vertex(x, y);
+        self.recorder.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.add_vertex(x, y);
+        self.recorder.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl PropertyProcessor for MvtTiler {
+    forward_processor_methods!(recorder; property);
+}
+
+impl FeatureProcessor for MvtTiler {
+    forward_processor_methods!(recorder; properties, geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.in_feature = true;
+        self.feature_extent = None;
+        self.recorder.record();
+        self.recorder.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.in_feature = false;
+        self.recorder.feature_end(idx)?;
+        let events = self.recorder.take();
+        // Features without coordinates are dropped
+        if let Some(extent) = self.feature_extent.take() {
+            self.features.push(TilerFeature { extent, events });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJson;
+    use crate::mvt::TileRef;
+    use crate::{GeozeroDatasource, ProcessToJson};
+
+    #[test]
+    fn tiles() -> Result<()> {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+            {"type": "Feature", "properties": {"name": "Sydney"}, "geometry": {"type": "Point", "coordinates": [151.21, -33.87]}},
+            {"type": "Feature", "properties": {"name": "Swiss"}, "geometry": {"type": "Polygon", "coordinates": [[[5.9, 45.8], [10.5, 45.8], [10.5, 47.8], [5.9, 47.8], [5.9, 45.8]]]}}
+        ]}"#;
+        let mut tiler = MvtTiler::new("places", 0, 2);
+        GeoJson(geojson).process(&mut tiler)?;
+        assert_eq!(tiler.feature_count(), 3);
+        assert_eq!(tiler.bounds().map(|b| b.maxx), Some(151.21));
+
+        let tiles = tiler.tiles(0);
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].features, vec![0, 1, 2]);
+
+        let tiles = tiler.tiles(2);
+        let ids: Vec<_> = tiles.iter().map(|tile| (tile.x, tile.y)).collect();
+        assert_eq!(ids, vec![(2, 1), (3, 2)]);
+        assert_eq!(tiles[0].features, vec![0, 2]);
+
+        let data = tiles[0].encode()?.unwrap();
+        let json = TileRef(&data).layer("places")?.unwrap().to_json()?;
+        assert!(json.contains(r#""name": "Bern""#), "{}", json);
+        assert!(json.contains(r#""type": "Polygon""#), "{}", json);
+        Ok(())
+    }
+}
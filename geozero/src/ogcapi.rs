@@ -0,0 +1,314 @@
+//! [OGC API - Features](https://ogcapi.ogc.org/features/) client.
+//!
+//! Collections of a service are read as ordinary datasources. Items are requested
+//! as GeoJSON in pages and the `next` links of the responses are followed until all
+//! selected features are processed.
+//!
+//! Requests are blocking and must not be made from within an async runtime.
+//!
+//! # Usage example:
+//!
+//! ```rust,ignore
+//! use geozero::geojson::GeoJsonWriter;
+//! use geozero::ogcapi::OgcApiFeatures;
+//! use geozero::processor::Extent;
+//! use geozero::GeozeroDatasource;
+//!
+//! let service = OgcApiFeatures::open("https://demo.pygeoapi.io/master")?;
+//! for collection in service.collections()? {
+//!     println!("{}: {:?}", collection.id, collection.title);
+//! }
+//! let mut lakes = service
+//!     .collection("lakes")
+//!     .bbox(Extent::new(-10.0, 40.0, 30.0, 60.0))
+//!     .datetime("2018-02-12T00:00:00Z/..");
+//! let mut out: Vec<u8> = Vec::new();
+//! lakes.process(&mut GeoJsonWriter::new(&mut out))?;
+//! ```
+use crate::error::{GeozeroError, Result};
+use crate::geojson::geojson_reader::process_feature;
+use crate::processor::{Borrowed, Extent, ResourceLimiter};
+use crate::{FeatureProcessor, GeozeroDatasource, ReadOptions};
+use geojson::{FeatureCollection, GeoJson};
+use reqwest::blocking::Client;
+use serde_json::Value as JsonValue;
+use url::Url;
+
+/// Media types requested for items
+const ACCEPT_GEOJSON: &str = "application/geo+json, application/json;q=0.9";
+
+fn http_error(e: reqwest::Error) -> GeozeroError {
+    match e.status() {
+        Some(status) => GeozeroError::HttpStatus(status.as_u16()),
+        None => GeozeroError::HttpError(e.to_string()),
+    }
+}
+
+/// OGC API - Features service
+pub struct OgcApiFeatures {
+    client: Client,
+    /// Landing page URL with trailing slash
+    base: Url,
+}
+
+/// Metadata of a feature collection
+#[derive(Clone, Debug)]
+pub struct CollectionInfo {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Spatial extent (first bbox of the collection extent)
+    pub extent: Option<Extent>,
+}
+
+impl OgcApiFeatures {
+    /// Service with landing page `url`
+    pub fn open(url: &str) -> Result<Self> {
+        let client = Client::builder().build().map_err(http_error)?;
+        Ok(OgcApiFeatures {
+            client,
+            base: base_url(url)?,
+        })
+    }
+    /// Feature collections of the service
+    pub fn collections(&self) -> Result<Vec<CollectionInfo>> {
+        let url = self
+            .base
+            .join("collections")
+            .map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+        let json: JsonValue = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.json())
+            .map_err(http_error)?;
+        let collections = json["collections"]
+            .as_array()
+            .ok_or_else(|| GeozeroError::Dataset("missing `collections`".to_string()))?;
+        Ok(collections.iter().filter_map(collection_info).collect())
+    }
+    /// Feature collection `id` as datasource
+    pub fn collection(&self, id: &str) -> OgcApiCollection {
+        OgcApiCollection {
+            client: self.client.clone(),
+            base: self.base.clone(),
+            id: id.to_string(),
+            bbox: None,
+            datetime: None,
+            limit: None,
+            max_features: None,
+        }
+    }
+}
+
+fn base_url(url: &str) -> Result<Url> {
+    let mut base = Url::parse(url).map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+    if !base.path().ends_with('/') {
+        let path = format!("{}/", base.path());
+        base.set_path(&path);
+    }
+    Ok(base)
+}
+
+fn collection_info(json: &JsonValue) -> Option<CollectionInfo> {
+    let extent = json["extent"]["spatial"]["bbox"][0]
+        .as_array()
+        .map(|bbox| {
+            bbox.iter()
+                .filter_map(JsonValue::as_f64)
+                .collect::<Vec<_>>()
+        })
+        .and_then(|bbox| match bbox.len() {
+            4 => Some(Extent::new(bbox[0], bbox[1], bbox[2], bbox[3])),
+            // minx, miny, minz, maxx, maxy, maxz
+            6 => Some(Extent::new(bbox[0], bbox[1], bbox[3], bbox[4])),
+            _ => None,
+        });
+    Some(CollectionInfo {
+        id: json["id"].as_str()?.to_string(),
+        title: json["title"].as_str().map(str::to_string),
+        description: json["description"].as_str().map(str::to_string),
+        extent,
+    })
+}
+
+/// Feature collection of an OGC API - Features service.
+///
+/// Created by [OgcApiFeatures::collection].
+pub struct OgcApiCollection {
+    client: Client,
+    base: Url,
+    id: String,
+    bbox: Option<Extent>,
+    datetime: Option<String>,
+    limit: Option<usize>,
+    max_features: Option<u64>,
+}
+
+impl OgcApiCollection {
+    /// Only features intersecting `bbox` (in WGS84)
+    pub fn bbox(mut self, bbox: Extent) -> Self {
+        self.bbox = Some(bbox);
+        self
+    }
+    /// Only features with a temporal property matching `datetime`
+    ///
+    /// A date-time (`2018-02-12T23:20:50Z`) or an interval (`2018-02-12T00:00:00Z/..`).
+    pub fn datetime(mut self, datetime: &str) -> Self {
+        self.datetime = Some(datetime.to_string());
+        self
+    }
+    /// Number of features requested per page (default: server default)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Stop after `max_features` features
+    pub fn max_features(mut self, max_features: u64) -> Self {
+        self.max_features = Some(max_features);
+        self
+    }
+    /// Collection id
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    /// URL of the first items page
+    pub fn items_url(&self) -> Result<Url> {
+        let mut url = self
+            .base
+            .join(&format!("collections/{}/items", self.id))
+            .map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(bbox) = &self.bbox {
+                let bbox = format!("{},{},{},{}", bbox.minx, bbox.miny, bbox.maxx, bbox.maxy);
+                query.append_pair("bbox", &bbox);
+            }
+            if let Some(datetime) = &self.datetime {
+                query.append_pair("datetime", datetime);
+            }
+            if let Some(limit) = self.limit {
+                query.append_pair("limit", &limit.to_string());
+            }
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+        Ok(url)
+    }
+    fn get_page(&self, url: Url) -> Result<FeatureCollection> {
+        let text = self
+            .client
+            .get(url)
+            .header(reqwest::header::ACCEPT, ACCEPT_GEOJSON)
+            .send()
+            .and_then(|resp| resp.error_for_status())
+            .and_then(|resp| resp.text())
+            .map_err(http_error)?;
+        match text.parse::<GeoJson>()? {
+            GeoJson::FeatureCollection(page) => Ok(page),
+            _ => Err(GeozeroError::Dataset(
+                "items response is not a FeatureCollection".to_string(),
+            )),
+        }
+    }
+}
+
+/// URL of the `next` link of an items page, resolved against the page URL
+fn next_link(page: &FeatureCollection, page_url: &Url) -> Option<Url> {
+    let links = page.foreign_members.as_ref()?.get("links")?.as_array()?;
+    links
+        .iter()
+        .find(|link| link["rel"].as_str() == Some("next"))
+        .and_then(|link| link["href"].as_str())
+        .and_then(|href| page_url.join(href).ok())
+}
+
+impl GeozeroDatasource for OgcApiCollection {
+    fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        self.process_with_options(processor, &ReadOptions::default())
+    }
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+        let processor = &mut processor;
+        processor.dataset_begin(Some(&self.id))?;
+        let mut idx = 0;
+        let mut url = Some(self.items_url()?);
+        while let Some(page_url) = url.take() {
+            let page = self.get_page(page_url.clone())?;
+            for feature in &page.features {
+                if matches!(self.max_features, Some(max) if idx as u64 >= max) {
+                    return processor.dataset_end();
+                }
+                process_feature(feature, idx, processor, options)?;
+                idx += 1;
+            }
+            // Servers may return a next link with an empty last page
+            if !page.features.is_empty() {
+                url = next_link(&page, &page_url);
+            }
+        }
+        processor.dataset_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn items_url() -> Result<()> {
+        let service = OgcApiFeatures::open("https://example.com/ogcapi")?;
+        let collection = service.collection("lakes");
+        assert_eq!(
+            collection.items_url()?.as_str(),
+            "https://example.com/ogcapi/collections/lakes/items"
+        );
+        let collection = collection
+            .bbox(Extent::new(-10.0, 40.0, 30.5, 60.0))
+            .datetime("2018-02-12T00:00:00Z/..")
+            .limit(100);
+        assert_eq!(
+            collection.items_url()?.as_str(),
+            "https://example.com/ogcapi/collections/lakes/items?bbox=-10%2C40%2C30.5%2C60&datetime=2018-02-12T00%3A00%3A00Z%2F..&limit=100"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn next_page() -> Result<()> {
+        let page_url = Url::parse("https://example.com/collections/lakes/items?limit=2").unwrap();
+        let page = r#"{"type": "FeatureCollection", "features": [], "links": [
+            {"rel": "self", "href": "items?limit=2"},
+            {"rel": "next", "href": "items?limit=2&offset=2"}
+        ]}"#;
+        let page: FeatureCollection = page.parse()?;
+        assert_eq!(
+            next_link(&page, &page_url).map(String::from).as_deref(),
+            Some("https://example.com/collections/lakes/items?limit=2&offset=2")
+        );
+        let page: FeatureCollection = r#"{"type": "FeatureCollection", "features": []}"#.parse()?;
+        assert_eq!(next_link(&page, &page_url), None);
+        Ok(())
+    }
+
+    #[test]
+    fn collection_metadata() {
+        let json = serde_json::json!({
+            "id": "lakes",
+            "title": "Large Lakes",
+            "extent": {"spatial": {"bbox": [[-180, -90, 180, 90]]}}
+        });
+        let info = collection_info(&json).unwrap();
+        assert_eq!(info.id, "lakes");
+        assert_eq!(info.title.as_deref(), Some("Large Lakes"));
+        assert_eq!(info.extent.map(|e| e.maxx), Some(180.0));
+        assert!(collection_info(&serde_json::json!({"title": "no id"})).is_none());
+    }
+}
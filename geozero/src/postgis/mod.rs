@@ -5,6 +5,8 @@
 //! Geometry types implementing [FromWkb](crate::wkb::FromWkb) can be decoded from PostGIS geometries using [wkb::Decode](crate::wkb::Decode).
 #[cfg(feature = "with-postgis-postgres")]
 mod postgis_postgres;
+#[cfg(all(feature = "with-postgis-sqlx", feature = "with-tokio"))]
+mod postgis_query;
 #[cfg(feature = "with-postgis-sqlx")]
 mod postgis_sqlx;
 
@@ -87,5 +89,7 @@ pub mod postgres {
 /// ```
 #[cfg(feature = "with-postgis-sqlx")]
 pub mod sqlx {
+    #[cfg(feature = "with-tokio")]
+    pub use super::postgis_query::*;
     pub use super::postgis_sqlx::*;
 }
@@ -0,0 +1,145 @@
+use crate::error::{GeozeroError, Result};
+use crate::wkb::process_ewkb_geom;
+use crate::{
+    AsyncGeozeroDatasource, ColumnDef, ColumnType, ColumnValue, FeatureProcessor, PropertyProcessor,
+};
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::{Column, Executor, Row, Statement, TypeInfo};
+
+/// Features of a PostGIS query result.
+///
+/// All columns except `geometry_column` with a boolean, integer, float or text
+/// type are passed as properties. Their definitions are emitted as schema
+/// before the first feature. Rows are processed as they arrive from the
+/// database instead of collecting the whole result first.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::postgis::sqlx::PostgisQuery;
+/// use geozero::AsyncGeozeroDatasource;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut query = PostgisQuery::new(&pool, "SELECT name, geom FROM countries", "geom");
+/// query.process(&mut GeoJsonWriter::new(&mut out)).await?;
+/// ```
+pub struct PostgisQuery<'a> {
+    pool: &'a PgPool,
+    sql: String,
+    geometry_column: String,
+}
+
+impl<'a> PostgisQuery<'a> {
+    pub fn new(pool: &'a PgPool, sql: &str, geometry_column: &str) -> Self {
+        PostgisQuery {
+            pool,
+            sql: sql.to_string(),
+            geometry_column: geometry_column.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncGeozeroDatasource for PostgisQuery<'_> {
+    async fn process<P: FeatureProcessor + Send>(&mut self, processor: &mut P) -> Result<()> {
+        let statement = self
+            .pool
+            .prepare(&self.sql)
+            .await
+            .map_err(|e| GeozeroError::Dataset(e.to_string()))?;
+        let columns: Vec<ColumnDef> = statement
+            .columns()
+            .iter()
+            .filter(|column| column.name() != self.geometry_column)
+            .filter_map(|column| {
+                column_type(column.type_info().name())
+                    .map(|col_type| ColumnDef::new(column.name(), col_type))
+            })
+            .collect();
+        let mut rows = sqlx::query(&self.sql).fetch(self.pool);
+        processor.dataset_begin(None)?;
+        processor.schema(&columns)?;
+        let mut idx = 0;
+        while let Some(row) = rows
+            .try_next()
+            .await
+            .map_err(|e| GeozeroError::Dataset(e.to_string()))?
+        {
+            processor.feature_begin(idx)?;
+            processor.properties_begin()?;
+            process_properties(&row, &self.geometry_column, processor)?;
+            processor.properties_end()?;
+            let geometry: Option<&[u8]> = row
+                .try_get(self.geometry_column.as_str())
+                .map_err(|e| GeozeroError::FeatureGeometry(e.to_string()))?;
+            if let Some(mut ewkb) = geometry {
+                processor.geometry_begin()?;
+                process_ewkb_geom(&mut ewkb, processor)?;
+                processor.geometry_end()?;
+            }
+            processor.feature_end(idx)?;
+            idx += 1;
+        }
+        processor.dataset_end()
+    }
+}
+
+/// Column type of supported PostgreSQL types
+fn column_type(type_name: &str) -> Option<ColumnType> {
+    match type_name {
+        "BOOL" => Some(ColumnType::Bool),
+        "INT2" => Some(ColumnType::Short),
+        "INT4" => Some(ColumnType::Int),
+        "INT8" => Some(ColumnType::Long),
+        "FLOAT4" => Some(ColumnType::Float),
+        "FLOAT8" => Some(ColumnType::Double),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => Some(ColumnType::String),
+        _ => None,
+    }
+}
+
+/// Process supported columns of `row` as properties
+fn process_properties<P: PropertyProcessor>(
+    row: &PgRow,
+    geometry_column: &str,
+    processor: &mut P,
+) -> Result<()> {
+    let mut idx = 0;
+    for column in row.columns() {
+        if column.name() == geometry_column {
+            continue;
+        }
+        let i = column.ordinal();
+        let value = match column.type_info().name() {
+            "BOOL" => row
+                .try_get(i)
+                .map(|v: Option<bool>| v.map(ColumnValue::Bool)),
+            "INT2" => row
+                .try_get(i)
+                .map(|v: Option<i16>| v.map(ColumnValue::Short)),
+            "INT4" => row.try_get(i).map(|v: Option<i32>| v.map(ColumnValue::Int)),
+            "INT8" => row
+                .try_get(i)
+                .map(|v: Option<i64>| v.map(ColumnValue::Long)),
+            "FLOAT4" => row
+                .try_get(i)
+                .map(|v: Option<f32>| v.map(ColumnValue::Float)),
+            "FLOAT8" => row
+                .try_get(i)
+                .map(|v: Option<f64>| v.map(ColumnValue::Double)),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => row
+                .try_get(i)
+                .map(|v: Option<&str>| v.map(ColumnValue::String)),
+            _ => continue,
+        }
+        .map_err(|e| GeozeroError::Property(e.to_string()))?;
+        if processor.property(idx, column.name(), &value.unwrap_or(ColumnValue::Null))? {
+            break;
+        }
+        idx += 1;
+    }
+    Ok(())
+}
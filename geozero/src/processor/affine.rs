@@ -0,0 +1,203 @@
+use crate::error::Result;
+use crate::{DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// 2D affine transformation matrix.
+///
+/// Transforms coordinates with `x' = a * x + b * y + xoff` and `y' = d * x + e * y + yoff`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub xoff: f64,
+    pub d: f64,
+    pub e: f64,
+    pub yoff: f64,
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Affine {
+    pub fn new(a: f64, b: f64, xoff: f64, d: f64, e: f64, yoff: f64) -> Self {
+        Affine {
+            a,
+            b,
+            xoff,
+            d,
+            e,
+            yoff,
+        }
+    }
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0)
+    }
+    pub fn translate(dx: f64, dy: f64) -> Self {
+        Self::new(1.0, 0.0, dx, 0.0, 1.0, dy)
+    }
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, 0.0, sy, 0.0)
+    }
+    /// Counter-clockwise rotation around the origin
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+    /// Shear with angles in degrees along the X and Y axis
+    pub fn shear(x_degrees: f64, y_degrees: f64) -> Self {
+        let (sx, sy) = (x_degrees.to_radians().tan(), y_degrees.to_radians().tan());
+        Self::new(1.0, sx, 0.0, sy, 1.0, 0.0)
+    }
+    /// Transformation from pixel/line to georeferenced coordinates
+    /// given as GDAL geotransform `[xoff, a, b, yoff, d, e]`
+    pub fn from_gdal(geotransform: &[f64; 6]) -> Self {
+        let gt = geotransform;
+        Self::new(gt[1], gt[2], gt[0], gt[4], gt[5], gt[3])
+    }
+    /// GDAL geotransform `[xoff, a, b, yoff, d, e]`
+    pub fn to_gdal(&self) -> [f64; 6] {
+        [self.xoff, self.a, self.b, self.yoff, self.d, self.e]
+    }
+    /// Transformation applying `self` followed by `other`
+    pub fn then(&self, other: &Affine) -> Self {
+        Self::new(
+            other.a * self.a + other.b * self.d,
+            other.a * self.b + other.b * self.e,
+            other.a * self.xoff + other.b * self.yoff + other.xoff,
+            other.d * self.a + other.e * self.d,
+            other.d * self.b + other.e * self.e,
+            other.d * self.xoff + other.e * self.yoff + other.yoff,
+        )
+    }
+    /// Inverse transformation, if the matrix is invertible
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.e - self.b * self.d;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let (a, b, d, e) = (self.e / det, -self.b / det, -self.d / det, self.a / det);
+        Some(Self::new(
+            a,
+            b,
+            -(a * self.xoff + b * self.yoff),
+            d,
+            e,
+            -(d * self.xoff + e * self.yoff),
+        ))
+    }
+    /// Transform coordinate
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.b * y + self.xoff,
+            self.d * x + self.e * y + self.yoff,
+        )
+    }
+}
+
+/// Applies an affine transformation to all XY coordinates.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Affine, AffineTransform};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let affine = Affine::scale(2.0, 2.0).then(&Affine::translate(10.0, 0.0));
+/// let mut processor = AffineTransform::new(WktWriter::new(&mut out), affine);
+/// WktStr("POINT(1 2)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(12 4)");
+/// ```
+pub struct AffineTransform<P> {
+    inner: P,
+    affine: Affine,
+}
+
+impl<P> AffineTransform<P> {
+    pub fn new(inner: P, affine: Affine) -> Self {
+        AffineTransform { inner, affine }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for AffineTransform<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        let (x, y) = self.affine.apply(x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let (x, y) = self.affine.apply(x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for AffineTransform<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for AffineTransform<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, warning, feature, properties,
+        geometry);
+
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        let mut metadata = metadata.clone();
+        let affine = &self.affine;
+        metadata.extent = metadata
+            .extent
+            .map(|e| e.map_corners(|x, y| affine.apply(x, y)));
+        self.inner.metadata(&metadata)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn affine() {
+        assert_close(Affine::rotate(90.0).apply(1.0, 0.0), (0.0, 1.0));
+        let affine = Affine::translate(1.0, 2.0).then(&Affine::scale(2.0, 3.0));
+        assert_close(affine.apply(1.0, 1.0), (4.0, 9.0));
+        let inverse = affine.inverse().unwrap();
+        assert_close(inverse.apply(4.0, 9.0), (1.0, 1.0));
+        assert!(Affine::scale(0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn gdal_geotransform() {
+        let gt = [2600000.0, 0.5, 0.0, 1200000.0, 0.0, -0.5];
+        let affine = Affine::from_gdal(&gt);
+        assert_close(affine.apply(10.0, 20.0), (2600005.0, 1199990.0));
+        assert_eq!(affine.to_gdal(), gt);
+    }
+}
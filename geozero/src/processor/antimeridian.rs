@@ -0,0 +1,407 @@
+use crate::error::Result;
+use crate::processor::Vertex;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Geometry currently buffered
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Buffered {
+    LineString(usize),
+    MultiLineString(usize),
+    Polygon(usize),
+    MultiPolygon(usize),
+}
+
+/// Splits geometries crossing the antimeridian.
+///
+/// Coordinates are expected as longitude/latitude in degrees. A segment with a
+/// longitude difference of more than 180° is considered to cross the antimeridian,
+/// following the recommendation of RFC 7946 section 3.1.9. LineStrings and Polygons
+/// crossing it are split into MultiLineStrings and MultiPolygons with parts on both
+/// sides, new vertices are inserted at ±180°.
+///
+/// Points and curves are passed on unchanged, as are polygons enclosing a pole.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::AntimeridianSplit;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = AntimeridianSplit::new(WktWriter::new(&mut out));
+/// WktStr("LINESTRING(170 0,-170 10)").process_geom(&mut processor).unwrap();
+/// assert_eq!(
+///     std::str::from_utf8(&out).unwrap(),
+///     "MULTILINESTRING((170 0,180 5),(-180 5,-170 10))"
+/// );
+/// ```
+pub struct AntimeridianSplit<P> {
+    inner: P,
+    buffered: Option<Buffered>,
+    lines: Vec<Vec<Vertex>>,
+    polygons: Vec<Vec<Vec<Vertex>>>,
+}
+
+impl<P> AntimeridianSplit<P> {
+    pub fn new(inner: P) -> Self {
+        AntimeridianSplit {
+            inner,
+            buffered: None,
+            lines: Vec::new(),
+            polygons: Vec::new(),
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn add_vertex(&mut self, vertex: Vertex) {
+        let vertices = match self.buffered {
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.last_mut()
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.last_mut().and_then(|rings| rings.last_mut())
+            }
+            None => None,
+        };
+        if let Some(vertices) = vertices {
+            vertices.push(vertex);
+        }
+    }
+}
+
+/// Segment crosses the antimeridian
+fn crosses(a: &Vertex, b: &Vertex) -> bool {
+    (b.x - a.x).abs() > 180.0
+}
+
+/// Split line at antimeridian crossings
+fn split_line(line: &[Vertex]) -> Vec<Vec<Vertex>> {
+    let mut parts = Vec::new();
+    let mut part = Vec::with_capacity(line.len());
+    for (i, vertex) in line.iter().enumerate() {
+        if i > 0 && crosses(&line[i - 1], vertex) {
+            let a = &line[i - 1];
+            // Longitude of the crossed meridian seen from `a`
+            let (boundary, dx) = if a.x > vertex.x {
+                (180.0, 360.0)
+            } else {
+                (-180.0, -360.0)
+            };
+            let fraction = (boundary - a.x) / (vertex.x + dx - a.x);
+            part.push(a.interpolate(vertex, boundary, fraction));
+            parts.push(std::mem::take(&mut part));
+            part.push(a.interpolate(vertex, -boundary, fraction));
+        }
+        part.push(*vertex);
+    }
+    parts.push(part);
+    parts
+}
+
+/// Shift vertices by multiples of 360° to make longitudes continuous
+fn unwrap_ring(ring: &[Vertex]) -> Vec<Vertex> {
+    let mut unwrapped: Vec<Vertex> = Vec::with_capacity(ring.len());
+    let mut dx = 0.0;
+    for (i, vertex) in ring.iter().enumerate() {
+        if i > 0 && crosses(&ring[i - 1], vertex) {
+            dx += if ring[i - 1].x > vertex.x {
+                360.0
+            } else {
+                -360.0
+            };
+        }
+        unwrapped.push(vertex.shifted(dx));
+    }
+    unwrapped
+}
+
+/// Clip ring to the half-plane left (`keep_left`) or right of longitude `x`
+fn clip_ring(ring: &[Vertex], x: f64, keep_left: bool) -> Vec<Vertex> {
+    let inside = |v: &Vertex| if keep_left { v.x <= x } else { v.x >= x };
+    let mut clipped = Vec::with_capacity(ring.len() + 2);
+    for (a, b) in ring.iter().zip(ring.iter().skip(1)) {
+        if inside(a) {
+            clipped.push(*a);
+        }
+        if inside(a) != inside(b) && a.x != x && b.x != x {
+            clipped.push(a.interpolate(b, x, (x - a.x) / (b.x - a.x)));
+        }
+    }
+    if let Some(first) = clipped.first().copied() {
+        clipped.push(first);
+    }
+    if clipped.len() < 4 {
+        clipped.clear();
+    }
+    clipped
+}
+
+/// Split polygon at the antimeridian into one or two polygons
+fn split_polygon(rings: &[Vec<Vertex>]) -> Vec<Vec<Vec<Vertex>>> {
+    let crossing = rings.iter().any(|ring| {
+        ring.iter()
+            .zip(ring.iter().skip(1))
+            .any(|(a, b)| crosses(a, b))
+    });
+    if !crossing || rings[0].is_empty() {
+        return vec![rings.to_vec()];
+    }
+    let mean_x = |ring: &[Vertex]| ring.iter().map(|v| v.x).sum::<f64>() / ring.len() as f64;
+    let exterior = unwrap_ring(&rings[0]);
+    if exterior.first().map(|v| v.x) != exterior.last().map(|v| v.x) {
+        // Ring enclosing a pole
+        return vec![rings.to_vec()];
+    }
+    let center = mean_x(&exterior);
+    let mut unwrapped = vec![exterior];
+    for ring in rings.iter().skip(1).filter(|ring| !ring.is_empty()) {
+        let ring = unwrap_ring(ring);
+        // Move holes next to the exterior ring
+        let dx = ((center - mean_x(&ring)) / 360.0).round() * 360.0;
+        unwrapped.push(ring.iter().map(|v| v.shifted(dx)).collect());
+    }
+    let (boundary, dx) = if unwrapped[0].iter().any(|v| v.x > 180.0) {
+        (180.0, -360.0)
+    } else {
+        (-180.0, 360.0)
+    };
+    let mut polygons = Vec::new();
+    for (keep_left, shift) in [(true, boundary < 0.0), (false, boundary > 0.0)] {
+        let parts: Vec<Vec<Vertex>> = unwrapped
+            .iter()
+            .map(|ring| clip_ring(ring, boundary, keep_left))
+            .map(|ring| {
+                if shift {
+                    ring.iter().map(|v| v.shifted(dx)).collect()
+                } else {
+                    ring
+                }
+            })
+            .collect();
+        if !parts[0].is_empty() {
+            polygons.push(parts.into_iter().filter(|ring| !ring.is_empty()).collect());
+        }
+    }
+    polygons
+}
+
+impl<P: GeomProcessor> AntimeridianSplit<P> {
+    fn emit_line(&mut self, line: &[Vertex], tagged: bool, idx: usize) -> Result<()> {
+        self.inner.linestring_begin(tagged, line.len(), idx)?;
+        for (idx, vertex) in line.iter().enumerate() {
+            vertex.emit(&mut self.inner, idx)?;
+        }
+        self.inner.linestring_end(tagged, idx)
+    }
+    fn emit_polygon(&mut self, rings: &[Vec<Vertex>], tagged: bool, idx: usize) -> Result<()> {
+        self.inner.polygon_begin(tagged, rings.len(), idx)?;
+        for (i, ring) in rings.iter().enumerate() {
+            self.emit_line(ring, false, i)?;
+        }
+        self.inner.polygon_end(tagged, idx)
+    }
+    /// Split buffered geometry and pass it on
+    fn flush(&mut self) -> Result<()> {
+        let buffered = self.buffered.take();
+        match buffered {
+            Some(Buffered::LineString(idx)) | Some(Buffered::MultiLineString(idx)) => {
+                let single = matches!(buffered, Some(Buffered::LineString(_)));
+                let lines: Vec<Vec<Vertex>> = std::mem::take(&mut self.lines)
+                    .iter()
+                    .flat_map(|line| split_line(line))
+                    .collect();
+                if single && lines.len() == 1 {
+                    self.emit_line(&lines[0], true, idx)
+                } else {
+                    self.inner.multilinestring_begin(lines.len(), idx)?;
+                    for (i, line) in lines.iter().enumerate() {
+                        self.emit_line(line, false, i)?;
+                    }
+                    self.inner.multilinestring_end(idx)
+                }
+            }
+            Some(Buffered::Polygon(idx)) | Some(Buffered::MultiPolygon(idx)) => {
+                let single = matches!(buffered, Some(Buffered::Polygon(_)));
+                let polygons: Vec<Vec<Vec<Vertex>>> = std::mem::take(&mut self.polygons)
+                    .iter()
+                    .flat_map(|rings| split_polygon(rings))
+                    .collect();
+                if single && polygons.len() == 1 {
+                    self.emit_polygon(&polygons[0], true, idx)
+                } else {
+                    self.inner.multipolygon_begin(polygons.len(), idx)?;
+                    for (i, rings) in polygons.iter().enumerate() {
+                        self.emit_polygon(rings, false, i)?;
+                    }
+                    self.inner.multipolygon_end(idx)
+                }
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for AntimeridianSplit<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::xy(x, y));
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::coordinate(x, y, z, m, t, tm));
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.push(Vec::with_capacity(size));
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                if let Some(rings) = self.polygons.last_mut() {
+                    rings.push(Vec::with_capacity(size));
+                }
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::LineString(idx));
+                self.lines.push(Vec::with_capacity(size));
+            }
+            None => return self.inner.linestring_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) => self.flush(),
+            Some(_) => Ok(()),
+            None => self.inner.linestring_end(tagged, idx),
+        }
+    }
+    fn multilinestring_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiLineString(idx));
+        Ok(())
+    }
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::Polygon(idx));
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            _ => return self.inner.polygon_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::Polygon(_)) => self.flush(),
+            Some(Buffered::MultiPolygon(_)) => Ok(()),
+            _ => self.inner.polygon_end(tagged, idx),
+        }
+    }
+    fn multipolygon_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiPolygon(idx));
+        Ok(())
+    }
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for AntimeridianSplit<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for AntimeridianSplit<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn vertices(xy: &[(f64, f64)]) -> Vec<Vertex> {
+        xy.iter().map(|(x, y)| Vertex::xy(*x, *y)).collect()
+    }
+
+    fn xy(vertices: &[Vertex]) -> Vec<(f64, f64)> {
+        vertices.iter().map(|v| (v.x, v.y)).collect()
+    }
+
+    #[test]
+    fn split_lines() {
+        let parts = split_line(&vertices(&[(-170.0, 0.0), (170.0, 10.0), (160.0, 10.0)]));
+        assert_eq!(parts.len(), 2);
+        assert_eq!(xy(&parts[0]), vec![(-170.0, 0.0), (-180.0, 5.0)]);
+        assert_eq!(
+            xy(&parts[1]),
+            vec![(180.0, 5.0), (170.0, 10.0), (160.0, 10.0)]
+        );
+
+        let parts = split_line(&vertices(&[(10.0, 0.0), (20.0, 0.0)]));
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn split_polygons() {
+        let square = vertices(&[
+            (170.0, 0.0),
+            (-170.0, 0.0),
+            (-170.0, 10.0),
+            (170.0, 10.0),
+            (170.0, 0.0),
+        ]);
+        let polygons = split_polygon(&[square]);
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(
+            xy(&polygons[0][0]),
+            vec![
+                (170.0, 0.0),
+                (180.0, 0.0),
+                (180.0, 10.0),
+                (170.0, 10.0),
+                (170.0, 0.0)
+            ]
+        );
+        assert_eq!(
+            xy(&polygons[1][0]),
+            vec![
+                (-180.0, 0.0),
+                (-170.0, 0.0),
+                (-170.0, 10.0),
+                (-180.0, 10.0),
+                (-180.0, 0.0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::error::Result;
+use crate::processor::{Extent, FeatureBuffer};
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Passes on features intersecting a bounding box.
+///
+/// Features are buffered until their extent is known, which makes spatial
+/// filtering possible for formats without spatial index. Features without
+/// coordinates are dropped and passed features are renumbered. Geometries
+/// processed outside of a feature are passed on unfiltered.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::{BboxFilter, Extent};
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}}
+/// ]}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut filter = BboxFilter::new(GeoJsonWriter::new(&mut out), Extent::new(5.9, 45.8, 10.5, 47.8));
+/// GeoJson(geojson).process(&mut filter).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains("Bern") && !json.contains("Paris"));
+/// ```
+pub struct BboxFilter<P> {
+    buffer: FeatureBuffer<P>,
+    extent: Extent,
+    feature_extent: Option<Extent>,
+    in_feature: bool,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> BboxFilter<P> {
+    pub fn new(inner: P, extent: Extent) -> Self {
+        BboxFilter {
+            buffer: FeatureBuffer::new(inner),
+            extent,
+            feature_extent: None,
+            in_feature: false,
+            count: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume filter and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        if !self.in_feature {
+            return;
+        }
+        match &mut self.feature_extent {
+            Some(extent) => extent.extend(x, y),
+            None => self.feature_extent = Some(Extent::from_point(x, y)),
+        }
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for BboxFilter<P> {
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.add_vertex(x, y);
+        self.buffer.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.add_vertex(x, y);
+        self.buffer.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for BboxFilter<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for BboxFilter<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.in_feature = true;
+        self.feature_extent = None;
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.in_feature = false;
+        self.buffer.feature_end(idx)?;
+        match self.feature_extent.take() {
+            Some(extent) if extent.intersects(&self.extent) => {
+                self.count += 1;
+                self.buffer.replay(self.count - 1)
+            }
+            _ => {
+                self.buffer.discard();
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnValue;
+
+    /// Collects feature indices and the `name` property of processed features
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl GeomProcessor for Names {}
+
+    impl PropertyProcessor for Names {
+        fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+            if name == "name" {
+                self.0.push(value.to_string());
+            }
+            Ok(false)
+        }
+    }
+
+    impl FeatureProcessor for Names {
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push(idx.to_string());
+            Ok(())
+        }
+    }
+
+    fn point_feature(
+        processor: &mut impl FeatureProcessor,
+        idx: u64,
+        name: &str,
+        x: f64,
+        y: f64,
+    ) -> Result<()> {
+        processor.feature_begin(idx)?;
+        processor.properties_begin()?;
+        processor.property(0, "name", &ColumnValue::String(name))?;
+        processor.properties_end()?;
+        processor.geometry_begin()?;
+        processor.point_begin(0)?;
+        processor.xy(x, y, 0)?;
+        processor.point_end(0)?;
+        processor.geometry_end()?;
+        processor.feature_end(idx)
+    }
+
+    #[test]
+    fn filter() -> Result<()> {
+        let mut filter = BboxFilter::new(Names::default(), Extent::new(0.0, 0.0, 10.0, 10.0));
+        filter.dataset_begin(None)?;
+        point_feature(&mut filter, 0, "inside", 5.0, 5.0)?;
+        point_feature(&mut filter, 1, "outside", 15.0, 5.0)?;
+        point_feature(&mut filter, 2, "boundary", 10.0, 0.0)?;
+        filter.dataset_end()?;
+        assert_eq!(filter.into_inner().0, vec!["0", "inside", "1", "boundary"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Forwards all calls to a borrowed processor.
+///
+/// Allows wrapping processors, which take ownership of their inner processor,
+/// around a processor passed by reference.
+pub(crate) struct Borrowed<'a, P> {
+    inner: &'a mut P,
+}
+
+impl<'a, P> Borrowed<'a, P> {
+    pub fn new(inner: &'a mut P) -> Self {
+        Borrowed { inner }
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for Borrowed<'_, P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for Borrowed<'_, P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Borrowed<'_, P> {
+    forward_processor_methods!(inner; feature_processor);
+}
@@ -0,0 +1,424 @@
+use crate::error::Result;
+use crate::{
+    ColumnDef, ColumnValue, FeatureProcessor, GeomProcessor, OwnedColumnValue, PropertyProcessor,
+};
+
+/// Recorded processor call
+///
+/// Variants correspond to the processor methods with their arguments. Schema and CRS
+/// calls are not recorded.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Event {
+    Srid(Option<i32>),
+    Xy(f64, f64, usize),
+    Coordinate(
+        f64,
+        f64,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<u64>,
+        usize,
+    ),
+    EmptyPoint(usize),
+    PointBegin(usize),
+    PointEnd(usize),
+    MultiPointBegin(usize, usize),
+    MultiPointEnd(usize),
+    LineStringBegin(bool, usize, usize),
+    LineStringEnd(bool, usize),
+    MultiLineStringBegin(usize, usize),
+    MultiLineStringEnd(usize),
+    PolygonBegin(bool, usize, usize),
+    PolygonEnd(bool, usize),
+    MultiPolygonBegin(usize, usize),
+    MultiPolygonEnd(usize),
+    GeometryCollectionBegin(usize, usize),
+    GeometryCollectionEnd(usize),
+    CircularStringBegin(usize, usize),
+    CircularStringEnd(usize),
+    CompoundCurveBegin(usize, usize),
+    CompoundCurveEnd(usize),
+    CurvePolygonBegin(usize, usize),
+    CurvePolygonEnd(usize),
+    MultiCurveBegin(usize, usize),
+    MultiCurveEnd(usize),
+    MultiSurfaceBegin(usize, usize),
+    MultiSurfaceEnd(usize),
+    TriangleBegin(bool, usize, usize),
+    TriangleEnd(bool, usize),
+    PolyhedralSurfaceBegin(usize, usize),
+    PolyhedralSurfaceEnd(usize),
+    TinBegin(usize, usize),
+    TinEnd(usize),
+    Property(usize, String, OwnedColumnValue),
+    FeatureBegin(u64),
+    Fid(OwnedColumnValue),
+    FeatureEnd(u64),
+    PropertiesBegin,
+    PropertiesEnd,
+    GeometryBegin,
+    GeometryEnd,
+    DatasetBegin(Option<String>),
+    DatasetEnd,
+}
+
+impl Event {
+    /// Pass recorded call to `processor`
+    pub fn replay<P: FeatureProcessor>(&self, processor: &mut P) -> Result<()> {
+        match self {
+            Event::Property(idx, name, value) => processor
+                .property(*idx, name, &value.as_column_value())
+                .map(|_| ()),
+            Event::FeatureBegin(idx) => processor.feature_begin(*idx),
+            Event::Fid(fid) => processor.fid(&fid.as_column_value()),
+            Event::FeatureEnd(idx) => processor.feature_end(*idx),
+            Event::PropertiesBegin => processor.properties_begin(),
+            Event::PropertiesEnd => processor.properties_end(),
+            Event::GeometryBegin => processor.geometry_begin(),
+            Event::GeometryEnd => processor.geometry_end(),
+            Event::DatasetBegin(name) => processor.dataset_begin(name.as_deref()),
+            Event::DatasetEnd => processor.dataset_end(),
+            _ => self.replay_geom(processor),
+        }
+    }
+    /// Pass recorded geometry call to `processor`, ignoring feature and property calls
+    pub fn replay_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        match self {
+            Event::Srid(srid) => processor.srid(*srid),
+            Event::Xy(x, y, idx) => processor.xy(*x, *y, *idx),
+            Event::Coordinate(x, y, z, m, t, tm, idx) => {
+                processor.coordinate(*x, *y, *z, *m, *t, *tm, *idx)
+            }
+            Event::EmptyPoint(idx) => processor.empty_point(*idx),
+            Event::PointBegin(idx) => processor.point_begin(*idx),
+            Event::PointEnd(idx) => processor.point_end(*idx),
+            Event::MultiPointBegin(size, idx) => processor.multipoint_begin(*size, *idx),
+            Event::MultiPointEnd(idx) => processor.multipoint_end(*idx),
+            Event::LineStringBegin(tagged, size, idx) => {
+                processor.linestring_begin(*tagged, *size, *idx)
+            }
+            Event::LineStringEnd(tagged, idx) => processor.linestring_end(*tagged, *idx),
+            Event::MultiLineStringBegin(size, idx) => processor.multilinestring_begin(*size, *idx),
+            Event::MultiLineStringEnd(idx) => processor.multilinestring_end(*idx),
+            Event::PolygonBegin(tagged, size, idx) => processor.polygon_begin(*tagged, *size, *idx),
+            Event::PolygonEnd(tagged, idx) => processor.polygon_end(*tagged, *idx),
+            Event::MultiPolygonBegin(size, idx) => processor.multipolygon_begin(*size, *idx),
+            Event::MultiPolygonEnd(idx) => processor.multipolygon_end(*idx),
+            Event::GeometryCollectionBegin(size, idx) => {
+                processor.geometrycollection_begin(*size, *idx)
+            }
+            Event::GeometryCollectionEnd(idx) => processor.geometrycollection_end(*idx),
+            Event::CircularStringBegin(size, idx) => processor.circularstring_begin(*size, *idx),
+            Event::CircularStringEnd(idx) => processor.circularstring_end(*idx),
+            Event::CompoundCurveBegin(size, idx) => processor.compoundcurve_begin(*size, *idx),
+            Event::CompoundCurveEnd(idx) => processor.compoundcurve_end(*idx),
+            Event::CurvePolygonBegin(size, idx) => processor.curvepolygon_begin(*size, *idx),
+            Event::CurvePolygonEnd(idx) => processor.curvepolygon_end(*idx),
+            Event::MultiCurveBegin(size, idx) => processor.multicurve_begin(*size, *idx),
+            Event::MultiCurveEnd(idx) => processor.multicurve_end(*idx),
+            Event::MultiSurfaceBegin(size, idx) => processor.multisurface_begin(*size, *idx),
+            Event::MultiSurfaceEnd(idx) => processor.multisurface_end(*idx),
+            Event::TriangleBegin(tagged, size, idx) => {
+                processor.triangle_begin(*tagged, *size, *idx)
+            }
+            Event::TriangleEnd(tagged, idx) => processor.triangle_end(*tagged, *idx),
+            Event::PolyhedralSurfaceBegin(size, idx) => {
+                processor.polyhedralsurface_begin(*size, *idx)
+            }
+            Event::PolyhedralSurfaceEnd(idx) => processor.polyhedralsurface_end(*idx),
+            Event::TinBegin(size, idx) => processor.tin_begin(*size, *idx),
+            Event::TinEnd(idx) => processor.tin_end(*idx),
+            Event::Property(..)
+            | Event::FeatureBegin(_)
+            | Event::Fid(_)
+            | Event::FeatureEnd(_)
+            | Event::PropertiesBegin
+            | Event::PropertiesEnd
+            | Event::GeometryBegin
+            | Event::GeometryEnd
+            | Event::DatasetBegin(_)
+            | Event::DatasetEnd => Ok(()),
+        }
+    }
+    /// Change of geometry nesting level: 1 for `*_begin`, -1 for `*_end`, 0 otherwise
+    pub fn nesting(&self) -> i32 {
+        match self {
+            Event::PointBegin(..)
+            | Event::MultiPointBegin(..)
+            | Event::LineStringBegin(..)
+            | Event::MultiLineStringBegin(..)
+            | Event::PolygonBegin(..)
+            | Event::MultiPolygonBegin(..)
+            | Event::GeometryCollectionBegin(..)
+            | Event::CircularStringBegin(..)
+            | Event::CompoundCurveBegin(..)
+            | Event::CurvePolygonBegin(..)
+            | Event::MultiCurveBegin(..)
+            | Event::MultiSurfaceBegin(..)
+            | Event::TriangleBegin(..)
+            | Event::PolyhedralSurfaceBegin(..)
+            | Event::TinBegin(..) => 1,
+            Event::PointEnd(..)
+            | Event::MultiPointEnd(..)
+            | Event::LineStringEnd(..)
+            | Event::MultiLineStringEnd(..)
+            | Event::PolygonEnd(..)
+            | Event::MultiPolygonEnd(..)
+            | Event::GeometryCollectionEnd(..)
+            | Event::CircularStringEnd(..)
+            | Event::CompoundCurveEnd(..)
+            | Event::CurvePolygonEnd(..)
+            | Event::MultiCurveEnd(..)
+            | Event::MultiSurfaceEnd(..)
+            | Event::TriangleEnd(..)
+            | Event::PolyhedralSurfaceEnd(..)
+            | Event::TinEnd(..) => -1,
+            _ => 0,
+        }
+    }
+}
+
+/// Pass recorded feature calls to `processor`, appending property `name` with `value`.
+///
+/// The property is added as last feature property, features without properties get
+/// a properties block before their geometry. A recorded property with the same name
+/// is replaced, so that no duplicate property names are passed on.
+pub(crate) fn replay_with_property<P: FeatureProcessor>(
+    events: &[Event],
+    processor: &mut P,
+    name: &str,
+    value: &ColumnValue,
+) -> Result<()> {
+    let mut property_count = 0;
+    let mut added = false;
+    for event in events {
+        match event {
+            Event::Property(_, n, _) if n == name => continue,
+            Event::Property(_, n, v) => {
+                processor.property(property_count, n, &v.as_column_value())?;
+                property_count += 1;
+                continue;
+            }
+            Event::PropertiesEnd if !added => {
+                processor.property(property_count, name, value)?;
+                added = true;
+            }
+            Event::GeometryBegin | Event::FeatureEnd(_) if !added => {
+                processor.properties_begin()?;
+                processor.property(0, name, value)?;
+                processor.properties_end()?;
+                added = true;
+            }
+            _ => {}
+        }
+        event.replay(processor)?;
+    }
+    Ok(())
+}
+
+/// Schema `columns` with `column` appended, replacing a column with the same name.
+///
+/// Matches the properties passed by [replay_with_property].
+pub(crate) fn schema_with_column(columns: &[ColumnDef], column: ColumnDef) -> Vec<ColumnDef> {
+    let mut columns: Vec<ColumnDef> = columns
+        .iter()
+        .filter(|col| col.name != column.name)
+        .cloned()
+        .collect();
+    columns.push(column);
+    columns
+}
+
+/// Passes calls to the wrapped processor or records them for later replay.
+///
+/// Used by processors which can decide about passing on a feature only after
+/// having seen all of its properties and coordinates.
+pub(crate) struct FeatureBuffer<P> {
+    inner: P,
+    recording: bool,
+    events: Vec<Event>,
+}
+
+impl<P> FeatureBuffer<P> {
+    pub fn new(inner: P) -> Self {
+        FeatureBuffer {
+            inner,
+            recording: false,
+            events: Vec::new(),
+        }
+    }
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    /// Start recording calls
+    pub fn record(&mut self) {
+        self.recording = true;
+        self.events.clear();
+    }
+    /// Stop recording and drop recorded calls
+    pub fn discard(&mut self) {
+        self.recording = false;
+        self.events.clear();
+    }
+    /// Recorded calls
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+    /// Record `event` without passing it on
+    pub fn push(&mut self, event: Event) {
+        self.events.push(event);
+    }
+    /// Stop recording and return recorded calls
+    pub fn take(&mut self) -> Vec<Event> {
+        self.recording = false;
+        std::mem::take(&mut self.events)
+    }
+    fn record_or_forward(&mut self, event: Event, forward: fn(&mut P) -> Result<()>) -> Result<()> {
+        if self.recording {
+            self.events.push(event);
+            Ok(())
+        } else {
+            forward(&mut self.inner)
+        }
+    }
+}
+
+impl<P: FeatureProcessor> FeatureBuffer<P> {
+    /// Stop recording and pass recorded calls on with feature index `feature_idx`
+    pub fn replay(&mut self, feature_idx: u64) -> Result<()> {
+        self.recording = false;
+        for event in &self.events {
+            match event {
+                Event::FeatureBegin(_) => self.inner.feature_begin(feature_idx)?,
+                Event::FeatureEnd(_) => self.inner.feature_end(feature_idx)?,
+                _ => event.replay(&mut self.inner)?,
+            }
+        }
+        self.events.clear();
+        Ok(())
+    }
+}
+
+/// Record call as `Event` or pass it to the wrapped processor
+macro_rules! record_or_forward {
+    ($( $method:ident($($arg:ident: $ty:ty),*) => $event:ident; )*) => {
+        $(
+            fn $method(&mut self $(, $arg: $ty)*) -> Result<()> {
+                if self.recording {
+                    self.events.push(Event::$event($($arg),*));
+                    Ok(())
+                } else {
+                    self.inner.$method($($arg),*)
+                }
+            }
+        )*
+    };
+}
+
+impl<P: GeomProcessor> GeomProcessor for FeatureBuffer<P> {
+    forward_processor_methods!(inner; dimensions);
+
+    record_or_forward! {
+        srid(srid: Option<i32>) => Srid;
+        xy(x: f64, y: f64, idx: usize) => Xy;
+        empty_point(idx: usize) => EmptyPoint;
+        point_begin(idx: usize) => PointBegin;
+        point_end(idx: usize) => PointEnd;
+        multipoint_begin(size: usize, idx: usize) => MultiPointBegin;
+        multipoint_end(idx: usize) => MultiPointEnd;
+        linestring_begin(tagged: bool, size: usize, idx: usize) => LineStringBegin;
+        linestring_end(tagged: bool, idx: usize) => LineStringEnd;
+        multilinestring_begin(size: usize, idx: usize) => MultiLineStringBegin;
+        multilinestring_end(idx: usize) => MultiLineStringEnd;
+        polygon_begin(tagged: bool, size: usize, idx: usize) => PolygonBegin;
+        polygon_end(tagged: bool, idx: usize) => PolygonEnd;
+        multipolygon_begin(size: usize, idx: usize) => MultiPolygonBegin;
+        multipolygon_end(idx: usize) => MultiPolygonEnd;
+        geometrycollection_begin(size: usize, idx: usize) => GeometryCollectionBegin;
+        geometrycollection_end(idx: usize) => GeometryCollectionEnd;
+        circularstring_begin(size: usize, idx: usize) => CircularStringBegin;
+        circularstring_end(idx: usize) => CircularStringEnd;
+        compoundcurve_begin(size: usize, idx: usize) => CompoundCurveBegin;
+        compoundcurve_end(idx: usize) => CompoundCurveEnd;
+        curvepolygon_begin(size: usize, idx: usize) => CurvePolygonBegin;
+        curvepolygon_end(idx: usize) => CurvePolygonEnd;
+        multicurve_begin(size: usize, idx: usize) => MultiCurveBegin;
+        multicurve_end(idx: usize) => MultiCurveEnd;
+        multisurface_begin(size: usize, idx: usize) => MultiSurfaceBegin;
+        multisurface_end(idx: usize) => MultiSurfaceEnd;
+        triangle_begin(tagged: bool, size: usize, idx: usize) => TriangleBegin;
+        triangle_end(tagged: bool, idx: usize) => TriangleEnd;
+        polyhedralsurface_begin(size: usize, idx: usize) => PolyhedralSurfaceBegin;
+        polyhedralsurface_end(idx: usize) => PolyhedralSurfaceEnd;
+        tin_begin(size: usize, idx: usize) => TinBegin;
+        tin_end(idx: usize) => TinEnd;
+    }
+
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.recording {
+            self.events.push(Event::Coordinate(x, y, z, m, t, tm, idx));
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for FeatureBuffer<P> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if self.recording {
+            self.events
+                .push(Event::Property(idx, name.to_string(), value.into()));
+            Ok(false)
+        } else {
+            self.inner.property(idx, name, value)
+        }
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for FeatureBuffer<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning);
+
+    record_or_forward! {
+        feature_begin(idx: u64) => FeatureBegin;
+        feature_end(idx: u64) => FeatureEnd;
+    }
+
+    fn fid(&mut self, fid: &ColumnValue) -> Result<()> {
+        if self.recording {
+            self.events.push(Event::Fid(fid.into()));
+            Ok(())
+        } else {
+            self.inner.fid(fid)
+        }
+    }
+
+    fn properties_begin(&mut self) -> Result<()> {
+        self.record_or_forward(Event::PropertiesBegin, |p| p.properties_begin())
+    }
+    fn properties_end(&mut self) -> Result<()> {
+        self.record_or_forward(Event::PropertiesEnd, |p| p.properties_end())
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.record_or_forward(Event::GeometryBegin, |p| p.geometry_begin())
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        self.record_or_forward(Event::GeometryEnd, |p| p.geometry_end())
+    }
+}
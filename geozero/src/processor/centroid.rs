@@ -0,0 +1,449 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Representative point of a geometry
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PointMethod {
+    /// Center of mass of the components with the highest dimension
+    Centroid,
+    /// Point guaranteed to lie on the geometry (in the interior of the largest polygon)
+    PointOnSurface,
+}
+
+/// Replaces geometries with a representative point.
+///
+/// Each geometry is buffered and replaced by its centroid or by a point on its
+/// surface, useful for labeling or clustering. Curves are approximated by their
+/// vertices, empty geometries are replaced by an empty point.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{PointMethod, ReplaceWithPoint};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = ReplaceWithPoint::new(WktWriter::new(&mut out), PointMethod::Centroid);
+/// WktStr("POLYGON((0 0,4 0,4 2,0 2,0 0))").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(2 1)");
+/// ```
+pub struct ReplaceWithPoint<P> {
+    inner: P,
+    method: PointMethod,
+    /// Geometry nesting level
+    depth: usize,
+    points: Vec<(f64, f64)>,
+    lines: Vec<Vec<(f64, f64)>>,
+    polygons: Vec<Vec<Vec<(f64, f64)>>>,
+    in_polygon: bool,
+    in_curve: bool,
+    in_compound: bool,
+}
+
+impl<P> ReplaceWithPoint<P> {
+    pub fn new(inner: P, method: PointMethod) -> Self {
+        ReplaceWithPoint {
+            inner,
+            method,
+            depth: 0,
+            points: Vec::new(),
+            lines: Vec::new(),
+            polygons: Vec::new(),
+            in_polygon: false,
+            in_curve: false,
+            in_compound: false,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        let vertices = if !self.in_curve {
+            Some(&mut self.points)
+        } else if self.in_polygon {
+            self.polygons.last_mut().and_then(|rings| rings.last_mut())
+        } else {
+            self.lines.last_mut()
+        };
+        if let Some(vertices) = vertices {
+            vertices.push((x, y));
+        }
+    }
+    fn curve_begin(&mut self) {
+        if !self.in_compound {
+            if self.in_polygon {
+                if let Some(rings) = self.polygons.last_mut() {
+                    rings.push(Vec::new());
+                }
+            } else {
+                self.lines.push(Vec::new());
+            }
+        }
+        self.in_curve = true;
+    }
+    fn curve_end(&mut self) {
+        if !self.in_compound {
+            self.in_curve = false;
+        }
+    }
+    fn polygon_begin(&mut self) {
+        self.polygons.push(Vec::new());
+        self.in_polygon = true;
+    }
+    /// Representative point of the buffered geometry
+    fn point(&self) -> Option<(f64, f64)> {
+        let centroid = self.centroid()?;
+        match self.method {
+            PointMethod::Centroid => Some(centroid),
+            PointMethod::PointOnSurface => Some(self.point_on_surface(centroid)),
+        }
+    }
+    fn centroid(&self) -> Option<(f64, f64)> {
+        let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for rings in &self.polygons {
+            for (i, ring) in rings.iter().enumerate() {
+                let (a, x, y) = ring_centroid(ring);
+                // Holes are subtracted independent of their orientation
+                let weight = if i == 0 { a.abs() } else { -a.abs() };
+                area += weight;
+                cx += weight * x;
+                cy += weight * y;
+            }
+        }
+        if area > 0.0 {
+            return Some((cx / area, cy / area));
+        }
+        let (mut length, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for line in self.lines.iter().chain(self.polygons.iter().flatten()) {
+            for (a, b) in line.iter().zip(line.iter().skip(1)) {
+                let len = (b.0 - a.0).hypot(b.1 - a.1);
+                length += len;
+                cx += len * (a.0 + b.0) / 2.0;
+                cy += len * (a.1 + b.1) / 2.0;
+            }
+        }
+        if length > 0.0 {
+            return Some((cx / length, cy / length));
+        }
+        let vertices = self.vertices().collect::<Vec<_>>();
+        if vertices.is_empty() {
+            return None;
+        }
+        let n = vertices.len() as f64;
+        Some((
+            vertices.iter().map(|v| v.0).sum::<f64>() / n,
+            vertices.iter().map(|v| v.1).sum::<f64>() / n,
+        ))
+    }
+    fn vertices(&self) -> impl Iterator<Item = &(f64, f64)> {
+        self.points
+            .iter()
+            .chain(self.lines.iter().flatten())
+            .chain(self.polygons.iter().flatten().flatten())
+    }
+    fn point_on_surface(&self, centroid: (f64, f64)) -> (f64, f64) {
+        let largest = self
+            .polygons
+            .iter()
+            .map(|rings| (rings, rings.first().map(|ring| ring_centroid(ring).0.abs())))
+            .filter_map(|(rings, area)| area.filter(|a| *a > 0.0).map(|a| (rings, a)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some(point) = largest.and_then(|(rings, _)| interior_point(rings)) {
+            return point;
+        }
+        // Vertex nearest to the centroid
+        let dist = |v: &&(f64, f64)| (v.0 - centroid.0).hypot(v.1 - centroid.1);
+        self.vertices()
+            .min_by(|a, b| {
+                dist(a)
+                    .partial_cmp(&dist(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .copied()
+            .unwrap_or(centroid)
+    }
+}
+
+/// Signed area and centroid of a ring
+fn ring_centroid(ring: &[(f64, f64)]) -> (f64, f64, f64) {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    let closing = ring.last().copied().zip(ring.first().copied());
+    for (a, b) in ring
+        .iter()
+        .copied()
+        .zip(ring.iter().copied().skip(1))
+        .chain(closing)
+    {
+        let cross = a.0 * b.1 - b.0 * a.1;
+        area += cross;
+        cx += (a.0 + b.0) * cross;
+        cy += (a.1 + b.1) * cross;
+    }
+    if area == 0.0 {
+        (0.0, 0.0, 0.0)
+    } else {
+        (area / 2.0, cx / (3.0 * area), cy / (3.0 * area))
+    }
+}
+
+/// Midpoint of the widest interior section of a horizontal line through the middle of the polygon
+fn interior_point(rings: &[Vec<(f64, f64)>]) -> Option<(f64, f64)> {
+    let exterior = rings.first()?;
+    let miny = exterior.iter().map(|v| v.1).fold(f64::INFINITY, f64::min);
+    let maxy = exterior
+        .iter()
+        .map(|v| v.1)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let y = (miny + maxy) / 2.0;
+    let mut crossings = Vec::new();
+    for ring in rings {
+        let closing = ring.last().copied().zip(ring.first().copied());
+        for (a, b) in ring
+            .iter()
+            .copied()
+            .zip(ring.iter().copied().skip(1))
+            .chain(closing)
+        {
+            if (a.1 <= y && y < b.1) || (b.1 <= y && y < a.1) {
+                crossings.push(a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0));
+            }
+        }
+    }
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    crossings
+        .chunks_exact(2)
+        .max_by(|a, b| {
+            (a[1] - a[0])
+                .partial_cmp(&(b[1] - b[0]))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .filter(|section| section[1] > section[0])
+        .map(|section| ((section[0] + section[1]) / 2.0, y))
+}
+
+impl<P: GeomProcessor> ReplaceWithPoint<P> {
+    fn begin(&mut self) {
+        self.depth += 1;
+    }
+    /// Pass representative point when the outermost geometry is complete
+    fn end(&mut self) -> Result<()> {
+        self.depth -= 1;
+        if self.depth > 0 {
+            return Ok(());
+        }
+        let point = self.point();
+        self.points.clear();
+        self.lines.clear();
+        self.polygons.clear();
+        match point {
+            Some((x, y)) => {
+                self.inner.point_begin(0)?;
+                self.inner.xy(x, y, 0)?;
+                self.inner.point_end(0)
+            }
+            None => self.inner.empty_point(0),
+        }
+    }
+}
+
+/// Track nesting of geometries without further processing
+macro_rules! nesting {
+    ($( $begin:ident($($arg:ident: $ty:ty),*), $end:ident; )*) => {
+        $(
+            fn $begin(&mut self $(, $arg: $ty)*) -> Result<()> {
+                self.begin();
+                Ok(())
+            }
+            fn $end(&mut self, _idx: usize) -> Result<()> {
+                self.end()
+            }
+        )*
+    };
+}
+
+impl<P: GeomProcessor> GeomProcessor for ReplaceWithPoint<P> {
+    forward_processor_methods!(inner; srid);
+
+    nesting! {
+        point_begin(_idx: usize), point_end;
+        multipoint_begin(_size: usize, _idx: usize), multipoint_end;
+        multilinestring_begin(_size: usize, _idx: usize), multilinestring_end;
+        multipolygon_begin(_size: usize, _idx: usize), multipolygon_end;
+        geometrycollection_begin(_size: usize, _idx: usize), geometrycollection_end;
+        multicurve_begin(_size: usize, _idx: usize), multicurve_end;
+        multisurface_begin(_size: usize, _idx: usize), multisurface_end;
+        polyhedralsurface_begin(_size: usize, _idx: usize), polyhedralsurface_end;
+        tin_begin(_size: usize, _idx: usize), tin_end;
+    }
+
+    fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+        self.add_vertex(x, y);
+        Ok(())
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        _z: Option<f64>,
+        _m: Option<f64>,
+        _t: Option<f64>,
+        _tm: Option<u64>,
+        _idx: usize,
+    ) -> Result<()> {
+        self.add_vertex(x, y);
+        Ok(())
+    }
+    fn empty_point(&mut self, _idx: usize) -> Result<()> {
+        self.begin();
+        self.end()
+    }
+    fn linestring_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.curve_begin();
+        Ok(())
+    }
+    fn linestring_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.curve_end();
+        self.end()
+    }
+    fn circularstring_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.curve_begin();
+        Ok(())
+    }
+    fn circularstring_end(&mut self, _idx: usize) -> Result<()> {
+        self.curve_end();
+        self.end()
+    }
+    fn compoundcurve_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.curve_begin();
+        self.in_compound = true;
+        Ok(())
+    }
+    fn compoundcurve_end(&mut self, _idx: usize) -> Result<()> {
+        self.in_compound = false;
+        self.curve_end();
+        self.end()
+    }
+    fn polygon_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.polygon_begin();
+        Ok(())
+    }
+    fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.in_polygon = false;
+        self.end()
+    }
+    fn triangle_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.polygon_begin();
+        Ok(())
+    }
+    fn triangle_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.in_polygon = false;
+        self.end()
+    }
+    fn curvepolygon_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin();
+        self.polygon_begin();
+        Ok(())
+    }
+    fn curvepolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.in_polygon = false;
+        self.end()
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for ReplaceWithPoint<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ReplaceWithPoint<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Collects points
+    #[derive(Default)]
+    struct Points(Vec<(f64, f64)>);
+
+    impl GeomProcessor for Points {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.push((x, y));
+            Ok(())
+        }
+    }
+
+    fn polygon(processor: &mut impl GeomProcessor, rings: &[&[(f64, f64)]]) -> Result<()> {
+        processor.polygon_begin(true, rings.len(), 0)?;
+        for (i, ring) in rings.iter().enumerate() {
+            processor.linestring_begin(false, ring.len(), i)?;
+            for (idx, (x, y)) in ring.iter().enumerate() {
+                processor.xy(*x, *y, idx)?;
+            }
+            processor.linestring_end(false, i)?;
+        }
+        processor.polygon_end(true, 0)
+    }
+
+    #[test]
+    fn centroid_with_hole() -> Result<()> {
+        let mut processor = ReplaceWithPoint::new(Points::default(), PointMethod::Centroid);
+        polygon(
+            &mut processor,
+            &[
+                &[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)],
+                &[(0.0, 0.0), (0.0, 2.0), (4.0, 2.0), (4.0, 0.0), (0.0, 0.0)],
+            ],
+        )?;
+        assert_eq!(processor.into_inner().0, vec![(2.0, 3.0)]);
+        Ok(())
+    }
+
+    #[test]
+    fn point_on_surface() -> Result<()> {
+        // U-shaped polygon with centroid outside
+        let ring = [
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 3.0),
+            (2.0, 3.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 3.0),
+            (0.0, 3.0),
+            (0.0, 0.0),
+        ];
+        let mut processor = ReplaceWithPoint::new(Points::default(), PointMethod::PointOnSurface);
+        polygon(&mut processor, &[&ring])?;
+        assert_eq!(processor.into_inner().0, vec![(2.5, 1.5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn line_centroid() -> Result<()> {
+        let mut processor = ReplaceWithPoint::new(Points::default(), PointMethod::Centroid);
+        processor.multilinestring_begin(2, 0)?;
+        processor.linestring_begin(false, 2, 0)?;
+        processor.xy(0.0, 0.0, 0)?;
+        processor.xy(2.0, 0.0, 1)?;
+        processor.linestring_end(false, 0)?;
+        processor.linestring_begin(false, 2, 1)?;
+        processor.xy(0.0, 2.0, 0)?;
+        processor.xy(2.0, 2.0, 1)?;
+        processor.linestring_end(false, 1)?;
+        processor.multilinestring_end(0)?;
+        assert_eq!(processor.into_inner().0, vec![(1.0, 1.0)]);
+        Ok(())
+    }
+}
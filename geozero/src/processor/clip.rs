@@ -0,0 +1,711 @@
+use crate::error::Result;
+use crate::processor::{Extent, FeatureBuffer, Vertex};
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Geometry currently buffered
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Buffered {
+    Point(usize),
+    MultiPoint(usize),
+    LineString(usize),
+    MultiLineString(usize),
+    Polygon(usize),
+    MultiPolygon(usize),
+}
+
+/// Clips geometries to a rectangular extent.
+///
+/// Points outside the extent are removed, linestrings are cut at the extent border
+/// (Liang-Barsky) and the ring parts of polygons within the extent are joined along
+/// its border (Weiler-Atherton).
+/// A LineString leaving and re-entering the extent becomes a MultiLineString, a Polygon
+/// falling apart into several parts becomes a MultiPolygon.
+/// Geometries completely outside are replaced by empty geometries and features
+/// without remaining coordinates are dropped, passed features are renumbered.
+/// Curves are passed on unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{ClipToExtent, Extent};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = ClipToExtent::new(WktWriter::new(&mut out), Extent::new(0.0, 0.0, 10.0, 10.0));
+/// WktStr("POLYGON((5 5,15 5,15 15,5 15,5 5))").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POLYGON((5 10,5 5,10 5,10 10,5 10))");
+/// ```
+pub struct ClipToExtent<P> {
+    buffer: FeatureBuffer<P>,
+    extent: Extent,
+    buffered: Option<Buffered>,
+    points: Vec<Vertex>,
+    lines: Vec<Vec<Vertex>>,
+    polygons: Vec<Vec<Vec<Vertex>>>,
+    /// Current feature has coordinates within the extent
+    has_vertices: bool,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> ClipToExtent<P> {
+    pub fn new(inner: P, extent: Extent) -> Self {
+        ClipToExtent {
+            buffer: FeatureBuffer::new(inner),
+            extent,
+            buffered: None,
+            points: Vec::new(),
+            lines: Vec::new(),
+            polygons: Vec::new(),
+            has_vertices: false,
+            count: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    fn add_vertex(&mut self, vertex: Vertex) {
+        let vertices = match self.buffered {
+            Some(Buffered::Point(_)) | Some(Buffered::MultiPoint(_)) => Some(&mut self.points),
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.last_mut()
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.last_mut().and_then(|rings| rings.last_mut())
+            }
+            None => None,
+        };
+        if let Some(vertices) = vertices {
+            vertices.push(vertex);
+        }
+    }
+}
+
+/// Parameter range of the segment from `a` to `b` within `extent` (Liang-Barsky)
+fn clip_segment(a: &Vertex, b: &Vertex, extent: &Extent) -> Option<(f64, f64)> {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let (mut t0, mut t1) = (0.0, 1.0);
+    for (p, q) in [
+        (-dx, a.x - extent.minx),
+        (dx, extent.maxx - a.x),
+        (-dy, a.y - extent.miny),
+        (dy, extent.maxy - a.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                if r > t1 {
+                    return None;
+                }
+                t0 = r.max(t0);
+            } else {
+                if r < t0 {
+                    return None;
+                }
+                t1 = r.min(t1);
+            }
+        }
+    }
+    Some((t0, t1))
+}
+
+fn same_position(a: &Vertex, b: &Vertex) -> bool {
+    a.x == b.x && a.y == b.y
+}
+
+/// Parts of `line` within `extent`
+fn clip_line(line: &[Vertex], extent: &Extent) -> Vec<Vec<Vertex>> {
+    let mut parts = Vec::new();
+    let mut part: Vec<Vertex> = Vec::new();
+    for (a, b) in line.iter().zip(line.iter().skip(1)) {
+        match clip_segment(a, b, extent) {
+            Some((t0, t1)) => {
+                let start = if t0 > 0.0 { a.at(b, t0) } else { *a };
+                let end = if t1 < 1.0 { a.at(b, t1) } else { *b };
+                if !matches!(part.last(), Some(last) if same_position(last, &start)) {
+                    parts.push(std::mem::take(&mut part));
+                    part.push(start);
+                }
+                part.push(end);
+            }
+            None => parts.push(std::mem::take(&mut part)),
+        }
+    }
+    parts.push(part);
+    parts.retain(|part| part.iter().any(|v| !same_position(v, &part[0])));
+    parts
+}
+
+/// Twice the signed area of `ring`, positive for counterclockwise rings
+fn signed_area(ring: &[Vertex]) -> f64 {
+    ring.iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| a.x * b.y - b.x * a.y)
+        .sum()
+}
+
+/// Check whether point is within `ring` (even-odd rule)
+fn ring_contains(ring: &[Vertex], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    for (a, b) in ring.iter().zip(ring.iter().cycle().skip(1)) {
+        if (a.y > y) != (b.y > y) && x < a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Corners of `extent` counterclockwise with their boundary position
+fn corners(extent: &Extent) -> [(f64, f64, f64); 4] {
+    let (w, h) = (extent.maxx - extent.minx, extent.maxy - extent.miny);
+    [
+        (0.0, extent.minx, extent.miny),
+        (w, extent.maxx, extent.miny),
+        (w + h, extent.maxx, extent.maxy),
+        (2.0 * w + h, extent.minx, extent.maxy),
+    ]
+}
+
+/// Position of `v` on the boundary of `extent`, counterclockwise from the lower left corner
+fn boundary_position(v: &Vertex, extent: &Extent) -> f64 {
+    let (w, h) = (extent.maxx - extent.minx, extent.maxy - extent.miny);
+    // (distance to edge, position on edge)
+    let edges = [
+        ((v.y - extent.miny).abs(), v.x - extent.minx),
+        ((v.x - extent.maxx).abs(), w + v.y - extent.miny),
+        ((v.y - extent.maxy).abs(), w + h + extent.maxx - v.x),
+        ((v.x - extent.minx).abs(), 2.0 * w + h + extent.maxy - v.y),
+    ];
+    // Interpolated vertices are not exactly on the boundary, take the nearest edge
+    edges
+        .iter()
+        .fold(
+            edges[0],
+            |nearest, edge| {
+                if edge.0 < nearest.0 {
+                    *edge
+                } else {
+                    nearest
+                }
+            },
+        )
+        .1
+}
+
+fn push_distinct(ring: &mut Vec<Vertex>, vertex: Vertex) {
+    if !matches!(ring.last(), Some(last) if same_position(last, &vertex)) {
+        ring.push(vertex);
+    }
+}
+
+/// Clip polygon `rings` to `extent` (Weiler-Atherton)
+///
+/// Rings are cut into parts within the extent, which are joined along the extent
+/// border. Concave polygons can fall apart into several polygons.
+fn clip_polygon(rings: &[Vec<Vertex>], extent: &Extent) -> Vec<Vec<Vec<Vertex>>> {
+    let exterior_ccw = match rings.first() {
+        Some(exterior) if exterior.len() > 2 => signed_area(exterior) > 0.0,
+        _ => return Vec::new(),
+    };
+    let perimeter = 2.0 * (extent.maxx - extent.minx + extent.maxy - extent.miny);
+    let mut parts: Vec<Vec<Vertex>> = Vec::new();
+    let mut exteriors: Vec<Vec<Vertex>> = Vec::new();
+    let mut holes: Vec<Vec<Vertex>> = Vec::new();
+    for (i, ring) in rings.iter().enumerate() {
+        let mut ring = ring.clone();
+        if ring.len() > 1 && same_position(&ring[0], &ring[ring.len() - 1]) {
+            ring.pop();
+        }
+        if ring.len() < 3 {
+            continue;
+        }
+        // Counterclockwise exterior and clockwise holes have the interior on the left
+        if (signed_area(&ring) > 0.0) != (i == 0) {
+            ring.reverse();
+        }
+        match ring.iter().position(|v| !extent.contains(v.x, v.y)) {
+            Some(outside) => {
+                // Starting outside, every part begins and ends on the border
+                ring.rotate_left(outside);
+                ring.push(ring[0]);
+                parts.extend(clip_line(&ring, extent));
+            }
+            None => {
+                ring.push(ring[0]);
+                if i == 0 {
+                    exteriors.push(ring);
+                } else {
+                    holes.push(ring);
+                }
+            }
+        }
+    }
+    if parts.is_empty() {
+        // No ring crosses the border, the extent is either covered completely or not at all
+        let (x, y) = ((extent.minx + extent.maxx) / 2.0, extent.miny);
+        if rings
+            .iter()
+            .filter(|ring| ring_contains(ring, x, y))
+            .count()
+            % 2
+            == 1
+        {
+            let template = rings[0][0];
+            let mut rect: Vec<Vertex> = corners(extent)
+                .iter()
+                .map(|&(_, x, y)| Vertex { x, y, ..template })
+                .collect();
+            rect.push(rect[0]);
+            exteriors.push(rect);
+        }
+    }
+    // Join parts counterclockwise along the border with the nearest following part
+    let mut used = vec![false; parts.len()];
+    for first in 0..parts.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring: Vec<Vertex> = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            for vertex in &parts[current] {
+                push_distinct(&mut ring, *vertex);
+            }
+            let end = ring[ring.len() - 1];
+            let from = boundary_position(&end, extent);
+            let distance = |part: &[Vertex]| {
+                (boundary_position(&part[0], extent) - from).rem_euclid(perimeter)
+            };
+            let (next, gap) = (0..parts.len())
+                .filter(|&i| !used[i] || i == first)
+                .map(|i| (i, distance(&parts[i])))
+                .fold((first, distance(&parts[first])), |nearest, candidate| {
+                    if candidate.1 < nearest.1 {
+                        candidate
+                    } else {
+                        nearest
+                    }
+                });
+            let mut passed: Vec<(f64, Vertex)> = corners(extent)
+                .iter()
+                .map(|&(pos, x, y)| ((pos - from).rem_euclid(perimeter), Vertex { x, y, ..end }))
+                .filter(|(d, _)| *d > 0.0 && *d < gap)
+                .collect();
+            passed.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for (_, corner) in passed {
+                push_distinct(&mut ring, corner);
+            }
+            if next == first {
+                break;
+            }
+            current = next;
+        }
+        ring.push(ring[0]);
+        // Parts running along the border result in degenerated rings
+        if ring.len() > 3 && signed_area(&ring) > 0.0 {
+            exteriors.push(ring);
+        }
+    }
+    let mut polygons: Vec<Vec<Vec<Vertex>>> = exteriors
+        .into_iter()
+        .map(|exterior| vec![exterior])
+        .collect();
+    for hole in holes {
+        let inner = hole
+            .iter()
+            .find(|v| {
+                v.x > extent.minx && v.x < extent.maxx && v.y > extent.miny && v.y < extent.maxy
+            })
+            .unwrap_or(&hole[0]);
+        let (x, y) = (inner.x, inner.y);
+        if let Some(polygon) = polygons
+            .iter_mut()
+            .find(|polygon| ring_contains(&polygon[0], x, y))
+        {
+            polygon.push(hole);
+        }
+    }
+    if !exterior_ccw {
+        // Keep orientation of input rings
+        for ring in polygons.iter_mut().flatten() {
+            ring.reverse();
+        }
+    }
+    polygons
+}
+
+impl<P: FeatureProcessor> ClipToExtent<P> {
+    fn emit_line(&mut self, line: &[Vertex], tagged: bool, idx: usize) -> Result<()> {
+        self.has_vertices |= !line.is_empty();
+        self.buffer.linestring_begin(tagged, line.len(), idx)?;
+        for (idx, vertex) in line.iter().enumerate() {
+            vertex.emit(&mut self.buffer, idx)?;
+        }
+        self.buffer.linestring_end(tagged, idx)
+    }
+    fn emit_polygon(&mut self, rings: &[Vec<Vertex>], tagged: bool, idx: usize) -> Result<()> {
+        self.buffer.polygon_begin(tagged, rings.len(), idx)?;
+        for (i, ring) in rings.iter().enumerate() {
+            self.emit_line(ring, false, i)?;
+        }
+        self.buffer.polygon_end(tagged, idx)
+    }
+    /// Clip buffered geometry and pass it on
+    fn flush(&mut self) -> Result<()> {
+        let extent = self.extent;
+        let buffered = self.buffered.take();
+        match buffered {
+            Some(Buffered::Point(idx)) => {
+                let points = std::mem::take(&mut self.points);
+                match points.first() {
+                    Some(point) if extent.contains(point.x, point.y) => {
+                        self.has_vertices = true;
+                        self.buffer.point_begin(idx)?;
+                        point.emit(&mut self.buffer, 0)?;
+                        self.buffer.point_end(idx)
+                    }
+                    _ => self.buffer.empty_point(idx),
+                }
+            }
+            Some(Buffered::MultiPoint(idx)) => {
+                let mut points = std::mem::take(&mut self.points);
+                points.retain(|point| extent.contains(point.x, point.y));
+                self.has_vertices |= !points.is_empty();
+                self.buffer.multipoint_begin(points.len(), idx)?;
+                for (i, point) in points.iter().enumerate() {
+                    point.emit(&mut self.buffer, i)?;
+                }
+                self.buffer.multipoint_end(idx)
+            }
+            Some(Buffered::LineString(idx)) => {
+                let mut lines = clip_line(&std::mem::take(&mut self.lines)[0], &extent);
+                match lines.len() {
+                    0 => self.emit_line(&[], true, idx),
+                    1 => self.emit_line(&lines.remove(0), true, idx),
+                    _ => {
+                        self.buffer.multilinestring_begin(lines.len(), idx)?;
+                        for (i, line) in lines.iter().enumerate() {
+                            self.emit_line(line, false, i)?;
+                        }
+                        self.buffer.multilinestring_end(idx)
+                    }
+                }
+            }
+            Some(Buffered::MultiLineString(idx)) => {
+                let lines: Vec<Vec<Vertex>> = std::mem::take(&mut self.lines)
+                    .iter()
+                    .flat_map(|line| clip_line(line, &extent))
+                    .collect();
+                self.buffer.multilinestring_begin(lines.len(), idx)?;
+                for (i, line) in lines.iter().enumerate() {
+                    self.emit_line(line, false, i)?;
+                }
+                self.buffer.multilinestring_end(idx)
+            }
+            Some(Buffered::Polygon(idx)) | Some(Buffered::MultiPolygon(idx)) => {
+                let polygons: Vec<Vec<Vec<Vertex>>> = std::mem::take(&mut self.polygons)
+                    .iter()
+                    .flat_map(|rings| clip_polygon(rings, &extent))
+                    .collect();
+                match (buffered, polygons.len()) {
+                    (Some(Buffered::Polygon(_)), 0) => self.emit_polygon(&[], true, idx),
+                    (Some(Buffered::Polygon(_)), 1) => self.emit_polygon(&polygons[0], true, idx),
+                    _ => {
+                        // A concave polygon can fall apart into several parts
+                        self.buffer.multipolygon_begin(polygons.len(), idx)?;
+                        for (i, rings) in polygons.iter().enumerate() {
+                            self.emit_polygon(rings, false, i)?;
+                        }
+                        self.buffer.multipolygon_end(idx)
+                    }
+                }
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for ClipToExtent<P> {
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometrycollection,
+        curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::xy(x, y));
+            Ok(())
+        } else {
+            self.buffer.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::coordinate(x, y, z, m, t, tm));
+            Ok(())
+        } else {
+            self.buffer.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn point_begin(&mut self, idx: usize) -> Result<()> {
+        if self.buffered.is_none() {
+            self.buffered = Some(Buffered::Point(idx));
+        }
+        Ok(())
+    }
+    fn point_end(&mut self, _idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::Point(_)) => self.flush(),
+            _ => Ok(()),
+        }
+    }
+    fn multipoint_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiPoint(idx));
+        Ok(())
+    }
+    fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.push(Vec::with_capacity(size));
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                if let Some(rings) = self.polygons.last_mut() {
+                    rings.push(Vec::with_capacity(size));
+                }
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::LineString(idx));
+                self.lines.push(Vec::with_capacity(size));
+            }
+            _ => return self.buffer.linestring_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) => self.flush(),
+            Some(_) => Ok(()),
+            None => self.buffer.linestring_end(tagged, idx),
+        }
+    }
+    fn multilinestring_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiLineString(idx));
+        Ok(())
+    }
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::Polygon(idx));
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            _ => return self.buffer.polygon_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::Polygon(_)) => self.flush(),
+            Some(Buffered::MultiPolygon(_)) => Ok(()),
+            _ => self.buffer.polygon_end(tagged, idx),
+        }
+    }
+    fn multipolygon_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiPolygon(idx));
+        Ok(())
+    }
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for ClipToExtent<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ClipToExtent<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.has_vertices = false;
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        if self.has_vertices {
+            self.count += 1;
+            self.buffer.replay(self.count - 1)
+        } else {
+            self.buffer.discard();
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnValue;
+
+    fn vertices(xy: &[(f64, f64)]) -> Vec<Vertex> {
+        xy.iter().map(|(x, y)| Vertex::xy(*x, *y)).collect()
+    }
+
+    fn xy(vertices: &[Vertex]) -> Vec<(f64, f64)> {
+        vertices.iter().map(|v| (v.x, v.y)).collect()
+    }
+
+    #[test]
+    fn clip_lines() {
+        let extent = Extent::new(0.0, 0.0, 10.0, 10.0);
+        let parts = clip_line(
+            &vertices(&[
+                (-5.0, 5.0),
+                (5.0, 5.0),
+                (5.0, 15.0),
+                (8.0, 15.0),
+                (8.0, 5.0),
+            ]),
+            &extent,
+        );
+        assert_eq!(parts.len(), 2);
+        assert_eq!(xy(&parts[0]), vec![(0.0, 5.0), (5.0, 5.0), (5.0, 10.0)]);
+        assert_eq!(xy(&parts[1]), vec![(8.0, 10.0), (8.0, 5.0)]);
+
+        let parts = clip_line(&vertices(&[(-5.0, -5.0), (-1.0, 20.0)]), &extent);
+        assert!(parts.is_empty());
+    }
+
+    #[test]
+    fn clip_polygons() {
+        let extent = Extent::new(0.0, 0.0, 10.0, 10.0);
+        let ring = vertices(&[
+            (-5.0, 5.0),
+            (5.0, -5.0),
+            (15.0, 5.0),
+            (5.0, 15.0),
+            (-5.0, 5.0),
+        ]);
+        let polygons = clip_polygon(&[ring], &extent);
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(
+            xy(&polygons[0][0]),
+            vec![
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 10.0),
+                (0.0, 10.0),
+                (0.0, 0.0)
+            ]
+        );
+        let outside = vertices(&[(20.0, 20.0), (30.0, 20.0), (30.0, 30.0), (20.0, 20.0)]);
+        assert!(clip_polygon(&[outside], &extent).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "with-wkt")]
+    fn clip_concave_polygon() -> Result<()> {
+        use crate::wkt::{WktStr, WktWriter};
+        use crate::GeozeroGeometry;
+
+        let clip = |wkt: &str, extent: Extent| -> Result<String> {
+            let mut out: Vec<u8> = Vec::new();
+            WktStr(wkt).process_geom(&mut ClipToExtent::new(WktWriter::new(&mut out), extent))?;
+            Ok(String::from_utf8(out).unwrap())
+        };
+        let u_shape = "POLYGON((0 0,3 0,3 3,2 3,2 1,1 1,1 3,0 3,0 0))";
+        assert_eq!(
+            clip(u_shape, Extent::new(-1.0, 2.0, 4.0, 4.0))?,
+            "MULTIPOLYGON(((3 2,3 3,2 3,2 2,3 2)),((1 2,1 3,0 3,0 2,1 2)))"
+        );
+        // Clockwise rings keep their orientation
+        assert_eq!(
+            clip(
+                "POLYGON((0 0,0 3,1 3,1 1,2 1,2 3,3 3,3 0,0 0))",
+                Extent::new(-1.0, 2.0, 4.0, 4.0)
+            )?,
+            "MULTIPOLYGON(((3 2,2 2,2 3,3 3,3 2)),((1 2,0 2,0 3,1 3,1 2)))"
+        );
+        assert_eq!(
+            clip(u_shape, Extent::new(-1.0, 0.5, 4.0, 4.0))?,
+            "POLYGON((3 0.5,3 3,2 3,2 1,1 1,1 3,0 3,0 0.5,3 0.5))"
+        );
+        // Hole within extent and extent within polygon
+        assert_eq!(
+            clip(
+                "POLYGON((0 0,10 0,10 10,0 10,0 0),(4 4,4 6,6 6,6 4,4 4))",
+                Extent::new(2.0, 2.0, 8.0, 8.0)
+            )?,
+            "POLYGON((2 2,8 2,8 8,2 8,2 2),(4 4,4 6,6 6,6 4,4 4))"
+        );
+        Ok(())
+    }
+
+    /// Collects the `name` property of processed features
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl GeomProcessor for Names {}
+
+    impl PropertyProcessor for Names {
+        fn property(&mut self, _idx: usize, _name: &str, value: &ColumnValue) -> Result<bool> {
+            self.0.push(value.to_string());
+            Ok(false)
+        }
+    }
+
+    impl FeatureProcessor for Names {
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push(idx.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn drop_features() -> Result<()> {
+        let mut clip = ClipToExtent::new(Names::default(), Extent::new(0.0, 0.0, 10.0, 10.0));
+        for (idx, (name, x)) in [("outside", 20.0), ("inside", 5.0)].iter().enumerate() {
+            clip.feature_begin(idx as u64)?;
+            clip.properties_begin()?;
+            clip.property(0, "name", &ColumnValue::String(name))?;
+            clip.properties_end()?;
+            clip.geometry_begin()?;
+            clip.linestring_begin(true, 2, 0)?;
+            clip.xy(*x, 1.0, 0)?;
+            clip.xy(*x, 2.0, 1)?;
+            clip.linestring_end(true, 0)?;
+            clip.geometry_end()?;
+            clip.feature_end(idx as u64)?;
+        }
+        assert_eq!(clip.into_inner().0, vec!["0", "inside"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,292 @@
+use crate::error::{GeozeroError, Result};
+use crate::{
+    ColumnDef, ColumnValue, Crs, DatasetMetadata, FeatureProcessor, GeomProcessor,
+    PropertyProcessor, Warning,
+};
+use std::fmt;
+
+/// Error reported by [ContinueOnError]
+#[derive(Debug)]
+pub struct FeatureError {
+    /// Index of failed feature, `None` for errors outside of features
+    pub feature_idx: Option<u64>,
+    pub error: GeozeroError,
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(idx) = self.feature_idx {
+            write!(f, "feature {}: ", idx)?;
+        }
+        write!(f, "{}", self.error)
+    }
+}
+
+/// Reports errors of the wrapped processor and continues processing.
+///
+/// The first error of a feature is reported, further calls of the failed feature
+/// are still passed on, but their errors are ignored. Errors outside of features
+/// are all reported. [Stopped](GeozeroError::Stopped) is passed on unchanged.
+///
+/// Combined with a writer in dry run mode, all errors of a load are reported
+/// without writing any output. Output of failed features is incomplete otherwise.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::processor::ContinueOnError;
+/// use geozero::{FeatureProcessor, GeomProcessor, WriterOptions};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let writer = GeoJsonWriter::builder()
+///     .options(WriterOptions::default().with_dry_run(true))
+///     .build(&mut out);
+/// let mut errors = Vec::new();
+/// let mut processor = ContinueOnError::new(writer, |e| errors.push(e.to_string()));
+/// for (idx, x) in [1.0, f64::NAN, 3.0].iter().enumerate() {
+///     processor.feature_begin(idx as u64).unwrap();
+///     processor.geometry_begin().unwrap();
+///     processor.point_begin(0).unwrap();
+///     processor.xy(*x, 0.0, 0).unwrap();
+///     processor.point_end(0).unwrap();
+///     processor.geometry_end().unwrap();
+///     processor.feature_end(idx as u64).unwrap();
+/// }
+/// assert_eq!(processor.error_count(), 1);
+/// drop(processor);
+/// assert!(out.is_empty());
+/// assert_eq!(
+///     errors,
+///     vec!["feature 1: processing geometry `non-finite coordinate value `NaN``"]
+/// );
+/// ```
+pub struct ContinueOnError<P, F: FnMut(&FeatureError)> {
+    inner: P,
+    report: F,
+    /// Index of current feature
+    feature: Option<u64>,
+    /// Current feature has failed
+    failed: bool,
+    errors: u64,
+}
+
+impl<P, F: FnMut(&FeatureError)> ContinueOnError<P, F> {
+    pub fn new(inner: P, report: F) -> Self {
+        ContinueOnError {
+            inner,
+            report,
+            feature: None,
+            failed: false,
+            errors: 0,
+        }
+    }
+    /// Number of reported errors
+    pub fn error_count(&self) -> u64 {
+        self.errors
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn handle<T: Default>(&mut self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(GeozeroError::Stopped) => Err(GeozeroError::Stopped),
+            Err(error) => {
+                if !self.failed {
+                    self.failed = self.feature.is_some();
+                    self.errors += 1;
+                    (self.report)(&FeatureError {
+                        feature_idx: self.feature,
+                        error,
+                    });
+                }
+                Ok(T::default())
+            }
+            ok => ok,
+        }
+    }
+}
+
+/// Forward call to `self.inner`, reporting errors with `self.handle`
+macro_rules! forward_and_handle {
+    ($($method:ident($($arg:ident: $t:ty),*)),* $(,)?) => {
+        $(
+            fn $method(&mut self, $($arg: $t),*) -> Result<()> {
+                let result = self.inner.$method($($arg),*);
+                self.handle(result)
+            }
+        )*
+    };
+}
+
+impl<P: GeomProcessor, F: FnMut(&FeatureError)> GeomProcessor for ContinueOnError<P, F> {
+    forward_processor_methods!(inner; dimensions);
+
+    forward_and_handle!(
+        srid(srid: Option<i32>),
+        xy(x: f64, y: f64, idx: usize),
+        empty_point(idx: usize),
+        point_begin(idx: usize),
+        point_end(idx: usize),
+        multipoint_begin(size: usize, idx: usize),
+        multipoint_end(idx: usize),
+        linestring_begin(tagged: bool, size: usize, idx: usize),
+        linestring_end(tagged: bool, idx: usize),
+        multilinestring_begin(size: usize, idx: usize),
+        multilinestring_end(idx: usize),
+        polygon_begin(tagged: bool, size: usize, idx: usize),
+        polygon_end(tagged: bool, idx: usize),
+        multipolygon_begin(size: usize, idx: usize),
+        multipolygon_end(idx: usize),
+        geometrycollection_begin(size: usize, idx: usize),
+        geometrycollection_end(idx: usize),
+        circularstring_begin(size: usize, idx: usize),
+        circularstring_end(idx: usize),
+        compoundcurve_begin(size: usize, idx: usize),
+        compoundcurve_end(idx: usize),
+        curvepolygon_begin(size: usize, idx: usize),
+        curvepolygon_end(idx: usize),
+        multicurve_begin(size: usize, idx: usize),
+        multicurve_end(idx: usize),
+        multisurface_begin(size: usize, idx: usize),
+        multisurface_end(idx: usize),
+        triangle_begin(tagged: bool, size: usize, idx: usize),
+        triangle_end(tagged: bool, idx: usize),
+        polyhedralsurface_begin(size: usize, idx: usize),
+        polyhedralsurface_end(idx: usize),
+        tin_begin(size: usize, idx: usize),
+        tin_end(idx: usize),
+    );
+
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let result = self.inner.coordinate(x, y, z, m, t, tm, idx);
+        self.handle(result)
+    }
+}
+
+impl<P: PropertyProcessor, F: FnMut(&FeatureError)> PropertyProcessor for ContinueOnError<P, F> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        let result = self.inner.property(idx, name, value);
+        self.handle(result)
+    }
+}
+
+impl<P: FeatureProcessor, F: FnMut(&FeatureError)> FeatureProcessor for ContinueOnError<P, F> {
+    forward_and_handle!(
+        dataset_begin(name: Option<&str>),
+        dataset_end(),
+        schema(columns: &[ColumnDef]),
+        crs(crs: &Crs),
+        metadata(metadata: &DatasetMetadata),
+        warning(warning: &Warning),
+        fid(fid: &ColumnValue),
+        properties_begin(),
+        properties_end(),
+        geometry_begin(),
+        geometry_end(),
+    );
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature = Some(idx);
+        self.failed = false;
+        let result = self.inner.feature_begin(idx);
+        self.handle(result)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let result = self.inner.feature_end(idx);
+        let result = self.handle(result);
+        self.feature = None;
+        self.failed = false;
+        result
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::{GeoJson, GeoJsonWriter};
+    use crate::{GeozeroDatasource, WriterOptions};
+
+    const GEOJSON: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+        {"type": "Feature", "properties": {"name": "b"}, "geometry": {"type": "Point", "coordinates": [1, 1]}},
+        {"type": "Feature", "properties": {"name": "c"}, "geometry": {"type": "Point", "coordinates": [1, 1]}}]}"#;
+
+    /// Fails on coordinates of feature 1 and properties of feature 2
+    struct Failing<P> {
+        inner: P,
+        feature: Option<u64>,
+    }
+
+    impl<P: GeomProcessor> GeomProcessor for Failing<P> {
+        forward_processor_methods!(inner; geometries);
+
+        fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+            if self.feature == Some(1) {
+                return Err(GeozeroError::Geometry("invalid".to_string()));
+            }
+            self.inner.xy(x, y, idx)
+        }
+    }
+
+    impl<P: PropertyProcessor> PropertyProcessor for Failing<P> {
+        fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+            if self.feature == Some(2) {
+                return Err(GeozeroError::Property("invalid".to_string()));
+            }
+            self.inner.property(idx, name, value)
+        }
+    }
+
+    impl<P: FeatureProcessor> FeatureProcessor for Failing<P> {
+        forward_processor_methods!(inner; dataset, warning, fid, properties, geometry);
+
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.feature = Some(idx);
+            self.inner.feature_begin(idx)
+        }
+        fn feature_end(&mut self, idx: u64) -> Result<()> {
+            self.inner.feature_end(idx)
+        }
+    }
+
+    #[test]
+    fn report_errors() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let writer = GeoJsonWriter::builder()
+            .options(WriterOptions::default().with_dry_run(true))
+            .build(&mut out);
+        let mut errors = Vec::new();
+        let failing = Failing {
+            inner: writer,
+            feature: None,
+        };
+        let mut processor = ContinueOnError::new(failing, |e| errors.push(e.to_string()));
+        GeoJson(GEOJSON).process(&mut processor)?;
+        assert_eq!(processor.error_count(), 2);
+        assert_eq!(
+            errors,
+            vec![
+                "feature 1: processing geometry `invalid`",
+                "feature 2: processing feature property: `invalid`"
+            ]
+        );
+        assert!(out.is_empty());
+        Ok(())
+    }
+}
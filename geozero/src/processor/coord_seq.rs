@@ -0,0 +1,256 @@
+use crate::error::Result;
+use crate::GeomProcessor;
+
+/// Coordinate values besides XY
+#[derive(Clone, Copy)]
+struct CoordAttrs {
+    z: Option<f64>,
+    m: Option<f64>,
+    t: Option<f64>,
+    tm: Option<u64>,
+    idx: usize,
+    /// Passed with `coordinate` instead of `xy`
+    multi_dim: bool,
+}
+
+/// Buffered vertex
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Vertex {
+    pub x: f64,
+    pub y: f64,
+    pub z: Option<f64>,
+    pub m: Option<f64>,
+    pub t: Option<f64>,
+    pub tm: Option<u64>,
+    /// Passed with `coordinate` instead of `xy`
+    pub multi_dim: bool,
+}
+
+impl Vertex {
+    pub fn xy(x: f64, y: f64) -> Self {
+        Vertex {
+            x,
+            y,
+            z: None,
+            m: None,
+            t: None,
+            tm: None,
+            multi_dim: false,
+        }
+    }
+    pub fn coordinate(
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+    ) -> Self {
+        Vertex {
+            x,
+            y,
+            z,
+            m,
+            t,
+            tm,
+            multi_dim: true,
+        }
+    }
+    /// Vertex at `fraction` of the segment to `other` with X value `x`
+    pub fn interpolate(&self, other: &Vertex, x: f64, fraction: f64) -> Vertex {
+        let lerp = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a + (b - a) * fraction),
+            _ => None,
+        };
+        Vertex {
+            x,
+            y: self.y + (other.y - self.y) * fraction,
+            z: lerp(self.z, other.z),
+            m: lerp(self.m, other.m),
+            t: lerp(self.t, other.t),
+            tm: lerp(self.tm.map(|v| v as f64), other.tm.map(|v| v as f64)).map(|v| v as u64),
+            multi_dim: self.multi_dim,
+        }
+    }
+    /// Vertex at `fraction` of the segment to `other`
+    pub fn at(&self, other: &Vertex, fraction: f64) -> Vertex {
+        self.interpolate(other, self.x + (other.x - self.x) * fraction, fraction)
+    }
+    pub fn shifted(&self, dx: f64) -> Vertex {
+        Vertex {
+            x: self.x + dx,
+            ..*self
+        }
+    }
+    /// Pass vertex to `processor`
+    pub fn emit<P: GeomProcessor>(&self, processor: &mut P, idx: usize) -> Result<()> {
+        if self.multi_dim {
+            processor.coordinate(self.x, self.y, self.z, self.m, self.t, self.tm, idx)
+        } else {
+            processor.xy(self.x, self.y, idx)
+        }
+    }
+}
+
+/// Sequence of buffered coordinates for batch processing.
+#[derive(Default, Clone)]
+pub(crate) struct CoordSeq {
+    /// XY values, which can be modified before emitting
+    pub xy: Vec<(f64, f64)>,
+    attrs: Vec<CoordAttrs>,
+}
+
+impl CoordSeq {
+    pub fn len(&self) -> usize {
+        self.xy.len()
+    }
+    /// Allocated buffer size in bytes
+    pub fn heap_size(&self) -> usize {
+        self.xy.capacity() * std::mem::size_of::<(f64, f64)>()
+            + self.attrs.capacity() * std::mem::size_of::<CoordAttrs>()
+    }
+    pub fn push_xy(&mut self, x: f64, y: f64, idx: usize) {
+        self.xy.push((x, y));
+        self.attrs.push(CoordAttrs {
+            z: None,
+            m: None,
+            t: None,
+            tm: None,
+            idx,
+            multi_dim: false,
+        });
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn push_coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) {
+        self.xy.push((x, y));
+        self.attrs.push(CoordAttrs {
+            z,
+            m,
+            t,
+            tm,
+            idx,
+            multi_dim: true,
+        });
+    }
+    /// Keep coordinates marked in `keep` and renumber their indices
+    pub fn retain(&mut self, keep: &[bool]) {
+        let mut keep_iter = keep.iter();
+        self.xy.retain(|_| *keep_iter.next().unwrap_or(&true));
+        let mut keep_iter = keep.iter();
+        self.attrs.retain(|_| *keep_iter.next().unwrap_or(&true));
+        for (idx, c) in self.attrs.iter_mut().enumerate() {
+            c.idx = idx;
+        }
+    }
+    /// Remove consecutive duplicate vertices, keeping at least two vertices
+    pub fn dedup(&mut self) {
+        self.dedup_within(0.0);
+    }
+    /// Remove vertices within `epsilon` of the previous kept vertex.
+    ///
+    /// The first and last vertex are always kept, so closed rings stay closed.
+    pub fn dedup_within(&mut self, epsilon: f64) {
+        let n = self.xy.len();
+        let mut last_kept = 0;
+        let mut keep: Vec<bool> = (0..n)
+            .map(|i| {
+                let (x, y) = self.xy[i];
+                let (kx, ky) = self.xy[last_kept];
+                if i > 0 && (x - kx).hypot(y - ky) <= epsilon {
+                    false
+                } else {
+                    last_kept = i;
+                    true
+                }
+            })
+            .collect();
+        if n > 1 && last_kept != n - 1 {
+            // Replace last kept vertex with the end vertex
+            keep[n - 1] = true;
+            if last_kept > 0 {
+                keep[last_kept] = false;
+            }
+        }
+        self.retain(&keep);
+    }
+    /// Insert `segments(a, b) - 1` vertices between consecutive vertices `a` and `b`.
+    ///
+    /// Inserted XY values are computed with `point(a, b, fraction)`, other
+    /// values are interpolated linearly. Indices are renumbered.
+    pub fn densify(
+        &mut self,
+        segments: impl Fn((f64, f64), (f64, f64)) -> usize,
+        point: impl Fn((f64, f64), (f64, f64), f64) -> (f64, f64),
+    ) {
+        if self.xy.len() < 2 {
+            return;
+        }
+        let lerp = |a: Option<f64>, b: Option<f64>, f: f64| match (a, b) {
+            (Some(a), Some(b)) => Some(a + (b - a) * f),
+            _ => None,
+        };
+        let mut xy = Vec::with_capacity(self.xy.len());
+        let mut attrs = Vec::with_capacity(self.attrs.len());
+        for i in 0..self.xy.len() - 1 {
+            let (a, b) = (self.xy[i], self.xy[i + 1]);
+            let (ca, cb) = (&self.attrs[i], &self.attrs[i + 1]);
+            let n = segments(a, b).max(1);
+            xy.push(a);
+            attrs.push(*ca);
+            for j in 1..n {
+                let f = j as f64 / n as f64;
+                xy.push(point(a, b, f));
+                attrs.push(CoordAttrs {
+                    z: lerp(ca.z, cb.z, f),
+                    m: lerp(ca.m, cb.m, f),
+                    t: lerp(ca.t, cb.t, f),
+                    tm: match (ca.tm, cb.tm) {
+                        (Some(a), Some(b)) => {
+                            lerp(Some(a as f64), Some(b as f64), f).map(|v| v as u64)
+                        }
+                        _ => None,
+                    },
+                    idx: 0,
+                    multi_dim: ca.multi_dim,
+                });
+            }
+        }
+        xy.push(self.xy[self.xy.len() - 1]);
+        attrs.push(self.attrs[self.attrs.len() - 1]);
+        for (idx, c) in attrs.iter_mut().enumerate() {
+            c.idx = idx;
+        }
+        self.xy = xy;
+        self.attrs = attrs;
+    }
+    /// Reverse vertex order and renumber indices
+    pub fn reverse(&mut self) {
+        self.xy.reverse();
+        self.attrs.reverse();
+        for (idx, c) in self.attrs.iter_mut().enumerate() {
+            c.idx = idx;
+        }
+    }
+    /// Pass buffered coordinates to `processor` and clear buffer
+    pub fn emit<P: GeomProcessor>(&mut self, processor: &mut P) -> Result<()> {
+        for (c, (x, y)) in self.attrs.iter().zip(&self.xy) {
+            if c.multi_dim {
+                processor.coordinate(*x, *y, c.z, c.m, c.t, c.tm, c.idx)?;
+            } else {
+                processor.xy(*x, *y, c.idx)?;
+            }
+        }
+        self.xy.clear();
+        self.attrs.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::Result;
+use crate::processor::{Encode, Event, FeatureBuffer};
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::collections::HashSet;
+
+/// Drops duplicate features.
+///
+/// Features are compared by the encoded values of their geometry and properties. By default
+/// all properties are compared, `properties` restricts the comparison to the given
+/// columns. With `by_id` only the value of an id column is compared, ignoring the geometry.
+/// Features without the id column are passed without comparison.
+/// Passed features are renumbered starting at 0. The encoded values of all passed features
+/// are kept in memory.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::Deduplicate;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"fid": 1, "name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"fid": 1, "name": "Berne"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}
+/// ]}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = Deduplicate::new(GeoJsonWriter::new(&mut out)).by_id("fid");
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains("Bern") && !json.contains("Berne"));
+/// ```
+pub struct Deduplicate<P> {
+    buffer: FeatureBuffer<P>,
+    /// Compared properties (`None` for all)
+    properties: Option<Vec<String>>,
+    /// Compare geometries
+    geometry: bool,
+    /// Encoded compared values of passed features
+    seen: HashSet<Vec<u8>>,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> Deduplicate<P> {
+    pub fn new(inner: P) -> Self {
+        Deduplicate {
+            buffer: FeatureBuffer::new(inner),
+            properties: None,
+            geometry: true,
+            seen: HashSet::new(),
+            count: 0,
+        }
+    }
+    /// Compare geometry and the given properties only
+    pub fn properties(mut self, names: &[&str]) -> Self {
+        self.properties = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+    /// Compare value of id column `name` only
+    pub fn by_id(mut self, name: &str) -> Self {
+        self.properties = Some(vec![name.to_string()]);
+        self.geometry = false;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    fn compared(&self, name: &str) -> bool {
+        match &self.properties {
+            Some(names) => names.iter().any(|n| n == name),
+            None => true,
+        }
+    }
+    /// Encoded compared geometry and property values, `None` if nothing is compared
+    fn key(&self, events: &[Event]) -> Result<Option<Vec<u8>>> {
+        let mut key = Vec::new();
+        let mut compared = self.geometry;
+        for event in events {
+            match event {
+                Event::FeatureBegin(_)
+                | Event::Fid(_)
+                | Event::FeatureEnd(_)
+                | Event::PropertiesBegin
+                | Event::PropertiesEnd => {}
+                Event::Property(_, name, value) if self.compared(name) => {
+                    name.encode(&mut key)?;
+                    value.encode(&mut key)?;
+                    compared = true;
+                }
+                Event::Property(..) => {}
+                _ if self.geometry => event.encode(&mut key)?,
+                _ => {}
+            }
+        }
+        Ok(compared.then_some(key))
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for Deduplicate<P> {
+    forward_processor_methods!(buffer; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for Deduplicate<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Deduplicate<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let unique = match self.key(self.buffer.events())? {
+            Some(key) => self.seen.insert(key),
+            None => true,
+        };
+        if unique {
+            self.buffer.replay(self.count)?;
+            self.count += 1;
+        } else {
+            self.buffer.discard();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnValue;
+
+    /// Records passed feature indices
+    #[derive(Default)]
+    struct Features(Vec<u64>);
+
+    impl GeomProcessor for Features {}
+
+    impl PropertyProcessor for Features {}
+
+    impl FeatureProcessor for Features {
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push(idx);
+            Ok(())
+        }
+    }
+
+    fn process<P: FeatureProcessor>(
+        processor: &mut P,
+        features: &[(f64, &str, i32)],
+    ) -> Result<()> {
+        for (idx, (x, name, fid)) in features.iter().enumerate() {
+            processor.feature_begin(idx as u64)?;
+            processor.properties_begin()?;
+            processor.property(0, "fid", &ColumnValue::Int(*fid))?;
+            processor.property(1, "name", &ColumnValue::String(name))?;
+            processor.properties_end()?;
+            processor.geometry_begin()?;
+            processor.point_begin(0)?;
+            processor.xy(*x, 0.0, 0)?;
+            processor.point_end(0)?;
+            processor.geometry_end()?;
+            processor.feature_end(idx as u64)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn deduplicate() -> Result<()> {
+        let features = [(1.0, "a", 1), (1.0, "a", 1), (1.0, "b", 1), (2.0, "a", 2)];
+        let mut processor = Deduplicate::new(Features::default());
+        process(&mut processor, &features)?;
+        assert_eq!(processor.into_inner().0, vec![0, 1, 2]);
+
+        let mut processor = Deduplicate::new(Features::default()).properties(&["fid"]);
+        process(&mut processor, &features)?;
+        assert_eq!(processor.into_inner().0, vec![0, 1]);
+
+        let mut processor = Deduplicate::new(Features::default()).by_id("name");
+        process(&mut processor, &features)?;
+        assert_eq!(processor.into_inner().0, vec![0, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_id() -> Result<()> {
+        let mut processor = Deduplicate::new(Features::default()).by_id("fid");
+        for idx in 0..3 {
+            processor.feature_begin(idx)?;
+            processor.properties_begin()?;
+            processor.property(0, "name", &ColumnValue::String("a"))?;
+            processor.properties_end()?;
+            processor.feature_end(idx)?;
+        }
+        assert_eq!(processor.into_inner().0, vec![0, 1, 2]);
+        Ok(())
+    }
+}
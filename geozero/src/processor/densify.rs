@@ -0,0 +1,276 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::CoordSeq;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Mean earth radius in meters
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Maximal number of parts a single segment is split into
+const MAX_SEGMENT_PARTS: usize = 1 << 16;
+
+/// Segment length measure
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DensifyMode {
+    /// Euclidean distance in source units, vertices are inserted on the straight segment
+    Planar,
+    /// Great circle distance in meters on a spherical earth for lon/lat coordinates in degrees,
+    /// vertices are inserted on the great circle
+    Geodesic,
+}
+
+/// Inserts vertices so that no segment exceeds a maximal length.
+///
+/// Applies to linestrings and polygon rings. Z, M and time values of inserted
+/// vertices are interpolated linearly. Densifying long segments before reprojection
+/// makes them follow the curved path in the target projection.
+///
+/// Segments with non-finite coordinates are passed unchanged. A single segment is split
+/// into at most 65536 parts, so extremely long segments may exceed the maximal length.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Densify, DensifyMode};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = Densify::new(WktWriter::new(&mut out), DensifyMode::Planar, 1.0).unwrap();
+/// WktStr("LINESTRING(0 0,3 0)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(0 0,1 0,2 0,3 0)");
+/// ```
+pub struct Densify<P> {
+    inner: P,
+    mode: DensifyMode,
+    /// Maximal segment length
+    max_length: f64,
+    /// Open linestring
+    in_linestring: bool,
+    coords: CoordSeq,
+}
+
+impl<P> Densify<P> {
+    /// Densify segments longer than `max_length`, which has to be positive
+    pub fn new(inner: P, mode: DensifyMode, max_length: f64) -> Result<Self> {
+        if max_length.is_nan() || max_length <= 0.0 {
+            return Err(GeozeroError::Geometry(format!(
+                "invalid densify length {}",
+                max_length
+            )));
+        }
+        Ok(Densify {
+            inner,
+            mode,
+            max_length,
+            in_linestring: false,
+            coords: CoordSeq::default(),
+        })
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// Number of parts for a segment of `length`
+fn segment_parts(length: f64, max_length: f64) -> usize {
+    if length.is_finite() {
+        (length / max_length).ceil().min(MAX_SEGMENT_PARTS as f64) as usize
+    } else {
+        1
+    }
+}
+
+/// Central angle between two lon/lat points in radians
+fn central_angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.1.to_radians(), b.1.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.0 - a.0).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * h.sqrt().min(1.0).asin()
+}
+
+/// Point at `fraction` of the great circle arc from `a` to `b`
+fn great_circle_point(a: (f64, f64), b: (f64, f64), fraction: f64) -> (f64, f64) {
+    let delta = central_angle(a, b);
+    if delta == 0.0 {
+        return a;
+    }
+    let (lon1, lat1) = (a.0.to_radians(), a.1.to_radians());
+    let (lon2, lat2) = (b.0.to_radians(), b.1.to_radians());
+    let fa = ((1.0 - fraction) * delta).sin() / delta.sin();
+    let fb = (fraction * delta).sin() / delta.sin();
+    let x = fa * lat1.cos() * lon1.cos() + fb * lat2.cos() * lon2.cos();
+    let y = fa * lat1.cos() * lon1.sin() + fb * lat2.cos() * lon2.sin();
+    let z = fa * lat1.sin() + fb * lat2.sin();
+    (y.atan2(x).to_degrees(), z.atan2(x.hypot(y)).to_degrees())
+}
+
+/// Linear interpolation between `a` and `b`
+fn planar_point(a: (f64, f64), b: (f64, f64), fraction: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * fraction, a.1 + (b.1 - a.1) * fraction)
+}
+
+impl<P: GeomProcessor> Densify<P> {
+    /// Densify buffered linestring and pass it on
+    fn flush(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        let max_length = self.max_length;
+        match self.mode {
+            DensifyMode::Planar => self.coords.densify(
+                |a, b| segment_parts((b.0 - a.0).hypot(b.1 - a.1), max_length),
+                planar_point,
+            ),
+            DensifyMode::Geodesic => self.coords.densify(
+                |a, b| segment_parts(central_angle(a, b) * EARTH_RADIUS, max_length),
+                great_circle_point,
+            ),
+        }
+        self.inner
+            .linestring_begin(tagged, self.coords.len(), idx)?;
+        self.coords.emit(&mut self.inner)?;
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for Densify<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, polygon, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.in_linestring {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.in_linestring {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.in_linestring = true;
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        self.in_linestring = false;
+        self.flush(tagged, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for Densify<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Densify<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Collects coordinates as (x, y, z)
+    #[derive(Default)]
+    struct Coords(Vec<(f64, f64, Option<f64>)>);
+
+    impl GeomProcessor for Coords {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.push((x, y, None));
+            Ok(())
+        }
+        fn coordinate(
+            &mut self,
+            x: f64,
+            y: f64,
+            z: Option<f64>,
+            _m: Option<f64>,
+            _t: Option<f64>,
+            _tm: Option<u64>,
+            _idx: usize,
+        ) -> Result<()> {
+            self.0.push((x, y, z));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn densify_planar() -> Result<()> {
+        let mut densify = Densify::new(Coords::default(), DensifyMode::Planar, 2.0)?;
+        densify.linestring_begin(true, 3, 0)?;
+        densify.coordinate(0.0, 0.0, Some(0.0), None, None, None, 0)?;
+        densify.coordinate(4.0, 0.0, Some(8.0), None, None, None, 1)?;
+        densify.coordinate(5.0, 0.0, Some(10.0), None, None, None, 2)?;
+        densify.linestring_end(true, 0)?;
+        assert_eq!(
+            densify.into_inner().0,
+            vec![
+                (0.0, 0.0, Some(0.0)),
+                (2.0, 0.0, Some(4.0)),
+                (4.0, 0.0, Some(8.0)),
+                (5.0, 0.0, Some(10.0))
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn densify_geodesic() -> Result<()> {
+        // Equator segment of 10 degrees is about 1112 km long
+        let mut densify = Densify::new(Coords::default(), DensifyMode::Geodesic, 500_000.0)?;
+        densify.linestring_begin(true, 2, 0)?;
+        densify.xy(0.0, 0.0, 0)?;
+        densify.xy(10.0, 0.0, 1)?;
+        densify.linestring_end(true, 0)?;
+        let coords = densify.into_inner().0;
+        assert_eq!(coords.len(), 4);
+        assert!((coords[1].0 - 10.0 / 3.0).abs() < 1e-9);
+        assert!(coords[1].1.abs() < 1e-9);
+
+        // Points on meridian-crossing arcs leave the straight lon/lat line
+        let mid = great_circle_point((-60.0, 45.0), (60.0, 45.0), 0.5);
+        assert!(mid.0.abs() < 1e-9);
+        assert!(mid.1 > 45.0);
+        Ok(())
+    }
+
+    #[test]
+    fn densify_invalid() -> Result<()> {
+        assert!(Densify::new(Coords::default(), DensifyMode::Planar, 0.0).is_err());
+        assert!(Densify::new(Coords::default(), DensifyMode::Planar, -1.0).is_err());
+        assert!(Densify::new(Coords::default(), DensifyMode::Planar, f64::NAN).is_err());
+
+        let mut densify = Densify::new(Coords::default(), DensifyMode::Planar, 1.0)?;
+        densify.linestring_begin(true, 3, 0)?;
+        densify.xy(0.0, 0.0, 0)?;
+        densify.xy(f64::INFINITY, 0.0, 1)?;
+        densify.xy(1.0, f64::NAN, 2)?;
+        densify.linestring_end(true, 0)?;
+        assert_eq!(densify.into_inner().0.len(), 3);
+
+        let mut densify = Densify::new(Coords::default(), DensifyMode::Planar, 1e-300)?;
+        densify.linestring_begin(true, 2, 0)?;
+        densify.xy(0.0, 0.0, 0)?;
+        densify.xy(1e300, 0.0, 1)?;
+        densify.linestring_end(true, 0)?;
+        assert_eq!(densify.into_inner().0.len(), MAX_SEGMENT_PARTS + 1);
+        Ok(())
+    }
+}
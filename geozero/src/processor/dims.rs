@@ -0,0 +1,240 @@
+use crate::error::Result;
+use crate::processor::{Event, FeatureBuffer, Literal};
+use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Drops or sets Z and M values.
+///
+/// Dropping dimensions is useful for 2D-only outputs, setting Z for 3D outputs of
+/// 2D data. Z can be a constant or the numeric value of a feature property. Features
+/// are buffered when Z is read from a property, since properties may follow the geometry.
+/// Coordinates without remaining Z, M or time values are passed on with `xy`.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::AdjustDimensions;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::{CoordDimensions, GeozeroGeometry};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut writer = WktWriter::new(&mut out);
+/// writer.dims = CoordDimensions::xyz();
+/// let mut processor = AdjustDimensions::new(writer).set_z(500.0);
+/// WktStr("POINT(7.44 46.95)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(7.44 46.95 500)");
+/// ```
+pub struct AdjustDimensions<P> {
+    buffer: FeatureBuffer<P>,
+    drop_z: bool,
+    drop_m: bool,
+    z: Option<f64>,
+    z_property: Option<String>,
+}
+
+impl<P> AdjustDimensions<P> {
+    pub fn new(inner: P) -> Self {
+        AdjustDimensions {
+            buffer: FeatureBuffer::new(inner),
+            drop_z: false,
+            drop_m: false,
+            z: None,
+            z_property: None,
+        }
+    }
+    /// Remove Z values
+    pub fn drop_z(mut self) -> Self {
+        self.drop_z = true;
+        self
+    }
+    /// Remove M values
+    pub fn drop_m(mut self) -> Self {
+        self.drop_m = true;
+        self
+    }
+    /// Set Z of all coordinates to `z`
+    pub fn set_z(mut self, z: f64) -> Self {
+        self.z = Some(z);
+        self
+    }
+    /// Set Z of all coordinates to the value of property `name`.
+    ///
+    /// Z values of features without numeric value are kept.
+    pub fn z_from_property(mut self, name: &str) -> Self {
+        self.z_property = Some(name.to_string());
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> AdjustDimensions<P> {
+    #[allow(clippy::too_many_arguments)]
+    fn adjusted(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let z = match self.z {
+            Some(z) => Some(z),
+            None if self.drop_z => None,
+            None => z,
+        };
+        let m = if self.drop_m { None } else { m };
+        if z.is_none() && m.is_none() && t.is_none() && tm.is_none() {
+            self.buffer.xy(x, y, idx)
+        } else {
+            self.buffer.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    /// Pass recorded feature on with Z from property
+    fn replay(&mut self, events: Vec<Event>) -> Result<()> {
+        let name = self.z_property.as_deref().unwrap_or_default();
+        let z = events.iter().find_map(|event| match event {
+            Event::Property(_, n, value) if n == name => match Literal::from(value) {
+                Literal::Number(z) => Some(z),
+                Literal::String(s) => s.parse().ok(),
+                _ => None,
+            },
+            _ => None,
+        });
+        let constant = self.z;
+        self.z = z.or(constant);
+        let result = events.iter().try_for_each(|event| match *event {
+            Event::Xy(x, y, idx) => self.adjusted(x, y, None, None, None, None, idx),
+            Event::Coordinate(x, y, z, m, t, tm, idx) => self.adjusted(x, y, z, m, t, tm, idx),
+            _ => event.replay(self.buffer.inner_mut()),
+        });
+        self.z = constant;
+        result
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for AdjustDimensions<P> {
+    forward_processor_methods!(buffer; srid, empty_point, geometries);
+
+    fn dimensions(&self) -> CoordDimensions {
+        let mut dimensions = self.buffer.dimensions();
+        // Z is set by this processor
+        dimensions.z &= !self.drop_z && self.z.is_none() && self.z_property.is_none();
+        dimensions.m &= !self.drop_m;
+        dimensions
+    }
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.adjusted(x, y, None, None, None, None, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.adjusted(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for AdjustDimensions<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for AdjustDimensions<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        if self.z_property.is_some() {
+            self.buffer.record();
+        }
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        if self.z_property.is_some() {
+            let events = self.buffer.take();
+            self.replay(events)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnValue;
+
+    /// Collects coordinates as (x, y, z, m)
+    #[derive(Default)]
+    struct Coords(Vec<(f64, f64, Option<f64>, Option<f64>)>);
+
+    impl GeomProcessor for Coords {
+        fn dimensions(&self) -> CoordDimensions {
+            CoordDimensions::xyzm()
+        }
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.push((x, y, None, None));
+            Ok(())
+        }
+        fn coordinate(
+            &mut self,
+            x: f64,
+            y: f64,
+            z: Option<f64>,
+            m: Option<f64>,
+            _t: Option<f64>,
+            _tm: Option<u64>,
+            _idx: usize,
+        ) -> Result<()> {
+            self.0.push((x, y, z, m));
+            Ok(())
+        }
+    }
+
+    impl PropertyProcessor for Coords {}
+
+    impl FeatureProcessor for Coords {}
+
+    #[test]
+    fn drop_dimensions() -> Result<()> {
+        let mut processor = AdjustDimensions::new(Coords::default()).drop_z();
+        assert!(!processor.dimensions().z);
+        assert!(processor.dimensions().m);
+        processor.coordinate(1.0, 2.0, Some(3.0), Some(4.0), None, None, 0)?;
+        processor.coordinate(1.0, 2.0, Some(3.0), None, None, None, 1)?;
+        assert_eq!(
+            processor.into_inner().0,
+            vec![(1.0, 2.0, None, Some(4.0)), (1.0, 2.0, None, None)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn z_from_property() -> Result<()> {
+        let mut processor = AdjustDimensions::new(Coords::default()).z_from_property("height");
+        processor.feature_begin(0)?;
+        processor.geometry_begin()?;
+        processor.point_begin(0)?;
+        processor.xy(1.0, 2.0, 0)?;
+        processor.point_end(0)?;
+        processor.geometry_end()?;
+        processor.properties_begin()?;
+        processor.property(0, "height", &ColumnValue::Int(12))?;
+        processor.properties_end()?;
+        processor.feature_end(0)?;
+        assert_eq!(processor.into_inner().0, vec![(1.0, 2.0, Some(12.0), None)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+use crate::error::Result;
+use crate::processor::{Event, FeatureBuffer};
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor,
+};
+
+/// Splits multi geometries into single geometry features.
+///
+/// Each member of a MultiPoint, MultiLineString, MultiPolygon, MultiCurve, MultiSurface or
+/// GeometryCollection becomes a feature of its own with the properties of the parent feature.
+/// Nested collections are exploded recursively. Output features are numbered consecutively,
+/// the index of the member within its parent feature can be added as property.
+/// Members of features with several parts get the feature id `<parent id>-<part>`.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::ExplodeMulti;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "Islands"},
+///     "geometry": {"type": "MultiPoint", "coordinates": [[1, 2], [3, 4]]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut explode = ExplodeMulti::new(GeoJsonWriter::new(&mut out)).with_part_column("part");
+/// GeoJson(geojson).process(&mut explode).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert_eq!(json.matches(r#""name": "Islands""#).count(), 2);
+/// assert!(json.contains(r#""part": 1"#));
+/// ```
+pub struct ExplodeMulti<P> {
+    buffer: FeatureBuffer<P>,
+    part_column: Option<String>,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> ExplodeMulti<P> {
+    pub fn new(inner: P) -> Self {
+        ExplodeMulti {
+            buffer: FeatureBuffer::new(inner),
+            part_column: None,
+            count: 0,
+        }
+    }
+    /// Add member index within parent feature as property `name`
+    pub fn with_part_column(mut self, name: &str) -> Self {
+        self.part_column = Some(name.to_string());
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> ExplodeMulti<P> {
+    /// Pass feature events with `geometry` as member `part` of `parts`
+    fn emit_part(
+        &mut self,
+        feature: &[Event],
+        geometry: &[Event],
+        part: usize,
+        parts: usize,
+    ) -> Result<()> {
+        let idx = self.count;
+        self.count += 1;
+        let inner = self.buffer.inner_mut();
+        let part_value = ColumnValue::UInt(part as u32);
+        // Features without properties get a properties block for the part column
+        let mut properties_missing =
+            self.part_column.is_some() && !feature.contains(&Event::PropertiesEnd);
+        let mut property_count = 0;
+        for event in feature {
+            if properties_missing && matches!(event, Event::GeometryBegin | Event::FeatureEnd(_)) {
+                if let Some(name) = &self.part_column {
+                    inner.properties_begin()?;
+                    inner.property(0, name, &part_value)?;
+                    inner.properties_end()?;
+                }
+                properties_missing = false;
+            }
+            match event {
+                Event::FeatureBegin(_) => inner.feature_begin(idx)?,
+                Event::FeatureEnd(_) => inner.feature_end(idx)?,
+                Event::Fid(fid) if parts > 1 => {
+                    let fid = format!("{}-{}", fid.as_column_value(), part);
+                    inner.fid(&ColumnValue::String(&fid))?;
+                }
+                Event::Property(..) => {
+                    property_count += 1;
+                    event.replay(inner)?;
+                }
+                Event::PropertiesEnd => {
+                    if let Some(name) = &self.part_column {
+                        inner.property(property_count, name, &part_value)?;
+                    }
+                    inner.properties_end()?;
+                }
+                Event::GeometryBegin => {
+                    inner.geometry_begin()?;
+                    for event in geometry {
+                        event.replay(inner)?;
+                    }
+                }
+                _ => event.replay(inner)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split geometry events into top level members
+fn members(events: &[Event]) -> Vec<&[Event]> {
+    let mut members = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, event) in events.iter().enumerate() {
+        depth += event.nesting();
+        if depth == 0 {
+            members.push(&events[start..=i]);
+            start = i + 1;
+        }
+    }
+    members
+}
+
+/// Event with member index 0 and tagged as standalone geometry
+pub(crate) fn standalone(event: &Event) -> Event {
+    match *event {
+        Event::EmptyPoint(_) => Event::EmptyPoint(0),
+        Event::PointBegin(_) => Event::PointBegin(0),
+        Event::PointEnd(_) => Event::PointEnd(0),
+        Event::LineStringBegin(_, size, _) => Event::LineStringBegin(true, size, 0),
+        Event::LineStringEnd(_, _) => Event::LineStringEnd(true, 0),
+        Event::PolygonBegin(_, size, _) => Event::PolygonBegin(true, size, 0),
+        Event::PolygonEnd(_, _) => Event::PolygonEnd(true, 0),
+        Event::TriangleBegin(_, size, _) => Event::TriangleBegin(true, size, 0),
+        Event::TriangleEnd(_, _) => Event::TriangleEnd(true, 0),
+        Event::MultiPointBegin(size, _) => Event::MultiPointBegin(size, 0),
+        Event::MultiPointEnd(_) => Event::MultiPointEnd(0),
+        Event::MultiLineStringBegin(size, _) => Event::MultiLineStringBegin(size, 0),
+        Event::MultiLineStringEnd(_) => Event::MultiLineStringEnd(0),
+        Event::MultiPolygonBegin(size, _) => Event::MultiPolygonBegin(size, 0),
+        Event::MultiPolygonEnd(_) => Event::MultiPolygonEnd(0),
+        Event::GeometryCollectionBegin(size, _) => Event::GeometryCollectionBegin(size, 0),
+        Event::GeometryCollectionEnd(_) => Event::GeometryCollectionEnd(0),
+        Event::CircularStringBegin(size, _) => Event::CircularStringBegin(size, 0),
+        Event::CircularStringEnd(_) => Event::CircularStringEnd(0),
+        Event::CompoundCurveBegin(size, _) => Event::CompoundCurveBegin(size, 0),
+        Event::CompoundCurveEnd(_) => Event::CompoundCurveEnd(0),
+        Event::CurvePolygonBegin(size, _) => Event::CurvePolygonBegin(size, 0),
+        Event::CurvePolygonEnd(_) => Event::CurvePolygonEnd(0),
+        Event::MultiCurveBegin(size, _) => Event::MultiCurveBegin(size, 0),
+        Event::MultiCurveEnd(_) => Event::MultiCurveEnd(0),
+        Event::MultiSurfaceBegin(size, _) => Event::MultiSurfaceBegin(size, 0),
+        Event::MultiSurfaceEnd(_) => Event::MultiSurfaceEnd(0),
+        Event::PolyhedralSurfaceBegin(size, _) => Event::PolyhedralSurfaceBegin(size, 0),
+        Event::PolyhedralSurfaceEnd(_) => Event::PolyhedralSurfaceEnd(0),
+        Event::TinBegin(size, _) => Event::TinBegin(size, 0),
+        Event::TinEnd(_) => Event::TinEnd(0),
+        Event::Xy(x, y, _) => Event::Xy(x, y, 0),
+        Event::Coordinate(x, y, z, m, t, tm, _) => Event::Coordinate(x, y, z, m, t, tm, 0),
+        _ => event.clone(),
+    }
+}
+
+/// Explode geometry events recursively into single geometries
+fn explode(geometry: &[Event], parts: &mut Vec<Vec<Event>>) {
+    let inner = match geometry {
+        [Event::MultiPointBegin(..), inner @ .., _]
+        | [Event::MultiLineStringBegin(..), inner @ .., _]
+        | [Event::MultiPolygonBegin(..), inner @ .., _]
+        | [Event::GeometryCollectionBegin(..), inner @ .., _]
+        | [Event::MultiCurveBegin(..), inner @ .., _]
+        | [Event::MultiSurfaceBegin(..), inner @ .., _] => inner,
+        _ => &[],
+    };
+    if inner.is_empty() {
+        parts.push(geometry.to_vec());
+        return;
+    }
+    for member in members(inner) {
+        let last = member.len() - 1;
+        let single: Vec<Event> = match member[0] {
+            // MultiPoint members are passed as bare coordinates
+            Event::Xy(..) | Event::Coordinate(..) => vec![
+                Event::PointBegin(0),
+                standalone(&member[0]),
+                Event::PointEnd(0),
+            ],
+            _ => member
+                .iter()
+                .enumerate()
+                .map(|(i, event)| {
+                    if i == 0 || i == last {
+                        standalone(event)
+                    } else {
+                        event.clone()
+                    }
+                })
+                .collect(),
+        };
+        explode(&single, parts);
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for ExplodeMulti<P> {
+    forward_processor_methods!(buffer; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for ExplodeMulti<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ExplodeMulti<P> {
+    forward_processor_methods!(buffer; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        match &self.part_column {
+            Some(name) => {
+                let mut columns = columns.to_vec();
+                columns.push(ColumnDef::new(name, ColumnType::UInt));
+                self.buffer.schema(&columns)
+            }
+            None => self.buffer.schema(columns),
+        }
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let geometry_begin = events.iter().position(|e| *e == Event::GeometryBegin);
+        let geometry_end = events.iter().rposition(|e| *e == Event::GeometryEnd);
+        let (begin, end) = match (geometry_begin, geometry_end) {
+            (Some(begin), Some(end)) if begin < end => (begin, end),
+            _ => return self.emit_part(&events, &[], 0, 1),
+        };
+        let mut feature = events[..=begin].to_vec();
+        feature.extend_from_slice(&events[end..]);
+        let geometry = &events[begin + 1..end];
+        // Srid applies to all parts
+        let srid_count = geometry
+            .iter()
+            .take_while(|e| matches!(e, Event::Srid(_)))
+            .count();
+        let mut parts = Vec::new();
+        explode(&geometry[srid_count..], &mut parts);
+        for (i, part) in parts.iter().enumerate() {
+            let mut events = geometry[..srid_count].to_vec();
+            events.extend_from_slice(part);
+            self.emit_part(&feature, &events, i, parts.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records features as `idx:property,...:geometry events`
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl GeomProcessor for Recorder {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0
+                .last_mut()
+                .unwrap()
+                .push_str(&format!(" {}/{}", x, y));
+            Ok(())
+        }
+        fn point_begin(&mut self, idx: usize) -> Result<()> {
+            self.0.last_mut().unwrap().push_str(&format!(" P{}", idx));
+            Ok(())
+        }
+        fn linestring_begin(&mut self, tagged: bool, _size: usize, idx: usize) -> Result<()> {
+            let tag = if tagged { "L" } else { "l" };
+            self.0
+                .last_mut()
+                .unwrap()
+                .push_str(&format!(" {}{}", tag, idx));
+            Ok(())
+        }
+    }
+
+    impl PropertyProcessor for Recorder {
+        fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+            let entry = format!(" {}:{}={}", idx, name, value);
+            self.0.last_mut().unwrap().push_str(&entry);
+            Ok(false)
+        }
+    }
+
+    impl FeatureProcessor for Recorder {
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push(format!("{}", idx));
+            Ok(())
+        }
+        fn fid(&mut self, fid: &ColumnValue) -> Result<()> {
+            let entry = format!(" fid={}", fid);
+            self.0.last_mut().unwrap().push_str(&entry);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn explode_features() -> Result<()> {
+        let mut explode = ExplodeMulti::new(Recorder::default()).with_part_column("part");
+        explode.dataset_begin(None)?;
+        explode.feature_begin(0)?;
+        explode.properties_begin()?;
+        explode.property(0, "name", &ColumnValue::String("a"))?;
+        explode.properties_end()?;
+        explode.geometry_begin()?;
+        explode.multipoint_begin(2, 0)?;
+        explode.xy(1.0, 2.0, 0)?;
+        explode.xy(3.0, 4.0, 1)?;
+        explode.multipoint_end(0)?;
+        explode.geometry_end()?;
+        explode.feature_end(0)?;
+
+        explode.feature_begin(1)?;
+        explode.properties_begin()?;
+        explode.property(0, "name", &ColumnValue::String("b"))?;
+        explode.properties_end()?;
+        explode.geometry_begin()?;
+        explode.geometrycollection_begin(2, 0)?;
+        explode.point_begin(0)?;
+        explode.xy(5.0, 6.0, 0)?;
+        explode.point_end(0)?;
+        explode.multilinestring_begin(1, 1)?;
+        explode.linestring_begin(false, 2, 0)?;
+        explode.xy(7.0, 8.0, 0)?;
+        explode.xy(9.0, 10.0, 1)?;
+        explode.linestring_end(false, 0)?;
+        explode.multilinestring_end(1)?;
+        explode.geometrycollection_end(0)?;
+        explode.geometry_end()?;
+        explode.feature_end(1)?;
+        explode.dataset_end()?;
+
+        assert_eq!(
+            explode.into_inner().0,
+            vec![
+                "0 0:name=a 1:part=0 P0 1/2",
+                "1 0:name=a 1:part=1 P0 3/4",
+                "2 0:name=b 1:part=0 P0 5/6",
+                "3 0:name=b 1:part=1 L0 7/8 9/10",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn explode_without_properties() -> Result<()> {
+        let mut explode = ExplodeMulti::new(Recorder::default()).with_part_column("part");
+        explode.dataset_begin(None)?;
+        explode.feature_begin(0)?;
+        explode.fid(&ColumnValue::Long(7))?;
+        explode.geometry_begin()?;
+        explode.multipoint_begin(2, 0)?;
+        explode.xy(1.0, 2.0, 0)?;
+        explode.xy(3.0, 4.0, 1)?;
+        explode.multipoint_end(0)?;
+        explode.geometry_end()?;
+        explode.feature_end(0)?;
+
+        explode.feature_begin(1)?;
+        explode.fid(&ColumnValue::Long(8))?;
+        explode.geometry_begin()?;
+        explode.point_begin(0)?;
+        explode.xy(5.0, 6.0, 0)?;
+        explode.point_end(0)?;
+        explode.geometry_end()?;
+        explode.feature_end(1)?;
+        explode.dataset_end()?;
+
+        assert_eq!(
+            explode.into_inner().0,
+            vec![
+                "0 fid=7-0 0:part=0 P0 1/2",
+                "1 fid=7-1 0:part=1 P0 3/4",
+                "2 fid=8 0:part=0 P0 5/6",
+            ]
+        );
+        Ok(())
+    }
+}
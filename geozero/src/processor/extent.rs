@@ -0,0 +1,268 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, ProcessorSink, PropertyProcessor};
+
+/// 2D bounding box.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Extent {
+    pub minx: f64,
+    pub miny: f64,
+    pub maxx: f64,
+    pub maxy: f64,
+}
+
+impl Extent {
+    pub fn new(minx: f64, miny: f64, maxx: f64, maxy: f64) -> Self {
+        Extent {
+            minx,
+            miny,
+            maxx,
+            maxy,
+        }
+    }
+    /// Extent of a single point
+    pub fn from_point(x: f64, y: f64) -> Self {
+        Self::new(x, y, x, y)
+    }
+    /// Expand extent to include point
+    pub fn extend(&mut self, x: f64, y: f64) {
+        self.minx = self.minx.min(x);
+        self.miny = self.miny.min(y);
+        self.maxx = self.maxx.max(x);
+        self.maxy = self.maxy.max(y);
+    }
+    /// Expand extent to include other extent
+    pub fn merge(&mut self, other: &Extent) {
+        self.extend(other.minx, other.miny);
+        self.extend(other.maxx, other.maxy);
+    }
+    /// Check whether point is within extent or on its boundary
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.minx && x <= self.maxx && y >= self.miny && y <= self.maxy
+    }
+    /// Check whether extents intersect or touch
+    pub fn intersects(&self, other: &Extent) -> bool {
+        self.minx <= other.maxx
+            && self.maxx >= other.minx
+            && self.miny <= other.maxy
+            && self.maxy >= other.miny
+    }
+    pub fn width(&self) -> f64 {
+        self.maxx - self.minx
+    }
+    pub fn height(&self) -> f64 {
+        self.maxy - self.miny
+    }
+    /// Center point
+    pub fn center(&self) -> (f64, f64) {
+        ((self.minx + self.maxx) / 2.0, (self.miny + self.maxy) / 2.0)
+    }
+    /// Extent of the transformed corners
+    ///
+    /// Exact for affine transformations and transformations monotonic in each axis.
+    pub fn map_corners<F: Fn(f64, f64) -> (f64, f64)>(&self, f: F) -> Extent {
+        let (x, y) = f(self.minx, self.miny);
+        let mut extent = Extent::from_point(x, y);
+        for (x, y) in [
+            (self.minx, self.maxy),
+            (self.maxx, self.miny),
+            (self.maxx, self.maxy),
+        ]
+        .iter()
+        {
+            let (x, y) = f(*x, *y);
+            extent.extend(x, y);
+        }
+        extent
+    }
+}
+
+/// Expand optional extent
+pub(crate) fn extend(extent: &mut Option<Extent>, x: f64, y: f64) {
+    match extent {
+        Some(extent) => extent.extend(x, y),
+        None => *extent = Some(Extent::from_point(x, y)),
+    }
+}
+
+/// Calculates dataset extent, feature extents, feature and vertex count in a single pass.
+///
+/// Created with `ExtentCalc::new()` it is used as a sink, created with `ExtentCalc::tee(processor)`
+/// all calls are passed to the wrapped processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Extent, ExtentCalc};
+/// use geozero::wkt::WktStr;
+/// use geozero::GeozeroGeometry;
+///
+/// let mut calc = ExtentCalc::new();
+/// WktStr("LINESTRING(1 2,3 4,0 5)").process_geom(&mut calc).unwrap();
+/// assert_eq!(calc.extent(), Some(Extent::new(0.0, 2.0, 3.0, 5.0)));
+/// assert_eq!(calc.vertex_count(), 3);
+/// ```
+pub struct ExtentCalc<P = ProcessorSink> {
+    inner: P,
+    extent: Option<Extent>,
+    feature_extent: Option<Extent>,
+    feature_extents: Option<Vec<Option<Extent>>>,
+    feature_count: u64,
+    vertex_count: u64,
+}
+
+impl ExtentCalc<ProcessorSink> {
+    pub fn new() -> Self {
+        Self::tee(ProcessorSink::new())
+    }
+}
+
+impl Default for ExtentCalc<ProcessorSink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> ExtentCalc<P> {
+    /// Pass-through extent calculation
+    pub fn tee(inner: P) -> Self {
+        ExtentCalc {
+            inner,
+            extent: None,
+            feature_extent: None,
+            feature_extents: None,
+            feature_count: 0,
+            vertex_count: 0,
+        }
+    }
+    /// Collect extents of each feature
+    pub fn with_feature_extents(mut self) -> Self {
+        self.feature_extents = Some(Vec::new());
+        self
+    }
+    /// Extent of all processed geometries
+    pub fn extent(&self) -> Option<Extent> {
+        self.extent
+    }
+    /// Extents of processed features (`None` for features without coordinates).
+    ///
+    /// Empty, if not requested with `with_feature_extents`.
+    pub fn feature_extents(&self) -> &[Option<Extent>] {
+        self.feature_extents.as_deref().unwrap_or(&[])
+    }
+    /// Number of processed features
+    pub fn feature_count(&self) -> u64 {
+        self.feature_count
+    }
+    /// Number of processed vertices
+    pub fn vertex_count(&self) -> u64 {
+        self.vertex_count
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume calculator and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        extend(&mut self.extent, x, y);
+        if self.feature_extents.is_some() {
+            extend(&mut self.feature_extent, x, y);
+        }
+        self.vertex_count += 1;
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for ExtentCalc<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.add_vertex(x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.add_vertex(x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for ExtentCalc<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ExtentCalc<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature_extent = None;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.feature_count += 1;
+        if let Some(extents) = &mut self.feature_extents {
+            extents.push(self.feature_extent.take());
+        }
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extent() {
+        let mut extent = Extent::from_point(1.0, 1.0);
+        extent.extend(3.0, -1.0);
+        assert_eq!(extent, Extent::new(1.0, -1.0, 3.0, 1.0));
+        extent.merge(&Extent::new(0.0, 0.0, 2.0, 2.0));
+        assert_eq!(extent, Extent::new(0.0, -1.0, 3.0, 2.0));
+        assert!(extent.contains(3.0, 2.0));
+        assert!(!extent.contains(3.1, 2.0));
+        assert!(extent.intersects(&Extent::new(3.0, 2.0, 4.0, 4.0)));
+        assert!(!extent.intersects(&Extent::new(3.5, 2.0, 4.0, 4.0)));
+    }
+
+    #[test]
+    fn feature_extents() -> Result<()> {
+        let mut calc = ExtentCalc::new().with_feature_extents();
+        calc.dataset_begin(None)?;
+        calc.feature_begin(0)?;
+        calc.point_begin(0)?;
+        calc.xy(1.0, 2.0, 0)?;
+        calc.point_end(0)?;
+        calc.feature_end(0)?;
+        calc.feature_begin(1)?;
+        calc.feature_end(1)?;
+        calc.feature_begin(2)?;
+        calc.multipoint_begin(2, 0)?;
+        calc.xy(5.0, 1.0, 0)?;
+        calc.xy(6.0, 0.0, 1)?;
+        calc.multipoint_end(0)?;
+        calc.feature_end(2)?;
+        calc.dataset_end()?;
+
+        assert_eq!(calc.extent(), Some(Extent::new(1.0, 0.0, 6.0, 2.0)));
+        assert_eq!(calc.vertex_count(), 3);
+        assert_eq!(
+            calc.feature_extents(),
+            &[
+                Some(Extent::from_point(1.0, 2.0)),
+                None,
+                Some(Extent::new(5.0, 0.0, 6.0, 1.0))
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,663 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::FeatureBuffer;
+use crate::{ColumnValue, FeatureProcessor, GeomProcessor, OwnedColumnValue, PropertyProcessor};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Literal value of a filter expression
+#[derive(Clone, PartialEq, Debug)]
+pub enum Literal {
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Null,
+}
+
+impl From<&OwnedColumnValue> for Literal {
+    fn from(value: &OwnedColumnValue) -> Self {
+        match value {
+            OwnedColumnValue::Byte(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::UByte(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::Bool(v) => Literal::Bool(*v),
+            OwnedColumnValue::Short(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::UShort(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::Int(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::UInt(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::Long(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::ULong(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::Float(v) => Literal::Number(*v as f64),
+            OwnedColumnValue::Double(v) => Literal::Number(*v),
+            OwnedColumnValue::BigInt(v) => v.parse().map_or(Literal::Null, Literal::Number),
+            OwnedColumnValue::String(v)
+            | OwnedColumnValue::Json(v)
+            | OwnedColumnValue::DateTime(v)
+            | OwnedColumnValue::Date(v) => Literal::String(v.clone()),
+            OwnedColumnValue::Binary(_) | OwnedColumnValue::Null => Literal::Null,
+        }
+    }
+}
+
+impl Literal {
+    /// Compare values of the same type (`None` for NULL or different types)
+    fn compare(&self, other: &Literal) -> Option<Ordering> {
+        match (self, other) {
+            (Literal::Number(a), Literal::Number(b)) => a.partial_cmp(b),
+            (Literal::String(a), Literal::String(b)) => Some(a.cmp(b)),
+            (Literal::Bool(a), Literal::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+/// Property name or literal value
+#[derive(Clone, PartialEq, Debug)]
+pub enum Operand {
+    Property(String),
+    Literal(Literal),
+}
+
+/// Comparison operator
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Filter expression.
+///
+/// Supports the basic CQL2-text subset: comparisons (`=`, `<>`, `<`, `<=`, `>`, `>=`),
+/// `AND`, `OR`, `NOT`, `IS [NOT] NULL`, `[NOT] LIKE`, `[NOT] IN` and `[NOT] BETWEEN`.
+/// Comparisons with NULL values or values of different types are false.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    IsNull(Operand),
+    /// Pattern with `%` and `_` wildcards
+    Like(Operand, String),
+    In(Operand, Vec<Literal>),
+    Between(Operand, Operand, Operand),
+    /// Boolean value
+    Operand(Operand),
+}
+
+impl Expr {
+    /// Parse CQL2-text expression, e.g. `population > 100000 AND country = 'CH'`
+    pub fn parse(text: &str) -> Result<Expr> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(GeozeroError::Filter(format!(
+                "unexpected token {:?}",
+                token
+            ))),
+        }
+    }
+    /// Evaluate expression with given property values
+    pub fn evaluate(&self, properties: &HashMap<String, OwnedColumnValue>) -> bool {
+        let value = |operand| value_of(operand, properties);
+        match self {
+            Expr::And(a, b) => a.evaluate(properties) && b.evaluate(properties),
+            Expr::Or(a, b) => a.evaluate(properties) || b.evaluate(properties),
+            Expr::Not(expr) => !expr.evaluate(properties),
+            Expr::Compare(a, op, b) => match value(a).compare(&value(b)) {
+                Some(ordering) => match op {
+                    CompareOp::Eq => ordering == Ordering::Equal,
+                    CompareOp::Ne => ordering != Ordering::Equal,
+                    CompareOp::Lt => ordering == Ordering::Less,
+                    CompareOp::Le => ordering != Ordering::Greater,
+                    CompareOp::Gt => ordering == Ordering::Greater,
+                    CompareOp::Ge => ordering != Ordering::Less,
+                },
+                None => false,
+            },
+            Expr::IsNull(operand) => value(operand) == Literal::Null,
+            Expr::Like(operand, pattern) => match value(operand) {
+                Literal::String(s) => {
+                    let s: Vec<char> = s.chars().collect();
+                    let pattern: Vec<char> = pattern.chars().collect();
+                    like(&s, &pattern)
+                }
+                _ => false,
+            },
+            Expr::In(operand, list) => {
+                let value = value(operand);
+                list.iter()
+                    .any(|item| value.compare(item) == Some(Ordering::Equal))
+            }
+            Expr::Between(operand, low, high) => {
+                let value = value(operand);
+                matches!(
+                    value.compare(&value_of(low, properties)),
+                    Some(Ordering::Greater) | Some(Ordering::Equal)
+                ) && matches!(
+                    value.compare(&value_of(high, properties)),
+                    Some(Ordering::Less) | Some(Ordering::Equal)
+                )
+            }
+            Expr::Operand(operand) => value(operand) == Literal::Bool(true),
+        }
+    }
+    /// Names of properties referenced by the expression
+    pub fn properties(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_properties(&mut names);
+        names
+    }
+    fn collect_properties<'a>(&'a self, names: &mut Vec<&'a str>) {
+        let operands: Vec<&Operand> = match self {
+            Expr::And(a, b) | Expr::Or(a, b) => {
+                a.collect_properties(names);
+                b.collect_properties(names);
+                Vec::new()
+            }
+            Expr::Not(expr) => {
+                expr.collect_properties(names);
+                Vec::new()
+            }
+            Expr::Compare(a, _, b) => vec![a, b],
+            Expr::IsNull(operand)
+            | Expr::Like(operand, _)
+            | Expr::In(operand, _)
+            | Expr::Operand(operand) => vec![operand],
+            Expr::Between(operand, low, high) => vec![operand, low, high],
+        };
+        for operand in operands {
+            if let Operand::Property(name) = operand {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+}
+
+/// Value of operand for given properties
+fn value_of(operand: &Operand, properties: &HashMap<String, OwnedColumnValue>) -> Literal {
+    match operand {
+        Operand::Property(name) => properties
+            .get(name)
+            .map(Literal::from)
+            .unwrap_or(Literal::Null),
+        Operand::Literal(literal) => literal.clone(),
+    }
+}
+
+/// Match string against LIKE pattern
+///
+/// Greedy matching, which backtracks to the last `%` only, in O(len(s) * len(pattern)).
+fn like(s: &[char], pattern: &[char]) -> bool {
+    let (mut si, mut pi) = (0, 0);
+    // Pattern position after the last `%` and string position matched by it
+    let mut backtrack = None;
+    while si < s.len() {
+        match pattern.get(pi) {
+            Some('%') => {
+                pi += 1;
+                backtrack = Some((pi, si));
+            }
+            Some(&c) if c == '_' || c == s[si] => {
+                pi += 1;
+                si += 1;
+            }
+            _ => match backtrack {
+                // Let the last `%` match one more char
+                Some((bp, bs)) => {
+                    pi = bp;
+                    si = bs + 1;
+                    backtrack = Some((bp, si));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[pi..].iter().all(|c| *c == '%')
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    String(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\r' | '\n' => {
+                chars.next();
+            }
+            '(' | ')' | ',' | '=' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    ',' => Token::Comma,
+                    _ => Token::Op(CompareOp::Eq),
+                });
+            }
+            '<' | '>' | '!' => {
+                chars.next();
+                let op = match (c, chars.peek()) {
+                    ('<', Some('=')) => Some(CompareOp::Le),
+                    ('<', Some('>')) | ('!', Some('=')) => Some(CompareOp::Ne),
+                    ('>', Some('=')) => Some(CompareOp::Ge),
+                    _ => None,
+                };
+                let op = match (op, c) {
+                    (Some(op), _) => {
+                        chars.next();
+                        op
+                    }
+                    (None, '<') => CompareOp::Lt,
+                    (None, '>') => CompareOp::Gt,
+                    _ => return Err(GeozeroError::Filter("unexpected `!`".to_string())),
+                };
+                tokens.push(Token::Op(op));
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => {
+                            // Quote escaped by doubling
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                                s.push(c);
+                            } else {
+                                break;
+                            }
+                        }
+                        Some(ch) => s.push(ch),
+                        None => {
+                            return Err(GeozeroError::Filter("unterminated string".to_string()))
+                        }
+                    }
+                }
+                tokens.push(if c == '\'' {
+                    Token::String(s)
+                } else {
+                    Token::Ident(s)
+                });
+            }
+            '0'..='9' | '-' | '+' | '.' => {
+                let mut s = String::new();
+                while let Some(&ch) = chars.peek() {
+                    let exponent_sign = (ch == '-' || ch == '+') && s.ends_with(['e', 'E']);
+                    if ch.is_ascii_digit()
+                        || ch == '.'
+                        || ch == 'e'
+                        || ch == 'E'
+                        || exponent_sign
+                        || s.is_empty()
+                    {
+                        s.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = s
+                    .parse()
+                    .map_err(|_| GeozeroError::Filter(format!("invalid number `{}`", s)))?;
+                tokens.push(Token::Number(number));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_alphanumeric() || ch == '_' || ch == '.' || ch == ':' {
+                        s.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(s));
+            }
+            _ => {
+                return Err(GeozeroError::Filter(format!(
+                    "unexpected character `{}`",
+                    c
+                )))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword))
+    }
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+    fn expect(&mut self, token: Token) -> Result<()> {
+        if self.tokens.get(self.pos) == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(GeozeroError::Filter(format!("expected {:?}", token)))
+        }
+    }
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.not()?;
+        while self.keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+    fn not(&mut self) -> Result<Expr> {
+        if self.keyword("NOT") {
+            Ok(Expr::Not(Box::new(self.not()?)))
+        } else {
+            self.predicate()
+        }
+    }
+    fn predicate(&mut self) -> Result<Expr> {
+        if self.tokens.get(self.pos) == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.or()?;
+            self.expect(Token::RParen)?;
+            return Ok(expr);
+        }
+        let operand = self.operand()?;
+        if let Some(Token::Op(op)) = self.tokens.get(self.pos) {
+            let op = *op;
+            self.pos += 1;
+            return Ok(Expr::Compare(operand, op, self.operand()?));
+        }
+        if self.keyword("IS") {
+            let negated = self.keyword("NOT");
+            if !self.keyword("NULL") {
+                return Err(GeozeroError::Filter("expected NULL".to_string()));
+            }
+            return Ok(negate(Expr::IsNull(operand), negated));
+        }
+        let negated = self.keyword("NOT");
+        let expr = if self.keyword("LIKE") {
+            match self.operand()? {
+                Operand::Literal(Literal::String(pattern)) => Expr::Like(operand, pattern),
+                _ => return Err(GeozeroError::Filter("expected LIKE pattern".to_string())),
+            }
+        } else if self.keyword("IN") {
+            self.expect(Token::LParen)?;
+            let mut list = Vec::new();
+            loop {
+                match self.operand()? {
+                    Operand::Literal(literal) => list.push(literal),
+                    Operand::Property(_) => {
+                        return Err(GeozeroError::Filter("expected literal".to_string()))
+                    }
+                }
+                if self.tokens.get(self.pos) != Some(&Token::Comma) {
+                    break;
+                }
+                self.pos += 1;
+            }
+            self.expect(Token::RParen)?;
+            Expr::In(operand, list)
+        } else if self.keyword("BETWEEN") {
+            let low = self.operand()?;
+            if !self.keyword("AND") {
+                return Err(GeozeroError::Filter("expected AND".to_string()));
+            }
+            Expr::Between(operand, low, self.operand()?)
+        } else if negated {
+            return Err(GeozeroError::Filter(
+                "expected LIKE, IN or BETWEEN".to_string(),
+            ));
+        } else {
+            Expr::Operand(operand)
+        };
+        Ok(negate(expr, negated))
+    }
+    fn operand(&mut self) -> Result<Operand> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| GeozeroError::Filter("unexpected end".to_string()))?;
+        self.pos += 1;
+        let operand = match token {
+            Token::Number(n) => Operand::Literal(Literal::Number(n)),
+            Token::String(s) => Operand::Literal(Literal::String(s)),
+            Token::Ident(s) if s.eq_ignore_ascii_case("TRUE") => {
+                Operand::Literal(Literal::Bool(true))
+            }
+            Token::Ident(s) if s.eq_ignore_ascii_case("FALSE") => {
+                Operand::Literal(Literal::Bool(false))
+            }
+            Token::Ident(s) if s.eq_ignore_ascii_case("NULL") => Operand::Literal(Literal::Null),
+            Token::Ident(s) => Operand::Property(s),
+            token => {
+                return Err(GeozeroError::Filter(format!(
+                    "unexpected token {:?}",
+                    token
+                )))
+            }
+        };
+        Ok(operand)
+    }
+}
+
+fn negate(expr: Expr, negated: bool) -> Expr {
+    if negated {
+        Expr::Not(Box::new(expr))
+    } else {
+        expr
+    }
+}
+
+/// Passes on features matching a filter expression.
+///
+/// Features are buffered until all properties are known. Passed features are renumbered.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::AttributeFilter;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern", "population": 134591}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"name": "Zürich", "population": 421878}, "geometry": {"type": "Point", "coordinates": [8.54, 47.37]}}
+/// ]}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut filter =
+///     AttributeFilter::parse(GeoJsonWriter::new(&mut out), "population > 200000").unwrap();
+/// GeoJson(geojson).process(&mut filter).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains("Zürich") && !json.contains("Bern"));
+/// ```
+pub struct AttributeFilter<P> {
+    buffer: FeatureBuffer<P>,
+    expr: Expr,
+    properties: HashMap<String, OwnedColumnValue>,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> AttributeFilter<P> {
+    pub fn new(inner: P, expr: Expr) -> Self {
+        AttributeFilter {
+            buffer: FeatureBuffer::new(inner),
+            expr,
+            properties: HashMap::new(),
+            count: 0,
+        }
+    }
+    /// Filter with CQL2-text expression
+    pub fn parse(inner: P, expr: &str) -> Result<Self> {
+        Ok(Self::new(inner, Expr::parse(expr)?))
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume filter and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for AttributeFilter<P> {
+    forward_processor_methods!(buffer; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for AttributeFilter<P> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.properties.insert(name.to_string(), value.into());
+        self.buffer.property(idx, name, value)
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for AttributeFilter<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.properties.clear();
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        if self.expr.evaluate(&self.properties) {
+            self.count += 1;
+            self.buffer.replay(self.count - 1)
+        } else {
+            self.buffer.discard();
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn props(values: &[(&str, OwnedColumnValue)]) -> HashMap<String, OwnedColumnValue> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn eval(expr: &str, properties: &HashMap<String, OwnedColumnValue>) -> bool {
+        Expr::parse(expr).unwrap().evaluate(properties)
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Expr::parse("population > 100000 AND country='CH'").unwrap(),
+            Expr::And(
+                Box::new(Expr::Compare(
+                    Operand::Property("population".to_string()),
+                    CompareOp::Gt,
+                    Operand::Literal(Literal::Number(100000.0))
+                )),
+                Box::new(Expr::Compare(
+                    Operand::Property("country".to_string()),
+                    CompareOp::Eq,
+                    Operand::Literal(Literal::String("CH".to_string()))
+                ))
+            )
+        );
+        assert!(Expr::parse("a >").is_err());
+        assert!(Expr::parse("a = 'x").is_err());
+        assert!(Expr::parse("(a = 1").is_err());
+        assert!(Expr::parse("a = 1 b").is_err());
+    }
+
+    #[test]
+    fn properties() {
+        let expr = Expr::parse("pop > 1000 AND (name LIKE 'B%' OR pop BETWEEN low AND 5)").unwrap();
+        assert_eq!(expr.properties(), vec!["pop", "name", "low"]);
+    }
+
+    #[test]
+    fn like_patterns() {
+        let like = |s: &str, pattern: &str| {
+            let s: Vec<char> = s.chars().collect();
+            let pattern: Vec<char> = pattern.chars().collect();
+            like(&s, &pattern)
+        };
+        assert!(like("", ""));
+        assert!(like("", "%%"));
+        assert!(!like("", "_"));
+        assert!(like("Zürich", "Z_rich"));
+        assert!(like("abcabd", "%ab_"));
+        assert!(like("abcabd", "a%b%d"));
+        assert!(!like("abcabd", "a%b%c"));
+        assert!(like("mississippi", "m%iss%ppi"));
+        // Exponential with recursive backtracking
+        let s = "a".repeat(1000);
+        assert!(!like(&s, &format!("{}b", "%a".repeat(50))));
+    }
+
+    #[test]
+    fn evaluate() {
+        let p = props(&[
+            ("name", OwnedColumnValue::String("Bern".to_string())),
+            ("population", OwnedColumnValue::Int(134591)),
+            ("capital", OwnedColumnValue::Bool(true)),
+            ("area", OwnedColumnValue::Null),
+        ]);
+        assert!(eval("population > 100000 AND name = 'Bern'", &p));
+        assert!(eval("population < 1e5 OR NOT (name <> 'Bern')", &p));
+        assert!(eval("capital", &p));
+        assert!(eval("capital = TRUE", &p));
+        assert!(eval("area IS NULL AND missing IS NULL", &p));
+        assert!(eval("name IS NOT NULL", &p));
+        assert!(!eval("area > 0", &p));
+        assert!(!eval("area = NULL", &p));
+        assert!(eval("name LIKE 'B_r%'", &p));
+        assert!(eval("name NOT LIKE 'Z%'", &p));
+        assert!(eval("name LIKE '%'", &p));
+        assert!(eval("name LIKE '%n%'", &p));
+        assert!(!eval("name LIKE '_'", &p));
+        assert!(!eval("name LIKE 'B%rn_'", &p));
+        assert!(eval("name IN ('Basel', 'Bern')", &p));
+        assert!(eval("population BETWEEN 100000 AND 200000", &p));
+        assert!(!eval("population NOT BETWEEN 100000 AND 200000", &p));
+        assert!(eval("\"population\" >= -1", &p));
+        assert!(!eval("name > 1", &p));
+    }
+}
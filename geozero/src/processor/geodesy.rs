@@ -0,0 +1,272 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::CoordSeq;
+use crate::{Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use geodesy::preamble::*;
+
+/// Reprojects XY coordinates with the pure Rust [geodesy](https://docs.rs/geodesy) crate.
+///
+/// Alternative to [ProjTransform](crate::processor::ProjTransform) for targets
+/// without libproj (e.g. musl or WASM), supporting the following CRS:
+///
+/// * `EPSG:4326` (geographic coordinates in lon/lat order)
+/// * `EPSG:3857` (Web Mercator)
+/// * `EPSG:326xx` and `EPSG:327xx` (UTM north and south zones on WGS84)
+/// * `EPSG:3035` (ETRS89 LAEA Europe)
+///
+/// The CRS and SRID of the source are replaced by the target CRS.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::processor::GeodesyTransform;
+/// use geozero::geojson::GeoJsonWriter;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = GeodesyTransform::new(GeoJsonWriter::new(&mut out), "EPSG:4326", "EPSG:32632")?;
+/// ```
+pub struct GeodesyTransform<P> {
+    inner: P,
+    ops: GeodesyOps,
+    /// Target CRS
+    target: Option<Crs>,
+    coords: CoordSeq,
+}
+
+/// Geodesy operator steps converting XY coordinates
+#[derive(Default)]
+pub(crate) struct GeodesyOps {
+    ctx: Minimal,
+    steps: Vec<(OpHandle, Direction)>,
+    /// Input coordinates in degrees
+    geographic_in: bool,
+    /// Output coordinates in degrees
+    geographic_out: bool,
+    buf: Vec<Coord>,
+}
+
+/// Geodesy operator definition of a CRS (`None` for geographic WGS84)
+fn crs_definition(crs: &str) -> Result<Option<String>> {
+    let unsupported = || GeozeroError::Crs(format!("`{}`", crs));
+    let code = crs
+        .strip_prefix("EPSG:")
+        .or_else(|| crs.strip_prefix("epsg:"))
+        .ok_or_else(unsupported)?;
+    let code: u32 = code.parse().map_err(|_| unsupported())?;
+    let definition = match code {
+        4326 => return Ok(None),
+        // Web Mercator projects the ellipsoidal coordinates onto a sphere
+        3857 => "merc ellps=unitsphere k_0=6378137".to_string(),
+        3035 => "laea lat_0=52 lon_0=10 x_0=4321000 y_0=3210000 ellps=GRS80".to_string(),
+        32601..=32660 => format!("utm zone={}", code - 32600),
+        32701..=32760 => format!("utm zone={} south", code - 32700),
+        _ => return Err(unsupported()),
+    };
+    Ok(Some(definition))
+}
+
+impl GeodesyOps {
+    /// Conversion from CRS `from` to CRS `to`, given as authority codes like `EPSG:4326`.
+    pub fn new(from: &str, to: &str) -> Result<Self> {
+        let mut ops = Self::default();
+        if let Some(definition) = crs_definition(from)? {
+            ops.add_step(&definition, Inv)?;
+        } else {
+            ops.geographic_in = true;
+        }
+        if let Some(definition) = crs_definition(to)? {
+            ops.add_step(&definition, Fwd)?;
+        } else {
+            ops.geographic_out = true;
+        }
+        Ok(ops)
+    }
+    /// Conversion with a geodesy operator definition.
+    pub fn from_pipeline(definition: &str) -> Result<Self> {
+        let mut ops = Self::default();
+        ops.add_step(definition, Fwd)?;
+        Ok(ops)
+    }
+    fn add_step(&mut self, definition: &str, direction: Direction) -> Result<()> {
+        let op = self
+            .ctx
+            .op(definition)
+            .map_err(|e| GeozeroError::Crs(e.to_string()))?;
+        self.steps.push((op, direction));
+        Ok(())
+    }
+    /// Convert XY values in place
+    pub fn convert(&mut self, xy: &mut [(f64, f64)]) -> Result<()> {
+        let geographic_in = self.geographic_in;
+        self.buf.clear();
+        self.buf.extend(xy.iter().map(|(x, y)| {
+            if geographic_in {
+                Coord::raw(x.to_radians(), y.to_radians(), 0.0, 0.0)
+            } else {
+                Coord::raw(*x, *y, 0.0, 0.0)
+            }
+        }));
+        for (op, direction) in &self.steps {
+            // geodesy's Direction is not Copy
+            let direction = match direction {
+                Fwd => Fwd,
+                Inv => Inv,
+            };
+            self.ctx
+                .apply(*op, direction, &mut self.buf)
+                .map_err(|e| GeozeroError::Transform(e.to_string()))?;
+        }
+        for (xy, c) in xy.iter_mut().zip(&self.buf) {
+            *xy = if self.geographic_out {
+                (c[0].to_degrees(), c[1].to_degrees())
+            } else {
+                (c[0], c[1])
+            };
+        }
+        Ok(())
+    }
+}
+
+impl<P> GeodesyTransform<P> {
+    /// Transformation from CRS `from` to CRS `to`, given as authority codes like `EPSG:4326`.
+    pub fn new(inner: P, from: &str, to: &str) -> Result<Self> {
+        let mut transform = Self::with_ops(inner, GeodesyOps::new(from, to)?);
+        transform.target = Crs::from_definition(to);
+        Ok(transform)
+    }
+    /// Transformation with a geodesy operator definition (e.g. `utm zone=32 | inv utm zone=33`).
+    ///
+    /// Geographic coordinates are expected and returned in radians.
+    pub fn from_pipeline(inner: P, definition: &str) -> Result<Self> {
+        Ok(Self::with_ops(
+            inner,
+            GeodesyOps::from_pipeline(definition)?,
+        ))
+    }
+    fn with_ops(inner: P, ops: GeodesyOps) -> Self {
+        GeodesyTransform {
+            inner,
+            ops,
+            target: None,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Set target CRS passed on instead of the source CRS, e.g. for pipeline definitions
+    pub fn target_crs(mut self, crs: Crs) -> Self {
+        self.target = Some(crs);
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeodesyTransform<P> {
+    /// Transform buffered coordinates and pass them on
+    fn flush(&mut self) -> Result<()> {
+        if self.coords.xy.is_empty() {
+            return Ok(());
+        }
+        self.ops.convert(&mut self.coords.xy)?;
+        self.coords.emit(&mut self.inner)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for GeodesyTransform<P> {
+    forward_processor_methods!(inner; dimensions);
+
+    fn srid(&mut self, _srid: Option<i32>) -> Result<()> {
+        self.inner.srid(self.target.as_ref().and_then(Crs::srid))
+    }
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.coords.push_xy(x, y, idx);
+        Ok(())
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+        Ok(())
+    }
+    flush_and_forward!(geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for GeodesyTransform<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for GeodesyTransform<P> {
+    forward_processor_methods!(inner; dataset, schema, warning, feature, properties);
+
+    fn crs(&mut self, _crs: &Crs) -> Result<()> {
+        // Source CRS is replaced by the target CRS
+        match &self.target {
+            Some(target) => self.inner.crs(target),
+            None => Ok(()),
+        }
+    }
+
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        self.inner.metadata(&metadata.without_extent())
+    }
+
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.inner.geometry_begin()
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        self.flush()?;
+        self.inner.geometry_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wkt::{WktStr, WktWriter};
+    use crate::GeozeroGeometry;
+
+    #[test]
+    fn crs_definitions() -> Result<()> {
+        assert_eq!(crs_definition("EPSG:4326")?, None);
+        assert_eq!(
+            crs_definition("EPSG:32632")?,
+            Some("utm zone=32".to_string())
+        );
+        assert_eq!(
+            crs_definition("EPSG:32733")?,
+            Some("utm zone=33 south".to_string())
+        );
+        assert!(crs_definition("EPSG:2056").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reproject() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor =
+            GeodesyTransform::new(WktWriter::new(&mut out), "EPSG:4326", "EPSG:3857")?;
+        WktStr("POINT(10 0)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("POINT(1113194.9"), "{}", wkt);
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor =
+            GeodesyTransform::new(WktWriter::new(&mut out), "EPSG:4326", "EPSG:32632")?;
+        WktStr("POINT(9 0)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("POINT(500000"), "{}", wkt);
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use crate::error::Result;
+use crate::processor::{extend, replay_with_property, schema_with_column, Extent, FeatureBuffer};
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, ProcessorSink,
+    PropertyProcessor,
+};
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Encode WGS84 lon/lat as geohash with `precision` characters.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::geohash;
+///
+/// assert_eq!(geohash(10.40744, 57.64911, 11), "u4pruydqqvj");
+/// ```
+pub fn geohash(lon: f64, lat: f64, precision: usize) -> String {
+    let mut lon_range = (-180.0, 180.0);
+    let mut lat_range = (-90.0, 90.0);
+    let mut hash = String::with_capacity(precision);
+    let mut bits = 0;
+    let mut idx = 0;
+    // Bits alternate between longitude and latitude, starting with longitude
+    let mut lon_bit = true;
+    while hash.len() < precision {
+        let (range, value) = if lon_bit {
+            (&mut lon_range, lon)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        idx <<= 1;
+        if value >= mid {
+            idx |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        lon_bit = !lon_bit;
+        bits += 1;
+        if bits == 5 {
+            hash.push(BASE32[idx] as char);
+            bits = 0;
+            idx = 0;
+        }
+    }
+    hash
+}
+
+/// Adds the geohash of each feature as property.
+///
+/// Points are encoded with their location, other geometries with the center of
+/// their bounding box. Coordinates are expected in WGS84 lon/lat.
+/// Each feature is buffered until its geometry is complete. The property is
+/// appended to the feature properties and the schema, features without
+/// geometry get a NULL value.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::GeohashProperty;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "Bern"},
+///     "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = GeohashProperty::new(GeoJsonWriter::new(&mut out), "geohash").precision(5);
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// assert!(std::str::from_utf8(&out).unwrap().contains(r#""geohash": "u0m71""#));
+/// ```
+pub struct GeohashProperty<P> {
+    buffer: FeatureBuffer<P>,
+    name: String,
+    precision: usize,
+    extent: Option<Extent>,
+}
+
+impl<P> GeohashProperty<P> {
+    pub fn new(inner: P, name: &str) -> Self {
+        GeohashProperty {
+            buffer: FeatureBuffer::new(inner),
+            name: name.to_string(),
+            precision: 9,
+            extent: None,
+        }
+    }
+    /// Number of geohash characters (default: 9)
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for GeohashProperty<P> {
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.buffer.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.buffer.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for GeohashProperty<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for GeohashProperty<P> {
+    forward_processor_methods!(buffer; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let mut column = ColumnDef::new(&self.name, ColumnType::String);
+        column.width = Some(self.precision);
+        self.buffer.schema(&schema_with_column(columns, column))
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.extent = None;
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let hash = self.extent.take().map(|extent| {
+            let (lon, lat) = extent.center();
+            geohash(lon, lat, self.precision)
+        });
+        let value = match &hash {
+            Some(hash) => ColumnValue::String(hash),
+            None => ColumnValue::Null,
+        };
+        replay_with_property(&events, self.buffer.inner_mut(), &self.name, &value)
+    }
+}
+
+/// Collects the geohash of each feature.
+///
+/// Geohashes are computed like in [GeohashProperty]. Created with
+/// `GeohashCollector::new(precision)` it is used as a sink, created with
+/// `GeohashCollector::tee(processor, precision)` all calls are passed to the
+/// wrapped processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::GeohashCollector;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {}, "geometry": null}
+/// ]}"#;
+/// let mut collector = GeohashCollector::new(5);
+/// GeoJson(geojson).process(&mut collector).unwrap();
+/// assert_eq!(collector.geohashes(), &[Some("u0m71".to_string()), None]);
+/// ```
+pub struct GeohashCollector<P = ProcessorSink> {
+    inner: P,
+    precision: usize,
+    extent: Option<Extent>,
+    geohashes: Vec<Option<String>>,
+}
+
+impl GeohashCollector<ProcessorSink> {
+    pub fn new(precision: usize) -> Self {
+        Self::tee(ProcessorSink::new(), precision)
+    }
+}
+
+impl<P> GeohashCollector<P> {
+    /// Pass-through geohash collector
+    pub fn tee(inner: P, precision: usize) -> Self {
+        GeohashCollector {
+            inner,
+            precision,
+            extent: None,
+            geohashes: Vec::new(),
+        }
+    }
+    /// Geohashes of processed features, `None` for features without geometry
+    pub fn geohashes(&self) -> &[Option<String>] {
+        &self.geohashes
+    }
+    /// Consume collector and return geohashes
+    pub fn into_geohashes(self) -> Vec<Option<String>> {
+        self.geohashes
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume collector and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for GeohashCollector<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for GeohashCollector<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for GeohashCollector<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.extent = None;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let hash = self.extent.take().map(|extent| {
+            let (lon, lat) = extent.center();
+            geohash(lon, lat, self.precision)
+        });
+        self.geohashes.push(hash);
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(geohash(-5.6, 42.6, 5), "ezs42");
+        assert_eq!(geohash(0.0, 0.0, 4), "s000");
+        assert_eq!(geohash(-180.0, -90.0, 3), "000");
+        assert_eq!(geohash(180.0, 90.0, 3), "zzz");
+    }
+
+    #[test]
+    fn bbox_center() -> Result<()> {
+        let mut collector = GeohashCollector::new(5);
+        collector.feature_begin(0)?;
+        collector.linestring_begin(true, 2, 0)?;
+        collector.xy(-6.0, 42.0, 0)?;
+        collector.xy(-5.2, 43.2, 1)?;
+        collector.linestring_end(true, 0)?;
+        collector.feature_end(0)?;
+        assert_eq!(collector.into_geohashes(), vec![Some("ezs42".to_string())]);
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use crate::error::Result;
+use crate::geos::geos_reader::process_geom_n;
+use crate::geos::GeosWriter;
+use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use geos::{Geom, GeometryTypes, JoinStyle};
+
+/// Pass calls to the GEOS writer and materialize the geometry after the outermost `*_end`
+macro_rules! materialize {
+    (@begin $idx:ident; $method:ident($($arg:ident: $ty:ty),*)) => {
+        fn $method(&mut self, $($arg: $ty),*) -> Result<()> {
+            if self.depth == 0 {
+                self.idx = $idx;
+            }
+            self.depth += 1;
+            self.writer.$method($($arg),*)
+        }
+    };
+    (@end $method:ident($($arg:ident: $ty:ty),*)) => {
+        fn $method(&mut self, $($arg: $ty),*) -> Result<()> {
+            self.writer.$method($($arg),*)?;
+            self.depth -= 1;
+            if self.depth == 0 {
+                self.flush()?;
+            }
+            Ok(())
+        }
+    };
+    (point) => {
+        materialize!(@begin idx; point_begin(idx: usize));
+        materialize!(@end point_end(idx: usize));
+    };
+    (tagged $begin:ident, $end:ident) => {
+        materialize!(@begin idx; $begin(tagged: bool, size: usize, idx: usize));
+        materialize!(@end $end(tagged: bool, idx: usize));
+    };
+    (sized $begin:ident, $end:ident) => {
+        materialize!(@begin idx; $begin(size: usize, idx: usize));
+        materialize!(@end $end(idx: usize));
+    };
+}
+
+/// Buffers geometries with [GEOS](https://libgeos.org/).
+///
+/// Each point, line and polygon geometry is converted to a GEOS geometry, buffered by
+/// `distance` and passed on as the resulting (multi)polygon. Members of geometry
+/// collections are buffered individually. Linestrings can be replaced by their offset
+/// curve instead, with negative distances offsetting to the right.
+/// Output coordinates are 2D.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::GeosBuffer;
+/// use geozero::GeozeroDatasource;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = GeosBuffer::new(GeoJsonWriter::new(&mut out), 500.0).quadrant_segments(4);
+/// GeoJson(geojson).process(&mut processor)?;
+/// ```
+pub struct GeosBuffer<P> {
+    inner: P,
+    distance: f64,
+    quadrant_segments: i32,
+    offset_lines: bool,
+    writer: GeosWriter<'static>,
+    /// Geometry nesting level
+    depth: usize,
+    /// Index of materialized geometry
+    idx: usize,
+}
+
+impl<P> GeosBuffer<P> {
+    pub fn new(inner: P, distance: f64) -> Self {
+        GeosBuffer {
+            inner,
+            distance,
+            quadrant_segments: 8,
+            offset_lines: false,
+            writer: GeosWriter::new(),
+            depth: 0,
+            idx: 0,
+        }
+    }
+    /// Number of segments used to approximate a quarter circle (default: 8)
+    pub fn quadrant_segments(mut self, segments: i32) -> Self {
+        self.quadrant_segments = segments;
+        self
+    }
+    /// Replace linestrings by their offset curve instead of buffering them
+    pub fn offset_lines(mut self) -> Self {
+        self.offset_lines = true;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeosBuffer<P> {
+    /// Buffer materialized geometry and pass on the result
+    fn flush(&mut self) -> Result<()> {
+        let writer = std::mem::replace(&mut self.writer, GeosWriter::new());
+        let geom = writer.geometry();
+        let result = match geom.geometry_type() {
+            GeometryTypes::LineString | GeometryTypes::MultiLineString if self.offset_lines => {
+                geom.offset_curve(self.distance, self.quadrant_segments, JoinStyle::Round, 5.0)?
+            }
+            _ => geom.buffer(self.distance, self.quadrant_segments)?,
+        };
+        process_geom_n(&result, self.idx, &mut self.inner)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for GeosBuffer<P> {
+    forward_processor_methods!(inner; srid, empty_point, geometrycollection, curves, surfaces);
+
+    fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions::xy()
+    }
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.depth > 0 {
+            self.writer.xy(x, y, idx)
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.depth > 0 {
+            self.writer.xy(x, y, idx)
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    materialize!(point);
+    materialize!(sized multipoint_begin, multipoint_end);
+    materialize!(tagged linestring_begin, linestring_end);
+    materialize!(sized multilinestring_begin, multilinestring_end);
+    materialize!(tagged polygon_begin, polygon_end);
+    materialize!(sized multipolygon_begin, multipolygon_end);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for GeosBuffer<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for GeosBuffer<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn buffer_point() -> Result<()> {
+        let mut processor = GeosBuffer::new(GeosWriter::new(), 2.0).quadrant_segments(32);
+        processor.point_begin(0)?;
+        processor.xy(1.0, 1.0, 0)?;
+        processor.point_end(0)?;
+        let polygon = processor.into_inner().geom;
+        assert_eq!(polygon.geometry_type(), GeometryTypes::Polygon);
+        let area = polygon.area()?;
+        assert!((area - std::f64::consts::PI * 4.0).abs() < 0.1);
+        Ok(())
+    }
+}
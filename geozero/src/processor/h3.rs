@@ -0,0 +1,382 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::{replay_with_property, schema_with_column, FeatureBuffer};
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, ProcessorSink,
+    PropertyProcessor,
+};
+use geo_types::{Coord, LineString};
+use h3o::geom::{PolyfillConfig, ToCells};
+use h3o::{CellIndex, LatLng, Resolution};
+
+/// Collects the H3 cells covering a feature
+struct H3Calc {
+    resolution: Resolution,
+    /// Nesting level of polygons and triangles
+    surface_depth: usize,
+    /// Ring index within current polygon, `None` for linestrings
+    ring: Option<usize>,
+    in_line: bool,
+    coords: Vec<Coord<f64>>,
+    rings: Vec<LineString<f64>>,
+    cells: Vec<CellIndex>,
+}
+
+impl H3Calc {
+    fn new(resolution: Resolution) -> Self {
+        H3Calc {
+            resolution,
+            surface_depth: 0,
+            ring: None,
+            in_line: false,
+            coords: Vec::new(),
+            rings: Vec::new(),
+            cells: Vec::new(),
+        }
+    }
+    fn cell(&self, coord: Coord<f64>) -> Option<CellIndex> {
+        LatLng::new(coord.y, coord.x)
+            .ok()
+            .map(|ll| ll.to_cell(self.resolution))
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        if self.in_line {
+            self.coords.push(Coord { x, y });
+        } else if let Some(cell) = self.cell(Coord { x, y }) {
+            // Points and multipoints
+            self.cells.push(cell);
+        }
+    }
+    /// Add cells along `line`
+    fn trace(&mut self, line: &[Coord<f64>]) {
+        let mut prev: Option<CellIndex> = None;
+        for coord in line {
+            let cell = match self.cell(*coord) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            match prev {
+                Some(prev) if prev != cell => match prev.grid_path_cells(cell) {
+                    Ok(path) => self.cells.extend(path.filter_map(|cell| cell.ok())),
+                    // No path across pentagon distortions
+                    Err(_) => self.cells.push(cell),
+                },
+                Some(_) => {}
+                None => self.cells.push(cell),
+            }
+            prev = Some(cell);
+        }
+    }
+    fn line_begin(&mut self, size: usize, idx: usize) {
+        self.ring = if self.surface_depth > 0 {
+            Some(idx)
+        } else {
+            None
+        };
+        self.in_line = true;
+        self.coords.clear();
+        self.coords.reserve(size);
+    }
+    fn line_end(&mut self) {
+        self.in_line = false;
+        let line = std::mem::take(&mut self.coords);
+        self.trace(&line);
+        if self.ring.is_some() {
+            self.rings.push(LineString::new(line));
+        }
+    }
+    fn surface_begin(&mut self) {
+        self.surface_depth += 1;
+        self.rings.clear();
+    }
+    fn surface_end(&mut self) -> Result<()> {
+        self.surface_depth -= 1;
+        let mut rings = std::mem::take(&mut self.rings).into_iter();
+        let exterior = match rings.next() {
+            Some(exterior) => exterior,
+            None => return Ok(()),
+        };
+        // Cells with center inside the polygon, boundary cells are added by `line_end`
+        let polygon =
+            h3o::geom::Polygon::from_degrees(geo_types::Polygon::new(exterior, rings.collect()))
+                .map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+        self.cells
+            .extend(polygon.to_cells(PolyfillConfig::new(self.resolution)));
+        Ok(())
+    }
+    /// Return sorted cells of processed geometries and reset
+    fn take(&mut self) -> Vec<CellIndex> {
+        self.surface_depth = 0;
+        self.in_line = false;
+        self.rings.clear();
+        let mut cells = std::mem::take(&mut self.cells);
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+}
+
+/// GeomProcessor implementation passing coordinates and rings to `self.calc`
+/// before forwarding calls to the wrapped processor `$inner`
+macro_rules! h3_geom_impls {
+    ($processor:ident, $inner:ident) => {
+        impl<P: GeomProcessor> GeomProcessor for $processor<P> {
+            forward_processor_methods!($inner; dimensions, srid, empty_point, point, multipoint,
+                multilinestring, multipolygon, geometrycollection, curves, polyhedralsurface, tin);
+
+            fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+                self.calc.add_vertex(x, y);
+                self.$inner.xy(x, y, idx)
+            }
+            fn coordinate(
+                &mut self,
+                x: f64,
+                y: f64,
+                z: Option<f64>,
+                m: Option<f64>,
+                t: Option<f64>,
+                tm: Option<u64>,
+                idx: usize,
+            ) -> Result<()> {
+                self.calc.add_vertex(x, y);
+                self.$inner.coordinate(x, y, z, m, t, tm, idx)
+            }
+            fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.line_begin(size, idx);
+                self.$inner.linestring_begin(tagged, size, idx)
+            }
+            fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.line_end();
+                self.$inner.linestring_end(tagged, idx)
+            }
+            fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.surface_begin();
+                self.$inner.polygon_begin(tagged, size, idx)
+            }
+            fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.surface_end()?;
+                self.$inner.polygon_end(tagged, idx)
+            }
+            fn triangle_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.surface_begin();
+                self.$inner.triangle_begin(tagged, size, idx)
+            }
+            fn triangle_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.surface_end()?;
+                self.$inner.triangle_end(tagged, idx)
+            }
+        }
+    };
+}
+
+/// Adds the H3 cells covering each feature as property.
+///
+/// Points are assigned to their cell, linestrings and polygon boundaries are traced
+/// cell by cell and polygons are filled with the [h3o](https://docs.rs/h3o) polyfill.
+/// Coordinates are expected in WGS84 lon/lat. Each feature is buffered until its
+/// geometry is complete. The property is appended to the feature properties and the
+/// schema as JSON array of sorted cell indexes in hex notation, features without
+/// geometry get a NULL value. Curves are not covered.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::H3Property;
+/// use geozero::GeozeroDatasource;
+/// use h3o::Resolution;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "Bern"},
+///     "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = H3Property::new(GeoJsonWriter::new(&mut out), Resolution::Five, "h3");
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// assert!(std::str::from_utf8(&out).unwrap().contains(r#""h3": ["85"#));
+/// ```
+pub struct H3Property<P> {
+    buffer: FeatureBuffer<P>,
+    calc: H3Calc,
+    name: String,
+}
+
+impl<P> H3Property<P> {
+    pub fn new(inner: P, resolution: Resolution, name: &str) -> Self {
+        H3Property {
+            buffer: FeatureBuffer::new(inner),
+            calc: H3Calc::new(resolution),
+            name: name.to_string(),
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+h3_geom_impls!(H3Property, buffer);
+
+impl<P: FeatureProcessor> PropertyProcessor for H3Property<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for H3Property<P> {
+    forward_processor_methods!(buffer; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let column = ColumnDef::new(&self.name, ColumnType::Json);
+        self.buffer.schema(&schema_with_column(columns, column))
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.calc.take();
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let cells = self.calc.take();
+        let json = if cells.is_empty() {
+            None
+        } else {
+            let cells: Vec<String> = cells.iter().map(ToString::to_string).collect();
+            Some(serde_json::to_string(&cells)?)
+        };
+        let value = match &json {
+            Some(json) => ColumnValue::Json(json),
+            None => ColumnValue::Null,
+        };
+        replay_with_property(&events, self.buffer.inner_mut(), &self.name, &value)
+    }
+}
+
+/// Collects the H3 cells covering each feature.
+///
+/// Cells are computed like in [H3Property] and collected as table of feature index
+/// and cell, e.g. for aggregating features per cell. Created with
+/// `H3Collector::new(resolution)` it is used as a sink, created with
+/// `H3Collector::tee(processor, resolution)` all calls are passed to the wrapped
+/// processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::H3Collector;
+/// use geozero::GeozeroDatasource;
+/// use h3o::Resolution;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.45, 46.95]}}
+/// ]}"#;
+/// let mut collector = H3Collector::new(Resolution::Five);
+/// GeoJson(geojson).process(&mut collector).unwrap();
+/// let cells = collector.cells();
+/// assert_eq!(cells.len(), 2);
+/// assert_eq!(cells[0].1, cells[1].1);
+/// ```
+pub struct H3Collector<P = ProcessorSink> {
+    inner: P,
+    calc: H3Calc,
+    cells: Vec<(u64, CellIndex)>,
+}
+
+impl H3Collector<ProcessorSink> {
+    pub fn new(resolution: Resolution) -> Self {
+        Self::tee(ProcessorSink::new(), resolution)
+    }
+}
+
+impl<P> H3Collector<P> {
+    /// Pass-through H3 cell collector
+    pub fn tee(inner: P, resolution: Resolution) -> Self {
+        H3Collector {
+            inner,
+            calc: H3Calc::new(resolution),
+            cells: Vec::new(),
+        }
+    }
+    /// Feature index and covering cell, one row per cell
+    pub fn cells(&self) -> &[(u64, CellIndex)] {
+        &self.cells
+    }
+    /// Consume collector and return cell table
+    pub fn into_cells(self) -> Vec<(u64, CellIndex)> {
+        self.cells
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume collector and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+h3_geom_impls!(H3Collector, inner);
+
+impl<P: PropertyProcessor> PropertyProcessor for H3Collector<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for H3Collector<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.calc.take();
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let cells = self.calc.take();
+        self.cells.extend(cells.into_iter().map(|cell| (idx, cell)));
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJson;
+    use crate::GeozeroDatasource;
+
+    /// Cells covering the feature with geometry `geometry`
+    fn cells(geometry: &str, resolution: Resolution) -> Result<Vec<CellIndex>> {
+        let geojson = format!(
+            r#"{{"type": "Feature", "properties": {{}}, "geometry": {}}}"#,
+            geometry
+        );
+        let mut collector = H3Collector::new(resolution);
+        GeoJson(&geojson).process(&mut collector)?;
+        Ok(collector
+            .into_cells()
+            .into_iter()
+            .map(|(_, cell)| cell)
+            .collect())
+    }
+
+    #[test]
+    fn coverage() -> Result<()> {
+        let point = LatLng::new(46.95, 7.44).unwrap().to_cell(Resolution::Seven);
+        let line = r#"{"type": "LineString", "coordinates": [[7.44, 46.95], [7.60, 46.95]]}"#;
+        let line_cells = cells(line, Resolution::Seven)?;
+        assert!(line_cells.len() > 2);
+        assert!(line_cells.contains(&point));
+
+        let polygon = r#"{"type": "Polygon", "coordinates": [[[7.3, 46.9], [7.6, 46.9], [7.6, 47.0], [7.3, 47.0], [7.3, 46.9]]]}"#;
+        let polygon_cells = cells(polygon, Resolution::Seven)?;
+        assert!(polygon_cells.contains(&point));
+        // Interior and boundary cells
+        assert!(polygon_cells.len() > line_cells.len() * 4);
+
+        // Polygon smaller than a cell is covered by its boundary cells
+        let small = r#"{"type": "Polygon", "coordinates": [[[7.44, 46.95], [7.4401, 46.95], [7.4401, 46.9501], [7.44, 46.95]]]}"#;
+        assert_eq!(cells(small, Resolution::Seven)?, vec![point]);
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use crate::error::Result;
+use crate::index::hilbert;
+use crate::processor::{
+    events_size, Event, Extent, FeatureBuffer, MemoryAccount, MemoryStats, SpillFile, SpillPos,
+};
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::path::PathBuf;
+
+/// Recorded calls of a feature
+enum Stored {
+    /// Events with their estimated size
+    Memory(Vec<Event>, usize),
+    Disk(SpillPos),
+}
+
+/// Buffered feature
+struct SortItem {
+    /// Center of bounding box
+    center: Option<(f64, f64)>,
+    events: Stored,
+}
+
+/// Sorts features along a Hilbert curve.
+///
+/// Features are buffered until `dataset_end` and passed on ordered by the Hilbert
+/// value of their bounding box center, which puts features close to each other in
+/// space close to each other in the output. Features without coordinates come last.
+/// When more than `spill_after` features or more than `spill_above` bytes are
+/// buffered, recorded features are moved to a temporary file. Memory usage is
+/// reported to the `memory_callback` after moving features to disk and at the end
+/// of the dataset.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::HilbertSort;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///   {"type": "Feature", "properties": {"id": 1}, "geometry": {"type": "Point", "coordinates": [10, 0]}},
+///   {"type": "Feature", "properties": {"id": 2}, "geometry": {"type": "Point", "coordinates": [0, 0]}}
+/// ]}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = HilbertSort::new(GeoJsonWriter::new(&mut out));
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.find(r#""id": 2"#) < json.find(r#""id": 1"#));
+/// ```
+pub struct HilbertSort<P> {
+    buffer: FeatureBuffer<P>,
+    spill_after: usize,
+    spill_above: usize,
+    spill_dir: PathBuf,
+    spill: Option<SpillFile>,
+    /// Number of features kept in memory
+    in_memory: usize,
+    items: Vec<SortItem>,
+    memory: MemoryAccount,
+}
+
+impl<P> HilbertSort<P> {
+    pub fn new(inner: P) -> Self {
+        HilbertSort {
+            buffer: FeatureBuffer::new(inner),
+            spill_after: 100_000,
+            spill_above: 512 * 1024 * 1024,
+            spill_dir: std::env::temp_dir(),
+            spill: None,
+            in_memory: 0,
+            items: Vec::new(),
+            memory: MemoryAccount::default(),
+        }
+    }
+    /// Maximal number of features kept in memory (default: 100'000)
+    pub fn spill_after(mut self, features: usize) -> Self {
+        self.spill_after = features;
+        self
+    }
+    /// Maximal estimated size in bytes of features kept in memory (default: 512 MiB)
+    pub fn spill_above(mut self, bytes: usize) -> Self {
+        self.spill_above = bytes;
+        self
+    }
+    /// Callback receiving memory statistics
+    pub fn memory_callback<F: FnMut(&MemoryStats) + 'static>(mut self, callback: F) -> Self {
+        self.memory.set_callback(Box::new(callback));
+        self
+    }
+    /// Memory statistics
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.memory.stats()
+    }
+    /// Directory for temporary files (default: system temp directory)
+    pub fn spill_dir<D: Into<PathBuf>>(mut self, dir: D) -> Self {
+        self.spill_dir = dir.into();
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    /// Move features kept in memory to temporary file
+    fn spill(&mut self) -> Result<()> {
+        if self.spill.is_none() {
+            self.spill = Some(SpillFile::create(&self.spill_dir)?);
+        }
+        let file = match &mut self.spill {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        for item in &mut self.items {
+            if let Stored::Memory(events, size) = &item.events {
+                let pos = file.write(events)?;
+                self.memory.spill(*size, pos.size());
+                item.events = Stored::Disk(pos);
+            }
+        }
+        self.in_memory = 0;
+        self.memory.report();
+        Ok(())
+    }
+}
+
+/// Center of bounding box of recorded coordinates
+fn center(events: &[Event]) -> Option<(f64, f64)> {
+    let mut extent: Option<Extent> = None;
+    for event in events {
+        let (x, y) = match event {
+            Event::Xy(x, y, _) | Event::Coordinate(x, y, ..) => (*x, *y),
+            _ => continue,
+        };
+        match &mut extent {
+            Some(extent) => extent.extend(x, y),
+            None => extent = Some(Extent::from_point(x, y)),
+        }
+    }
+    extent.map(|e| ((e.minx + e.maxx) / 2.0, (e.miny + e.maxy) / 2.0))
+}
+
+impl<P: FeatureProcessor> HilbertSort<P> {
+    /// Pass on buffered features in Hilbert order
+    fn flush(&mut self) -> Result<()> {
+        let mut items = std::mem::take(&mut self.items);
+        let extent = items.iter().filter_map(|item| item.center).fold(
+            None,
+            |extent: Option<Extent>, (x, y)| match extent {
+                Some(mut extent) => {
+                    extent.extend(x, y);
+                    Some(extent)
+                }
+                None => Some(Extent::from_point(x, y)),
+            },
+        );
+        let cell = |value: f64, min: f64, size: f64| {
+            if size > 0.0 {
+                ((value - min) / size * (u16::MAX as f64)) as u32
+            } else {
+                0
+            }
+        };
+        items.sort_by_cached_key(|item| match (item.center, extent) {
+            (Some((x, y)), Some(e)) => {
+                hilbert(cell(x, e.minx, e.width()), cell(y, e.miny, e.height()))
+            }
+            // Features without coordinates last
+            _ => u64::MAX,
+        });
+        for (idx, item) in items.into_iter().enumerate() {
+            let events = match item.events {
+                Stored::Memory(events, size) => {
+                    self.memory.release(size);
+                    events
+                }
+                Stored::Disk(pos) => match &mut self.spill {
+                    Some(file) => file.read(pos)?,
+                    None => Vec::new(),
+                },
+            };
+            let inner = self.buffer.inner_mut();
+            for event in &events {
+                match event {
+                    Event::FeatureBegin(_) => inner.feature_begin(idx as u64)?,
+                    Event::FeatureEnd(_) => inner.feature_end(idx as u64)?,
+                    _ => event.replay(inner)?,
+                }
+            }
+        }
+        self.spill = None;
+        self.in_memory = 0;
+        self.memory.report();
+        Ok(())
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for HilbertSort<P> {
+    forward_processor_methods!(buffer; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for HilbertSort<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for HilbertSort<P> {
+    forward_processor_methods!(buffer; schema, crs, metadata, warning, fid, properties, geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.buffer.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.flush()?;
+        self.buffer.dataset_end()
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let size = events_size(&events);
+        self.memory.add(size);
+        self.items.push(SortItem {
+            center: center(&events),
+            events: Stored::Memory(events, size),
+        });
+        self.in_memory += 1;
+        if self.in_memory > self.spill_after || self.memory.buffered() > self.spill_above {
+            self.spill()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records feature index and first X/Y value of each feature
+    #[derive(Default)]
+    struct Features {
+        features: Vec<(u64, f64, f64)>,
+        idx: u64,
+        ended: bool,
+    }
+
+    impl GeomProcessor for Features {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.features.push((self.idx, x, y));
+            Ok(())
+        }
+    }
+
+    impl PropertyProcessor for Features {}
+
+    impl FeatureProcessor for Features {
+        fn dataset_end(&mut self) -> Result<()> {
+            self.ended = true;
+            Ok(())
+        }
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.idx = idx;
+            Ok(())
+        }
+    }
+
+    fn process(spill_after: usize) -> Result<Features> {
+        let mut processor = HilbertSort::new(Features::default()).spill_after(spill_after);
+        processor.dataset_begin(None)?;
+        for (idx, (x, y)) in [(10.0, 0.0), (10.0, 10.0), (0.0, 0.0), (0.0, 10.0)]
+            .iter()
+            .enumerate()
+        {
+            processor.feature_begin(idx as u64)?;
+            processor.geometry_begin()?;
+            processor.point_begin(0)?;
+            processor.xy(*x, *y, 0)?;
+            processor.point_end(0)?;
+            processor.geometry_end()?;
+            processor.feature_end(idx as u64)?;
+            assert!(processor.inner().features.is_empty());
+        }
+        processor.dataset_end()?;
+        Ok(processor.into_inner())
+    }
+
+    #[test]
+    fn hilbert_order() -> Result<()> {
+        let expected = vec![
+            (0, 0.0, 0.0),
+            (1, 0.0, 10.0),
+            (2, 10.0, 10.0),
+            (3, 10.0, 0.0),
+        ];
+        let features = process(100)?;
+        assert!(features.ended);
+        assert_eq!(features.features, expected);
+        assert_eq!(process(1)?.features, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn spill_above_bytes() -> Result<()> {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let r = reports.clone();
+        let mut processor = HilbertSort::new(Features::default())
+            .spill_above(1)
+            .memory_callback(move |stats| r.borrow_mut().push(*stats));
+        processor.dataset_begin(None)?;
+        for idx in 0..2 {
+            processor.feature_begin(idx)?;
+            processor.property(0, "name", &crate::ColumnValue::String("feature"))?;
+            processor.feature_end(idx)?;
+        }
+        processor.dataset_end()?;
+        let reports = reports.borrow();
+        // Two spills and the final report
+        assert_eq!(reports.len(), 3);
+        let last = reports[2];
+        assert_eq!(last.buffered, 0);
+        assert!(last.peak > 0);
+        assert!(last.spilled > 0);
+        assert_eq!(processor.memory_stats(), last);
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use crate::error::{GeozeroError, Result};
+use crate::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor, ResourceLimits};
+use std::io::Read;
+use std::mem::size_of;
+
+/// Enforces [ResourceLimits] on the processed calls.
+///
+/// Fails with `GeozeroError::ResourceLimit` as soon as a limit is exceeded, before
+/// passing the offending call on. Readers apply the limits of their `ReadOptions`
+/// with this processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::ResourceLimiter;
+/// use geozero::wkt::WktWriter;
+/// use geozero::{GeomProcessor, ResourceLimits};
+///
+/// let limits = ResourceLimits {
+///     max_vertices: Some(2),
+///     ..Default::default()
+/// };
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = ResourceLimiter::new(WktWriter::new(&mut out), limits);
+/// processor.linestring_begin(true, 3, 0).unwrap();
+/// processor.xy(0.0, 0.0, 0).unwrap();
+/// processor.xy(1.0, 1.0, 1).unwrap();
+/// assert!(processor.xy(2.0, 2.0, 2).is_err());
+/// ```
+pub struct ResourceLimiter<P> {
+    inner: P,
+    limits: ResourceLimits,
+    /// Index of current feature
+    feature: Option<u64>,
+    /// Nesting depth of current geometry part
+    depth: usize,
+    /// Vertices of current geometry
+    vertices: usize,
+    /// Properties of current feature
+    properties: usize,
+    /// Estimated size of current feature
+    feature_size: usize,
+}
+
+impl<P> ResourceLimiter<P> {
+    pub fn new(inner: P, limits: ResourceLimits) -> Self {
+        ResourceLimiter {
+            inner,
+            limits,
+            feature: None,
+            depth: 0,
+            vertices: 0,
+            properties: 0,
+            feature_size: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume limiter and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn error(&self, msg: String) -> GeozeroError {
+        match self.feature {
+            Some(idx) => GeozeroError::ResourceLimit(format!("feature {}: {}", idx, msg)),
+            None => GeozeroError::ResourceLimit(msg),
+        }
+    }
+    /// Begin of nested geometry part
+    fn enter(&mut self) -> Result<()> {
+        if self.depth == 0 {
+            self.vertices = 0;
+        }
+        self.depth += 1;
+        match self.limits.max_depth {
+            Some(max) if self.depth > max => {
+                Err(self.error(format!("geometry nesting depth exceeds {}", max)))
+            }
+            _ => Ok(()),
+        }
+    }
+    fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+    /// Vertex with `size` bytes of coordinate values
+    fn vertex(&mut self, size: usize) -> Result<()> {
+        self.vertices += 1;
+        match self.limits.max_vertices {
+            Some(max) if self.vertices > max => {
+                Err(self.error(format!("more than {} vertices in geometry", max)))
+            }
+            _ => self.add_size(size),
+        }
+    }
+    fn add_size(&mut self, size: usize) -> Result<()> {
+        self.feature_size += size;
+        match self.limits.max_feature_size {
+            Some(max) if self.feature.is_some() && self.feature_size > max => {
+                Err(self.error(format!("feature size exceeds {} bytes", max)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Estimated size of a property value in bytes
+fn value_size(value: &ColumnValue) -> usize {
+    match value {
+        ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v)
+        | ColumnValue::BigInt(v) => v.len(),
+        ColumnValue::Binary(v) => v.len(),
+        _ => size_of::<f64>(),
+    }
+}
+
+/// Count nesting depth of geometry parts before forwarding
+macro_rules! nested_geometry_methods {
+    ($($begin:ident($($arg:ident: $t:ty),*), $end:ident($($end_arg:ident: $end_t:ty),*);)*) => {
+        $(
+            fn $begin(&mut self, $($arg: $t),*) -> Result<()> {
+                self.enter()?;
+                self.inner.$begin($($arg),*)
+            }
+            fn $end(&mut self, $($end_arg: $end_t),*) -> Result<()> {
+                self.leave();
+                self.inner.$end($($end_arg),*)
+            }
+        )*
+    };
+}
+
+impl<P: GeomProcessor> GeomProcessor for ResourceLimiter<P> {
+    forward_processor_methods!(inner; dimensions, srid);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.vertex(2 * size_of::<f64>())?;
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let values = 2 + [z, m, t].iter().flatten().count() + tm.iter().count();
+        self.vertex(values * size_of::<f64>())?;
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+    fn empty_point(&mut self, idx: usize) -> Result<()> {
+        self.enter()?;
+        self.leave();
+        self.inner.empty_point(idx)
+    }
+    nested_geometry_methods! {
+        point_begin(idx: usize), point_end(idx: usize);
+        multipoint_begin(size: usize, idx: usize), multipoint_end(idx: usize);
+        linestring_begin(tagged: bool, size: usize, idx: usize), linestring_end(tagged: bool, idx: usize);
+        multilinestring_begin(size: usize, idx: usize), multilinestring_end(idx: usize);
+        polygon_begin(tagged: bool, size: usize, idx: usize), polygon_end(tagged: bool, idx: usize);
+        multipolygon_begin(size: usize, idx: usize), multipolygon_end(idx: usize);
+        geometrycollection_begin(size: usize, idx: usize), geometrycollection_end(idx: usize);
+        circularstring_begin(size: usize, idx: usize), circularstring_end(idx: usize);
+        compoundcurve_begin(size: usize, idx: usize), compoundcurve_end(idx: usize);
+        curvepolygon_begin(size: usize, idx: usize), curvepolygon_end(idx: usize);
+        multicurve_begin(size: usize, idx: usize), multicurve_end(idx: usize);
+        multisurface_begin(size: usize, idx: usize), multisurface_end(idx: usize);
+        triangle_begin(tagged: bool, size: usize, idx: usize), triangle_end(tagged: bool, idx: usize);
+        polyhedralsurface_begin(size: usize, idx: usize), polyhedralsurface_end(idx: usize);
+        tin_begin(size: usize, idx: usize), tin_end(idx: usize);
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for ResourceLimiter<P> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.properties += 1;
+        if let Some(max) = self.limits.max_properties {
+            if self.properties > max {
+                return Err(self.error(format!("more than {} properties", max)));
+            }
+        }
+        let size = value_size(value);
+        if let Some(max) = self.limits.max_property_size {
+            if size > max {
+                return Err(self.error(format!("property `{}` exceeds {} bytes", name, max)));
+            }
+        }
+        self.add_size(name.len() + size)?;
+        self.inner.property(idx, name, value)
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ResourceLimiter<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature = Some(idx);
+        self.properties = 0;
+        self.feature_size = 0;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.feature = None;
+        self.inner.feature_end(idx)
+    }
+}
+
+/// Read complete input into a string, failing as soon as it exceeds `max_input_size`
+pub(crate) fn read_input<R: Read>(mut reader: R, limits: &ResourceLimits) -> Result<String> {
+    let mut input = String::new();
+    match limits.max_input_size {
+        Some(max) => {
+            reader.take(max as u64 + 1).read_to_string(&mut input)?;
+            if input.len() > max {
+                return Err(GeozeroError::ResourceLimit(format!(
+                    "input size exceeds {} bytes",
+                    max
+                )));
+            }
+        }
+        None => {
+            reader.read_to_string(&mut input)?;
+        }
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::read_geojson_with_options;
+    use crate::{ProcessorSink, ReadOptions};
+
+    const GEOJSON: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "properties": {"name": "Bern", "population": 134591},
+         "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+        {"type": "Feature", "properties": {"name": "Aare"},
+         "geometry": {"type": "GeometryCollection", "geometries": [
+            {"type": "LineString", "coordinates": [[8.0, 47.5], [7.44, 46.95], [7.2, 46.7]]}]}}]}"#;
+
+    fn read(limits: ResourceLimits) -> Result<()> {
+        let options = ReadOptions {
+            limits,
+            ..Default::default()
+        };
+        read_geojson_with_options(GEOJSON.as_bytes(), &mut ProcessorSink::new(), &options)
+    }
+
+    fn error(limits: ResourceLimits) -> String {
+        read(limits).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn limits() -> Result<()> {
+        read(ResourceLimits::default())?;
+        read(ResourceLimits::untrusted())?;
+        read(ResourceLimits {
+            max_depth: Some(2),
+            max_vertices: Some(3),
+            max_properties: Some(2),
+            max_property_size: Some(8),
+            max_feature_size: Some(64),
+            max_input_size: Some(GEOJSON.len()),
+        })?;
+
+        assert_eq!(
+            error(ResourceLimits {
+                max_depth: Some(1),
+                ..Default::default()
+            }),
+            "resource limit exceeded: feature 1: geometry nesting depth exceeds 1"
+        );
+        assert_eq!(
+            error(ResourceLimits {
+                max_vertices: Some(2),
+                ..Default::default()
+            }),
+            "resource limit exceeded: feature 1: more than 2 vertices in geometry"
+        );
+        assert_eq!(
+            error(ResourceLimits {
+                max_properties: Some(1),
+                ..Default::default()
+            }),
+            "resource limit exceeded: feature 0: more than 1 properties"
+        );
+        assert_eq!(
+            error(ResourceLimits {
+                max_property_size: Some(3),
+                ..Default::default()
+            }),
+            "resource limit exceeded: feature 0: property `name` exceeds 3 bytes"
+        );
+        assert_eq!(
+            error(ResourceLimits {
+                max_feature_size: Some(32),
+                ..Default::default()
+            }),
+            "resource limit exceeded: feature 0: feature size exceeds 32 bytes"
+        );
+        assert_eq!(
+            error(ResourceLimits {
+                max_input_size: Some(64),
+                ..Default::default()
+            }),
+            "resource limit exceeded: input size exceeds 64 bytes"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-wkt")]
+    fn default_datasource() {
+        use crate::wkt::WktReader;
+        use crate::GeozeroDatasource;
+
+        let options = ReadOptions {
+            limits: ResourceLimits {
+                max_depth: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut wkt = "GEOMETRYCOLLECTION(GEOMETRYCOLLECTION(POINT(1 1)))".as_bytes();
+        assert!(WktReader(&mut wkt)
+            .process_with_options(&mut ProcessorSink::new(), &options)
+            .is_err());
+
+        let options = ReadOptions {
+            limits: ResourceLimits {
+                max_input_size: Some(8),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut wkt = "POINT(1 1)".as_bytes();
+        let err = WktReader(&mut wkt)
+            .process_with_options(&mut ProcessorSink::new(), &options)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "resource limit exceeded: input size exceeds 8 bytes"
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "with-tokio")]
+    async fn async_geojson() {
+        use crate::geojson::AsyncGeoJsonReader;
+        use crate::AsyncGeozeroDatasource;
+
+        let options = ReadOptions {
+            limits: ResourceLimits {
+                max_input_size: Some(GEOJSON.len()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(AsyncGeoJsonReader(GEOJSON.as_bytes())
+            .process_with_options(&mut ProcessorSink::new(), &options)
+            .await
+            .is_ok());
+
+        let options = ReadOptions {
+            limits: ResourceLimits {
+                max_input_size: Some(64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = AsyncGeoJsonReader(GEOJSON.as_bytes())
+            .process_with_options(&mut ProcessorSink::new(), &options)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "resource limit exceeded: input size exceeds 64 bytes"
+        );
+
+        let options = ReadOptions {
+            limits: ResourceLimits {
+                max_vertices: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let err = AsyncGeoJsonReader(GEOJSON.as_bytes())
+            .process_with_options(&mut ProcessorSink::new(), &options)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "resource limit exceeded: feature 1: more than 2 vertices in geometry"
+        );
+    }
+}
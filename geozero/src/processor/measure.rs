@@ -0,0 +1,494 @@
+use crate::error::Result;
+use crate::processor::{replay_with_property, schema_with_column, Event, FeatureBuffer};
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, ProcessorSink,
+    PropertyProcessor,
+};
+use geo::{Area, Coord, EuclideanLength, LineString, Polygon};
+use geographiclib_rs::{Geodesic, InverseGeodesic, PolygonArea, Winding};
+
+/// Measure of a geometry
+///
+/// Planar measures are in units of the CRS, geodesic measures on the WGS84 ellipsoid
+/// in m and m² for lon/lat coordinates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Measure {
+    /// Planar area of polygons and triangles, with holes subtracted
+    Area,
+    /// Planar length of linestrings and perimeter of polygon rings
+    Length,
+    /// Geodesic area of polygons and triangles, with holes subtracted
+    GeodesicArea,
+    /// Geodesic length of linestrings and perimeter of polygon rings
+    GeodesicLength,
+}
+
+impl Measure {
+    fn is_length(&self) -> bool {
+        matches!(self, Measure::Length | Measure::GeodesicLength)
+    }
+}
+
+/// Geodesic length of `line` in m
+fn geodesic_length(line: &[Coord<f64>]) -> f64 {
+    let geod = Geodesic::wgs84();
+    line.windows(2)
+        .map(|s| {
+            let s12: f64 = geod.inverse(s[0].y, s[0].x, s[1].y, s[1].x);
+            s12
+        })
+        .sum()
+}
+
+/// Geodesic area of `ring` in m²
+fn geodesic_area(ring: &[Coord<f64>]) -> f64 {
+    let geod = Geodesic::wgs84();
+    let mut polygon = PolygonArea::new(&geod, Winding::CounterClockwise);
+    for coord in ring {
+        polygon.add_point(coord.y, coord.x);
+    }
+    // Signed result avoids the area of the rest of the earth for clockwise rings
+    let (_perimeter, area, _count) = polygon.compute(true);
+    area.abs()
+}
+
+/// Accumulates the measure of a feature ring by ring
+struct MeasureCalc {
+    measure: Measure,
+    /// Nesting level of polygons and triangles
+    surface_depth: usize,
+    /// Ring index within current polygon, `None` for linestrings
+    ring: Option<usize>,
+    collecting: bool,
+    coords: Vec<Coord<f64>>,
+    value: f64,
+}
+
+impl MeasureCalc {
+    fn new(measure: Measure) -> Self {
+        MeasureCalc {
+            measure,
+            surface_depth: 0,
+            ring: None,
+            collecting: false,
+            coords: Vec::new(),
+            value: 0.0,
+        }
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        if self.collecting {
+            self.coords.push(Coord { x, y });
+        }
+    }
+    fn line_begin(&mut self, size: usize, idx: usize) {
+        self.ring = if self.surface_depth > 0 {
+            Some(idx)
+        } else {
+            None
+        };
+        self.collecting = self.measure.is_length() || self.ring.is_some();
+        self.coords.clear();
+        self.coords.reserve(size);
+    }
+    fn line_end(&mut self) {
+        if !self.collecting {
+            return;
+        }
+        self.collecting = false;
+        let line = std::mem::take(&mut self.coords);
+        let value = match self.measure {
+            Measure::Length => LineString::new(line).euclidean_length(),
+            Measure::GeodesicLength => geodesic_length(&line),
+            Measure::Area if self.ring.is_some() => {
+                Polygon::new(LineString::new(line), vec![]).unsigned_area()
+            }
+            Measure::GeodesicArea if self.ring.is_some() => geodesic_area(&line),
+            Measure::Area | Measure::GeodesicArea => 0.0,
+        };
+        // Holes are subtracted from the area
+        if self.ring.unwrap_or(0) > 0 && !self.measure.is_length() {
+            self.value -= value;
+        } else {
+            self.value += value;
+        }
+    }
+    /// Return measure of processed geometries and reset
+    fn take(&mut self) -> f64 {
+        self.surface_depth = 0;
+        self.collecting = false;
+        std::mem::take(&mut self.value)
+    }
+}
+
+/// GeomProcessor implementation passing coordinates and rings to `self.calc`
+/// before forwarding calls to the wrapped processor `$inner`
+macro_rules! measure_geom_impls {
+    ($processor:ident, $inner:ident) => {
+        impl<P: GeomProcessor> GeomProcessor for $processor<P> {
+            forward_processor_methods!($inner; dimensions, srid, empty_point, point, multipoint,
+                multilinestring, multipolygon, geometrycollection, curves, polyhedralsurface, tin);
+
+            fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+                self.calc.add_vertex(x, y);
+                self.$inner.xy(x, y, idx)
+            }
+            fn coordinate(
+                &mut self,
+                x: f64,
+                y: f64,
+                z: Option<f64>,
+                m: Option<f64>,
+                t: Option<f64>,
+                tm: Option<u64>,
+                idx: usize,
+            ) -> Result<()> {
+                self.calc.add_vertex(x, y);
+                self.$inner.coordinate(x, y, z, m, t, tm, idx)
+            }
+            fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.line_begin(size, idx);
+                self.$inner.linestring_begin(tagged, size, idx)
+            }
+            fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.line_end();
+                self.$inner.linestring_end(tagged, idx)
+            }
+            fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.surface_depth += 1;
+                self.$inner.polygon_begin(tagged, size, idx)
+            }
+            fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.surface_depth -= 1;
+                self.$inner.polygon_end(tagged, idx)
+            }
+            fn triangle_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+                self.calc.surface_depth += 1;
+                self.$inner.triangle_begin(tagged, size, idx)
+            }
+            fn triangle_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+                self.calc.surface_depth -= 1;
+                self.$inner.triangle_end(tagged, idx)
+            }
+        }
+    };
+}
+
+/// Adds the area or length of each feature as property.
+///
+/// Measures are computed ring by ring, planar with the [geo](https://docs.rs/geo) algorithms
+/// and geodesic with [geographiclib-rs](https://docs.rs/geographiclib-rs), multiplied by
+/// [scale](MeasureProperty::scale).
+/// Each feature is buffered until its geometry is complete. The property is
+/// appended to the feature properties and the schema, features without
+/// geometry get a value of 0. Curves are not measured.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::{Measure, MeasureProperty};
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "field"},
+///     "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [2000, 0], [2000, 1000], [0, 1000], [0, 0]]]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = MeasureProperty::new(GeoJsonWriter::new(&mut out), Measure::Area, "area_km2").scale(1e-6);
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// assert!(std::str::from_utf8(&out).unwrap().contains(r#""area_km2": 2}"#));
+/// ```
+pub struct MeasureProperty<P> {
+    buffer: FeatureBuffer<P>,
+    calc: MeasureCalc,
+    name: String,
+    scale: f64,
+}
+
+impl<P> MeasureProperty<P> {
+    pub fn new(inner: P, measure: Measure, name: &str) -> Self {
+        MeasureProperty {
+            buffer: FeatureBuffer::new(inner),
+            calc: MeasureCalc::new(measure),
+            name: name.to_string(),
+            scale: 1.0,
+        }
+    }
+    /// Factor applied to measures, e.g. `1e-6` for km² of metric coordinates (default: 1)
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> MeasureProperty<P> {
+    /// Pass buffered feature on with measure property
+    fn replay(&mut self, events: Vec<Event>, value: f64) -> Result<()> {
+        let value = ColumnValue::Double(value);
+        replay_with_property(&events, self.buffer.inner_mut(), &self.name, &value)
+    }
+}
+
+measure_geom_impls!(MeasureProperty, buffer);
+
+impl<P: FeatureProcessor> PropertyProcessor for MeasureProperty<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for MeasureProperty<P> {
+    forward_processor_methods!(buffer; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let mut column = ColumnDef::new(&self.name, ColumnType::Double);
+        column.nullable = false;
+        self.buffer.schema(&schema_with_column(columns, column))
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.calc.take();
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let value = self.calc.take() * self.scale;
+        self.replay(events, value)
+    }
+}
+
+/// Collects area or length statistics of features while streaming.
+///
+/// Measures are computed like in [MeasureProperty]. Created with `MeasureStats::new(measure)`
+/// it is used as a sink, created with `MeasureStats::tee(processor, measure)` all calls are
+/// passed to the wrapped processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::{Measure, MeasureStats};
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[0, 0], [3, 4]]}},
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[0, 0], [0, 1], [1, 1]]}}
+/// ]}"#;
+/// let mut stats = MeasureStats::new(Measure::Length);
+/// GeoJson(geojson).process(&mut stats).unwrap();
+/// assert_eq!(stats.total(), 7.0);
+/// assert_eq!(stats.max(), Some(5.0));
+/// ```
+pub struct MeasureStats<P = ProcessorSink> {
+    inner: P,
+    calc: MeasureCalc,
+    scale: f64,
+    feature_count: u64,
+    total: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl MeasureStats<ProcessorSink> {
+    pub fn new(measure: Measure) -> Self {
+        Self::tee(ProcessorSink::new(), measure)
+    }
+}
+
+impl<P> MeasureStats<P> {
+    /// Pass-through measure statistics
+    pub fn tee(inner: P, measure: Measure) -> Self {
+        MeasureStats {
+            inner,
+            calc: MeasureCalc::new(measure),
+            scale: 1.0,
+            feature_count: 0,
+            total: 0.0,
+            min: None,
+            max: None,
+        }
+    }
+    /// Factor applied to measures, e.g. `1e-3` for km of metric coordinates (default: 1)
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+    /// Number of measured features
+    pub fn feature_count(&self) -> u64 {
+        self.feature_count
+    }
+    /// Sum of feature measures
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+    /// Smallest feature measure
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+    /// Largest feature measure
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+    /// Mean feature measure
+    pub fn mean(&self) -> Option<f64> {
+        if self.feature_count > 0 {
+            Some(self.total / self.feature_count as f64)
+        } else {
+            None
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+measure_geom_impls!(MeasureStats, inner);
+
+impl<P: PropertyProcessor> PropertyProcessor for MeasureStats<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for MeasureStats<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.calc.take();
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let value = self.calc.take() * self.scale;
+        self.feature_count += 1;
+        self.total += value;
+        self.min = Some(self.min.map_or(value, |min| min.min(value)));
+        self.max = Some(self.max.map_or(value, |max| max.max(value)));
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-wkt")]
+mod test {
+    use super::*;
+    use crate::wkt::WktStr;
+    use crate::FeatureAccess;
+
+    struct WktFeature(&'static str);
+
+    impl crate::FeatureProperties for WktFeature {
+        fn process_properties<P: PropertyProcessor>(&self, _processor: &mut P) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    impl crate::GeozeroGeometry for WktFeature {
+        fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+            WktStr(self.0).process_geom(processor)
+        }
+    }
+
+    impl FeatureAccess for WktFeature {}
+
+    fn measure(wkt: &'static str, measure: Measure) -> Result<f64> {
+        let mut stats = MeasureStats::new(measure);
+        WktFeature(wkt).process(&mut stats, 0)?;
+        Ok(stats.total())
+    }
+
+    #[test]
+    fn areas() -> Result<()> {
+        assert_eq!(
+            measure("POLYGON((0 0,4 0,4 4,0 4,0 0))", Measure::Area)?,
+            16.0
+        );
+        assert_eq!(
+            measure(
+                "POLYGON((0 0,4 0,4 4,0 4,0 0),(1 1,2 1,2 2,1 2,1 1))",
+                Measure::Area
+            )?,
+            15.0
+        );
+        assert_eq!(
+            measure(
+                "MULTIPOLYGON(((0 0,1 0,1 1,0 1,0 0)),((5 5,7 5,7 7,5 7,5 5)))",
+                Measure::Area
+            )?,
+            5.0
+        );
+        assert_eq!(measure("LINESTRING(0 0,10 0)", Measure::Area)?, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn lengths() -> Result<()> {
+        assert_eq!(measure("LINESTRING(0 0,3 4,3 10)", Measure::Length)?, 11.0);
+        assert_eq!(
+            measure("MULTILINESTRING((0 0,1 0),(0 0,0 2))", Measure::Length)?,
+            3.0
+        );
+        assert_eq!(
+            measure("POLYGON((0 0,4 0,4 4,0 4,0 0))", Measure::Length)?,
+            16.0
+        );
+        assert_eq!(measure("POINT(1 1)", Measure::Length)?, 0.0);
+        Ok(())
+    }
+
+    fn assert_relative(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 0.01,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn geodesic() -> Result<()> {
+        // One degree along the equator
+        let length = measure("LINESTRING(0 0,1 0)", Measure::GeodesicLength)?;
+        assert_relative(length, 111_319.49);
+        let area = measure("POLYGON((0 0,1 0,1 1,0 1,0 0))", Measure::GeodesicArea)?;
+        assert_relative(area, 12_308_778_361.0);
+        // Clockwise ring
+        let area = measure("POLYGON((0 0,0 1,1 1,1 0,0 0))", Measure::GeodesicArea)?;
+        assert_relative(area, 12_308_778_361.0);
+        let area = measure(
+            "POLYGON((-10 -10,10 -10,10 10,-10 10,-10 -10),(-1 -1,1 -1,1 1,-1 1,-1 -1))",
+            Measure::GeodesicArea,
+        )?;
+        // 20°x20° square minus 2°x2° hole around the equator
+        assert_relative(area, 4_948_480_469_170.0 - 49_238_887_519.0);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn measure_property() -> Result<()> {
+        use crate::geojson::{GeoJson, GeoJsonWriter};
+        use crate::GeozeroDatasource;
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "a"}, "geometry": {"type": "LineString", "coordinates": [[0, 0], [0, 2]]}},
+            {"type": "Feature", "properties": {}, "geometry": null}
+        ]}"#;
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor =
+            MeasureProperty::new(GeoJsonWriter::new(&mut out), Measure::Length, "len");
+        GeoJson(geojson).process(&mut processor)?;
+        let json = std::str::from_utf8(&out).unwrap();
+        assert!(json.contains(r#""name": "a", "len": 2}"#), "{}", json);
+        assert!(json.contains(r#""len": 0}"#), "{}", json);
+        Ok(())
+    }
+}
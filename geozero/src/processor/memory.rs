@@ -0,0 +1,85 @@
+use crate::processor::Event;
+use crate::OwnedColumnValue;
+use std::mem::size_of_val;
+
+/// Memory usage of a buffering processor.
+///
+/// Sizes are estimates of the buffered data, excluding allocator overhead.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct MemoryStats {
+    /// Bytes currently buffered in memory
+    pub buffered: usize,
+    /// Maximum of buffered bytes
+    pub peak: usize,
+    /// Bytes moved to temporary files
+    pub spilled: u64,
+}
+
+/// Callback receiving memory statistics of a buffering processor
+pub type MemoryCallback = Box<dyn FnMut(&MemoryStats)>;
+
+/// Memory accounting of a buffering processor
+#[derive(Default)]
+pub(crate) struct MemoryAccount {
+    stats: MemoryStats,
+    callback: Option<MemoryCallback>,
+}
+
+impl MemoryAccount {
+    pub fn set_callback(&mut self, callback: MemoryCallback) {
+        self.callback = Some(callback);
+    }
+    pub fn stats(&self) -> MemoryStats {
+        self.stats
+    }
+    pub fn buffered(&self) -> usize {
+        self.stats.buffered
+    }
+    /// Account `bytes` buffered in addition
+    pub fn add(&mut self, bytes: usize) {
+        self.set(self.stats.buffered + bytes);
+    }
+    /// Account `bytes` released from the buffer
+    pub fn release(&mut self, bytes: usize) {
+        self.stats.buffered = self.stats.buffered.saturating_sub(bytes);
+    }
+    /// Set currently buffered bytes
+    pub fn set(&mut self, bytes: usize) {
+        self.stats.buffered = bytes;
+        self.stats.peak = self.stats.peak.max(bytes);
+    }
+    /// Account `bytes` moved from memory to a temporary file
+    pub fn spill(&mut self, bytes: usize, file_bytes: usize) {
+        self.release(bytes);
+        self.stats.spilled += file_bytes as u64;
+    }
+    /// Pass current statistics to the callback
+    pub fn report(&mut self) {
+        if let Some(callback) = &mut self.callback {
+            callback(&self.stats);
+        }
+    }
+}
+
+/// Estimated size of recorded events including property values
+pub(crate) fn events_size(events: &[Event]) -> usize {
+    let values: usize = events
+        .iter()
+        .map(|event| match event {
+            Event::Property(_, name, value) => {
+                name.capacity()
+                    + match value {
+                        OwnedColumnValue::String(v)
+                        | OwnedColumnValue::Json(v)
+                        | OwnedColumnValue::DateTime(v)
+                        | OwnedColumnValue::Date(v)
+                        | OwnedColumnValue::BigInt(v) => v.capacity(),
+                        OwnedColumnValue::Binary(v) => v.capacity(),
+                        _ => 0,
+                    }
+            }
+            _ => 0,
+        })
+        .sum();
+    size_of_val(events) + values
+}
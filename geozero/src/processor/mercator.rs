@@ -0,0 +1,302 @@
+use crate::error::Result;
+use crate::{Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::f64::consts::PI;
+
+/// Earth radius of the Web Mercator sphere in meters
+const EARTH_RADIUS: f64 = 6378137.0;
+/// Half the circumference of the Web Mercator sphere in meters
+const HALF_EXTENT: f64 = PI * EARTH_RADIUS;
+/// Maximal latitude of the square Web Mercator extent
+pub const MAX_MERCATOR_LAT: f64 = 85.051_128_779_806_59;
+
+/// Convert WGS84 lon/lat to Web Mercator (`EPSG:3857`) meters.
+///
+/// Latitudes are clamped to ±[MAX_MERCATOR_LAT].
+pub fn lonlat_to_mercator(lon: f64, lat: f64) -> (f64, f64) {
+    let lat = lat.clamp(-MAX_MERCATOR_LAT, MAX_MERCATOR_LAT);
+    let x = lon.to_radians() * EARTH_RADIUS;
+    let y = (PI / 4.0 + lat.to_radians() / 2.0).tan().ln() * EARTH_RADIUS;
+    (x, y)
+}
+
+/// Convert Web Mercator (`EPSG:3857`) meters to WGS84 lon/lat.
+pub fn mercator_to_lonlat(x: f64, y: f64) -> (f64, f64) {
+    let lon = (x / EARTH_RADIUS).to_degrees();
+    let lat = (2.0 * (y / EARTH_RADIUS).exp().atan() - PI / 2.0).to_degrees();
+    (lon, lat)
+}
+
+/// Transformation direction
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    /// From lon/lat
+    Forward,
+    /// To lon/lat
+    Inverse,
+}
+
+/// Converts between WGS84 lon/lat and Web Mercator (`EPSG:3857`) meters
+/// without PROJ.
+///
+/// The CRS and SRID of the source are replaced by the target CRS.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::WebMercator;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = WebMercator::new(WktWriter::new(&mut out));
+/// WktStr("POINT(10 0)").process_geom(&mut processor).unwrap();
+/// assert!(std::str::from_utf8(&out).unwrap().starts_with("POINT(1113194.9"));
+/// ```
+pub struct WebMercator<P> {
+    inner: P,
+    direction: Direction,
+}
+
+impl<P> WebMercator<P> {
+    /// Conversion from lon/lat to Web Mercator
+    pub fn new(inner: P) -> Self {
+        WebMercator {
+            inner,
+            direction: Direction::Forward,
+        }
+    }
+    /// Conversion from Web Mercator to lon/lat
+    pub fn inverse(inner: P) -> Self {
+        WebMercator {
+            inner,
+            direction: Direction::Inverse,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn target_crs(&self) -> Option<Crs> {
+        match self.direction {
+            Direction::Forward => Some(Crs::Epsg(3857)),
+            Direction::Inverse => Some(Crs::wgs84()),
+        }
+    }
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        match self.direction {
+            Direction::Forward => lonlat_to_mercator(x, y),
+            Direction::Inverse => mercator_to_lonlat(x, y),
+        }
+    }
+}
+
+/// Converts between WGS84 lon/lat and pixel coordinates of a Web Mercator
+/// tile pyramid without PROJ.
+///
+/// Pixel coordinates have their origin at the top left corner of the world at the
+/// given zoom level, or of the tile selected with [tile](TilePixels::tile),
+/// with Y pointing down.
+/// Pixel coordinates have no CRS, the CRS and SRID of the source are dropped.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::TilePixels;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = TilePixels::new(WktWriter::new(&mut out), 1);
+/// WktStr("POINT(0 0)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(256 256)");
+/// ```
+pub struct TilePixels<P> {
+    inner: P,
+    direction: Direction,
+    zoom: u8,
+    tile_size: u32,
+    /// Tile column and row of pixel origin
+    tile: (u32, u32),
+}
+
+impl<P> TilePixels<P> {
+    /// Conversion from lon/lat to pixels at `zoom`
+    pub fn new(inner: P, zoom: u8) -> Self {
+        TilePixels {
+            inner,
+            direction: Direction::Forward,
+            zoom,
+            tile_size: 256,
+            tile: (0, 0),
+        }
+    }
+    /// Conversion from pixels at `zoom` to lon/lat
+    pub fn inverse(inner: P, zoom: u8) -> Self {
+        TilePixels {
+            direction: Direction::Inverse,
+            ..Self::new(inner, zoom)
+        }
+    }
+    /// Tile size in pixels (default: 256)
+    pub fn tile_size(mut self, size: u32) -> Self {
+        self.tile_size = size;
+        self
+    }
+    /// Pixel coordinates relative to tile `x`/`y`
+    pub fn tile(mut self, x: u32, y: u32) -> Self {
+        self.tile = (x, y);
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    /// World size in pixels and pixel offset of tile origin
+    fn world_size_and_offset(&self) -> (f64, (f64, f64)) {
+        let size = f64::from(self.tile_size);
+        let world_size = size * 2f64.powi(i32::from(self.zoom));
+        let offset = (f64::from(self.tile.0) * size, f64::from(self.tile.1) * size);
+        (world_size, offset)
+    }
+    fn target_crs(&self) -> Option<Crs> {
+        match self.direction {
+            Direction::Forward => None,
+            Direction::Inverse => Some(Crs::wgs84()),
+        }
+    }
+    fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let (world_size, (dx, dy)) = self.world_size_and_offset();
+        let extent = 2.0 * HALF_EXTENT;
+        match self.direction {
+            Direction::Forward => {
+                let (x, y) = lonlat_to_mercator(x, y);
+                (
+                    (x / extent + 0.5) * world_size - dx,
+                    (0.5 - y / extent) * world_size - dy,
+                )
+            }
+            Direction::Inverse => mercator_to_lonlat(
+                ((x + dx) / world_size - 0.5) * extent,
+                (0.5 - (y + dy) / world_size) * extent,
+            ),
+        }
+    }
+}
+
+/// Processor trait implementations for XY converting processors with `apply` and `target_crs`
+macro_rules! convert_xy_impls {
+    ($processor:ident) => {
+        impl<P: GeomProcessor> GeomProcessor for $processor<P> {
+            forward_processor_methods!(inner; dimensions, empty_point, geometries);
+
+            fn srid(&mut self, _srid: Option<i32>) -> Result<()> {
+                self.inner.srid(self.target_crs().as_ref().and_then(Crs::srid))
+            }
+            fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+                let (x, y) = self.apply(x, y);
+                self.inner.xy(x, y, idx)
+            }
+            fn coordinate(
+                &mut self,
+                x: f64,
+                y: f64,
+                z: Option<f64>,
+                m: Option<f64>,
+                t: Option<f64>,
+                tm: Option<u64>,
+                idx: usize,
+            ) -> Result<()> {
+                let (x, y) = self.apply(x, y);
+                self.inner.coordinate(x, y, z, m, t, tm, idx)
+            }
+        }
+
+        impl<P: PropertyProcessor> PropertyProcessor for $processor<P> {
+            forward_processor_methods!(inner; property);
+        }
+
+        impl<P: FeatureProcessor> FeatureProcessor for $processor<P> {
+            forward_processor_methods!(inner; dataset, schema, warning, feature, properties,
+                geometry);
+
+            fn crs(&mut self, _crs: &Crs) -> Result<()> {
+                // Source CRS is replaced by the target CRS
+                match self.target_crs() {
+                    Some(target) => self.inner.crs(&target),
+                    None => Ok(()),
+                }
+            }
+            fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+                let mut metadata = metadata.clone();
+                metadata.extent = metadata.extent.map(|e| e.map_corners(|x, y| self.apply(x, y)));
+                self.inner.metadata(&metadata)
+            }
+        }
+    };
+}
+
+convert_xy_impls!(WebMercator);
+convert_xy_impls!(TilePixels);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64), eps: f64) {
+        assert!(
+            (actual.0 - expected.0).abs() < eps && (actual.1 - expected.1).abs() < eps,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn mercator() {
+        let (x, y) = lonlat_to_mercator(180.0, MAX_MERCATOR_LAT);
+        assert_close((x, y), (HALF_EXTENT, HALF_EXTENT), 1e-6);
+        assert_close(lonlat_to_mercator(7.44, 90.0), (x / 180.0 * 7.44, y), 1e-6);
+        assert_close(
+            mercator_to_lonlat(
+                lonlat_to_mercator(7.44, 46.95).0,
+                lonlat_to_mercator(7.44, 46.95).1,
+            ),
+            (7.44, 46.95),
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn tile_pixels() {
+        assert_eq!(TilePixels::new((), 1).apply(0.0, 0.0), (256.0, 256.0));
+        let pixels = TilePixels::new((), 2).tile(2, 1);
+        assert_close(pixels.apply(0.0, 0.0), (0.0, 256.0), 1e-9);
+        assert_close(
+            pixels.apply(-180.0, MAX_MERCATOR_LAT),
+            (-512.0, -256.0),
+            1e-6,
+        );
+        let inverse = TilePixels::inverse((), 2).tile(2, 1);
+        assert_close(
+            inverse.apply(100.0, 50.0),
+            pixels_to_lonlat(612.0, 306.0, 2),
+            1e-9,
+        );
+    }
+
+    /// Pixel to lon/lat conversion following the OSM slippy map formula
+    fn pixels_to_lonlat(px: f64, py: f64, zoom: i32) -> (f64, f64) {
+        let n = 256.0 * 2f64.powi(zoom);
+        let lon = px / n * 360.0 - 180.0;
+        let lat = (PI * (1.0 - 2.0 * py / n)).sinh().atan().to_degrees();
+        (lon, lat)
+    }
+}
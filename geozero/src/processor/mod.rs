@@ -0,0 +1,403 @@
+//! Processors wrapping and modifying the stream passed to another processor.
+//!
+//! Wrapping processors implement all processing traits, which are implemented by the
+//! wrapped processor. Calls not affected by the wrapper are forwarded unchanged.
+
+/// Forward processor methods to the wrapped processor `self.$inner`.
+///
+/// Methods are selected by name or group name, e.g.
+/// `forward_processor_methods!(inner; dimensions, srid, geometries)`.
+macro_rules! forward_processor_methods {
+    ($inner:ident; $($group:ident),* $(,)?) => {
+        $( forward_processor_methods!(@ $inner, $group); )*
+    };
+    // Groups
+    (@ $inner:ident, geometries) => {
+        forward_processor_methods!($inner; point, multipoint, linestring, multilinestring,
+            polygon, multipolygon, geometrycollection, curves, surfaces);
+    };
+    (@ $inner:ident, curves) => {
+        forward_processor_methods!($inner; circularstring, compoundcurve, curvepolygon,
+            multicurve, multisurface);
+    };
+    (@ $inner:ident, surfaces) => {
+        forward_processor_methods!($inner; triangle, polyhedralsurface, tin);
+    };
+    (@ $inner:ident, feature_processor) => {
+        forward_processor_methods!($inner; dataset, schema, crs, metadata, warning, feature,
+            properties, geometry);
+    };
+    // GeomProcessor
+    (@ $inner:ident, dimensions) => {
+        fn dimensions(&self) -> $crate::CoordDimensions {
+            self.$inner.dimensions()
+        }
+        fn multi_dim(&self) -> bool {
+            self.$inner.multi_dim()
+        }
+    };
+    (@ $inner:ident, srid) => {
+        fn srid(&mut self, srid: Option<i32>) -> $crate::error::Result<()> {
+            self.$inner.srid(srid)
+        }
+    };
+    (@ $inner:ident, xy) => {
+        fn xy(&mut self, x: f64, y: f64, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.xy(x, y, idx)
+        }
+    };
+    (@ $inner:ident, coordinate) => {
+        fn coordinate(
+            &mut self,
+            x: f64,
+            y: f64,
+            z: Option<f64>,
+            m: Option<f64>,
+            t: Option<f64>,
+            tm: Option<u64>,
+            idx: usize,
+        ) -> $crate::error::Result<()> {
+            self.$inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    };
+    (@ $inner:ident, empty_point) => {
+        fn empty_point(&mut self, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.empty_point(idx)
+        }
+    };
+    (@ $inner:ident, point) => {
+        fn point_begin(&mut self, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.point_begin(idx)
+        }
+        fn point_end(&mut self, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.point_end(idx)
+        }
+    };
+    (@ $inner:ident, linestring) => {
+        fn linestring_begin(
+            &mut self,
+            tagged: bool,
+            size: usize,
+            idx: usize,
+        ) -> $crate::error::Result<()> {
+            self.$inner.linestring_begin(tagged, size, idx)
+        }
+        fn linestring_end(&mut self, tagged: bool, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.linestring_end(tagged, idx)
+        }
+    };
+    (@ $inner:ident, polygon) => {
+        fn polygon_begin(
+            &mut self,
+            tagged: bool,
+            size: usize,
+            idx: usize,
+        ) -> $crate::error::Result<()> {
+            self.$inner.polygon_begin(tagged, size, idx)
+        }
+        fn polygon_end(&mut self, tagged: bool, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.polygon_end(tagged, idx)
+        }
+    };
+    (@ $inner:ident, triangle) => {
+        fn triangle_begin(
+            &mut self,
+            tagged: bool,
+            size: usize,
+            idx: usize,
+        ) -> $crate::error::Result<()> {
+            self.$inner.triangle_begin(tagged, size, idx)
+        }
+        fn triangle_end(&mut self, tagged: bool, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.triangle_end(tagged, idx)
+        }
+    };
+    (@ $inner:ident, multipoint) => {
+        forward_processor_methods!(@sized $inner, multipoint_begin, multipoint_end);
+    };
+    (@ $inner:ident, multilinestring) => {
+        forward_processor_methods!(@sized $inner, multilinestring_begin, multilinestring_end);
+    };
+    (@ $inner:ident, multipolygon) => {
+        forward_processor_methods!(@sized $inner, multipolygon_begin, multipolygon_end);
+    };
+    (@ $inner:ident, geometrycollection) => {
+        forward_processor_methods!(@sized $inner, geometrycollection_begin, geometrycollection_end);
+    };
+    (@ $inner:ident, circularstring) => {
+        forward_processor_methods!(@sized $inner, circularstring_begin, circularstring_end);
+    };
+    (@ $inner:ident, compoundcurve) => {
+        forward_processor_methods!(@sized $inner, compoundcurve_begin, compoundcurve_end);
+    };
+    (@ $inner:ident, curvepolygon) => {
+        forward_processor_methods!(@sized $inner, curvepolygon_begin, curvepolygon_end);
+    };
+    (@ $inner:ident, multicurve) => {
+        forward_processor_methods!(@sized $inner, multicurve_begin, multicurve_end);
+    };
+    (@ $inner:ident, multisurface) => {
+        forward_processor_methods!(@sized $inner, multisurface_begin, multisurface_end);
+    };
+    (@ $inner:ident, polyhedralsurface) => {
+        forward_processor_methods!(@sized $inner, polyhedralsurface_begin, polyhedralsurface_end);
+    };
+    (@ $inner:ident, tin) => {
+        forward_processor_methods!(@sized $inner, tin_begin, tin_end);
+    };
+    (@sized $inner:ident, $begin:ident, $end:ident) => {
+        fn $begin(&mut self, size: usize, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.$begin(size, idx)
+        }
+        fn $end(&mut self, idx: usize) -> $crate::error::Result<()> {
+            self.$inner.$end(idx)
+        }
+    };
+    // PropertyProcessor
+    (@ $inner:ident, property) => {
+        fn property(
+            &mut self,
+            idx: usize,
+            name: &str,
+            value: &$crate::ColumnValue,
+        ) -> $crate::error::Result<bool> {
+            self.$inner.property(idx, name, value)
+        }
+    };
+    // FeatureProcessor
+    (@ $inner:ident, dataset) => {
+        fn dataset_begin(&mut self, name: Option<&str>) -> $crate::error::Result<()> {
+            self.$inner.dataset_begin(name)
+        }
+        fn dataset_end(&mut self) -> $crate::error::Result<()> {
+            self.$inner.dataset_end()
+        }
+    };
+    (@ $inner:ident, schema) => {
+        fn schema(&mut self, columns: &[$crate::ColumnDef]) -> $crate::error::Result<()> {
+            self.$inner.schema(columns)
+        }
+    };
+    (@ $inner:ident, crs) => {
+        fn crs(&mut self, crs: &$crate::Crs) -> $crate::error::Result<()> {
+            self.$inner.crs(crs)
+        }
+    };
+    (@ $inner:ident, metadata) => {
+        fn metadata(
+            &mut self,
+            metadata: &$crate::DatasetMetadata,
+        ) -> $crate::error::Result<()> {
+            self.$inner.metadata(metadata)
+        }
+    };
+    (@ $inner:ident, warning) => {
+        fn warning(&mut self, warning: &$crate::Warning) -> $crate::error::Result<()> {
+            self.$inner.warning(warning)
+        }
+    };
+    (@ $inner:ident, fid) => {
+        fn fid(&mut self, fid: &$crate::ColumnValue) -> $crate::error::Result<()> {
+            self.$inner.fid(fid)
+        }
+    };
+    (@ $inner:ident, feature) => {
+        forward_processor_methods!(@ $inner, fid);
+        fn feature_begin(&mut self, idx: u64) -> $crate::error::Result<()> {
+            self.$inner.feature_begin(idx)
+        }
+        fn feature_end(&mut self, idx: u64) -> $crate::error::Result<()> {
+            self.$inner.feature_end(idx)
+        }
+    };
+    (@ $inner:ident, properties) => {
+        fn properties_begin(&mut self) -> $crate::error::Result<()> {
+            self.$inner.properties_begin()
+        }
+        fn properties_end(&mut self) -> $crate::error::Result<()> {
+            self.$inner.properties_end()
+        }
+    };
+    (@ $inner:ident, geometry) => {
+        fn geometry_begin(&mut self) -> $crate::error::Result<()> {
+            self.$inner.geometry_begin()
+        }
+        fn geometry_end(&mut self) -> $crate::error::Result<()> {
+            self.$inner.geometry_end()
+        }
+    };
+}
+
+pub(crate) use forward_processor_methods;
+
+/// Flush buffered coordinates with `self.flush()` before forwarding the call
+/// to the wrapped processor `self.inner`.
+#[allow(unused_macros)]
+macro_rules! flush_and_forward {
+    (geometries) => {
+        flush_and_forward!(
+            empty_point(idx: usize),
+            point_begin(idx: usize),
+            point_end(idx: usize),
+            multipoint_begin(size: usize, idx: usize),
+            multipoint_end(idx: usize),
+            linestring_begin(tagged: bool, size: usize, idx: usize),
+            linestring_end(tagged: bool, idx: usize),
+            multilinestring_begin(size: usize, idx: usize),
+            multilinestring_end(idx: usize),
+            polygon_begin(tagged: bool, size: usize, idx: usize),
+            polygon_end(tagged: bool, idx: usize),
+            multipolygon_begin(size: usize, idx: usize),
+            multipolygon_end(idx: usize),
+            geometrycollection_begin(size: usize, idx: usize),
+            geometrycollection_end(idx: usize),
+            circularstring_begin(size: usize, idx: usize),
+            circularstring_end(idx: usize),
+            compoundcurve_begin(size: usize, idx: usize),
+            compoundcurve_end(idx: usize),
+            curvepolygon_begin(size: usize, idx: usize),
+            curvepolygon_end(idx: usize),
+            multicurve_begin(size: usize, idx: usize),
+            multicurve_end(idx: usize),
+            multisurface_begin(size: usize, idx: usize),
+            multisurface_end(idx: usize),
+            triangle_begin(tagged: bool, size: usize, idx: usize),
+            triangle_end(tagged: bool, idx: usize),
+            polyhedralsurface_begin(size: usize, idx: usize),
+            polyhedralsurface_end(idx: usize),
+            tin_begin(size: usize, idx: usize),
+            tin_end(idx: usize),
+        );
+    };
+    ($($method:ident($($arg:ident: $t:ty),*)),* $(,)?) => {
+        $(
+            fn $method(&mut self, $($arg: $t),*) -> $crate::error::Result<()> {
+                self.flush()?;
+                self.inner.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+mod affine;
+mod antimeridian;
+mod bbox_filter;
+mod borrowed;
+mod buffer;
+mod centroid;
+mod clip;
+mod continue_on_error;
+mod coord_seq;
+mod dedup;
+mod densify;
+mod dims;
+mod explode;
+mod extent;
+mod filter;
+#[cfg(feature = "with-geodesy")]
+mod geodesy;
+mod geohash;
+#[cfg(feature = "with-geos")]
+mod geos_buffer;
+#[cfg(feature = "with-h3")]
+mod h3;
+mod hilbert;
+mod limits;
+#[cfg(feature = "with-geo-algorithms")]
+mod measure;
+mod memory;
+mod mercator;
+mod non_finite;
+mod paging;
+mod partition;
+#[cfg(feature = "with-rayon")]
+mod parallel;
+mod precision;
+mod progress;
+#[cfg(feature = "with-proj")]
+mod proj;
+mod promote;
+mod property_map;
+mod recorder;
+mod repeated;
+#[cfg(feature = "with-rstar")]
+mod rtree;
+#[cfg(any(feature = "with-proj", feature = "with-geodesy"))]
+mod reproject;
+#[cfg(feature = "with-s2")]
+mod s2;
+mod simplify;
+mod snap;
+mod spill;
+mod stats;
+mod swap;
+mod tile_coverage;
+#[cfg(feature = "with-tracing")]
+mod trace;
+mod transform;
+mod unique_properties;
+mod validity;
+mod warnings;
+mod winding;
+
+#[cfg(feature = "with-geodesy")]
+pub use self::geodesy::*;
+#[cfg(feature = "with-geos")]
+pub use self::geos_buffer::*;
+#[cfg(feature = "with-proj")]
+pub use self::proj::*;
+#[cfg(feature = "with-s2")]
+pub use self::s2::*;
+pub use affine::*;
+pub use antimeridian::*;
+pub use bbox_filter::*;
+pub(crate) use borrowed::*;
+pub(crate) use buffer::*;
+pub use buffer::Event;
+pub use centroid::*;
+pub use clip::*;
+pub use continue_on_error::*;
+pub(crate) use coord_seq::*;
+pub use dedup::*;
+pub use densify::*;
+pub use dims::*;
+pub use explode::*;
+pub use extent::*;
+pub use filter::*;
+pub use geohash::*;
+#[cfg(feature = "with-h3")]
+pub use h3::*;
+pub use hilbert::*;
+pub use limits::*;
+#[cfg(feature = "with-geo-algorithms")]
+pub use measure::*;
+pub use memory::*;
+pub use mercator::*;
+pub use non_finite::*;
+pub use paging::*;
+pub use partition::*;
+#[cfg(feature = "with-rayon")]
+pub use parallel::*;
+pub use precision::*;
+pub use progress::*;
+pub use promote::*;
+pub use property_map::*;
+pub use recorder::*;
+pub use repeated::*;
+#[cfg(feature = "with-rstar")]
+pub use rtree::*;
+#[cfg(any(feature = "with-proj", feature = "with-geodesy"))]
+pub use reproject::*;
+pub use simplify::*;
+pub use snap::*;
+pub(crate) use spill::*;
+pub use stats::*;
+pub use swap::*;
+pub use tile_coverage::*;
+#[cfg(feature = "with-tracing")]
+pub use trace::*;
+pub use transform::*;
+pub use unique_properties::*;
+pub use validity::*;
+pub use warnings::*;
+pub use winding::*;
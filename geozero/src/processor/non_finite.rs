@@ -0,0 +1,248 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::FeatureBuffer;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor, Warning, WarningKind};
+
+/// Handling of NaN and infinite coordinate values.
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum NonFinitePolicy {
+    /// Abort processing with an error
+    #[default]
+    Error,
+    /// Remove vertices with non-finite values
+    DropVertex,
+    /// Drop features with non-finite values
+    SkipFeature,
+}
+
+/// Applies a [NonFinitePolicy] to coordinates with NaN or infinite values.
+///
+/// X, Y and requested Z, M and T values are checked. Dropped vertices are removed
+/// from their coordinate sequence, size hints of the enclosing geometry are passed on
+/// unchanged. With [SkipFeature](NonFinitePolicy::SkipFeature), features are buffered
+/// until their end and passed features are renumbered. Non-finite values in geometries
+/// processed outside of a feature are an error in this case.
+///
+/// Skipped features and dropped vertices are reported as [Warning], once per feature.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{NonFiniteFilter, NonFinitePolicy};
+/// use geozero::wkt::WktWriter;
+/// use geozero::GeomProcessor;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor =
+///     NonFiniteFilter::new(WktWriter::new(&mut out), NonFinitePolicy::DropVertex);
+/// processor.linestring_begin(true, 3, 0).unwrap();
+/// processor.xy(0.0, 0.0, 0).unwrap();
+/// processor.xy(f64::NAN, 1.0, 1).unwrap();
+/// processor.xy(2.0, f64::INFINITY, 2).unwrap();
+/// processor.xy(3.0, 3.0, 3).unwrap();
+/// processor.linestring_end(true, 0).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(0 0,3 3)");
+/// ```
+pub struct NonFiniteFilter<P> {
+    buffer: FeatureBuffer<P>,
+    policy: NonFinitePolicy,
+    /// Index of current feature
+    feature: Option<u64>,
+    /// Current feature has non-finite values
+    rejected: bool,
+    /// Dropped vertices of current feature have been reported
+    reported: bool,
+    /// Dropped vertices of current coordinate sequence
+    dropped: usize,
+    /// Number of passed features
+    count: u64,
+}
+
+impl<P> NonFiniteFilter<P> {
+    pub fn new(inner: P, policy: NonFinitePolicy) -> Self {
+        NonFiniteFilter {
+            buffer: FeatureBuffer::new(inner),
+            policy,
+            feature: None,
+            rejected: false,
+            reported: false,
+            dropped: 0,
+            count: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume filter and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    fn skips_features(&self) -> bool {
+        self.policy == NonFinitePolicy::SkipFeature && self.feature.is_some()
+    }
+}
+
+impl<P: FeatureProcessor> NonFiniteFilter<P> {
+    /// Apply policy to vertex and return its index, `None` if dropped
+    fn check(&mut self, values: &[Option<f64>], idx: usize) -> Result<Option<usize>> {
+        if idx == 0 {
+            self.dropped = 0;
+        }
+        if values.iter().flatten().all(|v| v.is_finite()) {
+            return Ok(Some(idx - self.dropped));
+        }
+        match self.policy {
+            NonFinitePolicy::DropVertex => {
+                self.dropped += 1;
+                if !self.reported {
+                    self.reported = self.feature.is_some();
+                    let mut warning = Warning::new(
+                        WarningKind::DroppedValue,
+                        "dropped vertices with non-finite coordinate values",
+                    );
+                    warning.feature_idx = self.feature;
+                    self.buffer.warning(&warning)?;
+                }
+                Ok(None)
+            }
+            NonFinitePolicy::SkipFeature if self.feature.is_some() => {
+                self.rejected = true;
+                Ok(Some(idx))
+            }
+            _ => Err(GeozeroError::Geometry(format!(
+                "non-finite coordinate value at vertex {}",
+                idx
+            ))),
+        }
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for NonFiniteFilter<P> {
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        match self.check(&[Some(x), Some(y)], idx)? {
+            Some(idx) => self.buffer.xy(x, y, idx),
+            None => Ok(()),
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        match self.check(&[Some(x), Some(y), z, m, t], idx)? {
+            Some(idx) => self.buffer.coordinate(x, y, z, m, t, tm, idx),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for NonFiniteFilter<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for NonFiniteFilter<P> {
+    forward_processor_methods!(buffer; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature = Some(idx);
+        self.reported = false;
+        if self.skips_features() {
+            self.rejected = false;
+            self.buffer.record();
+        }
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let skips_features = self.skips_features();
+        self.feature = None;
+        self.buffer.feature_end(idx)?;
+        if !skips_features {
+            return Ok(());
+        }
+        if self.rejected {
+            self.buffer.discard();
+            self.buffer.warning(
+                &Warning::new(
+                    WarningKind::SkippedFeature,
+                    "skipped feature with non-finite coordinate values",
+                )
+                .feature(idx),
+            )
+        } else {
+            self.count += 1;
+            self.buffer.replay(self.count - 1)
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJsonWriter;
+    use crate::ColumnValue;
+
+    fn feature(processor: &mut impl FeatureProcessor, idx: u64, name: &str, x: f64) -> Result<()> {
+        processor.feature_begin(idx)?;
+        processor.properties_begin()?;
+        processor.property(0, "name", &ColumnValue::String(name))?;
+        processor.properties_end()?;
+        processor.geometry_begin()?;
+        processor.linestring_begin(true, 2, 0)?;
+        processor.xy(0.0, 0.0, 0)?;
+        processor.xy(x, 1.0, 1)?;
+        processor.linestring_end(true, 0)?;
+        processor.geometry_end()?;
+        processor.feature_end(idx)
+    }
+
+    fn process(policy: NonFinitePolicy) -> Result<String> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut filter = NonFiniteFilter::new(GeoJsonWriter::new(&mut out), policy);
+        filter.dataset_begin(None)?;
+        feature(&mut filter, 0, "a", f64::NAN)?;
+        feature(&mut filter, 1, "b", 1.0)?;
+        feature(&mut filter, 2, "c", f64::NEG_INFINITY)?;
+        filter.dataset_end()?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn policies() -> Result<()> {
+        assert!(process(NonFinitePolicy::Error).is_err());
+
+        let json = process(NonFinitePolicy::DropVertex)?;
+        assert!(json.contains(r#""coordinates": [[0,0]]"#));
+        assert!(json.contains(r#""coordinates": [[0,0],[1,1]]"#));
+
+        let json = process(NonFinitePolicy::SkipFeature)?;
+        assert!(!json.contains(r#""a""#) && !json.contains(r#""c""#));
+        assert!(json.contains(r#"{"type": "Feature", "properties": {"name": "b"}"#));
+        Ok(())
+    }
+
+    #[test]
+    fn dropped_vertex_indices() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut filter =
+            NonFiniteFilter::new(GeoJsonWriter::new(&mut out), NonFinitePolicy::DropVertex);
+        filter.multipoint_begin(3, 0)?;
+        filter.xy(f64::NAN, f64::NAN, 0)?;
+        filter.coordinate(1.0, 1.0, Some(f64::INFINITY), None, None, None, 1)?;
+        filter.coordinate(2.0, 2.0, Some(3.0), None, None, None, 2)?;
+        filter.multipoint_end(0)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{"type": "MultiPoint", "coordinates": [[2,2,3]]}"#
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::FeatureBuffer;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Skips the first `offset` features and passes at most `limit` features.
+///
+/// Passed features are renumbered starting at 0. When the limit is reached,
+/// the dataset is ended and [`GeozeroError::Stopped`] is returned, which makes
+/// the reader stop without processing the remaining input. Geometries processed
+/// outside of a feature are passed on unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::error::GeozeroError;
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::Paging;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}},
+///     {"type": "Feature", "properties": {"name": "Rome"}, "geometry": {"type": "Point", "coordinates": [12.5, 41.9]}}
+/// ]}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut paging = Paging::new(GeoJsonWriter::new(&mut out)).offset(1).limit(1);
+/// match GeoJson(geojson).process(&mut paging) {
+///     Ok(()) | Err(GeozeroError::Stopped) => {}
+///     Err(e) => panic!("{}", e),
+/// }
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains("Paris") && !json.contains("Bern") && !json.contains("Rome"));
+/// ```
+pub struct Paging<P> {
+    buffer: FeatureBuffer<P>,
+    offset: u64,
+    limit: Option<u64>,
+    /// Number of seen features
+    seen: u64,
+    /// Number of passed features
+    count: u64,
+    in_dataset: bool,
+}
+
+impl<P> Paging<P> {
+    pub fn new(inner: P) -> Self {
+        Paging {
+            buffer: FeatureBuffer::new(inner),
+            offset: 0,
+            limit: None,
+            seen: 0,
+            count: 0,
+            in_dataset: false,
+        }
+    }
+    /// Skip the first `offset` features
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+    /// Pass at most `limit` features
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+    fn limit_reached(&self) -> bool {
+        matches!(self.limit, Some(limit) if self.count >= limit)
+    }
+}
+
+impl<P: FeatureProcessor> Paging<P> {
+    /// End dataset and signal reader to stop
+    fn stop(&mut self) -> Result<()> {
+        if self.in_dataset {
+            self.in_dataset = false;
+            self.buffer.dataset_end()?;
+        }
+        Err(GeozeroError::Stopped)
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for Paging<P> {
+    forward_processor_methods!(buffer; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for Paging<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Paging<P> {
+    forward_processor_methods!(buffer; schema, crs, metadata, warning, fid, properties, geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.in_dataset = true;
+        self.buffer.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        if self.in_dataset {
+            self.in_dataset = false;
+            self.buffer.dataset_end()
+        } else {
+            Ok(())
+        }
+    }
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        if self.limit_reached() {
+            return self.stop();
+        }
+        self.seen += 1;
+        if self.seen <= self.offset {
+            // Skipped features are recorded and discarded at the end
+            self.buffer.record();
+            Ok(())
+        } else {
+            self.buffer.feature_begin(self.count)
+        }
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        if self.seen <= self.offset {
+            self.buffer.discard();
+            return Ok(());
+        }
+        self.buffer.feature_end(self.count)?;
+        self.count += 1;
+        if self.limit_reached() {
+            return self.stop();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records feature indices and dataset calls
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl GeomProcessor for Recorder {
+        fn xy(&mut self, x: f64, _y: f64, _idx: usize) -> Result<()> {
+            self.0.push(format!("x={}", x));
+            Ok(())
+        }
+    }
+
+    impl PropertyProcessor for Recorder {}
+
+    impl FeatureProcessor for Recorder {
+        fn dataset_end(&mut self) -> Result<()> {
+            self.0.push("end".to_string());
+            Ok(())
+        }
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push(format!("feature {}", idx));
+            Ok(())
+        }
+    }
+
+    fn process(paging: &mut Paging<Recorder>, features: u64) -> Result<()> {
+        paging.dataset_begin(None)?;
+        for idx in 0..features {
+            paging.feature_begin(idx)?;
+            paging.geometry_begin()?;
+            paging.point_begin(0)?;
+            paging.xy(idx as f64, 0.0, 0)?;
+            paging.point_end(0)?;
+            paging.geometry_end()?;
+            paging.feature_end(idx)?;
+        }
+        paging.dataset_end()
+    }
+
+    #[test]
+    fn offset_limit() {
+        let mut paging = Paging::new(Recorder::default()).offset(2).limit(2);
+        assert!(matches!(
+            process(&mut paging, 10),
+            Err(GeozeroError::Stopped)
+        ));
+        assert_eq!(
+            paging.into_inner().0,
+            vec!["feature 0", "x=2", "feature 1", "x=3", "end"]
+        );
+    }
+
+    #[test]
+    fn offset_only() {
+        let mut paging = Paging::new(Recorder::default()).offset(2);
+        assert!(process(&mut paging, 3).is_ok());
+        assert_eq!(paging.into_inner().0, vec!["feature 0", "x=2", "end"]);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::Result;
+use crate::processor::{Event, FeatureBuffer};
+use crate::{
+    ColumnDef, CoordDimensions, Crs, DatasetMetadata, FeatureProcessor, GeomProcessor,
+    GeozeroDatasource, PropertyProcessor, Warning,
+};
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Processing result of a chunk
+#[derive(Default)]
+struct ChunkOutput {
+    events: Vec<Event>,
+    schema: Option<Vec<ColumnDef>>,
+    crs: Option<Crs>,
+    metadata: Option<DatasetMetadata>,
+    warnings: Vec<Warning>,
+}
+
+/// Captures schema, CRS, metadata and warnings of a chunk
+struct ChunkHeader(Rc<RefCell<ChunkOutput>>);
+
+impl GeomProcessor for ChunkHeader {}
+
+impl PropertyProcessor for ChunkHeader {}
+
+impl FeatureProcessor for ChunkHeader {
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.0.borrow_mut().schema = Some(columns.to_vec());
+        Ok(())
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        self.0.borrow_mut().crs = Some(crs.clone());
+        Ok(())
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        self.0.borrow_mut().metadata = Some(metadata.clone());
+        Ok(())
+    }
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        self.0.borrow_mut().warnings.push(warning.clone());
+        Ok(())
+    }
+}
+
+/// Innermost processor of a chunk pipeline, recording the processed features.
+///
+/// Recorded features are merged into the output processor after the pipeline is dropped.
+pub struct ChunkRecorder {
+    buffer: FeatureBuffer<ChunkHeader>,
+    output: Rc<RefCell<ChunkOutput>>,
+    /// Dimensions of the output processor
+    dims: CoordDimensions,
+}
+
+impl ChunkRecorder {
+    fn new(dims: CoordDimensions) -> Self {
+        let output = Rc::new(RefCell::new(ChunkOutput::default()));
+        let mut buffer = FeatureBuffer::new(ChunkHeader(output.clone()));
+        buffer.record();
+        ChunkRecorder {
+            buffer,
+            output,
+            dims,
+        }
+    }
+}
+
+impl Drop for ChunkRecorder {
+    fn drop(&mut self) {
+        self.output.borrow_mut().events = self.buffer.take();
+    }
+}
+
+impl GeomProcessor for ChunkRecorder {
+    forward_processor_methods!(buffer; srid, xy, coordinate, empty_point, geometries);
+
+    fn dimensions(&self) -> CoordDimensions {
+        self.dims
+    }
+}
+
+impl PropertyProcessor for ChunkRecorder {
+    forward_processor_methods!(buffer; property);
+}
+
+impl FeatureProcessor for ChunkRecorder {
+    forward_processor_methods!(buffer; feature_processor);
+}
+
+/// Process `chunk` with the pipeline created by `pipeline`
+fn process_chunk<D, F, Q>(mut chunk: D, pipeline: &F, dims: CoordDimensions) -> Result<ChunkOutput>
+where
+    D: GeozeroDatasource,
+    F: Fn(ChunkRecorder) -> Result<Q>,
+    Q: FeatureProcessor,
+{
+    let recorder = ChunkRecorder::new(dims);
+    let output = recorder.output.clone();
+    {
+        let mut processor = pipeline(recorder)?;
+        chunk.process(&mut processor)?;
+    }
+    let output = output.take();
+    Ok(output)
+}
+
+/// Process datasource chunks in parallel with [rayon](https://docs.rs/rayon) and pass
+/// the results in order to `processor`.
+///
+/// Used for datasources which can be split into independent feature chunks, e.g. line
+/// ranges of newline-delimited GeoJSON or feature ranges of formats with a feature index,
+/// with CPU-heavy processors like reprojection, simplification or tessellation.
+/// Each chunk is processed on the rayon thread pool by the processor returned by
+/// `pipeline`, which wraps the given [ChunkRecorder]. Features are passed to
+/// `processor` in chunk order and renumbered starting at 0. Schema, CRS and metadata
+/// without extent are taken from the first chunk, coordinate dimensions from `processor`.
+/// Warnings of a chunk are passed on before its features, with feature indices offset by
+/// the number of features of preceding chunks. At most twice the number of rayon threads
+/// chunks are kept in memory.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::{process_parallel, TransformXY};
+///
+/// let chunks = vec![
+///     GeoJson(r#"{"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [1, 1]}}"#),
+///     GeoJson(r#"{"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [2, 2]}}"#),
+/// ];
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut writer = GeoJsonWriter::new(&mut out);
+/// process_parallel(chunks, &mut writer, |recorder| {
+///     Ok(TransformXY::new(recorder, |x, y| (x * 1000.0, y * 1000.0)))
+/// })
+/// .unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.find("[1000,1000]").unwrap() < json.find("[2000,2000]").unwrap());
+/// ```
+pub fn process_parallel<D, F, Q, P>(
+    chunks: impl IntoIterator<Item = D>,
+    processor: &mut P,
+    pipeline: F,
+) -> Result<()>
+where
+    D: GeozeroDatasource + Send,
+    F: Fn(ChunkRecorder) -> Result<Q> + Sync,
+    Q: FeatureProcessor,
+    P: FeatureProcessor,
+{
+    let batch_size = 2 * rayon::current_num_threads();
+    let dims = processor.dimensions();
+    let mut chunks = chunks.into_iter();
+    let mut first = true;
+    let mut feature_idx = 0;
+    processor.dataset_begin(None)?;
+    loop {
+        let batch: Vec<D> = chunks.by_ref().take(batch_size).collect();
+        if batch.is_empty() {
+            break;
+        }
+        let outputs = batch
+            .into_par_iter()
+            .map(|chunk| process_chunk(chunk, &pipeline, dims))
+            .collect::<Result<Vec<_>>>()?;
+        for output in outputs {
+            if first {
+                if let Some(schema) = &output.schema {
+                    processor.schema(schema)?;
+                }
+                if let Some(crs) = &output.crs {
+                    processor.crs(crs)?;
+                }
+                if let Some(metadata) = &output.metadata {
+                    // The extent of the first chunk doesn't cover the dataset
+                    processor.metadata(&metadata.without_extent())?;
+                }
+                first = false;
+            }
+            for warning in &output.warnings {
+                let mut warning = warning.clone();
+                warning.feature_idx = warning.feature_idx.map(|idx| feature_idx + idx);
+                processor.warning(&warning)?;
+            }
+            for event in &output.events {
+                match event {
+                    Event::FeatureBegin(_) => processor.feature_begin(feature_idx)?,
+                    Event::FeatureEnd(_) => {
+                        processor.feature_end(feature_idx)?;
+                        feature_idx += 1;
+                    }
+                    _ => event.replay(processor)?,
+                }
+            }
+        }
+    }
+    processor.dataset_end()
+}
+
+#[cfg(all(test, feature = "with-geojson"))]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJsonString;
+    use crate::processor::TransformXY;
+
+    /// Records feature indices and X values
+    #[derive(Default)]
+    struct Features(Vec<(u64, f64)>);
+
+    impl GeomProcessor for Features {
+        fn xy(&mut self, x: f64, _y: f64, _idx: usize) -> Result<()> {
+            if let Some(feature) = self.0.last_mut() {
+                feature.1 = x;
+            }
+            Ok(())
+        }
+    }
+
+    impl PropertyProcessor for Features {}
+
+    impl FeatureProcessor for Features {
+        fn feature_begin(&mut self, idx: u64) -> Result<()> {
+            self.0.push((idx, f64::NAN));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ordered_merge() -> Result<()> {
+        let chunks = (0..50).map(|i| {
+            GeoJsonString(format!(
+                r#"{{"type": "Feature", "properties": {{}}, "geometry": {{"type": "Point", "coordinates": [{}, 0]}}}}"#,
+                i
+            ))
+        });
+        let mut features = Features::default();
+        process_parallel(chunks, &mut features, |recorder| {
+            Ok(TransformXY::new(recorder, |x, y| (x * 2.0, y)))
+        })?;
+        let expected: Vec<_> = (0..50).map(|i| (i, i as f64 * 2.0)).collect();
+        assert_eq!(features.0, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::explode::standalone;
+use crate::processor::{Event, FeatureBuffer};
+use crate::{
+    ColumnDef, ColumnValue, Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, ProcessorSink,
+    PropertyProcessor,
+};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::io::Write;
+
+/// Partition of a feature
+#[derive(Clone, PartialEq, Debug)]
+pub enum PartitionKey {
+    /// Value of a property, `null` if missing
+    Property(String),
+    /// Cell of a regular grid with the given cell size, named `<col>_<row>`
+    Grid(f64),
+    /// Web Mercator tile of the given zoom level (lon/lat coordinates), named `<z>_<x>_<y>`
+    Tile(u8),
+}
+
+/// Output format of partitions
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PartitionFormat {
+    /// GeoJSON FeatureCollection
+    #[cfg(feature = "with-geojson")]
+    GeoJson,
+    /// Newline-delimited GeoJSON features
+    #[cfg(feature = "with-geojson")]
+    GeoJsonLines,
+    /// One WKT geometry per line
+    #[cfg(feature = "with-wkt")]
+    Wkt,
+}
+
+/// Routes features into multiple outputs by a [PartitionKey].
+///
+/// Outputs are created on demand by calling `create` with the partition name, which
+/// contains only ASCII letters, digits, `-` and `_` and can be used as file name.
+/// Each feature is buffered until its partition is known and then written to the
+/// partition output. When the dataset ends, all partitions are completed and flushed.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::{PartitionFormat, PartitionKey, PartitionedWriter};
+/// use geozero::GeozeroDatasource;
+///
+/// let mut source = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"country": "CH"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"country": "FR"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}},
+///     {"type": "Feature", "properties": {"country": "CH"}, "geometry": {"type": "Point", "coordinates": [8.54, 47.37]}}
+/// ]}"#);
+/// let mut writer = PartitionedWriter::new(
+///     PartitionKey::Property("country".to_string()),
+///     PartitionFormat::GeoJsonLines,
+///     |_name| Ok(Vec::<u8>::new()),
+/// );
+/// source.process(&mut writer).unwrap();
+/// let outputs = writer.into_outputs();
+/// assert_eq!(outputs.keys().collect::<Vec<_>>(), vec!["CH", "FR"]);
+/// assert_eq!(std::str::from_utf8(&outputs["CH"]).unwrap().lines().count(), 2);
+/// ```
+///
+/// Writing one file per partition:
+///
+/// ```rust,ignore
+/// let mut writer = PartitionedWriter::new(
+///     PartitionKey::Property("country".to_string()),
+///     PartitionFormat::GeoJsonLines,
+///     |name| Ok(BufWriter::new(File::create(format!("out/{}.geojsonl", name))?)),
+/// );
+/// ```
+pub struct PartitionedWriter<O, F> {
+    key: PartitionKey,
+    format: PartitionFormat,
+    create: F,
+    partitions: BTreeMap<String, Partition<O>>,
+    buffer: FeatureBuffer<ProcessorSink>,
+    name: Option<String>,
+    crs: Option<Crs>,
+    metadata: Option<DatasetMetadata>,
+    /// Property value of current feature
+    value: Option<String>,
+    /// Bounding box of current feature
+    bbox: Option<[f64; 4]>,
+}
+
+struct Partition<O> {
+    out: O,
+    feature_count: u64,
+}
+
+impl<O, F> PartitionedWriter<O, F>
+where
+    O: Write,
+    F: FnMut(&str) -> Result<O>,
+{
+    pub fn new(key: PartitionKey, format: PartitionFormat, create: F) -> Self {
+        PartitionedWriter {
+            key,
+            format,
+            create,
+            partitions: BTreeMap::new(),
+            buffer: FeatureBuffer::new(ProcessorSink::new()),
+            name: None,
+            crs: None,
+            metadata: None,
+            value: None,
+            bbox: None,
+        }
+    }
+    /// Names of created partitions
+    pub fn partitions(&self) -> impl Iterator<Item = &str> {
+        self.partitions.keys().map(String::as_str)
+    }
+    /// Number of features written to partition `name`
+    pub fn feature_count(&self, name: &str) -> u64 {
+        self.partitions
+            .get(name)
+            .map(|partition| partition.feature_count)
+            .unwrap_or(0)
+    }
+    /// Consume writer and return outputs by partition name
+    pub fn into_outputs(self) -> BTreeMap<String, O> {
+        self.partitions
+            .into_iter()
+            .map(|(name, partition)| (name, partition.out))
+            .collect()
+    }
+    /// Partition name of current feature
+    fn partition_name(&self) -> String {
+        let name = match self.key {
+            PartitionKey::Property(_) => self.value.clone().unwrap_or_else(|| "null".to_string()),
+            PartitionKey::Grid(size) => match self.bbox {
+                Some(bbox) => {
+                    let (x, y) = bbox_center(&bbox);
+                    format!("{}_{}", (x / size).floor(), (y / size).floor())
+                }
+                None => "empty".to_string(),
+            },
+            PartitionKey::Tile(zoom) => match self.bbox {
+                Some(bbox) => {
+                    let (lon, lat) = bbox_center(&bbox);
+                    let (x, y) = tile(lon, lat, zoom);
+                    format!("{}_{}_{}", zoom, x, y)
+                }
+                None => "empty".to_string(),
+            },
+        };
+        sanitize(&name)
+    }
+    fn add_coord(&mut self, x: f64, y: f64) {
+        let bbox = self.bbox.get_or_insert([x, y, x, y]);
+        bbox[0] = bbox[0].min(x);
+        bbox[1] = bbox[1].min(y);
+        bbox[2] = bbox[2].max(x);
+        bbox[3] = bbox[3].max(y);
+    }
+    /// Write recorded feature to its partition
+    fn write_feature(&mut self, events: Vec<Event>) -> Result<()> {
+        let name = self.partition_name();
+        if !self.partitions.contains_key(&name) {
+            let out = (self.create)(&name)?;
+            self.partitions.insert(
+                name.clone(),
+                Partition {
+                    out,
+                    feature_count: 0,
+                },
+            );
+        }
+        let partition = self
+            .partitions
+            .get_mut(&name)
+            .ok_or_else(|| GeozeroError::Dataset(format!("missing partition `{}`", name)))?;
+        let first = partition.feature_count == 0;
+        let idx = partition.feature_count;
+        partition.feature_count += 1;
+        match self.format {
+            #[cfg(feature = "with-geojson")]
+            PartitionFormat::GeoJson => {
+                let mut writer = match &self.crs {
+                    Some(crs) if first => crate::geojson::GeoJsonWriter::builder()
+                        .options(crate::WriterOptions::default().with_crs(crs.clone()))
+                        .build(&mut partition.out),
+                    _ => crate::geojson::GeoJsonWriter::new(&mut partition.out),
+                };
+                if first {
+                    writer.dataset_begin(self.name.as_deref())?;
+                    if let Some(metadata) = &self.metadata {
+                        writer.metadata(metadata)?;
+                    }
+                }
+                replay(&events, idx, &mut writer)?;
+                writer.flush()
+            }
+            #[cfg(feature = "with-geojson")]
+            PartitionFormat::GeoJsonLines => {
+                let mut writer = crate::geojson::GeoJsonWriter::new(&mut partition.out);
+                replay(&events, 0, &mut writer)?;
+                writer.flush()?;
+                partition.out.write_all(b"\n")?;
+                Ok(())
+            }
+            #[cfg(feature = "with-wkt")]
+            PartitionFormat::Wkt => {
+                let mut writer = crate::wkt::WktWriter::new(&mut partition.out);
+                replay(&events, idx, &mut writer)?;
+                writer.flush()?;
+                partition.out.write_all(b"\n")?;
+                Ok(())
+            }
+        }
+    }
+    /// Complete and flush all partitions
+    fn finish(&mut self) -> Result<()> {
+        for partition in self.partitions.values_mut() {
+            match self.format {
+                #[cfg(feature = "with-geojson")]
+                PartitionFormat::GeoJson => {
+                    crate::geojson::GeoJsonWriter::new(&mut partition.out).dataset_end()?
+                }
+                #[allow(unreachable_patterns)]
+                _ => {}
+            }
+            partition.out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Pass recorded feature on with feature index `idx` and a standalone top level geometry
+fn replay<P: FeatureProcessor>(events: &[Event], idx: u64, processor: &mut P) -> Result<()> {
+    let mut depth = 0;
+    for event in events {
+        let outer = depth == 0;
+        depth += event.nesting();
+        match event {
+            Event::FeatureBegin(_) => processor.feature_begin(idx)?,
+            Event::FeatureEnd(_) => processor.feature_end(idx)?,
+            // Readers may pass the feature index as geometry index
+            _ if outer || depth == 0 => standalone(event).replay(processor)?,
+            _ => event.replay(processor)?,
+        }
+    }
+    Ok(())
+}
+
+fn bbox_center(bbox: &[f64; 4]) -> (f64, f64) {
+    ((bbox[0] + bbox[2]) / 2.0, (bbox[1] + bbox[3]) / 2.0)
+}
+
+/// Web Mercator tile containing lon/lat position
+fn tile(lon: f64, lat: f64, zoom: u8) -> (u32, u32) {
+    let n = 2f64.powi(zoom as i32);
+    let max = n - 1.0;
+    let lat = lat.clamp(-85.051_128_78, 85.051_128_78).to_radians();
+    let x = ((lon + 180.0) / 360.0 * n).floor().clamp(0.0, max);
+    let y = ((1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n)
+        .floor()
+        .clamp(0.0, max);
+    (x as u32, y as u32)
+}
+
+/// Replace characters not allowed in partition names
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name
+    }
+}
+
+impl<O, F> GeomProcessor for PartitionedWriter<O, F>
+where
+    O: Write,
+    F: FnMut(&str) -> Result<O>,
+{
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometries);
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.add_coord(x, y);
+        self.buffer.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.add_coord(x, y);
+        self.buffer.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<O, F> PropertyProcessor for PartitionedWriter<O, F>
+where
+    O: Write,
+    F: FnMut(&str) -> Result<O>,
+{
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if let PartitionKey::Property(key) = &self.key {
+            if key == name && *value != ColumnValue::Null {
+                self.value = Some(value.to_string());
+            }
+        }
+        self.buffer.property(idx, name, value)
+    }
+}
+
+impl<O, F> FeatureProcessor for PartitionedWriter<O, F>
+where
+    O: Write,
+    F: FnMut(&str) -> Result<O>,
+{
+    forward_processor_methods!(buffer; fid, properties, geometry);
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.name = name.map(str::to_string);
+        Ok(())
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.finish()
+    }
+    fn schema(&mut self, _columns: &[ColumnDef]) -> Result<()> {
+        Ok(())
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        self.crs = Some(crs.clone());
+        Ok(())
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        // The extent of the dataset doesn't apply to single partitions
+        self.metadata = Some(metadata.without_extent());
+        Ok(())
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.value = None;
+        self.bbox = None;
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        self.write_feature(events)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn partition_names() {
+        assert_eq!(sanitize("CH"), "CH");
+        assert_eq!(sanitize("../etc"), "___etc");
+        assert_eq!(sanitize(""), "_");
+        assert_eq!(tile(7.44, 46.95, 0), (0, 0));
+        assert_eq!(tile(7.44, 46.95, 8), (133, 90));
+        assert_eq!(tile(180.0, -90.0, 2), (3, 3));
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn partition_by_grid() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let mut source = GeoJson(
+            r#"{"type": "FeatureCollection", "name": "cities", "features": [
+            {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+            {"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}},
+            {"type": "Feature", "properties": {"name": "Zurich"}, "geometry": {"type": "Point", "coordinates": [8.54, 47.37]}}
+        ]}"#,
+        );
+        let mut writer =
+            PartitionedWriter::new(PartitionKey::Grid(5.0), PartitionFormat::GeoJson, |_name| {
+                Ok(Vec::<u8>::new())
+            });
+        source.process(&mut writer)?;
+        assert_eq!(writer.partitions().collect::<Vec<_>>(), vec!["0_9", "1_9"]);
+        assert_eq!(writer.feature_count("1_9"), 2);
+        let outputs = writer.into_outputs();
+        let json: serde_json::Value = serde_json::from_slice(&outputs["1_9"]).unwrap();
+        assert_eq!(json["features"][1]["properties"]["name"], "Zurich");
+        let json: serde_json::Value = serde_json::from_slice(&outputs["0_9"]).unwrap();
+        assert_eq!(json["features"][0]["properties"]["name"], "Paris");
+        Ok(())
+    }
+}
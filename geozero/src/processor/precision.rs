@@ -0,0 +1,167 @@
+use crate::error::Result;
+use crate::processor::CoordSeq;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Coordinate precision
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Precision {
+    /// Number of decimal places
+    Decimals(u8),
+    /// Grid cell size in source units
+    Grid(f64),
+}
+
+impl Precision {
+    /// Round value to precision
+    pub fn round(&self, value: f64) -> f64 {
+        match *self {
+            Precision::Decimals(decimals) => {
+                let factor = 10f64.powi(decimals as i32);
+                (value * factor).round() / factor
+            }
+            Precision::Grid(size) => (value / size).round() * size,
+        }
+    }
+}
+
+/// Rounds XY coordinates to a given precision.
+///
+/// Optionally removes consecutive duplicate vertices in linestrings and rings
+/// resulting from rounding.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Precision, RoundPrecision};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = RoundPrecision::new(WktWriter::new(&mut out), Precision::Decimals(1))
+///     .remove_duplicates();
+/// WktStr("LINESTRING(1.04 2,1.01 2,3.66 4)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(1 2,3.7 4)");
+/// ```
+pub struct RoundPrecision<P> {
+    inner: P,
+    precision: Precision,
+    dedup: bool,
+    /// Open linestring (`tagged`, `idx`)
+    linestring: Option<(bool, usize)>,
+    coords: CoordSeq,
+}
+
+impl<P> RoundPrecision<P> {
+    pub fn new(inner: P, precision: Precision) -> Self {
+        RoundPrecision {
+            inner,
+            precision,
+            dedup: false,
+            linestring: None,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Remove consecutive duplicate vertices after rounding
+    pub fn remove_duplicates(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for RoundPrecision<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, polygon, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        let (x, y) = (self.precision.round(x), self.precision.round(y));
+        if self.linestring.is_some() {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let (x, y) = (self.precision.round(x), self.precision.round(y));
+        if self.linestring.is_some() {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        if self.dedup {
+            self.linestring = Some((tagged, idx));
+            Ok(())
+        } else {
+            self.inner.linestring_begin(tagged, size, idx)
+        }
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if self.linestring.take().is_some() {
+            self.coords.dedup();
+            self.inner
+                .linestring_begin(tagged, self.coords.len(), idx)?;
+            self.coords.emit(&mut self.inner)?;
+        }
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for RoundPrecision<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for RoundPrecision<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round() {
+        assert_eq!(Precision::Decimals(2).round(8.123456), 8.12);
+        assert_eq!(Precision::Decimals(0).round(-2.5), -3.0);
+        assert_eq!(Precision::Grid(5.0).round(12.6), 15.0);
+        assert_eq!(Precision::Grid(0.5).round(1.3), 1.5);
+    }
+
+    #[test]
+    fn remove_duplicates() {
+        let mut coords = CoordSeq::default();
+        for (idx, (x, y)) in [(0.0, 0.0), (0.0, 0.0), (1.0, 0.0), (1.0, 0.0)]
+            .iter()
+            .enumerate()
+        {
+            coords.push_xy(*x, *y, idx);
+        }
+        coords.dedup();
+        assert_eq!(coords.xy, vec![(0.0, 0.0), (1.0, 0.0)]);
+
+        let mut coords = CoordSeq::default();
+        coords.push_xy(1.0, 1.0, 0);
+        coords.push_xy(1.0, 1.0, 1);
+        coords.dedup();
+        assert_eq!(coords.len(), 2);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Reports the number of processed features while passing all calls on unchanged.
+///
+/// `report` is called with the number of features processed so far after every
+/// [interval](Progress::interval) features and at the end of the dataset.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::Progress;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {"name": "Paris"}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}},
+///     {"type": "Feature", "properties": {"name": "Rome"}, "geometry": {"type": "Point", "coordinates": [12.5, 41.9]}}
+/// ]}"#;
+/// let mut counts = Vec::new();
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor =
+///     Progress::new(GeoJsonWriter::new(&mut out), |count| counts.push(count)).interval(2);
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// drop(processor);
+/// assert_eq!(counts, vec![2, 3]);
+/// ```
+pub struct Progress<P, F: FnMut(u64)> {
+    inner: P,
+    report: F,
+    interval: u64,
+    count: u64,
+}
+
+impl<P, F: FnMut(u64)> Progress<P, F> {
+    pub fn new(inner: P, report: F) -> Self {
+        Progress {
+            inner,
+            report,
+            interval: 1000,
+            count: 0,
+        }
+    }
+    /// Report every `interval` features (default: 1000), only at the end of the dataset with 0
+    pub fn interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Number of processed features
+    pub fn feature_count(&self) -> u64 {
+        self.count
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor, F: FnMut(u64)> GeomProcessor for Progress<P, F> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor, F: FnMut(u64)> PropertyProcessor for Progress<P, F> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor, F: FnMut(u64)> FeatureProcessor for Progress<P, F> {
+    forward_processor_methods!(inner; schema, crs, metadata, warning, fid, properties, geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.inner.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        (self.report)(self.count);
+        self.inner.dataset_end()
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.count += 1;
+        if self.interval > 0 && self.count.is_multiple_of(self.interval) {
+            (self.report)(self.count);
+        }
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ProcessorSink;
+
+    #[test]
+    fn report_interval() -> Result<()> {
+        let mut counts = Vec::new();
+        {
+            let mut progress =
+                Progress::new(ProcessorSink::new(), |count| counts.push(count)).interval(3);
+            progress.dataset_begin(None)?;
+            for idx in 0..7 {
+                progress.feature_begin(idx)?;
+                progress.feature_end(idx)?;
+            }
+            progress.dataset_end()?;
+            assert_eq!(progress.feature_count(), 7);
+        }
+        assert_eq!(counts, vec![3, 6, 7]);
+        Ok(())
+    }
+
+    #[test]
+    fn report_at_end() -> Result<()> {
+        let mut counts = Vec::new();
+        {
+            let mut progress =
+                Progress::new(ProcessorSink::new(), |count| counts.push(count)).interval(0);
+            progress.dataset_begin(None)?;
+            for idx in 0..3 {
+                progress.feature_begin(idx)?;
+                progress.feature_end(idx)?;
+            }
+            progress.dataset_end()?;
+        }
+        assert_eq!(counts, vec![3]);
+        Ok(())
+    }
+}
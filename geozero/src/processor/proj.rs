@@ -0,0 +1,153 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::CoordSeq;
+use crate::{Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use proj::Proj;
+
+/// Reprojects XY coordinates with [PROJ](https://proj.org/).
+///
+/// Coordinate sequences (e.g. linestrings and rings) are buffered and
+/// transformed with a single call to PROJ. The CRS and SRID of the source are
+/// replaced by the target CRS.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::processor::ProjTransform;
+/// use geozero::geojson::GeoJsonWriter;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = ProjTransform::new(GeoJsonWriter::new(&mut out), "EPSG:2056", "EPSG:4326")?;
+/// ```
+pub struct ProjTransform<P> {
+    inner: P,
+    proj: Proj,
+    /// Target CRS
+    target: Option<Crs>,
+    coords: CoordSeq,
+}
+
+impl<P> ProjTransform<P> {
+    /// Transformation from CRS `from` to CRS `to`.
+    ///
+    /// CRS definitions can be given as authority codes like `EPSG:4326`,
+    /// PROJ strings or WKT. Geographic coordinates are in lon/lat order.
+    pub fn new(inner: P, from: &str, to: &str) -> Result<Self> {
+        let proj =
+            Proj::new_known_crs(from, to, None).map_err(|e| GeozeroError::Crs(e.to_string()))?;
+        let mut transform = Self::with_proj(inner, proj);
+        transform.target = Crs::from_definition(to);
+        Ok(transform)
+    }
+    /// Transformation with a PROJ pipeline definition.
+    pub fn from_pipeline(inner: P, definition: &str) -> Result<Self> {
+        let proj = Proj::new(definition).map_err(|e| GeozeroError::Crs(e.to_string()))?;
+        Ok(Self::with_proj(inner, proj))
+    }
+    /// Transformation with a preconfigured `Proj` instance.
+    pub fn with_proj(inner: P, proj: Proj) -> Self {
+        ProjTransform {
+            inner,
+            proj,
+            target: None,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Set target CRS passed on instead of the source CRS, e.g. for pipeline definitions
+    pub fn target_crs(mut self, crs: Crs) -> Self {
+        self.target = Some(crs);
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> ProjTransform<P> {
+    /// Transform buffered coordinates and pass them on
+    fn flush(&mut self) -> Result<()> {
+        if self.coords.xy.is_empty() {
+            return Ok(());
+        }
+        self.proj
+            .convert_array(&mut self.coords.xy)
+            .map_err(|e| GeozeroError::Transform(e.to_string()))?;
+        self.coords.emit(&mut self.inner)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for ProjTransform<P> {
+    forward_processor_methods!(inner; dimensions);
+
+    fn srid(&mut self, _srid: Option<i32>) -> Result<()> {
+        self.inner.srid(self.target.as_ref().and_then(Crs::srid))
+    }
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.coords.push_xy(x, y, idx);
+        Ok(())
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+        Ok(())
+    }
+    flush_and_forward!(geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for ProjTransform<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for ProjTransform<P> {
+    forward_processor_methods!(inner; dataset, schema, warning, feature, properties);
+
+    fn crs(&mut self, _crs: &Crs) -> Result<()> {
+        // Source CRS is replaced by the target CRS
+        match &self.target {
+            Some(target) => self.inner.crs(target),
+            None => Ok(()),
+        }
+    }
+
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        self.inner.metadata(&metadata.without_extent())
+    }
+
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.inner.geometry_begin()
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        self.flush()?;
+        self.inner.geometry_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::wkt::{WktStr, WktWriter};
+    use crate::GeozeroGeometry;
+
+    #[test]
+    fn reproject() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = ProjTransform::new(WktWriter::new(&mut out), "EPSG:2056", "EPSG:4326")?;
+        WktStr("LINESTRING(2600000 1200000,2700000 1200000)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("LINESTRING(7.43"), "{}", wkt);
+        Ok(())
+    }
+}
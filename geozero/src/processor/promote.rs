@@ -0,0 +1,213 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Promotes single geometries to their Multi* counterparts.
+///
+/// Point, LineString and Polygon become MultiPoint, MultiLineString and MultiPolygon.
+/// CircularString and CompoundCurve become MultiCurve, CurvePolygon becomes MultiSurface.
+/// Members of geometry collections are left unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::PromoteToMulti;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = PromoteToMulti::new(WktWriter::new(&mut out));
+/// WktStr("POLYGON((0 0,1 0,1 1,0 0))").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "MULTIPOLYGON(((0 0,1 0,1 1,0 0)))");
+/// ```
+pub struct PromoteToMulti<P> {
+    inner: P,
+    /// Nesting level of geometries with tagged members
+    depth: usize,
+}
+
+impl<P> PromoteToMulti<P> {
+    pub fn new(inner: P) -> Self {
+        PromoteToMulti { inner, depth: 0 }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for PromoteToMulti<P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, multipoint,
+        multilinestring, multipolygon, surfaces);
+
+    fn empty_point(&mut self, idx: usize) -> Result<()> {
+        if self.depth == 0 {
+            self.inner.multipoint_begin(0, idx)?;
+            self.inner.multipoint_end(idx)
+        } else {
+            self.inner.empty_point(idx)
+        }
+    }
+    fn point_begin(&mut self, idx: usize) -> Result<()> {
+        if self.depth == 0 {
+            self.inner.multipoint_begin(1, idx)
+        } else {
+            self.inner.point_begin(idx)
+        }
+    }
+    fn point_end(&mut self, idx: usize) -> Result<()> {
+        if self.depth == 0 {
+            self.inner.multipoint_end(idx)
+        } else {
+            self.inner.point_end(idx)
+        }
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        if tagged && self.depth == 0 {
+            self.inner.multilinestring_begin(1, idx)?;
+            self.inner.linestring_begin(false, size, 0)
+        } else {
+            self.inner.linestring_begin(tagged, size, idx)
+        }
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if tagged && self.depth == 0 {
+            self.inner.linestring_end(false, 0)?;
+            self.inner.multilinestring_end(idx)
+        } else {
+            self.inner.linestring_end(tagged, idx)
+        }
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        if tagged && self.depth == 0 {
+            self.inner.multipolygon_begin(1, idx)?;
+            self.inner.polygon_begin(false, size, 0)
+        } else {
+            self.inner.polygon_begin(tagged, size, idx)
+        }
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if tagged && self.depth == 0 {
+            self.inner.polygon_end(false, 0)?;
+            self.inner.multipolygon_end(idx)
+        } else {
+            self.inner.polygon_end(tagged, idx)
+        }
+    }
+    fn geometrycollection_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        self.depth += 1;
+        self.inner.geometrycollection_begin(size, idx)
+    }
+    fn geometrycollection_end(&mut self, idx: usize) -> Result<()> {
+        self.depth -= 1;
+        self.inner.geometrycollection_end(idx)
+    }
+    fn circularstring_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        if self.depth == 0 {
+            self.inner.multicurve_begin(1, idx)?;
+            self.inner.circularstring_begin(size, 0)
+        } else {
+            self.inner.circularstring_begin(size, idx)
+        }
+    }
+    fn circularstring_end(&mut self, idx: usize) -> Result<()> {
+        if self.depth == 0 {
+            self.inner.circularstring_end(0)?;
+            self.inner.multicurve_end(idx)
+        } else {
+            self.inner.circularstring_end(idx)
+        }
+    }
+    fn compoundcurve_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        self.depth += 1;
+        if self.depth == 1 {
+            self.inner.multicurve_begin(1, idx)?;
+            self.inner.compoundcurve_begin(size, 0)
+        } else {
+            self.inner.compoundcurve_begin(size, idx)
+        }
+    }
+    fn compoundcurve_end(&mut self, idx: usize) -> Result<()> {
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.inner.compoundcurve_end(0)?;
+            self.inner.multicurve_end(idx)
+        } else {
+            self.inner.compoundcurve_end(idx)
+        }
+    }
+    fn curvepolygon_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        self.depth += 1;
+        if self.depth == 1 {
+            self.inner.multisurface_begin(1, idx)?;
+            self.inner.curvepolygon_begin(size, 0)
+        } else {
+            self.inner.curvepolygon_begin(size, idx)
+        }
+    }
+    fn curvepolygon_end(&mut self, idx: usize) -> Result<()> {
+        self.depth -= 1;
+        if self.depth == 0 {
+            self.inner.curvepolygon_end(0)?;
+            self.inner.multisurface_end(idx)
+        } else {
+            self.inner.curvepolygon_end(idx)
+        }
+    }
+    fn multicurve_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        self.depth += 1;
+        self.inner.multicurve_begin(size, idx)
+    }
+    fn multicurve_end(&mut self, idx: usize) -> Result<()> {
+        self.depth -= 1;
+        self.inner.multicurve_end(idx)
+    }
+    fn multisurface_begin(&mut self, size: usize, idx: usize) -> Result<()> {
+        self.depth += 1;
+        self.inner.multisurface_begin(size, idx)
+    }
+    fn multisurface_end(&mut self, idx: usize) -> Result<()> {
+        self.depth -= 1;
+        self.inner.multisurface_end(idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for PromoteToMulti<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for PromoteToMulti<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-wkt")]
+mod test {
+    use super::*;
+    use crate::wkt::{WktStr, WktWriter};
+    use crate::GeozeroGeometry;
+
+    fn promote(wkt: &str) -> Result<String> {
+        let mut out: Vec<u8> = Vec::new();
+        WktStr(wkt).process_geom(&mut PromoteToMulti::new(WktWriter::new(&mut out)))?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn promote_geometries() -> Result<()> {
+        assert_eq!(promote("POINT(1 2)")?, "MULTIPOINT(1 2)");
+        assert_eq!(
+            promote("LINESTRING(1 2,3 4)")?,
+            "MULTILINESTRING((1 2,3 4))"
+        );
+        assert_eq!(promote("MULTIPOINT(1 2,3 4)")?, "MULTIPOINT(1 2,3 4)");
+        assert_eq!(
+            promote("GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(1 2,3 4))")?,
+            "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(1 2,3 4))"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::error::Result;
+use crate::{
+    ColumnDef, ColumnValue, DuplicateKeyPolicy, FeatureProcessor, GeomProcessor, OwnedColumnValue,
+    PropertyProcessor,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Renames, drops, reorders and adds feature properties.
+///
+/// Properties of a feature are collected and passed on at `properties_end`.
+/// The schema passed to `schema` is transformed accordingly. Properties with equal
+/// output names, e.g. after renaming or adding a constant, are merged with the later
+/// value winning.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::PropertyMapper;
+/// use geozero::{GeozeroDatasource, OwnedColumnValue};
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "Bern", "id": 1},
+///     "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut mapper = PropertyMapper::new(GeoJsonWriter::new(&mut out))
+///     .rename("name", "city")
+///     .drop("id")
+///     .constant("country", OwnedColumnValue::String("CH".to_string()));
+/// GeoJson(geojson).process(&mut mapper).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains(r#""properties": {"city": "Bern", "country": "CH"}"#));
+/// ```
+pub struct PropertyMapper<P> {
+    inner: P,
+    renames: HashMap<String, String>,
+    dropped: HashSet<String>,
+    /// Columns passed on (all if `None`)
+    selected: Option<HashSet<String>>,
+    order: Vec<String>,
+    constants: Vec<(String, OwnedColumnValue)>,
+    /// Collected properties of current feature
+    properties: Vec<(String, OwnedColumnValue)>,
+    in_properties: bool,
+}
+
+impl<P> PropertyMapper<P> {
+    pub fn new(inner: P) -> Self {
+        PropertyMapper {
+            inner,
+            renames: HashMap::new(),
+            dropped: HashSet::new(),
+            selected: None,
+            order: Vec::new(),
+            constants: Vec::new(),
+            properties: Vec::new(),
+            in_properties: false,
+        }
+    }
+    /// Rename column `from` to `to`
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.renames.insert(from.to_string(), to.to_string());
+        self
+    }
+    /// Drop column (original name)
+    pub fn drop(mut self, name: &str) -> Self {
+        self.dropped.insert(name.to_string());
+        self
+    }
+    /// Pass only listed columns (original names) and drop all others
+    pub fn select(mut self, names: &[&str]) -> Self {
+        self.selected = Some(names.iter().map(|name| name.to_string()).collect());
+        self
+    }
+    /// Pass listed columns (output names) first in given order, followed by all other columns
+    pub fn order(mut self, names: &[&str]) -> Self {
+        self.order = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+    /// Add column with a constant value to each feature
+    pub fn constant(mut self, name: &str, value: OwnedColumnValue) -> Self {
+        self.constants.push((name.to_string(), value));
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume mapper and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    /// Output name of column, `None` if dropped
+    fn output_name<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if let Some(selected) = &self.selected {
+            if !selected.contains(name) {
+                return None;
+            }
+        }
+        if self.dropped.contains(name) {
+            None
+        } else {
+            Some(self.renames.get(name).map(String::as_str).unwrap_or(name))
+        }
+    }
+    /// Sort key of output column
+    fn position(&self, name: &str) -> usize {
+        self.order
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or(self.order.len())
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for PropertyMapper<P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for PropertyMapper<P> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        let name = match self.output_name(name) {
+            Some(name) => name.to_string(),
+            None => return Ok(false),
+        };
+        if self.in_properties {
+            self.properties.push((name, value.into()));
+            Ok(false)
+        } else {
+            // Properties outside of `properties_begin`/`properties_end` are not reordered
+            self.inner.property(idx, &name, value)
+        }
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for PropertyMapper<P> {
+    forward_processor_methods!(inner; dataset, crs, metadata, warning, feature, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let mut output: Vec<(String, ColumnDef)> = columns
+            .iter()
+            .filter_map(|col| {
+                self.output_name(&col.name).map(|name| {
+                    let col = ColumnDef {
+                        name: name.to_string(),
+                        ..col.clone()
+                    };
+                    (col.name.clone(), col)
+                })
+            })
+            .collect();
+        for (name, value) in &self.constants {
+            let mut col = ColumnDef::new(name, value.as_column_value().column_type());
+            col.nullable = *value == OwnedColumnValue::Null;
+            output.push((col.name.clone(), col));
+        }
+        DuplicateKeyPolicy::LastWins.apply(&mut output)?;
+        let mut output: Vec<ColumnDef> = output.into_iter().map(|(_, col)| col).collect();
+        output.sort_by_key(|col| self.position(&col.name));
+        self.inner.schema(&output)
+    }
+    fn properties_begin(&mut self) -> Result<()> {
+        self.in_properties = true;
+        self.properties.clear();
+        self.inner.properties_begin()
+    }
+    fn properties_end(&mut self) -> Result<()> {
+        self.in_properties = false;
+        let mut properties = std::mem::take(&mut self.properties);
+        properties.extend(self.constants.iter().cloned());
+        DuplicateKeyPolicy::LastWins.apply(&mut properties)?;
+        // Stable sort keeps input order of unlisted columns
+        properties.sort_by_key(|(name, _)| self.position(name));
+        for (idx, (name, value)) in properties.iter().enumerate() {
+            if self.inner.property(idx, name, &value.as_column_value())? {
+                break;
+            }
+        }
+        self.properties = properties;
+        self.inner.properties_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnType;
+
+    /// Records passed properties and schema
+    #[derive(Default)]
+    struct Recorder {
+        columns: Vec<String>,
+        properties: Vec<String>,
+    }
+
+    impl GeomProcessor for Recorder {}
+
+    impl PropertyProcessor for Recorder {
+        fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+            self.properties.push(format!("{}:{}={}", idx, name, value));
+            Ok(false)
+        }
+    }
+
+    impl FeatureProcessor for Recorder {
+        fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+            self.columns = columns.iter().map(|col| col.name.clone()).collect();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mapping() -> Result<()> {
+        let mut mapper = PropertyMapper::new(Recorder::default())
+            .rename("name", "city")
+            .drop("fid")
+            .order(&["country", "population"])
+            .constant("country", OwnedColumnValue::String("CH".to_string()));
+        mapper.schema(&[
+            ColumnDef::new("fid", ColumnType::Long),
+            ColumnDef::new("name", ColumnType::String),
+            ColumnDef::new("population", ColumnType::Int),
+        ])?;
+        mapper.feature_begin(0)?;
+        mapper.properties_begin()?;
+        mapper.property(0, "fid", &ColumnValue::Long(7))?;
+        mapper.property(1, "name", &ColumnValue::String("Bern"))?;
+        mapper.property(2, "population", &ColumnValue::Int(134591))?;
+        mapper.properties_end()?;
+        mapper.feature_end(0)?;
+
+        let recorder = mapper.into_inner();
+        assert_eq!(recorder.columns, vec!["country", "population", "city"]);
+        assert_eq!(
+            recorder.properties,
+            vec!["0:country=CH", "1:population=134591", "2:city=Bern"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn select() -> Result<()> {
+        let mut mapper = PropertyMapper::new(Recorder::default())
+            .select(&["population", "name"])
+            .order(&["population", "name"]);
+        mapper.schema(&[
+            ColumnDef::new("fid", ColumnType::Long),
+            ColumnDef::new("name", ColumnType::String),
+            ColumnDef::new("population", ColumnType::Int),
+        ])?;
+        mapper.feature_begin(0)?;
+        mapper.properties_begin()?;
+        mapper.property(0, "fid", &ColumnValue::Long(7))?;
+        mapper.property(1, "name", &ColumnValue::String("Bern"))?;
+        mapper.property(2, "population", &ColumnValue::Int(134591))?;
+        mapper.properties_end()?;
+        mapper.feature_end(0)?;
+
+        let recorder = mapper.into_inner();
+        assert_eq!(recorder.columns, vec!["population", "name"]);
+        assert_eq!(
+            recorder.properties,
+            vec!["0:population=134591", "1:name=Bern"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn merged_names() -> Result<()> {
+        let mut mapper = PropertyMapper::new(Recorder::default())
+            .rename("name_de", "name")
+            .constant("country", OwnedColumnValue::String("CH".to_string()));
+        mapper.schema(&[
+            ColumnDef::new("name", ColumnType::String),
+            ColumnDef::new("name_de", ColumnType::String),
+            ColumnDef::new("country", ColumnType::String),
+        ])?;
+        mapper.feature_begin(0)?;
+        mapper.properties_begin()?;
+        mapper.property(0, "name", &ColumnValue::String("Berne"))?;
+        mapper.property(1, "name_de", &ColumnValue::String("Bern"))?;
+        mapper.property(2, "country", &ColumnValue::String("Schweiz"))?;
+        mapper.properties_end()?;
+        mapper.feature_end(0)?;
+
+        let recorder = mapper.into_inner();
+        assert_eq!(recorder.columns, vec!["name", "country"]);
+        assert_eq!(recorder.properties, vec!["0:name=Bern", "1:country=CH"]);
+        Ok(())
+    }
+}
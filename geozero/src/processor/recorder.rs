@@ -0,0 +1,123 @@
+use crate::error::Result;
+use crate::processor::{Event, FeatureBuffer};
+use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, ProcessorSink, PropertyProcessor};
+
+/// Records all processor calls with their arguments.
+///
+/// Used in tests for asserting the exact sequence of calls made by a reader.
+/// Coordinates are requested in XY unless other dimensions are set with
+/// [with_dims](ProcessorRecorder::with_dims). Schema and CRS calls are not recorded.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Event, ProcessorRecorder};
+/// use geozero::wkt::WktStr;
+/// use geozero::GeozeroGeometry;
+///
+/// let mut recorder = ProcessorRecorder::new();
+/// WktStr("POINT(1 2)").process_geom(&mut recorder).unwrap();
+/// assert_eq!(
+///     recorder.events(),
+///     &[Event::PointBegin(0), Event::Xy(1.0, 2.0, 0), Event::PointEnd(0)]
+/// );
+/// ```
+pub struct ProcessorRecorder {
+    buffer: FeatureBuffer<ProcessorSink>,
+    dims: CoordDimensions,
+}
+
+impl ProcessorRecorder {
+    pub fn new() -> Self {
+        let mut buffer = FeatureBuffer::new(ProcessorSink::new());
+        buffer.record();
+        ProcessorRecorder {
+            buffer,
+            dims: CoordDimensions::xy(),
+        }
+    }
+    /// Request coordinates with dimensions `dims`
+    pub fn with_dims(mut self, dims: CoordDimensions) -> Self {
+        self.dims = dims;
+        self
+    }
+    /// Recorded calls
+    pub fn events(&self) -> &[Event] {
+        self.buffer.events()
+    }
+    /// Consume recorder and return recorded calls
+    pub fn into_events(mut self) -> Vec<Event> {
+        self.buffer.take()
+    }
+}
+
+impl Default for ProcessorRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeomProcessor for ProcessorRecorder {
+    forward_processor_methods!(buffer; srid, xy, coordinate, empty_point, geometries);
+
+    fn dimensions(&self) -> CoordDimensions {
+        self.dims
+    }
+    fn multi_dim(&self) -> bool {
+        self.dims.z || self.dims.m || self.dims.t || self.dims.tm
+    }
+}
+
+impl PropertyProcessor for ProcessorRecorder {
+    forward_processor_methods!(buffer; property);
+}
+
+impl FeatureProcessor for ProcessorRecorder {
+    forward_processor_methods!(buffer; feature, properties, geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.buffer
+            .push(Event::DatasetBegin(name.map(ToString::to_string)));
+        Ok(())
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.buffer.push(Event::DatasetEnd);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJson;
+    use crate::{GeozeroDatasource, OwnedColumnValue};
+
+    #[test]
+    fn record_features() -> Result<()> {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95, 540]}}
+        ]}"#;
+        let mut recorder = ProcessorRecorder::new().with_dims(CoordDimensions::xyz());
+        GeoJson(geojson).process(&mut recorder)?;
+        #[rustfmt::skip]
+        assert_eq!(
+            recorder.into_events(),
+            vec![
+                Event::DatasetBegin(None),
+                Event::FeatureBegin(0),
+                    Event::PropertiesBegin,
+                        Event::Property(0, "name".to_string(), OwnedColumnValue::String("Bern".to_string())),
+                    Event::PropertiesEnd,
+                    Event::GeometryBegin,
+                        Event::PointBegin(0),
+                            Event::Coordinate(7.44, 46.95, Some(540.0), None, None, None, 0),
+                        Event::PointEnd(0),
+                    Event::GeometryEnd,
+                Event::FeatureEnd(0),
+                Event::DatasetEnd,
+            ]
+        );
+        Ok(())
+    }
+}
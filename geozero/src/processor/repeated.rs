@@ -0,0 +1,172 @@
+use crate::error::Result;
+use crate::processor::CoordSeq;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Removes repeated vertices from linestrings and polygon rings.
+///
+/// A vertex is removed when it lies within `epsilon` of the previous kept vertex,
+/// with `0.0` removing identical vertices only. First and last vertices are kept,
+/// so rings stay closed. Rings which would end up with less than 4 vertices are
+/// passed on unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::RemoveRepeatedPoints;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = RemoveRepeatedPoints::new(WktWriter::new(&mut out), 0.1);
+/// WktStr("LINESTRING(0 0,0 0,0.05 0,1 0,1 0)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(0 0,1 0)");
+/// ```
+pub struct RemoveRepeatedPoints<P> {
+    inner: P,
+    /// Tolerance in source units
+    epsilon: f64,
+    /// Open linestring
+    in_linestring: bool,
+    coords: CoordSeq,
+}
+
+impl<P> RemoveRepeatedPoints<P> {
+    pub fn new(inner: P, epsilon: f64) -> Self {
+        RemoveRepeatedPoints {
+            inner,
+            epsilon,
+            in_linestring: false,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> RemoveRepeatedPoints<P> {
+    /// Remove repeated vertices of buffered linestring and pass it on
+    fn flush(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        let n = self.coords.len();
+        let closed = n > 1 && self.coords.xy[0] == self.coords.xy[n - 1];
+        if closed && n >= 4 {
+            let original = self.coords.clone();
+            self.coords.dedup_within(self.epsilon);
+            if self.coords.len() < 4 {
+                // Keep collapsing rings unchanged
+                self.coords = original;
+            }
+        } else if !closed {
+            self.coords.dedup_within(self.epsilon);
+        }
+        self.inner
+            .linestring_begin(tagged, self.coords.len(), idx)?;
+        self.coords.emit(&mut self.inner)?;
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for RemoveRepeatedPoints<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, polygon, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.in_linestring {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.in_linestring {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.in_linestring = true;
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        self.in_linestring = false;
+        self.flush(tagged, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for RemoveRepeatedPoints<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for RemoveRepeatedPoints<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Collects XY coordinates
+    #[derive(Default)]
+    struct Coords(Vec<(f64, f64)>);
+
+    impl GeomProcessor for Coords {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.push((x, y));
+            Ok(())
+        }
+    }
+
+    fn process(epsilon: f64, xy: &[(f64, f64)]) -> Result<Vec<(f64, f64)>> {
+        let mut processor = RemoveRepeatedPoints::new(Coords::default(), epsilon);
+        processor.linestring_begin(true, xy.len(), 0)?;
+        for (idx, (x, y)) in xy.iter().enumerate() {
+            processor.xy(*x, *y, idx)?;
+        }
+        processor.linestring_end(true, 0)?;
+        Ok(processor.into_inner().0)
+    }
+
+    #[test]
+    fn remove_repeated() -> Result<()> {
+        assert_eq!(
+            process(0.0, &[(0.0, 0.0), (0.0, 0.0), (1.0, 0.0), (1.0, 0.0)])?,
+            vec![(0.0, 0.0), (1.0, 0.0)]
+        );
+        // Closing vertex is kept
+        assert_eq!(
+            process(
+                0.5,
+                &[
+                    (0.0, 0.0),
+                    (2.0, 0.0),
+                    (2.0, 2.0),
+                    (0.0, 2.0),
+                    (0.1, 0.1),
+                    (0.0, 0.0)
+                ]
+            )?,
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]
+        );
+        // Collapsing ring is unchanged
+        let ring = [(0.0, 0.0), (0.1, 0.0), (0.1, 0.1), (0.0, 0.0)];
+        assert_eq!(process(1.0, &ring)?, ring.to_vec());
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use crate::error::{GeozeroError, Result};
+use crate::processor::CoordSeq;
+#[cfg(feature = "with-geodesy")]
+use crate::processor::GeodesyOps;
+use crate::{AxisOrder, Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+#[cfg(feature = "with-proj")]
+use proj::Proj;
+
+/// Reprojection backend converting XY values
+enum Converter {
+    #[cfg(feature = "with-proj")]
+    Proj(Proj),
+    #[cfg(feature = "with-geodesy")]
+    Geodesy(GeodesyOps),
+}
+
+impl Converter {
+    #[cfg_attr(not(feature = "with-proj"), allow(unused_variables))]
+    fn new(from: &Crs, to: &Crs, use_geodesy: bool) -> Result<Self> {
+        #[cfg(feature = "with-proj")]
+        if !use_geodesy {
+            let proj = Proj::new_known_crs(&from.definition(), &to.definition(), None)
+                .map_err(|e| GeozeroError::Crs(e.to_string()))?;
+            return Ok(Converter::Proj(proj));
+        }
+        // geodesy only supports CRS with EPSG codes
+        let epsg = |crs: &Crs| {
+            crs.epsg()
+                .map(|code| format!("EPSG:{}", code))
+                .ok_or_else(|| GeozeroError::Crs(format!("`{}`", crs)))
+        };
+        let (from, to) = (epsg(from)?, epsg(to)?);
+        #[cfg(feature = "with-geodesy")]
+        return Ok(Converter::Geodesy(GeodesyOps::new(&from, &to)?));
+        #[cfg(not(feature = "with-geodesy"))]
+        Err(GeozeroError::Crs(format!(
+            "reprojection from `{}` to `{}` requires feature `with-geodesy`",
+            from, to
+        )))
+    }
+    fn convert(&mut self, xy: &mut [(f64, f64)]) -> Result<()> {
+        match self {
+            #[cfg(feature = "with-proj")]
+            Converter::Proj(proj) => {
+                proj.convert_array(xy)
+                    .map_err(|e| GeozeroError::Transform(e.to_string()))?;
+                Ok(())
+            }
+            #[cfg(feature = "with-geodesy")]
+            Converter::Geodesy(ops) => ops.convert(xy),
+        }
+    }
+}
+
+/// Reprojects coordinates to an output CRS, if it differs from the source CRS.
+///
+/// The source CRS is taken from the `crs` call of the datasource, or from the
+/// SRID of the first geometry for datasources without CRS information. Input
+/// without any CRS information is passed unchanged, unless a source CRS is set
+/// with [Reproject::source_crs]. The output CRS replaces the
+/// source CRS and SRID.
+///
+/// Input in north-first axis order declared by its CRS (e.g. `urn:ogc:def:crs:EPSG::4326`,
+/// see [Crs::coordinate_axis_order]) is swapped to easting or longitude first. Output
+/// coordinates follow the axis order declared by the output CRS.
+///
+/// Coordinates are transformed with [PROJ](https://proj.org/) if the feature
+/// `with-proj` is enabled, otherwise with the pure Rust geodesy backend, which
+/// supports the CRS listed for [GeodesyTransform](crate::processor::GeodesyTransform).
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::processor::OutputCrs;
+/// use geozero::{Crs, GeozeroDatasource};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut writer = GeoJsonWriter::new(&mut out).output_crs(Crs::wgs84());
+/// gpkg_layer.process(&mut writer)?;
+/// ```
+pub struct Reproject<P> {
+    inner: P,
+    output: Crs,
+    /// Source CRS overriding the CRS of the input
+    source: Option<Crs>,
+    use_geodesy: bool,
+    /// Source CRS of dataset is known
+    source_known: bool,
+    /// Converter from source to output CRS (`None` for unchanged coordinates)
+    converter: Option<Converter>,
+    /// Source coordinates are north-first
+    swap_input: bool,
+    /// Output coordinates are north-first
+    swap_output: bool,
+    coords: CoordSeq,
+}
+
+impl<P> Reproject<P> {
+    pub fn new(inner: P, output_crs: Crs) -> Self {
+        Reproject {
+            inner,
+            source: None,
+            use_geodesy: cfg!(not(feature = "with-proj")),
+            source_known: false,
+            converter: None,
+            swap_input: false,
+            swap_output: output_crs.coordinate_axis_order() == AxisOrder::NorthEast,
+            output: output_crs,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Assume input in `crs`, ignoring the CRS and SRID of the datasource
+    pub fn source_crs(mut self, crs: Crs) -> Self {
+        self.source = Some(crs);
+        self
+    }
+    /// Use the geodesy backend, also if PROJ is available
+    #[cfg(feature = "with-geodesy")]
+    pub fn geodesy(mut self) -> Self {
+        self.use_geodesy = true;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    /// Set source CRS and create converter if it differs from the output CRS
+    fn set_source(&mut self, source: &Crs) -> Result<()> {
+        self.source_known = true;
+        self.swap_input = source.coordinate_axis_order() == AxisOrder::NorthEast;
+        let same = match (source.epsg(), self.output.epsg()) {
+            (Some(a), Some(b)) => a == b,
+            _ => *source == self.output,
+        };
+        self.converter = if same {
+            None
+        } else {
+            Some(Converter::new(source, &self.output, self.use_geodesy)?)
+        };
+        Ok(())
+    }
+}
+
+impl<P: GeomProcessor> Reproject<P> {
+    /// Transform buffered coordinates and pass them on
+    fn flush(&mut self) -> Result<()> {
+        if let Some(converter) = &mut self.converter {
+            if !self.coords.xy.is_empty() {
+                converter.convert(&mut self.coords.xy)?;
+                if self.swap_output {
+                    for (x, y) in &mut self.coords.xy {
+                        std::mem::swap(x, y);
+                    }
+                }
+                self.coords.emit(&mut self.inner)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reprojection of processor input
+pub trait OutputCrs: Sized {
+    /// Reproject input to `crs` before passing it to this processor
+    fn output_crs(self, crs: Crs) -> Reproject<Self> {
+        Reproject::new(self, crs)
+    }
+}
+
+impl<P: GeomProcessor> OutputCrs for P {}
+
+impl<P: GeomProcessor> GeomProcessor for Reproject<P> {
+    forward_processor_methods!(inner; dimensions);
+
+    fn srid(&mut self, srid: Option<i32>) -> Result<()> {
+        if !self.source_known {
+            if let Some(source) = self.source.clone().or_else(|| srid.map(Crs::Epsg)) {
+                self.set_source(&source)?;
+            }
+        }
+        self.inner.srid(self.output.srid())
+    }
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        let (x, y) = if self.swap_input { (y, x) } else { (x, y) };
+        if self.converter.is_some() {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else if self.swap_output {
+            self.inner.xy(y, x, idx)
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let (x, y) = if self.swap_input { (y, x) } else { (x, y) };
+        if self.converter.is_some() {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else if self.swap_output {
+            self.inner.coordinate(y, x, z, m, t, tm, idx)
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    flush_and_forward!(geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for Reproject<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Reproject<P> {
+    forward_processor_methods!(inner; schema, warning, feature, properties);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.source_known = false;
+        self.converter = None;
+        self.swap_input = false;
+        if let Some(source) = self.source.clone() {
+            self.set_source(&source)?;
+        }
+        self.inner.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.inner.dataset_end()
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        // Source CRS is replaced by the output CRS
+        if self.source.is_none() {
+            self.set_source(crs)?;
+        }
+        self.inner.crs(&self.output)
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        self.inner.metadata(&metadata.without_extent())
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.inner.geometry_begin()
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        self.flush()?;
+        self.inner.geometry_end()
+    }
+}
+
+#[cfg(all(test, feature = "with-geodesy", feature = "with-wkt"))]
+mod test {
+    use super::*;
+    use crate::wkt::{WktStr, WktWriter};
+    use crate::GeozeroGeometry;
+
+    #[test]
+    fn reproject_srid() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = WktWriter::new(&mut out)
+            .output_crs(Crs::Epsg(3857))
+            .geodesy();
+        processor.srid(Some(4326))?;
+        WktStr("POINT(10 0)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("POINT(1113194.9"), "{}", wkt);
+        Ok(())
+    }
+
+    #[test]
+    fn same_crs() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = WktWriter::new(&mut out).output_crs(Crs::wgs84());
+        processor.crs(&Crs::Epsg(4326))?;
+        WktStr("POINT(10 0)").process_geom(&mut processor)?;
+        assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(10 0)");
+        Ok(())
+    }
+
+    #[test]
+    fn north_first_input() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = WktWriter::new(&mut out).output_crs(Crs::wgs84());
+        processor.crs(&Crs::from_definition("urn:ogc:def:crs:EPSG::4326").unwrap())?;
+        WktStr("POINT(47 8)").process_geom(&mut processor)?;
+        assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(8 47)");
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = WktWriter::new(&mut out)
+            .output_crs(Crs::Epsg(3857))
+            .geodesy();
+        processor.crs(&Crs::from_definition("urn:ogc:def:crs:EPSG::4326").unwrap())?;
+        WktStr("POINT(0 10)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("POINT(1113194.9"), "{}", wkt);
+        Ok(())
+    }
+
+    #[test]
+    fn source_crs() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = WktWriter::new(&mut out)
+            .output_crs(Crs::Epsg(3857))
+            .source_crs(Crs::wgs84())
+            .geodesy();
+        processor.dataset_begin(None)?;
+        // Wrong CRS of input is ignored
+        processor.crs(&Crs::Epsg(3857))?;
+        WktStr("POINT(10 0)").process_geom(&mut processor)?;
+        let wkt = std::str::from_utf8(&out).unwrap();
+        assert!(wkt.starts_with("POINT(1113194.9"), "{}", wkt);
+        Ok(())
+    }
+}
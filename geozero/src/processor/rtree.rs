@@ -0,0 +1,168 @@
+use crate::error::Result;
+use crate::processor::Extent;
+use crate::{FeatureProcessor, GeomProcessor, ProcessorSink, PropertyProcessor};
+use rstar::primitives::{GeomWithData, Rectangle};
+use rstar::RTree;
+
+/// Bounding box of a feature with its feature index
+pub type FeatureEnvelope = GeomWithData<Rectangle<[f64; 2]>, u64>;
+
+/// Builds an [RTree] of feature bounding boxes in a single pass.
+///
+/// Created with `RTreeBuilder::new()` it is used as a sink, created with `RTreeBuilder::tee(processor)`
+/// all calls are passed to the wrapped processor. Features without coordinates are not indexed.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::RTreeBuilder;
+/// use geozero::GeozeroDatasource;
+/// use rstar::AABB;
+///
+/// let mut geojson = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [2.35, 48.86]}}
+/// ]}"#);
+/// let mut builder = RTreeBuilder::new();
+/// geojson.process(&mut builder).unwrap();
+/// let rtree = builder.into_rtree();
+/// let found: Vec<u64> = rtree
+///     .locate_in_envelope_intersecting(&AABB::from_corners([5.0, 45.0], [10.0, 48.0]))
+///     .map(|envelope| envelope.data)
+///     .collect();
+/// assert_eq!(found, vec![0]);
+/// ```
+pub struct RTreeBuilder<P = ProcessorSink> {
+    inner: P,
+    envelopes: Vec<FeatureEnvelope>,
+    feature_extent: Option<Extent>,
+}
+
+impl RTreeBuilder<ProcessorSink> {
+    pub fn new() -> Self {
+        Self::tee(ProcessorSink::new())
+    }
+}
+
+impl Default for RTreeBuilder<ProcessorSink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> RTreeBuilder<P> {
+    /// Pass-through index construction
+    pub fn tee(inner: P) -> Self {
+        RTreeBuilder {
+            inner,
+            envelopes: Vec::new(),
+            feature_extent: None,
+        }
+    }
+    /// Number of indexed features
+    pub fn len(&self) -> usize {
+        self.envelopes.len()
+    }
+    /// No feature indexed
+    pub fn is_empty(&self) -> bool {
+        self.envelopes.is_empty()
+    }
+    /// Bulk load index of processed features
+    pub fn into_rtree(self) -> RTree<FeatureEnvelope> {
+        RTree::bulk_load(self.envelopes)
+    }
+    /// Bulk load index of processed features and return wrapped processor
+    pub fn into_parts(self) -> (RTree<FeatureEnvelope>, P) {
+        (RTree::bulk_load(self.envelopes), self.inner)
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    fn add_vertex(&mut self, x: f64, y: f64) {
+        match &mut self.feature_extent {
+            Some(extent) => extent.extend(x, y),
+            None => self.feature_extent = Some(Extent::from_point(x, y)),
+        }
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for RTreeBuilder<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.add_vertex(x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.add_vertex(x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for RTreeBuilder<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for RTreeBuilder<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature_extent = None;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        if let Some(extent) = self.feature_extent.take() {
+            let rect =
+                Rectangle::from_corners([extent.minx, extent.miny], [extent.maxx, extent.maxy]);
+            self.envelopes.push(GeomWithData::new(rect, idx));
+        }
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::processor::ExtentCalc;
+    use rstar::AABB;
+
+    #[test]
+    fn tee_index() -> Result<()> {
+        let mut builder = RTreeBuilder::tee(ExtentCalc::new());
+        builder.dataset_begin(None)?;
+        for (idx, (x, y)) in [(0.0, 0.0), (5.0, 5.0), (10.0, 0.0)].iter().enumerate() {
+            builder.feature_begin(idx as u64)?;
+            builder.linestring_begin(true, 2, 0)?;
+            builder.xy(*x, *y, 0)?;
+            builder.xy(x + 1.0, y + 1.0, 1)?;
+            builder.linestring_end(true, 0)?;
+            builder.feature_end(idx as u64)?;
+        }
+        builder.feature_begin(3)?;
+        builder.feature_end(3)?;
+        builder.dataset_end()?;
+        assert_eq!(builder.len(), 3);
+
+        let (rtree, calc) = builder.into_parts();
+        assert_eq!(calc.feature_count(), 4);
+        let mut found: Vec<u64> = rtree
+            .locate_in_envelope_intersecting(&AABB::from_corners([0.5, 0.5], [5.5, 5.5]))
+            .map(|envelope| envelope.data)
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![0, 1]);
+        Ok(())
+    }
+}
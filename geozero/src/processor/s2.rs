@@ -0,0 +1,180 @@
+use crate::error::Result;
+use crate::processor::{extend, Extent};
+use crate::{FeatureProcessor, GeomProcessor, ProcessorSink, PropertyProcessor};
+use s2::cellid::CellID;
+use s2::latlng::LatLng;
+use s2::rect::Rect;
+use s2::region::RegionCoverer;
+
+/// Collects the S2 cell covering of each feature.
+///
+/// Features are covered with the [s2](https://docs.rs/s2) region coverer, using the
+/// lat/lon rectangle of the feature bounding box. Coordinates are expected in WGS84
+/// lon/lat, bounding boxes crossing the antimeridian are not supported.
+/// Cells are collected as table of feature index and cell, e.g. for writing S2 index
+/// rows into a key-value store. Created with `S2Collector::new()` it is used as a sink,
+/// created with `S2Collector::tee(processor)` all calls are passed to the wrapped
+/// processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::S2Collector;
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {},
+///     "geometry": {"type": "Polygon", "coordinates": [[[5.9, 45.8], [10.5, 45.8], [10.5, 47.8], [5.9, 47.8], [5.9, 45.8]]]}}"#;
+/// let mut collector = S2Collector::new().max_level(12).max_cells(4);
+/// GeoJson(geojson).process(&mut collector).unwrap();
+/// let cells = collector.cells();
+/// assert!(cells.len() <= 4);
+/// assert!(cells.iter().all(|(idx, cell)| *idx == 0 && cell.level() <= 12));
+/// ```
+pub struct S2Collector<P = ProcessorSink> {
+    inner: P,
+    coverer: RegionCoverer,
+    extent: Option<Extent>,
+    cells: Vec<(u64, CellID)>,
+}
+
+impl S2Collector<ProcessorSink> {
+    pub fn new() -> Self {
+        Self::tee(ProcessorSink::new())
+    }
+}
+
+impl Default for S2Collector<ProcessorSink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> S2Collector<P> {
+    /// Pass-through S2 covering collector
+    pub fn tee(inner: P) -> Self {
+        S2Collector {
+            inner,
+            coverer: RegionCoverer {
+                min_level: 0,
+                max_level: 30,
+                level_mod: 1,
+                max_cells: 8,
+            },
+            extent: None,
+            cells: Vec::new(),
+        }
+    }
+    /// Minimal cell level (default: 0)
+    pub fn min_level(mut self, level: u8) -> Self {
+        self.coverer.min_level = level;
+        self
+    }
+    /// Maximal cell level (default: 30)
+    pub fn max_level(mut self, level: u8) -> Self {
+        self.coverer.max_level = level;
+        self
+    }
+    /// Maximal number of cells per feature (default: 8)
+    ///
+    /// Coverings may contain more cells, if required by `min_level`.
+    pub fn max_cells(mut self, max_cells: usize) -> Self {
+        self.coverer.max_cells = max_cells;
+        self
+    }
+    /// Feature index and covering cell, one row per cell
+    pub fn cells(&self) -> &[(u64, CellID)] {
+        &self.cells
+    }
+    /// Consume collector and return cell table
+    pub fn into_cells(self) -> Vec<(u64, CellID)> {
+        self.cells
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume collector and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for S2Collector<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for S2Collector<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for S2Collector<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.extent = None;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        if let Some(extent) = self.extent.take() {
+            let rect = Rect::from_point_pair(
+                &LatLng::from_degrees(extent.miny, extent.minx),
+                &LatLng::from_degrees(extent.maxy, extent.maxx),
+            );
+            let covering = self.coverer.covering(&rect);
+            self.cells
+                .extend(covering.0.into_iter().map(|cell| (idx, cell)));
+        }
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "with-geojson")]
+mod test {
+    use super::*;
+    use crate::geojson::GeoJson;
+    use crate::GeozeroDatasource;
+
+    #[test]
+    fn covering() -> Result<()> {
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+            {"type": "Feature", "properties": {}, "geometry": null},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[7.3, 46.9], [7.6, 47.0]]}}
+        ]}"#;
+        let mut collector = S2Collector::new().min_level(8).max_level(16);
+        GeoJson(geojson).process(&mut collector)?;
+        let cells = collector.into_cells();
+        assert!(cells.iter().all(|(idx, _)| *idx == 0 || *idx == 2));
+        assert!(cells
+            .iter()
+            .all(|(_, cell)| (8..=16).contains(&cell.level())));
+
+        let point = LatLng::from_degrees(46.95, 7.44);
+        let (_, point_cell) = cells[0];
+        assert_eq!(point_cell, CellID::from(&point).parent(16));
+        let line_cells: Vec<_> = cells.iter().filter(|(idx, _)| *idx == 2).collect();
+        assert!(!line_cells.is_empty() && line_cells.len() <= 8);
+        Ok(())
+    }
+}
@@ -0,0 +1,407 @@
+use crate::error::Result;
+use crate::processor::{CoordSeq, MemoryAccount, MemoryStats};
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Line simplification algorithm
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SimplifyAlgorithm {
+    /// Ramer-Douglas-Peucker with the tolerance as maximal distance
+    DouglasPeucker,
+    /// Visvalingam-Whyatt with the tolerance as minimal effective triangle area
+    VisvalingamWhyatt,
+}
+
+/// Simplifies linestrings and polygon rings.
+///
+/// Each linestring or ring is buffered and simplified on its own, so topology
+/// between neighbouring geometries is not preserved. Rings keep at least 4
+/// vertices and stay closed.
+///
+/// Memory usage is bounded by the largest buffered linestring. Linestrings announced
+/// with more than `max_vertices` vertices are passed on unsimplified without
+/// buffering. The buffer size is reported to the `memory_callback` at the end of
+/// the dataset.
+///
+/// The tolerance is a distance in source units for Douglas-Peucker and an area
+/// in squared source units for Visvalingam-Whyatt.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Simplify, SimplifyAlgorithm};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = Simplify::new(WktWriter::new(&mut out), SimplifyAlgorithm::DouglasPeucker, 0.5);
+/// WktStr("LINESTRING(0 0,1 0.1,2 -0.1,3 0)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(0 0,3 0)");
+/// ```
+pub struct Simplify<P> {
+    inner: P,
+    algorithm: SimplifyAlgorithm,
+    /// Tolerance in source units
+    tolerance: f64,
+    /// Open linestring (`tagged`, `idx`)
+    linestring: Option<(bool, usize)>,
+    coords: CoordSeq,
+    keep: Vec<bool>,
+    max_vertices: usize,
+    memory: MemoryAccount,
+}
+
+impl<P> Simplify<P> {
+    pub fn new(inner: P, algorithm: SimplifyAlgorithm, tolerance: f64) -> Self {
+        Simplify {
+            inner,
+            algorithm,
+            tolerance,
+            linestring: None,
+            coords: CoordSeq::default(),
+            keep: Vec::new(),
+            max_vertices: usize::MAX,
+            memory: MemoryAccount::default(),
+        }
+    }
+    /// Maximal number of buffered vertices, longer linestrings are passed on unsimplified (default: unlimited)
+    pub fn max_vertices(mut self, vertices: usize) -> Self {
+        self.max_vertices = vertices;
+        self
+    }
+    /// Callback receiving memory statistics
+    pub fn memory_callback<F: FnMut(&MemoryStats) + 'static>(mut self, callback: F) -> Self {
+        self.memory.set_callback(Box::new(callback));
+        self
+    }
+    /// Memory statistics
+    pub fn memory_stats(&self) -> MemoryStats {
+        self.memory.stats()
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume simplifier and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+/// Distance of point `p` to the segment from `a` to `b`
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let (x, y) = if len2 == 0.0 {
+        a
+    } else {
+        let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0);
+        (a.0 + t * dx, a.1 + t * dy)
+    };
+    (p.0 - x).hypot(p.1 - y)
+}
+
+/// Mark vertices kept by the Douglas-Peucker algorithm
+fn douglas_peucker(xy: &[(f64, f64)], tolerance: f64, keep: &mut [bool]) {
+    let last = xy.len() - 1;
+    keep[0] = true;
+    keep[last] = true;
+    let mut ranges = vec![(0, last)];
+    while let Some((first, last)) = ranges.pop() {
+        let mut max_dist = 0.0;
+        let mut max_idx = first;
+        for i in first + 1..last {
+            let dist = segment_distance(xy[i], xy[first], xy[last]);
+            if dist > max_dist {
+                max_dist = dist;
+                max_idx = i;
+            }
+        }
+        if max_dist > tolerance {
+            keep[max_idx] = true;
+            ranges.push((first, max_idx));
+            ranges.push((max_idx, last));
+        }
+    }
+}
+
+/// Vertex with its effective area
+#[derive(PartialEq)]
+struct Candidate {
+    area: f64,
+    idx: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed for min-heap
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Area of the triangle `a`, `b`, `c`
+fn triangle_area(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+}
+
+/// Mark vertices kept by the Visvalingam-Whyatt algorithm
+fn visvalingam_whyatt(xy: &[(f64, f64)], tolerance: f64, keep: &mut [bool]) {
+    let n = xy.len();
+    keep.iter_mut().for_each(|keep| *keep = true);
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| i + 1).collect();
+    let mut areas = vec![f64::INFINITY; n];
+    let mut heap = BinaryHeap::with_capacity(n);
+    for idx in 1..n - 1 {
+        areas[idx] = triangle_area(xy[idx - 1], xy[idx], xy[idx + 1]);
+        heap.push(Candidate {
+            area: areas[idx],
+            idx,
+        });
+    }
+    while let Some(Candidate { area, idx }) = heap.pop() {
+        if !keep[idx] || area != areas[idx] {
+            // Outdated entry
+            continue;
+        }
+        if area >= tolerance {
+            break;
+        }
+        keep[idx] = false;
+        let (p, nx) = (prev[idx], next[idx]);
+        next[p] = nx;
+        prev[nx] = p;
+        for j in [p, nx] {
+            if j == 0 || j == n - 1 {
+                continue;
+            }
+            // Areas never decrease, so removal order follows effective area
+            areas[j] = triangle_area(xy[prev[j]], xy[j], xy[next[j]]).max(area);
+            heap.push(Candidate {
+                area: areas[j],
+                idx: j,
+            });
+        }
+    }
+}
+
+impl<P: GeomProcessor> Simplify<P> {
+    /// Simplify buffered linestring and pass it on
+    fn flush(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        let n = self.coords.len();
+        self.keep.clear();
+        self.keep.resize(n, n < 3);
+        self.memory
+            .set(self.coords.heap_size() + self.keep.capacity());
+        if n >= 3 {
+            match self.algorithm {
+                SimplifyAlgorithm::DouglasPeucker => {
+                    douglas_peucker(&self.coords.xy, self.tolerance, &mut self.keep)
+                }
+                SimplifyAlgorithm::VisvalingamWhyatt => {
+                    visvalingam_whyatt(&self.coords.xy, self.tolerance, &mut self.keep)
+                }
+            }
+            let closed = self.coords.xy[0] == self.coords.xy[n - 1];
+            if closed && self.keep.iter().filter(|keep| **keep).count() < 4 {
+                // Keep collapsed rings unchanged
+                self.keep.iter_mut().for_each(|keep| *keep = true);
+            }
+            self.coords.retain(&self.keep);
+        }
+        self.inner
+            .linestring_begin(tagged, self.coords.len(), idx)?;
+        self.coords.emit(&mut self.inner)?;
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for Simplify<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, polygon, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.linestring.is_some() {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.linestring.is_some() {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        if size > self.max_vertices {
+            return self.inner.linestring_begin(tagged, size, idx);
+        }
+        self.linestring = Some((tagged, idx));
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if self.linestring.take().is_some() {
+            self.flush(tagged, idx)
+        } else {
+            self.inner.linestring_end(tagged, idx)
+        }
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for Simplify<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Simplify<P> {
+    forward_processor_methods!(inner; schema, crs, metadata, warning, feature, properties,
+        geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.inner.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.memory.report();
+        self.inner.dataset_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn simplify(
+        algorithm: SimplifyAlgorithm,
+        xy: &[(f64, f64)],
+        tolerance: f64,
+    ) -> Vec<(f64, f64)> {
+        let mut keep = vec![false; xy.len()];
+        match algorithm {
+            SimplifyAlgorithm::DouglasPeucker => douglas_peucker(xy, tolerance, &mut keep),
+            SimplifyAlgorithm::VisvalingamWhyatt => visvalingam_whyatt(xy, tolerance, &mut keep),
+        }
+        xy.iter()
+            .zip(keep)
+            .filter_map(|(xy, keep)| if keep { Some(*xy) } else { None })
+            .collect()
+    }
+
+    #[test]
+    fn douglas_peucker_line() {
+        let line = [
+            (0.0, 0.0),
+            (1.0, 0.1),
+            (2.0, -0.1),
+            (3.0, 5.0),
+            (4.0, 6.0),
+            (5.0, 7.0),
+        ];
+        assert_eq!(
+            simplify(SimplifyAlgorithm::DouglasPeucker, &line, 1.5),
+            vec![(0.0, 0.0), (2.0, -0.1), (5.0, 7.0)]
+        );
+        // Collinear vertices are removed with zero tolerance
+        assert_eq!(
+            simplify(SimplifyAlgorithm::DouglasPeucker, &line, 0.0),
+            vec![(0.0, 0.0), (1.0, 0.1), (2.0, -0.1), (3.0, 5.0), (5.0, 7.0)]
+        );
+    }
+
+    #[test]
+    fn douglas_peucker_ring() {
+        let ring = [
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+            (0.0, 0.0),
+        ];
+        assert_eq!(
+            simplify(SimplifyAlgorithm::DouglasPeucker, &ring, 0.1),
+            vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn visvalingam_whyatt_line() {
+        let line = [(0.0, 0.0), (1.0, 0.1), (2.0, 0.0), (3.0, 2.0), (4.0, 0.0)];
+        assert_eq!(
+            simplify(SimplifyAlgorithm::VisvalingamWhyatt, &line, 0.5),
+            vec![(0.0, 0.0), (2.0, 0.0), (3.0, 2.0), (4.0, 0.0)]
+        );
+        assert_eq!(
+            simplify(SimplifyAlgorithm::VisvalingamWhyatt, &line, 10.0),
+            vec![(0.0, 0.0), (4.0, 0.0)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "with-wkt")]
+    fn simplify_polygon() -> Result<()> {
+        use crate::wkt::{WktStr, WktWriter};
+        use crate::GeozeroGeometry;
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = Simplify::new(
+            WktWriter::new(&mut out),
+            SimplifyAlgorithm::VisvalingamWhyatt,
+            1.0,
+        );
+        WktStr("POLYGON((0 0,1 0.1,2 0,2 2,0 2,0 0))").process_geom(&mut processor)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "POLYGON((0 0,2 0,2 2,0 2,0 0))"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-wkt")]
+    fn max_vertices() -> Result<()> {
+        use crate::wkt::{WktStr, WktWriter};
+        use crate::GeozeroGeometry;
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = Simplify::new(
+            WktWriter::new(&mut out),
+            SimplifyAlgorithm::DouglasPeucker,
+            0.5,
+        )
+        .max_vertices(3);
+        WktStr("MULTILINESTRING((0 0,1 0.1,2 0),(0 0,1 0.1,2 -0.1,3 0))")
+            .process_geom(&mut processor)?;
+        assert!(processor.memory_stats().peak > 0);
+        drop(processor);
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "MULTILINESTRING((0 0,2 0),(0 0,1 0.1,2 -0.1,3 0))"
+        );
+        Ok(())
+    }
+}
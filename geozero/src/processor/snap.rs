@@ -0,0 +1,332 @@
+use crate::error::Result;
+use crate::processor::Vertex;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Geometry currently buffered
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Buffered {
+    LineString(usize),
+    MultiLineString(usize),
+    Polygon(usize),
+    MultiPolygon(usize),
+}
+
+/// Snaps coordinates to a regular grid.
+///
+/// XY values are moved to the nearest grid node defined by an origin and a cell size.
+/// Consecutive vertices snapped to the same node are merged. Linestrings collapsing
+/// to a single node and rings with less than 4 vertices are removed from multi geometries
+/// and polygons, single geometries collapsing completely are passed on empty.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::SnapToGrid;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = SnapToGrid::new(WktWriter::new(&mut out), 10.0).origin(5.0, 5.0);
+/// WktStr("LINESTRING(1 2,3 4,27 36)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(5 5,25 35)");
+/// ```
+pub struct SnapToGrid<P> {
+    inner: P,
+    origin: (f64, f64),
+    size: (f64, f64),
+    buffered: Option<Buffered>,
+    lines: Vec<Vec<Vertex>>,
+    polygons: Vec<Vec<Vec<Vertex>>>,
+}
+
+impl<P> SnapToGrid<P> {
+    /// Snap to grid with square cells of `size` and origin at (0, 0)
+    pub fn new(inner: P, size: f64) -> Self {
+        SnapToGrid {
+            inner,
+            origin: (0.0, 0.0),
+            size: (size, size),
+            buffered: None,
+            lines: Vec::new(),
+            polygons: Vec::new(),
+        }
+    }
+    /// Set grid origin
+    pub fn origin(mut self, x: f64, y: f64) -> Self {
+        self.origin = (x, y);
+        self
+    }
+    /// Set different cell sizes in X and Y direction
+    pub fn cell_size(mut self, x: f64, y: f64) -> Self {
+        self.size = (x, y);
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    /// Nearest grid node
+    fn snap(&self, x: f64, y: f64) -> (f64, f64) {
+        let snap = |value: f64, origin: f64, size: f64| {
+            if size > 0.0 {
+                origin + ((value - origin) / size).round() * size
+            } else {
+                value
+            }
+        };
+        (
+            snap(x, self.origin.0, self.size.0),
+            snap(y, self.origin.1, self.size.1),
+        )
+    }
+    fn add_vertex(&mut self, vertex: Vertex) {
+        let vertices = match self.buffered {
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.last_mut()
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.last_mut().and_then(|rings| rings.last_mut())
+            }
+            None => None,
+        };
+        if let Some(vertices) = vertices {
+            if !matches!(vertices.last(), Some(last) if last.x == vertex.x && last.y == vertex.y) {
+                vertices.push(vertex);
+            }
+        }
+    }
+}
+
+/// Line has at least two distinct vertices
+fn valid_line(line: &[Vertex]) -> bool {
+    line.len() >= 2
+}
+
+/// Ring has at least four vertices
+fn valid_ring(ring: &[Vertex]) -> bool {
+    ring.len() >= 4
+}
+
+impl<P: GeomProcessor> SnapToGrid<P> {
+    fn emit_line(&mut self, line: &[Vertex], tagged: bool, idx: usize) -> Result<()> {
+        self.inner.linestring_begin(tagged, line.len(), idx)?;
+        for (idx, vertex) in line.iter().enumerate() {
+            vertex.emit(&mut self.inner, idx)?;
+        }
+        self.inner.linestring_end(tagged, idx)
+    }
+    fn emit_polygon(&mut self, rings: &[Vec<Vertex>], tagged: bool, idx: usize) -> Result<()> {
+        self.inner.polygon_begin(tagged, rings.len(), idx)?;
+        for (i, ring) in rings.iter().enumerate() {
+            self.emit_line(ring, false, i)?;
+        }
+        self.inner.polygon_end(tagged, idx)
+    }
+    /// Pass on buffered geometry without degenerate parts
+    fn flush(&mut self) -> Result<()> {
+        let buffered = self.buffered.take();
+        let mut lines = std::mem::take(&mut self.lines);
+        lines.retain(|line| valid_line(line));
+        let polygons: Vec<Vec<Vec<Vertex>>> = std::mem::take(&mut self.polygons)
+            .into_iter()
+            .filter(|rings| matches!(rings.first(), Some(exterior) if valid_ring(exterior)))
+            .map(|rings| rings.into_iter().filter(|ring| valid_ring(ring)).collect())
+            .collect();
+        match buffered {
+            Some(Buffered::LineString(idx)) => {
+                let line = lines.first().map(Vec::as_slice).unwrap_or(&[]);
+                self.emit_line(line, true, idx)
+            }
+            Some(Buffered::MultiLineString(idx)) => {
+                self.inner.multilinestring_begin(lines.len(), idx)?;
+                for (i, line) in lines.iter().enumerate() {
+                    self.emit_line(line, false, i)?;
+                }
+                self.inner.multilinestring_end(idx)
+            }
+            Some(Buffered::Polygon(idx)) => {
+                let rings = polygons.first().map(Vec::as_slice).unwrap_or(&[]);
+                self.emit_polygon(rings, true, idx)
+            }
+            Some(Buffered::MultiPolygon(idx)) => {
+                self.inner.multipolygon_begin(polygons.len(), idx)?;
+                for (i, rings) in polygons.iter().enumerate() {
+                    self.emit_polygon(rings, false, i)?;
+                }
+                self.inner.multipolygon_end(idx)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for SnapToGrid<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        let (x, y) = self.snap(x, y);
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::xy(x, y));
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let (x, y) = self.snap(x, y);
+        if self.buffered.is_some() {
+            self.add_vertex(Vertex::coordinate(x, y, z, m, t, tm));
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) | Some(Buffered::MultiLineString(_)) => {
+                self.lines.push(Vec::with_capacity(size));
+            }
+            Some(Buffered::Polygon(_)) | Some(Buffered::MultiPolygon(_)) => {
+                if let Some(rings) = self.polygons.last_mut() {
+                    rings.push(Vec::with_capacity(size));
+                }
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::LineString(idx));
+                self.lines.push(Vec::with_capacity(size));
+            }
+            None => return self.inner.linestring_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::LineString(_)) => self.flush(),
+            Some(_) => Ok(()),
+            None => self.inner.linestring_end(tagged, idx),
+        }
+    }
+    fn multilinestring_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiLineString(idx));
+        Ok(())
+    }
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::MultiPolygon(_)) => {
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            None if tagged => {
+                self.buffered = Some(Buffered::Polygon(idx));
+                self.polygons.push(Vec::with_capacity(size));
+            }
+            _ => return self.inner.polygon_begin(tagged, size, idx),
+        }
+        Ok(())
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        match self.buffered {
+            Some(Buffered::Polygon(_)) => self.flush(),
+            Some(Buffered::MultiPolygon(_)) => Ok(()),
+            _ => self.inner.polygon_end(tagged, idx),
+        }
+    }
+    fn multipolygon_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.buffered = Some(Buffered::MultiPolygon(idx));
+        Ok(())
+    }
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.flush()
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for SnapToGrid<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for SnapToGrid<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records linestrings as vertex lists
+    #[derive(Default)]
+    struct Lines(Vec<Vec<(f64, f64)>>);
+
+    impl GeomProcessor for Lines {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.last_mut().unwrap().push((x, y));
+            Ok(())
+        }
+        fn linestring_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+            self.0.push(Vec::new());
+            Ok(())
+        }
+    }
+
+    fn line(processor: &mut impl GeomProcessor, xy: &[(f64, f64)], idx: usize) -> Result<()> {
+        processor.linestring_begin(false, xy.len(), idx)?;
+        for (i, (x, y)) in xy.iter().enumerate() {
+            processor.xy(*x, *y, i)?;
+        }
+        processor.linestring_end(false, idx)
+    }
+
+    #[test]
+    fn snap_multilinestring() -> Result<()> {
+        let mut snap = SnapToGrid::new(Lines::default(), 1.0).cell_size(1.0, 2.0);
+        snap.multilinestring_begin(2, 0)?;
+        line(&mut snap, &[(0.1, 0.2), (0.3, 0.4)], 0)?;
+        line(&mut snap, &[(0.1, 0.2), (0.9, 0.8), (2.2, 3.3)], 1)?;
+        snap.multilinestring_end(0)?;
+        assert_eq!(
+            snap.into_inner().0,
+            vec![vec![(0.0, 0.0), (1.0, 0.0), (2.0, 4.0)]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn collapse_polygon() -> Result<()> {
+        let mut snap = SnapToGrid::new(Lines::default(), 10.0);
+        snap.polygon_begin(true, 2, 0)?;
+        line(
+            &mut snap,
+            &[
+                (0.0, 0.0),
+                (20.0, 0.0),
+                (20.0, 20.0),
+                (0.0, 20.0),
+                (0.0, 0.0),
+            ],
+            0,
+        )?;
+        line(
+            &mut snap,
+            &[(5.1, 5.1), (6.0, 5.1), (6.0, 6.0), (5.1, 5.1)],
+            1,
+        )?;
+        snap.polygon_end(true, 0)?;
+        assert_eq!(snap.into_inner().0.len(), 1);
+        Ok(())
+    }
+}
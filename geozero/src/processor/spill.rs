@@ -0,0 +1,299 @@
+use crate::error::Result;
+use crate::processor::Event;
+use crate::OwnedColumnValue;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Binary encoding of recorded calls
+pub(crate) trait Encode: Sized {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()>;
+    fn decode<R: Read>(input: &mut R) -> Result<Self>;
+}
+
+macro_rules! number_codec {
+    ($($type:ty),*) => {
+        $(
+            impl Encode for $type {
+                fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+                    out.write_all(&self.to_le_bytes())?;
+                    Ok(())
+                }
+                fn decode<R: Read>(input: &mut R) -> Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$type>()];
+                    input.read_exact(&mut bytes)?;
+                    Ok(<$type>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+number_codec!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+impl Encode for usize {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+        (*self as u64).encode(out)
+    }
+    fn decode<R: Read>(input: &mut R) -> Result<Self> {
+        Ok(u64::decode(input)? as usize)
+    }
+}
+
+impl Encode for bool {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+        (*self as u8).encode(out)
+    }
+    fn decode<R: Read>(input: &mut R) -> Result<Self> {
+        Ok(u8::decode(input)? != 0)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+        self.is_some().encode(out)?;
+        match self {
+            Some(value) => value.encode(out),
+            None => Ok(()),
+        }
+    }
+    fn decode<R: Read>(input: &mut R) -> Result<Self> {
+        if bool::decode(input)? {
+            Ok(Some(T::decode(input)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl Encode for Vec<u8> {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+        self.len().encode(out)?;
+        out.write_all(self)?;
+        Ok(())
+    }
+    fn decode<R: Read>(input: &mut R) -> Result<Self> {
+        let mut bytes = vec![0; usize::decode(input)?];
+        input.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Encode for String {
+    fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+        self.len().encode(out)?;
+        out.write_all(self.as_bytes())?;
+        Ok(())
+    }
+    fn decode<R: Read>(input: &mut R) -> Result<Self> {
+        String::from_utf8(Vec::decode(input)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+    }
+}
+
+/// Encode enum variants with a tag byte followed by their fields
+macro_rules! enum_codec {
+    ($type:ident {
+        $($tag:literal => $variant:ident($($field:ident: $ty:ty),*),)*
+    } {
+        $($unit_tag:literal => $unit:ident,)*
+    }) => {
+        impl Encode for $type {
+            fn encode<W: Write>(&self, out: &mut W) -> Result<()> {
+                match self {
+                    $(
+                        $type::$variant($($field),*) => {
+                            ($tag as u8).encode(out)?;
+                            $( $field.encode(out)?; )*
+                        }
+                    )*
+                    $( $type::$unit => ($unit_tag as u8).encode(out)?, )*
+                }
+                Ok(())
+            }
+            fn decode<R: Read>(input: &mut R) -> Result<Self> {
+                Ok(match u8::decode(input)? {
+                    $( $tag => $type::$variant($(<$ty>::decode(input)?),*), )*
+                    $( $unit_tag => $type::$unit, )*
+                    tag => {
+                        let msg = format!("invalid {} tag {}", stringify!($type), tag);
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, msg).into());
+                    }
+                })
+            }
+        }
+    };
+}
+
+enum_codec!(OwnedColumnValue {
+    0 => Byte(v: i8),
+    1 => UByte(v: u8),
+    2 => Bool(v: bool),
+    3 => Short(v: i16),
+    4 => UShort(v: u16),
+    5 => Int(v: i32),
+    6 => UInt(v: u32),
+    7 => Long(v: i64),
+    8 => ULong(v: u64),
+    9 => Float(v: f32),
+    10 => Double(v: f64),
+    11 => String(v: String),
+    12 => Json(v: String),
+    13 => DateTime(v: String),
+    14 => Date(v: String),
+    15 => Binary(v: Vec<u8>),
+    17 => BigInt(v: String),
+} {
+    16 => Null,
+});
+
+enum_codec!(Event {
+    0 => Srid(srid: Option<i32>),
+    1 => Xy(x: f64, y: f64, idx: usize),
+    2 => Coordinate(x: f64, y: f64, z: Option<f64>, m: Option<f64>, t: Option<f64>,
+        tm: Option<u64>, idx: usize),
+    3 => EmptyPoint(idx: usize),
+    4 => PointBegin(idx: usize),
+    5 => PointEnd(idx: usize),
+    6 => MultiPointBegin(size: usize, idx: usize),
+    7 => MultiPointEnd(idx: usize),
+    8 => LineStringBegin(tagged: bool, size: usize, idx: usize),
+    9 => LineStringEnd(tagged: bool, idx: usize),
+    10 => MultiLineStringBegin(size: usize, idx: usize),
+    11 => MultiLineStringEnd(idx: usize),
+    12 => PolygonBegin(tagged: bool, size: usize, idx: usize),
+    13 => PolygonEnd(tagged: bool, idx: usize),
+    14 => MultiPolygonBegin(size: usize, idx: usize),
+    15 => MultiPolygonEnd(idx: usize),
+    16 => GeometryCollectionBegin(size: usize, idx: usize),
+    17 => GeometryCollectionEnd(idx: usize),
+    18 => CircularStringBegin(size: usize, idx: usize),
+    19 => CircularStringEnd(idx: usize),
+    20 => CompoundCurveBegin(size: usize, idx: usize),
+    21 => CompoundCurveEnd(idx: usize),
+    22 => CurvePolygonBegin(size: usize, idx: usize),
+    23 => CurvePolygonEnd(idx: usize),
+    24 => MultiCurveBegin(size: usize, idx: usize),
+    25 => MultiCurveEnd(idx: usize),
+    26 => MultiSurfaceBegin(size: usize, idx: usize),
+    27 => MultiSurfaceEnd(idx: usize),
+    28 => TriangleBegin(tagged: bool, size: usize, idx: usize),
+    29 => TriangleEnd(tagged: bool, idx: usize),
+    30 => PolyhedralSurfaceBegin(size: usize, idx: usize),
+    31 => PolyhedralSurfaceEnd(idx: usize),
+    32 => TinBegin(size: usize, idx: usize),
+    33 => TinEnd(idx: usize),
+    34 => Property(idx: usize, name: String, value: OwnedColumnValue),
+    35 => FeatureBegin(idx: u64),
+    36 => FeatureEnd(idx: u64),
+    41 => DatasetBegin(name: Option<String>),
+    43 => Fid(fid: OwnedColumnValue),
+} {
+    37 => PropertiesBegin,
+    38 => PropertiesEnd,
+    39 => GeometryBegin,
+    40 => GeometryEnd,
+    42 => DatasetEnd,
+});
+
+/// Position of spilled events in a `SpillFile`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct SpillPos {
+    offset: u64,
+    len: usize,
+}
+
+impl SpillPos {
+    /// Size of spilled events in bytes
+    pub fn size(&self) -> usize {
+        self.len
+    }
+}
+
+/// Temporary file for recorded calls not fitting into memory.
+///
+/// The file is removed when dropped.
+pub(crate) struct SpillFile {
+    file: File,
+    path: PathBuf,
+    len: u64,
+}
+
+impl SpillFile {
+    /// Create new temporary file in directory `dir`
+    pub fn create(dir: &Path) -> Result<Self> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "geozero-{}-{}.spill",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        );
+        let path = dir.join(name);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(SpillFile { file, path, len: 0 })
+    }
+    /// Append events
+    pub fn write(&mut self, events: &[Event]) -> Result<SpillPos> {
+        let mut bytes = Vec::new();
+        for event in events {
+            event.encode(&mut bytes)?;
+        }
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.file.write_all(&bytes)?;
+        let pos = SpillPos {
+            offset: self.len,
+            len: bytes.len(),
+        };
+        self.len += bytes.len() as u64;
+        Ok(pos)
+    }
+    /// Read events written at `pos`
+    pub fn read(&mut self, pos: SpillPos) -> Result<Vec<Event>> {
+        let mut bytes = vec![0; pos.len];
+        self.file.seek(SeekFrom::Start(pos.offset))?;
+        self.file.read_exact(&mut bytes)?;
+        let mut input = bytes.as_slice();
+        let mut events = Vec::new();
+        while !input.is_empty() {
+            events.push(Event::decode(&mut input)?);
+        }
+        Ok(events)
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spill_events() -> Result<()> {
+        let events = vec![
+            Event::FeatureBegin(3),
+            Event::Fid(OwnedColumnValue::String("ch-1".into())),
+            Event::Coordinate(1.0, 2.0, Some(3.0), None, None, Some(4), 0),
+            Event::Property(0, "name".to_string(), OwnedColumnValue::String("a".into())),
+            Event::Property(1, "null".to_string(), OwnedColumnValue::Null),
+            Event::GeometryEnd,
+        ];
+        let mut file = SpillFile::create(&std::env::temp_dir())?;
+        let path = file.path.clone();
+        let first = file.write(&events)?;
+        let second = file.write(&events[1..2])?;
+        assert_eq!(file.read(second)?, &events[1..2]);
+        assert_eq!(file.read(first)?, events);
+        drop(file);
+        assert!(!path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use crate::error::Result;
+use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::collections::BTreeMap;
+
+/// Collects geometry statistics of a dataset while streaming.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::StatsCollector;
+/// use geozero::wkt::WktStr;
+/// use geozero::GeozeroGeometry;
+///
+/// let mut stats = StatsCollector::new();
+/// WktStr("POLYGON((0 0,1 0,1 1,0 0))").process_geom(&mut stats).unwrap();
+/// assert_eq!(stats.geometry_type(), Some("Polygon"));
+/// assert_eq!(stats.vertex_count, 4);
+/// assert_eq!(stats.ring_count, 1);
+/// ```
+#[derive(Default, Debug)]
+pub struct StatsCollector {
+    /// Number of processed features
+    pub feature_count: u64,
+    /// Number of top-level geometries
+    pub geometry_count: u64,
+    /// Number of top-level geometries by type name
+    pub geometry_types: BTreeMap<&'static str, u64>,
+    /// Total number of vertices
+    pub vertex_count: u64,
+    /// Number of polygon rings
+    pub ring_count: u64,
+    /// Number of empty points
+    pub empty_count: u64,
+    /// Number of vertices with Z value
+    pub z_count: u64,
+    /// Number of vertices with M value
+    pub m_count: u64,
+    /// Currently open geometries
+    parents: Vec<&'static str>,
+}
+
+impl StatsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Geometry type of all top-level geometries, if the dataset is homogeneous
+    pub fn geometry_type(&self) -> Option<&'static str> {
+        let mut types = self.geometry_types.keys();
+        match (types.next(), types.next()) {
+            (Some(geometry_type), None) => Some(geometry_type),
+            _ => None,
+        }
+    }
+    /// Mean number of vertices per top-level geometry
+    pub fn mean_vertex_count(&self) -> f64 {
+        if self.geometry_count == 0 {
+            0.0
+        } else {
+            self.vertex_count as f64 / self.geometry_count as f64
+        }
+    }
+    /// Dimensions used by at least one vertex
+    pub fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions {
+            z: self.z_count > 0,
+            m: self.m_count > 0,
+            ..CoordDimensions::xy()
+        }
+    }
+    fn begin(&mut self, geometry_type: &'static str) {
+        match self.parents.last() {
+            None => {
+                self.geometry_count += 1;
+                *self.geometry_types.entry(geometry_type).or_insert(0) += 1;
+            }
+            Some(&"Polygon") | Some(&"Triangle") | Some(&"CurvePolygon") => {
+                self.ring_count += 1;
+            }
+            _ => {}
+        }
+        self.parents.push(geometry_type);
+    }
+    fn end(&mut self) {
+        self.parents.pop();
+    }
+}
+
+impl GeomProcessor for StatsCollector {
+    fn dimensions(&self) -> CoordDimensions {
+        CoordDimensions::xyzm()
+    }
+    fn xy(&mut self, _x: f64, _y: f64, _idx: usize) -> Result<()> {
+        self.vertex_count += 1;
+        Ok(())
+    }
+    fn coordinate(
+        &mut self,
+        _x: f64,
+        _y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        _t: Option<f64>,
+        _tm: Option<u64>,
+        _idx: usize,
+    ) -> Result<()> {
+        self.vertex_count += 1;
+        if z.is_some() {
+            self.z_count += 1;
+        }
+        if m.is_some() {
+            self.m_count += 1;
+        }
+        Ok(())
+    }
+    fn empty_point(&mut self, _idx: usize) -> Result<()> {
+        self.empty_count += 1;
+        if self.parents.is_empty() {
+            self.geometry_count += 1;
+            *self.geometry_types.entry("Point").or_insert(0) += 1;
+        }
+        Ok(())
+    }
+    fn point_begin(&mut self, _idx: usize) -> Result<()> {
+        self.begin("Point");
+        Ok(())
+    }
+    fn point_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn multipoint_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("MultiPoint");
+        Ok(())
+    }
+    fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn linestring_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("LineString");
+        Ok(())
+    }
+    fn linestring_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn multilinestring_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("MultiLineString");
+        Ok(())
+    }
+    fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn polygon_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("Polygon");
+        Ok(())
+    }
+    fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn multipolygon_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("MultiPolygon");
+        Ok(())
+    }
+    fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn geometrycollection_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("GeometryCollection");
+        Ok(())
+    }
+    fn geometrycollection_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn circularstring_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("CircularString");
+        Ok(())
+    }
+    fn circularstring_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn compoundcurve_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("CompoundCurve");
+        Ok(())
+    }
+    fn compoundcurve_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn curvepolygon_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("CurvePolygon");
+        Ok(())
+    }
+    fn curvepolygon_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn multicurve_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("MultiCurve");
+        Ok(())
+    }
+    fn multicurve_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn multisurface_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("MultiSurface");
+        Ok(())
+    }
+    fn multisurface_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn triangle_begin(&mut self, _tagged: bool, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("Triangle");
+        Ok(())
+    }
+    fn triangle_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn polyhedralsurface_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("PolyhedralSurface");
+        Ok(())
+    }
+    fn polyhedralsurface_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+    fn tin_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
+        self.begin("Tin");
+        Ok(())
+    }
+    fn tin_end(&mut self, _idx: usize) -> Result<()> {
+        self.end();
+        Ok(())
+    }
+}
+
+impl PropertyProcessor for StatsCollector {}
+
+impl FeatureProcessor for StatsCollector {
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        self.feature_count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stats() -> Result<()> {
+        let mut stats = StatsCollector::new();
+        stats.dataset_begin(None)?;
+        stats.feature_begin(0)?;
+        stats.multipolygon_begin(1, 0)?;
+        stats.polygon_begin(false, 2, 0)?;
+        for i in 0..2 {
+            stats.linestring_begin(false, 4, i)?;
+            for idx in 0..4 {
+                stats.coordinate(0.0, 0.0, Some(1.0), None, None, None, idx)?;
+            }
+            stats.linestring_end(false, i)?;
+        }
+        stats.polygon_end(false, 0)?;
+        stats.multipolygon_end(0)?;
+        stats.feature_end(0)?;
+        stats.feature_begin(1)?;
+        stats.point_begin(0)?;
+        stats.xy(0.0, 0.0, 0)?;
+        stats.point_end(0)?;
+        stats.feature_end(1)?;
+        stats.dataset_end()?;
+
+        assert_eq!(stats.feature_count, 2);
+        assert_eq!(stats.geometry_count, 2);
+        assert_eq!(stats.geometry_types.get("MultiPolygon"), Some(&1));
+        assert_eq!(stats.geometry_types.get("Point"), Some(&1));
+        assert_eq!(stats.geometry_type(), None);
+        assert_eq!(stats.vertex_count, 9);
+        assert_eq!(stats.mean_vertex_count(), 4.5);
+        assert_eq!(stats.ring_count, 2);
+        assert_eq!(stats.z_count, 8);
+        assert!(stats.dimensions().z);
+        assert!(!stats.dimensions().m);
+        Ok(())
+    }
+}
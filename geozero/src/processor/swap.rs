@@ -0,0 +1,212 @@
+use crate::error::Result;
+use crate::{AxisOrder, Crs, DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Exchanges X and Y of all coordinates.
+///
+/// Used for sources delivering coordinates in latitude/longitude or northing/easting
+/// order, like WFS servers following the EPSG axis order of `EPSG:4326`.
+/// Z, M and time values are passed unchanged.
+///
+/// With [from_crs](XYSwap::from_crs), axes are swapped depending on the axis order
+/// declared by the CRS of the datasource. [Reproject](crate::processor::Reproject)
+/// swaps north-first input automatically.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::XYSwap;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = XYSwap::new(WktWriter::new(&mut out));
+/// WktStr("POINT(46.95 7.44)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT(7.44 46.95)");
+/// ```
+pub struct XYSwap<P> {
+    inner: P,
+    swap: bool,
+    /// Set `swap` from CRS axis order
+    from_crs: bool,
+}
+
+impl<P> XYSwap<P> {
+    pub fn new(inner: P) -> Self {
+        Self::with_swap(inner, true)
+    }
+    /// Swap axes only if `swap` is true, e.g. when the axis order of a CRS is known at runtime
+    pub fn with_swap(inner: P, swap: bool) -> Self {
+        XYSwap {
+            inner,
+            swap,
+            from_crs: false,
+        }
+    }
+    /// Swap axes of sources declaring a north-first axis order with their CRS, like
+    /// `urn:ogc:def:crs:EPSG::4326` (e.g. WFS 2.0, GML) or WKT with latitude as first `AXIS`.
+    ///
+    /// The axis order is taken from the `crs` call of each dataset, see
+    /// [Crs::coordinate_axis_order]. Plain EPSG codes and SRIDs of geometries don't swap
+    /// axes. The CRS passed on declares the swapped axis order for EPSG URNs.
+    pub fn from_crs(inner: P) -> Self {
+        XYSwap {
+            inner,
+            swap: false,
+            from_crs: true,
+        }
+    }
+    fn set_crs(&mut self, crs: &Crs) {
+        if self.from_crs {
+            self.swap = crs.coordinate_axis_order() == AxisOrder::NorthEast;
+        }
+    }
+    /// Axes are swapped
+    pub fn swaps(&self) -> bool {
+        self.swap
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for XYSwap<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.swap {
+            self.inner.xy(y, x, idx)
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.swap {
+            self.inner.coordinate(y, x, z, m, t, tm, idx)
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for XYSwap<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for XYSwap<P> {
+    forward_processor_methods!(inner; schema, warning, feature, properties, geometry);
+
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        if self.from_crs {
+            self.swap = false;
+        }
+        self.inner.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.inner.dataset_end()
+    }
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        self.set_crs(crs);
+        match crs {
+            Crs::EpsgUrn(code) if self.swap => self.inner.crs(&Crs::Epsg(*code)),
+            _ => self.inner.crs(crs),
+        }
+    }
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        let mut metadata = metadata.clone();
+        if self.swap {
+            metadata.extent = metadata.extent.map(|e| e.map_corners(|x, y| (y, x)));
+        }
+        self.inner.metadata(&metadata)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct CoordCollector(Vec<(f64, f64, Option<f64>)>);
+
+    impl GeomProcessor for CoordCollector {
+        fn xy(&mut self, x: f64, y: f64, _idx: usize) -> Result<()> {
+            self.0.push((x, y, None));
+            Ok(())
+        }
+        fn coordinate(
+            &mut self,
+            x: f64,
+            y: f64,
+            z: Option<f64>,
+            _m: Option<f64>,
+            _t: Option<f64>,
+            _tm: Option<u64>,
+            _idx: usize,
+        ) -> Result<()> {
+            self.0.push((x, y, z));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn swap() -> Result<()> {
+        let mut processor = XYSwap::new(CoordCollector(Vec::new()));
+        processor.xy(1.0, 2.0, 0)?;
+        processor.coordinate(3.0, 4.0, Some(5.0), None, None, None, 1)?;
+        assert_eq!(
+            processor.into_inner().0,
+            vec![(2.0, 1.0, None), (4.0, 3.0, Some(5.0))]
+        );
+
+        let mut processor = XYSwap::with_swap(CoordCollector(Vec::new()), false);
+        processor.xy(1.0, 2.0, 0)?;
+        assert_eq!(processor.into_inner().0, vec![(1.0, 2.0, None)]);
+        Ok(())
+    }
+
+    impl PropertyProcessor for CoordCollector {}
+    impl FeatureProcessor for CoordCollector {}
+
+    #[test]
+    fn swap_from_crs() -> Result<()> {
+        let mut processor = XYSwap::from_crs(CoordCollector(Vec::new()));
+        processor.xy(1.0, 2.0, 0)?;
+        // SRIDs and plain EPSG codes are east-first
+        processor.srid(Some(4326))?;
+        processor.xy(1.0, 2.0, 0)?;
+        processor.crs(&Crs::Epsg(4326))?;
+        processor.xy(1.0, 2.0, 0)?;
+        processor.crs(&Crs::from_definition("urn:ogc:def:crs:EPSG::4326").unwrap())?;
+        processor.xy(1.0, 2.0, 0)?;
+        processor.crs(&Crs::from_definition("urn:ogc:def:crs:EPSG::2056").unwrap())?;
+        processor.xy(1.0, 2.0, 0)?;
+        processor.crs(&Crs::from_definition("urn:ogc:def:crs:EPSG::4326").unwrap())?;
+        // Axis order is reset for each dataset
+        processor.dataset_begin(None)?;
+        processor.xy(1.0, 2.0, 0)?;
+        assert_eq!(
+            processor.into_inner().0,
+            vec![
+                (1.0, 2.0, None),
+                (1.0, 2.0, None),
+                (1.0, 2.0, None),
+                (2.0, 1.0, None),
+                (1.0, 2.0, None),
+                (1.0, 2.0, None)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,367 @@
+use crate::error::Result;
+use crate::processor::{
+    extend, lonlat_to_mercator, replay_with_property, schema_with_column, Extent, FeatureBuffer,
+};
+use crate::{
+    ColumnDef, ColumnType, ColumnValue, FeatureProcessor, GeomProcessor, ProcessorSink,
+    PropertyProcessor,
+};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// Tile of a Web Mercator tile pyramid in XYZ scheme (row 0 at the north)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    pub fn new(z: u8, x: u32, y: u32) -> Self {
+        TileId { z, x, y }
+    }
+    /// Bing Maps quadkey, empty for zoom level 0
+    pub fn quadkey(&self) -> String {
+        (1..=self.z)
+            .rev()
+            .map(|level| {
+                let mask = 1 << (level - 1);
+                let digit = (self.x & mask != 0) as u8 + 2 * (self.y & mask != 0) as u8;
+                char::from(b'0' + digit)
+            })
+            .collect()
+    }
+    /// Tile of Bing Maps quadkey
+    pub fn from_quadkey(quadkey: &str) -> Option<Self> {
+        if quadkey.len() > 32 {
+            return None;
+        }
+        let mut tile = TileId::new(quadkey.len() as u8, 0, 0);
+        for digit in quadkey.bytes() {
+            let digit = match digit {
+                b'0'..=b'3' => u32::from(digit - b'0'),
+                _ => return None,
+            };
+            tile.x = tile.x << 1 | digit & 1;
+            tile.y = tile.y << 1 | digit >> 1;
+        }
+        Some(tile)
+    }
+}
+
+impl fmt::Display for TileId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.z, self.x, self.y)
+    }
+}
+
+/// Columns and rows of tiles at `zoom` intersecting lon/lat `extent` extended by
+/// `buffer` in tile units
+pub(crate) fn tile_ranges(
+    extent: &Extent,
+    zoom: u8,
+    buffer: f64,
+) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
+    let n = 1u64 << zoom;
+    let (half_extent, _) = lonlat_to_mercator(180.0, 0.0);
+    // Fractional tile column and row
+    let tile_coords = |lon: f64, lat: f64| {
+        let (x, y) = lonlat_to_mercator(lon, lat);
+        (
+            (x / half_extent + 1.0) / 2.0 * n as f64,
+            (1.0 - y / half_extent) / 2.0 * n as f64,
+        )
+    };
+    let tile_index = |v: f64| (v.floor().max(0.0) as u64).min(n - 1) as u32;
+    let (minx, miny) = tile_coords(extent.minx, extent.maxy);
+    let (maxx, maxy) = tile_coords(extent.maxx, extent.miny);
+    (
+        tile_index(minx - buffer)..=tile_index(maxx + buffer),
+        tile_index(miny - buffer)..=tile_index(maxy + buffer),
+    )
+}
+
+/// Tiles at `zoom` intersecting lon/lat `extent`
+fn extent_tiles(extent: &Extent, zoom: u8) -> impl Iterator<Item = TileId> {
+    let (cols, rows) = tile_ranges(extent, zoom, 0.0);
+    cols.flat_map(move |x| rows.clone().map(move |y| TileId::new(zoom, x, y)))
+}
+
+/// Tile notation of [TileCoverageProperty]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TileNotation {
+    /// `z/x/y`
+    Xyz,
+    /// Bing Maps quadkey
+    Quadkey,
+}
+
+/// Adds the tiles intersecting each feature as property.
+///
+/// Tiles of the Web Mercator tile pyramid at the given zoom level are computed from
+/// the feature bounding box in WGS84 lon/lat. Each feature is buffered until its
+/// geometry is complete. The property is appended to the feature properties and the
+/// schema as JSON array of sorted tiles in `z/x/y` or quadkey notation, features
+/// without geometry get a NULL value.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::{GeoJson, GeoJsonWriter};
+/// use geozero::processor::{TileCoverageProperty, TileNotation};
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "Feature", "properties": {"name": "Bern"},
+///     "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}}"#;
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = TileCoverageProperty::new(GeoJsonWriter::new(&mut out), 4, "tiles")
+///     .notation(TileNotation::Quadkey);
+/// GeoJson(geojson).process(&mut processor).unwrap();
+/// assert!(std::str::from_utf8(&out).unwrap().contains(r#""tiles": ["1202"]"#));
+/// ```
+pub struct TileCoverageProperty<P> {
+    buffer: FeatureBuffer<P>,
+    zoom: u8,
+    name: String,
+    notation: TileNotation,
+    extent: Option<Extent>,
+}
+
+impl<P> TileCoverageProperty<P> {
+    pub fn new(inner: P, zoom: u8, name: &str) -> Self {
+        TileCoverageProperty {
+            buffer: FeatureBuffer::new(inner),
+            zoom,
+            name: name.to_string(),
+            notation: TileNotation::Xyz,
+            extent: None,
+        }
+    }
+    /// Tile notation (default: `z/x/y`)
+    pub fn notation(mut self, notation: TileNotation) -> Self {
+        self.notation = notation;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        self.buffer.inner()
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.buffer.into_inner()
+    }
+}
+
+impl<P: FeatureProcessor> GeomProcessor for TileCoverageProperty<P> {
+    forward_processor_methods!(buffer; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.buffer.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.buffer.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: FeatureProcessor> PropertyProcessor for TileCoverageProperty<P> {
+    forward_processor_methods!(buffer; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for TileCoverageProperty<P> {
+    forward_processor_methods!(buffer; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let column = ColumnDef::new(&self.name, ColumnType::Json);
+        self.buffer.schema(&schema_with_column(columns, column))
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.extent = None;
+        self.buffer.record();
+        self.buffer.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.buffer.feature_end(idx)?;
+        let events = self.buffer.take();
+        let json = match self.extent.take() {
+            Some(extent) => {
+                let tiles: Vec<String> = extent_tiles(&extent, self.zoom)
+                    .map(|tile| match self.notation {
+                        TileNotation::Xyz => tile.to_string(),
+                        TileNotation::Quadkey => tile.quadkey(),
+                    })
+                    .collect();
+                Some(serde_json::to_string(&tiles)?)
+            }
+            None => None,
+        };
+        let value = match &json {
+            Some(json) => ColumnValue::Json(json),
+            None => ColumnValue::Null,
+        };
+        replay_with_property(&events, self.buffer.inner_mut(), &self.name, &value)
+    }
+}
+
+/// Collects the tiles intersecting processed features.
+///
+/// Tiles are computed like in [TileCoverageProperty] for all zoom levels from
+/// `minzoom` to `maxzoom` and collected into a sorted set, e.g. for seeding a tile
+/// cache. Created with `TileCoverage::new(minzoom, maxzoom)` it is used as a sink,
+/// created with `TileCoverage::tee(processor, minzoom, maxzoom)` all calls are passed
+/// to the wrapped processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJson;
+/// use geozero::processor::{TileCoverage, TileId};
+/// use geozero::GeozeroDatasource;
+///
+/// let geojson = r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [7.44, 46.95]}},
+///     {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [151.21, -33.87]}}
+/// ]}"#;
+/// let mut coverage = TileCoverage::new(0, 2);
+/// GeoJson(geojson).process(&mut coverage).unwrap();
+/// let tiles: Vec<String> = coverage.tiles().iter().map(TileId::to_string).collect();
+/// assert_eq!(tiles, vec!["0/0/0", "1/1/0", "1/1/1", "2/2/1", "2/3/2"]);
+/// ```
+pub struct TileCoverage<P = ProcessorSink> {
+    inner: P,
+    minzoom: u8,
+    maxzoom: u8,
+    extent: Option<Extent>,
+    tiles: BTreeSet<TileId>,
+}
+
+impl TileCoverage<ProcessorSink> {
+    pub fn new(minzoom: u8, maxzoom: u8) -> Self {
+        Self::tee(ProcessorSink::new(), minzoom, maxzoom)
+    }
+}
+
+impl<P> TileCoverage<P> {
+    /// Pass-through tile coverage
+    pub fn tee(inner: P, minzoom: u8, maxzoom: u8) -> Self {
+        TileCoverage {
+            inner,
+            minzoom,
+            maxzoom,
+            extent: None,
+            tiles: BTreeSet::new(),
+        }
+    }
+    /// Tiles intersecting processed features, ordered by zoom level, column and row
+    pub fn tiles(&self) -> &BTreeSet<TileId> {
+        &self.tiles
+    }
+    /// Consume collector and return tiles
+    pub fn into_tiles(self) -> BTreeSet<TileId> {
+        self.tiles
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume collector and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for TileCoverage<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        extend(&mut self.extent, x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for TileCoverage<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for TileCoverage<P> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, warning, fid, properties,
+        geometry);
+
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.extent = None;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        if let Some(extent) = self.extent.take() {
+            for zoom in self.minzoom..=self.maxzoom {
+                self.tiles.extend(extent_tiles(&extent, zoom));
+            }
+        }
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quadkeys() {
+        let tile = TileId::new(3, 3, 5);
+        assert_eq!(tile.quadkey(), "213");
+        assert_eq!(TileId::from_quadkey("213"), Some(tile));
+        assert_eq!(TileId::new(0, 0, 0).quadkey(), "");
+        assert_eq!(TileId::from_quadkey(""), Some(TileId::new(0, 0, 0)));
+        assert_eq!(TileId::from_quadkey("214"), None);
+    }
+
+    #[test]
+    fn polygon_tiles() -> Result<()> {
+        let mut coverage = TileCoverage::new(3, 3);
+        coverage.feature_begin(0)?;
+        coverage.polygon_begin(true, 1, 0)?;
+        coverage.linestring_begin(false, 3, 0)?;
+        coverage.xy(-10.0, 10.0, 0)?;
+        coverage.xy(10.0, 10.0, 1)?;
+        coverage.xy(10.0, -10.0, 2)?;
+        coverage.linestring_end(false, 0)?;
+        coverage.polygon_end(true, 0)?;
+        coverage.feature_end(0)?;
+        let tiles: Vec<_> = coverage.into_tiles().into_iter().collect();
+        assert_eq!(
+            tiles,
+            vec![
+                TileId::new(3, 3, 3),
+                TileId::new(3, 3, 4),
+                TileId::new(3, 4, 3),
+                TileId::new(3, 4, 4)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor, Warning};
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+use tracing::span::EnteredSpan;
+use tracing::{debug, debug_span, info_span, trace, warn};
+
+/// Emits [tracing](https://docs.rs/tracing) spans and events for processed datasets and features.
+///
+/// Each dataset is processed in a `dataset` span and each feature in a `feature`
+/// span with the feature index. At the end of a feature, an event with the time spent
+/// in the properties and geometry stages of the wrapped processor is emitted, at the
+/// end of a dataset an event with the number of features and the total time.
+/// [Warnings](Warning) are emitted as `warn` events.
+///
+/// Spans are entered on the current thread, processing must not move to another thread
+/// within a dataset.
+///
+/// # Usage example:
+///
+/// ```rust,ignore
+/// use geozero::geojson::{GeoJsonReader, GeoJsonWriter};
+/// use geozero::processor::{TraceProcessor, TracedRead};
+/// use geozero::GeozeroDatasource;
+///
+/// let mut input = TracedRead::new(File::open("countries.geojson")?);
+/// let mut out: Vec<u8> = Vec::new();
+/// GeoJsonReader(&mut input).process(&mut TraceProcessor::new(GeoJsonWriter::new(&mut out)))?;
+/// ```
+pub struct TraceProcessor<P> {
+    inner: P,
+    dataset_span: Option<EnteredSpan>,
+    feature_span: Option<EnteredSpan>,
+    dataset_start: Option<Instant>,
+    stage_start: Option<Instant>,
+    properties_time: Duration,
+    geometry_time: Duration,
+    feature_count: u64,
+}
+
+impl<P> TraceProcessor<P> {
+    pub fn new(inner: P) -> Self {
+        TraceProcessor {
+            inner,
+            dataset_span: None,
+            feature_span: None,
+            dataset_start: None,
+            stage_start: None,
+            properties_time: Duration::ZERO,
+            geometry_time: Duration::ZERO,
+            feature_count: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn stage_elapsed(&mut self) -> Duration {
+        self.stage_start
+            .take()
+            .map(|start| start.elapsed())
+            .unwrap_or_default()
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for TraceProcessor<P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for TraceProcessor<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for TraceProcessor<P> {
+    forward_processor_methods!(inner; schema, crs, metadata, fid);
+    fn dataset_begin(&mut self, name: Option<&str>) -> Result<()> {
+        self.dataset_span = Some(info_span!("dataset", name = name.unwrap_or_default()).entered());
+        self.dataset_start = Some(Instant::now());
+        self.feature_count = 0;
+        self.inner.dataset_begin(name)
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        let result = self.inner.dataset_end();
+        let elapsed = self
+            .dataset_start
+            .take()
+            .map(|start| start.elapsed())
+            .unwrap_or_default();
+        debug!(
+            features = self.feature_count,
+            elapsed_us = elapsed.as_micros() as u64,
+            "dataset processed"
+        );
+        self.dataset_span = None;
+        result
+    }
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        warn!(kind = ?warning.kind, "{}", warning);
+        self.inner.warning(warning)
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature_span = Some(debug_span!("feature", idx).entered());
+        self.properties_time = Duration::ZERO;
+        self.geometry_time = Duration::ZERO;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        let result = self.inner.feature_end(idx);
+        self.feature_count += 1;
+        trace!(
+            properties_us = self.properties_time.as_micros() as u64,
+            geometry_us = self.geometry_time.as_micros() as u64,
+            "feature processed"
+        );
+        self.feature_span = None;
+        result
+    }
+    fn properties_begin(&mut self) -> Result<()> {
+        self.stage_start = Some(Instant::now());
+        self.inner.properties_begin()
+    }
+    fn properties_end(&mut self) -> Result<()> {
+        let result = self.inner.properties_end();
+        let elapsed = self.stage_elapsed();
+        self.properties_time += elapsed;
+        result
+    }
+    fn geometry_begin(&mut self) -> Result<()> {
+        self.stage_start = Some(Instant::now());
+        self.inner.geometry_begin()
+    }
+    fn geometry_end(&mut self) -> Result<()> {
+        let result = self.inner.geometry_end();
+        let elapsed = self.stage_elapsed();
+        self.geometry_time += elapsed;
+        result
+    }
+}
+
+/// Counts bytes read from the wrapped reader.
+///
+/// The total is emitted as [tracing](https://docs.rs/tracing) event when the reader is dropped.
+pub struct TracedRead<R> {
+    inner: R,
+    bytes_read: u64,
+}
+
+impl<R: Read> TracedRead<R> {
+    pub fn new(inner: R) -> Self {
+        TracedRead {
+            inner,
+            bytes_read: 0,
+        }
+    }
+    /// Number of bytes read
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+}
+
+impl<R: Read> Read for TracedRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R> Drop for TracedRead<R> {
+    fn drop(&mut self) {
+        debug!(bytes_read = self.bytes_read, "input read");
+    }
+}
@@ -0,0 +1,120 @@
+use crate::error::Result;
+use crate::{DatasetMetadata, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Applies a coordinate transformation to all XY coordinates before passing them on.
+///
+/// Z, M and time values are passed unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::TransformXY;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = TransformXY::new(WktWriter::new(&mut out), |x, y| (x * 1000.0, y * 1000.0));
+/// WktStr("LINESTRING(1 2,3 4)").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "LINESTRING(1000 2000,3000 4000)");
+/// ```
+pub struct TransformXY<P, F: Fn(f64, f64) -> (f64, f64)> {
+    inner: P,
+    transform: F,
+}
+
+impl<P, F: Fn(f64, f64) -> (f64, f64)> TransformXY<P, F> {
+    pub fn new(inner: P, transform: F) -> Self {
+        TransformXY { inner, transform }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume transformer and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor, F: Fn(f64, f64) -> (f64, f64)> GeomProcessor for TransformXY<P, F> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, geometries);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        let (x, y) = (self.transform)(x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        let (x, y) = (self.transform)(x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+}
+
+impl<P: PropertyProcessor, F: Fn(f64, f64) -> (f64, f64)> PropertyProcessor for TransformXY<P, F> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor, F: Fn(f64, f64) -> (f64, f64)> FeatureProcessor for TransformXY<P, F> {
+    forward_processor_methods!(inner; dataset, schema, crs, warning, feature, properties,
+        geometry);
+
+    fn metadata(&mut self, metadata: &DatasetMetadata) -> Result<()> {
+        // The extent of arbitrary transformations is unknown
+        self.inner.metadata(&metadata.without_extent())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ColumnValue;
+
+    struct CoordCollector(Vec<(f64, f64, Option<f64>)>);
+
+    impl GeomProcessor for CoordCollector {
+        fn dimensions(&self) -> crate::CoordDimensions {
+            crate::CoordDimensions::xyz()
+        }
+        fn coordinate(
+            &mut self,
+            x: f64,
+            y: f64,
+            z: Option<f64>,
+            _m: Option<f64>,
+            _t: Option<f64>,
+            _tm: Option<u64>,
+            _idx: usize,
+        ) -> Result<()> {
+            self.0.push((x, y, z));
+            Ok(())
+        }
+    }
+    impl PropertyProcessor for CoordCollector {
+        fn property(&mut self, _idx: usize, _name: &str, _value: &ColumnValue) -> Result<bool> {
+            Ok(false)
+        }
+    }
+    impl FeatureProcessor for CoordCollector {}
+
+    #[test]
+    fn transform_coordinates() -> Result<()> {
+        let mut processor = TransformXY::new(CoordCollector(Vec::new()), |x, y| (y, -x));
+        assert!(processor.multi_dim());
+        processor.feature_begin(0)?;
+        assert!(!processor.property(0, "name", &ColumnValue::String("a"))?);
+        processor.point_begin(0)?;
+        processor.coordinate(1.0, 2.0, Some(3.0), None, None, None, 0)?;
+        processor.point_end(0)?;
+        processor.feature_end(0)?;
+        assert_eq!(processor.into_inner().0, vec![(2.0, -1.0, Some(3.0))]);
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::error::Result;
+use crate::{
+    ColumnDef, ColumnValue, DuplicateKeyPolicy, FeatureProcessor, GeomProcessor, OwnedColumnValue,
+    PropertyProcessor,
+};
+
+/// Resolves duplicate property names of a feature according to a [DuplicateKeyPolicy].
+///
+/// Properties of a feature are collected and passed on at `properties_end`, renumbered
+/// after removing duplicates. Duplicate columns of a schema are removed as well.
+/// Properties outside of `properties_begin`/`properties_end` are passed on unchanged.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::processor::UniqueProperties;
+/// use geozero::{ColumnValue, DuplicateKeyPolicy, FeatureProcessor, PropertyProcessor};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor =
+///     UniqueProperties::new(GeoJsonWriter::new(&mut out), DuplicateKeyPolicy::FirstWins);
+/// processor.feature_begin(0).unwrap();
+/// processor.properties_begin().unwrap();
+/// processor.property(0, "name", &ColumnValue::String("Bern")).unwrap();
+/// processor.property(1, "name", &ColumnValue::String("Berne")).unwrap();
+/// processor.properties_end().unwrap();
+/// processor.feature_end(0).unwrap();
+/// let json = std::str::from_utf8(&out).unwrap();
+/// assert!(json.contains(r#""properties": {"name": "Bern"}"#));
+/// ```
+pub struct UniqueProperties<P> {
+    inner: P,
+    policy: DuplicateKeyPolicy,
+    /// Collected properties of current feature
+    properties: Vec<(String, OwnedColumnValue)>,
+    in_properties: bool,
+}
+
+impl<P> UniqueProperties<P> {
+    pub fn new(inner: P, policy: DuplicateKeyPolicy) -> Self {
+        UniqueProperties {
+            inner,
+            policy,
+            properties: Vec::new(),
+            in_properties: false,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for UniqueProperties<P> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for UniqueProperties<P> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if self.in_properties {
+            self.properties.push((name.to_string(), value.into()));
+            Ok(false)
+        } else {
+            self.inner.property(idx, name, value)
+        }
+    }
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for UniqueProperties<P> {
+    forward_processor_methods!(inner; dataset, crs, metadata, warning, feature, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        let mut columns: Vec<(String, ColumnDef)> = columns
+            .iter()
+            .map(|col| (col.name.clone(), col.clone()))
+            .collect();
+        self.policy.apply(&mut columns)?;
+        let columns: Vec<ColumnDef> = columns.into_iter().map(|(_, col)| col).collect();
+        self.inner.schema(&columns)
+    }
+    fn properties_begin(&mut self) -> Result<()> {
+        self.in_properties = true;
+        self.properties.clear();
+        self.inner.properties_begin()
+    }
+    fn properties_end(&mut self) -> Result<()> {
+        self.in_properties = false;
+        self.policy.apply(&mut self.properties)?;
+        for (idx, (name, value)) in self.properties.iter().enumerate() {
+            if self.inner.property(idx, name, &value.as_column_value())? {
+                break;
+            }
+        }
+        self.inner.properties_end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Props(Vec<String>);
+
+    impl GeomProcessor for Props {}
+
+    impl PropertyProcessor for Props {
+        fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+            self.0.push(format!("{}:{}={}", idx, name, value));
+            Ok(false)
+        }
+    }
+
+    impl FeatureProcessor for Props {}
+
+    fn process(policy: DuplicateKeyPolicy) -> Result<Vec<String>> {
+        let mut processor = UniqueProperties::new(Props::default(), policy);
+        processor.feature_begin(0)?;
+        processor.properties_begin()?;
+        processor.property(0, "a", &ColumnValue::Int(1))?;
+        processor.property(1, "b", &ColumnValue::Int(2))?;
+        processor.property(2, "a", &ColumnValue::Int(3))?;
+        processor.property(3, "c", &ColumnValue::Int(4))?;
+        processor.properties_end()?;
+        processor.feature_end(0)?;
+        Ok(processor.into_inner().0)
+    }
+
+    #[test]
+    fn policies() -> Result<()> {
+        assert_eq!(
+            process(DuplicateKeyPolicy::FirstWins)?,
+            vec!["0:a=1", "1:b=2", "2:c=4"]
+        );
+        assert_eq!(
+            process(DuplicateKeyPolicy::LastWins)?,
+            vec!["0:a=3", "1:b=2", "2:c=4"]
+        );
+        assert_eq!(
+            process(DuplicateKeyPolicy::Error).unwrap_err().to_string(),
+            "processing feature property: `duplicate property `a``"
+        );
+        Ok(())
+    }
+}
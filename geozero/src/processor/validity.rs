@@ -0,0 +1,337 @@
+use crate::error::Result;
+use crate::processor::signed_area;
+use crate::{Crs, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use std::fmt;
+
+/// Structural geometry problem
+#[derive(Clone, PartialEq, Debug)]
+pub enum ValidityIssueKind {
+    /// First and last vertex of ring differ
+    UnclosedRing,
+    /// Ring with less than 4 vertices
+    TooFewRingPoints(usize),
+    /// LineString with less than 2 vertices
+    TooFewLineStringPoints(usize),
+    /// Coordinate value is NaN
+    NaNCoordinate,
+    /// Polygon without exterior ring
+    EmptyPolygon,
+    /// Longitude or latitude out of range (RFC 7946)
+    CoordinateOutOfRange,
+    /// Exterior ring not counter-clockwise or interior ring not clockwise (RFC 7946)
+    RingOrientation { exterior: bool },
+    /// Line segment spanning more than 180° longitude instead of being cut at the antimeridian (RFC 7946)
+    AntimeridianCrossing,
+    /// Dataset CRS other than WGS 84 (RFC 7946)
+    NonWgs84Crs(Crs),
+}
+
+/// Structural geometry problem found by [ValidityCheck]
+#[derive(Clone, PartialEq, Debug)]
+pub struct ValidityIssue {
+    /// Index of feature, if processed within a feature
+    pub feature_idx: Option<u64>,
+    pub kind: ValidityIssueKind,
+}
+
+impl fmt::Display for ValidityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(idx) = self.feature_idx {
+            write!(f, "feature {}: ", idx)?;
+        }
+        match &self.kind {
+            ValidityIssueKind::UnclosedRing => write!(f, "unclosed ring"),
+            ValidityIssueKind::TooFewRingPoints(n) => write!(f, "ring with {} points", n),
+            ValidityIssueKind::TooFewLineStringPoints(n) => {
+                write!(f, "linestring with {} points", n)
+            }
+            ValidityIssueKind::NaNCoordinate => write!(f, "NaN coordinate"),
+            ValidityIssueKind::EmptyPolygon => write!(f, "polygon without rings"),
+            ValidityIssueKind::CoordinateOutOfRange => write!(f, "coordinate out of lon/lat range"),
+            ValidityIssueKind::RingOrientation { exterior: true } => {
+                write!(f, "clockwise exterior ring")
+            }
+            ValidityIssueKind::RingOrientation { exterior: false } => {
+                write!(f, "counter-clockwise interior ring")
+            }
+            ValidityIssueKind::AntimeridianCrossing => write!(f, "segment crossing antimeridian"),
+            ValidityIssueKind::NonWgs84Crs(crs) => write!(f, "CRS {} instead of WGS 84", crs),
+        }
+    }
+}
+
+/// Vertices of current linestring
+struct LineState {
+    /// Index of ring within polygon
+    ring: Option<usize>,
+    first: (f64, f64),
+    last: (f64, f64),
+    count: usize,
+    /// Ring vertices for orientation check
+    coords: Vec<(f64, f64)>,
+}
+
+/// Reports structural geometry problems while passing all calls on unchanged.
+///
+/// Checks are limited to problems detectable without a topology engine
+/// (e.g. self-intersections are not detected).
+/// With [rfc7946](ValidityCheck::rfc7946), the stricter rules of GeoJSON
+/// (RFC 7946) are checked as well.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::ValidityCheck;
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut issues = Vec::new();
+/// let mut out: Vec<u8> = Vec::new();
+/// {
+///     let mut processor =
+///         ValidityCheck::new(WktWriter::new(&mut out), |issue| issues.push(issue.to_string()));
+///     WktStr("POLYGON((0 0,1 0,1 1))").process_geom(&mut processor).unwrap();
+/// }
+/// assert_eq!(issues, vec!["unclosed ring", "ring with 3 points"]);
+/// ```
+pub struct ValidityCheck<P, F: FnMut(&ValidityIssue)> {
+    inner: P,
+    report: F,
+    feature_idx: Option<u64>,
+    rfc7946: bool,
+    in_polygon: bool,
+    ring_count: usize,
+    line: Option<LineState>,
+}
+
+impl<P, F: FnMut(&ValidityIssue)> ValidityCheck<P, F> {
+    pub fn new(inner: P, report: F) -> Self {
+        ValidityCheck {
+            inner,
+            report,
+            feature_idx: None,
+            rfc7946: false,
+            in_polygon: false,
+            ring_count: 0,
+            line: None,
+        }
+    }
+    /// Check GeoJSON rules: WGS 84 coordinates in lon/lat range, counter-clockwise
+    /// exterior rings and no segments crossing the antimeridian
+    pub fn rfc7946(mut self) -> Self {
+        self.rfc7946 = true;
+        self
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume checker and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn report(&mut self, kind: ValidityIssueKind) {
+        let issue = ValidityIssue {
+            feature_idx: self.feature_idx,
+            kind,
+        };
+        (self.report)(&issue);
+    }
+    fn check_xy(&mut self, x: f64, y: f64) {
+        if x.is_nan() || y.is_nan() {
+            self.report(ValidityIssueKind::NaNCoordinate);
+        } else if self.rfc7946 && (x.abs() > 180.0 || y.abs() > 90.0) {
+            self.report(ValidityIssueKind::CoordinateOutOfRange);
+        }
+        let mut crossing = false;
+        if let Some(line) = &mut self.line {
+            if line.count == 0 {
+                line.first = (x, y);
+            } else {
+                crossing = (x - line.last.0).abs() > 180.0;
+            }
+            line.last = (x, y);
+            line.count += 1;
+            if self.rfc7946 && line.ring.is_some() {
+                line.coords.push((x, y));
+            }
+        }
+        if self.rfc7946 && crossing {
+            self.report(ValidityIssueKind::AntimeridianCrossing);
+        }
+    }
+}
+
+impl<P: GeomProcessor, F: FnMut(&ValidityIssue)> GeomProcessor for ValidityCheck<P, F> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        self.check_xy(x, y);
+        self.inner.xy(x, y, idx)
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        self.check_xy(x, y);
+        self.inner.coordinate(x, y, z, m, t, tm, idx)
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        self.in_polygon = true;
+        self.ring_count = 0;
+        self.inner.polygon_begin(tagged, size, idx)
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        self.in_polygon = false;
+        if self.ring_count == 0 {
+            self.report(ValidityIssueKind::EmptyPolygon);
+        }
+        self.inner.polygon_end(tagged, idx)
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        let ring = if self.in_polygon && !tagged {
+            self.ring_count += 1;
+            Some(self.ring_count - 1)
+        } else {
+            None
+        };
+        self.line = Some(LineState {
+            ring,
+            first: (0.0, 0.0),
+            last: (0.0, 0.0),
+            count: 0,
+            coords: Vec::new(),
+        });
+        self.inner.linestring_begin(tagged, size, idx)
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if let Some(line) = self.line.take() {
+            if let Some(ring_idx) = line.ring {
+                if line.count > 0 && line.first != line.last {
+                    self.report(ValidityIssueKind::UnclosedRing);
+                }
+                if line.count < 4 {
+                    self.report(ValidityIssueKind::TooFewRingPoints(line.count));
+                }
+                let exterior = ring_idx == 0;
+                let area = signed_area(&line.coords);
+                if self.rfc7946 && (exterior && area < 0.0 || !exterior && area > 0.0) {
+                    self.report(ValidityIssueKind::RingOrientation { exterior });
+                }
+            } else if line.count < 2 {
+                self.report(ValidityIssueKind::TooFewLineStringPoints(line.count));
+            }
+        }
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: PropertyProcessor, F: FnMut(&ValidityIssue)> PropertyProcessor for ValidityCheck<P, F> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor, F: FnMut(&ValidityIssue)> FeatureProcessor for ValidityCheck<P, F> {
+    forward_processor_methods!(inner; dataset, schema, metadata, warning, fid, properties,
+        geometry);
+
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        if self.rfc7946 && crs.epsg() != Some(4326) {
+            self.report(ValidityIssueKind::NonWgs84Crs(crs.clone()));
+        }
+        self.inner.crs(crs)
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature_idx = Some(idx);
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.feature_idx = None;
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ProcessorSink;
+
+    #[test]
+    fn issues() -> Result<()> {
+        let mut issues = Vec::new();
+        {
+            let mut processor =
+                ValidityCheck::new(ProcessorSink::new(), |issue| issues.push(issue.clone()));
+            processor.feature_begin(3)?;
+            processor.polygon_begin(true, 0, 0)?;
+            processor.polygon_end(true, 0)?;
+            processor.feature_end(3)?;
+            processor.linestring_begin(true, 1, 0)?;
+            processor.xy(f64::NAN, 0.0, 0)?;
+            processor.linestring_end(true, 0)?;
+        }
+
+        assert_eq!(
+            issues,
+            vec![
+                ValidityIssue {
+                    feature_idx: Some(3),
+                    kind: ValidityIssueKind::EmptyPolygon
+                },
+                ValidityIssue {
+                    feature_idx: None,
+                    kind: ValidityIssueKind::NaNCoordinate
+                },
+                ValidityIssue {
+                    feature_idx: None,
+                    kind: ValidityIssueKind::TooFewLineStringPoints(1)
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn rfc7946() -> Result<()> {
+        use crate::geojson::GeoJson;
+        use crate::GeozeroDatasource;
+
+        let geojson = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]}},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [0, 1], [1, 1], [0, 0]]]}},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "LineString", "coordinates": [[179, 0], [-179, 0], [200, 95]]}}
+        ]}"#;
+        let mut issues = Vec::new();
+        let mut processor =
+            ValidityCheck::new(ProcessorSink::new(), |issue| issues.push(issue.to_string()))
+                .rfc7946();
+        processor.crs(&Crs::Epsg(2056))?;
+        GeoJson(geojson).process(&mut processor)?;
+        drop(processor);
+        assert_eq!(
+            issues,
+            vec![
+                "CRS EPSG:2056 instead of WGS 84",
+                "feature 1: clockwise exterior ring",
+                "feature 2: segment crossing antimeridian",
+                "feature 2: coordinate out of lon/lat range",
+                "feature 2: segment crossing antimeridian",
+            ]
+        );
+
+        // Structural checks only
+        let mut issues = Vec::new();
+        GeoJson(geojson).process(&mut ValidityCheck::new(ProcessorSink::new(), |issue| {
+            issues.push(issue.to_string())
+        }))?;
+        assert!(issues.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::Result;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor, Warning};
+
+/// Reports [warnings](Warning) of readers and preceding processors to a callback.
+///
+/// Warnings without feature index emitted within a feature get the index of the
+/// current feature. Warnings are passed on to the wrapped processor.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::geojson::GeoJsonWriter;
+/// use geozero::processor::{NonFiniteFilter, NonFinitePolicy, WarningReporter};
+/// use geozero::{FeatureProcessor, GeomProcessor};
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut warnings = Vec::new();
+/// let reporter = WarningReporter::new(GeoJsonWriter::new(&mut out), |w| {
+///     warnings.push(w.to_string())
+/// });
+/// let mut processor = NonFiniteFilter::new(reporter, NonFinitePolicy::SkipFeature);
+/// processor.feature_begin(0).unwrap();
+/// processor.geometry_begin().unwrap();
+/// processor.point_begin(0).unwrap();
+/// processor.xy(f64::NAN, 0.0, 0).unwrap();
+/// processor.point_end(0).unwrap();
+/// processor.geometry_end().unwrap();
+/// processor.feature_end(0).unwrap();
+/// drop(processor);
+/// assert_eq!(
+///     warnings,
+///     vec!["feature 0: skipped feature with non-finite coordinate values"]
+/// );
+/// ```
+pub struct WarningReporter<P, F: FnMut(&Warning)> {
+    inner: P,
+    report: F,
+    /// Index of current feature
+    feature: Option<u64>,
+    warnings: u64,
+}
+
+impl<P, F: FnMut(&Warning)> WarningReporter<P, F> {
+    pub fn new(inner: P, report: F) -> Self {
+        WarningReporter {
+            inner,
+            report,
+            feature: None,
+            warnings: 0,
+        }
+    }
+    /// Number of reported warnings
+    pub fn warning_count(&self) -> u64 {
+        self.warnings
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor, F: FnMut(&Warning)> GeomProcessor for WarningReporter<P, F> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor, F: FnMut(&Warning)> PropertyProcessor for WarningReporter<P, F> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor, F: FnMut(&Warning)> FeatureProcessor for WarningReporter<P, F> {
+    forward_processor_methods!(inner; dataset, schema, crs, metadata, fid, properties, geometry);
+
+    fn warning(&mut self, warning: &Warning) -> Result<()> {
+        self.warnings += 1;
+        match (warning.feature_idx, self.feature) {
+            (None, Some(idx)) => {
+                let warning = warning.clone().feature(idx);
+                (self.report)(&warning);
+                self.inner.warning(&warning)
+            }
+            _ => {
+                (self.report)(warning);
+                self.inner.warning(warning)
+            }
+        }
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature = Some(idx);
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.feature = None;
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ProcessorSink, WarningKind};
+
+    #[test]
+    fn feature_index() -> Result<()> {
+        let mut warnings = Vec::new();
+        let mut processor =
+            WarningReporter::new(ProcessorSink::new(), |w| warnings.push(w.clone()));
+        processor.warning(&Warning::new(WarningKind::Other, "before features"))?;
+        processor.feature_begin(3)?;
+        processor.warning(&Warning::new(WarningKind::CoercedType, "coerced"))?;
+        processor.warning(&Warning::new(WarningKind::Truncated, "truncated").feature(2))?;
+        processor.feature_end(3)?;
+        assert_eq!(processor.warning_count(), 3);
+        let warnings: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "before features",
+                "feature 3: coerced",
+                "feature 2: truncated"
+            ]
+        );
+        Ok(())
+    }
+}
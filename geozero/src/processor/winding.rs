@@ -0,0 +1,175 @@
+use crate::error::Result;
+use crate::processor::CoordSeq;
+use crate::{FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Orientation of polygon exterior rings. Interior rings get the opposite orientation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Orientation {
+    /// Counter-clockwise exterior rings, as required by GeoJSON (RFC 7946)
+    CounterClockwise,
+    /// Clockwise exterior rings, as used by Shapefiles
+    Clockwise,
+}
+
+/// Signed area of a ring (positive for counter-clockwise orientation)
+pub(crate) fn signed_area(xy: &[(f64, f64)]) -> f64 {
+    let closing = xy.last().copied().zip(xy.first().copied());
+    let sum: f64 = xy
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .chain(closing)
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    sum / 2.0
+}
+
+/// Enforces the winding order of polygon rings.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::processor::{Orientation, Orient};
+/// use geozero::wkt::{WktStr, WktWriter};
+/// use geozero::GeozeroGeometry;
+///
+/// let mut out: Vec<u8> = Vec::new();
+/// let mut processor = Orient::new(WktWriter::new(&mut out), Orientation::CounterClockwise);
+/// WktStr("POLYGON((0 0,0 1,1 1,0 0))").process_geom(&mut processor).unwrap();
+/// assert_eq!(std::str::from_utf8(&out).unwrap(), "POLYGON((0 0,1 1,0 1,0 0))");
+/// ```
+pub struct Orient<P> {
+    inner: P,
+    orientation: Orientation,
+    in_polygon: bool,
+    /// Ring index within current polygon
+    ring_idx: Option<usize>,
+    coords: CoordSeq,
+}
+
+impl<P> Orient<P> {
+    pub fn new(inner: P, orientation: Orientation) -> Self {
+        Orient {
+            inner,
+            orientation,
+            in_polygon: false,
+            ring_idx: None,
+            coords: CoordSeq::default(),
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume processor and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: GeomProcessor> GeomProcessor for Orient<P> {
+    forward_processor_methods!(inner; dimensions, srid, empty_point, point, multipoint,
+        multilinestring, multipolygon, geometrycollection, curves, surfaces);
+
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        if self.ring_idx.is_some() {
+            self.coords.push_xy(x, y, idx);
+            Ok(())
+        } else {
+            self.inner.xy(x, y, idx)
+        }
+    }
+    fn coordinate(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        t: Option<f64>,
+        tm: Option<u64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.ring_idx.is_some() {
+            self.coords.push_coordinate(x, y, z, m, t, tm, idx);
+            Ok(())
+        } else {
+            self.inner.coordinate(x, y, z, m, t, tm, idx)
+        }
+    }
+    fn polygon_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        self.in_polygon = true;
+        self.inner.polygon_begin(tagged, size, idx)
+    }
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        self.in_polygon = false;
+        self.inner.polygon_end(tagged, idx)
+    }
+    fn linestring_begin(&mut self, tagged: bool, size: usize, idx: usize) -> Result<()> {
+        if self.in_polygon && !tagged {
+            self.ring_idx = Some(idx);
+            Ok(())
+        } else {
+            self.inner.linestring_begin(tagged, size, idx)
+        }
+    }
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
+        if let Some(ring_idx) = self.ring_idx.take() {
+            let ccw = signed_area(&self.coords.xy) > 0.0;
+            let exterior = ring_idx == 0;
+            let want_ccw = (self.orientation == Orientation::CounterClockwise) == exterior;
+            if ccw != want_ccw {
+                self.coords.reverse();
+            }
+            self.inner
+                .linestring_begin(tagged, self.coords.len(), idx)?;
+            self.coords.emit(&mut self.inner)?;
+        }
+        self.inner.linestring_end(tagged, idx)
+    }
+}
+
+impl<P: PropertyProcessor> PropertyProcessor for Orient<P> {
+    forward_processor_methods!(inner; property);
+}
+
+impl<P: FeatureProcessor> FeatureProcessor for Orient<P> {
+    forward_processor_methods!(inner; feature_processor);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn area() {
+        let ccw = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)];
+        assert_eq!(signed_area(&ccw), 4.0);
+        let mut cw = ccw;
+        cw.reverse();
+        assert_eq!(signed_area(&cw), -4.0);
+    }
+
+    #[test]
+    #[cfg(feature = "with-wkt")]
+    fn orient_holes() -> Result<()> {
+        use crate::wkt::{WktStr, WktWriter};
+        use crate::GeozeroGeometry;
+
+        let wkt = "POLYGON((0 0,10 0,10 10,0 10,0 0),(1 1,2 1,2 2,1 1))";
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = Orient::new(WktWriter::new(&mut out), Orientation::Clockwise);
+        WktStr(wkt).process_geom(&mut processor)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "POLYGON((0 0,0 10,10 10,10 0,0 0),(1 1,2 1,2 2,1 1))"
+        );
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut processor = Orient::new(WktWriter::new(&mut out), Orientation::CounterClockwise);
+        WktStr(wkt).process_geom(&mut processor)?;
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "POLYGON((0 0,10 0,10 10,0 10,0 0),(1 1,2 2,2 1,1 1))"
+        );
+        Ok(())
+    }
+}
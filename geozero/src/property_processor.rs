@@ -1,8 +1,14 @@
 use crate::error::{GeozeroError, Result};
+use serde_json::{Map as JsonMap, Value as JsonValue};
 use std::collections::HashMap;
 use std::fmt;
 
 /// Feature property value.
+///
+/// `BigInt` contains the decimal digits of an integer exceeding the range of `i64` and `u64`.
+/// `DateTime` values are formatted according to RFC 3339, `Date` values as `YYYY-MM-DD`.
+/// `Json` contains a serialized JSON value (array, object, ...).
+/// `Null` is an explicit NULL value, whereas missing properties are not emitted at all.
 #[derive(PartialEq, Debug)]
 pub enum ColumnValue<'a> {
     Byte(i8),
@@ -16,14 +22,201 @@ pub enum ColumnValue<'a> {
     ULong(u64),
     Float(f32),
     Double(f64),
+    BigInt(&'a str),
     String(&'a str),
     Json(&'a str),
     DateTime(&'a str),
+    Date(&'a str),
     Binary(&'a [u8]),
+    Null,
+}
+
+/// Owned feature property value.
+///
+/// Allocating counterpart of [ColumnValue] for keeping property values beyond a `property` call.
+#[derive(Clone, PartialEq, Debug)]
+pub enum OwnedColumnValue {
+    Byte(i8),
+    UByte(u8),
+    Bool(bool),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    Long(i64),
+    ULong(u64),
+    Float(f32),
+    Double(f64),
+    BigInt(String),
+    String(String),
+    Json(String),
+    DateTime(String),
+    Date(String),
+    Binary(Vec<u8>),
+    Null,
+}
+
+/// Feature property type.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColumnType {
+    Byte,
+    UByte,
+    Bool,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Float,
+    Double,
+    BigInt,
+    String,
+    Json,
+    DateTime,
+    Date,
+    Binary,
+    /// Type of NULL values without type information
+    Null,
+}
+
+/// Column definition of a dataset schema.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColumnDef {
+    /// Column name
+    pub name: String,
+    /// Column type
+    pub col_type: ColumnType,
+    /// Column can contain NULL values
+    pub nullable: bool,
+    /// Maximal column width (e.g. string length), if known
+    pub width: Option<usize>,
+}
+
+impl ColumnDef {
+    /// Nullable column without width information
+    pub fn new(name: &str, col_type: ColumnType) -> Self {
+        ColumnDef {
+            name: name.to_string(),
+            col_type,
+            nullable: true,
+            width: None,
+        }
+    }
+}
+
+impl ColumnValue<'_> {
+    /// Type of property value
+    pub fn column_type(&self) -> ColumnType {
+        match self {
+            ColumnValue::Byte(_) => ColumnType::Byte,
+            ColumnValue::UByte(_) => ColumnType::UByte,
+            ColumnValue::Bool(_) => ColumnType::Bool,
+            ColumnValue::Short(_) => ColumnType::Short,
+            ColumnValue::UShort(_) => ColumnType::UShort,
+            ColumnValue::Int(_) => ColumnType::Int,
+            ColumnValue::UInt(_) => ColumnType::UInt,
+            ColumnValue::Long(_) => ColumnType::Long,
+            ColumnValue::ULong(_) => ColumnType::ULong,
+            ColumnValue::Float(_) => ColumnType::Float,
+            ColumnValue::Double(_) => ColumnType::Double,
+            ColumnValue::BigInt(_) => ColumnType::BigInt,
+            ColumnValue::String(_) => ColumnType::String,
+            ColumnValue::Json(_) => ColumnType::Json,
+            ColumnValue::DateTime(_) => ColumnType::DateTime,
+            ColumnValue::Date(_) => ColumnType::Date,
+            ColumnValue::Binary(_) => ColumnType::Binary,
+            ColumnValue::Null => ColumnType::Null,
+        }
+    }
+}
+
+impl From<&ColumnValue<'_>> for OwnedColumnValue {
+    fn from(v: &ColumnValue) -> Self {
+        match *v {
+            ColumnValue::Byte(v) => OwnedColumnValue::Byte(v),
+            ColumnValue::UByte(v) => OwnedColumnValue::UByte(v),
+            ColumnValue::Bool(v) => OwnedColumnValue::Bool(v),
+            ColumnValue::Short(v) => OwnedColumnValue::Short(v),
+            ColumnValue::UShort(v) => OwnedColumnValue::UShort(v),
+            ColumnValue::Int(v) => OwnedColumnValue::Int(v),
+            ColumnValue::UInt(v) => OwnedColumnValue::UInt(v),
+            ColumnValue::Long(v) => OwnedColumnValue::Long(v),
+            ColumnValue::ULong(v) => OwnedColumnValue::ULong(v),
+            ColumnValue::Float(v) => OwnedColumnValue::Float(v),
+            ColumnValue::Double(v) => OwnedColumnValue::Double(v),
+            ColumnValue::BigInt(v) => OwnedColumnValue::BigInt(v.to_string()),
+            ColumnValue::String(v) => OwnedColumnValue::String(v.to_string()),
+            ColumnValue::Json(v) => OwnedColumnValue::Json(v.to_string()),
+            ColumnValue::DateTime(v) => OwnedColumnValue::DateTime(v.to_string()),
+            ColumnValue::Date(v) => OwnedColumnValue::Date(v.to_string()),
+            ColumnValue::Binary(v) => OwnedColumnValue::Binary(v.to_vec()),
+            ColumnValue::Null => OwnedColumnValue::Null,
+        }
+    }
+}
+
+impl OwnedColumnValue {
+    /// Borrowed property value, e.g. for passing to a `PropertyProcessor`
+    pub fn as_column_value(&self) -> ColumnValue<'_> {
+        match self {
+            OwnedColumnValue::Byte(v) => ColumnValue::Byte(*v),
+            OwnedColumnValue::UByte(v) => ColumnValue::UByte(*v),
+            OwnedColumnValue::Bool(v) => ColumnValue::Bool(*v),
+            OwnedColumnValue::Short(v) => ColumnValue::Short(*v),
+            OwnedColumnValue::UShort(v) => ColumnValue::UShort(*v),
+            OwnedColumnValue::Int(v) => ColumnValue::Int(*v),
+            OwnedColumnValue::UInt(v) => ColumnValue::UInt(*v),
+            OwnedColumnValue::Long(v) => ColumnValue::Long(*v),
+            OwnedColumnValue::ULong(v) => ColumnValue::ULong(*v),
+            OwnedColumnValue::Float(v) => ColumnValue::Float(*v),
+            OwnedColumnValue::Double(v) => ColumnValue::Double(*v),
+            OwnedColumnValue::BigInt(v) => ColumnValue::BigInt(v),
+            OwnedColumnValue::String(v) => ColumnValue::String(v),
+            OwnedColumnValue::Json(v) => ColumnValue::Json(v),
+            OwnedColumnValue::DateTime(v) => ColumnValue::DateTime(v),
+            OwnedColumnValue::Date(v) => ColumnValue::Date(v),
+            OwnedColumnValue::Binary(v) => ColumnValue::Binary(v),
+            OwnedColumnValue::Null => ColumnValue::Null,
+        }
+    }
+}
+
+/// Convert property value to JSON.
+///
+/// `Json` values are parsed, `BigInt` values are converted to strings and `Binary` values
+/// to an array of bytes.
+impl From<&ColumnValue<'_>> for JsonValue {
+    fn from(v: &ColumnValue) -> Self {
+        match *v {
+            ColumnValue::Byte(v) => JsonValue::from(v),
+            ColumnValue::UByte(v) => JsonValue::from(v),
+            ColumnValue::Bool(v) => JsonValue::from(v),
+            ColumnValue::Short(v) => JsonValue::from(v),
+            ColumnValue::UShort(v) => JsonValue::from(v),
+            ColumnValue::Int(v) => JsonValue::from(v),
+            ColumnValue::UInt(v) => JsonValue::from(v),
+            ColumnValue::Long(v) => JsonValue::from(v),
+            ColumnValue::ULong(v) => JsonValue::from(v),
+            ColumnValue::Float(v) => JsonValue::from(v),
+            ColumnValue::Double(v) => JsonValue::from(v),
+            ColumnValue::Json(v) => serde_json::from_str(v).unwrap_or_else(|_| JsonValue::from(v)),
+            ColumnValue::BigInt(v)
+            | ColumnValue::String(v)
+            | ColumnValue::DateTime(v)
+            | ColumnValue::Date(v) => JsonValue::from(v),
+            ColumnValue::Binary(v) => JsonValue::from(v),
+            ColumnValue::Null => JsonValue::Null,
+        }
+    }
 }
 
 /// Feature property processing trait.
 ///
+/// Property names and values are borrowed from the internal buffers of the datasource
+/// whenever possible and are only valid for the duration of the `property` call.
+/// Processors which need the values afterwards have to copy them.
+///
 /// # Usage example:
 ///
 /// ```rust
@@ -60,10 +253,13 @@ impl fmt::Display for ColumnValue<'_> {
             ColumnValue::ULong(v) => write!(f, "{}", v),
             ColumnValue::Float(v) => write!(f, "{}", v),
             ColumnValue::Double(v) => write!(f, "{}", v),
+            ColumnValue::BigInt(v) => write!(f, "{}", v),
             ColumnValue::String(v) => write!(f, "{}", v),
             ColumnValue::Json(v) => write!(f, "{}", v),
             ColumnValue::DateTime(v) => write!(f, "{}", v),
+            ColumnValue::Date(v) => write!(f, "{}", v),
             ColumnValue::Binary(_v) => write!(f, "[BINARY]"),
+            ColumnValue::Null => Ok(()),
         }
     }
 }
@@ -115,10 +311,13 @@ macro_rules! impl_scalar_property_reader {
     ( $t:ty, $e:path ) => {
         impl From<&ColumnValue<'_>> for Result<$t> {
             fn from(v: &ColumnValue) -> Result<$t> {
-                if let $e(v) = v {
-                    Ok(*v)
-                } else {
-                    Err(GeozeroError::ColumnType(stringify!($e).to_string(), format!("{:?}", v)))
+                match v {
+                    $e(v) => Ok(*v),
+                    ColumnValue::Null => Err(GeozeroError::ColumnNotFound),
+                    _ => Err(GeozeroError::ColumnType(
+                        stringify!($e).to_string(),
+                        format!("{:?}", v),
+                    )),
                 }
             }
         }
@@ -144,7 +343,10 @@ impl_scalar_property_reader!(f64, ColumnValue::Double);
 
 impl From<&ColumnValue<'_>> for Result<String> {
     fn from(v: &ColumnValue) -> Result<String> {
-        Ok(v.to_string())
+        match v {
+            ColumnValue::Null => Err(GeozeroError::ColumnNotFound),
+            _ => Ok(v.to_string()),
+        }
     }
 }
 
@@ -156,7 +358,24 @@ impl PropertyReadType for String {
 
 impl PropertyProcessor for HashMap<String, String> {
     fn property(&mut self, _idx: usize, colname: &str, colval: &ColumnValue) -> Result<bool> {
-        self.insert(colname.to_string(), colval.to_string());
+        // NULL values are omitted
+        if colval != &ColumnValue::Null {
+            self.insert(colname.to_string(), colval.to_string());
+        }
+        Ok(false)
+    }
+}
+
+impl PropertyProcessor for HashMap<String, OwnedColumnValue> {
+    fn property(&mut self, _idx: usize, colname: &str, colval: &ColumnValue) -> Result<bool> {
+        self.insert(colname.to_string(), colval.into());
+        Ok(false)
+    }
+}
+
+impl PropertyProcessor for JsonMap<String, JsonValue> {
+    fn property(&mut self, _idx: usize, colname: &str, colval: &ColumnValue) -> Result<bool> {
+        self.insert(colname.to_string(), colval.into());
         Ok(false)
     }
 }
@@ -177,4 +396,44 @@ fn convert_column_value() {
         Result::<i32>::from(v).unwrap_err().to_string(),
         "expected a `ColumnValue::Int` value but found `String(\"Yes\")`"
     );
+
+    let v = &ColumnValue::Null;
+    assert!(matches!(
+        Result::<i32>::from(v),
+        Err(GeozeroError::ColumnNotFound)
+    ));
+    assert!(matches!(
+        Result::<String>::from(v),
+        Err(GeozeroError::ColumnNotFound)
+    ));
+}
+
+#[test]
+fn column_type() {
+    assert_eq!(ColumnValue::Int(42).column_type(), ColumnType::Int);
+    assert_eq!(ColumnValue::String("Yes").column_type(), ColumnType::String);
+    let def = ColumnDef::new("name", ColumnType::String);
+    assert!(def.nullable);
+    assert_eq!(def.width, None);
+}
+
+#[test]
+fn owned_values() {
+    let mut props: HashMap<String, OwnedColumnValue> = HashMap::new();
+    props
+        .property(0, "name", &ColumnValue::String("Yes"))
+        .unwrap();
+    props.property(1, "count", &ColumnValue::Int(42)).unwrap();
+    assert_eq!(props["name"], OwnedColumnValue::String("Yes".to_string()));
+    assert_eq!(props["count"].as_column_value(), ColumnValue::Int(42));
+
+    let mut json = JsonMap::new();
+    json.property(0, "tags", &ColumnValue::Json("[1,2]"))
+        .unwrap();
+    json.property(1, "bin", &ColumnValue::Binary(&[0, 255]))
+        .unwrap();
+    assert_eq!(
+        JsonValue::Object(json),
+        serde_json::json!({"bin": [0, 255], "tags": [1, 2]})
+    );
 }
@@ -0,0 +1,462 @@
+//! Schema inference for schemaless datasources.
+use crate::api::GeozeroDatasource;
+use crate::error::{GeozeroError, Result};
+use crate::feature_processor::FeatureProcessor;
+use crate::geometry_processor::GeomProcessor;
+use crate::processor::forward_processor_methods;
+use crate::property_processor::{ColumnDef, ColumnType, ColumnValue, PropertyProcessor};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Infers column names, types and maximal widths from feature properties.
+///
+/// Columns are reported in order of their first appearance. Columns with differing
+/// value types are widened (e.g. `Int` and `Double` to `Double`) or fall back to `String`.
+/// A column is nullable, if it is missing or NULL in at least one feature.
+///
+/// With a sample size, [`GeozeroError::Stopped`] is returned after the last sampled
+/// feature, which makes the reader stop without processing the remaining input.
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::{ColumnType, GeozeroDatasource, SchemaInferrer};
+/// use geozero::geojson::GeoJson;
+///
+/// let mut geojson = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"name": "Bern", "pop": 133115}, "geometry": null},
+///     {"type": "Feature", "properties": {"name": "Zürich", "pop": 421878.5}, "geometry": null}]}"#);
+/// let mut inferrer = SchemaInferrer::new();
+/// geojson.process(&mut inferrer).unwrap();
+/// let columns = inferrer.columns();
+/// assert_eq!(columns[0].width, Some(6));
+/// assert_eq!(columns[1].col_type, ColumnType::Double);
+/// ```
+#[derive(Default, Debug)]
+pub struct SchemaInferrer {
+    columns: Vec<ColumnDef>,
+    column_idx: HashMap<String, usize>,
+    /// Columns seen in current feature
+    seen: Vec<bool>,
+    sample_size: Option<usize>,
+    feature_count: usize,
+}
+
+impl SchemaInferrer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Only inspect the first `n` features and stop processing afterwards
+    pub fn with_sample_size(n: usize) -> Self {
+        SchemaInferrer {
+            sample_size: Some(n),
+            ..Default::default()
+        }
+    }
+    /// Inferred columns
+    pub fn columns(&self) -> &[ColumnDef] {
+        &self.columns
+    }
+    /// Consume inferrer and return inferred columns
+    pub fn into_columns(self) -> Vec<ColumnDef> {
+        self.columns
+    }
+    fn sampling(&self) -> bool {
+        match self.sample_size {
+            Some(n) => self.feature_count < n,
+            None => true,
+        }
+    }
+}
+
+/// Infer schema of a datasource, optionally from its first `sample_size` features only.
+pub fn infer_schema<D: GeozeroDatasource>(
+    datasource: &mut D,
+    sample_size: Option<usize>,
+) -> Result<Vec<ColumnDef>> {
+    let mut inferrer = SchemaInferrer {
+        sample_size,
+        ..Default::default()
+    };
+    match datasource.process(&mut inferrer) {
+        Ok(()) | Err(GeozeroError::Stopped) => Ok(inferrer.into_columns()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Common type of two column types
+pub(crate) fn widen_type(t1: ColumnType, t2: ColumnType) -> ColumnType {
+    use ColumnType::*;
+    let int_rank = |t| match t {
+        Bool => Some(0),
+        Byte | UByte => Some(1),
+        Short | UShort => Some(2),
+        Int | UInt => Some(3),
+        Long | ULong => Some(4),
+        BigInt => Some(5),
+        _ => None,
+    };
+    match (t1, t2) {
+        _ if t1 == t2 => t1,
+        (Null, t) | (t, Null) => t,
+        (Date, DateTime) | (DateTime, Date) => DateTime,
+        (Float, Double) | (Double, Float) => Double,
+        (Float | Double, t) | (t, Float | Double) if int_rank(t).unwrap_or(0) > 0 => Double,
+        _ => match (int_rank(t1), int_rank(t2)) {
+            (Some(r1), Some(r2)) if r1 > 0 && r2 > 0 => {
+                if r1 >= r2 {
+                    t1
+                } else {
+                    t2
+                }
+            }
+            _ => String,
+        },
+    }
+}
+
+/// Width of formatted value
+fn value_width(value: &ColumnValue) -> usize {
+    match value {
+        ColumnValue::String(v)
+        | ColumnValue::Json(v)
+        | ColumnValue::DateTime(v)
+        | ColumnValue::Date(v) => v.chars().count(),
+        ColumnValue::Binary(v) => v.len(),
+        ColumnValue::Null => 0,
+        _ => value.to_string().len(),
+    }
+}
+
+impl PropertyProcessor for SchemaInferrer {
+    fn property(&mut self, _idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        if !self.sampling() {
+            return Ok(true);
+        }
+        let col_type = value.column_type();
+        let width = value_width(value);
+        if let Some(&idx) = self.column_idx.get(name) {
+            let column = &mut self.columns[idx];
+            column.col_type = widen_type(column.col_type, col_type);
+            column.width = column.width.max(Some(width));
+            column.nullable |= col_type == ColumnType::Null;
+            self.seen[idx] = true;
+        } else {
+            self.column_idx.insert(name.to_string(), self.columns.len());
+            let mut column = ColumnDef::new(name, col_type);
+            column.width = Some(width);
+            // Column was missing in previous features
+            column.nullable = self.feature_count > 0 || col_type == ColumnType::Null;
+            self.columns.push(column);
+            self.seen.push(true);
+        }
+        Ok(false)
+    }
+}
+
+impl FeatureProcessor for SchemaInferrer {
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        self.seen.iter_mut().for_each(|seen| *seen = false);
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        if self.sampling() {
+            for (column, seen) in self.columns.iter_mut().zip(&self.seen) {
+                if !seen {
+                    column.nullable = true;
+                }
+            }
+            self.feature_count += 1;
+        }
+        if self.sampling() {
+            Ok(())
+        } else {
+            Err(GeozeroError::Stopped)
+        }
+    }
+}
+
+impl GeomProcessor for SchemaInferrer {}
+
+/// Schema inconsistency found by [SchemaCheck]
+#[derive(Clone, PartialEq, Debug)]
+pub enum SchemaIssueKind {
+    /// Property not declared in dataset schema
+    UndeclaredColumn(String),
+    /// Property value not matching the declared or previously seen column type
+    TypeMismatch {
+        column: String,
+        expected: ColumnType,
+        found: ColumnType,
+    },
+    /// NULL value in column declared as not nullable
+    NullValue(String),
+}
+
+/// Schema inconsistency of a feature
+#[derive(Clone, PartialEq, Debug)]
+pub struct SchemaIssue {
+    pub feature_idx: u64,
+    pub kind: SchemaIssueKind,
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "feature {}: ", self.feature_idx)?;
+        match &self.kind {
+            SchemaIssueKind::UndeclaredColumn(name) => write!(f, "undeclared column `{}`", name),
+            SchemaIssueKind::TypeMismatch {
+                column,
+                expected,
+                found,
+            } => write!(f, "{:?} value in {:?} column `{}`", found, expected, column),
+            SchemaIssueKind::NullValue(name) => write!(f, "NULL in not nullable column `{}`", name),
+        }
+    }
+}
+
+/// Reports property values inconsistent with the dataset schema while passing all calls on unchanged.
+///
+/// Values are checked against the columns declared with
+/// [schema](FeatureProcessor::schema). Properties of schemaless datasources are
+/// checked against the type of the first non-NULL value of the column, widened for
+/// compatible types (e.g. `Int` and `Double`).
+///
+/// # Usage example:
+///
+/// ```rust
+/// use geozero::{GeozeroDatasource, ProcessorSink, SchemaCheck};
+/// use geozero::geojson::GeoJson;
+///
+/// let mut geojson = GeoJson(r#"{"type": "FeatureCollection", "features": [
+///     {"type": "Feature", "properties": {"pop": 133115}, "geometry": null},
+///     {"type": "Feature", "properties": {"pop": "unknown"}, "geometry": null}]}"#);
+/// let mut issues = Vec::new();
+/// geojson
+///     .process(&mut SchemaCheck::new(ProcessorSink::new(), |issue| {
+///         issues.push(issue.to_string())
+///     }))
+///     .unwrap();
+/// assert_eq!(issues, vec!["feature 1: String value in Long column `pop`"]);
+/// ```
+pub struct SchemaCheck<P, F: FnMut(&SchemaIssue)> {
+    inner: P,
+    report: F,
+    /// Columns declared by datasource
+    declared: bool,
+    columns: HashMap<String, ColumnDef>,
+    feature_idx: u64,
+}
+
+impl<P, F: FnMut(&SchemaIssue)> SchemaCheck<P, F> {
+    pub fn new(inner: P, report: F) -> Self {
+        SchemaCheck {
+            inner,
+            report,
+            declared: false,
+            columns: HashMap::new(),
+            feature_idx: 0,
+        }
+    }
+    /// Wrapped processor
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+    /// Consume checker and return wrapped processor
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+    fn report(&mut self, kind: SchemaIssueKind) {
+        let issue = SchemaIssue {
+            feature_idx: self.feature_idx,
+            kind,
+        };
+        (self.report)(&issue);
+    }
+    fn check(&mut self, name: &str, found: ColumnType) {
+        let column = match self.columns.get_mut(name) {
+            Some(column) => column,
+            None if self.declared => {
+                return self.report(SchemaIssueKind::UndeclaredColumn(name.to_string()))
+            }
+            None => {
+                self.columns
+                    .insert(name.to_string(), ColumnDef::new(name, found));
+                return;
+            }
+        };
+        let expected = column.col_type;
+        if found == ColumnType::Null {
+            if self.declared && !column.nullable {
+                self.report(SchemaIssueKind::NullValue(name.to_string()));
+            }
+            return;
+        }
+        let widened = widen_type(expected, found);
+        if widened == expected {
+            return;
+        }
+        if !self.declared && (expected == ColumnType::Null || widened != ColumnType::String) {
+            column.col_type = widened;
+            return;
+        }
+        self.report(SchemaIssueKind::TypeMismatch {
+            column: name.to_string(),
+            expected,
+            found,
+        });
+    }
+}
+
+impl<P: GeomProcessor, F: FnMut(&SchemaIssue)> GeomProcessor for SchemaCheck<P, F> {
+    forward_processor_methods!(inner; dimensions, srid, xy, coordinate, empty_point, geometries);
+}
+
+impl<P: PropertyProcessor, F: FnMut(&SchemaIssue)> PropertyProcessor for SchemaCheck<P, F> {
+    fn property(&mut self, idx: usize, name: &str, value: &ColumnValue) -> Result<bool> {
+        self.check(name, value.column_type());
+        self.inner.property(idx, name, value)
+    }
+}
+
+impl<P: FeatureProcessor, F: FnMut(&SchemaIssue)> FeatureProcessor for SchemaCheck<P, F> {
+    forward_processor_methods!(inner; dataset, crs, metadata, warning, fid, properties, geometry);
+
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.declared = !columns.is_empty();
+        self.columns = columns
+            .iter()
+            .map(|column| (column.name.clone(), column.clone()))
+            .collect();
+        self.inner.schema(columns)
+    }
+    fn feature_begin(&mut self, idx: u64) -> Result<()> {
+        self.feature_idx = idx;
+        self.inner.feature_begin(idx)
+    }
+    fn feature_end(&mut self, idx: u64) -> Result<()> {
+        self.inner.feature_end(idx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ProcessorSink;
+
+    #[test]
+    fn type_widening() {
+        use ColumnType::*;
+        assert_eq!(widen_type(Int, Int), Int);
+        assert_eq!(widen_type(Int, Long), Long);
+        assert_eq!(widen_type(BigInt, ULong), BigInt);
+        assert_eq!(widen_type(Int, Double), Double);
+        assert_eq!(widen_type(Float, Double), Double);
+        assert_eq!(widen_type(Date, DateTime), DateTime);
+        assert_eq!(widen_type(Bool, Int), String);
+        assert_eq!(widen_type(Int, String), String);
+        assert_eq!(widen_type(Null, Date), Date);
+    }
+
+    #[test]
+    fn infer_columns() -> Result<()> {
+        let mut inferrer = SchemaInferrer::with_sample_size(2);
+        inferrer.feature_begin(0)?;
+        inferrer.property(0, "name", &ColumnValue::String("Bern"))?;
+        inferrer.property(1, "pop", &ColumnValue::Long(133115))?;
+        inferrer.feature_end(0)?;
+        inferrer.feature_begin(1)?;
+        inferrer.property(0, "name", &ColumnValue::String("Zürich"))?;
+        inferrer.property(1, "area", &ColumnValue::Double(87.88))?;
+        assert!(matches!(
+            inferrer.feature_end(1),
+            Err(GeozeroError::Stopped)
+        ));
+
+        let columns = inferrer.into_columns();
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns[0].name, "name");
+        assert_eq!(columns[0].width, Some(6));
+        assert!(!columns[0].nullable);
+        assert_eq!(columns[1].col_type, ColumnType::Long);
+        assert!(columns[1].nullable);
+        assert_eq!(columns[2].col_type, ColumnType::Double);
+        assert!(columns[2].nullable);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "with-geojson")]
+    fn infer_sample() -> Result<()> {
+        use crate::geojson::GeoJson;
+
+        let mut geojson = GeoJson(
+            r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {"pop": 133115}, "geometry": null},
+            {"type": "Feature", "properties": {"pop": "unknown"}, "geometry": null},
+            {"type": "Feature", "properties": {"pop": 421878.5}, "geometry": null}]}"#,
+        );
+        let columns = infer_schema(&mut geojson, Some(1))?;
+        assert_eq!(columns[0].col_type, ColumnType::Long);
+        let columns = infer_schema(&mut geojson, None)?;
+        assert_eq!(columns[0].col_type, ColumnType::String);
+        Ok(())
+    }
+
+    #[test]
+    fn check_declared_schema() -> Result<()> {
+        let mut issues = Vec::new();
+        {
+            let mut check = SchemaCheck::new(ProcessorSink::new(), |issue| {
+                issues.push(issue.kind.clone())
+            });
+            let mut name = ColumnDef::new("name", ColumnType::String);
+            name.nullable = false;
+            check.schema(&[name, ColumnDef::new("pop", ColumnType::Long)])?;
+            check.feature_begin(0)?;
+            check.property(0, "name", &ColumnValue::Null)?;
+            check.property(1, "pop", &ColumnValue::Int(133115))?;
+            check.property(2, "area", &ColumnValue::Double(51.62))?;
+            check.feature_end(0)?;
+            check.feature_begin(1)?;
+            check.property(0, "name", &ColumnValue::String("Zürich"))?;
+            check.property(1, "pop", &ColumnValue::Double(421878.5))?;
+            check.feature_end(1)?;
+        }
+        assert_eq!(
+            issues,
+            vec![
+                SchemaIssueKind::NullValue("name".to_string()),
+                SchemaIssueKind::UndeclaredColumn("area".to_string()),
+                SchemaIssueKind::TypeMismatch {
+                    column: "pop".to_string(),
+                    expected: ColumnType::Long,
+                    found: ColumnType::Double
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn check_inferred_schema() -> Result<()> {
+        let mut issues = Vec::new();
+        {
+            let mut check =
+                SchemaCheck::new(ProcessorSink::new(), |issue| issues.push(issue.to_string()));
+            let values = [
+                ColumnValue::Null,
+                ColumnValue::Long(133115),
+                ColumnValue::Double(421878.5),
+                ColumnValue::Bool(true),
+            ];
+            for (idx, value) in values.iter().enumerate() {
+                check.feature_begin(idx as u64)?;
+                check.property(0, "pop", value)?;
+                check.feature_end(idx as u64)?;
+            }
+        }
+        assert_eq!(issues, vec!["feature 3: Bool value in Double column `pop`"]);
+        Ok(())
+    }
+}
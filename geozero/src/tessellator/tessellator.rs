@@ -17,7 +17,7 @@ pub trait VertexOutput {
 pub struct Tessellator<'a> {
     vertex_out: &'a dyn VertexOutput,
     builder: RefCell<Builder>,
-    num_rings: usize,
+    in_line: bool,
 }
 
 impl<'a> Tessellator<'a> {
@@ -25,15 +25,19 @@ impl<'a> Tessellator<'a> {
         Tessellator {
             vertex_out: out,
             builder: RefCell::new(Path::builder()),
-            num_rings: 0,
+            in_line: false,
         }
     }
 }
 
 impl<'a> GeomProcessor for Tessellator<'a> {
     fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
+        // Points don't contribute to tessellated paths
+        if !self.in_line {
+            return Ok(());
+        }
         if idx == 0 {
-            self.builder.borrow_mut().move_to(point(x as f32, y as f32));
+            self.builder.borrow_mut().begin(point(x as f32, y as f32));
         } else {
             self.builder.borrow_mut().line_to(point(x as f32, y as f32));
         }
@@ -51,16 +55,17 @@ impl<'a> GeomProcessor for Tessellator<'a> {
     fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
         Ok(())
     }
-    fn linestring_begin(&mut self, tagged: bool, _size: usize, _idx: usize) -> Result<()> {
-        if tagged {
-            self.num_rings = 1;
-        }
+    fn linestring_begin(&mut self, _tagged: bool, size: usize, _idx: usize) -> Result<()> {
+        self.in_line = size > 0;
         Ok(())
     }
-    fn linestring_end(&mut self, tagged: bool, idx: usize) -> Result<()> {
-        if idx == self.num_rings {
-            self.builder.borrow_mut().close();
+    fn linestring_end(&mut self, tagged: bool, _idx: usize) -> Result<()> {
+        if !self.in_line {
+            return Ok(());
         }
+        self.in_line = false;
+        // Polygon rings are closed, lines are open
+        self.builder.borrow_mut().end(!tagged);
         if tagged {
             let builder = self.builder.replace(Path::builder());
             let path = builder.build();
@@ -74,10 +79,6 @@ impl<'a> GeomProcessor for Tessellator<'a> {
     fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
         Ok(())
     }
-    fn polygon_begin(&mut self, _tagged: bool, size: usize, _idx: usize) -> Result<()> {
-        self.num_rings = size;
-        Ok(())
-    }
     fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
         let builder = self.builder.replace(Path::builder());
         let path = builder.build();
@@ -91,7 +92,7 @@ fn tessellate_line(path: &Path) {
     let mut vertex_builder = simple_builder(&mut geometry);
     let mut tessellator = StrokeTessellator::new();
     tessellator
-        .tessellate(path, &StrokeOptions::default(), &mut vertex_builder)
+        .tessellate_path(path, &StrokeOptions::default(), &mut vertex_builder)
         .unwrap();
     println!(
         " -- {:?} vertices {:?} indices",
@@ -106,9 +107,9 @@ fn tessellate_poly(path: &Path, out: &dyn VertexOutput) {
         .tessellate_path(
             path,
             &FillOptions::default(),
-            &mut BuffersBuilder::new(&mut buffers, |pos: Point, _: FillAttributes| {
+            &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
+                let pos = vertex.position();
                 out.vertex(pos.x, pos.y, 0.0);
-                ()
             }),
         )
         .unwrap();
@@ -135,7 +136,7 @@ impl VertexOutput for ObjWriter {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::geojson_reader::read_geojson;
+    use crate::geojson::read_geojson;
 
     #[test]
     fn point_geom() {
@@ -5,33 +5,41 @@ use scroll::IOread;
 use std::io::Read;
 
 /// WKB reader.
-pub struct Wkb(pub Vec<u8>);
+///
+/// Reads from any contiguous buffer like `Vec<u8>`, `&[u8]` or `bytes::Bytes` without copying.
+pub struct Wkb<B: AsRef<[u8]> = Vec<u8>>(pub B);
 
-impl GeozeroGeometry for Wkb {
+impl<B: AsRef<[u8]>> GeozeroGeometry for Wkb<B> {
     fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
-        process_wkb_geom(&mut self.0.as_slice(), processor)
+        process_wkb_geom(&mut self.0.as_ref(), processor)
     }
 }
 
 /// EWKB reader.
-pub struct Ewkb(pub Vec<u8>);
+///
+/// Reads from any contiguous buffer like `Vec<u8>`, `&[u8]` or `bytes::Bytes` without copying.
+pub struct Ewkb<B: AsRef<[u8]> = Vec<u8>>(pub B);
 
-impl GeozeroGeometry for Ewkb {
+impl<B: AsRef<[u8]>> GeozeroGeometry for Ewkb<B> {
     fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
-        process_ewkb_geom(&mut self.0.as_slice(), processor)
+        process_ewkb_geom(&mut self.0.as_ref(), processor)
     }
 }
 
 /// GeoPackage WKB reader.
-pub struct GpkgWkb(pub Vec<u8>);
+///
+/// Reads from any contiguous buffer like `Vec<u8>`, `&[u8]` or `bytes::Bytes` without copying.
+pub struct GpkgWkb<B: AsRef<[u8]> = Vec<u8>>(pub B);
 
-impl GeozeroGeometry for GpkgWkb {
+impl<B: AsRef<[u8]>> GeozeroGeometry for GpkgWkb<B> {
     fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
-        process_gpkg_geom(&mut self.0.as_slice(), processor)
+        process_gpkg_geom(&mut self.0.as_ref(), processor)
     }
 }
 
 /// Process WKB geometry.
+///
+/// Non-contiguous buffers implementing `bytes::Buf` are read with `Buf::reader`.
 pub fn process_wkb_geom<R: Read, P: GeomProcessor>(raw: &mut R, processor: &mut P) -> Result<()> {
     let info = read_wkb_header(raw)?;
     process_wkb_geom_n(raw, &info, read_wkb_header, 0, processor)
@@ -62,6 +70,25 @@ pub fn process_wkb_type_geom<R: Read, P: GeomProcessor>(
     }
 }
 
+/// Read WKB type geometry from async input and process it.
+///
+/// The input is read until EOF, e.g. a message body.
+#[cfg(feature = "with-tokio")]
+pub async fn process_wkb_type_geom_async<R, P>(
+    raw: &mut R,
+    processor: &mut P,
+    dialect: WkbDialect,
+) -> Result<()>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    P: GeomProcessor,
+{
+    use tokio::io::AsyncReadExt;
+    let mut buf = Vec::new();
+    raw.read_to_end(&mut buf).await?;
+    process_wkb_type_geom(&mut buf.as_slice(), processor, dialect)
+}
+
 #[derive(Debug)]
 pub(crate) struct WkbInfo {
     endian: scroll::Endian,
@@ -70,8 +97,17 @@ pub(crate) struct WkbInfo {
     has_m: bool,
     #[allow(dead_code)]
     srid: Option<i32>,
+    /// GPKG envelope values, `env_len` of them are used
+    envelope: [f64; 8],
+    env_len: usize,
+}
+
+impl WkbInfo {
+    /// GPKG envelope
     #[allow(dead_code)]
-    envelope: Vec<f64>,
+    fn envelope(&self) -> &[f64] {
+        &self.envelope[..self.env_len]
+    }
 }
 
 /// OGC WKB header.
@@ -94,7 +130,8 @@ pub(crate) fn read_wkb_header<R: Read>(raw: &mut R) -> Result<WkbInfo> {
         has_z,
         has_m,
         srid: None,
-        envelope: Vec::new(),
+        envelope: [0.0; 8],
+        env_len: 0,
     };
     Ok(info)
 }
@@ -125,7 +162,8 @@ fn read_ewkb_header<R: Read>(raw: &mut R) -> Result<WkbInfo> {
         has_z,
         has_m,
         srid,
-        envelope: Vec::new(),
+        envelope: [0.0; 8],
+        env_len: 0,
     };
     Ok(info)
 }
@@ -157,10 +195,10 @@ fn read_gpkg_header<R: Read>(raw: &mut R) -> Result<WkbInfo> {
         scroll::LE
     };
     let srid = raw.ioread_with::<i32>(endian)?;
-    let envelope: std::result::Result<Vec<f64>, _> = (0..env_len)
-        .map(|_| raw.ioread_with::<f64>(endian))
-        .collect();
-    let envelope = envelope?;
+    let mut envelope = [0.0; 8];
+    for value in envelope.iter_mut().take(env_len) {
+        *value = raw.ioread_with::<f64>(endian)?;
+    }
 
     let ogc_info = read_wkb_header(raw)?;
 
@@ -171,6 +209,7 @@ fn read_gpkg_header<R: Read>(raw: &mut R) -> Result<WkbInfo> {
         has_m: ogc_info.has_m,
         srid: Some(srid),
         envelope,
+        env_len,
     };
     Ok(info)
 }
@@ -631,7 +670,7 @@ mod test {
         assert_eq!(info.base_type, WKBGeometryType::MultiLineString);
         assert!(info.has_z);
         assert!(info.has_m);
-        assert_eq!(info.envelope, vec![10.0, 20.0, 10.0, 20.0]);
+        assert_eq!(info.envelope(), &[10.0, 20.0, 10.0, 20.0]);
 
         let mut wkt_data: Vec<u8> = Vec::new();
         assert!(process_gpkg_geom(&mut wkb.as_slice(), &mut WktWriter::new(&mut wkt_data)).is_ok());
@@ -644,7 +683,7 @@ mod test {
         let wkb = hex::decode("47500003e6100000000000000000f03f0000000000003640000000000000084000000000000036400107000000020000000101000000000000000000f03f00000000000008400103000000010000000400000000000000000035400000000000003540000000000000364000000000000035400000000000003540000000000000364000000000000035400000000000003540").unwrap();
         let info = read_gpkg_header(&mut wkb.as_slice()).unwrap();
         assert_eq!(info.base_type, WKBGeometryType::GeometryCollection);
-        assert_eq!(info.envelope, vec![1.0, 22.0, 3.0, 22.0]);
+        assert_eq!(info.envelope(), &[1.0, 22.0, 3.0, 22.0]);
 
         let mut wkt_data: Vec<u8> = Vec::new();
         assert!(process_gpkg_geom(&mut wkb.as_slice(), &mut WktWriter::new(&mut wkt_data)).is_ok());
@@ -672,5 +711,9 @@ mod test {
 
         let wkb = GpkgWkb(hex::decode("47500003E61000009A9999999999F13F9A9999999999F13F9A9999999999F13F9A9999999999F13F01010000009A9999999999F13F9A9999999999F13F").unwrap());
         assert_eq!(wkb.to_wkt().unwrap(), "POINT(1.1 1.1)");
+
+        // Borrowed buffer
+        let data = hex::decode("0101000000000000000000244000000000000034C0").unwrap();
+        assert_eq!(Wkb(&data[..]).to_wkt().unwrap(), "POINT(10 -20)");
     }
 }
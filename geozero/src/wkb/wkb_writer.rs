@@ -1,6 +1,8 @@
 use crate::error::Result;
 use crate::wkb::{WKBByteOrder, WKBGeometryType, WkbDialect};
-use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use crate::{
+    CoordDimensions, Crs, FeatureProcessor, GeomProcessor, PropertyProcessor, WriterOptions,
+};
 use scroll::IOwrite;
 use std::io::Write;
 
@@ -30,6 +32,39 @@ enum GeomState {
     MultiPointGeom,
 }
 
+/// Builder for [WkbWriter].
+#[derive(Debug)]
+pub struct WkbWriterBuilder {
+    dialect: WkbDialect,
+    options: WriterOptions,
+}
+
+impl WkbWriterBuilder {
+    /// Set shared writer options
+    pub fn options(mut self, options: WriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Create writer
+    pub fn build<W: Write>(self, out: &mut W) -> WkbWriter<'_, W> {
+        let mut writer = WkbWriter::new(out, self.dialect);
+        writer.dims = self.options.dims;
+        writer.srid = self.options.crs.as_ref().and_then(Crs::srid);
+        writer
+    }
+}
+
+// Not generic over the output type, which is set by `build`
+impl WkbWriter<'_, Vec<u8>> {
+    /// Writer builder
+    pub fn builder(dialect: WkbDialect) -> WkbWriterBuilder {
+        WkbWriterBuilder {
+            dialect,
+            options: WriterOptions::default(),
+        }
+    }
+}
+
 impl<'a, W: Write> WkbWriter<'a, W> {
     pub fn new(out: &'a mut W, dialect: WkbDialect) -> WkbWriter<'a, W> {
         WkbWriter {
@@ -288,7 +323,14 @@ impl<W: Write> GeomProcessor for WkbWriter<'_, W> {
 
 impl<W: Write> PropertyProcessor for WkbWriter<'_, W> {}
 
-impl<W: Write> FeatureProcessor for WkbWriter<'_, W> {}
+impl<W: Write> FeatureProcessor for WkbWriter<'_, W> {
+    fn crs(&mut self, crs: &Crs) -> Result<()> {
+        if self.srid.is_none() {
+            self.srid = crs.srid();
+        }
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -13,6 +13,17 @@ pub(crate) mod conversion {
     use crate::{CoordDimensions, GeozeroGeometry};
 
     /// Convert to WKT.
+    ///
+    /// # Usage example:
+    ///
+    /// Convert a GeoJSON geometry to WKT:
+    ///
+    /// ```
+    /// use geozero::{ToWkt, geojson::GeoJson};
+    ///
+    /// let geojson = GeoJson(r#"{"type": "Point", "coordinates": [10, -20]}"#);
+    /// assert_eq!(geojson.to_wkt().unwrap(), "POINT(10 -20)");
+    /// ```
     pub trait ToWkt {
         /// Convert to 2D WKT String.
         fn to_wkt(&self) -> Result<String>;
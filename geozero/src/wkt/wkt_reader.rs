@@ -1,5 +1,6 @@
 use crate::error::{GeozeroError, Result};
-use crate::{FeatureProcessor, GeomProcessor, GeozeroDatasource, GeozeroGeometry};
+use crate::processor::{read_input, Borrowed, ResourceLimiter};
+use crate::{FeatureProcessor, GeomProcessor, GeozeroDatasource, GeozeroGeometry, ReadOptions};
 
 use std::io::Read;
 use wkt::types::{
@@ -40,16 +41,32 @@ impl<'a, R: Read> GeozeroDatasource for WktReader<'a, R> {
     fn process<P: FeatureProcessor>(&mut self, processor: &mut P) -> Result<()> {
         read_wkt(&mut self.0, processor)
     }
+    /// WKT is read into memory up to the `max_input_size` limit and parsed,
+    /// the other resource limits are checked while processing the parsed geometry.
+    fn process_with_options<P: FeatureProcessor>(
+        &mut self,
+        processor: &mut P,
+        options: &ReadOptions,
+    ) -> Result<()> {
+        let wkt_string = read_input(&mut self.0, &options.limits)?;
+        let mut processor = ResourceLimiter::new(Borrowed::new(processor), options.limits);
+        process_wkt_str(&wkt_string, &mut processor)
+    }
 }
 
 /// Read and process WKT geometry.
 pub fn read_wkt<R: Read, P: GeomProcessor>(reader: &mut R, processor: &mut P) -> Result<()> {
-    use std::str::FromStr;
     // PERF: it would be good to avoid copying data into this string when we already
     // have a string as input. Maybe the wkt crate needs a from_reader implementation.
     let mut wkt_string = String::new();
     reader.read_to_string(&mut wkt_string)?;
-    let wkt = wkt::Wkt::from_str(&wkt_string).map_err(|e| GeozeroError::Geometry(e.to_string()))?;
+    process_wkt_str(&wkt_string, processor)
+}
+
+/// Parse and process WKT geometry
+fn process_wkt_str<P: GeomProcessor>(wkt_string: &str, processor: &mut P) -> Result<()> {
+    use std::str::FromStr;
+    let wkt = wkt::Wkt::from_str(wkt_string).map_err(|e| GeozeroError::Geometry(e.to_string()))?;
     process_wkt_geom(&wkt.item, processor)
 }
 
@@ -1,40 +1,153 @@
 use crate::error::Result;
-use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor};
+use crate::write_buffer::{check_finite, format_coord, WriteBuffer};
+use crate::{CoordDimensions, FeatureProcessor, GeomProcessor, PropertyProcessor, WriterOptions};
 use std::io::Write;
 
 /// WKT Writer.
+///
+/// Output is written unbuffered, unless an output buffer is set with `buffer_size`.
+/// Buffered output is written at the end of the dataset or with `flush`.
+/// Geometries of dataset features are separated by commas.
 pub struct WktWriter<'a, W: Write> {
     pub dims: CoordDimensions,
-    out: &'a mut W,
+    out: WriteBuffer<'a, W>,
+    /// Maximal number of decimals of coordinates
+    pub(crate) precision: Option<usize>,
+    pub(crate) state: WktState,
+}
+
+/// Writer state between processor calls
+#[derive(Clone, Copy, Default)]
+pub(crate) struct WktState {
+    /// Suffix of a begun geometry without parts, which is written as `EMPTY`
+    empty_suffix: Option<&'static [u8]>,
+    /// Within a point
+    in_point: bool,
+    /// Top-level geometry of a feature follows
+    feature_geom: bool,
+    /// Number of written features
+    features: u64,
+}
+
+/// Builder for [WktWriter].
+#[derive(Clone, Default, Debug)]
+pub struct WktWriterBuilder {
+    options: WriterOptions,
+    precision: Option<usize>,
+}
+
+impl WktWriterBuilder {
+    /// Set shared writer options
+    pub fn options(mut self, options: WriterOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Maximal number of decimals of coordinates (default: shortest exact representation)
+    pub fn precision(mut self, decimals: usize) -> Self {
+        self.precision = Some(decimals);
+        self
+    }
+    /// Create writer
+    pub fn build<W: Write>(self, out: &mut W) -> WktWriter<'_, W> {
+        let mut writer = WktWriter::new(out).buffer_size(self.options.buffer_size);
+        writer.dims = self.options.dims;
+        writer.precision = self.precision;
+        writer.out.set_discard(self.options.dry_run);
+        writer
+    }
+}
+
+// Not generic over the output type, which is set by `build`
+impl WktWriter<'_, Vec<u8>> {
+    /// Writer builder
+    pub fn builder() -> WktWriterBuilder {
+        WktWriterBuilder::default()
+    }
 }
 
 impl<'a, W: Write> WktWriter<'a, W> {
     pub fn new(out: &'a mut W) -> WktWriter<'a, W> {
         WktWriter {
             dims: CoordDimensions::default(),
-            out,
+            out: WriteBuffer::new(out),
+            precision: None,
+            state: WktState::default(),
         }
     }
+    /// Size of output buffer in bytes (default: 0, unbuffered)
+    pub fn buffer_size(mut self, bytes: usize) -> Self {
+        // Nothing is buffered yet
+        let _ = self.out.set_capacity(bytes);
+        self
+    }
+    /// Write buffered output
+    pub fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+    /// Write opening parenthesis of the parent geometry, when its first part follows
+    fn open(&mut self) -> Result<()> {
+        if self.state.empty_suffix.take().is_some() {
+            self.out.write_all(b"(")?;
+        }
+        Ok(())
+    }
     fn geom_begin(&mut self, idx: usize, tag: &[u8]) -> Result<()> {
-        if idx > 0 {
+        self.open()?;
+        // Feature geometries are separated independently of their index
+        let feature_geom = std::mem::take(&mut self.state.feature_geom);
+        if idx > 0 && !feature_geom {
             self.out.write_all(b",")?;
         }
         self.out.write_all(tag)?;
+        self.state.empty_suffix = Some(b" EMPTY");
         Ok(())
     }
     fn tagged_geom_begin(&mut self, tagged: bool, idx: usize, tag: &[u8]) -> Result<()> {
-        if idx > 0 {
-            self.out.write_all(b",")?;
-        }
         if tagged {
-            self.out.write_all(tag)?;
+            self.geom_begin(idx, tag)
         } else {
-            self.out.write_all(b"(")?;
+            self.geom_begin(idx, b"")?;
+            self.state.empty_suffix = Some(b"EMPTY");
+            Ok(())
         }
-        Ok(())
     }
     fn geom_end(&mut self) -> Result<()> {
-        self.out.write_all(b")")?;
+        match self.state.empty_suffix.take() {
+            Some(suffix) => self.out.write_all(suffix)?,
+            None => self.out.write_all(b")")?,
+        }
+        Ok(())
+    }
+    fn write_coord(
+        &mut self,
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        idx: usize,
+    ) -> Result<()> {
+        if self.state.in_point && x.is_nan() && y.is_nan() {
+            // Empty point in WKB
+            return Ok(());
+        }
+        check_finite(&[x, y, z.unwrap_or(0.0), m.unwrap_or(0.0)])?;
+        self.open()?;
+        if idx > 0 {
+            self.out.write_all(b",")?;
+        }
+        write!(
+            self.out,
+            "{} {}",
+            format_coord(x, self.precision),
+            format_coord(y, self.precision)
+        )?;
+        if let Some(z) = z {
+            write!(self.out, " {}", format_coord(z, self.precision))?;
+        }
+        if let Some(m) = m {
+            write!(self.out, " {}", format_coord(m, self.precision))?;
+        }
         Ok(())
     }
 }
@@ -44,12 +157,7 @@ impl<W: Write> GeomProcessor for WktWriter<'_, W> {
         self.dims
     }
     fn xy(&mut self, x: f64, y: f64, idx: usize) -> Result<()> {
-        if idx == 0 {
-            self.out.write_all(&format!("{} {}", x, y).as_bytes())?;
-        } else {
-            self.out.write_all(&format!(",{} {}", x, y).as_bytes())?;
-        }
-        Ok(())
+        self.write_coord(x, y, None, None, idx)
     }
     fn coordinate(
         &mut self,
@@ -61,115 +169,107 @@ impl<W: Write> GeomProcessor for WktWriter<'_, W> {
         _tm: Option<u64>,
         idx: usize,
     ) -> Result<()> {
-        if idx == 0 {
-            self.out.write_all(&format!("{} {}", x, y).as_bytes())?;
-        } else {
-            self.out.write_all(&format!(",{} {}", x, y).as_bytes())?;
-        }
-        if let Some(z) = z {
-            self.out.write_all(&format!(" {}", z).as_bytes())?;
-        }
-        if let Some(m) = m {
-            self.out.write_all(&format!(" {}", m).as_bytes())?;
-        }
-        Ok(())
+        self.write_coord(x, y, z, m, idx)
     }
 
     fn point_begin(&mut self, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"POINT(")
+        self.geom_begin(idx, b"POINT")?;
+        self.state.in_point = true;
+        Ok(())
     }
     fn point_end(&mut self, _idx: usize) -> Result<()> {
+        self.state.in_point = false;
         self.geom_end()
     }
 
     fn empty_point(&mut self, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"POINT EMPTY")
-        // we intentionally omit calling geom_end(), because POINT EMPTY has no closing paren
+        // Written as POINT EMPTY
+        self.geom_begin(idx, b"POINT")?;
+        self.geom_end()
     }
 
     fn multipoint_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"MULTIPOINT(")
+        self.geom_begin(idx, b"MULTIPOINT")
     }
     fn multipoint_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn linestring_begin(&mut self, tagged: bool, _size: usize, idx: usize) -> Result<()> {
-        self.tagged_geom_begin(tagged, idx, b"LINESTRING(")
+        self.tagged_geom_begin(tagged, idx, b"LINESTRING")
     }
     fn linestring_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn multilinestring_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"MULTILINESTRING(")
+        self.geom_begin(idx, b"MULTILINESTRING")
     }
     fn multilinestring_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn polygon_begin(&mut self, tagged: bool, _size: usize, idx: usize) -> Result<()> {
-        self.tagged_geom_begin(tagged, idx, b"POLYGON(")
+        self.tagged_geom_begin(tagged, idx, b"POLYGON")
     }
     fn polygon_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn multipolygon_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"MULTIPOLYGON(")
+        self.geom_begin(idx, b"MULTIPOLYGON")
     }
     fn multipolygon_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
-    fn geometrycollection_begin(&mut self, _size: usize, _idx: usize) -> Result<()> {
-        self.out.write_all(b"GEOMETRYCOLLECTION(")?;
-        Ok(())
+    fn geometrycollection_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
+        self.geom_begin(idx, b"GEOMETRYCOLLECTION")
     }
     fn geometrycollection_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn circularstring_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"CIRCULARSTRING(")
+        self.geom_begin(idx, b"CIRCULARSTRING")
     }
     fn circularstring_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn compoundcurve_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"COMPOUNDCURVE(")
+        self.geom_begin(idx, b"COMPOUNDCURVE")
     }
 
     fn compoundcurve_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn curvepolygon_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"CURVEPOLYGON(")
+        self.geom_begin(idx, b"CURVEPOLYGON")
     }
     fn curvepolygon_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn multicurve_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"MULTICURVE(")
+        self.geom_begin(idx, b"MULTICURVE")
     }
     fn multicurve_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn multisurface_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"MULTISURFACE(")
+        self.geom_begin(idx, b"MULTISURFACE")
     }
     fn multisurface_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
 
     fn triangle_begin(&mut self, tagged: bool, _size: usize, idx: usize) -> Result<()> {
-        self.tagged_geom_begin(tagged, idx, b"TRIANGLE(")
+        self.tagged_geom_begin(tagged, idx, b"TRIANGLE")
     }
     fn triangle_end(&mut self, _tagged: bool, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn polyhedralsurface_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"POLYHEDRALSURFACE(")
+        self.geom_begin(idx, b"POLYHEDRALSURFACE")
     }
     fn polyhedralsurface_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
     }
     fn tin_begin(&mut self, _size: usize, idx: usize) -> Result<()> {
-        self.geom_begin(idx, b"TIN(")
+        self.geom_begin(idx, b"TIN")
     }
     fn tin_end(&mut self, _idx: usize) -> Result<()> {
         self.geom_end()
@@ -178,16 +278,110 @@ impl<W: Write> GeomProcessor for WktWriter<'_, W> {
 
 impl<W: Write> PropertyProcessor for WktWriter<'_, W> {}
 
-impl<W: Write> FeatureProcessor for WktWriter<'_, W> {}
+impl<W: Write> FeatureProcessor for WktWriter<'_, W> {
+    fn feature_begin(&mut self, _idx: u64) -> Result<()> {
+        if self.state.features > 0 {
+            self.out.write_all(b",")?;
+        }
+        self.state.feature_geom = true;
+        Ok(())
+    }
+    fn feature_end(&mut self, _idx: u64) -> Result<()> {
+        self.state.feature_geom = false;
+        self.state.features += 1;
+        Ok(())
+    }
+    fn dataset_end(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
 
 #[cfg(test)]
 mod test {
-    use crate::ToWkt;
+    use super::*;
+    use crate::wkt::WktStr;
+    use crate::GeozeroGeometry;
 
     #[test]
     #[cfg(feature = "with-geo")]
     fn to_wkt() {
+        use crate::ToWkt;
+
         let geom: geo_types::Geometry<f64> = geo_types::Point::new(10.0, 20.0).into();
         assert_eq!(&geom.to_wkt().unwrap(), "POINT(10 20)");
     }
+
+    #[test]
+    fn builder_precision() {
+        let mut out: Vec<u8> = Vec::new();
+        let options = WriterOptions::default().with_buffer_size(8);
+        let mut writer = WktWriter::builder()
+            .precision(1)
+            .options(options)
+            .build(&mut out);
+        WktStr("LINESTRING(1.23456 2,3 4.06)")
+            .process_geom(&mut writer)
+            .unwrap();
+        writer.flush().unwrap();
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "LINESTRING(1.2 2,3 4.1)"
+        );
+    }
+
+    #[test]
+    fn empty_and_nested() {
+        use crate::ToWkt;
+
+        for wkt in [
+            "LINESTRING EMPTY",
+            "MULTIPOLYGON EMPTY",
+            "GEOMETRYCOLLECTION EMPTY",
+            "GEOMETRYCOLLECTION(POINT EMPTY,LINESTRING EMPTY,POINT(1 2))",
+            "GEOMETRYCOLLECTION(POINT(1 2),GEOMETRYCOLLECTION(POINT(3 4),POINT(5 6)))",
+        ] {
+            assert_eq!(WktStr(wkt).to_wkt().unwrap(), wkt);
+        }
+    }
+
+    #[test]
+    fn features() -> Result<()> {
+        let mut out: Vec<u8> = Vec::new();
+        let mut writer = WktWriter::new(&mut out);
+        // Features with geometry index 0 and filtered features with gaps
+        for (idx, x) in [(0, 1.0), (1, 2.0), (0, 3.0), (5, 4.0)] {
+            writer.feature_begin(idx)?;
+            writer.point_begin(idx as usize)?;
+            writer.xy(x, 0.0, 0)?;
+            writer.point_end(idx as usize)?;
+            writer.feature_end(idx)?;
+        }
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            "POINT(1 0),POINT(2 0),POINT(3 0),POINT(4 0)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite() {
+        let mut out: Vec<u8> = Vec::new();
+        let mut writer = WktWriter::new(&mut out);
+        // NaN point of WKB
+        writer.point_begin(0).unwrap();
+        writer.xy(f64::NAN, f64::NAN, 0).unwrap();
+        writer.point_end(0).unwrap();
+        assert_eq!(std::str::from_utf8(&out).unwrap(), "POINT EMPTY");
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut writer = WktWriter::new(&mut out);
+        writer.linestring_begin(true, 2, 0).unwrap();
+        writer.xy(0.0, 0.0, 0).unwrap();
+        assert!(writer.xy(f64::NAN, 1.0, 1).is_err());
+        assert!(writer
+            .coordinate(1.0, 1.0, Some(f64::INFINITY), None, None, None, 1)
+            .is_err());
+        assert!(!std::str::from_utf8(&out).unwrap().contains("NaN"));
+    }
 }
@@ -0,0 +1,210 @@
+use crate::error::{GeozeroError, Result};
+use crate::ColumnValue;
+use std::io::{self, IoSlice, Write};
+
+/// Output buffer of text writers.
+///
+/// With a capacity of 0, writes are passed through unbuffered. Otherwise small
+/// writes are collected and written when the buffer is full. Writes larger than the
+/// buffer are written together with the buffered data in a single vectored write.
+///
+/// Buffered data is written by `flush`, the buffer is not flushed when dropped.
+/// When discarding, all writes succeed without writing anything.
+pub(crate) struct WriteBuffer<'a, W: Write> {
+    out: &'a mut W,
+    buf: Vec<u8>,
+    capacity: usize,
+    discard: bool,
+}
+
+impl<'a, W: Write> WriteBuffer<'a, W> {
+    pub fn new(out: &'a mut W) -> Self {
+        WriteBuffer {
+            out,
+            buf: Vec::new(),
+            capacity: 0,
+            discard: false,
+        }
+    }
+    /// Discard all writes (dry run)
+    pub fn set_discard(&mut self, discard: bool) {
+        self.discard = discard;
+    }
+    /// Set buffer size, flushing buffered data
+    pub fn set_capacity(&mut self, capacity: usize) -> io::Result<()> {
+        self.flush_buf()?;
+        self.capacity = capacity;
+        self.buf = Vec::with_capacity(capacity);
+        Ok(())
+    }
+    fn flush_buf(&mut self) -> io::Result<()> {
+        if !self.buf.is_empty() {
+            self.out.write_all(&self.buf)?;
+            self.buf.clear();
+        }
+        Ok(())
+    }
+    /// Write buffered data followed by `data`
+    fn write_all_vectored(&mut self, data: &[u8]) -> io::Result<()> {
+        let buffered = self.buf.len();
+        let mut written = 0;
+        while written < buffered + data.len() {
+            let n = if written < buffered {
+                self.out
+                    .write_vectored(&[IoSlice::new(&self.buf[written..]), IoSlice::new(data)])?
+            } else {
+                self.out.write(&data[written - buffered..])?
+            };
+            if n == 0 {
+                return Err(io::ErrorKind::WriteZero.into());
+            }
+            written += n;
+        }
+        self.buf.clear();
+        Ok(())
+    }
+}
+
+/// Fail on NaN or infinite coordinate values, which text formats can't represent
+pub(crate) fn check_finite(values: &[f64]) -> Result<()> {
+    match values.iter().find(|v| !v.is_finite()) {
+        Some(v) => Err(GeozeroError::Geometry(format!(
+            "non-finite coordinate value `{}`",
+            v
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Hash of feature properties and coordinates for [FidStrategy::Hash](crate::FidStrategy::Hash).
+///
+/// Uses 64-bit FNV-1a, which is stable across platforms and Rust versions.
+pub(crate) struct FidHash(u64);
+
+impl Default for FidHash {
+    fn default() -> Self {
+        FidHash(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl FidHash {
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    pub fn property(&mut self, name: &str, value: &ColumnValue) {
+        self.update(name.as_bytes());
+        self.update(&[0]);
+        match value {
+            ColumnValue::Binary(v) => self.update(v),
+            _ => self.update(value.to_string().as_bytes()),
+        }
+        self.update(&[0]);
+    }
+    pub fn coord(&mut self, values: &[f64]) {
+        for v in values {
+            self.update(&v.to_le_bytes());
+        }
+    }
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Coordinate value with at most `precision` decimals, without trailing zeros
+pub(crate) fn format_coord(v: f64, precision: Option<usize>) -> String {
+    match precision {
+        None => v.to_string(),
+        Some(precision) => {
+            let s = format!("{:.*}", precision, v);
+            let s = if s.contains('.') {
+                s.trim_end_matches('0').trim_end_matches('.')
+            } else {
+                &s
+            };
+            if s == "-0" {
+                "0".to_string()
+            } else {
+                s.to_string()
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for WriteBuffer<'_, W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.discard {
+            return Ok(data.len());
+        }
+        if self.capacity == 0 {
+            return self.out.write(data);
+        }
+        if self.buf.len() + data.len() > self.capacity {
+            if data.len() >= self.capacity {
+                self.write_all_vectored(data)?;
+                return Ok(data.len());
+            }
+            self.flush_buf()?;
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        if self.discard {
+            return Ok(());
+        }
+        self.flush_buf()?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Records the size of each write call
+    #[derive(Default)]
+    struct Calls {
+        data: Vec<u8>,
+        writes: Vec<usize>,
+    }
+
+    impl Write for Calls {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.writes.push(data.len());
+            self.data.extend_from_slice(data);
+            Ok(data.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn buffered_writes() -> io::Result<()> {
+        let mut out = Calls::default();
+        let mut buffer = WriteBuffer::new(&mut out);
+        buffer.set_capacity(8)?;
+        buffer.write_all(b"abc")?;
+        buffer.write_all(b"def")?;
+        buffer.write_all(b"gh")?;
+        buffer.write_all(b"0123456789")?;
+        buffer.write_all(b"x")?;
+        buffer.flush()?;
+        // Default write_vectored writes the first non-empty slice only
+        assert_eq!(out.writes, vec![8, 10, 1]);
+        assert_eq!(out.data, b"abcdefgh0123456789x");
+        Ok(())
+    }
+
+    #[test]
+    fn coord_precision() {
+        assert_eq!(format_coord(1.23456789, None), "1.23456789");
+        assert_eq!(format_coord(1.23456789, Some(3)), "1.235");
+        assert_eq!(format_coord(1.5, Some(3)), "1.5");
+        assert_eq!(format_coord(2.0, Some(3)), "2");
+        assert_eq!(format_coord(-0.0001, Some(2)), "0");
+        assert_eq!(format_coord(10.0, Some(0)), "10");
+    }
+}
@@ -0,0 +1,72 @@
+//! Allocation counts of readers in steady state.
+//!
+//! Readers reuse their buffers across features, processing a feature into a
+//! non-allocating processor must not allocate.
+use geozero::mvt::tile;
+use geozero::wkb::process_gpkg_geom;
+use geozero::wkt::WktStr;
+use geozero::{GeozeroDatasource, ProcessorSink, ToMvt};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts allocations per thread, tests run in parallel
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made by `f` in the current thread
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn mvt_layer() {
+    let mut layer = tile::Layer {
+        name: "polygons".to_string(),
+        keys: vec!["name".to_string()],
+        values: vec![tile::Value {
+            string_value: Some("holes".to_string()),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    for wkt in [
+        "LINESTRING(0 0,10 10,20 0)",
+        "MULTILINESTRING((0 0,10 10),(20 20,30 30))",
+        "MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0),(2 2,2 4,4 4,4 2,2 2)),((20 20,30 20,30 30,20 20)))",
+    ] {
+        let mut feature = WktStr(wkt).to_mvt().unwrap();
+        feature.tags = vec![0, 0];
+        layer.features.push(feature);
+    }
+    let mut sink = ProcessorSink::new();
+    assert_eq!(allocations(|| layer.process(&mut sink).unwrap()), 0);
+}
+
+#[test]
+fn gpkg_wkb() {
+    // MultiLineString ZM with envelope
+    let wkb = hex::decode("47500003E6100000000000000000244000000000000034400000000000002440000000000000344001BD0B00000100000001BA0B0000020000000000000000003440000000000000244000000000000008400000000000001440000000000000244000000000000034400000000000001C400000000000000040").unwrap();
+    let mut sink = ProcessorSink::new();
+    assert_eq!(
+        allocations(|| process_gpkg_geom(&mut wkb.as_slice(), &mut sink).unwrap()),
+        0
+    );
+}
@@ -0,0 +1,413 @@
+//! Cross-format round-trip conformance suite.
+//!
+//! A canonical set of geometries and features is encoded in every supported format,
+//! converted with every reader→writer pair and read back. The result has to be equivalent
+//! to the canonical input, unless the loss is documented in [`Format::support`]
+//! or [`FeatureFormat::lossy_properties`].
+use geozero::error::Result;
+use geozero::geojson::{GeoJson, GeoJsonWriter};
+use geozero::processor::{Event, ProcessorRecorder};
+use geozero::wkt::WktStr;
+use geozero::{
+    CoordDimensions, GeomProcessor, GeometryComparison, GeozeroDatasource, GeozeroGeometry, ToJson,
+    ToWkt,
+};
+
+/// Geometry encoded in a format
+#[derive(Debug)]
+enum Encoded {
+    GeoJson(String),
+    Wkt(String),
+    #[cfg(feature = "with-wkb")]
+    Wkb(Vec<u8>),
+    #[cfg(feature = "with-wkb")]
+    Ewkb(Vec<u8>),
+    #[cfg(feature = "with-wkb")]
+    GpkgWkb(Vec<u8>),
+    #[cfg(feature = "with-geo")]
+    Geo(geo_types::Geometry<f64>),
+}
+
+impl GeozeroGeometry for Encoded {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        match self {
+            Encoded::GeoJson(json) => GeoJson(json).process_geom(processor),
+            Encoded::Wkt(wkt) => WktStr(wkt).process_geom(processor),
+            #[cfg(feature = "with-wkb")]
+            Encoded::Wkb(wkb) => geozero::wkb::Wkb(wkb).process_geom(processor),
+            #[cfg(feature = "with-wkb")]
+            Encoded::Ewkb(wkb) => geozero::wkb::Ewkb(wkb).process_geom(processor),
+            #[cfg(feature = "with-wkb")]
+            Encoded::GpkgWkb(wkb) => geozero::wkb::GpkgWkb(wkb).process_geom(processor),
+            #[cfg(feature = "with-geo")]
+            Encoded::Geo(geom) => geom.process_geom(processor),
+        }
+    }
+}
+
+/// Geometry format with reader and writer
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    GeoJson,
+    Wkt,
+    #[cfg(feature = "with-wkb")]
+    Wkb,
+    #[cfg(feature = "with-wkb")]
+    Ewkb,
+    #[cfg(feature = "with-wkb")]
+    GpkgWkb,
+    #[cfg(feature = "with-geo")]
+    GeoTypes,
+}
+
+const FORMATS: &[Format] = &[
+    Format::GeoJson,
+    Format::Wkt,
+    #[cfg(feature = "with-wkb")]
+    Format::Wkb,
+    #[cfg(feature = "with-wkb")]
+    Format::Ewkb,
+    #[cfg(feature = "with-wkb")]
+    Format::GpkgWkb,
+    #[cfg(feature = "with-geo")]
+    Format::GeoTypes,
+];
+
+/// Expected round-trip behaviour of a format
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Support {
+    Lossless,
+    /// Z values are dropped
+    DropsZ,
+    /// Conversion fails or loses the geometry
+    Unsupported,
+}
+
+impl Format {
+    fn write<G: GeozeroGeometry>(self, geom: &G, dims: CoordDimensions) -> Result<Encoded> {
+        Ok(match self {
+            Format::GeoJson => {
+                let mut out = Vec::new();
+                let mut writer = GeoJsonWriter::new(&mut out);
+                writer.dims = dims;
+                geom.process_geom(&mut writer)?;
+                Encoded::GeoJson(String::from_utf8(out).unwrap())
+            }
+            Format::Wkt => Encoded::Wkt(geom.to_wkt_ndim(dims)?),
+            #[cfg(feature = "with-wkb")]
+            Format::Wkb => Encoded::Wkb(geozero::ToWkb::to_wkb(geom, dims)?),
+            #[cfg(feature = "with-wkb")]
+            Format::Ewkb => Encoded::Ewkb(geozero::ToWkb::to_ewkb(geom, dims, Some(4326))?),
+            #[cfg(feature = "with-wkb")]
+            Format::GpkgWkb => Encoded::GpkgWkb(geozero::ToWkb::to_gpkg_wkb(
+                geom,
+                dims,
+                Some(4326),
+                Vec::new(),
+            )?),
+            #[cfg(feature = "with-geo")]
+            Format::GeoTypes => Encoded::Geo(geozero::ToGeo::to_geo(geom)?),
+        })
+    }
+
+    /// Documented round-trip behaviour for `case`
+    fn support(self, case: &Case) -> Support {
+        match self {
+            // The `wkt` crate reader doesn't parse Z coordinates
+            Format::Wkt if case.z => Support::Unsupported,
+            // GeoJSON has no empty point and skips empty collection members
+            Format::GeoJson if case.empty_point => Support::Unsupported,
+            // The WKB writer doesn't encode empty points as NaN coordinates
+            #[cfg(feature = "with-wkb")]
+            Format::Wkb | Format::Ewkb | Format::GpkgWkb if case.empty_point => {
+                Support::Unsupported
+            }
+            #[cfg(feature = "with-geo")]
+            Format::GeoTypes if case.z => Support::DropsZ,
+            #[cfg(feature = "with-geo")]
+            Format::GeoTypes if case.empty_point => Support::Unsupported,
+            _ => Support::Lossless,
+        }
+    }
+}
+
+/// Canonical test geometry
+struct Case {
+    name: &'static str,
+    /// WKT for XY geometries, GeoJSON for XYZ geometries
+    source: &'static str,
+    z: bool,
+    empty_point: bool,
+}
+
+impl Case {
+    const fn xy(name: &'static str, wkt: &'static str) -> Self {
+        Case {
+            name,
+            source: wkt,
+            z: false,
+            empty_point: false,
+        }
+    }
+    const fn xyz(name: &'static str, geojson: &'static str) -> Self {
+        Case {
+            name,
+            source: geojson,
+            z: true,
+            empty_point: false,
+        }
+    }
+    const fn empty(name: &'static str, wkt: &'static str) -> Self {
+        Case {
+            name,
+            source: wkt,
+            z: false,
+            empty_point: true,
+        }
+    }
+    fn dims(&self) -> CoordDimensions {
+        if self.z {
+            CoordDimensions::xyz()
+        } else {
+            CoordDimensions::xy()
+        }
+    }
+    fn geometry(&self) -> Encoded {
+        if self.z {
+            Encoded::GeoJson(self.source.to_string())
+        } else {
+            Encoded::Wkt(self.source.to_string())
+        }
+    }
+}
+
+const CASES: &[Case] = &[
+    Case::xy("point", "POINT(1 2)"),
+    Case::xy("negative point", "POINT(-179.99999 -89.123456789)"),
+    Case::xy("linestring", "LINESTRING(0 0,1 1,2 0.5)"),
+    Case::xy("polygon", "POLYGON((0 0,10 0,10 10,0 10,0 0))"),
+    Case::xy(
+        "polygon with holes",
+        "POLYGON((0 0,10 0,10 10,0 10,0 0),(1 1,2 1,2 2,1 1),(5 5,6 5,6 6,5 5))",
+    ),
+    Case::xy("multipoint", "MULTIPOINT(0 0,1 1,2 2)"),
+    Case::xy(
+        "multilinestring",
+        "MULTILINESTRING((0 0,1 1),(2 2,3 3,4 2))",
+    ),
+    Case::xy(
+        "multipolygon",
+        "MULTIPOLYGON(((0 0,1 0,1 1,0 0)),((5 5,6 5,6 6,5 5),(5.2 5.1,5.8 5.1,5.8 5.7,5.2 5.1)))",
+    ),
+    Case::xy(
+        "geometrycollection",
+        "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1),POLYGON((0 0,1 0,1 1,0 0)))",
+    ),
+    Case::xy(
+        "nested geometrycollection",
+        "GEOMETRYCOLLECTION(POINT(1 2),GEOMETRYCOLLECTION(MULTIPOINT(3 4,5 6)))",
+    ),
+    Case::xy("empty linestring", "LINESTRING EMPTY"),
+    Case::xy("empty multipolygon", "MULTIPOLYGON EMPTY"),
+    Case::xy("empty geometrycollection", "GEOMETRYCOLLECTION EMPTY"),
+    Case::empty("empty point", "POINT EMPTY"),
+    Case::empty(
+        "geometrycollection with empty point",
+        "GEOMETRYCOLLECTION(POINT EMPTY,POINT(1 2))",
+    ),
+    Case::xyz("point z", r#"{"type": "Point", "coordinates": [1,2,3]}"#),
+    Case::xyz(
+        "linestring z",
+        r#"{"type": "LineString", "coordinates": [[0,0,10],[1,1,11.5]]}"#,
+    ),
+    Case::xyz(
+        "polygon z",
+        r#"{"type": "Polygon", "coordinates": [[[0,0,1],[1,0,2],[1,1,3],[0,0,1]]]}"#,
+    ),
+    Case::xyz(
+        "multipolygon z",
+        r#"{"type": "MultiPolygon", "coordinates": [[[[0,0,1],[1,0,2],[1,1,3],[0,0,1]]],[[[5,5,0],[6,5,0],[6,6,0],[5,5,0]]]]}"#,
+    ),
+];
+
+/// Convert `case` from format `from` to format `to` and read it back
+fn round_trip(case: &Case, from: Format, to: Format) -> Result<Encoded> {
+    let source = from.write(&case.geometry(), case.dims())?;
+    // Z values dropped by the source can't be written
+    let dims = match from.support(case) {
+        Support::DropsZ => CoordDimensions::xy(),
+        _ => case.dims(),
+    };
+    to.write(&source, dims)
+}
+
+#[test]
+fn geometry_round_trips() -> Result<()> {
+    let lossless = GeometryComparison::new(0.0).with_dims(CoordDimensions::xyz());
+    let xy = GeometryComparison::new(0.0);
+    let mut failures = Vec::new();
+    for case in CASES {
+        for &from in FORMATS {
+            for &to in FORMATS {
+                let supports = [from.support(case), to.support(case)];
+                let pair = format!("{} ({:?} -> {:?})", case.name, from, to);
+                let result = round_trip(case, from, to);
+                if supports.contains(&Support::Unsupported) {
+                    // Documented loss must still be a loss
+                    let equivalent =
+                        result.and_then(|geom| lossless.equivalent(&geom, &case.geometry()));
+                    if let Ok(true) = equivalent {
+                        failures.push(format!("{}: unexpectedly lossless", pair));
+                    }
+                    continue;
+                }
+                let cmp = if supports.contains(&Support::DropsZ) {
+                    &xy
+                } else {
+                    &lossless
+                };
+                match result.map(|geom| (cmp.difference(&geom, &case.geometry()), geom)) {
+                    Ok((Ok(None), _)) => {}
+                    Ok((Ok(Some(diff)), geom)) => {
+                        failures.push(format!("{}: {} in {:?}", pair, diff, geom))
+                    }
+                    Ok((Err(e), _)) | Err(e) => failures.push(format!("{}: {}", pair, e)),
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    Ok(())
+}
+
+#[test]
+fn canonical_encodings() -> Result<()> {
+    // Spot checks of writer output, independent of the readers
+    let case = &CASES[0];
+    assert_eq!(
+        case.geometry().to_json()?,
+        r#"{"type": "Point", "coordinates": [1,2]}"#
+    );
+    let case = &CASES[15];
+    assert_eq!(case.geometry().to_wkt()?, "POINT(1 2)");
+    assert_eq!(case.geometry().to_wkt_ndim(case.dims())?, "POINT(1 2 3)");
+    Ok(())
+}
+
+/// Feature format with reader and writer
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FeatureFormat {
+    GeoJson,
+    #[cfg(feature = "with-csv")]
+    Csv,
+}
+
+const FEATURE_FORMATS: &[FeatureFormat] = &[
+    FeatureFormat::GeoJson,
+    #[cfg(feature = "with-csv")]
+    FeatureFormat::Csv,
+];
+
+impl FeatureFormat {
+    fn write<D: GeozeroDatasource>(self, source: &mut D) -> Result<String> {
+        match self {
+            FeatureFormat::GeoJson => geozero::ProcessToJson::to_json(source),
+            #[cfg(feature = "with-csv")]
+            FeatureFormat::Csv => geozero::ProcessToCsv::to_csv(source),
+        }
+    }
+    fn read(self, text: &str) -> Result<Vec<Event>> {
+        let mut recorder = ProcessorRecorder::new();
+        match self {
+            FeatureFormat::GeoJson => GeoJson(text).process(&mut recorder)?,
+            #[cfg(feature = "with-csv")]
+            FeatureFormat::Csv => {
+                geozero::csv::Csv::new("geometry", text).process(&mut recorder)?
+            }
+        }
+        Ok(recorder.into_events())
+    }
+    /// Property values are read back as strings
+    fn lossy_properties(self) -> bool {
+        match self {
+            FeatureFormat::GeoJson => false,
+            #[cfg(feature = "with-csv")]
+            FeatureFormat::Csv => true,
+        }
+    }
+}
+
+/// Recorded geometry
+struct Recorded(Vec<Event>);
+
+impl GeozeroGeometry for Recorded {
+    fn process_geom<P: GeomProcessor>(&self, processor: &mut P) -> Result<()> {
+        self.0
+            .iter()
+            .try_for_each(|event| event.replay_geom(processor))
+    }
+}
+
+/// Geometry and properties (name, value, text value) of a feature
+type Feature = (Recorded, Vec<(String, String, String)>);
+
+fn features(events: Vec<Event>) -> Vec<Feature> {
+    let mut features = Vec::new();
+    let mut in_geometry = false;
+    for event in events {
+        match event {
+            Event::FeatureBegin(_) => features.push((Recorded(Vec::new()), Vec::new())),
+            Event::GeometryBegin => in_geometry = true,
+            Event::GeometryEnd => in_geometry = false,
+            Event::Property(_, name, value) => {
+                let text = value.as_column_value().to_string();
+                let value = format!("{:?}", value);
+                features.last_mut().unwrap().1.push((name, value, text));
+            }
+            event if in_geometry => features.last_mut().unwrap().0 .0.push(event),
+            _ => {}
+        }
+    }
+    features
+}
+
+const FEATURES: &str = r#"{"type": "FeatureCollection", "features": [
+{"type": "Feature", "properties": {"name": "Zürich", "population": 421878, "area": 87.88, "capital": false, "note": "東京 ✓ \"quoted\", comma"}, "geometry": {"type": "Point", "coordinates": [8.541694,47.376887]}},
+{"type": "Feature", "properties": {"name": "Αθήνα", "population": 664046, "area": 38.96, "capital": true, "note": "multi\nline"}, "geometry": {"type": "Polygon", "coordinates": [[[23.7,37.9],[23.8,37.9],[23.8,38.0],[23.7,37.9]]]}},
+{"type": "Feature", "properties": {"name": "", "population": 0, "area": -1.5, "capital": false, "note": "🗺️"}, "geometry": {"type": "MultiLineString", "coordinates": [[[0,0],[1,1]],[[2,2],[3,3]]]}}
+]}"#;
+
+#[test]
+fn feature_round_trips() -> Result<()> {
+    let expected = features(FeatureFormat::GeoJson.read(FEATURES)?);
+    assert_eq!(expected.len(), 3);
+    let cmp = GeometryComparison::new(0.0);
+    for &from in FEATURE_FORMATS {
+        let source = from.write(&mut GeoJson(FEATURES))?;
+        for &to in FEATURE_FORMATS {
+            let pair = format!("{:?} -> {:?}", from, to);
+            let text = match from {
+                FeatureFormat::GeoJson => to.write(&mut GeoJson(&source))?,
+                #[cfg(feature = "with-csv")]
+                FeatureFormat::Csv => to.write(&mut geozero::csv::Csv::new("geometry", &source))?,
+            };
+            let actual = features(to.read(&text)?);
+            assert_eq!(actual.len(), expected.len(), "{}", pair);
+            let lossy = from.lossy_properties() || to.lossy_properties();
+            for (actual, expected) in actual.iter().zip(&expected) {
+                assert_eq!(cmp.difference(&actual.0, &expected.0)?, None, "{}", pair);
+                let props = |feature: &Feature| {
+                    feature
+                        .1
+                        .iter()
+                        .map(|(name, value, text)| {
+                            (name.clone(), if lossy { text } else { value }.clone())
+                        })
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(props(actual), props(expected), "{}", pair);
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use gdal::vector::LayerAccess;
 use gdal::Dataset;
-use geozero::gdal::process_geom;
+use geozero::gdal::{process_geom, GdalLayer};
 use geozero::svg::SvgWriter;
+use geozero::{ColumnStatsCollector, GeozeroDatasource};
 use std::path::Path;
 
 #[test]
@@ -19,3 +20,14 @@ fn ogr_to_svg() -> Result<(), gdal::errors::GdalError> {
     );
     Ok(())
 }
+
+#[test]
+fn layer_datasource() -> geozero::error::Result<()> {
+    let dataset = Dataset::open(Path::new("tests/data/places.json"))?;
+    let mut layer = GdalLayer(dataset.layer(0)?);
+    let mut stats = ColumnStatsCollector::new();
+    layer.process(&mut stats)?;
+    assert_eq!(stats.feature_count(), 1249);
+    assert!(stats.column_stats("NAME").is_some());
+    Ok(())
+}
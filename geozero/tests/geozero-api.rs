@@ -1,6 +1,9 @@
 use flatgeobuf::{FallibleStreamingIterator, FeatureProperties, FgbReader, GeometryType};
 use geozero::error::Result;
-use geozero::{ColumnValue, CoordDimensions, GeomProcessor, PropertyProcessor};
+use geozero::{
+    ColumnDef, ColumnType, ColumnValue, CoordDimensions, FeatureProcessor, GeomProcessor,
+    GeozeroDatasource, PropertyProcessor,
+};
 use seek_bufread::BufReader;
 use std::fs::File;
 
@@ -101,3 +104,32 @@ fn feature_finder() -> Result<()> {
 
     Ok(())
 }
+
+struct SchemaReader(Vec<ColumnDef>);
+
+impl FeatureProcessor for SchemaReader {
+    fn schema(&mut self, columns: &[ColumnDef]) -> Result<()> {
+        self.0 = columns.to_vec();
+        Ok(())
+    }
+}
+impl GeomProcessor for SchemaReader {}
+impl PropertyProcessor for SchemaReader {}
+
+#[test]
+fn fgb_schema() -> Result<()> {
+    let mut filein = BufReader::new(File::open("tests/data/countries.fgb")?);
+    let mut fgb = FgbReader::open(&mut filein)?.select_all()?;
+
+    let mut reader = SchemaReader(Vec::new());
+    fgb.process(&mut reader)?;
+    assert_eq!(
+        reader.0,
+        vec![
+            ColumnDef::new("id", ColumnType::String),
+            ColumnDef::new("name", ColumnType::String)
+        ]
+    );
+
+    Ok(())
+}
@@ -2,18 +2,16 @@ use geozero::gpx::{Gpx, GpxReader};
 
 use std::io;
 
-mod test_writer;
-
-use test_writer::{Cmd, TestWriter};
+use geozero::processor::{Event, ProcessorRecorder};
 
 #[test]
 fn test_empty_str() {
     let gpx_str = "";
     let mut cursor = io::Cursor::new(gpx_str);
-    let mut writer = TestWriter::default();
+    let mut recorder = ProcessorRecorder::new();
 
     assert!(matches!(
-        geozero::gpx::read_gpx(&mut cursor, &mut writer),
+        geozero::gpx::read_gpx(&mut cursor, &mut recorder),
         Err(geozero::error::GeozeroError::Geometry(_)),
     ));
 }
@@ -22,39 +20,39 @@ fn test_empty_str() {
 fn test_extensive() {
     let gpx_str = include_str!("data/extensive.gpx");
     let mut cursor = io::Cursor::new(gpx_str);
-    let mut writer = TestWriter::default();
+    let mut recorder = ProcessorRecorder::new();
 
-    geozero::gpx::read_gpx(&mut cursor, &mut writer).unwrap();
+    geozero::gpx::read_gpx(&mut cursor, &mut recorder).unwrap();
 
     #[rustfmt::skip]
     assert_eq!(
-        writer.0,
+        recorder.into_events(),
         vec![
-            Cmd::GeometryCollectionBegin { idx: 0, size: 4 },
-                Cmd::PointBegin { idx: 0 },
-                    Cmd::Xy { idx: 0, x: -1.5153741828293, y: 47.253146555709 },
-                Cmd::PointEnd { idx: 0 },
-                Cmd::PointBegin { idx: 1 },
-                    Cmd::Xy { idx: 0, x: -1.5482325613225, y: 47.235331031612 },
-                Cmd::PointEnd { idx: 1 },
-                Cmd::MultiLineStringBegin { idx: 2 },
-                    Cmd::LineStringBegin { idx: 0 },
-                        Cmd::Xy { idx: 0, x: -1.5521714646550901, y: 47.2278526991611 },
-                        Cmd::Xy { idx: 1, x: -1.5504753767742476, y: 47.229236980562256 },
-                    Cmd::LineStringEnd { idx: 0 },
-                    Cmd::LineStringBegin { idx: 1 },
-                        Cmd::Xy { idx: 0, x: -1.5493804339650867, y: 47.2301112449252 },
-                        Cmd::Xy { idx: 1, x: -1.5485645942249218, y: 47.230562942529104 },
-                    Cmd::LineStringEnd { idx: 1 },
-                Cmd::MultiLineStringEnd { idx: 2 },
-                Cmd::MultiLineStringBegin { idx: 3 },
-                    Cmd::LineStringBegin { idx: 0 },
-                        Cmd::Xy { idx: 0, x: -1.5521714646550901, y: 47.2278526991611 },
-                        Cmd::Xy { idx: 1, x: -1.5504753767742476, y: 47.229236980562256 },
-                        Cmd::Xy { idx: 2, x: -1.5493804339650867, y: 47.2301112449252 },
-                    Cmd::LineStringEnd { idx: 0 },
-                Cmd::MultiLineStringEnd { idx: 3 },
-            Cmd::GeometryCollectionEnd { idx: 0 },
+            Event::GeometryCollectionBegin(4, 0),
+                Event::PointBegin(0),
+                    Event::Xy(-1.5153741828293, 47.253146555709, 0),
+                Event::PointEnd(0),
+                Event::PointBegin(1),
+                    Event::Xy(-1.5482325613225, 47.235331031612, 0),
+                Event::PointEnd(1),
+                Event::MultiLineStringBegin(2, 2),
+                    Event::LineStringBegin(false, 2, 0),
+                        Event::Xy(-1.5521714646550901, 47.2278526991611, 0),
+                        Event::Xy(-1.5504753767742476, 47.229236980562256, 1),
+                    Event::LineStringEnd(false, 0),
+                    Event::LineStringBegin(false, 2, 1),
+                        Event::Xy(-1.5493804339650867, 47.2301112449252, 0),
+                        Event::Xy(-1.5485645942249218, 47.230562942529104, 1),
+                    Event::LineStringEnd(false, 1),
+                Event::MultiLineStringEnd(2),
+                Event::MultiLineStringBegin(1, 3),
+                    Event::LineStringBegin(false, 3, 0),
+                        Event::Xy(-1.5521714646550901, 47.2278526991611, 0),
+                        Event::Xy(-1.5504753767742476, 47.229236980562256, 1),
+                        Event::Xy(-1.5493804339650867, 47.2301112449252, 2),
+                    Event::LineStringEnd(false, 0),
+                Event::MultiLineStringEnd(3),
+            Event::GeometryCollectionEnd(0),
         ]
     );
 }
@@ -63,23 +61,23 @@ fn test_extensive() {
 fn test_wikipedia_example() {
     let gpx_str = include_str!("data/wikipedia_example.gpx");
     let mut cursor = io::Cursor::new(gpx_str);
-    let mut writer = TestWriter::default();
+    let mut recorder = ProcessorRecorder::new();
 
-    geozero::gpx::read_gpx(&mut cursor, &mut writer).unwrap();
+    geozero::gpx::read_gpx(&mut cursor, &mut recorder).unwrap();
 
     #[rustfmt::skip]
     assert_eq!(
-        writer.0,
+        recorder.into_events(),
         vec![
-            Cmd::GeometryCollectionBegin { idx: 0, size: 1 },
-                Cmd::MultiLineStringBegin { idx: 0 },
-                    Cmd::LineStringBegin { idx: 0 },
-                        Cmd::Xy { idx: 0, x: -122.326897, y: 47.644548, },
-                        Cmd::Xy { idx: 1, x: -122.326897, y: 47.644548, },
-                        Cmd::Xy { idx: 2, x: -122.326897, y: 47.644548, },
-                    Cmd::LineStringEnd { idx: 0 },
-                Cmd::MultiLineStringEnd { idx: 0 },
-            Cmd::GeometryCollectionEnd { idx: 0 },
+            Event::GeometryCollectionBegin(1, 0),
+                Event::MultiLineStringBegin(1, 0),
+                    Event::LineStringBegin(false, 3, 0),
+                        Event::Xy(-122.326897, 47.644548, 0),
+                        Event::Xy(-122.326897, 47.644548, 1),
+                        Event::Xy(-122.326897, 47.644548, 2),
+                    Event::LineStringEnd(false, 0),
+                Event::MultiLineStringEnd(0),
+            Event::GeometryCollectionEnd(0),
         ]
     );
 }
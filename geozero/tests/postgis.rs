@@ -157,6 +157,39 @@ mod postgis_sqlx {
         Ok(())
     }
 
+    #[cfg(feature = "with-tokio")]
+    #[tokio::test]
+    #[ignore]
+    async fn async_datasource() -> Result<(), sqlx::Error> {
+        use geozero::geojson::GeoJsonWriter;
+        use geozero::postgis::sqlx::PostgisQuery;
+        use geozero::AsyncGeozeroDatasource;
+
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&env::var("DATABASE_URL").unwrap())
+            .await?;
+
+        let mut out: Vec<u8> = Vec::new();
+        let mut query = PostgisQuery::new(
+            &pool,
+            "SELECT 'Bern' AS name, 'SRID=4326;POINT(7.44 46.95)'::geometry AS geom",
+            "geom",
+        );
+        query
+            .process(&mut GeoJsonWriter::new(&mut out))
+            .await
+            .expect("process failed");
+        assert_eq!(
+            std::str::from_utf8(&out).unwrap(),
+            r#"{
+"type": "FeatureCollection",
+"features": [{"type": "Feature", "properties": {"name": "Bern"}, "geometry": {"type": "Point", "coordinates": [7.44,46.95]}}]}"#
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[ignore]
     async fn point3d_query() -> Result<(), sqlx::Error> {